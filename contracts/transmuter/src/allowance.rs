@@ -0,0 +1,189 @@
+use cosmwasm_std::{ensure, Addr, StdResult, Storage, Uint128};
+use cw_storage_plus::Map;
+
+use crate::ContractError;
+
+/// cw20-style allowances for burning alloyed asset on a holder's behalf,
+/// for integrations that can't attach funds to their calls.
+pub struct AlloyedAllowances<'a> {
+    /// Map of (denom, owner, spender) -> allowance
+    allowances: Map<'a, (&'a str, &'a Addr, &'a Addr), Uint128>,
+}
+
+impl<'a> AlloyedAllowances<'a> {
+    pub const fn new(namespace: &'a str) -> Self {
+        Self {
+            allowances: Map::new(namespace),
+        }
+    }
+
+    pub fn get(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+        owner: &Addr,
+        spender: &Addr,
+    ) -> StdResult<Uint128> {
+        Ok(self
+            .allowances
+            .may_load(storage, (denom, owner, spender))?
+            .unwrap_or_default())
+    }
+
+    /// Increase the allowance by `amount`, saturating at max.
+    pub fn increase(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        owner: &Addr,
+        spender: &Addr,
+        amount: Uint128,
+    ) -> StdResult<Uint128> {
+        let allowance = self
+            .get(storage, denom, owner, spender)?
+            .saturating_add(amount);
+        self.save(storage, denom, owner, spender, allowance)?;
+        Ok(allowance)
+    }
+
+    /// Decrease the allowance by `amount`, saturating at zero.
+    pub fn decrease(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        owner: &Addr,
+        spender: &Addr,
+        amount: Uint128,
+    ) -> StdResult<Uint128> {
+        let allowance = self
+            .get(storage, denom, owner, spender)?
+            .saturating_sub(amount);
+        self.save(storage, denom, owner, spender, allowance)?;
+        Ok(allowance)
+    }
+
+    /// Deduct `amount` from the allowance, failing if the allowance is insufficient.
+    pub fn spend(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        owner: &Addr,
+        spender: &Addr,
+        amount: Uint128,
+    ) -> Result<(), ContractError> {
+        let allowance = self.get(storage, denom, owner, spender)?;
+        ensure!(
+            allowance >= amount,
+            ContractError::InsufficientAllowance {
+                required: amount,
+                available: allowance,
+            }
+        );
+
+        self.save(storage, denom, owner, spender, allowance - amount)?;
+        Ok(())
+    }
+
+    fn save(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        owner: &Addr,
+        spender: &Addr,
+        allowance: Uint128,
+    ) -> StdResult<()> {
+        if allowance.is_zero() {
+            self.allowances.remove(storage, (denom, owner, spender));
+            return Ok(());
+        }
+
+        self.allowances
+            .save(storage, (denom, owner, spender), &allowance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_alloyed_allowances() {
+        let mut deps = mock_dependencies();
+        let allowances = AlloyedAllowances::new("alloyed_allowances");
+        let owner = Addr::unchecked("owner");
+        let spender = Addr::unchecked("spender");
+
+        allowances
+            .increase(
+                &mut deps.storage,
+                "alloyed",
+                &owner,
+                &spender,
+                Uint128::new(100),
+            )
+            .unwrap();
+        allowances
+            .decrease(
+                &mut deps.storage,
+                "alloyed",
+                &owner,
+                &spender,
+                Uint128::new(30),
+            )
+            .unwrap();
+        assert_eq!(
+            allowances
+                .get(&deps.storage, "alloyed", &owner, &spender)
+                .unwrap(),
+            Uint128::new(70)
+        );
+
+        // allowance is per denom and direction
+        assert_eq!(
+            allowances
+                .get(&deps.storage, "other", &owner, &spender)
+                .unwrap(),
+            Uint128::zero()
+        );
+        assert_eq!(
+            allowances
+                .get(&deps.storage, "alloyed", &spender, &owner)
+                .unwrap(),
+            Uint128::zero()
+        );
+
+        let err = allowances
+            .spend(
+                &mut deps.storage,
+                "alloyed",
+                &owner,
+                &spender,
+                Uint128::new(71),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientAllowance {
+                required: Uint128::new(71),
+                available: Uint128::new(70),
+            }
+        );
+
+        allowances
+            .spend(
+                &mut deps.storage,
+                "alloyed",
+                &owner,
+                &spender,
+                Uint128::new(70),
+            )
+            .unwrap();
+        assert_eq!(
+            allowances
+                .get(&deps.storage, "alloyed", &owner, &spender)
+                .unwrap(),
+            Uint128::zero()
+        );
+    }
+}
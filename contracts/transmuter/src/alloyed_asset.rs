@@ -26,9 +26,16 @@ impl<'a> AlloyedAsset<'a> {
         }
     }
 
-    /// get the alloyed denom
-    pub fn get_alloyed_denom(&self, store: &dyn Storage) -> StdResult<String> {
-        self.alloyed_denom.load(store)
+    /// get the alloyed denom, fails if the alloyed asset is disabled
+    pub fn get_alloyed_denom(&self, store: &dyn Storage) -> Result<String, ContractError> {
+        self.may_get_alloyed_denom(store)?
+            .ok_or(ContractError::AlloyedAssetDisabled {})
+    }
+
+    /// get the alloyed denom, `None` if the alloyed asset is disabled
+    /// since the denom is never created in that case
+    pub fn may_get_alloyed_denom(&self, store: &dyn Storage) -> StdResult<Option<String>> {
+        self.alloyed_denom.may_load(store)
     }
 
     /// set the alloyed denom
@@ -41,18 +48,22 @@ impl<'a> AlloyedAsset<'a> {
     }
 
     /// get the total supply of alloyed asset
-    /// which is the total shares of the pool
+    /// which is the total shares of the pool, zero if the alloyed asset is disabled
     pub fn get_total_supply(&self, deps: Deps) -> StdResult<Uint128> {
-        let alloyed_denom = self.get_alloyed_denom(deps.storage)?;
+        let Some(alloyed_denom) = self.may_get_alloyed_denom(deps.storage)? else {
+            return Ok(Uint128::zero());
+        };
 
         deps.querier
             .query_supply(alloyed_denom)
             .map(|coin| coin.amount)
     }
 
-    /// get the balance of alloyed asset for a given address
+    /// get the balance of alloyed asset for a given address, zero if the alloyed asset is disabled
     pub fn get_balance(&self, deps: Deps, address: &Addr) -> StdResult<Uint128> {
-        let alloyed_denom = self.get_alloyed_denom(deps.storage)?;
+        let Some(alloyed_denom) = self.may_get_alloyed_denom(deps.storage)? else {
+            return Ok(Uint128::zero());
+        };
 
         deps.querier
             .query_balance(address, alloyed_denom)
@@ -0,0 +1,166 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Coin, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+
+use crate::{
+    alloyed_asset::{swap_from_alloyed, swap_to_alloyed},
+    transmuter_pool::TransmuterPool,
+    ContractError,
+};
+
+/// Alloyed asset registered in addition to the main alloyed asset,
+/// backed only by a subset of pool asset denoms, e.g. for senior/junior share classes.
+///
+/// Its backing is accounted separately from the main pool, so holders of
+/// the main alloyed asset have no claim on it and vice versa.
+#[cw_serde]
+pub struct RegisteredAlloyedAsset {
+    pub denom: String,
+    pub normalization_factor: Uint128,
+    /// Backing denoms and their amounts held for this alloyed asset
+    pub backing: TransmuterPool,
+}
+
+impl RegisteredAlloyedAsset {
+    /// Alloyed asset amount minted for depositing `tokens_in`, rounded down.
+    pub fn mint_amount_via_exact_in(&self, tokens_in: &[Coin]) -> Result<Uint128, ContractError> {
+        let out_amount = swap_to_alloyed::out_amount_via_exact_in(
+            self.backing
+                .pair_coins_with_normalization_factor(tokens_in)?,
+            Uint128::zero(),
+            self.normalization_factor,
+        )?;
+
+        ensure!(!out_amount.is_zero(), ContractError::ZeroValueOperation {});
+
+        Ok(out_amount)
+    }
+
+    /// Amount of `token_in_denom` to deposit for minting `out_amount`, rounded up.
+    pub fn deposit_amount_via_exact_out(
+        &self,
+        token_in_denom: &str,
+        out_amount: Uint128,
+    ) -> Result<Uint128, ContractError> {
+        swap_to_alloyed::in_amount_via_exact_out(
+            self.backing
+                .get_pool_asset_by_denom(token_in_denom)?
+                .normalization_factor(),
+            Uint128::MAX,
+            out_amount,
+            self.normalization_factor,
+        )
+    }
+
+    /// Amount of `token_out_denom` withdrawn for burning `burn_amount`, rounded down.
+    pub fn withdraw_amount_via_exact_in(
+        &self,
+        burn_amount: Uint128,
+        token_out_denom: &str,
+    ) -> Result<Uint128, ContractError> {
+        let out_amount = swap_from_alloyed::out_amount_via_exact_in(
+            burn_amount,
+            self.normalization_factor,
+            self.backing
+                .get_pool_asset_by_denom(token_out_denom)?
+                .normalization_factor(),
+            Uint128::zero(),
+        )?;
+
+        ensure!(!out_amount.is_zero(), ContractError::ZeroValueOperation {});
+
+        Ok(out_amount)
+    }
+
+    /// Alloyed asset amount to burn for withdrawing `tokens_out`, rounded up.
+    pub fn burn_amount_via_exact_out(&self, tokens_out: &[Coin]) -> Result<Uint128, ContractError> {
+        swap_from_alloyed::in_amount_via_exact_out(
+            Uint128::MAX,
+            self.normalization_factor,
+            self.backing
+                .pair_coins_with_normalization_factor(tokens_out)?,
+        )
+    }
+}
+
+/// Alloyed asset waiting for its denom to be created before being registered.
+#[cw_serde]
+pub struct PendingAlloyedAsset {
+    pub normalization_factor: Uint128,
+    pub backing: TransmuterPool,
+}
+
+/// Registry of alloyed assets registered in addition to the main alloyed asset,
+/// keyed by alloyed denom.
+pub struct AlloyedAssetRegistry<'a> {
+    pending: Item<'a, PendingAlloyedAsset>,
+    assets: Map<'a, &'a str, RegisteredAlloyedAsset>,
+}
+
+impl<'a> AlloyedAssetRegistry<'a> {
+    pub const fn new(pending_namespace: &'a str, assets_namespace: &'a str) -> Self {
+        Self {
+            pending: Item::new(pending_namespace),
+            assets: Map::new(assets_namespace),
+        }
+    }
+
+    /// Keep the alloyed asset to register until its denom is created.
+    pub fn set_pending(
+        &self,
+        storage: &mut dyn Storage,
+        pending: &PendingAlloyedAsset,
+    ) -> StdResult<()> {
+        self.pending.save(storage, pending)
+    }
+
+    /// Register the pending alloyed asset under its newly created `denom`.
+    pub fn register_pending(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+    ) -> StdResult<RegisteredAlloyedAsset> {
+        let PendingAlloyedAsset {
+            normalization_factor,
+            backing,
+        } = self.pending.load(storage)?;
+        self.pending.remove(storage);
+
+        let asset = RegisteredAlloyedAsset {
+            denom: denom.to_string(),
+            normalization_factor,
+            backing,
+        };
+        self.assets.save(storage, denom, &asset)?;
+
+        Ok(asset)
+    }
+
+    pub fn is_registered(&self, storage: &dyn Storage, denom: &str) -> bool {
+        self.assets.has(storage, denom)
+    }
+
+    pub fn load(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+    ) -> Result<RegisteredAlloyedAsset, ContractError> {
+        self.assets.may_load(storage, denom)?.ok_or_else(|| {
+            ContractError::AlloyedAssetNotRegistered {
+                denom: denom.to_string(),
+            }
+        })
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage, asset: &RegisteredAlloyedAsset) -> StdResult<()> {
+        self.assets.save(storage, &asset.denom, asset)
+    }
+
+    pub fn list(&self, storage: &dyn Storage) -> StdResult<Vec<RegisteredAlloyedAsset>> {
+        // there is no need to limit, since the number of registered alloyed assets is expected to be small
+        self.assets
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, asset)| asset))
+            .collect()
+    }
+}
@@ -0,0 +1,387 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, ensure_ne, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Map};
+
+use crate::{corruptable::Corruptable, ContractError};
+
+/// Group of pool asset denoms that are managed together,
+/// e.g. denoms that share the same bridge or issuer and hence the same risk.
+#[cw_serde]
+pub struct AssetGroup {
+    denoms: Vec<String>,
+    is_corrupted: bool,
+    /// Swaps increasing exposure to the group's denoms are rejected while paused
+    #[serde(default)]
+    is_paused: bool,
+}
+
+impl AssetGroup {
+    pub fn new(denoms: Vec<String>) -> Self {
+        Self {
+            denoms,
+            is_corrupted: false,
+            is_paused: false,
+        }
+    }
+
+    pub fn denoms(&self) -> &[String] {
+        &self.denoms
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) -> &mut Self {
+        self.is_paused = paused;
+        self
+    }
+
+    /// Merge other group into this group.
+    /// Denoms are deduped and corruption and pause are sticky, if either group is
+    /// corrupted or paused, the merged group is as well.
+    pub fn merge(&mut self, other: AssetGroup) -> &mut Self {
+        for denom in other.denoms {
+            if !self.denoms.contains(&denom) {
+                self.denoms.push(denom);
+            }
+        }
+
+        self.is_corrupted = self.is_corrupted || other.is_corrupted;
+        self.is_paused = self.is_paused || other.is_paused;
+        self
+    }
+}
+
+impl Corruptable for AssetGroup {
+    fn is_corrupted(&self) -> bool {
+        self.is_corrupted
+    }
+
+    fn mark_as_corrupted(&mut self) -> &mut Self {
+        self.is_corrupted = true;
+        self
+    }
+
+    fn unmark_as_corrupted(&mut self) -> &mut Self {
+        self.is_corrupted = false;
+        self
+    }
+}
+
+pub struct AssetGroups<'a> {
+    groups: Map<'a, &'a str, AssetGroup>,
+}
+
+impl<'a> AssetGroups<'a> {
+    pub const fn new(namespace: &'a str) -> Self {
+        Self {
+            groups: Map::new(namespace),
+        }
+    }
+
+    pub fn create(
+        &self,
+        storage: &mut dyn Storage,
+        label: &str,
+        denoms: Vec<String>,
+    ) -> Result<(), ContractError> {
+        ensure!(
+            !label.is_empty(),
+            ContractError::NonEmptyInputRequired {
+                field: "label".to_string()
+            }
+        );
+
+        ensure!(
+            !self.groups.has(storage, label),
+            ContractError::AssetGroupAlreadyExists {
+                label: label.to_string()
+            }
+        );
+
+        self.groups
+            .save(storage, label, &AssetGroup::new(denoms))
+            .map_err(Into::into)
+    }
+
+    pub fn get(&self, storage: &dyn Storage, label: &str) -> Result<AssetGroup, ContractError> {
+        self.groups
+            .may_load(storage, label)?
+            .ok_or_else(|| ContractError::AssetGroupNotFound {
+                label: label.to_string(),
+            })
+    }
+
+    pub fn remove(&self, storage: &mut dyn Storage, label: &str) -> Result<(), ContractError> {
+        self.get(storage, label)?;
+        self.groups.remove(storage, label);
+        Ok(())
+    }
+
+    pub fn update<A>(
+        &self,
+        storage: &mut dyn Storage,
+        label: &str,
+        action: A,
+    ) -> Result<AssetGroup, ContractError>
+    where
+        A: FnOnce(&mut AssetGroup),
+    {
+        let mut group = self.get(storage, label)?;
+        action(&mut group);
+        self.groups.save(storage, label, &group)?;
+        Ok(group)
+    }
+
+    /// Group resulting from merging `source` into `target`, without saving it.
+    pub fn merged(
+        &self,
+        storage: &dyn Storage,
+        source: &str,
+        target: &str,
+    ) -> Result<AssetGroup, ContractError> {
+        ensure_ne!(
+            source,
+            target,
+            ContractError::AssetGroupSelfMerge {
+                label: source.to_string()
+            }
+        );
+
+        let source_group = self.get(storage, source)?;
+        let mut merged = self.get(storage, target)?;
+        merged.merge(source_group);
+
+        Ok(merged)
+    }
+
+    /// Move all denoms of `source` into `target` and remove `source`.
+    /// Returns the merged group.
+    pub fn merge(
+        &self,
+        storage: &mut dyn Storage,
+        source: &str,
+        target: &str,
+    ) -> Result<AssetGroup, ContractError> {
+        let merged = self.merged(storage, source, target)?;
+        self.groups.save(storage, target, &merged)?;
+        self.groups.remove(storage, source);
+
+        Ok(merged)
+    }
+
+    /// Remove denom from all groups, groups that end up empty are removed.
+    pub fn remove_denom(&self, storage: &mut dyn Storage, denom: &str) -> StdResult<()> {
+        for (label, mut group) in self.list(storage)? {
+            if !group.denoms.iter().any(|d| d == denom) {
+                continue;
+            }
+
+            group.denoms.retain(|d| d != denom);
+
+            if group.denoms.is_empty() {
+                self.groups.remove(storage, &label);
+            } else {
+                self.groups.save(storage, &label, &group)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Label of a paused group containing `denom`, if any.
+    pub fn paused_group_of(&self, storage: &dyn Storage, denom: &str) -> StdResult<Option<String>> {
+        Ok(self
+            .list(storage)?
+            .into_iter()
+            .find(|(_, group)| group.is_paused && group.denoms.iter().any(|d| d == denom))
+            .map(|(label, _)| label))
+    }
+
+    pub fn count(&self, storage: &dyn Storage) -> usize {
+        self.groups
+            .keys_raw(storage, None, None, Order::Ascending)
+            .count()
+    }
+
+    pub fn list(&self, storage: &dyn Storage) -> StdResult<Vec<(String, AssetGroup)>> {
+        self.groups
+            .range(storage, None, None, Order::Ascending)
+            .collect()
+    }
+
+    /// Up to `limit` groups with labels after `start_after`, in label order.
+    pub fn list_page(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<&str>,
+        limit: usize,
+    ) -> StdResult<Vec<(String, AssetGroup)>> {
+        self.groups
+            .range(
+                storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_create_and_remove_asset_group() {
+        let mut deps = mock_dependencies();
+        let asset_groups = AssetGroups::new("asset_groups");
+
+        asset_groups
+            .create(
+                &mut deps.storage,
+                "group1",
+                vec!["denom1".to_string(), "denom2".to_string()],
+            )
+            .unwrap();
+
+        assert_eq!(
+            asset_groups
+                .create(&mut deps.storage, "group1", vec!["denom3".to_string()])
+                .unwrap_err(),
+            ContractError::AssetGroupAlreadyExists {
+                label: "group1".to_string()
+            }
+        );
+
+        assert_eq!(
+            asset_groups
+                .create(&mut deps.storage, "", vec!["denom3".to_string()])
+                .unwrap_err(),
+            ContractError::NonEmptyInputRequired {
+                field: "label".to_string()
+            }
+        );
+
+        assert_eq!(
+            asset_groups.list(&deps.storage).unwrap(),
+            vec![(
+                "group1".to_string(),
+                AssetGroup::new(vec!["denom1".to_string(), "denom2".to_string()])
+            )]
+        );
+
+        asset_groups.remove(&mut deps.storage, "group1").unwrap();
+        assert_eq!(
+            asset_groups
+                .remove(&mut deps.storage, "group1")
+                .unwrap_err(),
+            ContractError::AssetGroupNotFound {
+                label: "group1".to_string()
+            }
+        );
+        assert!(asset_groups.list(&deps.storage).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_merge_asset_groups() {
+        let mut deps = mock_dependencies();
+        let asset_groups = AssetGroups::new("asset_groups");
+
+        asset_groups
+            .create(
+                &mut deps.storage,
+                "corrupted",
+                vec!["denom1".to_string(), "denom2".to_string()],
+            )
+            .unwrap();
+        asset_groups
+            .update(&mut deps.storage, "corrupted", |group| {
+                group.mark_as_corrupted();
+            })
+            .unwrap();
+
+        asset_groups
+            .create(
+                &mut deps.storage,
+                "healthy",
+                vec!["denom2".to_string(), "denom3".to_string()],
+            )
+            .unwrap();
+
+        // missing labels
+        for (source, target, missing) in [
+            ("missing", "healthy", "missing"),
+            ("corrupted", "missing", "missing"),
+        ] {
+            assert_eq!(
+                asset_groups
+                    .merge(&mut deps.storage, source, target)
+                    .unwrap_err(),
+                ContractError::AssetGroupNotFound {
+                    label: missing.to_string()
+                }
+            );
+        }
+
+        assert_eq!(
+            asset_groups
+                .merge(&mut deps.storage, "healthy", "healthy")
+                .unwrap_err(),
+            ContractError::AssetGroupSelfMerge {
+                label: "healthy".to_string()
+            }
+        );
+
+        let merged = asset_groups
+            .merge(&mut deps.storage, "corrupted", "healthy")
+            .unwrap();
+
+        assert_eq!(
+            merged.denoms(),
+            &[
+                "denom2".to_string(),
+                "denom3".to_string(),
+                "denom1".to_string()
+            ]
+        );
+        assert!(merged.is_corrupted());
+
+        assert_eq!(
+            asset_groups.list(&deps.storage).unwrap(),
+            vec![("healthy".to_string(), merged)]
+        );
+    }
+
+    #[test]
+    fn test_remove_denom() {
+        let mut deps = mock_dependencies();
+        let asset_groups = AssetGroups::new("asset_groups");
+
+        asset_groups
+            .create(
+                &mut deps.storage,
+                "group1",
+                vec!["denom1".to_string(), "denom2".to_string()],
+            )
+            .unwrap();
+        asset_groups
+            .create(&mut deps.storage, "group2", vec!["denom1".to_string()])
+            .unwrap();
+
+        asset_groups
+            .remove_denom(&mut deps.storage, "denom1")
+            .unwrap();
+
+        assert_eq!(
+            asset_groups.list(&deps.storage).unwrap(),
+            vec![(
+                "group1".to_string(),
+                AssetGroup::new(vec!["denom2".to_string()])
+            )]
+        );
+    }
+}
@@ -1,26 +1,45 @@
-use std::{collections::BTreeMap, iter};
+use std::collections::BTreeMap;
 
 use crate::{
+    allowance::AlloyedAllowances,
     alloyed_asset::AlloyedAsset,
-    asset::{Asset, AssetConfig},
+    alloyed_registry::{AlloyedAssetRegistry, PendingAlloyedAsset, RegisteredAlloyedAsset},
+    asset::{convert_amount, Asset, AssetConfig, Rounding},
+    asset_group::{AssetGroup, AssetGroups},
+    corruptable::Corruptable,
+    denom_metadata::DenomMetadataCache,
     ensure_admin_authority, ensure_moderator_authority,
-    error::{non_empty_input_required, nonpayable, ContractError},
-    limiter::{Limiter, LimiterParams, Limiters},
+    error::{non_empty_input_required, nonpayable, ContractError, ERROR_CATALOG},
+    fee::{DynamicFeeParams, Fee, FeeSide},
+    governance_log::{GovernanceAction, GovernanceLog},
+    holders::AlloyedHolders,
+    limiter::{Limiter, LimiterParams, LimiterTripRecord, Limiters, StressConfig, WindowConfig},
     math::{self, rescale},
-    role::Role,
-    swap::{BurnTarget, Entrypoint, SwapFromAlloyedConstraint, SwapToAlloyedConstraint, SWAP_FEE},
+    migrations::{migration_history, MigrationRecord},
+    oracle::{self, AutoCorruptionConfig},
+    role::{Capability, Role},
+    rounding_audit::{RoundingAuditLog, RoundingResidual},
+    snapshot::{PoolSnapshot, PoolSnapshots},
+    state_dump::{FullStateDump, StateDumpCursor},
+    swap::{
+        alloyed_asset_transfer_event, BurnTarget, Entrypoint, LargeSwapCooldown, MintRateLimit,
+        SwapFromAlloyedConstraint, SwapToAlloyedConstraint, SwapVariant,
+    },
     transmuter_pool::TransmuterPool,
+    volume::{MintVolumes, SwapVolumes},
 };
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    ensure, ensure_ne, Addr, Coin, Decimal, DepsMut, Env, Reply, Response, StdError, Storage,
-    SubMsg, Uint128,
+    ensure, ensure_ne, Addr, BankMsg, Coin, Decimal, Decimal256, DepsMut, Env, Int256, Order,
+    Reply, Response, SignedDecimal, StdResult, Storage, SubMsg, Timestamp, Uint128, Uint64,
 };
 
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
 use osmosis_std::types::{
     cosmos::bank::v1beta1::Metadata,
-    osmosis::tokenfactory::v1beta1::{MsgCreateDenom, MsgCreateDenomResponse, MsgSetDenomMetadata},
+    osmosis::tokenfactory::v1beta1::{
+        MsgCreateDenom, MsgCreateDenomResponse, MsgMint, MsgSetDenomMetadata,
+    },
 };
 
 use sylvia::{
@@ -32,27 +51,247 @@ use sylvia::{
 pub const CONTRACT_NAME: &str = "crates.io:transmuter";
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-const CREATE_ALLOYED_DENOM_REPLY_ID: u64 = 1;
+/// Ids of submessages that require reply, each is routed to its own reply handler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub enum ReplyId {
+    CreateAlloyedDenom = 1,
+    CreateRegisteredAlloyedDenom = 2,
+}
+
+impl ReplyId {
+    pub const fn id(self) -> u64 {
+        self as u64
+    }
+}
+
+impl TryFrom<u64> for ReplyId {
+    type Error = ContractError;
+
+    fn try_from(id: u64) -> Result<Self, Self::Error> {
+        match id {
+            id if id == ReplyId::CreateAlloyedDenom.id() => Ok(ReplyId::CreateAlloyedDenom),
+            id if id == ReplyId::CreateRegisteredAlloyedDenom.id() => {
+                Ok(ReplyId::CreateRegisteredAlloyedDenom)
+            }
+            _ => Err(ContractError::UnknownReplyId { id }),
+        }
+    }
+}
+
+/// Operations that are paused while the pool is inactive, depending on the [`PauseScope`]
+#[cw_serde]
+pub enum PauseScope {
+    /// Pause all operations
+    All,
+    /// Pause swaps only, joining and exiting pool are still allowed,
+    /// so that liquidity can still be withdrawn while trading is halted
+    SwapsOnly,
+    /// Pause joining and exiting pool only, swaps are still allowed
+    JoinsOnly,
+}
+
+impl PauseScope {
+    pub fn pauses(&self, operation: PausableOperation) -> bool {
+        match self {
+            PauseScope::All => true,
+            PauseScope::SwapsOnly => operation == PausableOperation::Swap,
+            PauseScope::JoinsOnly => operation == PausableOperation::JoinOrExit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PausableOperation {
+    Swap,
+    JoinOrExit,
+    /// Any other operation, only paused by [`PauseScope::All`]
+    Other,
+}
+
+/// Which senders are allowed to swap, see [`Transmuter::ensure_sender_allowed`]
+#[cw_serde]
+#[derive(Default)]
+pub enum SenderAccessMode {
+    /// Any sender is allowed
+    #[default]
+    Open,
+    /// Only senders on the access list are allowed
+    Allowlist,
+    /// Senders on the access list are rejected
+    Denylist,
+}
+
+fn pause_scope_attribute(active: bool, scope: Option<PauseScope>) -> String {
+    match (active, scope) {
+        (true, _) => "none".to_string(),
+        (false, None) | (false, Some(PauseScope::All)) => "all".to_string(),
+        (false, Some(PauseScope::SwapsOnly)) => "swaps_only".to_string(),
+        (false, Some(PauseScope::JoinsOnly)) => "joins_only".to_string(),
+    }
+}
 
 /// Prefix for alloyed asset denom
 const ALLOYED_PREFIX: &str = "alloyed";
 
+/// Max subdenom length allowed by tokenfactory
+const MAX_TOKENFACTORY_SUBDENOM_LENGTH: usize = 44;
+
+/// Max number of quotes in a single batch quote query
+pub const MAX_BATCH_QUOTES: usize = 20;
+
+/// Max number of asset groups until set by admin
+pub const DEFAULT_MAX_ASSET_GROUPS: u32 = 20;
+
+/// Number of limiters and asset groups evaluated by a swap, below which it is [`SwapComplexityClass::Low`]
+pub const SWAP_COMPLEXITY_MEDIUM_THRESHOLD: u64 = 5;
+
+/// Number of limiters and asset groups evaluated by a swap, from which it is [`SwapComplexityClass::High`]
+pub const SWAP_COMPLEXITY_HIGH_THRESHOLD: u64 = 20;
+
+/// Distance from a limiter's upper limit within which the limiter is considered binding
+pub const LIMITER_BINDING_EPSILON: Decimal = Decimal::permille(1);
+
+/// Distance from 1 within which target weights are considered to sum to 1,
+/// to allow for weights that can't be represented exactly, e.g. 1/3
+pub const TARGET_WEIGHTS_SUM_TOLERANCE: Decimal = Decimal::raw(1_000_000_000_000);
+
 pub struct Transmuter<'a> {
     pub(crate) active_status: Item<'a, bool>,
+    pub(crate) pause_scope: Item<'a, PauseScope>,
+    pub(crate) reactivate_at: Item<'a, Timestamp>,
     pub(crate) pool: Item<'a, TransmuterPool>,
     pub(crate) alloyed_asset: AlloyedAsset<'a>,
     pub(crate) role: Role<'a>,
     pub(crate) limiters: Limiters<'a>,
+    pub(crate) fee: Fee<'a>,
+    pub(crate) max_swap_value: Item<'a, Uint128>,
+    pub(crate) max_total_liquidity: Item<'a, Uint128>,
+    pub(crate) amount_tolerance: Item<'a, Uint128>,
+    pub(crate) large_swap_cooldown: Item<'a, LargeSwapCooldown>,
+    pub(crate) last_large_swap_times: Map<'a, &'a Addr, Timestamp>,
+    pub(crate) mint_rate_limit: Item<'a, MintRateLimit>,
+    pub(crate) mint_volumes: MintVolumes<'a>,
+    pub(crate) asset_groups: AssetGroups<'a>,
+    pub(crate) governance_log: GovernanceLog<'a>,
+    pub(crate) corruption_drain_incentive: Item<'a, Decimal>,
+    pub(crate) pool_snapshots: PoolSnapshots<'a>,
+    /// Cache of bank denom metadata, to avoid repeated bank queries
+    pub(crate) denom_metadata_cache: DenomMetadataCache<'a>,
+    pub(crate) instantiate_params: Item<'a, InstantiateParams>,
+    pub(crate) denom_aliases: Map<'a, &'a str, String>,
+    pub(crate) directly_corrupted_denoms: Map<'a, &'a str, ()>,
+    /// Cumulative value of burned alloyed asset retained in the pool due to rounding,
+    /// in alloyed asset amount
+    pub(crate) retained_dust: Item<'a, Decimal256>,
+    pub(crate) min_locked_shares: Item<'a, Uint128>,
+    pub(crate) locked_shares: Item<'a, Uint128>,
+    pub(crate) alloyed_holders: AlloyedHolders<'a>,
+    /// Pool balances left below this amount after being swapped out are swept into retained dust
+    pub(crate) dust_threshold: Item<'a, Uint128>,
+    /// Alloyed assets registered in addition to the main alloyed asset,
+    /// each backed by its own subset of pool asset denoms
+    pub(crate) alloyed_registry: AlloyedAssetRegistry<'a>,
+    /// Operator-defined target weight per denom, advisory only and not enforced
+    pub(crate) target_weights: Map<'a, &'a str, Decimal>,
+    pub(crate) swap_volumes: SwapVolumes<'a>,
+    pub(crate) alloyed_minted_volumes: SwapVolumes<'a>,
+    pub(crate) alloyed_burned_volumes: SwapVolumes<'a>,
+    /// Osmosis cosmwasm pool id of this contract, which is not known to the contract at instantiation
+    pub(crate) pool_id: Item<'a, u64>,
+    /// Whether swaps increasing a denom without any registered limiter are rejected
+    pub(crate) require_limiter_coverage: Item<'a, bool>,
+    /// Map of denom -> number of decimals of its display unit, for human readable amounts
+    pub(crate) display_decimals: Map<'a, &'a str, u32>,
+    /// Whether token to token swaps may leave a non-corrupted denom with zero pool balance
+    pub(crate) allow_full_drain: Item<'a, bool>,
+    /// Whether rounding residuals of token to token swaps are recorded, defaults to false
+    pub(crate) enable_rounding_audit: Item<'a, bool>,
+    pub(crate) rounding_audit: RoundingAuditLog<'a>,
+    /// Retained swap fee accrued per denom and not yet withdrawn
+    pub(crate) accrued_fees: Map<'a, &'a str, Uint128>,
+    /// Oracle driven corruption config per denom
+    pub(crate) auto_corruption_configs: Map<'a, &'a str, AutoCorruptionConfig>,
+    /// Max number of asset groups, since every group is checked on corruption updates and swaps
+    pub(crate) max_asset_groups: Item<'a, u32>,
+    /// Min number of non-corrupted pool assets that marking assets as corrupted must leave
+    pub(crate) min_active_assets: Item<'a, u32>,
+    pub(crate) sender_access_mode: Item<'a, SenderAccessMode>,
+    /// Senders allowed or denied depending on the sender access mode
+    pub(crate) sender_access_list: Map<'a, &'a Addr, ()>,
+    pub(crate) alloyed_allowances: AlloyedAllowances<'a>,
 }
 
 pub mod key {
     pub const ACTIVE_STATUS: &str = "active_status";
+    pub const PAUSE_SCOPE: &str = "pause_scope";
+    pub const REACTIVATE_AT: &str = "reactivate_at";
     pub const POOL: &str = "pool";
     pub const ALLOYED_ASSET_DENOM: &str = "alloyed_denom";
     pub const ALLOYED_ASSET_NORMALIZATION_FACTOR: &str = "alloyed_asset_normalization_factor";
     pub const ADMIN: &str = "admin";
     pub const MODERATOR: &str = "moderator";
     pub const LIMITERS: &str = "limiters";
+    pub const TEMPORARY_LIMITER_BOUNDS: &str = "temporary_limiter_bounds";
+    pub const LIMITER_TRIP_COUNTS: &str = "limiter_trip_counts";
+    pub const LIMITER_TRIP_LOG_NEXT_ID: &str = "limiter_trip_log_next_id";
+    pub const LIMITER_TRIP_LOG: &str = "limiter_trip_log";
+    pub const LIMITER_STRESS_CONFIG: &str = "limiter_stress_config";
+    pub const SWAP_FEE: &str = "swap_fee";
+    pub const FEE_COLLECTOR: &str = "fee_collector";
+    pub const FEE_SPLIT: &str = "fee_split";
+    pub const DENOM_SWAP_FEES: &str = "denom_swap_fees";
+    pub const FEE_SIDE: &str = "fee_side";
+    pub const DYNAMIC_FEE_PARAMS: &str = "dynamic_fee_params";
+    pub const MAX_SWAP_VALUE: &str = "max_swap_value";
+    pub const MAX_TOTAL_LIQUIDITY: &str = "max_total_liquidity";
+    pub const AMOUNT_TOLERANCE: &str = "amount_tolerance";
+    pub const LARGE_SWAP_COOLDOWN: &str = "large_swap_cooldown";
+    pub const LAST_LARGE_SWAP_TIMES: &str = "last_large_swap_times";
+    pub const MINT_RATE_LIMIT: &str = "mint_rate_limit";
+    pub const MINT_VOLUME_BUCKETS: &str = "mint_volume_buckets";
+    pub const ASSET_GROUPS: &str = "asset_groups";
+    pub const GOVERNANCE_LOG_NEXT_ID: &str = "governance_log_next_id";
+    pub const GOVERNANCE_LOG: &str = "governance_log";
+    pub const CORRUPTION_DRAIN_INCENTIVE: &str = "corruption_drain_incentive";
+    pub const POOL_SNAPSHOT_INTERVAL: &str = "pool_snapshot_interval";
+    pub const POOL_SNAPSHOTS: &str = "pool_snapshots";
+    pub const DENOM_METADATA_CACHE: &str = "denom_metadata_cache";
+    pub const INSTANTIATE_PARAMS: &str = "instantiate_params";
+    pub const DENOM_ALIASES: &str = "denom_aliases";
+    pub const DIRECTLY_CORRUPTED_DENOMS: &str = "directly_corrupted_denoms";
+    pub const RETAINED_DUST: &str = "retained_dust";
+    pub const MIN_LOCKED_SHARES: &str = "min_locked_shares";
+    pub const LOCKED_SHARES: &str = "locked_shares";
+    pub const ALLOYED_HOLDER_COUNT: &str = "alloyed_holder_count";
+    pub const ALLOYED_HOLDERS: &str = "alloyed_holders";
+    pub const DUST_THRESHOLD: &str = "dust_threshold";
+    pub const PENDING_REGISTERED_ALLOYED_ASSET: &str = "pending_registered_alloyed_asset";
+    pub const REGISTERED_ALLOYED_ASSETS: &str = "registered_alloyed_assets";
+    pub const TARGET_WEIGHTS: &str = "target_weights";
+    pub const SWAP_VOLUME_BUCKETS: &str = "swap_volume_buckets";
+    pub const ALLOYED_MINTED_BUCKETS: &str = "alloyed_minted_buckets";
+    pub const ALLOYED_BURNED_BUCKETS: &str = "alloyed_burned_buckets";
+    pub const POOL_ID: &str = "pool_id";
+    pub const REQUIRE_LIMITER_COVERAGE: &str = "require_limiter_coverage";
+    pub const ALLOW_FULL_DRAIN: &str = "allow_full_drain";
+    pub const ENABLE_ROUNDING_AUDIT: &str = "enable_rounding_audit";
+    pub const ROUNDING_AUDIT_NEXT_ID: &str = "rounding_audit_next_id";
+    pub const ROUNDING_AUDIT: &str = "rounding_audit";
+    pub const DISPLAY_DECIMALS: &str = "display_decimals";
+    pub const ACCRUED_FEES: &str = "accrued_fees";
+    pub const AUTO_CORRUPTION_CONFIGS: &str = "auto_corruption_configs";
+    pub const MAX_ASSET_GROUPS: &str = "max_asset_groups";
+    pub const MIN_ACTIVE_ASSETS: &str = "min_active_assets";
+    pub const SENDER_ACCESS_MODE: &str = "sender_access_mode";
+    pub const SENDER_ACCESS_LIST: &str = "sender_access_list";
+    pub const ALLOYED_ALLOWANCES: &str = "alloyed_allowances";
+}
+
+impl Default for Transmuter<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[contract]
@@ -62,17 +301,77 @@ impl Transmuter<'_> {
     pub const fn new() -> Self {
         Self {
             active_status: Item::new(key::ACTIVE_STATUS),
+            pause_scope: Item::new(key::PAUSE_SCOPE),
+            reactivate_at: Item::new(key::REACTIVATE_AT),
             pool: Item::new(key::POOL),
             alloyed_asset: AlloyedAsset::new(
                 key::ALLOYED_ASSET_DENOM,
                 key::ALLOYED_ASSET_NORMALIZATION_FACTOR,
             ),
             role: Role::new(key::ADMIN, key::MODERATOR),
-            limiters: Limiters::new(key::LIMITERS),
+            limiters: Limiters::new(
+                key::LIMITERS,
+                key::TEMPORARY_LIMITER_BOUNDS,
+                key::LIMITER_TRIP_COUNTS,
+                key::LIMITER_TRIP_LOG_NEXT_ID,
+                key::LIMITER_TRIP_LOG,
+                key::LIMITER_STRESS_CONFIG,
+            ),
+            fee: Fee::new(
+                key::SWAP_FEE,
+                key::FEE_COLLECTOR,
+                key::FEE_SPLIT,
+                key::DENOM_SWAP_FEES,
+                key::FEE_SIDE,
+                key::DYNAMIC_FEE_PARAMS,
+            ),
+            max_swap_value: Item::new(key::MAX_SWAP_VALUE),
+            max_total_liquidity: Item::new(key::MAX_TOTAL_LIQUIDITY),
+            amount_tolerance: Item::new(key::AMOUNT_TOLERANCE),
+            large_swap_cooldown: Item::new(key::LARGE_SWAP_COOLDOWN),
+            last_large_swap_times: Map::new(key::LAST_LARGE_SWAP_TIMES),
+            mint_rate_limit: Item::new(key::MINT_RATE_LIMIT),
+            mint_volumes: MintVolumes::new(key::MINT_VOLUME_BUCKETS),
+            asset_groups: AssetGroups::new(key::ASSET_GROUPS),
+            governance_log: GovernanceLog::new(key::GOVERNANCE_LOG_NEXT_ID, key::GOVERNANCE_LOG),
+            corruption_drain_incentive: Item::new(key::CORRUPTION_DRAIN_INCENTIVE),
+            pool_snapshots: PoolSnapshots::new(key::POOL_SNAPSHOT_INTERVAL, key::POOL_SNAPSHOTS),
+            denom_metadata_cache: DenomMetadataCache::new(key::DENOM_METADATA_CACHE),
+            instantiate_params: Item::new(key::INSTANTIATE_PARAMS),
+            denom_aliases: Map::new(key::DENOM_ALIASES),
+            directly_corrupted_denoms: Map::new(key::DIRECTLY_CORRUPTED_DENOMS),
+            retained_dust: Item::new(key::RETAINED_DUST),
+            min_locked_shares: Item::new(key::MIN_LOCKED_SHARES),
+            locked_shares: Item::new(key::LOCKED_SHARES),
+            alloyed_holders: AlloyedHolders::new(key::ALLOYED_HOLDER_COUNT, key::ALLOYED_HOLDERS),
+            dust_threshold: Item::new(key::DUST_THRESHOLD),
+            alloyed_registry: AlloyedAssetRegistry::new(
+                key::PENDING_REGISTERED_ALLOYED_ASSET,
+                key::REGISTERED_ALLOYED_ASSETS,
+            ),
+            target_weights: Map::new(key::TARGET_WEIGHTS),
+            swap_volumes: SwapVolumes::new(key::SWAP_VOLUME_BUCKETS),
+            alloyed_minted_volumes: SwapVolumes::new(key::ALLOYED_MINTED_BUCKETS),
+            alloyed_burned_volumes: SwapVolumes::new(key::ALLOYED_BURNED_BUCKETS),
+            pool_id: Item::new(key::POOL_ID),
+            require_limiter_coverage: Item::new(key::REQUIRE_LIMITER_COVERAGE),
+            allow_full_drain: Item::new(key::ALLOW_FULL_DRAIN),
+            enable_rounding_audit: Item::new(key::ENABLE_ROUNDING_AUDIT),
+            rounding_audit: RoundingAuditLog::new(key::ROUNDING_AUDIT_NEXT_ID, key::ROUNDING_AUDIT),
+            display_decimals: Map::new(key::DISPLAY_DECIMALS),
+            accrued_fees: Map::new(key::ACCRUED_FEES),
+            auto_corruption_configs: Map::new(key::AUTO_CORRUPTION_CONFIGS),
+            max_asset_groups: Item::new(key::MAX_ASSET_GROUPS),
+            min_active_assets: Item::new(key::MIN_ACTIVE_ASSETS),
+            sender_access_mode: Item::new(key::SENDER_ACCESS_MODE),
+            sender_access_list: Map::new(key::SENDER_ACCESS_LIST),
+            alloyed_allowances: AlloyedAllowances::new(key::ALLOYED_ALLOWANCES),
         }
     }
 
     /// Instantiate the contract.
+    // each argument is a field of the instantiate msg, grouping them would change its schema
+    #[allow(clippy::too_many_arguments)]
     #[sv::msg(instantiate)]
     pub fn instantiate(
         &self,
@@ -82,12 +381,26 @@ impl Transmuter<'_> {
         alloyed_asset_normalization_factor: Uint128,
         admin: Option<String>,
         moderator: String,
+        enable_alloyed_asset: Option<bool>,
     ) -> Result<Response, ContractError> {
         nonpayable(&info.funds)?;
 
         // store contract version for migration info
         cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+        // store original params so that the pool can be redeployed with the same configuration
+        self.instantiate_params.save(
+            deps.storage,
+            &InstantiateParams {
+                pool_asset_configs: pool_asset_configs.clone(),
+                alloyed_asset_subdenom: alloyed_asset_subdenom.clone(),
+                alloyed_asset_normalization_factor,
+                admin: admin.clone(),
+                moderator: moderator.clone(),
+                enable_alloyed_asset,
+            },
+        )?;
+
         // set admin if exists
         if let Some(admin) = admin {
             self.role
@@ -120,48 +433,78 @@ impl Transmuter<'_> {
             }
         );
 
+        ensure_valid_alloyed_subdenom(&alloyed_asset_subdenom)?;
+
+        // set normalization factor for alloyed asset
+        self.alloyed_asset
+            .set_normalization_factor(deps.storage, alloyed_asset_normalization_factor)?;
+
+        let response = Response::new()
+            .add_attribute("method", "instantiate")
+            .add_attribute("contract_name", CONTRACT_NAME)
+            .add_attribute("contract_version", CONTRACT_VERSION);
+
+        // without alloyed asset, the pool only supports swaps between pool assets,
+        // so the alloyed denom is never created
+        if !enable_alloyed_asset.unwrap_or(true) {
+            return Ok(response.add_attribute("alloyed_asset", "disabled"));
+        }
+
         // create alloyed denom
         let msg_create_alloyed_denom = SubMsg::reply_on_success(
             MsgCreateDenom {
                 sender: env.contract.address.to_string(),
                 subdenom: format!("{}/{}", ALLOYED_PREFIX, alloyed_asset_subdenom),
             },
-            CREATE_ALLOYED_DENOM_REPLY_ID,
+            ReplyId::CreateAlloyedDenom.id(),
         );
 
-        // set normalization factor for alloyed asset
-        self.alloyed_asset
-            .set_normalization_factor(deps.storage, alloyed_asset_normalization_factor)?;
-
-        Ok(Response::new()
-            .add_attribute("method", "instantiate")
-            .add_attribute("contract_name", CONTRACT_NAME)
-            .add_attribute("contract_version", CONTRACT_VERSION)
-            .add_submessage(msg_create_alloyed_denom))
+        Ok(response.add_submessage(msg_create_alloyed_denom))
     }
 
     pub fn reply(&self, ctx: (DepsMut, Env), msg: Reply) -> Result<Response, ContractError> {
         let (deps, _env) = ctx;
 
-        match msg.id {
-            CREATE_ALLOYED_DENOM_REPLY_ID => {
-                // register created token denom
-                let MsgCreateDenomResponse { new_token_denom } = msg.result.try_into()?;
-                self.alloyed_asset
-                    .set_alloyed_denom(deps.storage, &new_token_denom)?;
-
-                Ok(Response::new().add_attribute("alloyed_denom", new_token_denom))
+        match ReplyId::try_from(msg.id)? {
+            ReplyId::CreateAlloyedDenom => self.reply_create_alloyed_denom(deps, msg),
+            ReplyId::CreateRegisteredAlloyedDenom => {
+                self.reply_create_registered_alloyed_denom(deps, msg)
             }
-            _ => Err(StdError::not_found(format!("No reply handler found for: {:?}", msg)).into()),
         }
     }
 
+    fn reply_create_registered_alloyed_denom(
+        &self,
+        deps: DepsMut,
+        msg: Reply,
+    ) -> Result<Response, ContractError> {
+        // register pending alloyed asset under the created token denom
+        let MsgCreateDenomResponse { new_token_denom } = msg.result.try_into()?;
+        self.alloyed_registry
+            .register_pending(deps.storage, &new_token_denom)?;
+
+        Ok(Response::new().add_attribute("registered_alloyed_denom", new_token_denom))
+    }
+
+    fn reply_create_alloyed_denom(
+        &self,
+        deps: DepsMut,
+        msg: Reply,
+    ) -> Result<Response, ContractError> {
+        // register created token denom
+        let MsgCreateDenomResponse { new_token_denom } = msg.result.try_into()?;
+        self.alloyed_asset
+            .set_alloyed_denom(deps.storage, &new_token_denom)?;
+
+        Ok(Response::new().add_attribute("alloyed_denom", new_token_denom))
+    }
+
     // === executes ===
 
     #[sv::msg(exec)]
     fn rescale_normalization_factor(
         &self,
-        ExecCtx { deps, env: _, info }: ExecCtx,
+        ExecCtx { deps, env, info }: ExecCtx,
         numerator: Uint128,
         denominator: Uint128,
     ) -> Result<Response, ContractError> {
@@ -170,6 +513,13 @@ impl Transmuter<'_> {
         // only admin can rescale normalization factor
         ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
 
+        self.governance_log.record(
+            deps.storage,
+            "rescale_normalization_factor",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
         // rescale normalization factor for pool assets
         self.pool.update(deps.storage, |pool| {
             pool.update_normalization_factor(|factor| {
@@ -193,6 +543,58 @@ impl Transmuter<'_> {
             .add_attribute("denominator", denominator))
     }
 
+    /// Correct the normalization factor of a single pool asset.
+    /// Since it reprices existing liquidity, changing it for a denom with non-zero balance
+    /// is rejected unless `force` is set and the pool is inactive.
+    #[sv::msg(exec)]
+    fn set_normalization_factor(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        denom: String,
+        factor: Uint128,
+        force: bool,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set normalization factor
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        let mut pool = self.pool.load(deps.storage)?;
+        let balance = pool.get_pool_asset_by_denom(&denom)?.amount();
+
+        ensure!(
+            balance.is_zero() || (force && !self.is_active_at(deps.storage, env.block.time)?),
+            ContractError::FundedDenomNormalizationFactorChange {
+                denom: denom.clone()
+            }
+        );
+
+        pool.set_normalization_factor(&denom, factor)?;
+
+        self.governance_log.record(
+            deps.storage,
+            "set_normalization_factor",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.pool.save(deps.storage, &pool)?;
+
+        // weights before and after the change are on different scales,
+        // so change limiter windows start over from the updated weights
+        self.limiters.reset_change_limiter_states(
+            deps.storage,
+            env.block.time,
+            pool.weights()?.unwrap_or_default(),
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_normalization_factor")
+            .add_attribute("denom", denom)
+            .add_attribute("factor", factor)
+            .add_attribute("force", force.to_string()))
+    }
+
     #[sv::msg(exec)]
     fn add_new_assets(
         &self,
@@ -205,12 +607,21 @@ impl Transmuter<'_> {
         // only admin can add new assets
         ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
 
-        // ensure that new denoms are not alloyed denom
-        let share_denom = self.alloyed_asset.get_alloyed_denom(deps.storage)?;
+        self.governance_log.record(
+            deps.storage,
+            "add_new_assets",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        // ensure that new denoms are not alloyed denom nor shadowed by a denom alias
         for cfg in &asset_configs {
+            self.ensure_not_pool_share_denom(deps.storage, &cfg.denom)?;
             ensure!(
-                cfg.denom != share_denom,
-                ContractError::ShareDenomNotAllowedAsPoolAsset {}
+                !self.denom_aliases.has(deps.storage, &cfg.denom),
+                ContractError::AmbiguousDenomAlias {
+                    alias: cfg.denom.clone()
+                }
             );
         }
 
@@ -242,7 +653,7 @@ impl Transmuter<'_> {
     #[sv::msg(exec)]
     fn mark_corrupted_assets(
         &self,
-        ExecCtx { deps, env: _, info }: ExecCtx,
+        ExecCtx { deps, env, info }: ExecCtx,
         denoms: Vec<String>,
     ) -> Result<Response, ContractError> {
         non_empty_input_required("denoms", &denoms)?;
@@ -251,11 +662,22 @@ impl Transmuter<'_> {
         // only moderator can mark corrupted assets
         ensure_moderator_authority!(info.sender, self.role.moderator, deps.as_ref());
 
-        self.pool
-            .update(deps.storage, |mut pool| -> Result<_, ContractError> {
-                pool.mark_corrupted_assets(&denoms)?;
-                Ok(pool)
-            })?;
+        self.governance_log.record(
+            deps.storage,
+            "mark_corrupted_assets",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        let mut pool = self.pool.load(deps.storage)?;
+        self.mark_pool_assets_corrupted(deps.storage, &mut pool, &denoms)?;
+        self.pool.save(deps.storage, &pool)?;
+
+        // marked denoms are directly corrupted, same as `mark_denom_corrupted`
+        for denom in denoms.iter() {
+            self.directly_corrupted_denoms
+                .save(deps.storage, denom, &())?;
+        }
 
         Ok(Response::new().add_attribute("method", "mark_corrupted_assets"))
     }
@@ -263,7 +685,7 @@ impl Transmuter<'_> {
     #[sv::msg(exec)]
     fn unmark_corrupted_assets(
         &self,
-        ExecCtx { deps, env: _, info }: ExecCtx,
+        ExecCtx { deps, env, info }: ExecCtx,
         denoms: Vec<String>,
     ) -> Result<Response, ContractError> {
         non_empty_input_required("denoms", &denoms)?;
@@ -272,942 +694,15714 @@ impl Transmuter<'_> {
         // only moderator can unmark corrupted assets
         ensure_moderator_authority!(info.sender, self.role.moderator, deps.as_ref());
 
-        self.pool
-            .update(deps.storage, |mut pool| -> Result<_, ContractError> {
-                pool.unmark_corrupted_assets(&denoms)?;
-                Ok(pool)
-            })?;
+        self.governance_log.record(
+            deps.storage,
+            "unmark_corrupted_assets",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        // denoms of corrupted asset groups remain corrupted, same as `unmark_denom_corrupted`
+        let mut pool = self.pool.load(deps.storage)?;
+        for denom in denoms.iter() {
+            ensure!(
+                pool.is_corrupted_asset(denom),
+                ContractError::InvalidCorruptedAssetDenom {
+                    denom: denom.clone()
+                }
+            );
+
+            if !self.is_corrupted_by_asset_group(deps.storage, denom, &[])? {
+                pool.unmark_corrupted_assets(std::slice::from_ref(denom))?;
+            }
+        }
+        self.pool.save(deps.storage, &pool)?;
+
+        for denom in denoms.iter() {
+            self.directly_corrupted_denoms.remove(deps.storage, denom);
+        }
 
         Ok(Response::new().add_attribute("method", "unmark_corrupted_assets"))
     }
 
+    /// Mark a single denom as corrupted, independent of the asset groups it belongs to.
+    /// The denom stays corrupted until it is unmarked directly,
+    /// even if a corrupted group containing it is unmarked.
     #[sv::msg(exec)]
-    fn register_limiter(
+    fn mark_denom_corrupted(
         &self,
-        ExecCtx { deps, env: _, info }: ExecCtx,
+        ExecCtx { deps, env, info }: ExecCtx,
         denom: String,
-        label: String,
-        limiter_params: LimiterParams,
     ) -> Result<Response, ContractError> {
         nonpayable(&info.funds)?;
 
-        // only admin can register limiter
+        // only admin can mark denom as corrupted
         ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
 
-        // ensure pool has the specified denom
-        let pool = self.pool.load(deps.storage)?;
-        ensure!(
-            pool.has_denom(&denom),
-            ContractError::InvalidPoolAssetDenom { denom }
-        );
-
-        let base_attrs = vec![
-            ("method", "register_limiter"),
-            ("denom", &denom),
-            ("label", &label),
-        ];
-        let limiter_attrs = match &limiter_params {
-            LimiterParams::ChangeLimiter {
-                window_config,
-                boundary_offset,
-            } => {
-                let window_size = window_config.window_size.to_string();
-                let division_count = window_config.division_count.to_string();
-                let boundary_offset_string = boundary_offset.to_string();
+        self.governance_log.record(
+            deps.storage,
+            "mark_denom_corrupted",
+            Some(&info.sender),
+            env.block.time,
+        )?;
 
-                vec![
-                    (String::from("limiter_type"), String::from("change_limiter")),
-                    (String::from("window_size"), window_size),
-                    (String::from("division_count"), division_count),
-                    (String::from("boundary_offset"), boundary_offset_string),
-                ]
-            }
-            LimiterParams::StaticLimiter { upper_limit } => vec![
-                (String::from("limiter_type"), String::from("static_limiter")),
-                (String::from("upper_limit"), upper_limit.to_string()),
-            ],
-        };
+        let mut pool = self.pool.load(deps.storage)?;
+        self.mark_pool_assets_corrupted(deps.storage, &mut pool, std::slice::from_ref(&denom))?;
+        self.pool.save(deps.storage, &pool)?;
 
-        // register limiter
-        self.limiters
-            .register(deps.storage, &denom, &label, limiter_params)?;
+        self.directly_corrupted_denoms
+            .save(deps.storage, &denom, &())?;
 
         Ok(Response::new()
-            .add_attributes(base_attrs)
-            .add_attributes(limiter_attrs))
+            .add_attribute("method", "mark_denom_corrupted")
+            .add_attribute("denom", denom))
     }
 
+    /// Set the oracle and threshold for `denom` to be marked as corrupted automatically
+    /// once its oracle price deviates from the peg beyond the threshold, see `check_and_mark_corruption`.
+    /// Removes the config if `None`.
     #[sv::msg(exec)]
-    fn deregister_limiter(
+    fn set_auto_corruption_config(
         &self,
-        ExecCtx { deps, env: _, info }: ExecCtx,
+        ExecCtx { deps, env, info }: ExecCtx,
         denom: String,
-        label: String,
+        auto_corruption_config: Option<AutoCorruptionConfig>,
     ) -> Result<Response, ContractError> {
         nonpayable(&info.funds)?;
 
-        // only admin can deregister limiter
+        // only admin can set auto corruption config
         ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
 
-        let attrs = vec![
-            ("method", "deregister_limiter"),
-            ("denom", &denom),
-            ("label", &label),
-        ];
+        self.governance_log.record(
+            deps.storage,
+            "set_auto_corruption_config",
+            Some(&info.sender),
+            env.block.time,
+        )?;
 
-        // deregister limiter
-        self.limiters.deregister(deps.storage, &denom, &label)?;
+        let response = Response::new()
+            .add_attribute("method", "set_auto_corruption_config")
+            .add_attribute("denom", &denom);
 
-        Ok(Response::new().add_attributes(attrs))
+        let Some(config) = auto_corruption_config else {
+            self.auto_corruption_configs.remove(deps.storage, &denom);
+            return Ok(response.add_attribute("auto_corruption_config", "none"));
+        };
+
+        ensure!(
+            self.pool.load(deps.storage)?.has_denom(&denom),
+            ContractError::InvalidPoolAssetDenom { denom }
+        );
+        deps.api.addr_validate(config.oracle.as_str())?;
+
+        self.auto_corruption_configs
+            .save(deps.storage, &denom, &config)?;
+
+        Ok(response
+            .add_attribute("oracle", config.oracle)
+            .add_attribute("threshold", config.threshold.to_string()))
     }
 
+    /// Query the oracle configured for `denom` and mark it as corrupted
+    /// if its price deviates from the peg beyond the threshold. Callable by anyone.
+    ///
+    /// The denom is marked as if by `mark_denom_corrupted`, so it stays corrupted until unmarked directly.
+    /// Unlike manual marking, it is not bound by the min active assets,
+    /// so that a depegged asset can't stay tradable because of it.
     #[sv::msg(exec)]
-    fn set_change_limiter_boundary_offset(
+    fn check_and_mark_corruption(
         &self,
-        ExecCtx { deps, env: _, info }: ExecCtx,
+        ExecCtx { deps, env, info }: ExecCtx,
         denom: String,
-        label: String,
-        boundary_offset: Decimal,
     ) -> Result<Response, ContractError> {
         nonpayable(&info.funds)?;
 
-        // only admin can set boundary offset
-        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+        let config = self
+            .auto_corruption_configs
+            .may_load(deps.storage, &denom)?
+            .ok_or_else(|| ContractError::AutoCorruptionNotConfigured {
+                denom: denom.clone(),
+            })?;
 
-        let boundary_offset_string = boundary_offset.to_string();
-        let attrs = vec![
-            ("method", "set_change_limiter_boundary_offset"),
-            ("denom", &denom),
-            ("label", &label),
-            ("boundary_offset", boundary_offset_string.as_str()),
-        ];
+        let price = oracle::query_price(&deps.querier, &config.oracle, &denom)?;
+        let mut pool = self.pool.load(deps.storage)?;
+        let marked_corrupted = config.is_out_of_band(price) && !pool.is_corrupted_asset(&denom);
 
-        // set boundary offset
-        self.limiters.set_change_limiter_boundary_offset(
-            deps.storage,
-            &denom,
-            &label,
-            boundary_offset,
-        )?;
+        if marked_corrupted {
+            self.governance_log.record(
+                deps.storage,
+                "check_and_mark_corruption",
+                Some(&info.sender),
+                env.block.time,
+            )?;
 
-        Ok(Response::new().add_attributes(attrs))
+            pool.mark_corrupted_assets(std::slice::from_ref(&denom))?;
+            self.pool.save(deps.storage, &pool)?;
+            self.directly_corrupted_denoms
+                .save(deps.storage, &denom, &())?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("method", "check_and_mark_corruption")
+            .add_attribute("denom", denom)
+            .add_attribute("price", price.to_string())
+            .add_attribute("marked_corrupted", marked_corrupted.to_string()))
     }
 
+    /// Unmark a denom that was directly marked as corrupted.
+    /// The denom remains corrupted if it belongs to a corrupted asset group.
     #[sv::msg(exec)]
-    fn set_static_limiter_upper_limit(
+    fn unmark_denom_corrupted(
         &self,
-        ExecCtx { deps, env: _, info }: ExecCtx,
+        ExecCtx { deps, env, info }: ExecCtx,
         denom: String,
-        label: String,
-        upper_limit: Decimal,
     ) -> Result<Response, ContractError> {
         nonpayable(&info.funds)?;
 
-        // only admin can set upper limit
+        // only admin can unmark denom as corrupted
         ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
 
-        let upper_limit_string = upper_limit.to_string();
-        let attrs = vec![
-            ("method", "set_static_limiter_upper_limit"),
-            ("denom", &denom),
-            ("label", &label),
-            ("upper_limit", upper_limit_string.as_str()),
-        ];
-
-        // set upper limit
-        self.limiters
-            .set_static_limiter_upper_limit(deps.storage, &denom, &label, upper_limit)?;
+        self.governance_log.record(
+            deps.storage,
+            "unmark_denom_corrupted",
+            Some(&info.sender),
+            env.block.time,
+        )?;
 
-        Ok(Response::new().add_attributes(attrs))
+        ensure!(
+            self.directly_corrupted_denoms.has(deps.storage, &denom),
+            ContractError::InvalidCorruptedAssetDenom { denom }
+        );
+
+        if !self.is_corrupted_by_asset_group(deps.storage, &denom, &[])? {
+            self.pool
+                .update(deps.storage, |mut pool| -> Result<_, ContractError> {
+                    pool.unmark_corrupted_assets(std::slice::from_ref(&denom))?;
+                    Ok(pool)
+                })?;
+        }
+
+        self.directly_corrupted_denoms.remove(deps.storage, &denom);
+
+        Ok(Response::new()
+            .add_attribute("method", "unmark_denom_corrupted")
+            .add_attribute("denom", denom))
+    }
+
+    /// Check if denom belongs to any corrupted asset group, other than the `excluded` ones,
+    /// e.g. groups that are about to be unmarked.
+    fn is_corrupted_by_asset_group(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+        excluded: &[String],
+    ) -> StdResult<bool> {
+        Ok(self
+            .asset_groups
+            .list(storage)?
+            .into_iter()
+            .filter(|(label, _)| !excluded.contains(label))
+            .any(|(_, group)| group.is_corrupted() && group.denoms().iter().any(|d| d == denom)))
+    }
+
+    /// Mark `denoms` of `pool` as corrupted, ensuring that it does not drop the number of
+    /// active, i.e. non-corrupted, assets below the min active assets.
+    /// Marking that doesn't reduce active assets is always allowed, e.g. re-marking corrupted denoms.
+    fn mark_pool_assets_corrupted(
+        &self,
+        storage: &dyn Storage,
+        pool: &mut TransmuterPool,
+        denoms: &[String],
+    ) -> Result<(), ContractError> {
+        let count_active_assets = |pool: &TransmuterPool| {
+            pool.pool_assets
+                .iter()
+                .filter(|asset| !asset.is_corrupted())
+                .count() as u32
+        };
+
+        let prev_active_assets = count_active_assets(pool);
+        pool.mark_corrupted_assets(denoms)?;
+        let active_assets = count_active_assets(pool);
+
+        let min_active_assets = self
+            .min_active_assets
+            .may_load(storage)?
+            .unwrap_or_default();
+        ensure!(
+            active_assets >= prev_active_assets || active_assets >= min_active_assets,
+            ContractError::TooFewActiveAssets {
+                active_assets,
+                min_active_assets,
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Ensure `sender` is allowed to swap or join pool under the sender access mode.
+    /// Exiting pool is never restricted, so that liquidity can't be trapped.
+    pub(crate) fn ensure_sender_allowed(
+        &self,
+        storage: &dyn Storage,
+        sender: &Addr,
+    ) -> Result<(), ContractError> {
+        let is_listed = self.sender_access_list.has(storage, sender);
+        let is_allowed = match self
+            .sender_access_mode
+            .may_load(storage)?
+            .unwrap_or_default()
+        {
+            SenderAccessMode::Open => true,
+            SenderAccessMode::Allowlist => is_listed,
+            SenderAccessMode::Denylist => !is_listed,
+        };
+
+        ensure!(
+            is_allowed,
+            ContractError::SenderNotAllowed {
+                sender: sender.to_string()
+            }
+        );
+
+        Ok(())
+    }
+
+    fn load_max_asset_groups(&self, storage: &dyn Storage) -> StdResult<u32> {
+        Ok(self
+            .max_asset_groups
+            .may_load(storage)?
+            .unwrap_or(DEFAULT_MAX_ASSET_GROUPS))
+    }
+
+    /// Ensure denom is neither the alloyed denom nor a registered alloyed asset denom,
+    /// since treating pool shares as a pool asset would break the pool's accounting.
+    fn ensure_not_pool_share_denom(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+    ) -> Result<(), ContractError> {
+        let is_share_denom = self
+            .alloyed_asset
+            .may_get_alloyed_denom(storage)?
+            .as_deref()
+            == Some(denom)
+            || self.alloyed_registry.is_registered(storage, denom);
+
+        ensure!(
+            !is_share_denom,
+            ContractError::CannotUsePoolShareDenom {
+                denom: denom.to_string()
+            }
+        );
+
+        Ok(())
     }
 
+    /// Create asset group with designated label and denoms.
+    /// Asset group allows pool assets to be managed together, e.g. marked as corrupted at once.
     #[sv::msg(exec)]
-    pub fn set_alloyed_denom_metadata(
+    fn create_asset_group(
         &self,
         ExecCtx { deps, env, info }: ExecCtx,
-        metadata: Metadata,
+        label: String,
+        denoms: Vec<String>,
     ) -> Result<Response, ContractError> {
+        non_empty_input_required("denoms", &denoms)?;
         nonpayable(&info.funds)?;
 
-        // only admin can set denom metadata
+        // only admin can create asset group
         ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
 
-        let msg_set_denom_metadata = MsgSetDenomMetadata {
-            sender: env.contract.address.to_string(),
-            metadata: Some(metadata),
-        };
+        self.governance_log.record(
+            deps.storage,
+            "create_asset_group",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        // ensure that all denoms are pool assets
+        let pool = self.pool.load(deps.storage)?;
+        for denom in denoms.iter() {
+            self.ensure_not_pool_share_denom(deps.storage, denom)?;
+            ensure!(
+                pool.has_denom(denom),
+                ContractError::InvalidPoolAssetDenom {
+                    denom: denom.to_string()
+                }
+            );
+        }
+
+        let max = self.load_max_asset_groups(deps.storage)?;
+        ensure!(
+            self.asset_groups.count(deps.storage) < max as usize,
+            ContractError::TooManyAssetGroups { max }
+        );
+
+        self.asset_groups.create(deps.storage, &label, denoms)?;
 
         Ok(Response::new()
-            .add_attribute("method", "set_alloyed_denom_metadata")
-            .add_message(msg_set_denom_metadata))
+            .add_attribute("method", "create_asset_group")
+            .add_attribute("label", label))
     }
 
+    /// Import limiters, asset groups and config from a dump exported by `export_state`,
+    /// e.g. to move configuration to a contract of a new code id.
+    /// Only valid on a fresh contract, i.e. one without liquidity, limiters or asset groups.
     #[sv::msg(exec)]
-    fn set_active_status(
+    fn import_state(
         &self,
-        ExecCtx { deps, env: _, info }: ExecCtx,
-        active: bool,
+        ExecCtx { deps, env, info }: ExecCtx,
+        dump: FullStateDump,
     ) -> Result<Response, ContractError> {
         nonpayable(&info.funds)?;
 
-        // only moderator can set active status
-        ensure_moderator_authority!(info.sender, self.role.moderator, deps.as_ref());
+        // only admin can import state
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
 
-        // set active status
-        self.checked_set_active_status(deps.storage, active)?;
+        let version = dump.version;
+        self.import_state_dump(deps.storage, dump)?;
+
+        self.governance_log.record(
+            deps.storage,
+            "import_state",
+            Some(&info.sender),
+            env.block.time,
+        )?;
 
         Ok(Response::new()
-            .add_attribute("method", "set_active_status")
-            .add_attribute("active", active.to_string()))
+            .add_attribute("method", "import_state")
+            .add_attribute("version", version.to_string()))
     }
 
-    pub(crate) fn checked_set_active_status(
+    /// Set which senders are allowed to swap and join pool, defaults to [`SenderAccessMode::Open`].
+    /// The access list is kept across mode changes.
+    #[sv::msg(exec)]
+    fn set_sender_access_mode(
         &self,
-        storage: &mut dyn Storage,
-        active: bool,
-    ) -> Result<bool, ContractError> {
-        self.active_status
-            .update(storage, |prev_active| -> Result<bool, ContractError> {
-                ensure_ne!(
-                    prev_active,
-                    active,
-                    ContractError::UnchangedActiveStatus { status: active }
-                );
+        ExecCtx { deps, env, info }: ExecCtx,
+        mode: SenderAccessMode,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
 
-                Ok(active)
-            })
+        // only admin can set sender access mode
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_sender_access_mode",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.sender_access_mode.save(deps.storage, &mode)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_sender_access_mode")
+            .add_attribute(
+                "mode",
+                match mode {
+                    SenderAccessMode::Open => "open",
+                    SenderAccessMode::Allowlist => "allowlist",
+                    SenderAccessMode::Denylist => "denylist",
+                },
+            ))
     }
 
-    /// Join pool with tokens that exist in the pool.
-    /// Token used to join pool is sent to the contract via `funds` in `MsgExecuteContract`.
+    /// Add and remove senders on the access list, which is an allowlist or denylist
+    /// depending on the sender access mode.
     #[sv::msg(exec)]
-    pub fn join_pool(
+    fn update_sender_access_list(
         &self,
         ExecCtx { deps, env, info }: ExecCtx,
+        add: Vec<String>,
+        remove: Vec<String>,
     ) -> Result<Response, ContractError> {
-        self.swap_tokens_to_alloyed_asset(
-            Entrypoint::Exec,
-            SwapToAlloyedConstraint::ExactIn {
-                tokens_in: &info.funds,
-                token_out_min_amount: Uint128::zero(),
-            },
-            info.sender,
-            deps,
-            env,
-        )
-        .map(|res| res.add_attribute("method", "join_pool"))
+        nonpayable(&info.funds)?;
+
+        // only admin can update sender access list
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "update_sender_access_list",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        for sender in add.iter() {
+            let sender = deps.api.addr_validate(sender)?;
+            self.sender_access_list.save(deps.storage, &sender, &())?;
+        }
+
+        for sender in remove.iter() {
+            let sender = deps.api.addr_validate(sender)?;
+            self.sender_access_list.remove(deps.storage, &sender);
+        }
+
+        Ok(Response::new()
+            .add_attribute("method", "update_sender_access_list")
+            .add_attribute("added", add.join(","))
+            .add_attribute("removed", remove.join(",")))
     }
 
-    /// Exit pool with `tokens_out` amount of tokens.
-    /// As long as the sender has enough shares, the contract will send `tokens_out` amount of tokens to the sender.
-    /// The amount of shares will be deducted from the sender's shares.
+    /// Set the max number of asset groups, defaults to `DEFAULT_MAX_ASSET_GROUPS`.
+    /// Existing groups are kept if they exceed the new max, only further creation is rejected.
     #[sv::msg(exec)]
-    pub fn exit_pool(
+    fn set_max_asset_groups(
         &self,
         ExecCtx { deps, env, info }: ExecCtx,
-        tokens_out: Vec<Coin>,
+        max_asset_groups: u32,
     ) -> Result<Response, ContractError> {
-        // it will deduct shares directly from the sender's account
         nonpayable(&info.funds)?;
 
-        self.swap_alloyed_asset_to_tokens(
-            Entrypoint::Exec,
-            SwapFromAlloyedConstraint::ExactOut {
-                tokens_out: &tokens_out,
-                token_in_max_amount: Uint128::MAX,
-            },
-            BurnTarget::SenderAccount,
-            info.sender,
-            deps,
-            env,
-        )
-        .map(|res| res.add_attribute("method", "exit_pool"))
-    }
+        // only admin can set max asset groups
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
 
-    // === queries ===
+        self.governance_log.record(
+            deps.storage,
+            "set_max_asset_groups",
+            Some(&info.sender),
+            env.block.time,
+        )?;
 
-    #[sv::msg(query)]
-    fn list_asset_configs(
-        &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-    ) -> Result<ListAssetConfigsResponse, ContractError> {
-        let pool = self.pool.load(deps.storage)?;
-        let alloyed_asset_config = AssetConfig {
-            denom: self.alloyed_asset.get_alloyed_denom(deps.storage)?,
-            normalization_factor: self.alloyed_asset.get_normalization_factor(deps.storage)?,
-        };
+        self.max_asset_groups
+            .save(deps.storage, &max_asset_groups)?;
 
-        Ok(ListAssetConfigsResponse {
-            asset_configs: pool
-                .pool_assets
-                .iter()
-                .map(|asset| asset.config())
-                .chain(iter::once(alloyed_asset_config))
-                .collect(),
-        })
+        Ok(Response::new()
+            .add_attribute("method", "set_max_asset_groups")
+            .add_attribute("max_asset_groups", max_asset_groups.to_string()))
     }
 
-    #[sv::msg(query)]
-    fn list_limiters(
+    /// Set the min number of active, i.e. non-corrupted, pool assets, defaults to 0.
+    /// Marking denoms or asset groups as corrupted is rejected if it would leave fewer,
+    /// so that the pool can't be corrupted into a single asset state by accident.
+    #[sv::msg(exec)]
+    fn set_min_active_assets(
         &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-    ) -> Result<ListLimitersResponse, ContractError> {
-        let limiters = self.limiters.list_limiters(deps.storage)?;
+        ExecCtx { deps, env, info }: ExecCtx,
+        min_active_assets: u32,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
 
-        Ok(ListLimitersResponse { limiters })
-    }
+        // only admin can set min active assets
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
 
-    #[sv::msg(query)]
-    pub fn get_shares(
-        &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-        address: String,
-    ) -> Result<GetSharesResponse, ContractError> {
-        Ok(GetSharesResponse {
-            shares: self
-                .alloyed_asset
-                .get_balance(deps, &deps.api.addr_validate(&address)?)?,
-        })
-    }
+        self.governance_log.record(
+            deps.storage,
+            "set_min_active_assets",
+            Some(&info.sender),
+            env.block.time,
+        )?;
 
-    #[sv::msg(query)]
-    pub(crate) fn get_share_denom(
-        &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-    ) -> Result<GetShareDenomResponse, ContractError> {
-        Ok(GetShareDenomResponse {
-            share_denom: self.alloyed_asset.get_alloyed_denom(deps.storage)?,
-        })
-    }
+        self.min_active_assets
+            .save(deps.storage, &min_active_assets)?;
 
-    #[sv::msg(query)]
-    pub(crate) fn get_swap_fee(&self, _ctx: QueryCtx) -> Result<GetSwapFeeResponse, ContractError> {
-        Ok(GetSwapFeeResponse { swap_fee: SWAP_FEE })
+        Ok(Response::new()
+            .add_attribute("method", "set_min_active_assets")
+            .add_attribute("min_active_assets", min_active_assets.to_string()))
     }
 
-    #[sv::msg(query)]
-    pub(crate) fn is_active(
+    #[sv::msg(exec)]
+    fn remove_asset_group(
         &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-    ) -> Result<IsActiveResponse, ContractError> {
-        Ok(IsActiveResponse {
-            is_active: self.active_status.load(deps.storage)?,
-        })
-    }
+        ExecCtx { deps, env, info }: ExecCtx,
+        label: String,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
 
-    #[sv::msg(query)]
-    pub(crate) fn get_total_shares(
-        &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-    ) -> Result<GetTotalSharesResponse, ContractError> {
-        let total_shares = self.alloyed_asset.get_total_supply(deps)?;
-        Ok(GetTotalSharesResponse { total_shares })
-    }
+        // only admin can remove asset group
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
 
-    #[sv::msg(query)]
-    pub(crate) fn get_total_pool_liquidity(
-        &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-    ) -> Result<GetTotalPoolLiquidityResponse, ContractError> {
-        let pool = self.pool.load(deps.storage)?;
+        self.governance_log.record(
+            deps.storage,
+            "remove_asset_group",
+            Some(&info.sender),
+            env.block.time,
+        )?;
 
-        Ok(GetTotalPoolLiquidityResponse {
-            total_pool_liquidity: pool.pool_assets.iter().map(Asset::to_coin).collect(),
-        })
+        self.asset_groups.remove(deps.storage, &label)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "remove_asset_group")
+            .add_attribute("label", label))
     }
 
-    #[sv::msg(query)]
-    pub(crate) fn spot_price(
+    /// Register a human-readable alias, e.g. `usdc`, for a pool asset denom,
+    /// so that swap messages can reference the alias instead of the denom.
+    #[sv::msg(exec)]
+    fn register_denom_alias(
         &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-        base_asset_denom: String,
-        quote_asset_denom: String,
-    ) -> Result<SpotPriceResponse, ContractError> {
-        // ensure that it's not the same denom
+        ExecCtx { deps, env, info }: ExecCtx,
+        alias: String,
+        denom: String,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
         ensure!(
-            quote_asset_denom != base_asset_denom,
-            ContractError::SpotPriceQueryFailed {
-                reason: "quote_asset_denom and base_asset_denom cannot be the same".to_string()
+            !alias.is_empty(),
+            ContractError::NonEmptyInputRequired {
+                field: "alias".to_string()
             }
         );
 
-        // ensure that qoute asset denom are in swappable assets
+        // only admin can register denom alias
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "register_denom_alias",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
         let pool = self.pool.load(deps.storage)?;
-        let alloyed_denom = self.alloyed_asset.get_alloyed_denom(deps.storage)?;
-        let alloyed_normalization_factor =
-            self.alloyed_asset.get_normalization_factor(deps.storage)?;
-        let swappable_asset_norm_factors = pool
-            .pool_assets
-            .iter()
-            .map(|c| (c.denom().to_string(), c.normalization_factor()))
-            .chain(vec![(alloyed_denom, alloyed_normalization_factor)])
-            .collect::<BTreeMap<String, Uint128>>();
+        ensure!(
+            pool.has_denom(&denom),
+            ContractError::InvalidPoolAssetDenom { denom }
+        );
 
-        let base_asset_norm_factor = swappable_asset_norm_factors
-            .get(&base_asset_denom)
-            .cloned()
-            .ok_or_else(|| ContractError::SpotPriceQueryFailed {
-                reason: format!(
-                    "base_asset_denom is not in swappable assets: must be one of {:?} but got {}",
-                    swappable_asset_norm_factors.keys(),
-                    base_asset_denom
-                ),
-            })?;
+        // alias must not shadow any denom or other alias
+        let alloyed_denom = self.alloyed_asset.may_get_alloyed_denom(deps.storage)?;
+        let is_ambiguous = pool.has_denom(&alias)
+            || Some(&alias) == alloyed_denom.as_ref()
+            || self.denom_aliases.has(deps.storage, &alias);
+        ensure!(!is_ambiguous, ContractError::AmbiguousDenomAlias { alias });
 
-        let quote_asset_norm_factor = swappable_asset_norm_factors
-            .get(&quote_asset_denom)
-            .cloned()
-            .ok_or_else(|| ContractError::SpotPriceQueryFailed {
-                reason: format!(
-                    "quote_asset_denom is not in swappable assets: must be one of {:?} but got {}",
-                    swappable_asset_norm_factors.keys(),
-                    quote_asset_denom
-                ),
-            })?;
+        self.denom_aliases.save(deps.storage, &alias, &denom)?;
 
-        Ok(SpotPriceResponse {
-            spot_price: math::price(base_asset_norm_factor, quote_asset_norm_factor)?,
-        })
+        Ok(Response::new()
+            .add_attribute("method", "register_denom_alias")
+            .add_attribute("alias", alias)
+            .add_attribute("denom", denom))
     }
 
-    #[sv::msg(query)]
-    pub(crate) fn calc_out_amt_given_in(
+    #[sv::msg(exec)]
+    fn deregister_denom_alias(
         &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-        token_in: Coin,
-        token_out_denom: String,
-        swap_fee: Decimal,
-    ) -> Result<CalcOutAmtGivenInResponse, ContractError> {
-        self.ensure_valid_swap_fee(swap_fee)?;
-        let (_pool, token_out) = self.out_amt_given_in(deps, token_in, &token_out_denom)?;
+        ExecCtx { deps, env, info }: ExecCtx,
+        alias: String,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
 
-        Ok(CalcOutAmtGivenInResponse { token_out })
-    }
+        // only admin can deregister denom alias
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
 
-    #[sv::msg(query)]
-    pub(crate) fn calc_in_amt_given_out(
-        &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-        token_out: Coin,
-        token_in_denom: String,
-        swap_fee: Decimal,
-    ) -> Result<CalcInAmtGivenOutResponse, ContractError> {
-        self.ensure_valid_swap_fee(swap_fee)?;
-        let (_pool, token_in) = self.in_amt_given_out(deps, token_out, token_in_denom)?;
+        self.governance_log.record(
+            deps.storage,
+            "deregister_denom_alias",
+            Some(&info.sender),
+            env.block.time,
+        )?;
 
-        Ok(CalcInAmtGivenOutResponse { token_in })
-    }
+        ensure!(
+            self.denom_aliases.has(deps.storage, &alias),
+            ContractError::DenomAliasNotFound { alias }
+        );
 
-    #[sv::msg(query)]
-    pub(crate) fn get_corrupted_denoms(
-        &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-    ) -> Result<GetCorrruptedDenomsResponse, ContractError> {
-        let pool = self.pool.load(deps.storage)?;
-        let corrupted_denoms = pool
-            .corrupted_assets()
-            .into_iter()
-            .map(|a| a.denom().to_string())
-            .collect();
+        self.denom_aliases.remove(deps.storage, &alias);
 
-        Ok(GetCorrruptedDenomsResponse { corrupted_denoms })
+        Ok(Response::new()
+            .add_attribute("method", "deregister_denom_alias")
+            .add_attribute("alias", alias))
     }
 
-    // --- admin ---
-
+    /// Mark asset group as corrupted, which also marks all of its denoms as corrupted assets.
     #[sv::msg(exec)]
-    pub fn transfer_admin(
+    fn mark_asset_group_as_corrupted(
         &self,
-        ExecCtx { deps, env: _, info }: ExecCtx,
-        candidate: String,
+        ExecCtx { deps, env, info }: ExecCtx,
+        label: String,
     ) -> Result<Response, ContractError> {
-        let candidate_addr = deps.api.addr_validate(&candidate)?;
-        self.role
-            .admin
-            .transfer(deps, info.sender, candidate_addr)?;
+        nonpayable(&info.funds)?;
+
+        // only moderator can mark asset group as corrupted
+        ensure_moderator_authority!(info.sender, self.role.moderator, deps.as_ref());
+
+        // marking denoms can still fail, so it is done before anything is saved
+        let group = self.asset_groups.get(deps.storage, &label)?;
+        let mut pool = self.pool.load(deps.storage)?;
+        self.mark_pool_assets_corrupted(deps.storage, &mut pool, group.denoms())?;
+
+        self.governance_log.record(
+            deps.storage,
+            "mark_asset_group_as_corrupted",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.asset_groups.update(deps.storage, &label, |group| {
+            group.mark_as_corrupted();
+        })?;
+        self.pool.save(deps.storage, &pool)?;
 
         Ok(Response::new()
-            .add_attribute("method", "transfer_admin")
-            .add_attribute("candidate", candidate))
+            .add_attribute("method", "mark_asset_group_as_corrupted")
+            .add_attribute("label", label))
     }
 
+    /// Mark and unmark multiple asset groups as corrupted at once, e.g. during an incident
+    /// involving several assets. The whole batch is rejected if any label is unknown.
+    /// Groups are unmarked before others are marked, so denoms shared with a marked group
+    /// remain corrupted.
     #[sv::msg(exec)]
-    pub fn cancel_admin_transfer(
+    fn set_corruption_batch(
         &self,
-        ExecCtx { deps, env: _, info }: ExecCtx,
+        ExecCtx { deps, env, info }: ExecCtx,
+        mark: Vec<String>,
+        unmark: Vec<String>,
     ) -> Result<Response, ContractError> {
-        self.role.admin.cancel_transfer(deps, info.sender)?;
+        nonpayable(&info.funds)?;
 
-        Ok(Response::new().add_attribute("method", "cancel_admin_transfer"))
+        // only moderator can mark or unmark asset groups as corrupted
+        ensure_moderator_authority!(info.sender, self.role.moderator, deps.as_ref());
+
+        if let Some(label) = mark.iter().find(|label| unmark.contains(label)) {
+            return Err(ContractError::ConflictingAssetGroupCorruption {
+                label: label.clone(),
+            });
+        }
+
+        // the whole batch is validated before anything is saved, so that it applies all or nothing
+        let mut unmarked_denoms = vec![];
+        for label in unmark.iter() {
+            let group = self.asset_groups.get(deps.storage, label)?;
+            unmarked_denoms.extend(group.denoms().iter().cloned());
+        }
+
+        let mut marked_denoms = vec![];
+        for label in mark.iter() {
+            let group = self.asset_groups.get(deps.storage, label)?;
+            marked_denoms.extend(group.denoms().iter().cloned());
+        }
+
+        let mut uncorrupted_denoms = vec![];
+        for denom in unmarked_denoms {
+            if !uncorrupted_denoms.contains(&denom)
+                && !self.directly_corrupted_denoms.has(deps.storage, &denom)
+                && !self.is_corrupted_by_asset_group(deps.storage, &denom, &unmark)?
+            {
+                uncorrupted_denoms.push(denom);
+            }
+        }
+
+        let mut pool = self.pool.load(deps.storage)?;
+        pool.unmark_corrupted_assets(&uncorrupted_denoms)?;
+        self.mark_pool_assets_corrupted(deps.storage, &mut pool, &marked_denoms)?;
+
+        self.governance_log.record(
+            deps.storage,
+            "set_corruption_batch",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        for label in unmark.iter() {
+            self.asset_groups.update(deps.storage, label, |group| {
+                group.unmark_as_corrupted();
+            })?;
+        }
+        for label in mark.iter() {
+            self.asset_groups.update(deps.storage, label, |group| {
+                group.mark_as_corrupted();
+            })?;
+        }
+        self.pool.save(deps.storage, &pool)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_corruption_batch")
+            .add_attribute("marked", mark.join(","))
+            .add_attribute("unmarked", unmark.join(",")))
     }
 
+    /// Pause or unpause swaps that increase exposure to any denom in the asset group.
+    /// Unlike marking it as corrupted, this leaves the denoms untouched and is fully reversible.
     #[sv::msg(exec)]
-    pub fn reject_admin_transfer(
+    fn set_asset_group_paused(
         &self,
-        ExecCtx { deps, env: _, info }: ExecCtx,
+        ExecCtx { deps, env, info }: ExecCtx,
+        label: String,
+        paused: bool,
     ) -> Result<Response, ContractError> {
-        self.role.admin.reject_transfer(deps, info.sender)?;
+        nonpayable(&info.funds)?;
 
-        Ok(Response::new().add_attribute("method", "reject_admin_transfer"))
+        // only moderator can pause asset group
+        ensure_moderator_authority!(info.sender, self.role.moderator, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_asset_group_paused",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.asset_groups.update(deps.storage, &label, |group| {
+            group.set_paused(paused);
+        })?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_asset_group_paused")
+            .add_attribute("label", label)
+            .add_attribute("paused", paused.to_string()))
     }
 
+    /// Unmark asset group as corrupted, which also unmarks its denoms as corrupted assets,
+    /// except for denoms that are directly marked as corrupted or belong to another corrupted group.
     #[sv::msg(exec)]
-    pub fn claim_admin(
+    fn unmark_asset_group_as_corrupted(
         &self,
-        ExecCtx { deps, env: _, info }: ExecCtx,
+        ExecCtx { deps, env, info }: ExecCtx,
+        label: String,
     ) -> Result<Response, ContractError> {
-        let sender_string = info.sender.to_string();
-        self.role.admin.claim(deps, info.sender)?;
+        nonpayable(&info.funds)?;
+
+        // only moderator can unmark asset group as corrupted
+        ensure_moderator_authority!(info.sender, self.role.moderator, deps.as_ref());
+
+        // unmarking denoms can still fail, so it is done before anything is saved
+        let group = self.asset_groups.get(deps.storage, &label)?;
+
+        let mut uncorrupted_denoms = vec![];
+        for denom in group.denoms() {
+            if !self.directly_corrupted_denoms.has(deps.storage, denom)
+                && !self.is_corrupted_by_asset_group(
+                    deps.storage,
+                    denom,
+                    std::slice::from_ref(&label),
+                )?
+            {
+                uncorrupted_denoms.push(denom.clone());
+            }
+        }
+
+        let mut pool = self.pool.load(deps.storage)?;
+        pool.unmark_corrupted_assets(&uncorrupted_denoms)?;
+
+        self.governance_log.record(
+            deps.storage,
+            "unmark_asset_group_as_corrupted",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.asset_groups.update(deps.storage, &label, |group| {
+            group.unmark_as_corrupted();
+        })?;
+        self.pool.save(deps.storage, &pool)?;
 
         Ok(Response::new()
-            .add_attribute("method", "claim_admin")
-            .add_attribute("new_admin", sender_string))
+            .add_attribute("method", "unmark_asset_group_as_corrupted")
+            .add_attribute("label", label))
     }
 
-    #[sv::msg(query)]
-    fn get_admin(
+    /// Move all denoms of `source` asset group into `target` asset group and remove `source`.
+    /// Corruption is sticky, if either group is corrupted, the merged group and
+    /// all of its denoms are marked as corrupted.
+    #[sv::msg(exec)]
+    fn merge_asset_groups(
         &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-    ) -> Result<GetAdminResponse, ContractError> {
-        Ok(GetAdminResponse {
-            admin: self.role.admin.current(deps)?,
-        })
-    }
+        ExecCtx { deps, env, info }: ExecCtx,
+        source: String,
+        target: String,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
 
-    #[sv::msg(query)]
-    fn get_admin_candidate(
-        &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-    ) -> Result<GetAdminCandidateResponse, ContractError> {
-        Ok(GetAdminCandidateResponse {
-            admin_candidate: self.role.admin.candidate(deps)?,
-        })
+        // only admin can merge asset groups
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        // marking denoms can still fail, so it is done before anything is saved
+        let merged = self.asset_groups.merged(deps.storage, &source, &target)?;
+        let mut pool = self.pool.load(deps.storage)?;
+        if merged.is_corrupted() {
+            self.mark_pool_assets_corrupted(deps.storage, &mut pool, merged.denoms())?;
+        }
+
+        self.governance_log.record(
+            deps.storage,
+            "merge_asset_groups",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.asset_groups.merge(deps.storage, &source, &target)?;
+        self.pool.save(deps.storage, &pool)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "merge_asset_groups")
+            .add_attribute("source", source)
+            .add_attribute("target", target))
     }
 
-    // -- moderator --
+    /// Set whether every denom must have a registered limiter for swaps to increase it,
+    /// so that no asset is left with unbounded exposure. Defaults to false.
     #[sv::msg(exec)]
-    pub fn assign_moderator(
+    fn set_require_limiter_coverage(
         &self,
-        ExecCtx { deps, env: _, info }: ExecCtx,
-        address: String,
+        ExecCtx { deps, env, info }: ExecCtx,
+        require_limiter_coverage: bool,
     ) -> Result<Response, ContractError> {
-        let moderator_address = deps.api.addr_validate(&address)?;
+        nonpayable(&info.funds)?;
 
-        self.role
-            .assign_moderator(info.sender, deps, moderator_address)?;
+        // only admin can set limiter coverage requirement
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_require_limiter_coverage",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.require_limiter_coverage
+            .save(deps.storage, &require_limiter_coverage)?;
 
         Ok(Response::new()
-            .add_attribute("method", "assign_moderator")
-            .add_attribute("moderator", address))
+            .add_attribute("method", "set_require_limiter_coverage")
+            .add_attribute(
+                "require_limiter_coverage",
+                require_limiter_coverage.to_string(),
+            ))
     }
 
-    #[sv::msg(query)]
-    fn get_moderator(
+    /// Set whether token to token swaps may take the entire pool balance of a denom,
+    /// leaving it at exactly zero. Defaults to true. Corrupted denoms can always be drained.
+    #[sv::msg(exec)]
+    fn set_allow_full_drain(
         &self,
-        QueryCtx { deps, env: _ }: QueryCtx,
-    ) -> Result<GetModeratorResponse, ContractError> {
-        Ok(GetModeratorResponse {
-            moderator: self.role.moderator.get(deps)?,
-        })
-    }
-}
+        ExecCtx { deps, env, info }: ExecCtx,
+        allow_full_drain: bool,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
 
-#[cw_serde]
-pub struct ListAssetConfigsResponse {
-    pub asset_configs: Vec<AssetConfig>,
-}
+        // only admin can set whether full drain is allowed
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
 
-#[cw_serde]
-pub struct ListLimitersResponse {
-    pub limiters: Vec<((String, String), Limiter)>,
-}
+        self.governance_log.record(
+            deps.storage,
+            "set_allow_full_drain",
+            Some(&info.sender),
+            env.block.time,
+        )?;
 
-#[cw_serde]
-pub struct GetSharesResponse {
-    pub shares: Uint128,
-}
+        self.allow_full_drain
+            .save(deps.storage, &allow_full_drain)?;
 
-#[cw_serde]
-pub struct GetShareDenomResponse {
-    pub share_denom: String,
-}
+        Ok(Response::new()
+            .add_attribute("method", "set_allow_full_drain")
+            .add_attribute("allow_full_drain", allow_full_drain.to_string()))
+    }
 
-#[cw_serde]
-pub struct GetSwapFeeResponse {
-    pub swap_fee: Decimal,
-}
+    /// Set whether rounding residuals of token to token swaps are recorded in the rounding audit log.
+    /// Defaults to false, since recording every swap grows state.
+    #[sv::msg(exec)]
+    fn set_enable_rounding_audit(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        enable_rounding_audit: bool,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
 
-#[cw_serde]
-pub struct IsActiveResponse {
-    pub is_active: bool,
-}
+        // only admin can enable rounding audit
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
 
-#[cw_serde]
-pub struct GetTotalSharesResponse {
-    pub total_shares: Uint128,
-}
+        self.governance_log.record(
+            deps.storage,
+            "set_enable_rounding_audit",
+            Some(&info.sender),
+            env.block.time,
+        )?;
 
-#[cw_serde]
-pub struct GetTotalPoolLiquidityResponse {
-    pub total_pool_liquidity: Vec<Coin>,
-}
+        self.enable_rounding_audit
+            .save(deps.storage, &enable_rounding_audit)?;
 
-#[cw_serde]
-pub struct SpotPriceResponse {
-    pub spot_price: Decimal,
-}
+        Ok(Response::new()
+            .add_attribute("method", "set_enable_rounding_audit")
+            .add_attribute("enable_rounding_audit", enable_rounding_audit.to_string()))
+    }
 
-#[cw_serde]
-pub struct CalcOutAmtGivenInResponse {
-    pub token_out: Coin,
-}
+    /// Withdraw swap fees retained in the pool, as accrued per denom, to `recipient`
+    /// and reset the accruals. Fees accrued in alloyed asset, i.e. retained on alloyed asset burns,
+    /// are minted to `recipient` since their backing is already in the pool.
+    ///
+    /// Fails if the withdrawal would leave the pool short of backing outstanding alloyed asset,
+    /// e.g. after losses from corrupted assets.
+    #[sv::msg(exec)]
+    fn withdraw_fees(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        recipient: String,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
 
-#[cw_serde]
-pub struct CalcInAmtGivenOutResponse {
-    pub token_in: Coin,
-}
+        // only admin or fee collector can withdraw fees
+        let fee_collector = self.fee.get_fee_collector(deps.storage)?;
+        if fee_collector.as_ref() != Some(&info.sender) {
+            ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+        }
 
-#[cw_serde]
-pub struct GetCorrruptedDenomsResponse {
-    pub corrupted_denoms: Vec<String>,
-}
+        let recipient = deps.api.addr_validate(&recipient)?;
 
-#[cw_serde]
-pub struct GetAdminResponse {
-    pub admin: Addr,
-}
+        self.governance_log.record(
+            deps.storage,
+            "withdraw_fees",
+            Some(&info.sender),
+            env.block.time,
+        )?;
 
-#[cw_serde]
-pub struct GetAdminCandidateResponse {
-    pub admin_candidate: Option<Addr>,
-}
+        let accrued_fees = self
+            .accrued_fees
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        let alloyed_denom = self.alloyed_asset.may_get_alloyed_denom(deps.storage)?;
+        let mut pool = self.pool.load(deps.storage)?;
 
-#[cw_serde]
-pub struct GetModeratorResponse {
-    pub moderator: Addr,
-}
+        let mut tokens_out = vec![];
+        let mut alloyed_out = Uint128::zero();
+        for (denom, amount) in accrued_fees.iter().cloned() {
+            if alloyed_denom.as_deref() == Some(denom.as_str()) {
+                alloyed_out = amount;
+                continue;
+            }
 
-#[cfg(test)]
-mod tests {
+            // fee accrued in denoms no longer in the pool, e.g. drained corrupted assets, is dropped,
+            // and pool balance can fall short of the accrual after being swept as dust
+            let Ok(asset) = pool.get_pool_asset_by_denom(&denom) else {
+                continue;
+            };
+            let amount = amount.min(asset.amount());
+            if !amount.is_zero() {
+                tokens_out.push(Coin::new(amount.u128(), denom));
+            }
+        }
 
-    use super::sv::*;
-    use super::*;
-    use crate::limiter::{ChangeLimiter, StaticLimiter, WindowConfig};
-    use crate::sudo::SudoMsg;
-    use crate::*;
+        pool.exit_pool(&tokens_out)?;
 
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{
-        attr, from_json, BankMsg, BlockInfo, Storage, SubMsgResponse, SubMsgResult, Uint64,
-    };
-    use osmosis_std::types::osmosis::tokenfactory::v1beta1::MsgBurn;
+        // remaining pool must still back all outstanding alloyed asset, including the minted fee
+        if alloyed_denom.is_some() {
+            let required = self
+                .alloyed_asset
+                .get_total_supply(deps.as_ref())?
+                .checked_add(alloyed_out)?;
+            let available = self.pool_value_in_alloyed_asset(deps.storage, &pool)?;
+            ensure!(
+                available >= required,
+                ContractError::InsufficientFeeReserve {
+                    required,
+                    available
+                }
+            );
+        }
 
-    #[test]
-    fn test_invalid_subdenom() {
-        let mut deps = mock_dependencies();
+        // minting alloyed asset fee is capped like any other mint
+        if !alloyed_out.is_zero() {
+            self.ensure_within_max_total_liquidity(deps.storage, &pool, None)?;
+            self.record_mint(deps.storage, alloyed_out, env.block.time)?;
+        }
 
-        // make denom has non-zero total supply
-        deps.querier
-            .update_balance("someone", vec![Coin::new(1, "tbtc"), Coin::new(1, "nbtc")]);
+        // accruals are reset only once the withdrawal is known to succeed
+        for (denom, _) in accrued_fees {
+            self.accrued_fees.remove(deps.storage, &denom);
+        }
+        self.pool.save(deps.storage, &pool)?;
 
-        let admin = "admin";
-        let moderator = "moderator";
-        let init_msg = InstantiateMsg {
-            pool_asset_configs: vec![
-                AssetConfig::from_denom_str("tbtc"),
-                AssetConfig::from_denom_str("nbtc"),
-            ],
-            alloyed_asset_subdenom: "all/btc".to_string(),
-            alloyed_asset_normalization_factor: Uint128::one(),
-            admin: Some(admin.to_string()),
-            moderator: moderator.to_string(),
-        };
-        let env = mock_env();
-        let info = mock_info(admin, &[]);
+        let mut response = Response::new()
+            .add_attribute("method", "withdraw_fees")
+            .add_attribute("recipient", recipient.as_str());
 
-        // Instantiate the contract.
-        let err = instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap_err();
+        if !tokens_out.is_empty() {
+            response = response.add_message(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: tokens_out,
+            });
+        }
 
-        assert_eq!(
-            err,
-            ContractError::SubDenomExtraPartsNotAllowed {
-                subdenom: "all/btc".to_string()
-            }
-        )
+        if let Some(alloyed_denom) = alloyed_denom.filter(|_| !alloyed_out.is_zero()) {
+            let alloyed_fee = Coin::new(alloyed_out.u128(), alloyed_denom);
+            self.alloyed_minted_volumes.record(
+                deps.storage,
+                std::slice::from_ref(&alloyed_fee),
+                env.block.time,
+            )?;
+            response = response
+                .add_event(alloyed_asset_transfer_event(
+                    "mint",
+                    "",
+                    recipient.as_str(),
+                    &alloyed_fee,
+                ))
+                .add_message(MsgMint {
+                    sender: env.contract.address.to_string(),
+                    amount: Some(alloyed_fee.into()),
+                    mint_to_address: recipient.to_string(),
+                });
+        }
+
+        Ok(response)
     }
 
-    #[test]
-    fn test_add_new_assets() {
-        let mut deps = mock_dependencies();
+    /// Nudge pool weights toward their targets using accrued fees, callable by anyone, e.g. keepers.
+    /// Since swaps are at a fixed rate, there is no trade to make: the excess of each denom
+    /// over its target is withdrawn to the fee collector instead, paid for by the accrued fees.
+    /// The withdrawn value is bounded by the value of accrued fees, which are consumed
+    /// proportionally across denoms.
+    #[sv::msg(exec)]
+    fn rebalance(&self, ExecCtx { deps, env, info }: ExecCtx) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
 
-        // make denom has non-zero total supply
-        deps.querier.update_balance(
-            "someone",
-            vec![
-                Coin::new(1, "uosmo"),
-                Coin::new(1, "uion"),
-                Coin::new(1, "new_asset1"),
-                Coin::new(1, "new_asset2"),
-            ],
-        );
+        let fee_collector = self
+            .fee
+            .get_fee_collector(deps.storage)?
+            .ok_or(ContractError::FeeCollectorNotSet {})?;
 
-        let admin = "admin";
-        let moderator = "moderator";
-        let init_msg = InstantiateMsg {
-            pool_asset_configs: vec![
-                AssetConfig::from_denom_str("uosmo"),
-                AssetConfig::from_denom_str("uion"),
-            ],
-            alloyed_asset_subdenom: "uosmouion".to_string(),
-            alloyed_asset_normalization_factor: Uint128::one(),
-            admin: Some(admin.to_string()),
-            moderator: moderator.to_string(),
+        let mut pool = self.pool.load(deps.storage)?;
+        let Some(weights) = pool.weights()? else {
+            return Ok(Response::new().add_attribute("method", "rebalance"));
         };
-        let env = mock_env();
-        let info = mock_info(admin, &[]);
 
-        // Instantiate the contract.
-        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+        let alloyed_denom = self.alloyed_asset.may_get_alloyed_denom(deps.storage)?;
+        let alloyed_normalization_factor =
+            self.alloyed_asset.get_normalization_factor(deps.storage)?;
+        let accrued_fees = self
+            .accrued_fees
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        // fee budget in alloyed asset amount, fee accrued in denoms no longer in the pool is skipped
+        let mut budget = Uint128::zero();
+        for (denom, amount) in accrued_fees.iter() {
+            let value = if Some(denom) == alloyed_denom.as_ref() {
+                *amount
+            } else if let Ok(asset) = pool.get_pool_asset_by_denom(denom) {
+                convert_amount(
+                    *amount,
+                    asset.normalization_factor(),
+                    alloyed_normalization_factor,
+                    &Rounding::Down,
+                )?
+            } else {
+                continue;
+            };
+            budget = budget.checked_add(value)?;
+        }
 
-        // Manually reply
-        let alloyed_denom = "usomoion";
+        // value of each denom in excess of its target, relative to the current pool value
+        let pool_value = self.pool_value_in_alloyed_asset(deps.storage, &pool)?;
+        let target_weights = self.effective_target_weights(deps.storage, &pool)?;
+        let mut excesses = vec![];
+        for (denom, weight) in weights {
+            let target = target_weights.get(&denom).copied().unwrap_or_default();
+            let excess = pool_value.checked_mul_floor(weight.saturating_sub(target))?;
+            if !excess.is_zero() {
+                excesses.push((denom, excess));
+            }
+        }
 
-        reply(
-            deps.as_mut(),
-            env.clone(),
-            Reply {
-                id: 1,
-                result: SubMsgResult::Ok(SubMsgResponse {
-                    events: vec![],
-                    data: Some(
-                        MsgCreateDenomResponse {
-                            new_token_denom: alloyed_denom.to_string(),
-                        }
-                        .into(),
-                    ),
-                }),
-            },
-        )
-        .unwrap();
+        let total_excess = excesses
+            .iter()
+            .try_fold(Uint128::zero(), |acc, (_, excess)| acc.checked_add(*excess))?;
+        let scale = match total_excess.is_zero() {
+            true => Decimal::zero(),
+            false => Decimal::from_ratio(budget.min(total_excess), total_excess),
+        };
 
-        // join pool
-        let info = mock_info(
-            "someone",
-            &[
-                Coin::new(1000000000, "uosmo"),
-                Coin::new(1000000000, "uion"),
-            ],
-        );
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
-        execute(deps.as_mut(), env.clone(), info.clone(), join_pool_msg).unwrap();
+        let mut tokens_out = vec![];
+        let mut consumed = Uint128::zero();
+        for (denom, excess) in excesses {
+            let value = excess.checked_mul_floor(scale)?;
+            let amount = convert_amount(
+                value,
+                alloyed_normalization_factor,
+                pool.get_pool_asset_by_denom(&denom)?.normalization_factor(),
+                &Rounding::Down,
+            )?;
+            if !amount.is_zero() {
+                consumed = consumed.checked_add(value)?;
+                tokens_out.push(Coin::new(amount.u128(), denom));
+            }
+        }
 
-        // set limiters
-        let change_limiter_params = LimiterParams::ChangeLimiter {
-            window_config: WindowConfig {
-                window_size: Uint64::from(3600u64),
-                division_count: Uint64::from(10u64),
-            },
-            boundary_offset: Decimal::percent(20),
-        };
+        if tokens_out.is_empty() {
+            return Ok(Response::new().add_attribute("method", "rebalance"));
+        }
 
-        let static_limiter_params = LimiterParams::StaticLimiter {
-            upper_limit: Decimal::percent(60),
-        };
+        self.governance_log.record(
+            deps.storage,
+            "rebalance",
+            Some(&info.sender),
+            env.block.time,
+        )?;
 
-        let info = mock_info(admin, &[]);
-        for denom in ["uosmo", "uion"] {
-            let register_limiter_msg = ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
-                denom: denom.to_string(),
-                label: "change_limiter".to_string(),
-                limiter_params: change_limiter_params.clone(),
-            });
+        pool.exit_pool(&tokens_out)?;
 
-            execute(
-                deps.as_mut(),
-                env.clone(),
-                info.clone(),
-                register_limiter_msg,
-            )
-            .unwrap();
+        // remaining pool must still back all outstanding alloyed asset
+        let required = self.alloyed_asset.get_total_supply(deps.as_ref())?;
+        let available = self.pool_value_in_alloyed_asset(deps.storage, &pool)?;
+        ensure!(
+            available >= required,
+            ContractError::InsufficientFeeReserve {
+                required,
+                available
+            }
+        );
 
-            let register_limiter_msg = ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
-                denom: denom.to_string(),
-                label: "static_limiter".to_string(),
-                limiter_params: static_limiter_params.clone(),
-            });
+        self.pool.save(deps.storage, &pool)?;
 
-            execute(
-                deps.as_mut(),
-                env.clone(),
-                info.clone(),
-                register_limiter_msg,
-            )
-            .unwrap();
+        // consume accrued fees proportionally, rounding the consumption up
+        let consumed_ratio = Decimal::from_ratio(consumed, budget);
+        for (denom, amount) in accrued_fees {
+            let remaining = amount.saturating_sub(amount.checked_mul_ceil(consumed_ratio)?);
+            if remaining.is_zero() {
+                self.accrued_fees.remove(deps.storage, &denom);
+            } else {
+                self.accrued_fees.save(deps.storage, &denom, &remaining)?;
+            }
         }
 
-        // join pool a bit more to make limiters dirty
-        let mut env = env.clone();
-        env.block.time = env.block.time.plus_nanos(360);
-
-        let info = mock_info(
-            "someone",
-            &[Coin::new(550, "uosmo"), Coin::new(500, "uion")],
-        );
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
-        execute(deps.as_mut(), env.clone(), info.clone(), join_pool_msg).unwrap();
+        Ok(Response::new()
+            .add_attribute("method", "rebalance")
+            .add_attribute("consumed_fee_value", consumed)
+            .add_message(BankMsg::Send {
+                to_address: fee_collector.to_string(),
+                amount: tokens_out,
+            }))
+    }
 
-        env.block.time = env.block.time.plus_nanos(3000);
-        let info = mock_info(
-            "someone",
-            &[Coin::new(450, "uosmo"), Coin::new(500, "uion")],
-        );
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
-        execute(deps.as_mut(), env.clone(), info.clone(), join_pool_msg).unwrap();
+    #[sv::msg(exec)]
+    fn register_limiter(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        denom: String,
+        label: String,
+        limiter_params: LimiterParams,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can register limiter
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "register_limiter",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        // ensure pool has the specified denom
+        self.ensure_not_pool_share_denom(deps.storage, &denom)?;
+        let pool = self.pool.load(deps.storage)?;
+        ensure!(
+            pool.has_denom(&denom),
+            ContractError::InvalidPoolAssetDenom { denom }
+        );
+
+        let base_attrs = vec![
+            ("method", "register_limiter"),
+            ("denom", &denom),
+            ("label", &label),
+        ];
+        let limiter_attrs = match &limiter_params {
+            LimiterParams::ChangeLimiter {
+                window_config,
+                boundary_offset,
+            } => {
+                let window_size = window_config.window_size.to_string();
+                let division_count = window_config.division_count.to_string();
+                let boundary_offset_string = boundary_offset.to_string();
+
+                vec![
+                    (String::from("limiter_type"), String::from("change_limiter")),
+                    (String::from("window_size"), window_size),
+                    (String::from("division_count"), division_count),
+                    (String::from("boundary_offset"), boundary_offset_string),
+                ]
+            }
+            LimiterParams::StaticLimiter { upper_limit } => vec![
+                (String::from("limiter_type"), String::from("static_limiter")),
+                (String::from("upper_limit"), upper_limit.to_string()),
+            ],
+            LimiterParams::RelativeLimiter { max_deviation } => vec![
+                (
+                    String::from("limiter_type"),
+                    String::from("relative_limiter"),
+                ),
+                (String::from("max_deviation"), max_deviation.to_string()),
+            ],
+            LimiterParams::ChangeLimiterBps {
+                window_config,
+                boundary_offset_bps,
+            } => vec![
+                (String::from("limiter_type"), String::from("change_limiter")),
+                (
+                    String::from("window_size"),
+                    window_config.window_size.to_string(),
+                ),
+                (
+                    String::from("division_count"),
+                    window_config.division_count.to_string(),
+                ),
+                (
+                    String::from("boundary_offset_bps"),
+                    boundary_offset_bps.to_string(),
+                ),
+            ],
+            LimiterParams::StaticLimiterBps { upper_limit_bps } => vec![
+                (String::from("limiter_type"), String::from("static_limiter")),
+                (String::from("upper_limit_bps"), upper_limit_bps.to_string()),
+            ],
+            LimiterParams::RelativeLimiterBps { max_deviation_bps } => vec![
+                (
+                    String::from("limiter_type"),
+                    String::from("relative_limiter"),
+                ),
+                (
+                    String::from("max_deviation_bps"),
+                    max_deviation_bps.to_string(),
+                ),
+            ],
+            LimiterParams::NetFlowLimiter {
+                window_config,
+                max_net_flow,
+            } => vec![
+                (
+                    String::from("limiter_type"),
+                    String::from("net_flow_limiter"),
+                ),
+                (
+                    String::from("window_size"),
+                    window_config.window_size.to_string(),
+                ),
+                (
+                    String::from("division_count"),
+                    window_config.division_count.to_string(),
+                ),
+                (String::from("max_net_flow"), max_net_flow.to_string()),
+            ],
+        };
+
+        // register limiter
+        self.limiters
+            .register(deps.storage, &denom, &label, limiter_params)?;
+
+        Ok(Response::new()
+            .add_attributes(base_attrs)
+            .add_attributes(limiter_attrs))
+    }
+
+    #[sv::msg(exec)]
+    fn deregister_limiter(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        denom: String,
+        label: String,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can deregister limiter
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "deregister_limiter",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        let attrs = vec![
+            ("method", "deregister_limiter"),
+            ("denom", &denom),
+            ("label", &label),
+        ];
+
+        // deregister limiter
+        self.limiters.deregister(deps.storage, &denom, &label)?;
+
+        Ok(Response::new().add_attributes(attrs))
+    }
+
+    #[sv::msg(exec)]
+    fn set_change_limiter_boundary_offset(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        denom: String,
+        label: String,
+        boundary_offset: Decimal,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set boundary offset
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_change_limiter_boundary_offset",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        let boundary_offset_string = boundary_offset.to_string();
+        let attrs = vec![
+            ("method", "set_change_limiter_boundary_offset"),
+            ("denom", &denom),
+            ("label", &label),
+            ("boundary_offset", boundary_offset_string.as_str()),
+        ];
+
+        // set boundary offset
+        self.limiters.set_change_limiter_boundary_offset(
+            deps.storage,
+            &denom,
+            &label,
+            boundary_offset,
+        )?;
+
+        Ok(Response::new().add_attributes(attrs))
+    }
+
+    /// Reset change limiter state so that its moving average starts over
+    /// from the current weight of the denom.
+    #[sv::msg(exec)]
+    fn reset_limiter_state(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        denom: String,
+        label: String,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can reset limiter state
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "reset_limiter_state",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        // weight is zero if pool is empty
+        let weight = self
+            .pool
+            .load(deps.storage)?
+            .weights()?
+            .unwrap_or_default()
+            .into_iter()
+            .find(|(weight_denom, _)| weight_denom == &denom)
+            .map(|(_, weight)| weight)
+            .unwrap_or_default();
+
+        self.limiters.reset_change_limiter_state(
+            deps.storage,
+            &denom,
+            &label,
+            env.block.time,
+            weight,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("method", "reset_limiter_state")
+            .add_attribute("denom", denom)
+            .add_attribute("label", label)
+            .add_attribute("weight", weight.to_string()))
+    }
+
+    #[sv::msg(exec)]
+    fn set_static_limiter_upper_limit(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        denom: String,
+        label: String,
+        upper_limit: Decimal,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set upper limit
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_static_limiter_upper_limit",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        let upper_limit_string = upper_limit.to_string();
+        let attrs = vec![
+            ("method", "set_static_limiter_upper_limit"),
+            ("denom", &denom),
+            ("label", &label),
+            ("upper_limit", upper_limit_string.as_str()),
+        ];
+
+        // set upper limit
+        self.limiters
+            .set_static_limiter_upper_limit(deps.storage, &denom, &label, upper_limit)?;
+
+        Ok(Response::new().add_attributes(attrs))
+    }
+
+    /// Temporarily widen a limiter's bound to `new_bound`, e.g. during a planned rebalance.
+    /// The limiter's own bound applies again from `revert_at` onwards.
+    #[sv::msg(exec)]
+    fn set_temporary_limiter_bound(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        denom: String,
+        label: String,
+        new_bound: Decimal,
+        revert_at: Timestamp,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set temporary limiter bound
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_temporary_limiter_bound",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.limiters.set_temporary_bound(
+            deps.storage,
+            &denom,
+            &label,
+            new_bound,
+            revert_at,
+            env.block.time,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_temporary_limiter_bound")
+            .add_attribute("denom", denom)
+            .add_attribute("label", label)
+            .add_attribute("new_bound", new_bound.to_string())
+            .add_attribute("revert_at", revert_at.to_string()))
+    }
+
+    /// Set the stress config, under which static limiter upper limits are automatically tightened
+    /// while pool imbalance exceeds its threshold, and relaxed once the imbalance recovers.
+    /// Static limiters' own upper limits are kept intact. `None` disables tightening.
+    #[sv::msg(exec)]
+    fn set_limiter_stress_config(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        stress_config: Option<StressConfig>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set limiter stress config
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_limiter_stress_config",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.limiters
+            .set_stress_config(deps.storage, stress_config.clone())?;
+
+        let attrs = match stress_config {
+            Some(StressConfig {
+                imbalance_threshold,
+                tightening_factor,
+            }) => vec![
+                ("imbalance_threshold", imbalance_threshold.to_string()),
+                ("tightening_factor", tightening_factor.to_string()),
+            ],
+            None => vec![("stress_config", "none".to_string())],
+        };
+
+        Ok(Response::new()
+            .add_attribute("method", "set_limiter_stress_config")
+            .add_attributes(attrs))
+    }
+
+    #[sv::msg(exec)]
+    fn set_relative_limiter_max_deviation(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        denom: String,
+        label: String,
+        max_deviation: Decimal,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set max deviation
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_relative_limiter_max_deviation",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        let max_deviation_string = max_deviation.to_string();
+        let attrs = vec![
+            ("method", "set_relative_limiter_max_deviation"),
+            ("denom", &denom),
+            ("label", &label),
+            ("max_deviation", max_deviation_string.as_str()),
+        ];
+
+        // set max deviation
+        self.limiters.set_relative_limiter_max_deviation(
+            deps.storage,
+            &denom,
+            &label,
+            max_deviation,
+        )?;
+
+        Ok(Response::new().add_attributes(attrs))
+    }
+
+    #[sv::msg(exec)]
+    fn set_swap_fee(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        swap_fee: Decimal,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set swap fee
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_swap_fee",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.fee.set_swap_fee(deps.storage, swap_fee)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_swap_fee")
+            .add_attribute("swap_fee", swap_fee.to_string()))
+    }
+
+    /// Set swap fee for a specific denom, `None` removes it.
+    /// Effective swap fee of a swap is the max of the swap fee and
+    /// the per-denom swap fees of the in and out denoms.
+    #[sv::msg(exec)]
+    fn set_denom_swap_fee(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        denom: String,
+        swap_fee: Option<Decimal>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set denom swap fee
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_denom_swap_fee",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        // denom must be either pool asset or alloyed asset
+        let pool = self.pool.load(deps.storage)?;
+        let alloyed_denom = self.alloyed_asset.may_get_alloyed_denom(deps.storage)?;
+        ensure!(
+            pool.has_denom(&denom) || Some(&denom) == alloyed_denom.as_ref(),
+            ContractError::InvalidPoolAssetDenom { denom }
+        );
+
+        self.fee
+            .set_denom_swap_fee(deps.storage, &denom, swap_fee)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_denom_swap_fee")
+            .add_attribute("denom", denom)
+            .add_attribute(
+                "swap_fee",
+                swap_fee
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "none".to_string()),
+            ))
+    }
+
+    #[sv::msg(exec)]
+    fn set_fee_collector(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        address: String,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set fee collector
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_fee_collector",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        let fee_collector = deps.api.addr_validate(&address)?;
+        self.fee.set_fee_collector(deps.storage, fee_collector)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_fee_collector")
+            .add_attribute("fee_collector", address))
+    }
+
+    /// Set the fraction of swap fee that goes to the fee collector,
+    /// the rest of the swap fee is retained in the pool.
+    #[sv::msg(exec)]
+    fn set_fee_split(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        fee_split: Decimal,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set fee split
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_fee_split",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.fee.set_fee_split(deps.storage, fee_split)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_fee_split")
+            .add_attribute("fee_split", fee_split.to_string()))
+    }
+
+    /// Set the side of the swap the fee is charged on, see [`FeeSide`].
+    #[sv::msg(exec)]
+    fn set_fee_side(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        fee_side: FeeSide,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set fee side
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_fee_side",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.fee.set_fee_side(deps.storage, fee_side)?;
+
+        let fee_side = match fee_side {
+            FeeSide::In => "in",
+            FeeSide::Out => "out",
+        };
+
+        Ok(Response::new()
+            .add_attribute("method", "set_fee_side")
+            .add_attribute("fee_side", fee_side))
+    }
+
+    /// Set params of the swap fee that scales with how far the swap pushes denoms
+    /// from their target weights, see [`DynamicFeeParams`]. `None` reverts to the flat swap fee.
+    #[sv::msg(exec)]
+    fn set_dynamic_fee_params(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        dynamic_fee_params: Option<DynamicFeeParams>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set dynamic fee params
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_dynamic_fee_params",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.fee
+            .set_dynamic_fee_params(deps.storage, dynamic_fee_params.clone())?;
+
+        let attrs = match dynamic_fee_params {
+            Some(DynamicFeeParams { base, slope }) => {
+                vec![("base", base.to_string()), ("slope", slope.to_string())]
+            }
+            None => vec![],
+        };
+
+        Ok(Response::new()
+            .add_attribute("method", "set_dynamic_fee_params")
+            .add_attributes(attrs))
+    }
+
+    /// Set the maximum value of any single swap, denominated in alloyed asset amount
+    /// so that different denoms are compared via their normalization factors.
+    /// Setting it to `None` removes the cap.
+    #[sv::msg(exec)]
+    fn set_max_swap_value(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        max_swap_value: Option<Uint128>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set max swap value
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_max_swap_value",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        match max_swap_value {
+            Some(max_swap_value) => self.max_swap_value.save(deps.storage, &max_swap_value)?,
+            None => self.max_swap_value.remove(deps.storage),
+        }
+
+        Ok(Response::new()
+            .add_attribute("method", "set_max_swap_value")
+            .add_attribute(
+                "max_swap_value",
+                max_swap_value
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "none".to_string()),
+            ))
+    }
+
+    /// Set max total pool liquidity, denominated in alloyed asset amount.
+    /// Joining pool or swapping into alloyed asset is rejected if it would exceed the cap.
+    /// Setting `max_total_liquidity` to `None` removes the cap.
+    #[sv::msg(exec)]
+    fn set_max_total_liquidity(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        max_total_liquidity: Option<Uint128>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set max total liquidity
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_max_total_liquidity",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        match max_total_liquidity {
+            Some(max_total_liquidity) => self
+                .max_total_liquidity
+                .save(deps.storage, &max_total_liquidity)?,
+            None => self.max_total_liquidity.remove(deps.storage),
+        }
+
+        Ok(Response::new()
+            .add_attribute("method", "set_max_total_liquidity")
+            .add_attribute(
+                "max_total_liquidity",
+                max_total_liquidity
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "none".to_string()),
+            ))
+    }
+
+    /// Set the maximum difference tolerated between the requested and computed amounts
+    /// of token to token swaps, to absorb rounding from normalization.
+    /// Defaults to zero, which requires exact equality.
+    #[sv::msg(exec)]
+    fn set_amount_tolerance(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        amount_tolerance: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set amount tolerance
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_amount_tolerance",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.amount_tolerance
+            .save(deps.storage, &amount_tolerance)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_amount_tolerance")
+            .add_attribute("amount_tolerance", amount_tolerance))
+    }
+
+    /// Set the pool id assigned to this contract by the cosmwasm pool module,
+    /// so that it can be reported by the contract info query.
+    #[sv::msg(exec)]
+    fn set_pool_id(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        pool_id: u64,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set pool id
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_pool_id",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.pool_id.save(deps.storage, &pool_id)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_pool_id")
+            .add_attribute("pool_id", pool_id.to_string()))
+    }
+
+    /// Set bonus, as a fraction of token out, paid when swapping out a corrupted asset
+    /// to incentivize the market to drain it. The bonus is funded by the fee reserve,
+    /// which is the pool value in excess of the alloyed asset supply.
+    #[sv::msg(exec)]
+    fn set_corruption_drain_incentive(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        corruption_drain_incentive: Decimal,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set corruption drain incentive
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_corruption_drain_incentive",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        ensure!(
+            corruption_drain_incentive < Decimal::one(),
+            ContractError::CorruptionDrainIncentiveMustBeLessThanOne {}
+        );
+
+        self.corruption_drain_incentive
+            .save(deps.storage, &corruption_drain_incentive)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_corruption_drain_incentive")
+            .add_attribute(
+                "corruption_drain_incentive",
+                corruption_drain_incentive.to_string(),
+            ))
+    }
+
+    /// Set interval, in blocks, between pool snapshots taken on swaps.
+    /// Setting `interval` to `None` disables snapshots, existing snapshots are kept.
+    #[sv::msg(exec)]
+    fn set_pool_snapshot_interval(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        interval: Option<u64>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set pool snapshot interval
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_pool_snapshot_interval",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.pool_snapshots.set_interval(deps.storage, interval)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_pool_snapshot_interval")
+            .add_attribute(
+                "interval",
+                interval
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "none".to_string()),
+            ))
+    }
+
+    /// Set cooldown in nanoseconds between consecutive large swaps by the same sender.
+    /// Swap is considered large if its value, denominated in alloyed asset amount,
+    /// exceeds `large_swap_threshold`. Setting `large_swap_cooldown` to `None` disables the cooldown.
+    #[sv::msg(exec)]
+    fn set_large_swap_cooldown(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        large_swap_cooldown: Option<Uint64>,
+        large_swap_threshold: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set large swap cooldown
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_large_swap_cooldown",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        match large_swap_cooldown {
+            Some(cooldown) => self.large_swap_cooldown.save(
+                deps.storage,
+                &LargeSwapCooldown {
+                    threshold: large_swap_threshold,
+                    cooldown,
+                },
+            )?,
+            None => self.large_swap_cooldown.remove(deps.storage),
+        }
+
+        Ok(Response::new()
+            .add_attribute("method", "set_large_swap_cooldown")
+            .add_attribute(
+                "large_swap_cooldown",
+                large_swap_cooldown
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "none".to_string()),
+            )
+            .add_attribute("large_swap_threshold", large_swap_threshold.to_string()))
+    }
+
+    /// Set the amount of shares minted to the contract itself on the first mint,
+    /// which can never be redeemed. This keeps the pool from being drained to zero shares.
+    /// It has no effect once the alloyed asset has supply.
+    #[sv::msg(exec)]
+    fn set_min_locked_shares(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        min_locked_shares: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set min locked shares
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_min_locked_shares",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.min_locked_shares
+            .save(deps.storage, &min_locked_shares)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_min_locked_shares")
+            .add_attribute("min_locked_shares", min_locked_shares))
+    }
+
+    /// Set the pool balance below which a denom's residual, after being swapped out,
+    /// is swept out of pool accounting into retained dust. Zero disables sweeping.
+    #[sv::msg(exec)]
+    fn set_dust_threshold(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        dust_threshold: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set dust threshold
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_dust_threshold",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.dust_threshold.save(deps.storage, &dust_threshold)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_dust_threshold")
+            .add_attribute("dust_threshold", dust_threshold))
+    }
+
+    /// Set operator-defined target weights of pool assets, replacing any existing targets.
+    /// Targets are advisory metadata for keepers and UIs, the pool only moves toward them
+    /// through [`Transmuter::rebalance`], funded by accrued fees.
+    #[sv::msg(exec)]
+    fn set_target_weights(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        target_weights: BTreeMap<String, Decimal>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set target weights
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_target_weights",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        let pool = self.pool.load(deps.storage)?;
+        for denom in target_weights.keys() {
+            ensure!(
+                pool.has_denom(denom),
+                ContractError::InvalidPoolAssetDenom {
+                    denom: denom.to_string()
+                }
+            );
+        }
+
+        let sum = target_weights
+            .values()
+            .try_fold(Decimal::zero(), |sum, weight| sum.checked_add(*weight))?;
+        ensure!(
+            sum.abs_diff(Decimal::one()) <= TARGET_WEIGHTS_SUM_TOLERANCE,
+            ContractError::InvalidTargetWeightsSum { sum }
+        );
+
+        let existing_denoms = self
+            .target_weights
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for denom in existing_denoms {
+            self.target_weights.remove(deps.storage, &denom);
+        }
+
+        for (denom, weight) in target_weights.iter() {
+            self.target_weights.save(deps.storage, denom, weight)?;
+        }
+
+        Ok(Response::new().add_attribute("method", "set_target_weights"))
+    }
+
+    /// Register an alloyed asset in addition to the main alloyed asset, backed only by
+    /// `backing_denoms`, which must be pool asset denoms. Its backing is accounted separately
+    /// from the pool, so it can be used for share classes with different risk profiles.
+    #[sv::msg(exec)]
+    fn register_alloyed_asset(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        subdenom: String,
+        backing_denoms: Vec<String>,
+        normalization_factor: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can register alloyed asset
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "register_alloyed_asset",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        non_empty_input_required("backing_denoms", &backing_denoms)?;
+
+        // subdenom must not contain extra parts
+        ensure!(
+            !subdenom.contains('/'),
+            ContractError::SubDenomExtraPartsNotAllowed { subdenom }
+        );
+        ensure_valid_alloyed_subdenom(&subdenom)?;
+
+        // backing assets start empty and share normalization factors with the pool assets
+        let pool = self.pool.load(deps.storage)?;
+        let backing_assets = backing_denoms
+            .iter()
+            .map(|denom| {
+                let pool_asset = pool.get_pool_asset_by_denom(denom)?;
+                Asset::new(
+                    Uint128::zero(),
+                    pool_asset.denom(),
+                    pool_asset.normalization_factor(),
+                )
+            })
+            .collect::<Result<Vec<_>, ContractError>>()?;
+
+        self.alloyed_registry.set_pending(
+            deps.storage,
+            &PendingAlloyedAsset {
+                normalization_factor,
+                backing: TransmuterPool::new(backing_assets)?,
+            },
+        )?;
+
+        let msg_create_alloyed_denom = SubMsg::reply_on_success(
+            MsgCreateDenom {
+                sender: env.contract.address.to_string(),
+                subdenom: format!("{}/{}", ALLOYED_PREFIX, subdenom),
+            },
+            ReplyId::CreateRegisteredAlloyedDenom.id(),
+        );
+
+        Ok(Response::new()
+            .add_attribute("method", "register_alloyed_asset")
+            .add_attribute("subdenom", subdenom)
+            .add_submessage(msg_create_alloyed_denom))
+    }
+
+    /// Cap the alloyed asset amount minted, by joining pool, swapping into alloyed asset,
+    /// withdrawing alloyed asset fee or minting registered alloyed assets, valued in alloyed asset,
+    /// to `max_mint_per_window` within any rolling `mint_window` in nanoseconds.
+    /// Setting `mint_window` to `None` removes the cap. Burns are not limited.
+    #[sv::msg(exec)]
+    fn set_mint_rate_limit(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        mint_window: Option<Uint64>,
+        max_mint_per_window: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set mint rate limit
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_mint_rate_limit",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        match mint_window {
+            Some(window) => self.mint_rate_limit.save(
+                deps.storage,
+                &MintRateLimit {
+                    max_mint_per_window,
+                    window,
+                },
+            )?,
+            None => self.mint_rate_limit.remove(deps.storage),
+        }
+
+        // start over with the new limit
+        self.mint_volumes.clear(deps.storage)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_mint_rate_limit")
+            .add_attribute(
+                "mint_window",
+                mint_window
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "none".to_string()),
+            )
+            .add_attribute("max_mint_per_window", max_mint_per_window.to_string()))
+    }
+
+    #[sv::msg(exec)]
+    pub fn set_alloyed_denom_metadata(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        metadata: Metadata,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set denom metadata
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "set_alloyed_denom_metadata",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        let msg_set_denom_metadata = MsgSetDenomMetadata {
+            sender: env.contract.address.to_string(),
+            metadata: Some(metadata),
+        };
+
+        Ok(Response::new()
+            .add_attribute("method", "set_alloyed_denom_metadata")
+            .add_message(msg_set_denom_metadata))
+    }
+
+    /// Set the number of decimals of `denom`'s display unit, used for human readable amounts
+    /// in queries. If `decimals` is not set, it is discovered from the bank metadata of `denom`,
+    /// as the exponent of its display unit.
+    #[sv::msg(exec)]
+    fn set_display_decimals(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        denom: String,
+        decimals: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can set display decimals
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        // only pool asset or alloyed asset denoms have display decimals
+        if Some(&denom)
+            != self
+                .alloyed_asset
+                .may_get_alloyed_denom(deps.storage)?
+                .as_ref()
+        {
+            self.pool
+                .load(deps.storage)?
+                .get_pool_asset_by_denom(&denom)?;
+        }
+
+        let decimals = match decimals {
+            Some(decimals) => decimals,
+            None => self
+                .denom_metadata_cache
+                .get_or_query(deps.storage, &deps.querier, &denom)?
+                .and_then(|metadata| {
+                    metadata
+                        .denom_units
+                        .into_iter()
+                        .find(|unit| unit.denom == metadata.display)
+                })
+                .map(|unit| unit.exponent)
+                .ok_or_else(|| ContractError::DisplayDecimalsNotFound {
+                    denom: denom.clone(),
+                })?,
+        };
+
+        ensure!(
+            decimals <= Decimal::DECIMAL_PLACES,
+            ContractError::InvalidDisplayDecimals {
+                decimals,
+                max_decimals: Decimal::DECIMAL_PLACES,
+            }
+        );
+
+        self.governance_log.record(
+            deps.storage,
+            "set_display_decimals",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.display_decimals
+            .save(deps.storage, &denom, &decimals)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_display_decimals")
+            .add_attribute("denom", denom)
+            .add_attribute("decimals", decimals.to_string()))
+    }
+
+    /// Drop the cached bank metadata of `denom`, e.g. after its metadata is updated,
+    /// so that it is queried again on next access.
+    #[sv::msg(exec)]
+    fn invalidate_denom_metadata(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        denom: String,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only admin can invalidate cached denom metadata
+        ensure_admin_authority!(info.sender, self.role.admin, deps.as_ref());
+
+        self.governance_log.record(
+            deps.storage,
+            "invalidate_denom_metadata",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        self.denom_metadata_cache.invalidate(deps.storage, &denom)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "invalidate_denom_metadata")
+            .add_attribute("denom", denom))
+    }
+
+    /// Set active status of the pool. When deactivating, `scope` determines
+    /// which operations are paused, defaults to [`PauseScope::All`].
+    /// If `reactivate_at` is set, the pause lifts by itself once the block time reaches it,
+    /// without an intervening tx. It is ignored when activating.
+    #[sv::msg(exec)]
+    fn set_active_status(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        active: bool,
+        scope: Option<PauseScope>,
+        reactivate_at: Option<Timestamp>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        // only moderator can set active status
+        ensure_moderator_authority!(info.sender, self.role.moderator, deps.as_ref());
+
+        // set active status
+        self.checked_set_active_status(
+            deps.storage,
+            env.block.time,
+            active,
+            scope.clone(),
+            reactivate_at,
+        )?;
+
+        self.governance_log.record(
+            deps.storage,
+            "set_active_status",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_active_status")
+            .add_attribute("active", active.to_string())
+            .add_attribute("scope", pause_scope_attribute(active, scope)))
+    }
+
+    /// Set active status, comparing against the effective status at `block_time`,
+    /// so that an explicit call overrides a pause that has lifted by its reactivation time.
+    pub(crate) fn checked_set_active_status(
+        &self,
+        storage: &mut dyn Storage,
+        block_time: Timestamp,
+        active: bool,
+        scope: Option<PauseScope>,
+        reactivate_at: Option<Timestamp>,
+    ) -> Result<bool, ContractError> {
+        let prev_active = self.is_active_at(storage, block_time)?;
+        ensure_ne!(
+            prev_active,
+            active,
+            ContractError::UnchangedActiveStatus { status: active }
+        );
+
+        // validate before any write, so that a rejected update leaves the status untouched
+        if let Some(reactivate_at) = reactivate_at.filter(|_| !active) {
+            ensure!(
+                reactivate_at > block_time,
+                ContractError::ReactivationTimeNotInFuture {
+                    reactivate_at,
+                    block_time
+                }
+            );
+        }
+
+        self.active_status.save(storage, &active)?;
+
+        if active {
+            self.pause_scope.remove(storage);
+            self.reactivate_at.remove(storage);
+        } else {
+            self.pause_scope
+                .save(storage, &scope.unwrap_or(PauseScope::All))?;
+
+            match reactivate_at {
+                Some(reactivate_at) => self.reactivate_at.save(storage, &reactivate_at)?,
+                None => self.reactivate_at.remove(storage),
+            }
+        }
+
+        Ok(active)
+    }
+
+    /// Whether the pool is active at `block_time`, treating a pause as lifted
+    /// once its reactivation time, if any, is reached.
+    pub(crate) fn is_active_at(
+        &self,
+        storage: &dyn Storage,
+        block_time: Timestamp,
+    ) -> StdResult<bool> {
+        if self.active_status.load(storage)? {
+            return Ok(true);
+        }
+
+        Ok(self
+            .reactivate_at
+            .may_load(storage)?
+            .is_some_and(|reactivate_at| block_time >= reactivate_at))
+    }
+
+    /// Resolve denom alias to its pool asset denom, denoms that are not aliases are returned as is.
+    pub(crate) fn resolve_denom(
+        &self,
+        storage: &dyn Storage,
+        denom: String,
+    ) -> Result<String, ContractError> {
+        Ok(self
+            .denom_aliases
+            .may_load(storage, &denom)?
+            .unwrap_or(denom))
+    }
+
+    /// Ensure that the operation is not paused by the current active status and pause scope.
+    pub(crate) fn ensure_not_paused(
+        &self,
+        storage: &dyn Storage,
+        block_time: Timestamp,
+        operation: PausableOperation,
+    ) -> Result<(), ContractError> {
+        if self.is_active_at(storage, block_time)? {
+            return Ok(());
+        }
+
+        let scope = self
+            .pause_scope
+            .may_load(storage)?
+            .unwrap_or(PauseScope::All);
+
+        ensure!(!scope.pauses(operation), ContractError::InactivePool {});
+
+        Ok(())
+    }
+
+    /// Join pool with tokens that exist in the pool.
+    /// Token used to join pool is sent to the contract via `funds` in `MsgExecuteContract`.
+    /// If `min_shares_out` is set, joining fails when fewer shares would be minted,
+    /// protecting against drift between tx construction and execution, e.g. locked initial shares.
+    #[sv::msg(exec)]
+    pub fn join_pool(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        min_shares_out: Option<Uint128>,
+    ) -> Result<Response, ContractError> {
+        self.ensure_sender_allowed(deps.storage, &info.sender)?;
+
+        self.swap_tokens_to_alloyed_asset(
+            Entrypoint::Exec,
+            SwapToAlloyedConstraint::ExactIn {
+                tokens_in: &info.funds,
+                token_out_min_amount: min_shares_out.unwrap_or_default(),
+            },
+            info.sender,
+            deps,
+            env,
+        )
+        .map_err(|err| match err {
+            ContractError::InsufficientTokenOut {
+                min_required,
+                amount_out,
+            } => ContractError::InsufficientShares {
+                required: min_required,
+                available: amount_out,
+            },
+            err => err,
+        })
+        .map(|res| res.add_attribute("method", "join_pool"))
+    }
+
+    /// Exit pool with `tokens_out` amount of tokens.
+    /// As long as the sender has enough shares, the contract will send `tokens_out` amount of tokens to the sender.
+    /// The amount of shares will be deducted from the sender's shares.
+    #[sv::msg(exec)]
+    pub fn exit_pool(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        tokens_out: Vec<Coin>,
+    ) -> Result<Response, ContractError> {
+        // it will deduct shares directly from the sender's account
+        nonpayable(&info.funds)?;
+
+        self.swap_alloyed_asset_to_tokens(
+            Entrypoint::Exec,
+            SwapFromAlloyedConstraint::ExactOut {
+                tokens_out: &tokens_out,
+                token_in_max_amount: Uint128::MAX,
+            },
+            BurnTarget::SenderAccount,
+            info.sender,
+            deps,
+            env,
+        )
+        .map(|res| res.add_attribute("method", "exit_pool"))
+    }
+
+    /// Exit pool with `tokens_out` amount of tokens on behalf of `owner`, burning the required
+    /// shares from the owner's account and sending `tokens_out` to the sender.
+    /// The burned shares are deducted from the allowance `owner` granted to the sender.
+    #[sv::msg(exec)]
+    pub fn exit_pool_from(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        owner: String,
+        tokens_out: Vec<Coin>,
+    ) -> Result<Response, ContractError> {
+        // it will deduct shares directly from the owner's account
+        nonpayable(&info.funds)?;
+
+        let owner = deps.api.addr_validate(&owner)?;
+
+        self.swap_alloyed_asset_to_tokens(
+            Entrypoint::Exec,
+            SwapFromAlloyedConstraint::ExactOut {
+                tokens_out: &tokens_out,
+                token_in_max_amount: Uint128::MAX,
+            },
+            BurnTarget::Address(owner.clone()),
+            info.sender,
+            deps,
+            env,
+        )
+        .map(|res| {
+            res.add_attribute("method", "exit_pool_from")
+                .add_attribute("owner", owner)
+        })
+    }
+
+    /// Allow `spender` to burn `amount` more of the sender's alloyed asset, see `exit_pool_from`.
+    #[sv::msg(exec)]
+    pub fn increase_alloyed_allowance(
+        &self,
+        ExecCtx { deps, env: _, info }: ExecCtx,
+        spender: String,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        let spender = deps.api.addr_validate(&spender)?;
+        let alloyed_denom = self.alloyed_asset.get_alloyed_denom(deps.storage)?;
+        let allowance = self.alloyed_allowances.increase(
+            deps.storage,
+            &alloyed_denom,
+            &info.sender,
+            &spender,
+            amount,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("method", "increase_alloyed_allowance")
+            .add_attribute("owner", info.sender)
+            .add_attribute("spender", spender)
+            .add_attribute("allowance", allowance))
+    }
+
+    /// Lower the amount of the sender's alloyed asset `spender` is allowed to burn, down to zero.
+    #[sv::msg(exec)]
+    pub fn decrease_alloyed_allowance(
+        &self,
+        ExecCtx { deps, env: _, info }: ExecCtx,
+        spender: String,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info.funds)?;
+
+        let spender = deps.api.addr_validate(&spender)?;
+        let alloyed_denom = self.alloyed_asset.get_alloyed_denom(deps.storage)?;
+        let allowance = self.alloyed_allowances.decrease(
+            deps.storage,
+            &alloyed_denom,
+            &info.sender,
+            &spender,
+            amount,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("method", "decrease_alloyed_allowance")
+            .add_attribute("owner", info.sender)
+            .add_attribute("spender", spender)
+            .add_attribute("allowance", allowance))
+    }
+
+    /// Mint registered `alloyed_denom` for tokens sent via `funds`,
+    /// which must be backing denoms of that alloyed asset.
+    #[sv::msg(exec)]
+    pub fn join_alloyed_asset(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        alloyed_denom: String,
+    ) -> Result<Response, ContractError> {
+        self.mint_registered_alloyed_asset(&alloyed_denom, &info.funds, info.sender, deps, env)
+            .map(|res| res.add_attribute("method", "join_alloyed_asset"))
+    }
+
+    /// Withdraw `tokens_out` from the backing of registered `alloyed_denom`,
+    /// burning the required amount of it from the sender's account.
+    #[sv::msg(exec)]
+    pub fn exit_alloyed_asset(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        alloyed_denom: String,
+        tokens_out: Vec<Coin>,
+    ) -> Result<Response, ContractError> {
+        // it will deduct the alloyed asset directly from the sender's account
+        nonpayable(&info.funds)?;
+
+        self.burn_registered_alloyed_asset(
+            &alloyed_denom,
+            &tokens_out,
+            BurnTarget::SenderAccount,
+            info.sender,
+            deps,
+            env,
+        )
+        .map(|res| res.add_attribute("method", "exit_alloyed_asset"))
+    }
+
+    /// Exit the pool entirely into `target_denom` by burning `share_amount` of the sender's shares.
+    ///
+    /// Routing the exited basket through transmutes into `target_denom` puts every other denom
+    /// straight back into the pool, so the net effect is draining only `target_denom`,
+    /// which is done in a single step and is subject to limiters like any other exit.
+    #[sv::msg(exec)]
+    pub fn exit_to_single_denom(
+        &self,
+        ExecCtx { deps, env, info }: ExecCtx,
+        share_amount: Uint128,
+        target_denom: String,
+        min_amount_out: Uint128,
+    ) -> Result<Response, ContractError> {
+        // it will deduct shares directly from the sender's account
+        nonpayable(&info.funds)?;
+
+        self.swap_alloyed_asset_to_tokens(
+            Entrypoint::Exec,
+            SwapFromAlloyedConstraint::ExactIn {
+                token_out_denom: &target_denom,
+                token_out_min_amount: min_amount_out,
+                token_in_amount: share_amount,
+            },
+            BurnTarget::SenderAccount,
+            info.sender,
+            deps,
+            env,
+        )
+        .map(|res| {
+            res.add_attribute("method", "exit_to_single_denom")
+                .add_attribute("share_amount", share_amount)
+                .add_attribute("target_denom", target_denom)
+        })
+    }
+
+    // === queries ===
+
+    #[sv::msg(query)]
+    fn list_asset_configs(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<ListAssetConfigsResponse, ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+        let normalization_factor = self.alloyed_asset.get_normalization_factor(deps.storage)?;
+        let alloyed_asset_config =
+            self.alloyed_asset
+                .may_get_alloyed_denom(deps.storage)?
+                .map(|denom| AssetConfig {
+                    denom,
+                    normalization_factor,
+                });
+
+        Ok(ListAssetConfigsResponse {
+            asset_configs: pool
+                .pool_assets
+                .iter()
+                .map(|asset| asset.config())
+                .chain(alloyed_asset_config)
+                .collect(),
+        })
+    }
+
+    /// List all pool asset denoms, excluding the alloyed asset denom.
+    #[sv::msg(query)]
+    fn pool_denoms(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<PoolDenomsResponse, ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+
+        let mut denoms: Vec<String> = pool
+            .pool_assets
+            .iter()
+            .map(|asset| asset.denom().to_string())
+            .collect();
+        denoms.sort();
+
+        Ok(PoolDenomsResponse { denoms })
+    }
+
+    #[sv::msg(query)]
+    fn list_limiters(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<ListLimitersResponse, ContractError> {
+        let limiters = self.limiters.list_limiters(deps.storage)?;
+
+        Ok(ListLimitersResponse { limiters })
+    }
+
+    /// Number of trips of each `(denom, label)` limiter observed on partial fills,
+    /// for identifying mis-tuned limiters.
+    /// Swaps rejected by a limiter are not counted, since the rejection reverts the count
+    /// along with the rest of the transaction. Counts read zero if swaps are only ever rejected.
+    #[sv::msg(query)]
+    fn limiter_trip_counts(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<LimiterTripCountsResponse, ContractError> {
+        let trip_counts = self.limiters.list_trip_counts(deps.storage)?;
+
+        Ok(LimiterTripCountsResponse { trip_counts })
+    }
+
+    /// Most recent limiter trips observed on partial fills, most recent first, for reconstructing
+    /// the timeline of an incident. Only a bounded number of the latest trips are kept.
+    /// Like trip counts, swaps rejected by a limiter are never logged, since the rejection
+    /// reverts the record, so the log is empty if swaps are only ever rejected.
+    #[sv::msg(query)]
+    fn recent_limiter_trips(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        limit: Option<u32>,
+    ) -> Result<RecentLimiterTripsResponse, ContractError> {
+        let trips = self.limiters.list_recent_trips(deps.storage, limit)?;
+
+        Ok(RecentLimiterTripsResponse { trips })
+    }
+
+    /// Stress config of the limiters and whether the pool is currently under stress,
+    /// i.e. static limiter upper limits are tightened.
+    #[sv::msg(query)]
+    fn get_limiter_stress_config(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetLimiterStressConfigResponse, ContractError> {
+        let stress_config = self.limiters.get_stress_config(deps.storage)?;
+        let weights = self.pool.load(deps.storage)?.weights()?.unwrap_or_default();
+        let under_stress = self
+            .limiters
+            .stress_tightening_factor(deps.storage, &weights)?
+            .is_some();
+
+        Ok(GetLimiterStressConfigResponse {
+            stress_config,
+            under_stress,
+        })
+    }
+
+    /// Whether any denom's current weight is at or within [`LIMITER_BINDING_EPSILON`]
+    /// of a limiter's upper limit, signifying that the pool is near a constraint.
+    #[sv::msg(query)]
+    fn any_limiter_binding(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+    ) -> Result<AnyLimiterBindingResponse, ContractError> {
+        let Some(denom_weight_pairs) = self.pool.load(deps.storage)?.weights()? else {
+            return Ok(AnyLimiterBindingResponse { binding: false });
+        };
+
+        let binding = self.limiters.any_binding(
+            deps.storage,
+            denom_weight_pairs,
+            env.block.time,
+            LIMITER_BINDING_EPSILON,
+        )?;
+
+        Ok(AnyLimiterBindingResponse { binding })
+    }
+
+    /// Whether `denom` can be swapped in and out right now, combining the pool's active status,
+    /// asset group pauses, corruption, limiter coverage and limiters binding within
+    /// [`LIMITER_BINDING_EPSILON`]. Each reason blocking either direction is listed.
+    /// Sender specific constraints, e.g. sender access mode, are not taken into account.
+    #[sv::msg(query)]
+    fn denom_status(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+        denom: String,
+    ) -> Result<DenomStatusResponse, ContractError> {
+        let mut can_swap_in = true;
+        let mut can_swap_out = true;
+        let mut reasons = vec![];
+
+        if self
+            .ensure_not_paused(deps.storage, env.block.time, PausableOperation::Swap)
+            .is_err()
+        {
+            can_swap_in = false;
+            can_swap_out = false;
+            reasons.push("pool is inactive".to_string());
+        }
+
+        // alloyed asset is not subject to per asset constraints
+        if Some(&denom)
+            == self
+                .alloyed_asset
+                .may_get_alloyed_denom(deps.storage)?
+                .as_ref()
+        {
+            return Ok(DenomStatusResponse {
+                can_swap_in,
+                can_swap_out,
+                reasons,
+            });
+        }
+
+        let pool = self.pool.load(deps.storage)?;
+        let asset = pool.get_pool_asset_by_denom(&denom)?;
+
+        if let Some(label) = self.asset_groups.paused_group_of(deps.storage, &denom)? {
+            can_swap_in = false;
+            reasons.push(format!("asset group `{label}` is paused"));
+        }
+
+        if pool.is_corrupted_asset(&denom) {
+            can_swap_in = false;
+            reasons.push("asset is corrupted".to_string());
+        }
+
+        if self
+            .require_limiter_coverage
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+            && self
+                .limiters
+                .list_limiters_by_denom(deps.storage, &denom)?
+                .is_empty()
+        {
+            can_swap_in = false;
+            reasons.push("no limiter registered while limiter coverage is required".to_string());
+        }
+
+        if asset.amount().is_zero() {
+            can_swap_out = false;
+            reasons.push("no liquidity".to_string());
+        }
+
+        if let Some(denom_weight_pairs) = pool.weights()? {
+            // shift the weight of `denom` only, in the direction each swap would move it
+            let binding_labels = |shift: fn(Decimal, Decimal) -> Decimal| {
+                let shifted_pairs = denom_weight_pairs
+                    .iter()
+                    .map(|(d, weight)| match d == &denom {
+                        true => (d.clone(), shift(*weight, LIMITER_BINDING_EPSILON)),
+                        false => (d.clone(), *weight),
+                    })
+                    .collect();
+
+                self.limiters
+                    .check_each(deps.storage, shifted_pairs, env.block.time)
+                    .map(|results| {
+                        results
+                            .into_iter()
+                            .filter(|((d, _), passes)| d == &denom && !passes)
+                            .map(|((_, label), _)| label)
+                            .collect::<Vec<_>>()
+                    })
+            };
+
+            for label in binding_labels(Decimal::saturating_add)? {
+                can_swap_in = false;
+                reasons.push(format!("limiter `{label}` binds swapping in"));
+            }
+
+            for label in binding_labels(Decimal::saturating_sub)? {
+                can_swap_out = false;
+                reasons.push(format!("limiter `{label}` binds swapping out"));
+            }
+        }
+
+        Ok(DenomStatusResponse {
+            can_swap_in,
+            can_swap_out,
+            reasons,
+        })
+    }
+
+    /// Coarse cost class of swapping `token_in_denom` for `token_out_denom`, for front-ends
+    /// to warn before a costly swap. A swap checks the limiters of every pool asset, since
+    /// all weights shift, and scans every asset group for pauses, so the class is based on
+    /// the total number of limiters and asset groups.
+    #[sv::msg(query)]
+    fn swap_complexity(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        token_in_denom: String,
+        token_out_denom: String,
+    ) -> Result<SwapComplexityResponse, ContractError> {
+        self.swap_variant(&token_in_denom, &token_out_denom, deps)?;
+
+        let pool = self.pool.load(deps.storage)?;
+        let alloyed_denom = self.alloyed_asset.may_get_alloyed_denom(deps.storage)?;
+        for denom in [&token_in_denom, &token_out_denom] {
+            if alloyed_denom.as_ref() != Some(denom) {
+                pool.get_pool_asset_by_denom(denom)?;
+            }
+        }
+
+        let limiter_count = self.limiters.list_limiters(deps.storage)?.len() as u64;
+        let asset_group_count = self.asset_groups.count(deps.storage) as u64;
+
+        let evaluated = limiter_count + asset_group_count;
+        let complexity = if evaluated < SWAP_COMPLEXITY_MEDIUM_THRESHOLD {
+            SwapComplexityClass::Low
+        } else if evaluated < SWAP_COMPLEXITY_HIGH_THRESHOLD {
+            SwapComplexityClass::Medium
+        } else {
+            SwapComplexityClass::High
+        };
+
+        Ok(SwapComplexityResponse {
+            complexity,
+            limiter_count,
+            asset_group_count,
+        })
+    }
+
+    /// Run all limiters of the given denoms against a hypothetical weight vector,
+    /// without updating limiter states. Useful for simulating limiters apart from actual swaps.
+    #[sv::msg(query)]
+    fn check_weights(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+        weights: Vec<(String, Decimal)>,
+    ) -> Result<CheckWeightsResponse, ContractError> {
+        let results = self
+            .limiters
+            .check_each(deps.storage, weights, env.block.time)?
+            .into_iter()
+            .map(|((denom, label), passes)| LimiterCheckResult {
+                denom,
+                label,
+                passes,
+            })
+            .collect();
+
+        Ok(CheckWeightsResponse { results })
+    }
+
+    /// All limiters, split by limiter type, each tagged by denom and label.
+    #[sv::msg(query)]
+    fn limiters_by_type(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<LimitersByTypeResponse, ContractError> {
+        let mut static_limiters = vec![];
+        let mut change_limiters = vec![];
+        let mut relative_limiters = vec![];
+        let mut net_flow_limiters = vec![];
+
+        for ((denom, label), limiter) in self.limiters.list_limiters(deps.storage)? {
+            match limiter {
+                Limiter::StaticLimiter(limiter) => static_limiters.push(StaticLimiterInfo {
+                    denom,
+                    label,
+                    upper_limit: limiter.upper_limit(),
+                }),
+                Limiter::ChangeLimiter(limiter) => change_limiters.push(ChangeLimiterInfo {
+                    denom,
+                    label,
+                    window_config: limiter.window_config().clone(),
+                    boundary_offset: limiter.boundary_offset(),
+                }),
+                Limiter::RelativeLimiter(limiter) => relative_limiters.push(RelativeLimiterInfo {
+                    denom,
+                    label,
+                    max_deviation: limiter.max_deviation(),
+                }),
+                Limiter::NetFlowLimiter(limiter) => net_flow_limiters.push(NetFlowLimiterInfo {
+                    denom,
+                    label,
+                    window_config: limiter.window_config().clone(),
+                    max_net_flow: limiter.max_net_flow(),
+                }),
+            }
+        }
+
+        Ok(LimitersByTypeResponse {
+            static_limiters,
+            change_limiters,
+            relative_limiters,
+            net_flow_limiters,
+        })
+    }
+
+    /// Static window configuration of a change limiter, excluding its live state.
+    #[sv::msg(query)]
+    fn change_limiter_window(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        denom: String,
+        label: String,
+    ) -> Result<ChangeLimiterWindowResponse, ContractError> {
+        match self.limiters.get_limiter(deps.storage, &denom, &label)? {
+            Limiter::ChangeLimiter(limiter) => Ok(ChangeLimiterWindowResponse {
+                window_size: limiter.window_config().window_size,
+                division_count: limiter.window_config().division_count,
+                boundary_offset: limiter.boundary_offset(),
+            }),
+            Limiter::StaticLimiter(_) => Err(ContractError::WrongLimiterType {
+                expected: "change_limiter".to_string(),
+                actual: "static_limiter".to_string(),
+            }),
+            Limiter::RelativeLimiter(_) => Err(ContractError::WrongLimiterType {
+                expected: "change_limiter".to_string(),
+                actual: "relative_limiter".to_string(),
+            }),
+            Limiter::NetFlowLimiter(_) => Err(ContractError::WrongLimiterType {
+                expected: "change_limiter".to_string(),
+                actual: "net_flow_limiter".to_string(),
+            }),
+        }
+    }
+
+    #[sv::msg(query)]
+    pub fn get_shares(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        address: String,
+    ) -> Result<GetSharesResponse, ContractError> {
+        Ok(GetSharesResponse {
+            shares: self
+                .alloyed_asset
+                .get_balance(deps, &deps.api.addr_validate(&address)?)?,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_share_denom(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetShareDenomResponse, ContractError> {
+        Ok(GetShareDenomResponse {
+            share_denom: self.alloyed_asset.get_alloyed_denom(deps.storage)?,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_swap_fee(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetSwapFeeResponse, ContractError> {
+        Ok(GetSwapFeeResponse {
+            swap_fee: self.fee.get_swap_fee(deps.storage)?,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_fee_config(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetFeeConfigResponse, ContractError> {
+        Ok(GetFeeConfigResponse {
+            swap_fee: self.fee.get_swap_fee(deps.storage)?,
+            fee_collector: self.fee.get_fee_collector(deps.storage)?,
+            fee_split: self.fee.get_fee_split(deps.storage)?,
+            fee_side: self.fee.get_fee_side(deps.storage)?,
+            dynamic_fee_params: self.fee.get_dynamic_fee_params(deps.storage)?,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_denom_swap_fees(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetDenomSwapFeesResponse, ContractError> {
+        Ok(GetDenomSwapFeesResponse {
+            denom_swap_fees: self
+                .fee
+                .list_denom_swap_fees(deps.storage)?
+                .into_iter()
+                .collect(),
+        })
+    }
+
+    /// Swap fee charged when swapping `token_in_denom` for `token_out_denom`,
+    /// which is the highest of the swap fee and the per-denom swap fees of both denoms.
+    /// In dynamic fee mode, the swap fee is evaluated at the current deviation from target weights,
+    /// as if the swap were infinitesimal.
+    #[sv::msg(query)]
+    pub(crate) fn effective_swap_fee(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        token_in_denom: String,
+        token_out_denom: String,
+    ) -> Result<EffectiveSwapFeeResponse, ContractError> {
+        self.swap_variant(&token_in_denom, &token_out_denom, deps)?;
+
+        // denoms must be either pool asset or alloyed asset
+        let pool = self.pool.load(deps.storage)?;
+        let alloyed_denom = self.alloyed_asset.may_get_alloyed_denom(deps.storage)?;
+        for denom in [&token_in_denom, &token_out_denom] {
+            ensure!(
+                pool.has_denom(denom) || Some(denom) == alloyed_denom.as_ref(),
+                ContractError::InvalidPoolAssetDenom {
+                    denom: denom.to_string()
+                }
+            );
+        }
+
+        let denoms = [token_in_denom.as_str(), token_out_denom.as_str()];
+        Ok(EffectiveSwapFeeResponse {
+            swap_fee: self.fee.get_effective_swap_fee(
+                deps.storage,
+                &denoms,
+                self.max_target_deviation(deps.storage, &pool, &denoms)?,
+            )?,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_corruption_drain_incentive(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetCorruptionDrainIncentiveResponse, ContractError> {
+        Ok(GetCorruptionDrainIncentiveResponse {
+            corruption_drain_incentive: self
+                .corruption_drain_incentive
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_max_swap_value(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetMaxSwapValueResponse, ContractError> {
+        Ok(GetMaxSwapValueResponse {
+            max_swap_value: self.max_swap_value.may_load(deps.storage)?,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_max_total_liquidity(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetMaxTotalLiquidityResponse, ContractError> {
+        Ok(GetMaxTotalLiquidityResponse {
+            max_total_liquidity: self.max_total_liquidity.may_load(deps.storage)?,
+        })
+    }
+
+    /// Params passed at instantiation, `None` for contracts instantiated
+    /// before instantiate params were stored.
+    #[sv::msg(query)]
+    pub(crate) fn instantiate_params(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<InstantiateParamsResponse, ContractError> {
+        Ok(InstantiateParamsResponse {
+            instantiate_params: self.instantiate_params.may_load(deps.storage)?,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn version(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<VersionResponse, ContractError> {
+        let cw2::ContractVersion { contract, version } = cw2::get_contract_version(deps.storage)?;
+
+        Ok(VersionResponse {
+            contract,
+            version,
+            migration_history: migration_history(deps.storage)?,
+        })
+    }
+
+    /// Dump of the contract state, including pool, alloyed asset, limiters, asset groups, denom aliases
+    /// and config, for off-chain backup and migration rehearsal.
+    /// Asset groups and denom aliases are paginated, export continues from the `next` cursor of the
+    /// previous page until it is `None`.
+    #[sv::msg(query)]
+    pub(crate) fn export_state(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+        start_after: Option<StateDumpCursor>,
+        limit: Option<u32>,
+    ) -> Result<FullStateDump, ContractError> {
+        self.export_state_dump(deps, env.block.time, start_after, limit)
+    }
+
+    /// Stable code of every error the contract can return, see `ContractError::code`.
+    #[sv::msg(query)]
+    pub(crate) fn error_catalog(
+        &self,
+        QueryCtx { deps: _, env: _ }: QueryCtx,
+    ) -> Result<ErrorCatalogResponse, ContractError> {
+        Ok(ErrorCatalogResponse {
+            errors: ERROR_CATALOG
+                .iter()
+                .map(|(code, name)| ErrorCode {
+                    code: *code,
+                    name: name.to_string(),
+                })
+                .collect(),
+        })
+    }
+
+    /// Self-describing info of this deployment, to avoid multiple external lookups.
+    /// Code id is queried from the chain, so it reflects the latest migration.
+    #[sv::msg(query)]
+    pub(crate) fn contract_info(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+    ) -> Result<ContractInfoResponse, ContractError> {
+        let code_id = deps
+            .querier
+            .query_wasm_contract_info(env.contract.address.as_str())?
+            .code_id;
+        let cw2::ContractVersion { contract, version } = cw2::get_contract_version(deps.storage)?;
+
+        Ok(ContractInfoResponse {
+            code_id,
+            pool_id: self.pool_id.may_load(deps.storage)?,
+            contract_address: env.contract.address,
+            contract,
+            version,
+        })
+    }
+
+    /// Swap fees retained in the pool and not yet withdrawn, per denom.
+    #[sv::msg(query)]
+    pub(crate) fn get_accrued_fees(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetAccruedFeesResponse, ContractError> {
+        let accrued_fees = self
+            .accrued_fees
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| Coin::new(amount.u128(), denom)))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(GetAccruedFeesResponse { accrued_fees })
+    }
+
+    /// Amount of `owner`'s alloyed asset `spender` is allowed to burn.
+    #[sv::msg(query)]
+    pub(crate) fn alloyed_allowance(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        owner: String,
+        spender: String,
+    ) -> Result<AlloyedAllowanceResponse, ContractError> {
+        let owner = deps.api.addr_validate(&owner)?;
+        let spender = deps.api.addr_validate(&spender)?;
+        let alloyed_denom = self.alloyed_asset.get_alloyed_denom(deps.storage)?;
+
+        Ok(AlloyedAllowanceResponse {
+            allowance: self.alloyed_allowances.get(
+                deps.storage,
+                &alloyed_denom,
+                &owner,
+                &spender,
+            )?,
+        })
+    }
+
+    /// Whether `sender` is allowed to swap and join pool under the sender access mode.
+    #[sv::msg(query)]
+    pub(crate) fn is_sender_allowed(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        sender: String,
+    ) -> Result<IsSenderAllowedResponse, ContractError> {
+        let sender = deps.api.addr_validate(&sender)?;
+
+        Ok(IsSenderAllowedResponse {
+            mode: self
+                .sender_access_mode
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+            is_allowed: self.ensure_sender_allowed(deps.storage, &sender).is_ok(),
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_max_asset_groups(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetMaxAssetGroupsResponse, ContractError> {
+        Ok(GetMaxAssetGroupsResponse {
+            max_asset_groups: self.load_max_asset_groups(deps.storage)?,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_min_active_assets(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetMinActiveAssetsResponse, ContractError> {
+        Ok(GetMinActiveAssetsResponse {
+            min_active_assets: self
+                .min_active_assets
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_enable_rounding_audit(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetEnableRoundingAuditResponse, ContractError> {
+        Ok(GetEnableRoundingAuditResponse {
+            enable_rounding_audit: self
+                .enable_rounding_audit
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Most recent rounding residuals of token to token swaps, most recent first.
+    /// Only recorded while rounding audit is enabled, and only a bounded number of the latest are kept.
+    #[sv::msg(query)]
+    pub(crate) fn rounding_audit(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        limit: Option<u32>,
+    ) -> Result<RoundingAuditResponse, ContractError> {
+        let residuals = self.rounding_audit.list_recent(deps.storage, limit)?;
+
+        Ok(RoundingAuditResponse { residuals })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_allow_full_drain(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetAllowFullDrainResponse, ContractError> {
+        Ok(GetAllowFullDrainResponse {
+            allow_full_drain: self
+                .allow_full_drain
+                .may_load(deps.storage)?
+                .unwrap_or(true),
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_require_limiter_coverage(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetRequireLimiterCoverageResponse, ContractError> {
+        Ok(GetRequireLimiterCoverageResponse {
+            require_limiter_coverage: self
+                .require_limiter_coverage
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_amount_tolerance(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetAmountToleranceResponse, ContractError> {
+        Ok(GetAmountToleranceResponse {
+            amount_tolerance: self
+                .amount_tolerance
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_min_locked_shares(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetMinLockedSharesResponse, ContractError> {
+        Ok(GetMinLockedSharesResponse {
+            min_locked_shares: self
+                .min_locked_shares
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+            locked_shares: self
+                .locked_shares
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_dust_threshold(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetDustThresholdResponse, ContractError> {
+        Ok(GetDustThresholdResponse {
+            dust_threshold: self
+                .dust_threshold
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_mint_rate_limit(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+    ) -> Result<GetMintRateLimitResponse, ContractError> {
+        let mint_rate_limit = self.mint_rate_limit.may_load(deps.storage)?;
+        let minted = match &mint_rate_limit {
+            Some(MintRateLimit { window, .. }) => {
+                self.mint_volumes
+                    .windowed(deps.storage, *window, env.block.time)?
+            }
+            None => Uint128::zero(),
+        };
+
+        Ok(GetMintRateLimitResponse {
+            mint_rate_limit,
+            minted,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_large_swap_cooldown(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetLargeSwapCooldownResponse, ContractError> {
+        Ok(GetLargeSwapCooldownResponse {
+            large_swap_cooldown: self.large_swap_cooldown.may_load(deps.storage)?,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn list_asset_groups(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<ListAssetGroupsResponse, ContractError> {
+        Ok(ListAssetGroupsResponse {
+            asset_groups: self.asset_groups.list(deps.storage)?.into_iter().collect(),
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn list_denom_aliases(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<ListDenomAliasesResponse, ContractError> {
+        Ok(ListDenomAliasesResponse {
+            denom_aliases: self
+                .denom_aliases
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<_>>()?,
+        })
+    }
+
+    /// List admin, moderator and sudo actions in the order they were executed.
+    /// Only the most recent actions are kept, see [`crate::governance_log::MAX_GOVERNANCE_LOG_SIZE`].
+    #[sv::msg(query)]
+    pub(crate) fn governance_log(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<GovernanceLogResponse, ContractError> {
+        Ok(GovernanceLogResponse {
+            actions: self.governance_log.list(deps.storage, start_after, limit)?,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_pool_snapshot_interval(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetPoolSnapshotIntervalResponse, ContractError> {
+        Ok(GetPoolSnapshotIntervalResponse {
+            interval: self.pool_snapshots.get_interval(deps.storage)?,
+        })
+    }
+
+    /// Nearest pool snapshot taken at or before `height`.
+    #[sv::msg(query)]
+    pub(crate) fn snapshot_at(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        height: u64,
+    ) -> Result<SnapshotAtResponse, ContractError> {
+        Ok(SnapshotAtResponse {
+            snapshot: self.pool_snapshots.at(deps.storage, height)?,
+        })
+    }
+
+    /// Whether the pool is active, a pause past its reactivation time counts as lifted.
+    #[sv::msg(query)]
+    pub(crate) fn is_active(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+    ) -> Result<IsActiveResponse, ContractError> {
+        Ok(IsActiveResponse {
+            is_active: self.is_active_at(deps.storage, env.block.time)?,
+        })
+    }
+
+    /// Scope of the current pause, `None` if the pool is active.
+    #[sv::msg(query)]
+    pub(crate) fn get_pause_scope(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+    ) -> Result<GetPauseScopeResponse, ContractError> {
+        if self.is_active_at(deps.storage, env.block.time)? {
+            return Ok(GetPauseScopeResponse { pause_scope: None });
+        }
+
+        Ok(GetPauseScopeResponse {
+            pause_scope: self.pause_scope.may_load(deps.storage)?,
+        })
+    }
+
+    /// Time at which the current pause lifts by itself, `None` if the pool is active
+    /// or the pause has no reactivation time.
+    #[sv::msg(query)]
+    pub(crate) fn get_reactivate_at(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+    ) -> Result<GetReactivateAtResponse, ContractError> {
+        if self.is_active_at(deps.storage, env.block.time)? {
+            return Ok(GetReactivateAtResponse {
+                reactivate_at: None,
+            });
+        }
+
+        Ok(GetReactivateAtResponse {
+            reactivate_at: self.reactivate_at.may_load(deps.storage)?,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_total_shares(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetTotalSharesResponse, ContractError> {
+        let total_shares = self.alloyed_asset.get_total_supply(deps)?;
+        Ok(GetTotalSharesResponse { total_shares })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_total_pool_liquidity(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetTotalPoolLiquidityResponse, ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+
+        Ok(GetTotalPoolLiquidityResponse {
+            total_pool_liquidity: pool.pool_assets.iter().map(Asset::to_coin).collect(),
+        })
+    }
+
+    /// Pool balances keyed by denom, decoupled from the internal layout of [`TransmuterPool`].
+    /// If `human_readable` is set, balances are also returned in display units
+    /// for denoms with display decimals set.
+    #[sv::msg(query)]
+    pub(crate) fn pool_balances(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        human_readable: Option<bool>,
+    ) -> Result<PoolBalancesResponse, ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+
+        let balances: BTreeMap<String, Uint128> = pool
+            .pool_assets
+            .iter()
+            .map(|asset| (asset.denom().to_string(), asset.amount()))
+            .collect();
+
+        let human_readable_balances = if human_readable.unwrap_or_default() {
+            let mut human_readable_balances = BTreeMap::new();
+            for (denom, amount) in balances.iter() {
+                if let Some(amount) = self.human_readable_amount(deps.storage, denom, *amount)? {
+                    human_readable_balances.insert(denom.clone(), amount);
+                }
+            }
+            Some(human_readable_balances)
+        } else {
+            None
+        };
+
+        Ok(PoolBalancesResponse {
+            balances,
+            human_readable_balances,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn list_display_decimals(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<ListDisplayDecimalsResponse, ContractError> {
+        Ok(ListDisplayDecimalsResponse {
+            display_decimals: self
+                .display_decimals
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<_>>()?,
+        })
+    }
+
+    /// `amount` of `denom` in its display unit, or `None` if its display decimals are not set.
+    fn human_readable_amount(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+        amount: Uint128,
+    ) -> Result<Option<Decimal>, ContractError> {
+        let Some(decimals) = self.display_decimals.may_load(storage, denom)? else {
+            return Ok(None);
+        };
+
+        let unit = Uint128::new(10).checked_pow(decimals)?;
+
+        Ok(Some(Decimal::checked_from_ratio(amount, unit)?))
+    }
+
+    /// Compare tracked pool asset balances against the contract's actual bank balances.
+    /// Any mismatch signifies accounting drift between the pool and the bank module.
+    #[sv::msg(query)]
+    pub(crate) fn balance_reconciliation(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+    ) -> Result<BalanceReconciliationResponse, ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+
+        let balances = pool
+            .pool_assets
+            .iter()
+            .map(|asset| {
+                let actual = deps
+                    .querier
+                    .query_balance(&env.contract.address, asset.denom())?
+                    .amount;
+
+                Ok(BalanceReconciliation {
+                    denom: asset.denom().to_string(),
+                    tracked: asset.amount(),
+                    actual,
+                    is_mismatched: asset.amount() != actual,
+                })
+            })
+            .collect::<Result<Vec<_>, ContractError>>()?;
+
+        Ok(BalanceReconciliationResponse { balances })
+    }
+
+    /// Decompose the backing of alloyed asset by pool denom.
+    /// Each denom's backing is its pool amount denominated in alloyed asset amount,
+    /// and its share is the fraction of total backing, which is equivalent to its weight.
+    /// Shares are zero if the pool is empty.
+    #[sv::msg(query)]
+    pub(crate) fn backing(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<BackingResponse, ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+
+        self.backing_of(deps.storage, &pool)
+    }
+
+    /// Backing of alloyed asset, as in [`Self::backing`], if `denom` were marked as corrupted
+    /// and fully drained from the pool. Its contribution is removed and the remaining
+    /// denoms' shares are renormalized over what is left, without changing any state.
+    #[sv::msg(query)]
+    pub(crate) fn backing_after_corruption(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        denom: String,
+    ) -> Result<BackingResponse, ContractError> {
+        let mut pool = self.pool.load(deps.storage)?;
+
+        // ensure that the denom is a pool asset
+        pool.get_pool_asset_by_denom(&denom)?;
+        pool.pool_assets.retain(|asset| asset.denom() != denom);
+
+        self.backing_of(deps.storage, &pool)
+    }
+
+    fn backing_of(
+        &self,
+        storage: &dyn Storage,
+        pool: &TransmuterPool,
+    ) -> Result<BackingResponse, ContractError> {
+        let alloyed_normalization_factor = self.alloyed_asset.get_normalization_factor(storage)?;
+
+        let weights: BTreeMap<String, Decimal> = pool
+            .weights()?
+            .map(|weights| weights.into_iter().collect())
+            .unwrap_or_default();
+
+        let mut backing = pool
+            .pool_assets
+            .iter()
+            .map(|asset| {
+                Ok(DenomBacking {
+                    denom: asset.denom().to_string(),
+                    amount: convert_amount(
+                        asset.amount(),
+                        asset.normalization_factor(),
+                        alloyed_normalization_factor,
+                        &Rounding::Down,
+                    )?,
+                    share: weights.get(asset.denom()).copied().unwrap_or_default(),
+                })
+            })
+            .collect::<Result<Vec<_>, ContractError>>()?;
+        backing.sort_by(|a, b| a.denom.cmp(&b.denom));
+
+        let total_backing = backing
+            .iter()
+            .try_fold(Uint128::zero(), |acc, b| acc.checked_add(b.amount))?;
+
+        Ok(BackingResponse {
+            backing,
+            total_backing,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn spot_price(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        base_asset_denom: String,
+        quote_asset_denom: String,
+    ) -> Result<SpotPriceResponse, ContractError> {
+        // ensure that it's not the same denom
+        ensure!(
+            quote_asset_denom != base_asset_denom,
+            ContractError::SpotPriceQueryFailed {
+                reason: "quote_asset_denom and base_asset_denom cannot be the same".to_string()
+            }
+        );
+
+        // ensure that qoute asset denom are in swappable assets
+        let pool = self.pool.load(deps.storage)?;
+        let alloyed_denom = self.alloyed_asset.may_get_alloyed_denom(deps.storage)?;
+        let alloyed_normalization_factor =
+            self.alloyed_asset.get_normalization_factor(deps.storage)?;
+        let swappable_asset_norm_factors = pool
+            .pool_assets
+            .iter()
+            .map(|c| (c.denom().to_string(), c.normalization_factor()))
+            .chain(alloyed_denom.map(|denom| (denom, alloyed_normalization_factor)))
+            .collect::<BTreeMap<String, Uint128>>();
+
+        let base_asset_norm_factor = swappable_asset_norm_factors
+            .get(&base_asset_denom)
+            .cloned()
+            .ok_or_else(|| ContractError::SpotPriceQueryFailed {
+                reason: format!(
+                    "base_asset_denom is not in swappable assets: must be one of {:?} but got {}",
+                    swappable_asset_norm_factors.keys(),
+                    base_asset_denom
+                ),
+            })?;
+
+        let quote_asset_norm_factor = swappable_asset_norm_factors
+            .get(&quote_asset_denom)
+            .cloned()
+            .ok_or_else(|| ContractError::SpotPriceQueryFailed {
+                reason: format!(
+                    "quote_asset_denom is not in swappable assets: must be one of {:?} but got {}",
+                    swappable_asset_norm_factors.keys(),
+                    quote_asset_denom
+                ),
+            })?;
+
+        Ok(SpotPriceResponse {
+            spot_price: math::price(base_asset_norm_factor, quote_asset_norm_factor)?,
+        })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn calc_out_amt_given_in(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        token_in: Coin,
+        token_out_denom: String,
+        swap_fee: Decimal,
+    ) -> Result<CalcOutAmtGivenInResponse, ContractError> {
+        self.ensure_valid_swap_fee(deps.storage, swap_fee)?;
+        let (_pool, token_out, _fee_charge) =
+            self.out_amt_given_in(deps, token_in, &token_out_denom)?;
+
+        Ok(CalcOutAmtGivenInResponse { token_out })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn calc_in_amt_given_out(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        token_out: Coin,
+        token_in_denom: String,
+        swap_fee: Decimal,
+    ) -> Result<CalcInAmtGivenOutResponse, ContractError> {
+        self.ensure_valid_swap_fee(deps.storage, swap_fee)?;
+        let (_pool, token_in, _fee_charge) =
+            self.in_amt_given_out(deps, token_out, token_in_denom)?;
+
+        Ok(CalcInAmtGivenOutResponse { token_in })
+    }
+
+    /// Preview swapping exact `token_in` for `token_out_denom` via sudo, returning the fee that
+    /// would be charged, after dynamic fee adjustment, and the net token out sent to the user.
+    /// The fee is in token out when charged on token out, see [`FeeSide`].
+    ///
+    /// If `with_corrupted` is set, the swap is previewed as if those denoms were marked
+    /// as corrupted, so a swap increasing any of them fails as it would after the corruption.
+    #[sv::msg(query)]
+    pub(crate) fn preview_swap(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        token_in: Coin,
+        token_out_denom: String,
+        with_corrupted: Option<Vec<String>>,
+    ) -> Result<PreviewSwapResponse, ContractError> {
+        let swap_variant = self.swap_variant(&token_in.denom, &token_out_denom, deps)?;
+
+        let mut pool = self.pool.load(deps.storage)?;
+        pool.mark_corrupted_assets(&with_corrupted.unwrap_or_default())?;
+
+        let (_pool, token_out, fee_charge) =
+            self.out_amt_given_in_on_pool(deps, pool, token_in.clone(), &token_out_denom)?;
+
+        let fee_denom = match (swap_variant, self.fee.get_fee_side(deps.storage)?) {
+            (SwapVariant::TokenToToken, FeeSide::Out) => token_out_denom,
+            _ => token_in.denom,
+        };
+        let fee_amount = fee_charge
+            .collected_amount
+            .checked_add(fee_charge.retained_amount)?;
+
+        Ok(PreviewSwapResponse {
+            fee: Coin::new(fee_amount.u128(), fee_denom),
+            token_out,
+        })
+    }
+
+    /// Amount of `token_in_denom` to swap for `token_out_denom` via sudo to bring the weight of
+    /// `token_out_denom` down to `target_weight`, or as close as possible before a limiter binds.
+    /// The weight lands at or just above the target, due to rounding.
+    #[sv::msg(query)]
+    pub(crate) fn input_for_target_weight(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+        token_in_denom: String,
+        token_out_denom: String,
+        target_weight: Decimal,
+    ) -> Result<InputForTargetWeightResponse, ContractError> {
+        let amount = self.max_input_within_target_weight(
+            deps,
+            env.block.time,
+            &token_in_denom,
+            &token_out_denom,
+            target_weight,
+        )?;
+
+        Ok(InputForTargetWeightResponse {
+            token_in: Coin::new(amount.u128(), token_in_denom),
+        })
+    }
+
+    /// Quote multiple swaps at once, results are in the same order as requests.
+    /// Each result also flags whether the swap would trip a limiter.
+    #[sv::msg(query)]
+    pub(crate) fn batch_calc_out_amt_given_in(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+        requests: Vec<CalcOutRequest>,
+    ) -> Result<BatchCalcOutAmtGivenInResponse, ContractError> {
+        ensure!(
+            requests.len() <= MAX_BATCH_QUOTES,
+            ContractError::BatchTooLarge {
+                max: MAX_BATCH_QUOTES,
+                actual: requests.len(),
+            }
+        );
+
+        let results = requests
+            .into_iter()
+            .map(|request| {
+                self.ensure_valid_swap_fee(deps.storage, request.swap_fee)?;
+                let (pool, token_out, _fee_charge) =
+                    self.out_amt_given_in(deps, request.token_in, &request.token_out_denom)?;
+                let trips_limiter = !self.passes_limiters(deps.storage, &pool, env.block.time)?;
+
+                Ok(CalcOutResult {
+                    token_out,
+                    trips_limiter,
+                })
+            })
+            .collect::<Result<Vec<_>, ContractError>>()?;
+
+        Ok(BatchCalcOutAmtGivenInResponse { results })
+    }
+
+    /// Internal routes for swapping `token_in` into `token_out_denom`, both being pool asset denoms:
+    /// directly, or through the alloyed asset (token in -> alloyed asset -> token out).
+    /// Swap fee is charged on each hop and each hop is quoted against the current pool state.
+    #[sv::msg(query)]
+    pub(crate) fn swap_routes(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        token_in: Coin,
+        token_out_denom: String,
+    ) -> Result<SwapRoutesResponse, ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+        for denom in [&token_in.denom, &token_out_denom] {
+            ensure!(
+                pool.has_denom(denom),
+                ContractError::InvalidPoolAssetDenom {
+                    denom: denom.to_string()
+                }
+            );
+        }
+
+        let (_pool, direct_token_out, _fee_charge) =
+            self.out_amt_given_in(deps, token_in.clone(), &token_out_denom)?;
+
+        let mut routes = vec![SwapRoute {
+            path: vec![token_in.denom.clone(), token_out_denom.clone()],
+            token_out: direct_token_out,
+        }];
+
+        // route through the alloyed asset only exists if the alloyed asset is enabled
+        if let Some(alloyed_denom) = self.alloyed_asset.may_get_alloyed_denom(deps.storage)? {
+            let (_pool, alloyed_asset_out, _fee_charge) =
+                self.out_amt_given_in(deps, token_in.clone(), &alloyed_denom)?;
+            let (_pool, via_alloyed_token_out, _fee_charge) =
+                self.out_amt_given_in(deps, alloyed_asset_out, &token_out_denom)?;
+
+            routes.push(SwapRoute {
+                path: vec![token_in.denom, alloyed_denom, token_out_denom],
+                token_out: via_alloyed_token_out,
+            });
+        }
+
+        Ok(SwapRoutesResponse { routes })
+    }
+
+    /// Pool asset denoms that can currently be swapped via sudo for exact `token_out`,
+    /// i.e. the pool has enough of `token_out` and the swap passes the limiters.
+    /// Denoms that can't be swapped in at all, e.g. corrupted ones, are excluded.
+    #[sv::msg(query)]
+    pub(crate) fn feasible_inputs(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+        token_out: Coin,
+    ) -> Result<FeasibleInputsResponse, ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+        ensure!(
+            pool.has_denom(&token_out.denom),
+            ContractError::InvalidPoolAssetDenom {
+                denom: token_out.denom
+            }
+        );
+
+        let mut denoms = vec![];
+        for asset in pool.pool_assets.iter() {
+            let denom = asset.denom();
+            if denom == token_out.denom {
+                continue;
+            }
+
+            let feasible = match self.in_amt_given_out(deps, token_out.clone(), denom.to_string()) {
+                Ok((pool, _, _)) => self.passes_limiters(deps.storage, &pool, env.block.time)?,
+                Err(_) => false,
+            };
+
+            if feasible {
+                denoms.push(denom.to_string());
+            }
+        }
+
+        Ok(FeasibleInputsResponse { denoms })
+    }
+
+    /// Cumulative value retained in the pool from rounding when burning alloyed asset,
+    /// e.g. on exit pool, in alloyed asset amount.
+    #[sv::msg(query)]
+    pub(crate) fn retained_dust(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<RetainedDustResponse, ContractError> {
+        Ok(RetainedDustResponse {
+            retained_dust: self
+                .retained_dust
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Operator-defined target weights, see [`Transmuter::set_target_weights`].
+    #[sv::msg(query)]
+    pub(crate) fn target_weights(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<TargetWeightsResponse, ContractError> {
+        Ok(TargetWeightsResponse {
+            target_weights: self
+                .target_weights
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<_>>()?,
+        })
+    }
+
+    /// Target weights set by admin, or the even split across pool assets if none is set.
+    pub(crate) fn effective_target_weights(
+        &self,
+        storage: &dyn Storage,
+        pool: &TransmuterPool,
+    ) -> StdResult<BTreeMap<String, Decimal>> {
+        let target_weights = self
+            .target_weights
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<BTreeMap<String, Decimal>>>()?;
+        if !target_weights.is_empty() {
+            return Ok(target_weights);
+        }
+
+        // pool can be left without assets once all corrupted assets are drained and removed
+        if pool.pool_assets.is_empty() {
+            return Ok(BTreeMap::new());
+        }
+
+        let even_split = Decimal::from_ratio(1u128, pool.pool_assets.len() as u128);
+        Ok(pool
+            .pool_assets
+            .iter()
+            .map(|asset| (asset.denom().to_string(), even_split))
+            .collect())
+    }
+
+    /// Largest absolute deviation of `denoms` in `pool` from their target weights,
+    /// denoms that are not pool assets are skipped.
+    pub(crate) fn max_target_deviation(
+        &self,
+        storage: &dyn Storage,
+        pool: &TransmuterPool,
+        denoms: &[&str],
+    ) -> Result<Decimal, ContractError> {
+        let Some(weights) = pool.weights()? else {
+            return Ok(Decimal::zero());
+        };
+        let target_weights = self.effective_target_weights(storage, pool)?;
+
+        Ok(weights
+            .into_iter()
+            .filter(|(denom, _)| denoms.contains(&denom.as_str()))
+            .map(|(denom, weight)| {
+                weight.abs_diff(target_weights.get(&denom).copied().unwrap_or_default())
+            })
+            .max()
+            .unwrap_or_default())
+    }
+
+    /// Signed difference between each denom's current weight and its target weight,
+    /// positive when the denom is over its target. Without targets set, the implicit target
+    /// is the even split across pool assets. Denoms without a target have a target of zero.
+    #[sv::msg(query)]
+    pub(crate) fn deviation_from_targets(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<DeviationFromTargetsResponse, ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+        let current_weights: BTreeMap<String, Decimal> =
+            pool.weights()?.unwrap_or_default().into_iter().collect();
+        let target_weights = self.effective_target_weights(deps.storage, &pool)?;
+
+        // weights are within [0, 1], so their atomics always fit in a signed decimal
+        let signed = |weight: Decimal| SignedDecimal::raw(weight.atomics().u128() as i128);
+
+        let deviations = pool
+            .pool_assets
+            .iter()
+            .map(|asset| {
+                let denom = asset.denom();
+                let current = current_weights.get(denom).copied().unwrap_or_default();
+                let target = target_weights.get(denom).copied().unwrap_or_default();
+                Ok((
+                    denom.to_string(),
+                    signed(current).checked_sub(signed(target))?,
+                ))
+            })
+            .collect::<Result<_, ContractError>>()?;
+
+        Ok(DeviationFromTargetsResponse { deviations })
+    }
+
+    /// Herfindahl-Hirschman index of the pool, the sum of squared weights of pool assets.
+    /// It ranges from 1/n when evenly split across n assets to 1 when all value is in a single asset.
+    /// `None` if the pool is empty.
+    #[sv::msg(query)]
+    pub(crate) fn concentration_index(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<ConcentrationIndexResponse, ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+
+        let concentration_index = pool
+            .weights()?
+            .map(|weights| {
+                weights
+                    .into_iter()
+                    .try_fold(Decimal::zero(), |index, (_, weight)| {
+                        index.checked_add(weight.checked_mul(weight)?)
+                    })
+            })
+            .transpose()?;
+
+        Ok(ConcentrationIndexResponse {
+            concentration_index,
+        })
+    }
+
+    /// Largest exit that withdraws an equal value of every pool asset, in shares, along with its basket.
+    /// Each denom's portion is capped by its balance, so the scarcest denom sets the cap,
+    /// which is further capped by the total shares.
+    #[sv::msg(query)]
+    pub(crate) fn max_proportional_exit(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<MaxProportionalExitResponse, ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+        let alloyed_normalization_factor =
+            self.alloyed_asset.get_normalization_factor(deps.storage)?;
+        let total_shares = self.alloyed_asset.get_total_supply(deps)?;
+
+        let asset_count = Uint128::from(pool.pool_assets.len() as u128);
+        if asset_count.is_zero() {
+            return Ok(MaxProportionalExitResponse {
+                share_amount: Uint128::zero(),
+                tokens_out: vec![],
+            });
+        }
+
+        // value of each denom's portion of the basket, in shares
+        let portion = pool.pool_assets.iter().try_fold(
+            total_shares.checked_div(asset_count)?,
+            |portion, asset| -> Result<_, ContractError> {
+                let value = convert_amount(
+                    asset.amount(),
+                    asset.normalization_factor(),
+                    alloyed_normalization_factor,
+                    &Rounding::Down,
+                )?;
+                Ok(portion.min(value))
+            },
+        )?;
+
+        let tokens_out = pool
+            .pool_assets
+            .iter()
+            .map(|asset| {
+                let amount = convert_amount(
+                    portion,
+                    alloyed_normalization_factor,
+                    asset.normalization_factor(),
+                    &Rounding::Down,
+                )?;
+                Ok(Coin::new(amount.u128(), asset.denom()))
+            })
+            .collect::<Result<_, ContractError>>()?;
+
+        Ok(MaxProportionalExitResponse {
+            share_amount: portion.checked_mul(asset_count)?,
+            tokens_out,
+        })
+    }
+
+    /// Volume of `denom` swapped in or out of the pool within the last `window` nanoseconds,
+    /// accounted in hourly buckets, see [`crate::volume::MAX_VOLUME_BUCKETS`] for the longest window.
+    #[sv::msg(query)]
+    pub(crate) fn windowed_swap_volume(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+        denom: String,
+        window: Uint64,
+    ) -> Result<WindowedSwapVolumeResponse, ContractError> {
+        let volume = self
+            .swap_volumes
+            .windowed(deps.storage, &denom, window, env.block.time)?;
+
+        Ok(WindowedSwapVolumeResponse { volume })
+    }
+
+    /// Alloyed asset minted and burned within the last `window` nanoseconds, and the net flow
+    /// of minted minus burned, surfacing rapid expansion or contraction of the supply.
+    /// Accounted in the same hourly buckets as [`Transmuter::windowed_swap_volume`].
+    /// There is no flow if the alloyed asset is disabled, same as its total supply.
+    #[sv::msg(query)]
+    pub(crate) fn supply_flow(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+        window: Uint64,
+    ) -> Result<SupplyFlowResponse, ContractError> {
+        let Some(alloyed_denom) = self.alloyed_asset.may_get_alloyed_denom(deps.storage)? else {
+            return Ok(SupplyFlowResponse {
+                minted: Uint128::zero(),
+                burned: Uint128::zero(),
+                net_flow: Int256::zero(),
+            });
+        };
+        let minted = self.alloyed_minted_volumes.windowed(
+            deps.storage,
+            &alloyed_denom,
+            window,
+            env.block.time,
+        )?;
+        let burned = self.alloyed_burned_volumes.windowed(
+            deps.storage,
+            &alloyed_denom,
+            window,
+            env.block.time,
+        )?;
+
+        Ok(SupplyFlowResponse {
+            minted,
+            burned,
+            net_flow: Int256::from(minted).checked_sub(Int256::from(burned))?,
+        })
+    }
+
+    /// Alloyed assets registered in addition to the main alloyed asset, with their backing.
+    #[sv::msg(query)]
+    pub(crate) fn list_registered_alloyed_assets(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<ListRegisteredAlloyedAssetsResponse, ContractError> {
+        Ok(ListRegisteredAlloyedAssetsResponse {
+            alloyed_assets: self.alloyed_registry.list(deps.storage)?,
+        })
+    }
+
+    /// Approximate number of distinct alloyed asset holders, tracked from mints and full burns
+    /// through the contract. Transfers via the bank module are not accounted for.
+    #[sv::msg(query)]
+    pub(crate) fn holder_count(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<HolderCountResponse, ContractError> {
+        Ok(HolderCountResponse {
+            holder_count: self.alloyed_holders.count(deps.storage)?,
+        })
+    }
+
+    /// Maximum amount of alloyed asset shares that can be redeemed purely into `denom`,
+    /// given its pool balance and limiters.
+    #[sv::msg(query)]
+    pub(crate) fn max_redeem_into(
+        &self,
+        QueryCtx { deps, env }: QueryCtx,
+        denom: String,
+    ) -> Result<MaxRedeemIntoResponse, ContractError> {
+        let max_shares = self.max_redeemable_into(deps, env.block.time, &denom)?;
+
+        Ok(MaxRedeemIntoResponse { max_shares })
+    }
+
+    #[sv::msg(query)]
+    pub(crate) fn get_corrupted_denoms(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetCorrruptedDenomsResponse, ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+        let corrupted_denoms = pool
+            .corrupted_assets()
+            .into_iter()
+            .map(|a| a.denom().to_string())
+            .collect();
+
+        Ok(GetCorrruptedDenomsResponse { corrupted_denoms })
+    }
+
+    // --- admin ---
+
+    #[sv::msg(exec)]
+    pub fn transfer_admin(
+        &self,
+        ExecCtx {
+            mut deps,
+            env,
+            info,
+        }: ExecCtx,
+        candidate: String,
+    ) -> Result<Response, ContractError> {
+        let candidate_addr = deps.api.addr_validate(&candidate)?;
+        self.role
+            .admin
+            .transfer(deps.branch(), info.sender.clone(), candidate_addr)?;
+
+        self.governance_log.record(
+            deps.storage,
+            "transfer_admin",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("method", "transfer_admin")
+            .add_attribute("candidate", candidate))
+    }
+
+    #[sv::msg(exec)]
+    pub fn cancel_admin_transfer(
+        &self,
+        ExecCtx {
+            mut deps,
+            env,
+            info,
+        }: ExecCtx,
+    ) -> Result<Response, ContractError> {
+        self.role
+            .admin
+            .cancel_transfer(deps.branch(), info.sender.clone())?;
+
+        self.governance_log.record(
+            deps.storage,
+            "cancel_admin_transfer",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        Ok(Response::new().add_attribute("method", "cancel_admin_transfer"))
+    }
+
+    #[sv::msg(exec)]
+    pub fn reject_admin_transfer(
+        &self,
+        ExecCtx {
+            mut deps,
+            env,
+            info,
+        }: ExecCtx,
+    ) -> Result<Response, ContractError> {
+        self.role
+            .admin
+            .reject_transfer(deps.branch(), info.sender.clone())?;
+
+        self.governance_log.record(
+            deps.storage,
+            "reject_admin_transfer",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        Ok(Response::new().add_attribute("method", "reject_admin_transfer"))
+    }
+
+    #[sv::msg(exec)]
+    pub fn claim_admin(
+        &self,
+        ExecCtx {
+            mut deps,
+            env,
+            info,
+        }: ExecCtx,
+    ) -> Result<Response, ContractError> {
+        let sender_string = info.sender.to_string();
+        self.role.admin.claim(deps.branch(), info.sender.clone())?;
+
+        self.governance_log.record(
+            deps.storage,
+            "claim_admin",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("method", "claim_admin")
+            .add_attribute("new_admin", sender_string))
+    }
+
+    #[sv::msg(query)]
+    fn get_admin(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetAdminResponse, ContractError> {
+        Ok(GetAdminResponse {
+            admin: self.role.admin.current(deps)?,
+        })
+    }
+
+    #[sv::msg(query)]
+    fn get_admin_candidate(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetAdminCandidateResponse, ContractError> {
+        Ok(GetAdminCandidateResponse {
+            admin_candidate: self.role.admin.candidate(deps)?,
+        })
+    }
+
+    // -- moderator --
+    #[sv::msg(exec)]
+    pub fn assign_moderator(
+        &self,
+        ExecCtx {
+            mut deps,
+            env,
+            info,
+        }: ExecCtx,
+        address: String,
+    ) -> Result<Response, ContractError> {
+        let moderator_address = deps.api.addr_validate(&address)?;
+
+        self.role
+            .assign_moderator(info.sender.clone(), deps.branch(), moderator_address)?;
+
+        self.governance_log.record(
+            deps.storage,
+            "assign_moderator",
+            Some(&info.sender),
+            env.block.time,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("method", "assign_moderator")
+            .add_attribute("moderator", address))
+    }
+
+    #[sv::msg(query)]
+    fn get_moderator(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+    ) -> Result<GetModeratorResponse, ContractError> {
+        Ok(GetModeratorResponse {
+            moderator: self.role.moderator.get(deps)?,
+        })
+    }
+
+    /// Check whether the address is authorized to perform actions that require the capability.
+    #[sv::msg(query)]
+    fn can_execute(
+        &self,
+        QueryCtx { deps, env: _ }: QueryCtx,
+        address: String,
+        capability: Capability,
+    ) -> Result<CanExecuteResponse, ContractError> {
+        let address = deps.api.addr_validate(&address)?;
+
+        Ok(CanExecuteResponse {
+            can_execute: self.role.has_capability(deps, &address, &capability)?,
+        })
+    }
+}
+
+/// Validate alloyed asset subdenom against tokenfactory rules before creating the denom,
+/// since invalid subdenom would only fail opaquely when the create denom message is executed.
+/// The subdenom sent to tokenfactory is prefixed, i.e. `alloyed/{alloyed_asset_subdenom}`.
+fn ensure_valid_alloyed_subdenom(alloyed_asset_subdenom: &str) -> Result<(), ContractError> {
+    let invalid = |reason: String| ContractError::InvalidAlloyedSubdenom {
+        subdenom: alloyed_asset_subdenom.to_string(),
+        reason,
+    };
+
+    ensure!(
+        !alloyed_asset_subdenom.is_empty(),
+        invalid("subdenom must not be empty".to_string())
+    );
+
+    let full_subdenom_length = ALLOYED_PREFIX.len() + 1 + alloyed_asset_subdenom.len();
+    ensure!(
+        full_subdenom_length <= MAX_TOKENFACTORY_SUBDENOM_LENGTH,
+        invalid(format!(
+            "subdenom must not exceed {} characters",
+            MAX_TOKENFACTORY_SUBDENOM_LENGTH - ALLOYED_PREFIX.len() - 1
+        ))
+    );
+
+    ensure!(
+        alloyed_asset_subdenom
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | ':')),
+        invalid(
+            "subdenom must only contain alphanumeric characters, '.', '_', '-' or ':'".to_string()
+        )
+    );
+
+    Ok(())
+}
+
+#[cw_serde]
+pub struct ListAssetConfigsResponse {
+    pub asset_configs: Vec<AssetConfig>,
+}
+
+#[cw_serde]
+pub struct PoolDenomsResponse {
+    pub denoms: Vec<String>,
+}
+
+#[cw_serde]
+pub struct ListLimitersResponse {
+    pub limiters: Vec<((String, String), Limiter)>,
+}
+
+#[cw_serde]
+pub struct LimiterTripCountsResponse {
+    /// Trips observed on partial fills by `(denom, label)`, rejected swaps are not counted
+    pub trip_counts: Vec<((String, String), u64)>,
+}
+
+#[cw_serde]
+pub struct RecentLimiterTripsResponse {
+    /// Trips observed on partial fills, rejected swaps are not logged
+    pub trips: Vec<LimiterTripRecord>,
+}
+
+#[cw_serde]
+pub struct GetLimiterStressConfigResponse {
+    pub stress_config: Option<StressConfig>,
+    pub under_stress: bool,
+}
+
+#[cw_serde]
+pub struct GetSharesResponse {
+    pub shares: Uint128,
+}
+
+#[cw_serde]
+pub struct GetShareDenomResponse {
+    pub share_denom: String,
+}
+
+#[cw_serde]
+pub struct GetSwapFeeResponse {
+    pub swap_fee: Decimal,
+}
+
+#[cw_serde]
+pub struct GetFeeConfigResponse {
+    pub swap_fee: Decimal,
+    pub fee_collector: Option<Addr>,
+    pub fee_split: Decimal,
+    pub fee_side: FeeSide,
+    /// `None` if swap fee is flat
+    pub dynamic_fee_params: Option<DynamicFeeParams>,
+}
+
+#[cw_serde]
+pub struct GetMaxSwapValueResponse {
+    pub max_swap_value: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct GetMaxTotalLiquidityResponse {
+    pub max_total_liquidity: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct ListDenomAliasesResponse {
+    pub denom_aliases: BTreeMap<String, String>,
+}
+
+#[cw_serde]
+pub struct InstantiateParams {
+    pub pool_asset_configs: Vec<AssetConfig>,
+    pub alloyed_asset_subdenom: String,
+    pub alloyed_asset_normalization_factor: Uint128,
+    pub admin: Option<String>,
+    pub moderator: String,
+    pub enable_alloyed_asset: Option<bool>,
+}
+
+#[cw_serde]
+pub struct InstantiateParamsResponse {
+    pub instantiate_params: Option<InstantiateParams>,
+}
+
+#[cw_serde]
+pub struct VersionResponse {
+    pub contract: String,
+    pub version: String,
+    /// Migrations completed since migration history started being recorded, oldest first
+    pub migration_history: Vec<MigrationRecord>,
+}
+
+#[cw_serde]
+pub struct ErrorCode {
+    pub code: u32,
+    pub name: String,
+}
+
+#[cw_serde]
+pub struct ErrorCatalogResponse {
+    pub errors: Vec<ErrorCode>,
+}
+
+#[cw_serde]
+pub struct ContractInfoResponse {
+    pub code_id: u64,
+    /// `None` until set by admin
+    pub pool_id: Option<u64>,
+    pub contract_address: Addr,
+    /// cw2 contract name
+    pub contract: String,
+    /// cw2 contract version
+    pub version: String,
+}
+
+#[cw_serde]
+pub struct GetAccruedFeesResponse {
+    pub accrued_fees: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct InputForTargetWeightResponse {
+    pub token_in: Coin,
+}
+
+#[cw_serde]
+pub struct AlloyedAllowanceResponse {
+    pub allowance: Uint128,
+}
+
+#[cw_serde]
+pub struct IsSenderAllowedResponse {
+    pub mode: SenderAccessMode,
+    pub is_allowed: bool,
+}
+
+#[cw_serde]
+pub struct GetMaxAssetGroupsResponse {
+    pub max_asset_groups: u32,
+}
+
+#[cw_serde]
+pub struct GetMinActiveAssetsResponse {
+    pub min_active_assets: u32,
+}
+
+#[cw_serde]
+pub struct GetRequireLimiterCoverageResponse {
+    pub require_limiter_coverage: bool,
+}
+
+#[cw_serde]
+pub struct GetAllowFullDrainResponse {
+    pub allow_full_drain: bool,
+}
+
+#[cw_serde]
+pub struct GetEnableRoundingAuditResponse {
+    pub enable_rounding_audit: bool,
+}
+
+#[cw_serde]
+pub struct RoundingAuditResponse {
+    pub residuals: Vec<RoundingResidual>,
+}
+
+#[cw_serde]
+pub struct GetAmountToleranceResponse {
+    pub amount_tolerance: Uint128,
+}
+
+#[cw_serde]
+pub struct GetMinLockedSharesResponse {
+    pub min_locked_shares: Uint128,
+    /// Shares locked in the contract on the first mint
+    pub locked_shares: Uint128,
+}
+
+#[cw_serde]
+pub struct GetDustThresholdResponse {
+    pub dust_threshold: Uint128,
+}
+
+#[cw_serde]
+pub struct GetMintRateLimitResponse {
+    pub mint_rate_limit: Option<MintRateLimit>,
+    /// Alloyed asset amount minted within the current rolling window
+    pub minted: Uint128,
+}
+
+#[cw_serde]
+pub struct GetLargeSwapCooldownResponse {
+    pub large_swap_cooldown: Option<LargeSwapCooldown>,
+}
+
+#[cw_serde]
+pub struct ListAssetGroupsResponse {
+    pub asset_groups: BTreeMap<String, AssetGroup>,
+}
+
+#[cw_serde]
+pub struct EffectiveSwapFeeResponse {
+    pub swap_fee: Decimal,
+}
+
+#[cw_serde]
+pub struct GetDenomSwapFeesResponse {
+    pub denom_swap_fees: BTreeMap<String, Decimal>,
+}
+
+#[cw_serde]
+pub struct GovernanceLogResponse {
+    pub actions: Vec<GovernanceAction>,
+}
+
+#[cw_serde]
+pub struct GetCorruptionDrainIncentiveResponse {
+    pub corruption_drain_incentive: Decimal,
+}
+
+#[cw_serde]
+pub struct StaticLimiterInfo {
+    pub denom: String,
+    pub label: String,
+    pub upper_limit: Decimal,
+}
+
+#[cw_serde]
+pub struct ChangeLimiterInfo {
+    pub denom: String,
+    pub label: String,
+    pub window_config: WindowConfig,
+    pub boundary_offset: Decimal,
+}
+
+#[cw_serde]
+pub struct LimiterCheckResult {
+    pub denom: String,
+    pub label: String,
+    pub passes: bool,
+}
+
+#[cw_serde]
+pub struct CheckWeightsResponse {
+    pub results: Vec<LimiterCheckResult>,
+}
+
+#[cw_serde]
+pub struct RelativeLimiterInfo {
+    pub denom: String,
+    pub label: String,
+    pub max_deviation: Decimal,
+}
+
+#[cw_serde]
+pub struct NetFlowLimiterInfo {
+    pub denom: String,
+    pub label: String,
+    pub window_config: WindowConfig,
+    pub max_net_flow: Uint128,
+}
+
+#[cw_serde]
+pub struct LimitersByTypeResponse {
+    pub static_limiters: Vec<StaticLimiterInfo>,
+    pub change_limiters: Vec<ChangeLimiterInfo>,
+    pub relative_limiters: Vec<RelativeLimiterInfo>,
+    pub net_flow_limiters: Vec<NetFlowLimiterInfo>,
+}
+
+#[cw_serde]
+pub struct ChangeLimiterWindowResponse {
+    /// Size of the window in nanoseconds
+    pub window_size: Uint64,
+    pub division_count: Uint64,
+    pub boundary_offset: Decimal,
+}
+
+#[cw_serde]
+pub struct GetPoolSnapshotIntervalResponse {
+    pub interval: Option<u64>,
+}
+
+#[cw_serde]
+pub struct SnapshotAtResponse {
+    pub snapshot: Option<PoolSnapshot>,
+}
+
+#[cw_serde]
+pub struct IsActiveResponse {
+    pub is_active: bool,
+}
+
+#[cw_serde]
+pub struct GetPauseScopeResponse {
+    pub pause_scope: Option<PauseScope>,
+}
+
+#[cw_serde]
+pub struct GetReactivateAtResponse {
+    pub reactivate_at: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct GetTotalSharesResponse {
+    pub total_shares: Uint128,
+}
+
+#[cw_serde]
+pub struct GetTotalPoolLiquidityResponse {
+    pub total_pool_liquidity: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct PoolBalancesResponse {
+    pub balances: BTreeMap<String, Uint128>,
+    /// Balances in display units, only for denoms with display decimals set,
+    /// present only if requested
+    pub human_readable_balances: Option<BTreeMap<String, Decimal>>,
+}
+
+#[cw_serde]
+pub struct ListDisplayDecimalsResponse {
+    pub display_decimals: BTreeMap<String, u32>,
+}
+
+#[cw_serde]
+pub struct BalanceReconciliation {
+    pub denom: String,
+    /// balance tracked by the pool
+    pub tracked: Uint128,
+    /// balance held by the contract according to the bank module
+    pub actual: Uint128,
+    pub is_mismatched: bool,
+}
+
+#[cw_serde]
+pub struct BalanceReconciliationResponse {
+    pub balances: Vec<BalanceReconciliation>,
+}
+
+#[cw_serde]
+pub struct DenomBacking {
+    pub denom: String,
+    /// pool amount of the denom, denominated in alloyed asset amount
+    pub amount: Uint128,
+    /// fraction of total backing
+    pub share: Decimal,
+}
+
+#[cw_serde]
+pub struct BackingResponse {
+    pub backing: Vec<DenomBacking>,
+    /// total backing, denominated in alloyed asset amount
+    pub total_backing: Uint128,
+}
+
+#[cw_serde]
+pub struct SpotPriceResponse {
+    pub spot_price: Decimal,
+}
+
+#[cw_serde]
+pub struct PreviewSwapResponse {
+    /// Fee charged, both collected and retained in the pool
+    pub fee: Coin,
+    /// Token out sent to the user, net of fee
+    pub token_out: Coin,
+}
+
+#[cw_serde]
+pub struct CalcOutAmtGivenInResponse {
+    pub token_out: Coin,
+}
+
+#[cw_serde]
+pub struct CalcInAmtGivenOutResponse {
+    pub token_in: Coin,
+}
+
+#[cw_serde]
+pub struct AnyLimiterBindingResponse {
+    pub binding: bool,
+}
+
+/// Coarse cost class of a swap, see [`Transmuter::swap_complexity`]
+#[cw_serde]
+pub enum SwapComplexityClass {
+    Low,
+    Medium,
+    High,
+}
+
+#[cw_serde]
+pub struct SwapComplexityResponse {
+    pub complexity: SwapComplexityClass,
+    /// Number of limiters checked by the swap
+    pub limiter_count: u64,
+    /// Number of asset groups scanned by the swap
+    pub asset_group_count: u64,
+}
+
+#[cw_serde]
+pub struct DenomStatusResponse {
+    pub can_swap_in: bool,
+    pub can_swap_out: bool,
+    /// Reasons blocking swapping in or out, empty if the denom is freely swappable
+    pub reasons: Vec<String>,
+}
+
+#[cw_serde]
+pub struct CalcOutRequest {
+    pub token_in: Coin,
+    pub token_out_denom: String,
+    pub swap_fee: Decimal,
+}
+
+#[cw_serde]
+pub struct RetainedDustResponse {
+    pub retained_dust: Decimal256,
+}
+
+#[cw_serde]
+pub struct HolderCountResponse {
+    pub holder_count: u64,
+}
+
+#[cw_serde]
+pub struct TargetWeightsResponse {
+    pub target_weights: BTreeMap<String, Decimal>,
+}
+
+#[cw_serde]
+pub struct DeviationFromTargetsResponse {
+    pub deviations: BTreeMap<String, SignedDecimal>,
+}
+
+#[cw_serde]
+pub struct ConcentrationIndexResponse {
+    /// `None` if the pool is empty
+    pub concentration_index: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct MaxProportionalExitResponse {
+    /// Upper bound of shares burned by exiting with `tokens_out`
+    pub share_amount: Uint128,
+    pub tokens_out: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct WindowedSwapVolumeResponse {
+    pub volume: Uint128,
+}
+
+#[cw_serde]
+pub struct SupplyFlowResponse {
+    pub minted: Uint128,
+    pub burned: Uint128,
+    /// Minted minus burned, negative when the supply contracts
+    pub net_flow: Int256,
+}
+
+#[cw_serde]
+pub struct ListRegisteredAlloyedAssetsResponse {
+    pub alloyed_assets: Vec<RegisteredAlloyedAsset>,
+}
+
+#[cw_serde]
+pub struct SwapRoute {
+    /// Denoms the swap goes through, starting with token in and ending with token out
+    pub path: Vec<String>,
+    pub token_out: Coin,
+}
+
+#[cw_serde]
+pub struct SwapRoutesResponse {
+    pub routes: Vec<SwapRoute>,
+}
+
+#[cw_serde]
+pub struct FeasibleInputsResponse {
+    /// Input denoms that can fully satisfy the requested token out
+    pub denoms: Vec<String>,
+}
+
+#[cw_serde]
+pub struct CalcOutResult {
+    pub token_out: Coin,
+    pub trips_limiter: bool,
+}
+
+#[cw_serde]
+pub struct BatchCalcOutAmtGivenInResponse {
+    pub results: Vec<CalcOutResult>,
+}
+
+#[cw_serde]
+pub struct MaxRedeemIntoResponse {
+    pub max_shares: Uint128,
+}
+
+#[cw_serde]
+pub struct GetCorrruptedDenomsResponse {
+    pub corrupted_denoms: Vec<String>,
+}
+
+#[cw_serde]
+pub struct GetAdminResponse {
+    pub admin: Addr,
+}
+
+#[cw_serde]
+pub struct GetAdminCandidateResponse {
+    pub admin_candidate: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct GetModeratorResponse {
+    pub moderator: Addr,
+}
+
+#[cw_serde]
+pub struct CanExecuteResponse {
+    pub can_execute: bool,
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::sv::*;
+    use super::*;
+    use crate::limiter::{ChangeLimiter, Division, StaticLimiter};
+    use crate::sudo::SudoMsg;
+    use crate::swap::{
+        alloyed_asset_transfer_event, SwapDirection, SwapExactAmountInResponseData,
+        ALLOYED_ASSET_TRANSFER_EVENT,
+    };
+    use crate::*;
+    // the query entry point takes raw incoming messages, tests dispatch parsed ones
+    use crate::entry_points::dispatch_query as query;
+
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{
+        attr, from_json, to_json_binary, BankMsg, BlockInfo, ContractResult, CosmosMsg, Event,
+        Storage, SubMsgResponse, SubMsgResult, SystemResult, Uint64,
+    };
+    use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgBurn, MsgMint};
+    use rstest::rstest;
+
+    #[test]
+    fn test_invalid_subdenom() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "tbtc"), Coin::new(1, "nbtc")]);
+
+        let admin = "admin";
+        let moderator = "moderator";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("tbtc"),
+                AssetConfig::from_denom_str("nbtc"),
+            ],
+            alloyed_asset_subdenom: "all/btc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            admin: Some(admin.to_string()),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        let err = instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::SubDenomExtraPartsNotAllowed {
+                subdenom: "all/btc".to_string()
+            }
+        )
+    }
+
+    #[rstest]
+    #[case("", Some("subdenom must not be empty"))]
+    #[case(
+        "abcdefghijklmnopqrstuvwxyz01234567890",
+        Some("subdenom must not exceed 36 characters")
+    )]
+    #[case(
+        "us$dc",
+        Some("subdenom must only contain alphanumeric characters, '.', '_', '-' or ':'")
+    )]
+    #[case("abcdefghijklmnopqrstuvwxyz0123456789", None)]
+    #[case("usdc.axl_wh-noble:1", None)]
+    fn test_alloyed_subdenom_validation(
+        #[case] alloyed_asset_subdenom: &str,
+        #[case] expected_err_reason: Option<&str>,
+    ) {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "tbtc"), Coin::new(1, "nbtc")]);
+
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("tbtc"),
+                AssetConfig::from_denom_str("nbtc"),
+            ],
+            alloyed_asset_subdenom: alloyed_asset_subdenom.to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            admin: Some("admin".to_string()),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+
+        let res = instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), init_msg);
+
+        match expected_err_reason {
+            Some(reason) => assert_eq!(
+                res.unwrap_err(),
+                ContractError::InvalidAlloyedSubdenom {
+                    subdenom: alloyed_asset_subdenom.to_string(),
+                    reason: reason.to_string(),
+                }
+            ),
+            None => assert!(res.is_ok()),
+        }
+    }
+
+    #[test]
+    fn test_reply() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        let res = instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+        assert_eq!(res.messages[0].id, ReplyId::CreateAlloyedDenom.id());
+
+        let alloyed_denom = format!("factory/{}/alloyed/usomoion", env.contract.address);
+        let create_denom_reply = |id: u64| Reply {
+            id,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(
+                    MsgCreateDenomResponse {
+                        new_token_denom: alloyed_denom.clone(),
+                    }
+                    .into(),
+                ),
+            }),
+        };
+
+        // unknown reply id
+        let err = reply(deps.as_mut(), env.clone(), create_denom_reply(999)).unwrap_err();
+        assert_eq!(err, ContractError::UnknownReplyId { id: 999 });
+
+        // create alloyed denom reply
+        let res = reply(
+            deps.as_mut(),
+            env,
+            create_denom_reply(ReplyId::CreateAlloyedDenom.id()),
+        )
+        .unwrap();
+        assert_eq!(res.attributes, vec![attr("alloyed_denom", &alloyed_denom)]);
+        assert_eq!(
+            Transmuter::default()
+                .alloyed_asset
+                .get_alloyed_denom(&deps.storage)
+                .unwrap(),
+            alloyed_denom
+        );
+    }
+
+    #[test]
+    fn test_add_new_assets() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "uosmo"),
+                Coin::new(1, "uion"),
+                Coin::new(1, "new_asset1"),
+                Coin::new(1, "new_asset2"),
+            ],
+        );
+
+        let admin = "admin";
+        let moderator = "moderator";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            alloyed_asset_subdenom: "uosmouion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            admin: Some(admin.to_string()),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // join pool
+        let info = mock_info(
+            "someone",
+            &[
+                Coin::new(1000000000, "uosmo"),
+                Coin::new(1000000000, "uion"),
+            ],
+        );
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), join_pool_msg).unwrap();
+
+        // set limiters
+        let change_limiter_params = LimiterParams::ChangeLimiter {
+            window_config: WindowConfig {
+                window_size: Uint64::from(3600u64),
+                division_count: Uint64::from(10u64),
+            },
+            boundary_offset: Decimal::percent(20),
+        };
+
+        let static_limiter_params = LimiterParams::StaticLimiter {
+            upper_limit: Decimal::percent(60),
+        };
+
+        let info = mock_info(admin, &[]);
+        for denom in ["uosmo", "uion"] {
+            let register_limiter_msg = ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: denom.to_string(),
+                label: "change_limiter".to_string(),
+                limiter_params: change_limiter_params.clone(),
+            });
+
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                register_limiter_msg,
+            )
+            .unwrap();
+
+            let register_limiter_msg = ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: denom.to_string(),
+                label: "static_limiter".to_string(),
+                limiter_params: static_limiter_params.clone(),
+            });
+
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                register_limiter_msg,
+            )
+            .unwrap();
+        }
+
+        // join pool a bit more to make limiters dirty
+        let mut env = env.clone();
+        env.block.time = env.block.time.plus_nanos(360);
+
+        let info = mock_info(
+            "someone",
+            &[Coin::new(550, "uosmo"), Coin::new(500, "uion")],
+        );
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), join_pool_msg).unwrap();
+
+        env.block.time = env.block.time.plus_nanos(3000);
+        let info = mock_info(
+            "someone",
+            &[Coin::new(450, "uosmo"), Coin::new(500, "uion")],
+        );
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), join_pool_msg).unwrap();
+
+        for denom in ["uosmo", "uion"] {
+            assert_dirty_change_limiters_by_denom!(
+                denom,
+                Transmuter::new().limiters,
+                deps.as_ref().storage
+            );
+        }
+
+        // Add new assets
+
+        // Attempt to add assets with invalid denom
+        let info = mock_info(admin, &[]);
+        let invalid_denoms = vec!["invalid_asset1".to_string(), "invalid_asset2".to_string()];
+        let add_invalid_assets_msg = ContractExecMsg::Transmuter(ExecMsg::AddNewAssets {
+            asset_configs: invalid_denoms
+                .into_iter()
+                .map(|denom| AssetConfig::from_denom_str(denom.as_str()))
+                .collect(),
+        });
+
+        env.block.time = env.block.time.plus_nanos(360);
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            add_invalid_assets_msg,
+        );
+
+        // Check if the attempt resulted in DenomHasNoSupply error
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::DenomHasNoSupply {
+                denom: "invalid_asset1".to_string()
+            }
+        );
+
+        let new_assets = vec!["new_asset1".to_string(), "new_asset2".to_string()];
+        let add_assets_msg = ContractExecMsg::Transmuter(ExecMsg::AddNewAssets {
+            asset_configs: new_assets
+                .into_iter()
+                .map(|denom| AssetConfig::from_denom_str(denom.as_str()))
+                .collect(),
+        });
+
+        env.block.time = env.block.time.plus_nanos(360);
+
+        // Attempt to add assets by non-admin
+        let non_admin_info = mock_info("non_admin", &[]);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            non_admin_info,
+            add_assets_msg.clone(),
+        );
+
+        // Check if the attempt was unauthorized
+        assert_eq!(
+            res.unwrap_err(),
+            ContractError::Unauthorized {},
+            "Adding assets by non-admin should be unauthorized"
+        );
+
+        env.block.time = env.block.time.plus_nanos(360);
+
+        // successful asset addition
+        execute(deps.as_mut(), env.clone(), info, add_assets_msg).unwrap();
+
+        let reset_at = env.block.time;
+        let transmuter = Transmuter::new();
+
+        // Reset change limiter states if new assets are added
+        for denom in ["uosmo", "uion"] {
+            assert_reset_change_limiters_by_denom!(
+                denom,
+                reset_at,
+                transmuter,
+                deps.as_ref().storage
+            );
+        }
+
+        env.block.time = env.block.time.plus_nanos(360);
+
+        // Check if the new assets were added
+        let res = query(
+            deps.as_ref(),
+            env,
+            ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+        )
+        .unwrap();
+        let GetTotalPoolLiquidityResponse {
+            total_pool_liquidity,
+        } = from_json(res).unwrap();
+
+        assert_eq!(
+            total_pool_liquidity,
+            vec![
+                Coin::new(1000001000, "uosmo"),
+                Coin::new(1000001000, "uion"),
+                Coin::new(0, "new_asset1"),
+                Coin::new(0, "new_asset2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pool_share_denom_not_usable_as_pool_asset() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "uosmo"),
+                Coin::new(1, "uion"),
+                Coin::new(1, "alloyed"),
+            ],
+        );
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "alloyed".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        let expected_err = ContractError::CannotUsePoolShareDenom {
+            denom: "alloyed".to_string(),
+        };
+
+        for msg in [
+            ExecMsg::AddNewAssets {
+                asset_configs: vec![AssetConfig::from_denom_str("alloyed")],
+            },
+            ExecMsg::CreateAssetGroup {
+                label: "group".to_string(),
+                denoms: vec!["uosmo".to_string(), "alloyed".to_string()],
+            },
+            ExecMsg::RegisterLimiter {
+                denom: "alloyed".to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            },
+        ] {
+            let err = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+            .unwrap_err();
+            assert_eq!(err, expected_err);
+        }
+    }
+
+    #[test]
+    fn test_merge_asset_groups() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "axlusdc"),
+                Coin::new(1, "whusdc"),
+                Coin::new(1, "usdc"),
+            ],
+        );
+
+        let admin = "admin";
+        let moderator = "moderator";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("axlusdc"),
+                AssetConfig::from_denom_str("whusdc"),
+                AssetConfig::from_denom_str("usdc"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "allusdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        let exec = |deps: DepsMut, sender: &str, msg: ExecMsg| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+        };
+
+        // only admin can create asset group
+        let err = exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::CreateAssetGroup {
+                label: "bridged".to_string(),
+                denoms: vec!["axlusdc".to_string()],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // denoms must be pool assets
+        let err = exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::CreateAssetGroup {
+                label: "bridged".to_string(),
+                denoms: vec!["uosmo".to_string()],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPoolAssetDenom {
+                denom: "uosmo".to_string()
+            }
+        );
+
+        exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::CreateAssetGroup {
+                label: "axelar".to_string(),
+                denoms: vec!["axlusdc".to_string()],
+            },
+        )
+        .unwrap();
+
+        exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::CreateAssetGroup {
+                label: "bridged".to_string(),
+                denoms: vec!["axlusdc".to_string(), "whusdc".to_string()],
+            },
+        )
+        .unwrap();
+
+        // mark axelar group as corrupted
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::MarkAssetGroupAsCorrupted {
+                label: "axelar".to_string(),
+            },
+        )
+        .unwrap();
+
+        // reject if either label is missing
+        for (source, target) in [("missing", "bridged"), ("axelar", "missing")] {
+            let err = exec(
+                deps.as_mut(),
+                admin,
+                ExecMsg::MergeAssetGroups {
+                    source: source.to_string(),
+                    target: target.to_string(),
+                },
+            )
+            .unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::AssetGroupNotFound {
+                    label: "missing".to_string()
+                }
+            );
+        }
+
+        // merge corrupted group into healthy one
+        exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::MergeAssetGroups {
+                source: "axelar".to_string(),
+                target: "bridged".to_string(),
+            },
+        )
+        .unwrap();
+
+        let ListAssetGroupsResponse { asset_groups } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::ListAssetGroups {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let mut expected_group = AssetGroup::new(vec!["axlusdc".to_string(), "whusdc".to_string()]);
+        expected_group.mark_as_corrupted();
+
+        assert_eq!(
+            asset_groups,
+            BTreeMap::from([("bridged".to_string(), expected_group)])
+        );
+
+        // all denoms of the merged group are corrupted
+        let GetCorrruptedDenomsResponse { corrupted_denoms } = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                ContractQueryMsg::Transmuter(QueryMsg::GetCorruptedDenoms {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(corrupted_denoms, vec!["axlusdc", "whusdc"]);
+    }
+
+    #[test]
+    fn test_set_corruption_batch() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "axlusdc"),
+                Coin::new(1, "whusdc"),
+                Coin::new(1, "usdc"),
+            ],
+        );
+
+        let admin = "admin";
+        let moderator = "moderator";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("axlusdc"),
+                AssetConfig::from_denom_str("whusdc"),
+                AssetConfig::from_denom_str("usdc"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "allusdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        let exec = |deps: DepsMut, sender: &str, msg: ExecMsg| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+        };
+
+        for (label, denom) in [("axelar", "axlusdc"), ("wormhole", "whusdc")] {
+            exec(
+                deps.as_mut(),
+                admin,
+                ExecMsg::CreateAssetGroup {
+                    label: label.to_string(),
+                    denoms: vec![denom.to_string()],
+                },
+            )
+            .unwrap();
+        }
+
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::MarkAssetGroupAsCorrupted {
+                label: "axelar".to_string(),
+            },
+        )
+        .unwrap();
+
+        let states = |deps: cosmwasm_std::Deps| {
+            let ListAssetGroupsResponse { asset_groups } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::ListAssetGroups {}),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            let GetCorrruptedDenomsResponse { corrupted_denoms } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::GetCorruptedDenoms {}),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+            (asset_groups, corrupted_denoms)
+        };
+
+        let states_before = states(deps.as_ref());
+        assert_eq!(states_before.1, vec!["axlusdc"]);
+
+        // only moderator can set corruption batch
+        let err = exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::SetCorruptionBatch {
+                mark: vec!["wormhole".to_string()],
+                unmark: vec![],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // a label can't be both marked and unmarked
+        let err = exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::SetCorruptionBatch {
+                mark: vec!["wormhole".to_string()],
+                unmark: vec!["wormhole".to_string()],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ConflictingAssetGroupCorruption {
+                label: "wormhole".to_string()
+            }
+        );
+
+        // unknown label rejects the whole batch, even after known labels,
+        // leaving storage byte-for-byte unchanged
+        let hash_before = state_hash(&deps.storage);
+        let err = exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::SetCorruptionBatch {
+                mark: vec!["wormhole".to_string(), "missing".to_string()],
+                unmark: vec!["axelar".to_string()],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AssetGroupNotFound {
+                label: "missing".to_string()
+            }
+        );
+        assert_eq!(states(deps.as_ref()), states_before);
+        assert_eq!(state_hash(&deps.storage), hash_before);
+
+        let res = exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::SetCorruptionBatch {
+                mark: vec!["wormhole".to_string()],
+                unmark: vec!["axelar".to_string()],
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "set_corruption_batch"),
+                attr("marked", "wormhole"),
+                attr("unmarked", "axelar"),
+            ]
+        );
+
+        let mut wormhole = AssetGroup::new(vec!["whusdc".to_string()]);
+        wormhole.mark_as_corrupted();
+        assert_eq!(
+            states(deps.as_ref()),
+            (
+                BTreeMap::from([
+                    (
+                        "axelar".to_string(),
+                        AssetGroup::new(vec!["axlusdc".to_string()])
+                    ),
+                    ("wormhole".to_string(), wormhole),
+                ]),
+                vec!["whusdc".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn test_corrupted_assets() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "wbtc"),
+                Coin::new(1, "tbtc"),
+                Coin::new(1, "nbtc"),
+                Coin::new(1, "stbtc"),
+            ],
+        );
+
+        let admin = "admin";
+        let moderator = "moderator";
+        let alloyed_subdenom = "btc";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("wbtc"),
+                AssetConfig::from_denom_str("tbtc"),
+                AssetConfig::from_denom_str("nbtc"),
+                AssetConfig::from_denom_str("stbtc"),
+            ],
+            alloyed_asset_subdenom: alloyed_subdenom.to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            admin: Some(admin.to_string()),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        let info = mock_info(admin, &[]);
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+
+        let res = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_subdenom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        let alloyed_token_denom_kv = res.attributes[0].clone();
+        assert_eq!(alloyed_token_denom_kv.key, "alloyed_denom");
+        let alloyed_denom = alloyed_token_denom_kv.value;
+
+        // set limiters
+        let change_limiter_params = LimiterParams::ChangeLimiter {
+            window_config: WindowConfig {
+                window_size: Uint64::from(3600000000000u64),
+                division_count: Uint64::from(5u64),
+            },
+            boundary_offset: Decimal::percent(20),
+        };
+
+        let static_limiter_params = LimiterParams::StaticLimiter {
+            upper_limit: Decimal::percent(30),
+        };
+
+        // Mark corrupted assets by non-moderator
+        let info = mock_info("someone", &[]);
+        let mark_corrupted_assets_msg = ContractExecMsg::Transmuter(ExecMsg::MarkCorruptedAssets {
+            denoms: vec!["wbtc".to_string(), "tbtc".to_string()],
+        });
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            mark_corrupted_assets_msg,
+        );
+
+        // Check if the attempt resulted in Unauthorized error
+        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+
+        // Corrupted denoms must be empty
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetCorruptedDenoms {}),
+        )
+        .unwrap();
+        let GetCorrruptedDenomsResponse { corrupted_denoms } = from_json(res).unwrap();
+
+        assert_eq!(corrupted_denoms, Vec::<String>::new());
+
+        // The asset must not yet be removed
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+        )
+        .unwrap();
+        let GetTotalPoolLiquidityResponse {
+            total_pool_liquidity,
+        } = from_json(res).unwrap();
+
+        assert_eq!(
+            total_pool_liquidity,
+            vec![
+                Coin::new(0, "wbtc"),
+                Coin::new(0, "tbtc"),
+                Coin::new(0, "nbtc"),
+                Coin::new(0, "stbtc"),
+            ]
+        );
+
+        // provide some liquidity
+        let liquidity = vec![
+            Coin::new(1_000_000_000_000, "wbtc"),
+            Coin::new(1_000_000_000_000, "tbtc"),
+            Coin::new(1_000_000_000_000, "nbtc"),
+            Coin::new(1_000_000_000_000, "stbtc"),
+        ];
+        deps.querier.update_balance("someone", liquidity.clone());
+
+        let info = mock_info("someone", &liquidity);
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+
+        execute(deps.as_mut(), env.clone(), info.clone(), join_pool_msg).unwrap();
+
+        // set limiters
+        for denom in ["wbtc", "tbtc", "nbtc", "stbtc"] {
+            let register_limiter_msg = ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: denom.to_string(),
+                label: "change_limiter".to_string(),
+                limiter_params: change_limiter_params.clone(),
+            });
+
+            let info = mock_info(admin, &[]);
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                register_limiter_msg,
+            )
+            .unwrap();
+
+            let register_limiter_msg = ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: denom.to_string(),
+                label: "static_limiter".to_string(),
+                limiter_params: static_limiter_params.clone(),
+            });
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                register_limiter_msg,
+            )
+            .unwrap();
+        }
+
+        // exit pool a bit to make sure the limiters are dirty
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1_000, alloyed_denom.clone())]);
+        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+            tokens_out: vec![Coin::new(1_000, "nbtc")],
+        });
+
+        let info = mock_info("someone", &[]);
+        execute(deps.as_mut(), env.clone(), info.clone(), exit_pool_msg).unwrap();
+
+        // Mark corrupted assets by moderator
+        let corrupted_denoms = vec!["wbtc".to_string(), "tbtc".to_string()];
+        let mark_corrupted_assets_msg = ContractExecMsg::Transmuter(ExecMsg::MarkCorruptedAssets {
+            denoms: corrupted_denoms.clone(),
+        });
+
+        let info = mock_info(moderator, &[]);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            mark_corrupted_assets_msg,
+        )
+        .unwrap();
+        // no bank message should be sent, the corrupted asset waits for withdrawal
+        assert_eq!(res.messages, vec![]);
+
+        // corrupted denoms must be updated
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetCorruptedDenoms {}),
+        )
+        .unwrap();
+        let res: GetCorrruptedDenomsResponse = from_json(res).unwrap();
+
+        assert_eq!(res.corrupted_denoms, corrupted_denoms);
+
+        // Check if the assets were removed
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+        )
+        .unwrap();
+        let GetTotalPoolLiquidityResponse {
+            total_pool_liquidity,
+        } = from_json(res).unwrap();
+
+        assert_eq!(
+            total_pool_liquidity,
+            vec![
+                Coin::new(1_000_000_000_000, "wbtc"),
+                Coin::new(1_000_000_000_000, "tbtc"),
+                Coin::new(999_999_999_000, "nbtc"),
+                Coin::new(1_000_000_000_000, "stbtc"),
+            ]
+        );
+
+        // warm up the limiters
+        let env = increase_block_height(&env, 1);
+        deps.querier
+            .update_balance("someone", vec![Coin::new(4, alloyed_denom.clone())]);
+        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+            tokens_out: vec![
+                Coin::new(1, "wbtc"),
+                Coin::new(1, "tbtc"),
+                Coin::new(1, "nbtc"),
+                Coin::new(1, "stbtc"),
+            ],
+        });
+        let info = mock_info("someone", &[]);
+        execute(deps.as_mut(), env.clone(), info.clone(), exit_pool_msg).unwrap();
+
+        let env = increase_block_height(&env, 1);
+
+        deps.querier
+            .update_balance("someone", vec![Coin::new(4, alloyed_denom.clone())]);
+        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+            tokens_out: vec![
+                Coin::new(1, "wbtc"),
+                Coin::new(1, "tbtc"),
+                Coin::new(1, "nbtc"),
+                Coin::new(1, "stbtc"),
+            ],
+        });
+        let info = mock_info("someone", &[]);
+        execute(deps.as_mut(), env.clone(), info.clone(), exit_pool_msg).unwrap();
+
+        let env = increase_block_height(&env, 1);
+
+        for denom in corrupted_denoms {
+            let expected_err = ContractError::CorruptedAssetRelativelyIncreased {
+                denom: denom.clone(),
+            };
+
+            // join with corrupted denom should fail
+            let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            });
+            let err = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("user", &[Coin::new(1000, denom.clone())]),
+                join_pool_msg,
+            )
+            .unwrap_err();
+            assert_eq!(expected_err, err);
+
+            // swap exact in with corrupted denom as token in should fail
+            let swap_msg = SudoMsg::SwapExactAmountIn {
+                token_in: Coin::new(1000, denom.clone()),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                sender: "mock_sender".to_string(),
+                token_out_denom: "nbtc".to_string(),
+                token_out_min_amount: Uint128::new(500),
+                require_improves_balance: false,
+            };
+
+            let err = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap_err();
+            assert_eq!(expected_err, err);
+
+            // swap exact in with corrupted denom as token out should be ok since it decreases the corrupted asset
+            let swap_msg = SudoMsg::SwapExactAmountIn {
+                token_in: Coin::new(1000, "nbtc"),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                sender: "mock_sender".to_string(),
+                token_out_denom: denom.clone(),
+                token_out_min_amount: Uint128::new(500),
+                require_improves_balance: false,
+            };
+
+            sudo(deps.as_mut(), env.clone(), swap_msg).unwrap();
+
+            // swap exact out with corrupted denom as token out should be ok since it decreases the corrupted asset
+            let swap_msg = SudoMsg::SwapExactAmountOut {
+                sender: "mock_sender".to_string(),
+                token_out: Coin::new(500, denom.clone()),
+                swap_fee: Decimal::zero(),
+                token_in_denom: "nbtc".to_string(),
+                token_in_max_amount: Uint128::new(1000),
+                require_improves_balance: false,
+            };
+
+            sudo(deps.as_mut(), env.clone(), swap_msg).unwrap();
+
+            // swap exact out with corrupted denom as token in should fail
+            let swap_msg = SudoMsg::SwapExactAmountOut {
+                sender: "mock_sender".to_string(),
+                token_out: Coin::new(500, "nbtc"),
+                swap_fee: Decimal::zero(),
+                token_in_denom: denom.clone(),
+                token_in_max_amount: Uint128::new(1000),
+                require_improves_balance: false,
+            };
+
+            let err = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap_err();
+            assert_eq!(expected_err, err);
+
+            // exit with by any denom requires corrupted denom to not increase in weight
+            // (this case increase other remaining corrupted denom weight)
+            deps.querier.update_balance(
+                "someone",
+                vec![Coin::new(4_000_000_000, alloyed_denom.clone())],
+            );
+
+            let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+                tokens_out: vec![Coin::new(1_000_000_000, "stbtc")],
+            });
+
+            let info = mock_info("someone", &[]);
+
+            // this causes all corrupted denoms to be increased in weight
+            let err = execute(deps.as_mut(), env.clone(), info, exit_pool_msg).unwrap_err();
+            assert!(matches!(
+                err,
+                ContractError::CorruptedAssetRelativelyIncreased { .. }
+            ));
+
+            let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+                tokens_out: vec![
+                    Coin::new(1_000_000_000, "nbtc"),
+                    Coin::new(1_000_000_000, denom.clone()),
+                ],
+            });
+
+            let info = mock_info("someone", &[]);
+
+            // this causes other corrupted denom to be increased relatively
+            let err = execute(deps.as_mut(), env.clone(), info, exit_pool_msg).unwrap_err();
+            assert!(matches!(
+                err,
+                ContractError::CorruptedAssetRelativelyIncreased { .. }
+            ));
+        }
+
+        // exit with corrupted denom requires all corrupted denom exit with the same value
+        deps.querier.update_balance(
+            "someone",
+            vec![Coin::new(4_000_000_000, alloyed_denom.clone())],
+        );
+        let info = mock_info("someone", &[]);
+        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+            tokens_out: vec![
+                Coin::new(2_000_000_000, "nbtc"),
+                Coin::new(1_000_000_000, "wbtc"),
+                Coin::new(1_000_000_000, "tbtc"),
+            ],
+        });
+        execute(deps.as_mut(), env.clone(), info, exit_pool_msg).unwrap();
+
+        // force redeem corrupted assets
+
+        deps.querier.update_balance(
+            "someone",
+            vec![Coin::new(1_000_000_000_000, alloyed_denom.clone())], // TODO: increase shares
+        );
+        let all_nbtc = total_liquidity_of("nbtc", &deps.storage);
+        let force_redeem_corrupted_assets_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+            tokens_out: vec![all_nbtc],
+        });
+
+        let info = mock_info("someone", &[]);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            force_redeem_corrupted_assets_msg,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::CorruptedAssetRelativelyIncreased {
+                denom: "wbtc".to_string()
+            }
+        );
+
+        let all_wbtc = total_liquidity_of("wbtc", &deps.storage);
+        let force_redeem_corrupted_assets_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+            tokens_out: vec![all_wbtc],
+        });
+
+        deps.querier.update_balance(
+            "someone",
+            vec![Coin::new(1_000_000_000_000, alloyed_denom.clone())],
+        );
+
+        let info = mock_info("someone", &[]);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            force_redeem_corrupted_assets_msg,
+        )
+        .unwrap();
+
+        // check liquidity
+        let GetTotalPoolLiquidityResponse {
+            total_pool_liquidity,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            total_pool_liquidity,
+            vec![
+                Coin::new(998999998498, "tbtc"),
+                Coin::new(998000001998, "nbtc"),
+                Coin::new(999999999998, "stbtc"),
+            ]
+        );
+
+        assert_eq!(
+            Transmuter::new()
+                .limiters
+                .list_limiters_by_denom(&deps.storage, "wbtc")
+                .unwrap(),
+            vec![]
+        );
+
+        for denom in ["tbtc", "nbtc", "stbtc"] {
+            assert_reset_change_limiters_by_denom!(
+                denom,
+                env.block.time,
+                Transmuter::new(),
+                deps.as_ref().storage
+            );
+        }
+
+        // try unmark nbtc should fail
+        let unmark_corrupted_assets_msg =
+            ContractExecMsg::Transmuter(ExecMsg::UnmarkCorruptedAssets {
+                denoms: vec!["nbtc".to_string()],
+            });
+
+        let info = mock_info(moderator, &[]);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            unmark_corrupted_assets_msg,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::InvalidCorruptedAssetDenom {
+                denom: "nbtc".to_string()
+            }
+        );
+
+        // unmark tbtc by non moderator should fail
+        let unmark_corrupted_assets_msg =
+            ContractExecMsg::Transmuter(ExecMsg::UnmarkCorruptedAssets {
+                denoms: vec!["tbtc".to_string()],
+            });
+
+        let info = mock_info("someone", &[]);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            unmark_corrupted_assets_msg,
+        )
+        .unwrap_err();
+
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // unmark tbtc
+        let unmark_corrupted_assets_msg =
+            ContractExecMsg::Transmuter(ExecMsg::UnmarkCorruptedAssets {
+                denoms: vec!["tbtc".to_string()],
+            });
+
+        let info = mock_info(moderator, &[]);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            unmark_corrupted_assets_msg,
+        )
+        .unwrap();
+
+        // query corrupted denoms
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetCorruptedDenoms {}),
+        )
+        .unwrap();
+
+        let GetCorrruptedDenomsResponse { corrupted_denoms } = from_json(res).unwrap();
+
+        assert_eq!(corrupted_denoms, Vec::<String>::new());
+
+        // no liquidity or pool assets changes
+        let GetTotalPoolLiquidityResponse {
+            total_pool_liquidity,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            total_pool_liquidity,
+            vec![
+                Coin::new(998999998498, "tbtc"),
+                Coin::new(998000001998, "nbtc"),
+                Coin::new(999999999998, "stbtc"),
+            ]
+        );
+
+        // still has all the limiters
+        assert_eq!(
+            Transmuter::new()
+                .limiters
+                .list_limiters_by_denom(&deps.storage, "tbtc")
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    fn increase_block_height(env: &Env, height: u64) -> Env {
+        let block_time = 5; // hypothetical block time
+        Env {
+            block: BlockInfo {
+                height: env.block.height + height,
+                time: env.block.time.plus_seconds(block_time * height),
+                chain_id: env.block.chain_id.clone(),
+            },
+            ..env.clone()
+        }
+    }
+
+    fn total_liquidity_of(denom: &str, storage: &dyn Storage) -> Coin {
+        Transmuter::new()
+            .pool
+            .load(storage)
+            .unwrap()
+            .pool_assets
+            .into_iter()
+            .find(|a| a.denom() == denom)
+            .unwrap()
+            .to_coin()
+    }
+
+    /// Hash of all raw key-value pairs in `storage`, to assert that a rejected message
+    /// leaves storage byte-for-byte unchanged.
+    fn state_hash(storage: &dyn Storage) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        storage
+            .range(None, None, Order::Ascending)
+            .collect::<Vec<_>>()
+            .hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Swap 1_000 `denom_in` for `denom_out` via sudo, paying the swap fee configured for the pool.
+    fn swap_with_configured_fee(deps: DepsMut, denom_in: &str, denom_out: &str) {
+        let swap_fee = Transmuter::new().fee.get_swap_fee(deps.storage).unwrap();
+
+        sudo(
+            deps,
+            mock_env(),
+            SudoMsg::SwapExactAmountIn {
+                sender: "someone".to_string(),
+                token_in: Coin::new(1_000, denom_in),
+                token_out_denom: denom_out.to_string(),
+                token_out_min_amount: Uint128::one(),
+                swap_fee,
+                partial_fill: false,
+                require_improves_balance: false,
+            },
+        )
+        .unwrap();
+    }
+
+    #[rstest]
+    #[case(None, false, false, false)]
+    #[case(Some(PauseScope::All), false, false, false)]
+    #[case(Some(PauseScope::SwapsOnly), false, true, true)]
+    #[case(Some(PauseScope::JoinsOnly), true, false, true)]
+    fn test_pause_scope(
+        #[case] scope: Option<PauseScope>,
+        #[case] swap_allowed: bool,
+        #[case] join_and_exit_allowed: bool,
+        #[case] other_allowed: bool,
+    ) {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let moderator = "moderator";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[Coin::new(1000, "uosmo"), Coin::new(1000, "uion")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        deps.querier
+            .update_balance(admin, vec![Coin::new(2000, alloyed_denom)]);
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::IncreaseAlloyedAllowance {
+                spender: "someone".to_string(),
+                amount: Uint128::new(100),
+            }),
+        )
+        .unwrap();
+
+        // pause with scope
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(moderator, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus {
+                active: false,
+                scope: scope.clone(),
+                reactivate_at: None,
+            }),
+        )
+        .unwrap();
+
+        let GetPauseScopeResponse { pause_scope } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::GetPauseScope {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(pause_scope, Some(scope.unwrap_or(PauseScope::All)));
+
+        let assert_allowed = |res: Result<Response, ContractError>, allowed: bool| {
+            if allowed {
+                res.unwrap();
+            } else {
+                assert_eq!(res.unwrap_err(), ContractError::InactivePool {});
+            }
+        };
+
+        // swap
+        assert_allowed(
+            sudo(
+                deps.as_mut(),
+                env.clone(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: "someone".to_string(),
+                    token_in: Coin::new(100, "uosmo"),
+                    token_out_denom: "uion".to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            ),
+            swap_allowed,
+        );
+
+        // join & exit pool
+        assert_allowed(
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(admin, &[Coin::new(100, "uosmo")]),
+                ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                    min_shares_out: None,
+                }),
+            ),
+            join_and_exit_allowed,
+        );
+        assert_allowed(
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(admin, &[Coin::new(100, "uosmo")]),
+                ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                    min_shares_out: Some(Uint128::one()),
+                }),
+            ),
+            join_and_exit_allowed,
+        );
+        assert_allowed(
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+                    tokens_out: vec![Coin::new(100, "uion")],
+                }),
+            ),
+            join_and_exit_allowed,
+        );
+        assert_allowed(
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("someone", &[]),
+                ContractExecMsg::Transmuter(ExecMsg::ExitPoolFrom {
+                    owner: admin.to_string(),
+                    tokens_out: vec![Coin::new(100, "uion")],
+                }),
+            ),
+            join_and_exit_allowed,
+        );
+
+        // other operations
+        assert_allowed(
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::SetSwapFee {
+                    swap_fee: Decimal::percent(1),
+                }),
+            ),
+            other_allowed,
+        );
+
+        // reactivating clears the pause scope
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(moderator, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus {
+                active: true,
+                scope: None,
+                reactivate_at: None,
+            }),
+        )
+        .unwrap();
+
+        let GetPauseScopeResponse { pause_scope } = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                ContractQueryMsg::Transmuter(QueryMsg::GetPauseScope {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(pause_scope, None);
+    }
+
+    #[test]
+    fn test_set_active_status() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let moderator = "moderator";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            alloyed_asset_subdenom: "uosmouion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            admin: Some(admin.to_string()),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        // Manually set alloyed denom
+        let alloyed_denom = "uosmo".to_string();
+
+        let transmuter = Transmuter::new();
+        transmuter
+            .alloyed_asset
+            .set_alloyed_denom(&mut deps.storage, &alloyed_denom)
+            .unwrap();
+
+        // Check the initial active status.
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::IsActive {}),
+        )
+        .unwrap();
+        let active_status: IsActiveResponse = from_json(res).unwrap();
+        assert!(active_status.is_active);
+
+        // Attempt to set the active status by a non-admin user.
+        let non_admin_info = mock_info("non_moderator", &[]);
+        let non_admin_msg = ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus {
+            active: false,
+            scope: None,
+            reactivate_at: None,
+        });
+        let err = execute(deps.as_mut(), env.clone(), non_admin_info, non_admin_msg).unwrap_err();
+
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // Set the active status to false.
+        let msg = ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus {
+            active: false,
+            scope: None,
+            reactivate_at: None,
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(moderator, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+
+        // Check the active status again.
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::IsActive {}),
+        )
+        .unwrap();
+        let active_status: IsActiveResponse = from_json(res).unwrap();
+        assert!(!active_status.is_active);
+
+        // try to set the active status to false again
+        let err = execute(deps.as_mut(), env.clone(), mock_info(moderator, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::UnchangedActiveStatus { status: false });
+
+        // Test that JoinPool is blocked when active status is false
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]),
+            join_pool_msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InactivePool {});
+
+        // Test that SwapExactAmountIn is blocked when active status is false
+        let swap_exact_amount_in_msg = SudoMsg::SwapExactAmountIn {
+            token_in: Coin::new(1000, "uion"),
+            swap_fee: Decimal::zero(),
+            partial_fill: false,
+            sender: "mock_sender".to_string(),
+            token_out_denom: "uosmo".to_string(),
+            token_out_min_amount: Uint128::new(500),
+            require_improves_balance: false,
+        };
+        let err = sudo(deps.as_mut(), env.clone(), swap_exact_amount_in_msg).unwrap_err();
+        assert_eq!(err, ContractError::InactivePool {});
+
+        // Test that SwapExactAmountOut is blocked when active status is false
+        let swap_exact_amount_out_msg = SudoMsg::SwapExactAmountOut {
+            sender: "mock_sender".to_string(),
+            token_out: Coin::new(500, "uosmo"),
+            swap_fee: Decimal::zero(),
+            token_in_denom: "uion".to_string(),
+            token_in_max_amount: Uint128::new(1000),
+            require_improves_balance: false,
+        };
+        let err = sudo(deps.as_mut(), env.clone(), swap_exact_amount_out_msg).unwrap_err();
+        assert_eq!(err, ContractError::InactivePool {});
+
+        // Test that ExitPool is blocked when active status is false
+        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+            tokens_out: vec![Coin::new(1000, "uion"), Coin::new(1000, "uosmo")],
+        });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]),
+            exit_pool_msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InactivePool {});
+
+        // Set the active status back to true
+        let msg = ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus {
+            active: true,
+            scope: None,
+            reactivate_at: None,
+        });
+        execute(deps.as_mut(), env.clone(), mock_info(moderator, &[]), msg).unwrap();
+
+        // Check the active status again.
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::IsActive {}),
+        )
+        .unwrap();
+        let active_status: IsActiveResponse = from_json(res).unwrap();
+        assert!(active_status.is_active);
+
+        // Test that JoinPool is active when active status is true
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]),
+            join_pool_msg,
+        );
+        assert!(res.is_ok());
+
+        // Test that SwapExactAmountIn is active when active status is true
+        let swap_exact_amount_in_msg = SudoMsg::SwapExactAmountIn {
+            token_in: Coin::new(100, "uion"),
+            swap_fee: Decimal::zero(),
+            partial_fill: false,
+            sender: "mock_sender".to_string(),
+            token_out_denom: "uosmo".to_string(),
+            token_out_min_amount: Uint128::new(100),
+            require_improves_balance: false,
+        };
+        let res = sudo(deps.as_mut(), env.clone(), swap_exact_amount_in_msg);
+        assert!(res.is_ok());
+
+        // Test that SwapExactAmountOut is active when active status is true
+        let swap_exact_amount_out_msg = SudoMsg::SwapExactAmountOut {
+            sender: "mock_sender".to_string(),
+            token_out: Coin::new(100, "uosmo"),
+            swap_fee: Decimal::zero(),
+            token_in_denom: "uion".to_string(),
+            token_in_max_amount: Uint128::new(100),
+            require_improves_balance: false,
+        };
+        let res = sudo(deps.as_mut(), env.clone(), swap_exact_amount_out_msg);
+
+        assert!(res.is_ok());
+
+        // Test setting active status through sudo
+        let set_active_status_msg = SudoMsg::SetActive {
+            is_active: false,
+            scope: None,
+            reactivate_at: None,
+        };
+        let res = sudo(deps.as_mut(), env.clone(), set_active_status_msg);
+        assert!(res.is_ok());
+
+        // Check the active status again.
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::IsActive {}),
+        )
+        .unwrap();
+        let active_status: IsActiveResponse = from_json(res).unwrap();
+        assert!(!active_status.is_active);
+
+        // Set the active status back to true through sudo
+        let set_active_status_msg = SudoMsg::SetActive {
+            is_active: true,
+            scope: None,
+            reactivate_at: None,
+        };
+        let res = sudo(deps.as_mut(), env.clone(), set_active_status_msg);
+        assert!(res.is_ok());
+
+        // Check the active status again.
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::IsActive {}),
+        )
+        .unwrap();
+        let active_status: IsActiveResponse = from_json(res).unwrap();
+        assert!(active_status.is_active);
+
+        // try to set active status to true when it's already true
+        let set_active_status_msg = SudoMsg::SetActive {
+            is_active: true,
+            scope: None,
+            reactivate_at: None,
+        };
+
+        let err = sudo(deps.as_mut(), env, set_active_status_msg).unwrap_err();
+
+        assert_eq!(err, ContractError::UnchangedActiveStatus { status: true });
+    }
+
+    #[test]
+    fn test_reactivate_at() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "someone",
+                &[Coin::new(1_000, "uosmo"), Coin::new(1_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let swap = |deps: DepsMut, env: Env| {
+            sudo(
+                deps,
+                env,
+                SudoMsg::SwapExactAmountIn {
+                    sender: "someone".to_string(),
+                    token_in: Coin::new(100, "uosmo"),
+                    token_out_denom: "uion".to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+        };
+
+        // reactivation time must be in the future, rejected update leaves storage unchanged
+        let hash_before = state_hash(&deps.storage);
+        let err = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SetActive {
+                is_active: false,
+                scope: None,
+                reactivate_at: Some(env.block.time),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ReactivationTimeNotInFuture {
+                reactivate_at: env.block.time,
+                block_time: env.block.time,
+            }
+        );
+        assert_eq!(state_hash(&deps.storage), hash_before);
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("moderator", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus {
+                active: false,
+                scope: None,
+                reactivate_at: Some(env.block.time),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ReactivationTimeNotInFuture {
+                reactivate_at: env.block.time,
+                block_time: env.block.time,
+            }
+        );
+        assert_eq!(state_hash(&deps.storage), hash_before);
+
+        // pause for an hour
+        let reactivate_at = env.block.time.plus_seconds(3600);
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SetActive {
+                is_active: false,
+                scope: None,
+                reactivate_at: Some(reactivate_at),
+            },
+        )
+        .unwrap();
+
+        // swap fails before the reactivation time
+        let mut before = env.clone();
+        before.block.time = reactivate_at.minus_seconds(1);
+        let err = swap(deps.as_mut(), before.clone()).unwrap_err();
+        assert_eq!(err, ContractError::InactivePool {});
+
+        let GetReactivateAtResponse {
+            reactivate_at: queried,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                before,
+                ContractQueryMsg::Transmuter(QueryMsg::GetReactivateAt {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(queried, Some(reactivate_at));
+
+        // swap succeeds at the reactivation time, without an intervening tx
+        let mut after = env.clone();
+        after.block.time = reactivate_at;
+        swap(deps.as_mut(), after.clone()).unwrap();
+
+        let IsActiveResponse { is_active } = from_json(
+            query(
+                deps.as_ref(),
+                after.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::IsActive {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(is_active);
+
+        // explicit pause overrides the lifted pause
+        sudo(
+            deps.as_mut(),
+            after.clone(),
+            SudoMsg::SetActive {
+                is_active: false,
+                scope: None,
+                reactivate_at: None,
+            },
+        )
+        .unwrap();
+
+        let mut later = after.clone();
+        later.block.time = reactivate_at.plus_seconds(3600);
+        let err = swap(deps.as_mut(), later).unwrap_err();
+        assert_eq!(err, ContractError::InactivePool {});
+    }
+
+    #[test]
+    fn test_transfer_and_claim_admin() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let moderator = "moderator";
+        let canceling_candidate = "canceling_candidate";
+        let rejecting_candidate = "rejecting_candidate";
+        let candidate = "candidate";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Transfer admin rights to the canceling candidate
+        let transfer_admin_msg = ContractExecMsg::Transmuter(ExecMsg::TransferAdmin {
+            candidate: canceling_candidate.to_string(),
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), transfer_admin_msg).unwrap();
+
+        // Check the admin candidate
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetAdminCandidate {}),
+        )
+        .unwrap();
+        let admin_candidate: GetAdminCandidateResponse = from_json(res).unwrap();
+        assert_eq!(
+            admin_candidate.admin_candidate.unwrap().as_str(),
+            canceling_candidate
+        );
+
+        // Cancel admin rights transfer
+        let cancel_admin_transfer_msg =
+            ContractExecMsg::Transmuter(ExecMsg::CancelAdminTransfer {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            cancel_admin_transfer_msg,
+        )
+        .unwrap();
+
+        // Check the admin candidate
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetAdminCandidate {}),
+        )
+        .unwrap();
+        let admin_candidate: GetAdminCandidateResponse = from_json(res).unwrap();
+        assert_eq!(admin_candidate.admin_candidate, None);
+
+        // Transfer admin rights to the rejecting candidate
+        let transfer_admin_msg = ContractExecMsg::Transmuter(ExecMsg::TransferAdmin {
+            candidate: rejecting_candidate.to_string(),
+        });
+        execute(deps.as_mut(), env.clone(), info.clone(), transfer_admin_msg).unwrap();
+
+        // Check the admin candidate
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetAdminCandidate {}),
+        )
+        .unwrap();
+        let admin_candidate: GetAdminCandidateResponse = from_json(res).unwrap();
+        assert_eq!(
+            admin_candidate.admin_candidate.unwrap().as_str(),
+            rejecting_candidate
+        );
+
+        // Reject admin rights transfer
+        let reject_admin_transfer_msg =
+            ContractExecMsg::Transmuter(ExecMsg::RejectAdminTransfer {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(rejecting_candidate, &[]),
+            reject_admin_transfer_msg,
+        )
+        .unwrap();
+
+        // Check the admin candidate
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetAdminCandidate {}),
+        )
+        .unwrap();
+        let admin_candidate: GetAdminCandidateResponse = from_json(res).unwrap();
+        assert_eq!(admin_candidate.admin_candidate, None);
+
+        // Transfer admin rights to the candidate
+        let transfer_admin_msg = ContractExecMsg::Transmuter(ExecMsg::TransferAdmin {
+            candidate: candidate.to_string(),
+        });
+        execute(deps.as_mut(), env.clone(), info, transfer_admin_msg).unwrap();
+
+        // Check the admin candidate
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetAdminCandidate {}),
+        )
+        .unwrap();
+        let admin_candidate: GetAdminCandidateResponse = from_json(res).unwrap();
+        assert_eq!(admin_candidate.admin_candidate.unwrap().as_str(), candidate);
+
+        // Claim admin rights by the candidate
+        let claim_admin_msg = ContractExecMsg::Transmuter(ExecMsg::ClaimAdmin {});
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(candidate, &[]),
+            claim_admin_msg,
+        )
+        .unwrap();
+
+        // Check the current admin
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetAdmin {}),
+        )
+        .unwrap();
+        let admin: GetAdminResponse = from_json(res).unwrap();
+        assert_eq!(admin.admin.as_str(), candidate);
+    }
+
+    #[test]
+    fn test_can_execute() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        for (address, capability, expected) in [
+            (admin, Capability::Admin, true),
+            ("someone", Capability::Admin, false),
+            ("moderator", Capability::Moderator, true),
+            (admin, Capability::Moderator, false),
+        ] {
+            let res = query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::CanExecute {
+                    address: address.to_string(),
+                    capability,
+                }),
+            )
+            .unwrap();
+
+            let CanExecuteResponse { can_execute } = from_json(res).unwrap();
+            assert_eq!(can_execute, expected);
+        }
+    }
+
+    #[test]
+    fn test_assign_and_remove_moderator() {
+        let admin = "admin";
+        let moderator = "moderator";
+
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        // Instantiate the contract.
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Check the current moderator
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetModerator {}),
+        )
+        .unwrap();
+        let moderator_response: GetModeratorResponse = from_json(res).unwrap();
+        assert_eq!(moderator_response.moderator, moderator);
+
+        let new_moderator = "new_moderator";
+
+        // Try to assign new moderator by non admin
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("non_admin", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::AssignModerator {
+                address: new_moderator.to_string(),
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // Assign new moderator by admin
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::AssignModerator {
+                address: new_moderator.to_string(),
+            }),
+        )
+        .unwrap();
+
+        // Check the current moderator
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetModerator {}),
+        )
+        .unwrap();
+        let moderator_response: GetModeratorResponse = from_json(res).unwrap();
+        assert_eq!(moderator_response.moderator, new_moderator);
+    }
+
+    #[test]
+    fn test_limiter_registration_and_config() {
+        // register limiter
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let user = "user";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            moderator: "moderator".to_string(),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            enable_alloyed_asset: None,
+        };
+
+        instantiate(deps.as_mut(), mock_env(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // normal user can't register limiter
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(user, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "1h".to_string(),
+                limiter_params: LimiterParams::ChangeLimiter {
+                    window_config: WindowConfig {
+                        window_size: Uint64::from(604_800_000_000u64),
+                        division_count: Uint64::from(5u64),
+                    },
+                    boundary_offset: Decimal::one(),
+                },
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(user, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "1h".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // admin can register limiter
+        let window_config_1h = WindowConfig {
+            window_size: Uint64::from(3_600_000_000_000u64),
+            division_count: Uint64::from(5u64),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "1h".to_string(),
+                limiter_params: LimiterParams::ChangeLimiter {
+                    window_config: window_config_1h.clone(),
+                    boundary_offset: Decimal::percent(1),
+                },
+            }),
+        )
+        .unwrap();
+
+        let attrs = vec![
+            attr("method", "register_limiter"),
+            attr("denom", "uosmo"),
+            attr("label", "1h"),
+            attr("limiter_type", "change_limiter"),
+            attr("window_size", "3600000000000"),
+            attr("division_count", "5"),
+            attr("boundary_offset", "0.01"),
+        ];
+
+        assert_eq!(res.attributes, attrs);
+
+        // denom that is not in the pool can't be registered
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "invalid_denom".to_string(),
+                label: "1h".to_string(),
+                limiter_params: LimiterParams::ChangeLimiter {
+                    window_config: window_config_1h.clone(),
+                    boundary_offset: Decimal::percent(1),
+                },
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::InvalidPoolAssetDenom {
+                denom: "invalid_denom".to_string(),
+            }
+        );
+
+        // Query the list of limiters
+        let query_msg = ContractQueryMsg::Transmuter(QueryMsg::ListLimiters {});
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let limiters: ListLimitersResponse = from_json(res).unwrap();
+
+        assert_eq!(
+            limiters.limiters,
+            vec![(
+                (String::from("uosmo"), String::from("1h")),
+                Limiter::ChangeLimiter(
+                    ChangeLimiter::new(window_config_1h.clone(), Decimal::percent(1)).unwrap()
+                )
+            )]
+        );
+
+        let window_config_1w = WindowConfig {
+            window_size: Uint64::from(604_800_000_000u64),
+            division_count: Uint64::from(5u64),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "1w".to_string(),
+                limiter_params: LimiterParams::ChangeLimiter {
+                    window_config: window_config_1w.clone(),
+                    boundary_offset: Decimal::percent(1),
+                },
+            }),
+        )
+        .unwrap();
+
+        let attrs_1w = vec![
+            attr("method", "register_limiter"),
+            attr("denom", "uosmo"),
+            attr("label", "1w"),
+            attr("limiter_type", "change_limiter"),
+            attr("window_size", "604800000000"),
+            attr("division_count", "5"),
+            attr("boundary_offset", "0.01"),
+        ];
+
+        assert_eq!(res.attributes, attrs_1w);
+
+        // Query the list of limiters
+        let query_msg = ContractQueryMsg::Transmuter(QueryMsg::ListLimiters {});
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let limiters: ListLimitersResponse = from_json(res).unwrap();
+
+        assert_eq!(
+            limiters.limiters,
+            vec![
+                (
+                    (String::from("uosmo"), String::from("1h")),
+                    Limiter::ChangeLimiter(
+                        ChangeLimiter::new(window_config_1h, Decimal::percent(1)).unwrap()
+                    )
+                ),
+                (
+                    (String::from("uosmo"), String::from("1w")),
+                    Limiter::ChangeLimiter(
+                        ChangeLimiter::new(window_config_1w.clone(), Decimal::percent(1)).unwrap()
+                    )
+                ),
+            ]
+        );
+
+        // register static limiter
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            }),
+        )
+        .unwrap();
+
+        let attrs = vec![
+            attr("method", "register_limiter"),
+            attr("denom", "uosmo"),
+            attr("label", "static"),
+            attr("limiter_type", "static_limiter"),
+            attr("upper_limit", "0.6"),
+        ];
+
+        assert_eq!(res.attributes, attrs);
+
+        // deregister limiter by user is unauthorized
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(user, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::DeregisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "1h".to_string(),
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // deregister limiter by admin should work
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::DeregisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "1h".to_string(),
+            }),
+        )
+        .unwrap();
+
+        let attrs = vec![
+            attr("method", "deregister_limiter"),
+            attr("denom", "uosmo"),
+            attr("label", "1h"),
+        ];
+
+        assert_eq!(res.attributes, attrs);
+
+        // Query the list of limiters
+        let query_msg = ContractQueryMsg::Transmuter(QueryMsg::ListLimiters {});
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let limiters: ListLimitersResponse = from_json(res).unwrap();
+
+        assert_eq!(
+            limiters.limiters,
+            vec![
+                (
+                    (String::from("uosmo"), String::from("1w")),
+                    Limiter::ChangeLimiter(
+                        ChangeLimiter::new(window_config_1w.clone(), Decimal::percent(1)).unwrap()
+                    )
+                ),
+                (
+                    (String::from("uosmo"), String::from("static")),
+                    Limiter::StaticLimiter(StaticLimiter::new(Decimal::percent(60)).unwrap())
+                )
+            ]
+        );
+
+        // set boundary offset by user is unauthorized
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(user, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetChangeLimiterBoundaryOffset {
+                denom: "uosmo".to_string(),
+                label: "1w".to_string(),
+                boundary_offset: Decimal::zero(),
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // set boundary offset by admin but for osmo 1h should fail
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetChangeLimiterBoundaryOffset {
+                denom: "uosmo".to_string(),
+                label: "1h".to_string(),
+                boundary_offset: Decimal::zero(),
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::LimiterDoesNotExist {
+                denom: "uosmo".to_string(),
+                label: "1h".to_string()
+            }
+        );
+
+        // set boundary offset by admin for existing limiter should work
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetChangeLimiterBoundaryOffset {
+                denom: "uosmo".to_string(),
+                label: "1w".to_string(),
+                boundary_offset: Decimal::percent(10),
+            }),
+        )
+        .unwrap();
+
+        let attrs = vec![
+            attr("method", "set_change_limiter_boundary_offset"),
+            attr("denom", "uosmo"),
+            attr("label", "1w"),
+            attr("boundary_offset", "0.1"),
+        ];
+
+        assert_eq!(res.attributes, attrs);
+
+        // Query the list of limiters
+        let query_msg = ContractQueryMsg::Transmuter(QueryMsg::ListLimiters {});
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let limiters: ListLimitersResponse = from_json(res).unwrap();
+
+        assert_eq!(
+            limiters.limiters,
+            vec![
+                (
+                    (String::from("uosmo"), String::from("1w")),
+                    Limiter::ChangeLimiter(
+                        ChangeLimiter::new(window_config_1w.clone(), Decimal::percent(10)).unwrap()
+                    )
+                ),
+                (
+                    (String::from("uosmo"), String::from("static")),
+                    Limiter::StaticLimiter(StaticLimiter::new(Decimal::percent(60)).unwrap())
+                )
+            ]
+        );
+
+        // set upper limit by user is unauthorized
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(user, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetStaticLimiterUpperLimit {
+                denom: "uosmo".to_string(),
+                label: "static".to_string(),
+                upper_limit: Decimal::percent(50),
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // set upper limit by admin but for uosmo 1h should fail
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetStaticLimiterUpperLimit {
+                denom: "uosmo".to_string(),
+                label: "1h".to_string(),
+                upper_limit: Decimal::percent(50),
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::LimiterDoesNotExist {
+                denom: "uosmo".to_string(),
+                label: "1h".to_string()
+            }
+        );
+
+        // set upper limit by admin for change limiter should fail
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetStaticLimiterUpperLimit {
+                denom: "uosmo".to_string(),
+                label: "1w".to_string(),
+                upper_limit: Decimal::percent(50),
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::WrongLimiterType {
+                expected: "static_limiter".to_string(),
+                actual: "change_limiter".to_string()
+            }
+        );
+
+        // set upper limit by admin for static limiter should work
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetStaticLimiterUpperLimit {
+                denom: "uosmo".to_string(),
+                label: "static".to_string(),
+                upper_limit: Decimal::percent(50),
+            }),
+        )
+        .unwrap();
+
+        let attrs = vec![
+            attr("method", "set_static_limiter_upper_limit"),
+            attr("denom", "uosmo"),
+            attr("label", "static"),
+            attr("upper_limit", "0.5"),
+        ];
+
+        assert_eq!(res.attributes, attrs);
+
+        // Query the list of limiters
+        let query_msg = ContractQueryMsg::Transmuter(QueryMsg::ListLimiters {});
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let limiters: ListLimitersResponse = from_json(res).unwrap();
+
+        assert_eq!(
+            limiters.limiters,
+            vec![
+                (
+                    (String::from("uosmo"), String::from("1w")),
+                    Limiter::ChangeLimiter(
+                        ChangeLimiter::new(window_config_1w, Decimal::percent(10)).unwrap()
+                    )
+                ),
+                (
+                    (String::from("uosmo"), String::from("static")),
+                    Limiter::StaticLimiter(StaticLimiter::new(Decimal::percent(50)).unwrap())
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn test_change_limiter_window() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            moderator: "moderator".to_string(),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            enable_alloyed_asset: None,
+        };
+
+        instantiate(deps.as_mut(), mock_env(), mock_info(admin, &[]), init_msg).unwrap();
+
+        for (label, limiter_params) in [
+            (
+                "1h",
+                LimiterParams::ChangeLimiter {
+                    window_config: WindowConfig {
+                        window_size: Uint64::from(3_600_000_000_000u64),
+                        division_count: Uint64::from(5u64),
+                    },
+                    boundary_offset: Decimal::percent(2),
+                },
+            ),
+            (
+                "static",
+                LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            ),
+        ] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                    denom: "uosmo".to_string(),
+                    label: label.to_string(),
+                    limiter_params,
+                }),
+            )
+            .unwrap();
+        }
+
+        let query_window = |label: &str| {
+            query(
+                deps.as_ref(),
+                mock_env(),
+                ContractQueryMsg::Transmuter(QueryMsg::ChangeLimiterWindow {
+                    denom: "uosmo".to_string(),
+                    label: label.to_string(),
+                }),
+            )
+        };
+
+        let res: ChangeLimiterWindowResponse = from_json(query_window("1h").unwrap()).unwrap();
+        assert_eq!(
+            res,
+            ChangeLimiterWindowResponse {
+                window_size: Uint64::from(3_600_000_000_000u64),
+                division_count: Uint64::from(5u64),
+                boundary_offset: Decimal::percent(2),
+            }
+        );
+
+        assert_eq!(
+            query_window("static").unwrap_err(),
+            ContractError::WrongLimiterType {
+                expected: "change_limiter".to_string(),
+                actual: "static_limiter".to_string(),
+            }
+        );
+
+        assert_eq!(
+            query_window("missing").unwrap_err(),
+            ContractError::LimiterDoesNotExist {
+                denom: "uosmo".to_string(),
+                label: "missing".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_limiters_by_type() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            moderator: "moderator".to_string(),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            enable_alloyed_asset: None,
+        };
+
+        instantiate(deps.as_mut(), mock_env(), mock_info(admin, &[]), init_msg).unwrap();
+
+        let window_config = WindowConfig {
+            window_size: Uint64::from(3_600_000_000_000u64),
+            division_count: Uint64::from(5u64),
+        };
+
+        for (denom, label, limiter_params) in [
+            (
+                "uosmo",
+                "1h",
+                LimiterParams::ChangeLimiter {
+                    window_config: window_config.clone(),
+                    boundary_offset: Decimal::percent(2),
+                },
+            ),
+            (
+                "uion",
+                "static",
+                LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            ),
+        ] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                    denom: denom.to_string(),
+                    label: label.to_string(),
+                    limiter_params,
+                }),
+            )
+            .unwrap();
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            ContractQueryMsg::Transmuter(QueryMsg::LimitersByType {}),
+        )
+        .unwrap();
+        let res: LimitersByTypeResponse = from_json(res).unwrap();
+
+        assert_eq!(
+            res,
+            LimitersByTypeResponse {
+                static_limiters: vec![StaticLimiterInfo {
+                    denom: "uion".to_string(),
+                    label: "static".to_string(),
+                    upper_limit: Decimal::percent(60),
+                }],
+                change_limiters: vec![ChangeLimiterInfo {
+                    denom: "uosmo".to_string(),
+                    label: "1h".to_string(),
+                    window_config,
+                    boundary_offset: Decimal::percent(2),
+                }],
+                relative_limiters: vec![],
+                net_flow_limiters: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_limiter_on_nested_alloyed_denom() {
+        let mut deps = mock_dependencies();
+
+        // alloyed asset from another transmuter pool as a pool asset
+        let nested_alloyed_denom = "factory/contract_a/alloyed/usdc";
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![Coin::new(1, "uusdc"), Coin::new(1, nested_alloyed_denom)],
+        );
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uusdc"),
+                AssetConfig::from_denom_str(nested_alloyed_denom),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "factory/contract_b/alloyed/usdc";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // join pool
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        let join_info = mock_info(
+            admin,
+            &[
+                Coin::new(1000, "uusdc"),
+                Coin::new(1000, nested_alloyed_denom),
+            ],
+        );
+        execute(deps.as_mut(), env.clone(), join_info, join_pool_msg).unwrap();
+
+        deps.querier
+            .update_balance(admin, vec![Coin::new(2000, alloyed_denom)]);
+        deps.querier.update_balance(
+            env.contract.address.to_string(),
+            vec![Coin::new(2000, alloyed_denom)],
+        );
+
+        // limiter can not be registered on the pool's own alloyed denom
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: alloyed_denom.to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::CannotUsePoolShareDenom {
+                denom: alloyed_denom.to_string()
+            }
+        );
+
+        // but it can be registered on the nested alloyed denom
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: nested_alloyed_denom.to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            }),
+        )
+        .unwrap();
+
+        // 2000 / 3000 nested alloyed weight exceeds upper limit
+        let expected_err = ContractError::UpperLimitExceeded {
+            denom: nested_alloyed_denom.to_string(),
+            upper_limit: Decimal::percent(60),
+            value: Decimal::from_ratio(2000u128, 3000u128),
+        };
+
+        // join pool
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[Coin::new(1000, nested_alloyed_denom)]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, expected_err);
+
+        // swap token to alloyed
+        let err = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountIn {
+                sender: admin.to_string(),
+                token_in: Coin::new(1000, nested_alloyed_denom),
+                token_out_denom: alloyed_denom.to_string(),
+                token_out_min_amount: Uint128::one(),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                require_improves_balance: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, expected_err);
+
+        // 1000 / 1500 nested alloyed weight exceeds upper limit
+        let expected_err = ContractError::UpperLimitExceeded {
+            denom: nested_alloyed_denom.to_string(),
+            upper_limit: Decimal::percent(60),
+            value: Decimal::from_ratio(1000u128, 1500u128),
+        };
+
+        // exit pool
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+                tokens_out: vec![Coin::new(500, "uusdc")],
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, expected_err);
+
+        // swap alloyed to token
+        let err = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountOut {
+                sender: admin.to_string(),
+                token_in_denom: alloyed_denom.to_string(),
+                token_in_max_amount: Uint128::new(500),
+                token_out: Coin::new(500, "uusdc"),
+                swap_fee: Decimal::zero(),
+                require_improves_balance: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, expected_err);
+
+        // within the upper limit
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(admin, &[Coin::new(200, nested_alloyed_denom)]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_max_swap_value() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig {
+                    denom: "uosmo".to_string(),
+                    normalization_factor: Uint128::one(),
+                },
+                AssetConfig {
+                    denom: "uion".to_string(),
+                    normalization_factor: Uint128::new(10),
+                },
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::new(10),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // join pool
+        let join_info = mock_info(
+            admin,
+            &[Coin::new(100000, "uosmo"), Coin::new(1000000, "uion")],
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            join_info,
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        deps.querier.update_balance(
+            env.contract.address.to_string(),
+            vec![Coin::new(1001, alloyed_denom)],
+        );
+
+        // only admin can set max swap value
+        let set_max_swap_value_msg = ContractExecMsg::Transmuter(ExecMsg::SetMaxSwapValue {
+            max_swap_value: Some(Uint128::new(1000)),
+        });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            set_max_swap_value_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            set_max_swap_value_msg,
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetMaxSwapValue {}),
+        )
+        .unwrap();
+        let GetMaxSwapValueResponse { max_swap_value } = from_json(res).unwrap();
+        assert_eq!(max_swap_value, Some(Uint128::new(1000)));
+
+        let swap_exact_amount_in =
+            |token_in: Coin, token_out_denom: &str| SudoMsg::SwapExactAmountIn {
+                sender: "someone".to_string(),
+                token_in,
+                token_out_denom: token_out_denom.to_string(),
+                token_out_min_amount: Uint128::one(),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                require_improves_balance: false,
+            };
+
+        // 1uosmo is worth 10 alloyed asset amount, 1uion is worth 1 alloyed asset amount
+        for (token_in, token_out_denom, attempted) in [
+            (Coin::new(100, "uosmo"), "uion", None),
+            (Coin::new(101, "uosmo"), "uion", Some(1010u128)),
+            (Coin::new(1000, "uion"), "uosmo", None),
+            (Coin::new(1001, "uion"), "uosmo", Some(1001u128)),
+            (Coin::new(100, "uosmo"), alloyed_denom, None),
+            (Coin::new(1001, "uion"), alloyed_denom, Some(1001u128)),
+            (Coin::new(1000, alloyed_denom), "uion", None),
+            (Coin::new(1001, alloyed_denom), "uosmo", Some(1001u128)),
+        ] {
+            let res = sudo(
+                deps.as_mut(),
+                env.clone(),
+                swap_exact_amount_in(token_in, token_out_denom),
+            );
+
+            match attempted {
+                None => assert!(res.is_ok()),
+                Some(attempted) => assert_eq!(
+                    res.unwrap_err(),
+                    ContractError::SwapTooLarge {
+                        max: Uint128::new(1000),
+                        attempted: Uint128::new(attempted),
+                    }
+                ),
+            }
+        }
+
+        // exact amount out is capped by the required token in
+        let err = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountOut {
+                sender: "someone".to_string(),
+                token_in_denom: "uion".to_string(),
+                token_in_max_amount: Uint128::new(2000),
+                token_out: Coin::new(101, "uosmo"),
+                swap_fee: Decimal::zero(),
+                require_improves_balance: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SwapTooLarge {
+                max: Uint128::new(1000),
+                attempted: Uint128::new(1010),
+            }
+        );
+
+        // joining pool is also capped
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[Coin::new(50, "uosmo"), Coin::new(501, "uion")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SwapTooLarge {
+                max: Uint128::new(1000),
+                attempted: Uint128::new(1001),
+            }
+        );
+
+        // remove max swap value
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ContractExecMsg::Transmuter(ExecMsg::SetMaxSwapValue {
+                max_swap_value: None,
+            }),
+        )
+        .unwrap();
+
+        sudo(
+            deps.as_mut(),
+            env,
+            swap_exact_amount_in(Coin::new(1001, "uion"), "uosmo"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_large_swap_cooldown() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let mut env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // join pool
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                admin,
+                &[Coin::new(1000000, "uosmo"), Coin::new(1000000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        // only admin can set large swap cooldown
+        let set_large_swap_cooldown_msg =
+            ContractExecMsg::Transmuter(ExecMsg::SetLargeSwapCooldown {
+                large_swap_cooldown: Some(Uint64::new(60_000_000_000)),
+                large_swap_threshold: Uint128::new(1000),
+            });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            set_large_swap_cooldown_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            set_large_swap_cooldown_msg,
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetLargeSwapCooldown {}),
+        )
+        .unwrap();
+        let GetLargeSwapCooldownResponse {
+            large_swap_cooldown,
+        } = from_json(res).unwrap();
+        assert_eq!(
+            large_swap_cooldown,
+            Some(LargeSwapCooldown {
+                threshold: Uint128::new(1000),
+                cooldown: Uint64::new(60_000_000_000),
+            })
+        );
+
+        let swap_exact_amount_in = |sender: &str, amount: u128| SudoMsg::SwapExactAmountIn {
+            sender: sender.to_string(),
+            token_in: Coin::new(amount, "uosmo"),
+            token_out_denom: "uion".to_string(),
+            token_out_min_amount: Uint128::one(),
+            swap_fee: Decimal::zero(),
+            partial_fill: false,
+            require_improves_balance: false,
+        };
+
+        // large swap is allowed when there is no previous large swap
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            swap_exact_amount_in("someone", 1001),
+        )
+        .unwrap();
+
+        // back-to-back large swap is rejected
+        let err = sudo(
+            deps.as_mut(),
+            env.clone(),
+            swap_exact_amount_in("someone", 1001),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SwapCooldownActive {
+                retry_after: env.block.time.plus_seconds(60),
+            }
+        );
+
+        // small swap is unaffected
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            swap_exact_amount_in("someone", 1000),
+        )
+        .unwrap();
+
+        // large swap by other sender is unaffected
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            swap_exact_amount_in("someone_else", 1001),
+        )
+        .unwrap();
+
+        // still rejected right before cooldown elapses
+        env.block.time = env.block.time.plus_seconds(59);
+        let err = sudo(
+            deps.as_mut(),
+            env.clone(),
+            swap_exact_amount_in("someone", 1001),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SwapCooldownActive {
+                retry_after: env.block.time.plus_seconds(1),
+            }
+        );
+
+        // large swap is allowed after cooldown elapses
+        env.block.time = env.block.time.plus_seconds(1);
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            swap_exact_amount_in("someone", 1001),
+        )
+        .unwrap();
+
+        // remove large swap cooldown
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ContractExecMsg::Transmuter(ExecMsg::SetLargeSwapCooldown {
+                large_swap_cooldown: None,
+                large_swap_threshold: Uint128::zero(),
+            }),
+        )
+        .unwrap();
+
+        sudo(deps.as_mut(), env, swap_exact_amount_in("someone", 1001)).unwrap();
+    }
+
+    #[test]
+    fn test_governance_log() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let moderator = "moderator";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        let mut env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        let start_time = env.block.time;
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            }),
+        )
+        .unwrap();
+
+        env.block.time = env.block.time.plus_seconds(1);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(moderator, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::MarkCorruptedAssets {
+                denoms: vec!["uion".to_string()],
+            }),
+        )
+        .unwrap();
+
+        env.block.time = env.block.time.plus_seconds(1);
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SetActive {
+                is_active: false,
+                scope: None,
+                reactivate_at: None,
+            },
+        )
+        .unwrap();
+
+        // failed action is not recorded
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus {
+                active: true,
+                scope: None,
+                reactivate_at: None,
+            }),
+        )
+        .unwrap_err();
+
+        env.block.time = env.block.time.plus_seconds(1);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(moderator, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus {
+                active: true,
+                scope: None,
+                reactivate_at: None,
+            }),
+        )
+        .unwrap();
+
+        let expected_actions = vec![
+            GovernanceAction {
+                id: 0,
+                action: "register_limiter".to_string(),
+                actor: Some(Addr::unchecked(admin)),
+                time: start_time,
+            },
+            GovernanceAction {
+                id: 1,
+                action: "mark_corrupted_assets".to_string(),
+                actor: Some(Addr::unchecked(moderator)),
+                time: start_time.plus_seconds(1),
+            },
+            GovernanceAction {
+                id: 2,
+                action: "set_active".to_string(),
+                actor: None,
+                time: start_time.plus_seconds(2),
+            },
+            GovernanceAction {
+                id: 3,
+                action: "set_active_status".to_string(),
+                actor: Some(Addr::unchecked(moderator)),
+                time: start_time.plus_seconds(3),
+            },
+        ];
+
+        let query_governance_log = |start_after: Option<u64>, limit: Option<u32>| {
+            let GovernanceLogResponse { actions } = from_json(
+                query(
+                    deps.as_ref(),
+                    env.clone(),
+                    ContractQueryMsg::Transmuter(QueryMsg::GovernanceLog { start_after, limit }),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            actions
+        };
+
+        assert_eq!(query_governance_log(None, None), expected_actions);
+        assert_eq!(query_governance_log(None, Some(2)), expected_actions[..2]);
+        assert_eq!(query_governance_log(Some(1), None), expected_actions[2..]);
+
+        // role changes are recorded
+        let candidate = "candidate";
+        for (sender, msg) in [
+            (
+                admin,
+                ExecMsg::TransferAdmin {
+                    candidate: candidate.to_string(),
+                },
+            ),
+            (admin, ExecMsg::CancelAdminTransfer {}),
+            (
+                admin,
+                ExecMsg::TransferAdmin {
+                    candidate: candidate.to_string(),
+                },
+            ),
+            (candidate, ExecMsg::RejectAdminTransfer {}),
+            (
+                admin,
+                ExecMsg::TransferAdmin {
+                    candidate: candidate.to_string(),
+                },
+            ),
+            (candidate, ExecMsg::ClaimAdmin {}),
+            (
+                candidate,
+                ExecMsg::AssignModerator {
+                    address: "new_moderator".to_string(),
+                },
+            ),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(sender, &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+            .unwrap();
+        }
+
+        let GovernanceLogResponse { actions } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::GovernanceLog {
+                    start_after: Some(3),
+                    limit: None,
+                }),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            actions
+                .into_iter()
+                .map(|action| (action.action, action.actor.unwrap().to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("transfer_admin".to_string(), admin.to_string()),
+                ("cancel_admin_transfer".to_string(), admin.to_string()),
+                ("transfer_admin".to_string(), admin.to_string()),
+                ("reject_admin_transfer".to_string(), candidate.to_string()),
+                ("transfer_admin".to_string(), admin.to_string()),
+                ("claim_admin".to_string(), candidate.to_string()),
+                ("assign_moderator".to_string(), candidate.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_alloyed_denom_metadata() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let non_admin = "non_admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            alloyed_asset_subdenom: "uosmouion".to_string(),
+            admin: Some(admin.to_string()),
+            moderator: "moderator".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        let metadata = Metadata {
+            description: "description".to_string(),
+            base: "base".to_string(),
+            display: "display".to_string(),
+            name: "name".to_string(),
+            symbol: "symbol".to_string(),
+            denom_units: vec![],
+            uri: String::new(),
+            uri_hash: String::new(),
+        };
+
+        // Attempt to set alloyed denom metadata by a non-admin user.
+        let non_admin_info = mock_info(non_admin, &[]);
+        let non_admin_msg = ContractExecMsg::Transmuter(ExecMsg::SetAlloyedDenomMetadata {
+            metadata: metadata.clone(),
+        });
+        let err = execute(deps.as_mut(), env.clone(), non_admin_info, non_admin_msg).unwrap_err();
+
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // Set the alloyed denom metadata by admin.
+        let msg = ContractExecMsg::Transmuter(ExecMsg::SetAlloyedDenomMetadata {
+            metadata: metadata.clone(),
+        });
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        assert_eq!(
+            res.attributes,
+            vec![attr("method", "set_alloyed_denom_metadata")]
+        );
+
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(MsgSetDenomMetadata {
+                sender: env.contract.address.to_string(),
+                metadata: Some(metadata)
+            })]
+        );
+
+        // only admin can invalidate cached denom metadata
+        let invalidate_denom_metadata = |deps: DepsMut, sender: &str| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::InvalidateDenomMetadata {
+                    denom: "uosmo".to_string(),
+                }),
+            )
+        };
+        let err = invalidate_denom_metadata(deps.as_mut(), non_admin).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let res = invalidate_denom_metadata(deps.as_mut(), admin).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "invalidate_denom_metadata"),
+                attr("denom", "uosmo"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_join_pool() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let user = "user";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // join pool with amount 0 coin should error
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        let info = mock_info(user, &[Coin::new(1000, "uion"), Coin::new(0, "uosmo")]);
+        let err = execute(deps.as_mut(), env.clone(), info, join_pool_msg).unwrap_err();
+
+        assert_eq!(err, ContractError::ZeroValueOperation {});
+
+        // join pool properly works
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        let info = mock_info(user, &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]);
+        execute(deps.as_mut(), env.clone(), info, join_pool_msg).unwrap();
+
+        // Check pool asset
+        let GetTotalPoolLiquidityResponse {
+            total_pool_liquidity,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            total_pool_liquidity,
+            vec![Coin::new(1000, "uosmo"), Coin::new(1000, "uion")]
+        );
+    }
+
+    #[test]
+    fn test_exit_pool() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let user = "user";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // join pool by others for sufficient amount
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        let info = mock_info(admin, &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]);
+        execute(deps.as_mut(), env.clone(), info, join_pool_msg).unwrap();
+
+        // User tries to exit pool
+        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+            tokens_out: vec![Coin::new(1000, "uion"), Coin::new(1000, "uosmo")],
+        });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(user, &[]),
+            exit_pool_msg,
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::InsufficientShares {
+                required: 2000u128.into(),
+                available: Uint128::zero()
+            }
+        );
+        // User tries to join pool
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(user, &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]),
+            join_pool_msg,
+        );
+        assert!(res.is_ok());
+
+        deps.querier
+            .update_balance(user, vec![Coin::new(2000, alloyed_denom)]);
+
+        // User tries to exit pool with zero amount
+        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+            tokens_out: vec![Coin::new(0, "uion"), Coin::new(1, "uosmo")],
+        });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(user, &[]),
+            exit_pool_msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ZeroValueOperation {});
+
+        // User tries to exit pool again
+        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+            tokens_out: vec![Coin::new(1000, "uion"), Coin::new(1000, "uosmo")],
+        });
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(user, &[]),
+            exit_pool_msg,
+        )
+        .unwrap();
+
+        let expected = Response::new()
+            .add_attribute("method", "exit_pool")
+            .add_event(alloyed_asset_transfer_event(
+                "burn",
+                user,
+                "",
+                &Coin::new(2000u128, alloyed_denom),
+            ))
+            .add_message(MsgBurn {
+                sender: env.contract.address.to_string(),
+                amount: Some(Coin::new(2000u128, alloyed_denom).into()),
+                burn_from_address: user.to_string(),
+            })
+            .add_message(BankMsg::Send {
+                to_address: user.to_string(),
+                amount: vec![Coin::new(1000, "uion"), Coin::new(1000, "uosmo")],
+            });
+
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_shares_and_liquidity() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let user_1 = "user_1";
+        let user_2 = "user_2";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // Join pool
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(user_1, &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]),
+            join_pool_msg,
+        )
+        .unwrap();
+
+        // Update alloyed asset denom balance for user
+        deps.querier
+            .update_balance(user_1, vec![Coin::new(2000, "usomoion")]);
+
+        // Query the shares of the user
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetShares {
+                address: user_1.to_string(),
+            }),
+        )
+        .unwrap();
+        let shares: GetSharesResponse = from_json(res).unwrap();
+        assert_eq!(shares.shares.u128(), 2000u128);
+
+        // Query the total shares
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetTotalShares {}),
+        )
+        .unwrap();
+        let total_shares: GetTotalSharesResponse = from_json(res).unwrap();
+        assert_eq!(total_shares.total_shares.u128(), 2000u128);
+
+        // Query the total pool liquidity
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+        )
+        .unwrap();
+        let total_pool_liquidity: GetTotalPoolLiquidityResponse = from_json(res).unwrap();
+        assert_eq!(
+            total_pool_liquidity.total_pool_liquidity,
+            vec![Coin::new(1000, "uosmo"), Coin::new(1000, "uion")]
+        );
+
+        // Join pool
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(user_2, &[Coin::new(1000, "uion")]),
+            join_pool_msg,
+        )
+        .unwrap();
+
+        // Update balance for user 2
+        deps.querier
+            .update_balance(user_2, vec![Coin::new(1000, "usomoion")]);
+
+        // Query the total shares
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetTotalShares {}),
+        )
+        .unwrap();
+
+        let total_shares: GetTotalSharesResponse = from_json(res).unwrap();
+
+        assert_eq!(total_shares.total_shares.u128(), 3000u128);
+
+        // Query the total pool liquidity
+        let res = query(
+            deps.as_ref(),
+            env,
+            ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+        )
+        .unwrap();
+
+        let total_pool_liquidity: GetTotalPoolLiquidityResponse = from_json(res).unwrap();
+
+        assert_eq!(
+            total_pool_liquidity.total_pool_liquidity,
+            vec![Coin::new(1000, "uosmo"), Coin::new(2000, "uion")]
+        );
+    }
+
+    #[test]
+    fn test_denom() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // Query the share denom
+        let res = query(
+            deps.as_ref(),
+            env,
+            ContractQueryMsg::Transmuter(QueryMsg::GetShareDenom {}),
+        )
+        .unwrap();
+
+        let share_denom: GetShareDenomResponse = from_json(res).unwrap();
+        assert_eq!(share_denom.share_denom, "usomoion");
+    }
+
+    #[test]
+    fn test_pool_denoms() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "uosmo"),
+                Coin::new(1, "uion"),
+                Coin::new(1, "new_asset"),
+            ],
+        );
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // Query pool denoms, alloyed denom is excluded
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::PoolDenoms {}),
+        )
+        .unwrap();
+
+        let PoolDenomsResponse { denoms } = from_json(res).unwrap();
+        // denoms are sorted regardless of insertion order
+        assert_eq!(denoms, vec!["uion", "uosmo"]);
+
+        // add new asset
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ContractExecMsg::Transmuter(ExecMsg::AddNewAssets {
+                asset_configs: vec![AssetConfig::from_denom_str("new_asset")],
+            }),
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env,
+            ContractQueryMsg::Transmuter(QueryMsg::PoolDenoms {}),
+        )
+        .unwrap();
+
+        let PoolDenomsResponse { denoms } = from_json(res).unwrap();
+        assert_eq!(denoms, vec!["new_asset", "uion", "uosmo"]);
+    }
+
+    #[test]
+    fn test_backing() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig {
+                    denom: "uosmo".to_string(),
+                    normalization_factor: Uint128::one(),
+                },
+                AssetConfig {
+                    denom: "uion".to_string(),
+                    normalization_factor: Uint128::new(10),
+                },
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::new(10),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        let query_backing = |deps: cosmwasm_std::Deps| -> BackingResponse {
+            from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::Backing {}),
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+
+        // empty pool has no backing
+        assert_eq!(
+            query_backing(deps.as_ref()),
+            BackingResponse {
+                backing: vec![
+                    DenomBacking {
+                        denom: "uion".to_string(),
+                        amount: Uint128::zero(),
+                        share: Decimal::zero(),
+                    },
+                    DenomBacking {
+                        denom: "uosmo".to_string(),
+                        amount: Uint128::zero(),
+                        share: Decimal::zero(),
+                    },
+                ],
+                total_backing: Uint128::zero(),
+            }
+        );
+
+        // join pool
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[Coin::new(1000, "uosmo"), Coin::new(30000, "uion")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let res = query_backing(deps.as_ref());
+        assert_eq!(
+            res,
+            BackingResponse {
+                backing: vec![
+                    DenomBacking {
+                        denom: "uion".to_string(),
+                        amount: Uint128::new(30000),
+                        share: Decimal::percent(75),
+                    },
+                    DenomBacking {
+                        denom: "uosmo".to_string(),
+                        amount: Uint128::new(10000),
+                        share: Decimal::percent(25),
+                    },
+                ],
+                total_backing: Uint128::new(40000),
+            }
+        );
+
+        // backing shares sum to one and match weights
+        assert_eq!(
+            res.backing.iter().map(|b| b.share).sum::<Decimal>(),
+            Decimal::one()
+        );
+
+        let weights = Transmuter::default()
+            .pool
+            .load(&deps.storage)
+            .unwrap()
+            .weights()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            weights,
+            res.backing
+                .into_iter()
+                .map(|b| (b.denom, b.share))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_backing_after_corruption() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "uosmo"),
+                Coin::new(1, "uion"),
+                Coin::new(1, "uatom"),
+            ],
+        );
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+                AssetConfig::from_denom_str("uatom"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "provider",
+                &[
+                    Coin::new(1_000, "uosmo"),
+                    Coin::new(2_000, "uion"),
+                    Coin::new(1_000, "uatom"),
+                ],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let backing_after_corruption = |deps: cosmwasm_std::Deps, denom: &str| {
+            query(
+                deps,
+                mock_env(),
+                ContractQueryMsg::Transmuter(QueryMsg::BackingAfterCorruption {
+                    denom: denom.to_string(),
+                }),
+            )
+            .map(|res| from_json::<BackingResponse>(res).unwrap())
+        };
+
+        // uion's contribution is removed and the rest is renormalized
+        assert_eq!(
+            backing_after_corruption(deps.as_ref(), "uion").unwrap(),
+            BackingResponse {
+                backing: vec![
+                    DenomBacking {
+                        denom: "uatom".to_string(),
+                        amount: Uint128::new(1_000),
+                        share: Decimal::percent(50),
+                    },
+                    DenomBacking {
+                        denom: "uosmo".to_string(),
+                        amount: Uint128::new(1_000),
+                        share: Decimal::percent(50),
+                    },
+                ],
+                total_backing: Uint128::new(2_000),
+            }
+        );
+
+        // actual backing is unchanged
+        let BackingResponse {
+            backing,
+            total_backing,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                ContractQueryMsg::Transmuter(QueryMsg::Backing {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(total_backing, Uint128::new(4_000));
+        assert_eq!(
+            backing
+                .into_iter()
+                .map(|b| (b.denom, b.share))
+                .collect::<Vec<_>>(),
+            vec![
+                ("uatom".to_string(), Decimal::percent(25)),
+                ("uion".to_string(), Decimal::percent(50)),
+                ("uosmo".to_string(), Decimal::percent(25)),
+            ]
+        );
+
+        // not a pool asset
+        let err = backing_after_corruption(deps.as_ref(), "ubtc").unwrap_err();
+        assert!(matches!(err, ContractError::InvalidTransmuteDenom { .. }));
+    }
+
+    #[test]
+    fn test_balance_reconciliation() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // join pool
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        let info = mock_info(admin, &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]);
+        execute(deps.as_mut(), env.clone(), info, join_pool_msg).unwrap();
+
+        // bank balance of the contract is out of sync for uion
+        deps.querier.update_balance(
+            env.contract.address.to_string(),
+            vec![Coin::new(1000, "uosmo"), Coin::new(999, "uion")],
+        );
+
+        let res = query(
+            deps.as_ref(),
+            env,
+            ContractQueryMsg::Transmuter(QueryMsg::BalanceReconciliation {}),
+        )
+        .unwrap();
+
+        let BalanceReconciliationResponse { balances } = from_json(res).unwrap();
+        assert_eq!(
+            balances,
+            vec![
+                BalanceReconciliation {
+                    denom: "uosmo".to_string(),
+                    tracked: Uint128::new(1000),
+                    actual: Uint128::new(1000),
+                    is_mismatched: false,
+                },
+                BalanceReconciliation {
+                    denom: "uion".to_string(),
+                    tracked: Uint128::new(1000),
+                    actual: Uint128::new(999),
+                    is_mismatched: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spot_price() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "uosmoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "uosmoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // Test spot price with same denom
+        let err = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
+                quote_asset_denom: "uosmo".to_string(),
+                base_asset_denom: "uosmo".to_string(),
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::SpotPriceQueryFailed {
+                reason: "quote_asset_denom and base_asset_denom cannot be the same".to_string()
+            }
+        );
+
+        // Test spot price with denom not in swappable assets
+        let err = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
+                quote_asset_denom: "uatom".to_string(),
+                base_asset_denom: "uosmo".to_string(),
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::SpotPriceQueryFailed {
+                reason: "quote_asset_denom is not in swappable assets: must be one of [\"uion\", \"uosmo\", \"uosmoion\"] but got uatom".to_string()
+            }
+        );
+
+        let err = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
+                quote_asset_denom: "uion".to_string(),
+                base_asset_denom: "uatom".to_string(),
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::SpotPriceQueryFailed {
+                reason: "base_asset_denom is not in swappable assets: must be one of [\"uion\", \"uosmo\", \"uosmoion\"] but got uatom".to_string()
+            }
+        );
+
+        // Test spot price with pool assets
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
+                quote_asset_denom: "uosmo".to_string(),
+                base_asset_denom: "uion".to_string(),
+            }),
+        )
+        .unwrap();
+
+        let spot_price: SpotPriceResponse = from_json(res).unwrap();
+        assert_eq!(spot_price.spot_price, Decimal::one());
+
+        // Test spot price with alloyed denom
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
+                quote_asset_denom: "uosmo".to_string(),
+                base_asset_denom: alloyed_denom.to_string(),
+            }),
+        )
+        .unwrap();
+
+        let spot_price: SpotPriceResponse = from_json(res).unwrap();
+        assert_eq!(spot_price.spot_price, Decimal::one());
+
+        let res = query(
+            deps.as_ref(),
+            env,
+            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
+                quote_asset_denom: alloyed_denom.to_string(),
+                base_asset_denom: "uion".to_string(),
+            }),
+        )
+        .unwrap();
+
+        let spot_price: SpotPriceResponse = from_json(res).unwrap();
+        assert_eq!(spot_price.spot_price, Decimal::one());
+    }
+
+    #[test]
+    fn test_spot_price_with_different_norm_factor() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "tbtc"), Coin::new(1, "nbtc")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig {
+                    denom: "tbtc".to_string(),
+                    normalization_factor: Uint128::from(1u128),
+                },
+                AssetConfig {
+                    denom: "nbtc".to_string(),
+                    normalization_factor: Uint128::from(100u128),
+                },
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "allbtc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::from(100u128),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "allbtc";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // Test spot price with pool assets
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
+                base_asset_denom: "nbtc".to_string(),
+                quote_asset_denom: "tbtc".to_string(),
+            }),
+        )
+        .unwrap();
+
+        // tbtc/1 = nbtc/100
+        // tbtc = 1nbtc/100
+        let spot_price: SpotPriceResponse = from_json(res).unwrap();
+        assert_eq!(spot_price.spot_price, Decimal::from_ratio(1u128, 100u128));
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
+                base_asset_denom: "tbtc".to_string(),
+                quote_asset_denom: "nbtc".to_string(),
+            }),
+        )
+        .unwrap();
+
+        // nbtc/100 = tbtc/1
+        // nbtc = 100tbtc
+        let spot_price: SpotPriceResponse = from_json(res).unwrap();
+        assert_eq!(spot_price.spot_price, Decimal::from_ratio(100u128, 1u128));
+
+        // Test spot price with alloyed denom
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
+                quote_asset_denom: "nbtc".to_string(),
+                base_asset_denom: alloyed_denom.to_string(),
+            }),
+        )
+        .unwrap();
+
+        // nbtc/100 = allbtc/100
+        // nbtc = 1allbtc
+        let spot_price: SpotPriceResponse = from_json(res).unwrap();
+        assert_eq!(spot_price.spot_price, Decimal::one());
+
+        let res = query(
+            deps.as_ref(),
+            env,
+            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
+                quote_asset_denom: alloyed_denom.to_string(),
+                base_asset_denom: "tbtc".to_string(),
+            }),
+        )
+        .unwrap();
+
+        // allbtc/100 = tbtc/1
+        // tbtc = 100allbtc
+        let spot_price: SpotPriceResponse = from_json(res).unwrap();
+        assert_eq!(spot_price.spot_price, Decimal::from_ratio(100u128, 1u128));
+    }
+
+    #[test]
+    fn test_calc_out_amt_given_in() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![Coin::new(1, "axlusdc"), Coin::new(1, "whusdc")],
+        );
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("axlusdc"),
+                AssetConfig::from_denom_str("whusdc"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "alloyedusdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "alloyedusdc";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // join pool
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                admin,
+                &[Coin::new(1000, "axlusdc"), Coin::new(2000, "whusdc")],
+            ),
+            join_pool_msg,
+        )
+        .unwrap();
+
+        struct Case {
+            name: String,
+            token_in: Coin,
+            token_out_denom: String,
+            swap_fee: Decimal,
+            expected: Result<CalcOutAmtGivenInResponse, ContractError>,
+        }
+
+        for Case {
+            name,
+            token_in,
+            token_out_denom,
+            swap_fee,
+            expected,
+        } in vec![
+            Case {
+                name: String::from("axlusdc to whusdc - ok"),
+                token_in: Coin::new(1000, "axlusdc"),
+                token_out_denom: "whusdc".to_string(),
+                swap_fee: Decimal::zero(),
+                expected: Ok(CalcOutAmtGivenInResponse {
+                    token_out: Coin::new(1000, "whusdc"),
+                }),
+            },
+            Case {
+                name: String::from("whusdc to axlusdc - ok"),
+                token_in: Coin::new(1000, "whusdc"),
+                token_out_denom: "axlusdc".to_string(),
+                swap_fee: Decimal::zero(),
+                expected: Ok(CalcOutAmtGivenInResponse {
+                    token_out: Coin::new(1000, "axlusdc"),
+                }),
+            },
+            Case {
+                name: String::from("whusdc to axlusdc - token out not enough"),
+                token_in: Coin::new(1001, "whusdc"),
+                token_out_denom: "axlusdc".to_string(),
+                swap_fee: Decimal::zero(),
+                expected: Err(ContractError::InsufficientPoolAsset {
+                    required: Coin::new(1001, "axlusdc"),
+                    available: Coin::new(1000, "axlusdc"),
+                }),
+            },
+            Case {
+                name: String::from("same denom error (pool asset)"),
+                token_in: Coin::new(1000, "axlusdc"),
+                token_out_denom: "axlusdc".to_string(),
+                swap_fee: Decimal::zero(),
+                expected: Err(ContractError::SameDenomNotAllowed {
+                    denom: "axlusdc".to_string(),
+                }),
+            },
+            Case {
+                name: String::from("same denom error (alloyed asset)"),
+                token_in: Coin::new(1000, "alloyedusdc"),
+                token_out_denom: "alloyedusdc".to_string(),
+                swap_fee: Decimal::zero(),
+                expected: Err(ContractError::SameDenomNotAllowed {
+                    denom: "alloyedusdc".to_string(),
+                }),
+            },
+            Case {
+                name: String::from("alloyedusdc to axlusdc - ok"),
+                token_in: Coin::new(1000, "alloyedusdc"),
+                token_out_denom: "axlusdc".to_string(),
+                swap_fee: Decimal::zero(),
+                expected: Ok(CalcOutAmtGivenInResponse {
+                    token_out: Coin::new(1000, "axlusdc"),
+                }),
+            },
+            Case {
+                name: String::from("alloyedusdc to whusdc - ok"),
+                token_in: Coin::new(1000, "alloyedusdc"),
+                token_out_denom: "whusdc".to_string(),
+                swap_fee: Decimal::zero(),
+                expected: Ok(CalcOutAmtGivenInResponse {
+                    token_out: Coin::new(1000, "whusdc"),
+                }),
+            },
+            Case {
+                name: String::from("alloyedusdc to axlusdc - token out not enough"),
+                token_in: Coin::new(1001, "alloyedusdc"),
+                token_out_denom: "axlusdc".to_string(),
+                swap_fee: Decimal::zero(),
+                expected: Err(ContractError::InsufficientPoolAsset {
+                    required: Coin::new(1001, "axlusdc"),
+                    available: Coin::new(1000, "axlusdc"),
+                }),
+            },
+            Case {
+                name: String::from("axlusdc to alloyedusdc - ok"),
+                token_in: Coin::new(1000, "axlusdc"),
+                token_out_denom: "alloyedusdc".to_string(),
+                swap_fee: Decimal::zero(),
+                expected: Ok(CalcOutAmtGivenInResponse {
+                    token_out: Coin::new(1000, "alloyedusdc"),
+                }),
+            },
+            Case {
+                name: String::from("whusdc to alloyedusdc - ok"),
+                token_in: Coin::new(1000, "whusdc"),
+                token_out_denom: "alloyedusdc".to_string(),
+                swap_fee: Decimal::zero(),
+                expected: Ok(CalcOutAmtGivenInResponse {
+                    token_out: Coin::new(1000, "alloyedusdc"),
+                }),
+            },
+            Case {
+                name: String::from("invalid swap fee"),
+                token_in: Coin::new(1000, "axlusdc"),
+                token_out_denom: "whusdc".to_string(),
+                swap_fee: Decimal::percent(1),
+                expected: Err(ContractError::InvalidSwapFee {
+                    expected: Decimal::zero(),
+                    actual: Decimal::percent(1),
+                }),
+            },
+            Case {
+                name: String::from("invalid swap fee (alloyed asset as token in)"),
+                token_in: Coin::new(1000, "alloyedusdc"),
+                token_out_denom: "whusdc".to_string(),
+                swap_fee: Decimal::percent(1),
+                expected: Err(ContractError::InvalidSwapFee {
+                    expected: Decimal::zero(),
+                    actual: Decimal::percent(1),
+                }),
+            },
+            Case {
+                name: String::from("invalid swap fee (alloyed asset as token out)"),
+                token_in: Coin::new(1000, "axlusdc"),
+                token_out_denom: "alloyedusdc".to_string(),
+                swap_fee: Decimal::percent(2),
+                expected: Err(ContractError::InvalidSwapFee {
+                    expected: Decimal::zero(),
+                    actual: Decimal::percent(2),
+                }),
+            },
+        ] {
+            let res = query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::CalcOutAmtGivenIn {
+                    token_in: token_in.clone(),
+                    token_out_denom: token_out_denom.clone(),
+                    swap_fee,
+                }),
+            )
+            .map(|value| from_json(value).unwrap());
+
+            assert_eq!(res, expected, "case: {}", name);
+        }
+    }
+
+    #[test]
+    fn test_calc_in_amt_given_out() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![Coin::new(1, "axlusdc"), Coin::new(1, "whusdc")],
+        );
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("axlusdc"),
+                AssetConfig::from_denom_str("whusdc"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "alloyedusdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "alloyedusdc";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // join pool
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                admin,
+                &[Coin::new(1000, "axlusdc"), Coin::new(2000, "whusdc")],
+            ),
+            join_pool_msg,
+        )
+        .unwrap();
+
+        struct Case {
+            name: String,
+            token_in_denom: String,
+            token_out: Coin,
+            swap_fee: Decimal,
+            expected: Result<CalcInAmtGivenOutResponse, ContractError>,
+        }
+
+        for Case {
+            name,
+            token_in_denom,
+            token_out,
+            swap_fee,
+            expected,
+        } in vec![
+            Case {
+                name: String::from("axlusdc to whusdc - ok"),
+                token_in_denom: "axlusdc".to_string(),
+                token_out: Coin::new(1000, "whusdc"),
+                swap_fee: Decimal::zero(),
+                expected: Ok(CalcInAmtGivenOutResponse {
+                    token_in: Coin::new(1000, "axlusdc"),
+                }),
+            },
+            Case {
+                name: String::from("whusdc to axlusdc - ok"),
+                token_in_denom: "whusdc".to_string(),
+                token_out: Coin::new(1000, "axlusdc"),
+                swap_fee: Decimal::zero(),
+                expected: Ok(CalcInAmtGivenOutResponse {
+                    token_in: Coin::new(1000, "whusdc"),
+                }),
+            },
+            Case {
+                name: String::from("whusdc to axlusdc - token out not enough"),
+                token_in_denom: "whusdc".to_string(),
+                token_out: Coin::new(1001, "axlusdc"),
+                swap_fee: Decimal::zero(),
+                expected: Err(ContractError::InsufficientPoolAsset {
+                    required: Coin::new(1001, "axlusdc"),
+                    available: Coin::new(1000, "axlusdc"),
+                }),
+            },
+            Case {
+                name: String::from("same denom error (pool asset)"),
+                token_in_denom: "axlusdc".to_string(),
+                token_out: Coin::new(1000, "axlusdc"),
+                swap_fee: Decimal::zero(),
+                expected: Err(ContractError::SameDenomNotAllowed {
+                    denom: "axlusdc".to_string(),
+                }),
+            },
+            Case {
+                name: String::from("same denom error (alloyed asset)"),
+                token_in_denom: "alloyedusdc".to_string(),
+                token_out: Coin::new(1000, "alloyedusdc"),
+                swap_fee: Decimal::zero(),
+                expected: Err(ContractError::SameDenomNotAllowed {
+                    denom: "alloyedusdc".to_string(),
+                }),
+            },
+            Case {
+                name: String::from("alloyedusdc to axlusdc - ok"),
+                token_in_denom: "alloyedusdc".to_string(),
+                token_out: Coin::new(1000, "axlusdc"),
+                swap_fee: Decimal::zero(),
+                expected: Ok(CalcInAmtGivenOutResponse {
+                    token_in: Coin::new(1000, "alloyedusdc"),
+                }),
+            },
+            Case {
+                name: String::from("alloyedusdc to whusdc - ok"),
+                token_in_denom: "alloyedusdc".to_string(),
+                token_out: Coin::new(1000, "whusdc"),
+                swap_fee: Decimal::zero(),
+                expected: Ok(CalcInAmtGivenOutResponse {
+                    token_in: Coin::new(1000, "alloyedusdc"),
+                }),
+            },
+            Case {
+                name: String::from("alloyedusdc to axlusdc - token out not enough"),
+                token_in_denom: "alloyedusdc".to_string(),
+                token_out: Coin::new(1001, "axlusdc"),
+                swap_fee: Decimal::zero(),
+                expected: Err(ContractError::InsufficientPoolAsset {
+                    required: Coin::new(1001, "axlusdc"),
+                    available: Coin::new(1000, "axlusdc"),
+                }),
+            },
+            Case {
+                name: String::from("pool asset to alloyed asset - ok"),
+                token_in_denom: "axlusdc".to_string(),
+                token_out: Coin::new(1000, "alloyedusdc"),
+                swap_fee: Decimal::zero(),
+                expected: Ok(CalcInAmtGivenOutResponse {
+                    token_in: Coin::new(1000, "axlusdc"),
+                }),
+            },
+            Case {
+                name: String::from("invalid swap fee"),
+                token_in_denom: "whusdc".to_string(),
+                token_out: Coin::new(1000, "axlusdc"),
+                swap_fee: Decimal::percent(1),
+                expected: Err(ContractError::InvalidSwapFee {
+                    expected: Decimal::zero(),
+                    actual: Decimal::percent(1),
+                }),
+            },
+            Case {
+                name: String::from("invalid swap fee (alloyed asset as token in)"),
+                token_in_denom: "alloyedusdc".to_string(),
+                token_out: Coin::new(1000, "axlusdc"),
+                swap_fee: Decimal::percent(1),
+                expected: Err(ContractError::InvalidSwapFee {
+                    expected: Decimal::zero(),
+                    actual: Decimal::percent(1),
+                }),
+            },
+            Case {
+                name: String::from("invalid swap fee (alloyed asset as token out)"),
+                token_in_denom: "whusdc".to_string(),
+                token_out: Coin::new(1000, "alloyedusdc"),
+                swap_fee: Decimal::percent(2),
+                expected: Err(ContractError::InvalidSwapFee {
+                    expected: Decimal::zero(),
+                    actual: Decimal::percent(2),
+                }),
+            },
+        ] {
+            let res = query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::CalcInAmtGivenOut {
+                    token_in_denom: token_in_denom.clone(),
+                    token_out: token_out.clone(),
+                    swap_fee,
+                }),
+            )
+            .map(|value| from_json(value).unwrap());
+
+            assert_eq!(res, expected, "case: {}", name);
+        }
+    }
+
+    #[test]
+    fn test_rescale_normalization_factor() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![Coin::new(1, "axlusdc"), Coin::new(1, "whusdc")],
+        );
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("axlusdc"),
+                AssetConfig::from_denom_str("whusdc"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "alloyedusdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::from(100u128),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "alloyedusdc";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // list asset configs
+        let res: Result<ListAssetConfigsResponse, ContractError> = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::ListAssetConfigs {}),
+        )
+        .map(|value| from_json(value).unwrap());
+
+        assert_eq!(
+            res,
+            Ok(ListAssetConfigsResponse {
+                asset_configs: vec![
+                    AssetConfig::from_denom_str("axlusdc"),
+                    AssetConfig::from_denom_str("whusdc"),
+                    AssetConfig {
+                        denom: alloyed_denom.to_string(),
+                        normalization_factor: Uint128::from(100u128),
+                    }
+                ]
+            })
+        );
+
+        // scale up
+        let rescale_msg = ContractExecMsg::Transmuter(ExecMsg::RescaleNormalizationFactor {
+            numerator: Uint128::from(100u128),
+            denominator: Uint128::one(),
+        });
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            rescale_msg,
+        )
+        .unwrap();
+
+        // list asset configs
+        let res: Result<ListAssetConfigsResponse, ContractError> = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::ListAssetConfigs {}),
+        )
+        .map(|value| from_json(value).unwrap());
+
+        assert_eq!(
+            res,
+            Ok(ListAssetConfigsResponse {
+                asset_configs: vec![
+                    AssetConfig {
+                        denom: "axlusdc".to_string(),
+                        normalization_factor: Uint128::from(100u128),
+                    },
+                    AssetConfig {
+                        denom: "whusdc".to_string(),
+                        normalization_factor: Uint128::from(100u128),
+                    },
+                    AssetConfig {
+                        denom: alloyed_denom.to_string(),
+                        normalization_factor: Uint128::from(10000u128),
+                    }
+                ]
+            })
+        );
+
+        // scale down
+        let rescale_msg = ContractExecMsg::Transmuter(ExecMsg::RescaleNormalizationFactor {
+            numerator: Uint128::one(),
+            denominator: Uint128::from(100u128),
+        });
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            rescale_msg,
+        )
+        .unwrap();
+
+        // list asset configs
+        let res: Result<ListAssetConfigsResponse, ContractError> = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::ListAssetConfigs {}),
+        )
+        .map(|value| from_json(value).unwrap());
+
+        assert_eq!(
+            res,
+            Ok(ListAssetConfigsResponse {
+                asset_configs: vec![
+                    AssetConfig {
+                        denom: "axlusdc".to_string(),
+                        normalization_factor: Uint128::from(1u128),
+                    },
+                    AssetConfig {
+                        denom: "whusdc".to_string(),
+                        normalization_factor: Uint128::from(1u128),
+                    },
+                    AssetConfig {
+                        denom: alloyed_denom.to_string(),
+                        normalization_factor: Uint128::from(100u128),
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_snapshot_at() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let mut env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // join pool
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[Coin::new(1000, "uosmo"), Coin::new(1000, "uion")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        // zero interval is rejected
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::SetPoolSnapshotInterval { interval: Some(0) }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ZeroSnapshotInterval {});
+
+        // only admin can set pool snapshot interval
+        let set_interval_msg =
+            ContractExecMsg::Transmuter(ExecMsg::SetPoolSnapshotInterval { interval: Some(10) });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            set_interval_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(deps.as_mut(), env.clone(), info.clone(), set_interval_msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetPoolSnapshotInterval {}),
+        )
+        .unwrap();
+        let GetPoolSnapshotIntervalResponse { interval } = from_json(res).unwrap();
+        assert_eq!(interval, Some(10));
+
+        let snapshot_at = |deps: cosmwasm_std::Deps, height: u64| {
+            let res = query(
+                deps,
+                mock_env(),
+                ContractQueryMsg::Transmuter(QueryMsg::SnapshotAt { height }),
+            )
+            .unwrap();
+            let SnapshotAtResponse { snapshot } = from_json(res).unwrap();
+            snapshot
+        };
+
+        let start_height = env.block.height;
+
+        // swap 100 uosmo for uion at each height, snapshot is taken every 10 blocks
+        for height in [start_height, start_height + 5, start_height + 10] {
+            env.block.height = height;
+            sudo(
+                deps.as_mut(),
+                env.clone(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: "someone".to_string(),
+                    token_in: Coin::new(100, "uosmo"),
+                    token_out_denom: "uion".to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+            .unwrap();
+        }
+
+        // no snapshot before the first swap
+        assert_eq!(snapshot_at(deps.as_ref(), start_height - 1), None);
+
+        let first = snapshot_at(deps.as_ref(), start_height).unwrap();
+        assert_eq!(first.height, start_height);
+        assert_eq!(
+            first.pool_assets,
+            vec![Coin::new(1100, "uosmo"), Coin::new(900, "uion")]
+        );
+        assert_eq!(
+            first.weights,
+            vec![
+                ("uion".to_string(), Decimal::percent(45)),
+                ("uosmo".to_string(), Decimal::percent(55)),
+            ]
+        );
+
+        // swap within the interval does not take a snapshot
+        assert_eq!(snapshot_at(deps.as_ref(), start_height + 9).unwrap(), first);
+
+        let second = snapshot_at(deps.as_ref(), start_height + 100).unwrap();
+        assert_eq!(second.height, start_height + 10);
+        assert_eq!(
+            second.pool_assets,
+            vec![Coin::new(1300, "uosmo"), Coin::new(700, "uion")]
+        );
+    }
+
+    #[test]
+    fn test_max_total_liquidity() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig {
+                    denom: "uosmo".to_string(),
+                    normalization_factor: Uint128::one(),
+                },
+                AssetConfig {
+                    denom: "uion".to_string(),
+                    normalization_factor: Uint128::new(10),
+                },
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::new(10),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // only admin can set max total liquidity
+        let set_max_total_liquidity_msg =
+            ContractExecMsg::Transmuter(ExecMsg::SetMaxTotalLiquidity {
+                max_total_liquidity: Some(Uint128::new(30000)),
+            });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            set_max_total_liquidity_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            set_max_total_liquidity_msg,
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetMaxTotalLiquidity {}),
+        )
+        .unwrap();
+        let GetMaxTotalLiquidityResponse {
+            max_total_liquidity,
+        } = from_json(res).unwrap();
+        assert_eq!(max_total_liquidity, Some(Uint128::new(30000)));
+
+        let join_pool = |deps: DepsMut, tokens_in: &[Coin]| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info("someone", tokens_in),
+                ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                    min_shares_out: None,
+                }),
+            )
+        };
+
+        // 1uosmo is worth 10 alloyed asset amount, 1uion is worth 1 alloyed asset amount
+        // below the cap
+        join_pool(
+            deps.as_mut(),
+            &[Coin::new(1000, "uosmo"), Coin::new(10000, "uion")],
+        )
+        .unwrap();
+
+        // exceeding the cap
+        let err = join_pool(deps.as_mut(), &[Coin::new(1001, "uosmo")]).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::TvlCapExceeded {
+                max: Uint128::new(30000),
+                attempted: Uint128::new(30010),
+            }
+        );
+
+        // swapping into alloyed asset is also capped
+        let err = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountIn {
+                sender: "someone".to_string(),
+                token_in: Coin::new(10001, "uion"),
+                token_out_denom: alloyed_denom.to_string(),
+                token_out_min_amount: Uint128::one(),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                require_improves_balance: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::TvlCapExceeded {
+                max: Uint128::new(30000),
+                attempted: Uint128::new(30001),
+            }
+        );
+
+        // exactly at the cap
+        join_pool(deps.as_mut(), &[Coin::new(1000, "uosmo")]).unwrap();
+
+        // swaps between pool assets are unaffected
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountIn {
+                sender: "someone".to_string(),
+                token_in: Coin::new(100, "uosmo"),
+                token_out_denom: "uion".to_string(),
+                token_out_min_amount: Uint128::one(),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                require_improves_balance: false,
+            },
+        )
+        .unwrap();
+
+        // removing the cap allows joining again
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ContractExecMsg::Transmuter(ExecMsg::SetMaxTotalLiquidity {
+                max_total_liquidity: None,
+            }),
+        )
+        .unwrap();
+
+        join_pool(deps.as_mut(), &[Coin::new(1, "uosmo")]).unwrap();
+    }
+
+    #[test]
+    fn test_effective_swap_fee() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "uosmo"),
+                Coin::new(1, "uion"),
+                Coin::new(1, "uatom"),
+            ],
+        );
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+                AssetConfig::from_denom_str("uatom"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::SetSwapFee {
+                swap_fee: Decimal::permille(2),
+            }),
+        )
+        .unwrap();
+
+        for (denom, swap_fee) in [
+            ("uosmo", Decimal::permille(1)),
+            ("uion", Decimal::permille(5)),
+            (alloyed_denom, Decimal::permille(3)),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                ContractExecMsg::Transmuter(ExecMsg::SetDenomSwapFee {
+                    denom: denom.to_string(),
+                    swap_fee: Some(swap_fee),
+                }),
+            )
+            .unwrap();
+        }
+
+        let effective_swap_fee = |token_in_denom: &str, token_out_denom: &str| {
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::EffectiveSwapFee {
+                    token_in_denom: token_in_denom.to_string(),
+                    token_out_denom: token_out_denom.to_string(),
+                }),
+            )
+            .map(|res| from_json::<EffectiveSwapFeeResponse>(res).unwrap().swap_fee)
+        };
+
+        for (token_in_denom, token_out_denom, expected) in [
+            // swap fee dominates lower per-denom fee
+            ("uosmo", "uatom", Decimal::permille(2)),
+            ("uatom", "uosmo", Decimal::permille(2)),
+            // higher per-denom fee dominates regardless of direction
+            ("uosmo", "uion", Decimal::permille(5)),
+            ("uion", "uosmo", Decimal::permille(5)),
+            ("uion", alloyed_denom, Decimal::permille(5)),
+            (alloyed_denom, "uatom", Decimal::permille(3)),
+        ] {
+            assert_eq!(
+                effective_swap_fee(token_in_denom, token_out_denom).unwrap(),
+                expected
+            );
+        }
+
+        assert_eq!(
+            effective_swap_fee("uosmo", "uosmo").unwrap_err(),
+            ContractError::SameDenomNotAllowed {
+                denom: "uosmo".to_string()
+            }
+        );
+
+        assert_eq!(
+            effective_swap_fee("uosmo", "unknown").unwrap_err(),
+            ContractError::InvalidPoolAssetDenom {
+                denom: "unknown".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_reset_limiter_state() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let mut env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        for (label, limiter_params) in [
+            (
+                "1h",
+                LimiterParams::ChangeLimiter {
+                    window_config: WindowConfig {
+                        window_size: Uint64::from(3_600_000_000_000u64),
+                        division_count: Uint64::from(2u64),
+                    },
+                    boundary_offset: Decimal::percent(5),
+                },
+            ),
+            (
+                "static",
+                LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(90),
+                },
+            ),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                    denom: "uosmo".to_string(),
+                    label: label.to_string(),
+                    limiter_params,
+                }),
+            )
+            .unwrap();
+        }
+
+        // join pool - weight = 50:50
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "someone",
+                &[Coin::new(500_000, "uosmo"), Coin::new(500_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let swap_uosmo_for_uion = |deps: DepsMut, env: Env| {
+            sudo(
+                deps,
+                env,
+                SudoMsg::SwapExactAmountIn {
+                    sender: "someone".to_string(),
+                    token_in: Coin::new(40_000, "uosmo"),
+                    token_out_denom: "uion".to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+        };
+
+        // uosmo weight: 50% -> 54%
+        env.block.time = env.block.time.plus_minutes(1);
+        swap_uosmo_for_uion(deps.as_mut(), env.clone()).unwrap();
+
+        // moving average is still dragged down by the stale 50% data point,
+        // so uosmo weight: 54% -> 58% is rejected
+        env.block.time = env.block.time.plus_minutes(1);
+        let err = swap_uosmo_for_uion(deps.as_mut(), env.clone()).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::UpperLimitExceeded { denom, value, .. }
+                if denom == "uosmo" && value == Decimal::percent(58)
+        ));
+
+        // only admin can reset limiter state
+        let reset_msg = ContractExecMsg::Transmuter(ExecMsg::ResetLimiterState {
+            denom: "uosmo".to_string(),
+            label: "1h".to_string(),
+        });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            reset_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(deps.as_mut(), env.clone(), info.clone(), reset_msg).unwrap();
+
+        // static limiter has no state to reset
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::ResetLimiterState {
+                denom: "uosmo".to_string(),
+                label: "static".to_string(),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::WrongLimiterType {
+                expected: "change_limiter".to_string(),
+                actual: "static_limiter".to_string(),
+            }
+        );
+
+        // moving average starts over from 54%, so 58% is now allowed
+        env.block.time = env.block.time.plus_minutes(1);
+        swap_uosmo_for_uion(deps.as_mut(), env.clone()).unwrap();
+    }
+
+    #[test]
+    fn test_join_pool_emits_alloyed_asset_transfer_event() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "someone",
+                &[Coin::new(1_000, "uosmo"), Coin::new(500, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.events,
+            vec![Event::new("alloyed_asset_transfer")
+                .add_attribute("action", "mint")
+                .add_attribute("from", "")
+                .add_attribute("to", "someone")
+                .add_attribute("amount", format!("1500{alloyed_denom}"))]
+        );
+    }
+
+    #[test]
+    fn test_max_redeem_into() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // holder gets 2000 shares, but only 500 uosmo is in the pool
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "someone",
+                &[Coin::new(500, "uosmo"), Coin::new(1_500, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let max_redeem_into = |deps: cosmwasm_std::Deps, denom: &str| {
+            let res = query(
+                deps,
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::MaxRedeemInto {
+                    denom: denom.to_string(),
+                }),
+            )
+            .unwrap();
+            let MaxRedeemIntoResponse { max_shares } = from_json(res).unwrap();
+            max_shares
+        };
+
+        // capped by uosmo balance
+        assert_eq!(max_redeem_into(deps.as_ref(), "uosmo"), Uint128::new(500));
+        assert_eq!(max_redeem_into(deps.as_ref(), "uion"), Uint128::new(1_500));
+
+        // uion weight must stay <= 80%, so at least 375 uosmo must remain
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "uion".to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(80),
+                },
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(max_redeem_into(deps.as_ref(), "uosmo"), Uint128::new(125));
+
+        // redeeming the queried amount passes, one more does not
+        deps.querier
+            .update_balance("someone", vec![Coin::new(2_000, alloyed_denom)]);
+        let exit_into_uosmo = |deps: DepsMut, amount: u128| {
+            execute(
+                deps,
+                env.clone(),
+                mock_info("someone", &[]),
+                ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+                    tokens_out: vec![Coin::new(amount, "uosmo")],
+                }),
+            )
+        };
+
+        exit_into_uosmo(deps.as_mut(), 126).unwrap_err();
+        exit_into_uosmo(deps.as_mut(), 125).unwrap();
+        assert_eq!(max_redeem_into(deps.as_ref(), "uosmo"), Uint128::zero());
+    }
+
+    #[test]
+    fn test_amount_tolerance() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        let get_amount_tolerance = |deps: cosmwasm_std::Deps| {
+            let res = query(
+                deps,
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::GetAmountTolerance {}),
+            )
+            .unwrap();
+            let GetAmountToleranceResponse { amount_tolerance } = from_json(res).unwrap();
+            amount_tolerance
+        };
+
+        // strict by default
+        assert_eq!(get_amount_tolerance(deps.as_ref()), Uint128::zero());
+
+        let set_amount_tolerance_msg = ContractExecMsg::Transmuter(ExecMsg::SetAmountTolerance {
+            amount_tolerance: Uint128::one(),
+        });
+
+        // only admin can set amount tolerance
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            set_amount_tolerance_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            set_amount_tolerance_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "set_amount_tolerance"),
+                attr("amount_tolerance", "1"),
+            ]
+        );
+
+        assert_eq!(get_amount_tolerance(deps.as_ref()), Uint128::one());
+    }
+
+    #[test]
+    fn test_batch_calc_out_amt_given_in() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "someone",
+                &[Coin::new(1_000, "uosmo"), Coin::new(1_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        // uosmo weight must stay <= 60%
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            }),
+        )
+        .unwrap();
+
+        let request = |token_in: Coin, token_out_denom: &str| CalcOutRequest {
+            token_in,
+            token_out_denom: token_out_denom.to_string(),
+            swap_fee: Decimal::zero(),
+        };
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::BatchCalcOutAmtGivenIn {
+                requests: vec![
+                    request(Coin::new(100, "uosmo"), "uion"),
+                    request(Coin::new(100, "uion"), "uosmo"),
+                    // uosmo weight: 50% -> 70%
+                    request(Coin::new(400, "uosmo"), "uion"),
+                    request(Coin::new(100, "uosmo"), alloyed_denom),
+                ],
+            }),
+        )
+        .unwrap();
+        let BatchCalcOutAmtGivenInResponse { results } = from_json(res).unwrap();
+
+        assert_eq!(
+            results,
+            vec![
+                CalcOutResult {
+                    token_out: Coin::new(100, "uion"),
+                    trips_limiter: false,
+                },
+                CalcOutResult {
+                    token_out: Coin::new(100, "uosmo"),
+                    trips_limiter: false,
+                },
+                CalcOutResult {
+                    token_out: Coin::new(400, "uion"),
+                    trips_limiter: true,
+                },
+                CalcOutResult {
+                    token_out: Coin::new(100, alloyed_denom),
+                    trips_limiter: false,
+                },
+            ]
+        );
+
+        // batch size is capped
+        let err = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::BatchCalcOutAmtGivenIn {
+                requests: vec![request(Coin::new(1, "uosmo"), "uion"); MAX_BATCH_QUOTES + 1],
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::BatchTooLarge {
+                max: MAX_BATCH_QUOTES,
+                actual: MAX_BATCH_QUOTES + 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_set_normalization_factor() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // only uosmo is funded
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[Coin::new(1_000, "uosmo")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let set_normalization_factor_msg = |denom: &str, factor: u128, force: bool| {
+            ContractExecMsg::Transmuter(ExecMsg::SetNormalizationFactor {
+                denom: denom.to_string(),
+                factor: Uint128::new(factor),
+                force,
+            })
+        };
+
+        let normalization_factor = |deps: cosmwasm_std::Deps, denom: &str| {
+            Transmuter::new()
+                .pool
+                .load(deps.storage)
+                .unwrap()
+                .get_pool_asset_by_denom(denom)
+                .unwrap()
+                .normalization_factor()
+        };
+
+        // only admin can set normalization factor
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            set_normalization_factor_msg("uion", 100, false),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // empty denom can be updated
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            set_normalization_factor_msg("uion", 100, false),
+        )
+        .unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("method", "set_normalization_factor"),
+                attr("denom", "uion"),
+                attr("factor", "100"),
+                attr("force", "false"),
+            ]
+        );
+        assert_eq!(
+            normalization_factor(deps.as_ref(), "uion"),
+            Uint128::new(100)
+        );
+
+        // funded denom is rejected, even with force while the pool is active
+        for force in [false, true] {
+            let err = execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                set_normalization_factor_msg("uosmo", 100, force),
+            )
+            .unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::FundedDenomNormalizationFactorChange {
+                    denom: "uosmo".to_string()
+                }
+            );
+        }
+        assert_eq!(normalization_factor(deps.as_ref(), "uosmo"), Uint128::one());
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "1h".to_string(),
+                limiter_params: LimiterParams::ChangeLimiter {
+                    window_config: WindowConfig {
+                        window_size: Uint64::from(3_600_000_000_000u64),
+                        division_count: Uint64::from(5u64),
+                    },
+                    boundary_offset: Decimal::percent(10),
+                },
+            }),
+        )
+        .unwrap();
+
+        // deactivate the pool
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("moderator", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus {
+                active: false,
+                scope: None,
+                reactivate_at: None,
+            }),
+        )
+        .unwrap();
+
+        // funded denom is still rejected without force
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            set_normalization_factor_msg("uosmo", 100, false),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::FundedDenomNormalizationFactorChange {
+                denom: "uosmo".to_string()
+            }
+        );
+
+        // forced while the pool is inactive
+        let mut forced_env = env.clone();
+        forced_env.block.time = forced_env.block.time.plus_hours(1);
+        execute(
+            deps.as_mut(),
+            forced_env.clone(),
+            info.clone(),
+            set_normalization_factor_msg("uosmo", 100, true),
+        )
+        .unwrap();
+        assert_eq!(
+            normalization_factor(deps.as_ref(), "uosmo"),
+            Uint128::new(100)
+        );
+
+        // change limiter starts over from the weight on the new scale
+        let limiter = Transmuter::new()
+            .limiters
+            .list_limiters_by_denom(&deps.storage, "uosmo")
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap()
+            .1;
+        let Limiter::ChangeLimiter(limiter) = limiter else {
+            panic!("expected change limiter");
+        };
+        let now = forced_env.block.time;
+        assert_eq!(
+            limiter.divisions(),
+            [Division::new(now, now, Decimal::one(), Decimal::one()).unwrap()]
+        );
+
+        // not a pool asset
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            set_normalization_factor_msg("uatom", 100, false),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidTransmuteDenom { .. }));
+    }
+
+    #[test]
+    fn test_instantiate_params() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let pool_asset_configs = vec![
+            AssetConfig::from_denom_str("uosmo"),
+            AssetConfig {
+                denom: "uion".to_string(),
+                normalization_factor: Uint128::new(100),
+            },
+        ];
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: pool_asset_configs.clone(),
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::new(100),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env,
+            ContractQueryMsg::Transmuter(QueryMsg::InstantiateParams {}),
+        )
+        .unwrap();
+        let InstantiateParamsResponse { instantiate_params } = from_json(res).unwrap();
+
+        assert_eq!(
+            instantiate_params,
+            Some(InstantiateParams {
+                pool_asset_configs,
+                alloyed_asset_subdenom: "usomoion".to_string(),
+                alloyed_asset_normalization_factor: Uint128::new(100),
+                admin: Some(admin.to_string()),
+                moderator: "moderator".to_string(),
+                enable_alloyed_asset: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_any_limiter_binding() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        let any_limiter_binding = |deps: cosmwasm_std::Deps| {
+            let res = query(
+                deps,
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::AnyLimiterBinding {}),
+            )
+            .unwrap();
+            let AnyLimiterBindingResponse { binding } = from_json(res).unwrap();
+            binding
+        };
+
+        // empty pool
+        assert!(!any_limiter_binding(deps.as_ref()));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            }),
+        )
+        .unwrap();
+
+        // uosmo weight: 50%
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "someone",
+                &[Coin::new(1_000, "uosmo"), Coin::new(1_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+        assert!(!any_limiter_binding(deps.as_ref()));
+
+        // uosmo weight: 50% -> ~59.98%, within epsilon of the 60% cap
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[Coin::new(499, "uosmo")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+        assert!(any_limiter_binding(deps.as_ref()));
+    }
+
+    #[test]
+    fn test_denom_status() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "uosmo"),
+                Coin::new(1, "uion"),
+                Coin::new(1, "uatom"),
+            ],
+        );
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+                AssetConfig::from_denom_str("uatom"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "someone",
+                &[
+                    Coin::new(1_000, "uosmo"),
+                    Coin::new(1_000, "uion"),
+                    Coin::new(1_000, "uatom"),
+                ],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let denom_status = |deps: cosmwasm_std::Deps, denom: &str| {
+            let res = query(
+                deps,
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::DenomStatus {
+                    denom: denom.to_string(),
+                }),
+            )
+            .unwrap();
+            let status: DenomStatusResponse = from_json(res).unwrap();
+            status
+        };
+
+        let healthy = DenomStatusResponse {
+            can_swap_in: true,
+            can_swap_out: true,
+            reasons: vec![],
+        };
+
+        assert_eq!(denom_status(deps.as_ref(), "uion"), healthy);
+        assert_eq!(denom_status(deps.as_ref(), "uatom"), healthy);
+
+        // pause an asset group containing uion
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::CreateAssetGroup {
+                label: "group1".to_string(),
+                denoms: vec!["uion".to_string()],
+            }),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("moderator", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetAssetGroupPaused {
+                label: "group1".to_string(),
+                paused: true,
+            }),
+        )
+        .unwrap();
+
+        // mark uatom as corrupted
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("moderator", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::MarkCorruptedAssets {
+                denoms: vec!["uatom".to_string()],
+            }),
+        )
+        .unwrap();
+
+        // healthy denom is unaffected
+        assert_eq!(denom_status(deps.as_ref(), "uosmo"), healthy);
+
+        // paused group denom can only be swapped out
+        assert_eq!(
+            denom_status(deps.as_ref(), "uion"),
+            DenomStatusResponse {
+                can_swap_in: false,
+                can_swap_out: true,
+                reasons: vec!["asset group `group1` is paused".to_string()],
+            }
+        );
+
+        // corrupted denom can only be swapped out
+        assert_eq!(
+            denom_status(deps.as_ref(), "uatom"),
+            DenomStatusResponse {
+                can_swap_in: false,
+                can_swap_out: true,
+                reasons: vec!["asset is corrupted".to_string()],
+            }
+        );
+
+        // inactive pool blocks both directions for every denom
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("moderator", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus {
+                active: false,
+                scope: None,
+                reactivate_at: None,
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            denom_status(deps.as_ref(), "uosmo"),
+            DenomStatusResponse {
+                can_swap_in: false,
+                can_swap_out: false,
+                reasons: vec!["pool is inactive".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_swap_complexity() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        let swap_complexity = |deps: cosmwasm_std::Deps, token_out_denom: &str| {
+            query(
+                deps,
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::SwapComplexity {
+                    token_in_denom: "uosmo".to_string(),
+                    token_out_denom: token_out_denom.to_string(),
+                }),
+            )
+            .map(|res| from_json::<SwapComplexityResponse>(res).unwrap())
+        };
+
+        // labels must be unique per denom, so each call registers the given label indices
+        let register_limiters = |mut deps: DepsMut, denom: &str, indices: std::ops::Range<u64>| {
+            for i in indices {
+                execute(
+                    deps.branch(),
+                    env.clone(),
+                    info.clone(),
+                    ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                        denom: denom.to_string(),
+                        label: format!("static{i}"),
+                        limiter_params: LimiterParams::StaticLimiter {
+                            upper_limit: Decimal::percent(90),
+                        },
+                    }),
+                )
+                .unwrap();
+            }
+        };
+
+        assert_eq!(
+            swap_complexity(deps.as_ref(), "uion").unwrap(),
+            SwapComplexityResponse {
+                complexity: SwapComplexityClass::Low,
+                limiter_count: 0,
+                asset_group_count: 0,
+            }
+        );
+
+        register_limiters(deps.as_mut(), "uosmo", 0..5);
+        assert_eq!(
+            swap_complexity(deps.as_ref(), "uion").unwrap(),
+            SwapComplexityResponse {
+                complexity: SwapComplexityClass::Medium,
+                limiter_count: 5,
+                asset_group_count: 0,
+            }
+        );
+
+        // limiters of denoms not in the swap are still checked
+        register_limiters(deps.as_mut(), "uion", 0..10);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::CreateAssetGroup {
+                label: "group1".to_string(),
+                denoms: vec!["uion".to_string()],
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            swap_complexity(deps.as_ref(), alloyed_denom).unwrap(),
+            SwapComplexityResponse {
+                complexity: SwapComplexityClass::Medium,
+                limiter_count: 15,
+                asset_group_count: 1,
+            }
+        );
+
+        register_limiters(deps.as_mut(), "uosmo", 5..9);
+        assert_eq!(
+            swap_complexity(deps.as_ref(), "uion").unwrap(),
+            SwapComplexityResponse {
+                complexity: SwapComplexityClass::High,
+                limiter_count: 19,
+                asset_group_count: 1,
+            }
+        );
+
+        // not a swappable denom
+        let err = swap_complexity(deps.as_ref(), "uatom").unwrap_err();
+        assert!(matches!(err, ContractError::InvalidTransmuteDenom { .. }));
+    }
+
+    #[test]
+    fn test_pool_balances() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        let pool_balances = |deps: cosmwasm_std::Deps| {
+            let res = query(
+                deps,
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::PoolBalances {
+                    human_readable: None,
+                }),
+            )
+            .unwrap();
+            let PoolBalancesResponse {
+                balances,
+                human_readable_balances,
+            } = from_json(res).unwrap();
+            assert_eq!(human_readable_balances, None);
+            balances
+        };
+
+        let expected = |uosmo: u128, uion: u128| {
+            BTreeMap::from([
+                ("uion".to_string(), Uint128::new(uion)),
+                ("uosmo".to_string(), Uint128::new(uosmo)),
+            ])
+        };
+
+        // empty pool
+        assert_eq!(pool_balances(deps.as_ref()), expected(0, 0));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "someone",
+                &[Coin::new(1_000, "uosmo"), Coin::new(2_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+        assert_eq!(pool_balances(deps.as_ref()), expected(1_000, 2_000));
+
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountIn {
+                sender: "someone".to_string(),
+                token_in: Coin::new(500, "uosmo"),
+                token_out_denom: "uion".to_string(),
+                token_out_min_amount: Uint128::one(),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                require_improves_balance: false,
+            },
+        )
+        .unwrap();
+        assert_eq!(pool_balances(deps.as_ref()), expected(1_500, 1_500));
+
+        deps.querier
+            .update_balance("someone", vec![Coin::new(3_000, alloyed_denom)]);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+                tokens_out: vec![Coin::new(400, "uion")],
+            }),
+        )
+        .unwrap();
+        assert_eq!(pool_balances(deps.as_ref()), expected(1_500, 1_100));
+
+        // matches total pool liquidity
+        let GetTotalPoolLiquidityResponse {
+            total_pool_liquidity,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            pool_balances(deps.as_ref()),
+            total_pool_liquidity
+                .into_iter()
+                .map(|coin| (coin.denom, coin.amount))
+                .collect::<BTreeMap<_, _>>()
+        );
+    }
+
+    #[test]
+    fn test_display_decimals() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "provider",
+                &[Coin::new(1_234_567, "uosmo"), Coin::new(2_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let set_display_decimals = |deps: DepsMut, sender: &str, denom: &str, decimals: u32| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::SetDisplayDecimals {
+                    denom: denom.to_string(),
+                    decimals: Some(decimals),
+                }),
+            )
+        };
+
+        let human_readable_balances = |deps: cosmwasm_std::Deps| {
+            let PoolBalancesResponse {
+                human_readable_balances,
+                ..
+            } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::PoolBalances {
+                        human_readable: Some(true),
+                    }),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            human_readable_balances
+        };
+
+        // no display decimals set
+        assert_eq!(
+            human_readable_balances(deps.as_ref()),
+            Some(BTreeMap::new())
+        );
+
+        // only admin can set display decimals
+        let err = set_display_decimals(deps.as_mut(), "someone", "uosmo", 6).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // decimals beyond decimal precision are rejected
+        let err = set_display_decimals(deps.as_mut(), admin, "uosmo", 19).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidDisplayDecimals {
+                decimals: 19,
+                max_decimals: 18,
+            }
+        );
+
+        // only pool asset or alloyed asset denoms have display decimals
+        let err = set_display_decimals(deps.as_mut(), admin, "uatom", 6).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidTransmuteDenom { .. }));
+
+        set_display_decimals(deps.as_mut(), admin, "uosmo", 6).unwrap();
+        set_display_decimals(deps.as_mut(), admin, "usomoion", 6).unwrap();
+
+        // 6-decimal denom is formatted in its display unit, raw balances are unchanged
+        let PoolBalancesResponse {
+            balances,
+            human_readable_balances,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::PoolBalances {
+                    human_readable: Some(true),
+                }),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            balances,
+            BTreeMap::from([
+                ("uion".to_string(), Uint128::new(2_000)),
+                ("uosmo".to_string(), Uint128::new(1_234_567)),
+            ])
+        );
+        assert_eq!(
+            human_readable_balances,
+            Some(BTreeMap::from([(
+                "uosmo".to_string(),
+                Decimal::from_ratio(1_234_567u128, 1_000_000u128)
+            )]))
+        );
+
+        let ListDisplayDecimalsResponse { display_decimals } = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                ContractQueryMsg::Transmuter(QueryMsg::ListDisplayDecimals {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            display_decimals,
+            BTreeMap::from([("uosmo".to_string(), 6), ("usomoion".to_string(), 6)])
+        );
+    }
+
+    #[test]
+    fn test_supply_flow() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        let hour = 3_600_000_000_000u64;
+        let day = Uint64::new(24 * hour);
+
+        let supply_flow = |deps: cosmwasm_std::Deps, env: Env| {
+            let res = query(
+                deps,
+                env,
+                ContractQueryMsg::Transmuter(QueryMsg::SupplyFlow { window: day }),
+            )
+            .unwrap();
+            let flow: SupplyFlowResponse = from_json(res).unwrap();
+            flow
+        };
+
+        let exit_pool = |deps: DepsMut, env: Env, amount: u128| {
+            execute(
+                deps,
+                env,
+                mock_info("someone", &[]),
+                ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+                    tokens_out: vec![Coin::new(amount, "uion")],
+                }),
+            )
+            .unwrap();
+        };
+
+        // mint 2_000 and burn 500 within the window
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "someone",
+                &[Coin::new(1_000, "uosmo"), Coin::new(1_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+        deps.querier
+            .update_balance("someone", vec![Coin::new(2_000, alloyed_denom)]);
+        exit_pool(deps.as_mut(), env.clone(), 500);
+
+        assert_eq!(
+            supply_flow(deps.as_ref(), env.clone()),
+            SupplyFlowResponse {
+                minted: Uint128::new(2_000),
+                burned: Uint128::new(500),
+                net_flow: Int256::from(1_500i128),
+            }
+        );
+
+        // both buckets age out of the window, burning afterwards contracts the supply
+        let mut later = env.clone();
+        later.block.time = env.block.time.plus_nanos(25 * hour);
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1_500, alloyed_denom)]);
+        exit_pool(deps.as_mut(), later.clone(), 300);
+
+        assert_eq!(
+            supply_flow(deps.as_ref(), later),
+            SupplyFlowResponse {
+                minted: Uint128::zero(),
+                burned: Uint128::new(300),
+                net_flow: Int256::from(-300i128),
+            }
+        );
+    }
+
+    #[test]
+    fn test_exit_to_single_denom() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // balanced basket
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "someone",
+                &[Coin::new(1_000, "uosmo"), Coin::new(1_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("lp", &[Coin::new(1_000, "uosmo"), Coin::new(1_000, "uion")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+        deps.querier
+            .update_balance("lp", vec![Coin::new(2_000, alloyed_denom)]);
+
+        let exit_to_single_denom_msg = |min_amount_out: u128| {
+            ContractExecMsg::Transmuter(ExecMsg::ExitToSingleDenom {
+                share_amount: Uint128::new(2_000),
+                target_denom: "uosmo".to_string(),
+                min_amount_out: Uint128::new(min_amount_out),
+            })
+        };
+
+        // slippage floor is enforced
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("lp", &[]),
+            exit_to_single_denom_msg(2_001),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientTokenOut {
+                min_required: Uint128::new(2_001),
+                amount_out: Uint128::new(2_000),
+            }
+        );
+
+        // whole position is consolidated into uosmo
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("lp", &[]),
+            exit_to_single_denom_msg(2_000),
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|sub_msg| sub_msg.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                MsgBurn {
+                    sender: env.contract.address.to_string(),
+                    amount: Some(Coin::new(2_000, alloyed_denom).into()),
+                    burn_from_address: "lp".to_string(),
+                }
+                .into(),
+                BankMsg::Send {
+                    to_address: "lp".to_string(),
+                    amount: vec![Coin::new(2_000, "uosmo")],
+                }
+                .into(),
+            ]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+        )
+        .unwrap();
+        let GetTotalPoolLiquidityResponse {
+            total_pool_liquidity,
+        } = from_json(res).unwrap();
+        assert_eq!(
+            total_pool_liquidity,
+            vec![Coin::new(0, "uosmo"), Coin::new(2_000, "uion")]
+        );
+    }
+
+    #[test]
+    fn test_exit_pool_from() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let lp = deps.api.addr_make("lp").to_string();
+        let integration = deps.api.addr_make("integration").to_string();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(&lp, &[Coin::new(1_000, "uosmo"), Coin::new(1_000, "uion")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+        deps.querier
+            .update_balance(&lp, vec![Coin::new(2_000, alloyed_denom)]);
+
+        let exit_pool_from = |deps: DepsMut, amount: u128| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(&integration, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::ExitPoolFrom {
+                    owner: lp.clone(),
+                    tokens_out: vec![Coin::new(amount, "uosmo")],
+                }),
+            )
+        };
+
+        let allowance = |deps: cosmwasm_std::Deps| {
+            let AlloyedAllowanceResponse { allowance } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::AlloyedAllowance {
+                        owner: lp.clone(),
+                        spender: integration.clone(),
+                    }),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            allowance
+        };
+
+        // burning without allowance fails
+        let err = exit_pool_from(deps.as_mut(), 300).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientAllowance {
+                required: Uint128::new(300),
+                available: Uint128::zero(),
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(&lp, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::IncreaseAlloyedAllowance {
+                spender: integration.clone(),
+                amount: Uint128::new(500),
+            }),
+        )
+        .unwrap();
+        assert_eq!(allowance(deps.as_ref()), Uint128::new(500));
+
+        // shares are burned from the owner, and tokens out are sent to the spender
+        let res = exit_pool_from(deps.as_mut(), 300).unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|sub_msg| sub_msg.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                MsgBurn {
+                    sender: env.contract.address.to_string(),
+                    amount: Some(Coin::new(300, alloyed_denom).into()),
+                    burn_from_address: lp.clone(),
+                }
+                .into(),
+                BankMsg::Send {
+                    to_address: integration.clone(),
+                    amount: vec![Coin::new(300, "uosmo")],
+                }
+                .into(),
+            ]
+        );
+        assert_eq!(allowance(deps.as_ref()), Uint128::new(200));
+
+        // burning beyond the remaining allowance fails
+        let err = exit_pool_from(deps.as_mut(), 300).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientAllowance {
+                required: Uint128::new(300),
+                available: Uint128::new(200),
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(&lp, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::DecreaseAlloyedAllowance {
+                spender: integration.clone(),
+                amount: Uint128::new(1_000),
+            }),
+        )
+        .unwrap();
+        assert_eq!(allowance(deps.as_ref()), Uint128::zero());
+    }
+
+    #[test]
+    fn test_denom_alias() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![Coin::new(1, "ibc/usdc"), Coin::new(1, "ibc/usdt")],
+        );
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("ibc/usdc"),
+                AssetConfig::from_denom_str("ibc/usdt"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usd".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usd";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "someone",
+                &[Coin::new(1_000, "ibc/usdc"), Coin::new(1_000, "ibc/usdt")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let register_denom_alias_msg = |alias: &str, denom: &str| {
+            ContractExecMsg::Transmuter(ExecMsg::RegisterDenomAlias {
+                alias: alias.to_string(),
+                denom: denom.to_string(),
+            })
+        };
+
+        // only admin can register denom alias
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            register_denom_alias_msg("usdc", "ibc/usdc"),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            register_denom_alias_msg("usdc", "ibc/usdc"),
+        )
+        .unwrap();
+
+        // ambiguous aliases are rejected
+        for alias in ["usdc", "ibc/usdt", alloyed_denom] {
+            let err = execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                register_denom_alias_msg(alias, "ibc/usdt"),
+            )
+            .unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::AmbiguousDenomAlias {
+                    alias: alias.to_string()
+                }
+            );
+        }
+
+        // new pool asset can't take the denom of a registered alias
+        deps.querier
+            .update_balance("other", vec![Coin::new(1, "usdc")]);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::AddNewAssets {
+                asset_configs: vec![AssetConfig::from_denom_str("usdc")],
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AmbiguousDenomAlias {
+                alias: "usdc".to_string()
+            }
+        );
+
+        // alias must point to a pool asset
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            register_denom_alias_msg("atom", "uatom"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPoolAssetDenom {
+                denom: "uatom".to_string()
+            }
+        );
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::ListDenomAliases {}),
+        )
+        .unwrap();
+        let ListDenomAliasesResponse { denom_aliases } = from_json(res).unwrap();
+        assert_eq!(
+            denom_aliases,
+            BTreeMap::from([("usdc".to_string(), "ibc/usdc".to_string())])
+        );
+
+        // swap referencing the alias resolves to ibc/usdc
+        let res = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountIn {
+                sender: "someone".to_string(),
+                token_in: Coin::new(100, "ibc/usdt"),
+                token_out_denom: "usdc".to_string(),
+                token_out_min_amount: Uint128::new(100),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                require_improves_balance: false,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: "someone".to_string(),
+                amount: vec![Coin::new(100, "ibc/usdc")],
+            }
+            .into()
+        );
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+        )
+        .unwrap();
+        let GetTotalPoolLiquidityResponse {
+            total_pool_liquidity,
+        } = from_json(res).unwrap();
+        assert_eq!(
+            total_pool_liquidity,
+            vec![Coin::new(900, "ibc/usdc"), Coin::new(1_100, "ibc/usdt")]
+        );
+
+        // deregistered alias no longer resolves
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::DeregisterDenomAlias {
+                alias: "usdc".to_string(),
+            }),
+        )
+        .unwrap();
+
+        let err = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountOut {
+                sender: "someone".to_string(),
+                token_in_denom: "usdc".to_string(),
+                token_in_max_amount: Uint128::new(100),
+                token_out: Coin::new(100, "ibc/usdt"),
+                swap_fee: Decimal::zero(),
+                require_improves_balance: false,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidTransmuteDenom { .. }));
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::DeregisterDenomAlias {
+                alias: "usdc".to_string(),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::DenomAliasNotFound {
+                alias: "usdc".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_mint_rate_limit() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let mut env = mock_env();
+        // align to the window, so that its buckets age out at whole seconds
+        env.block.time = Timestamp::from_seconds(1_000 * 60);
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // only admin can set mint rate limit
+        let set_mint_rate_limit_msg = ContractExecMsg::Transmuter(ExecMsg::SetMintRateLimit {
+            mint_window: Some(Uint64::new(60_000_000_000)),
+            max_mint_per_window: Uint128::new(1500),
+        });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            set_mint_rate_limit_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            set_mint_rate_limit_msg,
+        )
+        .unwrap();
+
+        let join_pool = |deps: DepsMut, env: Env, amount: u128| {
+            execute(
+                deps,
+                env,
+                mock_info("user", &[Coin::new(amount, "uosmo")]),
+                ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                    min_shares_out: None,
+                }),
+            )
+        };
+
+        // mints accumulate within the window
+        join_pool(deps.as_mut(), env.clone(), 1000).unwrap();
+
+        let window_started_at = env.block.time;
+        env.block.time = env.block.time.plus_seconds(30);
+
+        let err = join_pool(deps.as_mut(), env.clone(), 600).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MintRateLimitExceeded {
+                retry_after: window_started_at.plus_seconds(60)
+            }
+        );
+
+        // swapping into alloyed asset counts toward the limit too
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountIn {
+                sender: "user".to_string(),
+                token_in: Coin::new(500, "uion"),
+                token_out_denom: alloyed_denom.to_string(),
+                token_out_min_amount: Uint128::one(),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                require_improves_balance: false,
+            },
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetMintRateLimit {}),
+        )
+        .unwrap();
+        let GetMintRateLimitResponse {
+            mint_rate_limit,
+            minted,
+        } = from_json(res).unwrap();
+        assert_eq!(
+            mint_rate_limit,
+            Some(MintRateLimit {
+                max_mint_per_window: Uint128::new(1500),
+                window: Uint64::new(60_000_000_000),
+            })
+        );
+        assert_eq!(minted, Uint128::new(1500));
+
+        let err = join_pool(deps.as_mut(), env.clone(), 1).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MintRateLimitExceeded {
+                retry_after: window_started_at.plus_seconds(60)
+            }
+        );
+
+        // burns are not limited
+        deps.querier
+            .update_balance("user", vec![Coin::new(1500, alloyed_denom)]);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+                tokens_out: vec![Coin::new(100, "uosmo")],
+            }),
+        )
+        .unwrap();
+
+        // window rolls forward, only the first join has aged out
+        env.block.time = window_started_at.plus_seconds(60);
+        let err = join_pool(deps.as_mut(), env.clone(), 1001).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MintRateLimitExceeded {
+                retry_after: window_started_at.plus_seconds(90)
+            }
+        );
+
+        join_pool(deps.as_mut(), env.clone(), 1000).unwrap();
+
+        let err = join_pool(deps.as_mut(), env.clone(), 1).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MintRateLimitExceeded {
+                retry_after: window_started_at.plus_seconds(90)
+            }
+        );
+
+        // withdrawing alloyed asset fee is a mint too
+        Transmuter::new()
+            .accrued_fees
+            .save(&mut deps.storage, alloyed_denom, &Uint128::one())
+            .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ContractExecMsg::Transmuter(ExecMsg::WithdrawFees {
+                recipient: "treasury".to_string(),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MintRateLimitExceeded {
+                retry_after: window_started_at.plus_seconds(90)
+            }
+        );
+
+        // removing the limit allows minting again
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ContractExecMsg::Transmuter(ExecMsg::SetMintRateLimit {
+                mint_window: None,
+                max_mint_per_window: Uint128::zero(),
+            }),
+        )
+        .unwrap();
+
+        join_pool(deps.as_mut(), env, 10000).unwrap();
+    }
+
+    #[test]
+    fn test_version() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some("admin".to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let mut env = mock_env();
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            init_msg,
+        )
+        .unwrap();
+
+        let query_version = |deps: cosmwasm_std::Deps, env: Env| -> VersionResponse {
+            from_json(
+                query(
+                    deps,
+                    env,
+                    ContractQueryMsg::Transmuter(QueryMsg::Version {}),
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            query_version(deps.as_ref(), env.clone()),
+            VersionResponse {
+                contract: CONTRACT_NAME.to_string(),
+                version: CONTRACT_VERSION.to_string(),
+                migration_history: vec![],
+            }
+        );
+
+        // pretend the contract was deployed at the version prior to migration
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "3.0.0").unwrap();
+
+        env.block.time = env.block.time.plus_seconds(100);
+        crate::migrations::v3_1_0::execute_migration(deps.as_mut(), env.clone()).unwrap();
+
+        assert_eq!(
+            query_version(deps.as_ref(), env.clone()),
+            VersionResponse {
+                contract: CONTRACT_NAME.to_string(),
+                version: "3.1.0".to_string(),
+                migration_history: vec![MigrationRecord {
+                    from_version: "3.0.0".to_string(),
+                    to_version: "3.1.0".to_string(),
+                    migrated_at: env.block.time,
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_contract_info() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| match query {
+            cosmwasm_std::WasmQuery::ContractInfo { contract_addr } => {
+                assert_eq!(contract_addr, MOCK_CONTRACT_ADDR);
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&cosmwasm_std::ContractInfoResponse::new(3, "creator")).unwrap(),
+                ))
+            }
+            _ => unimplemented!(),
+        });
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some("admin".to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            init_msg,
+        )
+        .unwrap();
+
+        let query_contract_info = |deps: cosmwasm_std::Deps| -> ContractInfoResponse {
+            from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::ContractInfo {}),
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            query_contract_info(deps.as_ref()),
+            ContractInfoResponse {
+                code_id: 3,
+                pool_id: None,
+                contract_address: Addr::unchecked(MOCK_CONTRACT_ADDR),
+                contract: CONTRACT_NAME.to_string(),
+                version: CONTRACT_VERSION.to_string(),
+            }
+        );
+
+        // only admin can set pool id
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("moderator", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetPoolId { pool_id: 42 }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("admin", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetPoolId { pool_id: 42 }),
+        )
+        .unwrap();
+
+        assert_eq!(query_contract_info(deps.as_ref()).pool_id, Some(42));
+    }
+
+    #[test]
+    fn test_mark_denom_corrupted() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "axlusdc"),
+                Coin::new(1, "whusdc"),
+                Coin::new(1, "usdc"),
+            ],
+        );
+
+        let admin = "admin";
+        let moderator = "moderator";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("axlusdc"),
+                AssetConfig::from_denom_str("whusdc"),
+                AssetConfig::from_denom_str("usdc"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "allusdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "allusdc".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "user",
+                &[
+                    Coin::new(1_000_000, "axlusdc"),
+                    Coin::new(1_000_000, "whusdc"),
+                    Coin::new(1_000_000, "usdc"),
+                ],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let exec = |deps: DepsMut, sender: &str, msg: ExecMsg| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+        };
+
+        let corrupted_denoms = |deps: cosmwasm_std::Deps| {
+            let GetCorrruptedDenomsResponse { corrupted_denoms } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::GetCorruptedDenoms {}),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            corrupted_denoms
+        };
+
+        let swap_into = |deps: DepsMut, denom: &str| {
+            sudo(
+                deps,
+                mock_env(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: "user".to_string(),
+                    token_in: Coin::new(1000, denom),
+                    token_out_denom: "usdc".to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+        };
+
+        // only admin can mark denom as corrupted
+        let err = exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::MarkDenomCorrupted {
+                denom: "axlusdc".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // denom must be a pool asset
+        let err = exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::MarkDenomCorrupted {
+                denom: "uosmo".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPoolAssetDenom {
+                denom: "uosmo".to_string()
+            }
+        );
+
+        exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::MarkDenomCorrupted {
+                denom: "axlusdc".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(corrupted_denoms(deps.as_ref()), vec!["axlusdc"]);
+
+        // increasing the corrupted denom is blocked, other denoms are unaffected
+        let err = swap_into(deps.as_mut(), "axlusdc").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::CorruptedAssetRelativelyIncreased {
+                denom: "axlusdc".to_string()
+            }
+        );
+        swap_into(deps.as_mut(), "whusdc").unwrap();
+
+        // direct corruption is kept when a corrupted group containing the denom is unmarked
+        exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::CreateAssetGroup {
+                label: "bridged".to_string(),
+                denoms: vec!["axlusdc".to_string(), "whusdc".to_string()],
+            },
+        )
+        .unwrap();
+
+        for msg in [
+            ExecMsg::MarkAssetGroupAsCorrupted {
+                label: "bridged".to_string(),
+            },
+            ExecMsg::UnmarkAssetGroupAsCorrupted {
+                label: "bridged".to_string(),
+            },
+        ] {
+            exec(deps.as_mut(), moderator, msg).unwrap();
+        }
+        assert_eq!(corrupted_denoms(deps.as_ref()), vec!["axlusdc"]);
+        swap_into(deps.as_mut(), "whusdc").unwrap();
+
+        // group corruption is kept when the denom is unmarked directly
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::MarkAssetGroupAsCorrupted {
+                label: "bridged".to_string(),
+            },
+        )
+        .unwrap();
+
+        exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::UnmarkDenomCorrupted {
+                denom: "axlusdc".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(corrupted_denoms(deps.as_ref()), vec!["axlusdc", "whusdc"]);
+        swap_into(deps.as_mut(), "axlusdc").unwrap_err();
+
+        // only directly marked denom can be unmarked directly
+        let err = exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::UnmarkDenomCorrupted {
+                denom: "axlusdc".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidCorruptedAssetDenom {
+                denom: "axlusdc".to_string()
+            }
+        );
+
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::UnmarkAssetGroupAsCorrupted {
+                label: "bridged".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(corrupted_denoms(deps.as_ref()), Vec::<String>::new());
+        swap_into(deps.as_mut(), "axlusdc").unwrap();
+
+        // denoms marked by moderator are directly corrupted too
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::MarkCorruptedAssets {
+                denoms: vec!["whusdc".to_string()],
+            },
+        )
+        .unwrap();
+
+        for msg in [
+            ExecMsg::MarkAssetGroupAsCorrupted {
+                label: "bridged".to_string(),
+            },
+            ExecMsg::UnmarkAssetGroupAsCorrupted {
+                label: "bridged".to_string(),
+            },
+        ] {
+            exec(deps.as_mut(), moderator, msg).unwrap();
+        }
+        assert_eq!(corrupted_denoms(deps.as_ref()), vec!["whusdc"]);
+        swap_into(deps.as_mut(), "whusdc").unwrap_err();
+
+        // group corruption is kept when the denom is unmarked by moderator
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::MarkAssetGroupAsCorrupted {
+                label: "bridged".to_string(),
+            },
+        )
+        .unwrap();
+
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::UnmarkCorruptedAssets {
+                denoms: vec!["whusdc".to_string()],
+            },
+        )
+        .unwrap();
+        assert_eq!(corrupted_denoms(deps.as_ref()), vec!["axlusdc", "whusdc"]);
+        swap_into(deps.as_mut(), "whusdc").unwrap_err();
+
+        // but it is no longer directly corrupted, so unmarking the group uncorrupts it
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::UnmarkAssetGroupAsCorrupted {
+                label: "bridged".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(corrupted_denoms(deps.as_ref()), Vec::<String>::new());
+        swap_into(deps.as_mut(), "whusdc").unwrap();
+    }
+
+    #[test]
+    fn test_min_active_assets() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "axlusdc"),
+                Coin::new(1, "whusdc"),
+                Coin::new(1, "nusdc"),
+                Coin::new(1, "usdc"),
+            ],
+        );
+
+        let admin = "admin";
+        let moderator = "moderator";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("axlusdc"),
+                AssetConfig::from_denom_str("whusdc"),
+                AssetConfig::from_denom_str("nusdc"),
+                AssetConfig::from_denom_str("usdc"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "allusdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "allusdc".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        let exec = |deps: DepsMut, sender: &str, msg: ExecMsg| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+        };
+
+        // no min by default
+        let GetMinActiveAssetsResponse { min_active_assets } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::GetMinActiveAssets {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(min_active_assets, 0);
+
+        // only admin can set min active assets
+        let err = exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::SetMinActiveAssets {
+                min_active_assets: 2,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::SetMinActiveAssets {
+                min_active_assets: 2,
+            },
+        )
+        .unwrap();
+
+        exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::CreateAssetGroup {
+                label: "bridged".to_string(),
+                denoms: vec!["whusdc".to_string(), "nusdc".to_string()],
+            },
+        )
+        .unwrap();
+
+        // corrupting a group down to the min is allowed
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::MarkAssetGroupAsCorrupted {
+                label: "bridged".to_string(),
+            },
+        )
+        .unwrap();
+
+        // re-marking a corrupted denom doesn't reduce active assets
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::MarkCorruptedAssets {
+                denoms: vec!["whusdc".to_string()],
+            },
+        )
+        .unwrap();
+
+        // corrupting any further is rejected, by any means
+        let expected_err = ContractError::TooFewActiveAssets {
+            active_assets: 1,
+            min_active_assets: 2,
+        };
+        let err = exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::MarkCorruptedAssets {
+                denoms: vec!["axlusdc".to_string()],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, expected_err);
+
+        let err = exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::MarkDenomCorrupted {
+                denom: "usdc".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, expected_err);
+
+        let GetCorrruptedDenomsResponse {
+            mut corrupted_denoms,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::GetCorruptedDenoms {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        corrupted_denoms.sort();
+        assert_eq!(corrupted_denoms, vec!["nusdc", "whusdc"]);
+
+        // lowering the min allows it again
+        exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::SetMinActiveAssets {
+                min_active_assets: 1,
+            },
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::MarkDenomCorrupted {
+                denom: "usdc".to_string(),
+            },
+        )
+        .unwrap();
+
+        // wrapping the last active denom in a group doesn't bypass the min
+        exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::CreateAssetGroup {
+                label: "axelar".to_string(),
+                denoms: vec!["axlusdc".to_string()],
+            },
+        )
+        .unwrap();
+        let err = exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::SetCorruptionBatch {
+                mark: vec!["axelar".to_string()],
+                unmark: vec![],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::TooFewActiveAssets {
+                active_assets: 0,
+                min_active_assets: 1,
+            }
+        );
+        assert!(!Transmuter::new()
+            .pool
+            .load(&deps.storage)
+            .unwrap()
+            .is_corrupted_asset("axlusdc"));
+    }
+
+    #[test]
+    fn test_max_asset_groups() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        let max_asset_groups = |deps: cosmwasm_std::Deps| {
+            let GetMaxAssetGroupsResponse { max_asset_groups } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::GetMaxAssetGroups {}),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            max_asset_groups
+        };
+
+        let create_asset_group = |deps: DepsMut, label: &str| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::CreateAssetGroup {
+                    label: label.to_string(),
+                    denoms: vec!["uosmo".to_string()],
+                }),
+            )
+        };
+
+        assert_eq!(max_asset_groups(deps.as_ref()), DEFAULT_MAX_ASSET_GROUPS);
+
+        // only admin can set max asset groups
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetMaxAssetGroups {
+                max_asset_groups: 2,
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetMaxAssetGroups {
+                max_asset_groups: 2,
+            }),
+        )
+        .unwrap();
+        assert_eq!(max_asset_groups(deps.as_ref()), 2);
+
+        // create up to the cap
+        create_asset_group(deps.as_mut(), "group1").unwrap();
+        create_asset_group(deps.as_mut(), "group2").unwrap();
+
+        let err = create_asset_group(deps.as_mut(), "group3").unwrap_err();
+        assert_eq!(err, ContractError::TooManyAssetGroups { max: 2 });
+
+        // removing a group frees up a slot
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::RemoveAssetGroup {
+                label: "group1".to_string(),
+            }),
+        )
+        .unwrap();
+        create_asset_group(deps.as_mut(), "group3").unwrap();
+    }
+
+    #[test]
+    fn test_export_state() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        for msg in [
+            ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            },
+            ExecMsg::CreateAssetGroup {
+                label: "group".to_string(),
+                denoms: vec!["uosmo".to_string()],
+            },
+            ExecMsg::CreateAssetGroup {
+                label: "other_group".to_string(),
+                denoms: vec!["uion".to_string()],
+            },
+            ExecMsg::RegisterDenomAlias {
+                alias: "osmo".to_string(),
+                denom: "uosmo".to_string(),
+            },
+            ExecMsg::UpdateSenderAccessList {
+                add: vec!["listed".to_string()],
+                remove: vec![],
+            },
+            ExecMsg::SetSwapFee {
+                swap_fee: Decimal::permille(3),
+            },
+            ExecMsg::SetPoolId { pool_id: 42 },
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+            .unwrap();
+        }
+
+        // pause for an hour
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("moderator", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus {
+                active: false,
+                scope: Some(PauseScope::SwapsOnly),
+                reactivate_at: Some(env.block.time.plus_hours(1)),
+            }),
+        )
+        .unwrap();
+
+        let export_state = |env: Env, start_after: Option<StateDumpCursor>, limit: Option<u32>| {
+            let dump = query(
+                deps.as_ref(),
+                env,
+                ContractQueryMsg::Transmuter(QueryMsg::ExportState { start_after, limit }),
+            )
+            .unwrap();
+            from_json::<FullStateDump>(dump).unwrap()
+        };
+
+        let dump = export_state(env.clone(), None, None);
+        assert!(!dump.config.is_active);
+        assert_eq!(dump.config.pause_scope, Some(PauseScope::SwapsOnly));
+        assert_eq!(
+            dump.config.reactivate_at,
+            Some(env.block.time.plus_hours(1))
+        );
+
+        // status is dumped as effective at the block time, after the pause lifts by itself
+        let mut later_env = env.clone();
+        later_env.block.time = env.block.time.plus_hours(2);
+        let dump = export_state(later_env, None, None);
+        assert!(dump.config.is_active);
+        assert_eq!(dump.config.pause_scope, None);
+        assert_eq!(dump.config.reactivate_at, None);
+
+        assert_eq!(dump.version, crate::state_dump::STATE_DUMP_VERSION);
+        assert_eq!(dump.contract_version, CONTRACT_VERSION);
+        assert_eq!(
+            dump.pool,
+            Transmuter::new().pool.load(&deps.storage).unwrap()
+        );
+        assert_eq!(dump.alloyed_asset.denom, Some("usomoion".to_string()));
+        assert_eq!(
+            dump.limiters
+                .iter()
+                .map(|limiter| (limiter.denom.as_str(), limiter.label.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("uosmo", "static")]
+        );
+        assert_eq!(
+            dump.asset_groups
+                .iter()
+                .map(|group| group.label.as_str())
+                .collect::<Vec<_>>(),
+            vec!["group", "other_group"]
+        );
+        assert_eq!(
+            dump.denom_aliases,
+            vec![("osmo".to_string(), "uosmo".to_string())]
+        );
+        assert_eq!(dump.sender_access_list, vec![Addr::unchecked("listed")]);
+        assert_eq!(dump.next, None);
+        assert_eq!(dump.config.admin, Addr::unchecked(admin));
+        assert_eq!(dump.config.swap_fee, Decimal::permille(3));
+        assert_eq!(dump.config.pool_id, Some(42));
+
+        // dump round-trips through serde
+        let roundtrip: FullStateDump = from_json(to_json_binary(&dump).unwrap()).unwrap();
+        assert_eq!(roundtrip, dump);
+
+        // asset groups, denom aliases and sender access list are paginated across sections
+        let first_page = export_state(env.clone(), None, Some(1));
+        assert_eq!(first_page.asset_groups, dump.asset_groups[..1]);
+        assert!(first_page.denom_aliases.is_empty());
+        assert_eq!(first_page.limiters, dump.limiters);
+        assert_eq!(
+            first_page.next,
+            Some(StateDumpCursor {
+                section: crate::state_dump::StateDumpSection::AssetGroups,
+                start_after: Some("group".to_string()),
+            })
+        );
+
+        let second_page = export_state(env.clone(), first_page.next, Some(1));
+        assert_eq!(second_page.asset_groups, dump.asset_groups[1..]);
+        assert!(second_page.denom_aliases.is_empty());
+        assert_eq!(
+            second_page.next,
+            Some(StateDumpCursor {
+                section: crate::state_dump::StateDumpSection::DenomAliases,
+                start_after: None,
+            })
+        );
+
+        let third_page = export_state(env.clone(), second_page.next, Some(1));
+        assert!(third_page.asset_groups.is_empty());
+        assert_eq!(third_page.denom_aliases, dump.denom_aliases);
+        assert!(third_page.sender_access_list.is_empty());
+        assert_eq!(
+            third_page.next,
+            Some(StateDumpCursor {
+                section: crate::state_dump::StateDumpSection::SenderAccessList,
+                start_after: None,
+            })
+        );
+
+        let last_page = export_state(env.clone(), third_page.next, Some(1));
+        assert!(last_page.denom_aliases.is_empty());
+        assert_eq!(last_page.sender_access_list, dump.sender_access_list);
+        assert_eq!(last_page.next, None);
+    }
+
+    #[test]
+    fn test_import_state() {
+        let admin = "admin";
+        let setup = || {
+            let mut deps = mock_dependencies();
+
+            // make denom has non-zero total supply
+            deps.querier
+                .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+            let init_msg = InstantiateMsg {
+                pool_asset_configs: vec![
+                    AssetConfig::from_denom_str("uosmo"),
+                    AssetConfig::from_denom_str("uion"),
+                ],
+                admin: Some(admin.to_string()),
+                alloyed_asset_subdenom: "usomoion".to_string(),
+                alloyed_asset_normalization_factor: Uint128::one(),
+                moderator: "moderator".to_string(),
+                enable_alloyed_asset: None,
+            };
+
+            // Instantiate the contract.
+            instantiate(deps.as_mut(), mock_env(), mock_info(admin, &[]), init_msg).unwrap();
+
+            // Manually reply
+            reply(
+                deps.as_mut(),
+                mock_env(),
+                Reply {
+                    id: 1,
+                    result: SubMsgResult::Ok(SubMsgResponse {
+                        events: vec![],
+                        data: Some(
+                            MsgCreateDenomResponse {
+                                new_token_denom: "usomoion".to_string(),
+                            }
+                            .into(),
+                        ),
+                    }),
+                },
+            )
+            .unwrap();
+
+            deps
+        };
+
+        let export_state = |deps: cosmwasm_std::Deps| -> FullStateDump {
+            from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::ExportState {
+                        start_after: None,
+                        limit: None,
+                    }),
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+
+        let import_state = |deps: DepsMut, dump: FullStateDump| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::ImportState { dump }),
+            )
+        };
+
+        let mut source = setup();
+        for msg in [
+            ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            },
+            ExecMsg::RegisterLimiter {
+                denom: "uion".to_string(),
+                label: "change".to_string(),
+                limiter_params: LimiterParams::ChangeLimiter {
+                    window_config: WindowConfig {
+                        window_size: Uint64::from(3_600_000_000_000u64),
+                        division_count: Uint64::from(5u64),
+                    },
+                    boundary_offset: Decimal::percent(10),
+                },
+            },
+            ExecMsg::CreateAssetGroup {
+                label: "group".to_string(),
+                denoms: vec!["uosmo".to_string(), "uion".to_string()],
+            },
+            ExecMsg::SetFeeCollector {
+                address: "collector".to_string(),
+            },
+            ExecMsg::SetSwapFee {
+                swap_fee: Decimal::permille(3),
+            },
+            ExecMsg::SetFeeSplit {
+                fee_split: Decimal::percent(50),
+            },
+            ExecMsg::SetMaxAssetGroups {
+                max_asset_groups: 5,
+            },
+            ExecMsg::RegisterDenomAlias {
+                alias: "osmo".to_string(),
+                denom: "uosmo".to_string(),
+            },
+            ExecMsg::SetLargeSwapCooldown {
+                large_swap_cooldown: Some(Uint64::new(60_000_000_000)),
+                large_swap_threshold: Uint128::new(1_000),
+            },
+            ExecMsg::SetMintRateLimit {
+                mint_window: Some(Uint64::new(60_000_000_000)),
+                max_mint_per_window: Uint128::new(1_000_000),
+            },
+            ExecMsg::SetMinLockedShares {
+                min_locked_shares: Uint128::new(100),
+            },
+            ExecMsg::SetDustThreshold {
+                dust_threshold: Uint128::new(10),
+            },
+            ExecMsg::SetSenderAccessMode {
+                mode: SenderAccessMode::Denylist,
+            },
+            ExecMsg::UpdateSenderAccessList {
+                add: vec!["denied".to_string()],
+                remove: vec![],
+            },
+            ExecMsg::SetAllowFullDrain {
+                allow_full_drain: true,
+            },
+            ExecMsg::SetEnableRoundingAudit {
+                enable_rounding_audit: true,
+            },
+            ExecMsg::SetMinActiveAssets {
+                min_active_assets: 1,
+            },
+            ExecMsg::SetDisplayDecimals {
+                denom: "uosmo".to_string(),
+                decimals: Some(6),
+            },
+            ExecMsg::SetDisplayDecimals {
+                denom: "usomoion".to_string(),
+                decimals: Some(6),
+            },
+            ExecMsg::SetLimiterStressConfig {
+                stress_config: Some(StressConfig {
+                    imbalance_threshold: Decimal::percent(20),
+                    tightening_factor: Decimal::percent(50),
+                }),
+            },
+            ExecMsg::SetPoolId { pool_id: 42 },
+        ] {
+            execute(
+                source.as_mut(),
+                mock_env(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+            .unwrap();
+        }
+        let dump = export_state(source.as_ref());
+
+        // dump of unsupported version is rejected
+        let mut target = setup();
+        let err = import_state(
+            target.as_mut(),
+            FullStateDump {
+                version: crate::state_dump::STATE_DUMP_VERSION + 1,
+                ..dump.clone()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UnsupportedStateDumpVersion {
+                expected: crate::state_dump::STATE_DUMP_VERSION,
+                actual: crate::state_dump::STATE_DUMP_VERSION + 1,
+            }
+        );
+
+        // dump missing later pages is rejected
+        let err = import_state(
+            target.as_mut(),
+            FullStateDump {
+                next: Some(StateDumpCursor {
+                    section: crate::state_dump::StateDumpSection::DenomAliases,
+                    start_after: None,
+                }),
+                ..dump.clone()
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::IncompleteStateDump {});
+
+        // only admin can import state
+        let err = execute(
+            target.as_mut(),
+            mock_env(),
+            mock_info("someone", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::ImportState { dump: dump.clone() }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // dump rejected after its limiters are validated leaves storage unchanged
+        let mut invalid_dump = dump.clone();
+        invalid_dump
+            .asset_groups
+            .push(crate::state_dump::AssetGroupDump {
+                label: "invalid".to_string(),
+                asset_group: AssetGroup::new(vec!["uatom".to_string()]),
+            });
+
+        let hash_before = state_hash(&target.storage);
+        let err = import_state(target.as_mut(), invalid_dump).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPoolAssetDenom {
+                denom: "uatom".to_string()
+            }
+        );
+        assert_eq!(state_hash(&target.storage), hash_before);
+
+        import_state(target.as_mut(), dump.clone()).unwrap();
+
+        // imported state is equivalent to the exported one, except for pool id
+        let imported = export_state(target.as_ref());
+        assert_eq!(imported.config.pool_id, None);
+        assert_eq!(
+            imported,
+            FullStateDump {
+                config: crate::state_dump::ConfigDump {
+                    pool_id: None,
+                    ..dump.config.clone()
+                },
+                ..dump.clone()
+            }
+        );
+
+        // state can only be imported once
+        let err = import_state(target.as_mut(), dump).unwrap_err();
+        assert_eq!(err, ContractError::StateNotFresh {});
+    }
+
+    #[test]
+    fn test_sender_access_mode() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "provider",
+                &[Coin::new(100_000, "uosmo"), Coin::new(100_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::UpdateSenderAccessList {
+                add: vec!["listed".to_string()],
+                remove: vec![],
+            }),
+        )
+        .unwrap();
+
+        let swap = |deps: DepsMut, sender: &str| {
+            sudo(
+                deps,
+                mock_env(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: sender.to_string(),
+                    token_in: Coin::new(1_000, "uosmo"),
+                    token_out_denom: "uion".to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+        };
+
+        let is_sender_allowed = |deps: cosmwasm_std::Deps, sender: &str| {
+            let IsSenderAllowedResponse { is_allowed, .. } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::IsSenderAllowed {
+                        sender: sender.to_string(),
+                    }),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            is_allowed
+        };
+
+        // only admin can set sender access mode
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetSenderAccessMode {
+                mode: SenderAccessMode::Allowlist,
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        for (mode, allowed, denied) in [
+            (SenderAccessMode::Open, vec!["listed", "unlisted"], vec![]),
+            (
+                SenderAccessMode::Allowlist,
+                vec!["listed"],
+                vec!["unlisted"],
+            ),
+            (SenderAccessMode::Denylist, vec!["unlisted"], vec!["listed"]),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::SetSenderAccessMode { mode }),
+            )
+            .unwrap();
+
+            for sender in allowed {
+                assert!(is_sender_allowed(deps.as_ref(), sender));
+                swap(deps.as_mut(), sender).unwrap();
+            }
+
+            for sender in denied {
+                assert!(!is_sender_allowed(deps.as_ref(), sender));
+                let err = swap(deps.as_mut(), sender).unwrap_err();
+                assert_eq!(
+                    err,
+                    ContractError::SenderNotAllowed {
+                        sender: sender.to_string()
+                    }
+                );
+            }
+        }
+
+        // denied sender can't join pool either
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("listed", &[Coin::new(1_000, "uosmo")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SenderNotAllowed {
+                sender: "listed".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_swap_fee_must_be_less_than_one() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
 
-        for denom in ["uosmo", "uion"] {
-            assert_dirty_change_limiters_by_denom!(
-                denom,
-                Transmuter::new().limiters,
-                deps.as_ref().storage
-            );
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "provider",
+                &[Coin::new(100_000, "uosmo"), Coin::new(100_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let swap = |deps: DepsMut, swap_fee: Decimal| {
+            sudo(
+                deps,
+                mock_env(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: "someone".to_string(),
+                    token_in: Coin::new(1_000, "uosmo"),
+                    token_out_denom: "uion".to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee,
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+        };
+
+        for swap_fee in [Decimal::one(), Decimal::percent(150)] {
+            let err = swap(deps.as_mut(), swap_fee).unwrap_err();
+            assert_eq!(err, ContractError::SwapFeeMustBeLessThanOne {});
         }
 
-        // Add new assets
+        let err = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountOut {
+                sender: "someone".to_string(),
+                token_in_denom: "uosmo".to_string(),
+                token_in_max_amount: Uint128::new(1_000),
+                token_out: Coin::new(1_000, "uion"),
+                swap_fee: Decimal::one(),
+                require_improves_balance: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::SwapFeeMustBeLessThanOne {});
+
+        swap(deps.as_mut(), Decimal::zero()).unwrap();
+    }
+
+    #[test]
+    fn test_input_for_target_weight() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "provider",
+                &[Coin::new(100_000, "uosmo"), Coin::new(100_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        // retained fee grows the pool, so that the weight math is not a plain ratio
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetSwapFee {
+                swap_fee: Decimal::permille(3),
+            }),
+        )
+        .unwrap();
+
+        let input_for_target_weight = |deps: cosmwasm_std::Deps, target_weight: Decimal| {
+            let InputForTargetWeightResponse { token_in } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::InputForTargetWeight {
+                        token_in_denom: "uosmo".to_string(),
+                        token_out_denom: "uion".to_string(),
+                        target_weight,
+                    }),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            token_in
+        };
+
+        let uion_weight = |storage: &dyn Storage| {
+            Transmuter::new()
+                .pool
+                .load(storage)
+                .unwrap()
+                .weights()
+                .unwrap()
+                .unwrap()
+                .into_iter()
+                .find(|(denom, _)| denom == "uion")
+                .unwrap()
+                .1
+        };
+
+        // weight already at or below target needs no input
+        assert_eq!(
+            input_for_target_weight(deps.as_ref(), Decimal::percent(60)),
+            Coin::new(0, "uosmo")
+        );
+
+        let target_weight = Decimal::percent(40);
+        let token_in = input_for_target_weight(deps.as_ref(), target_weight);
+        assert!(token_in.amount > Uint128::new(20_000));
+
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountIn {
+                sender: "someone".to_string(),
+                token_in,
+                token_out_denom: "uion".to_string(),
+                token_out_min_amount: Uint128::one(),
+                swap_fee: Decimal::permille(3),
+                partial_fill: false,
+                require_improves_balance: false,
+            },
+        )
+        .unwrap();
+
+        // weight lands at the target, within rounding
+        let weight = uion_weight(&deps.storage);
+        assert!(weight >= target_weight);
+        assert!(weight - target_weight < Decimal::raw(100_000_000_000_000));
+
+        // limiter caps the input before the target is reached
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(65),
+                },
+            }),
+        )
+        .unwrap();
+
+        let token_in = input_for_target_weight(deps.as_ref(), Decimal::percent(20));
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountIn {
+                sender: "someone".to_string(),
+                token_in,
+                token_out_denom: "uion".to_string(),
+                token_out_min_amount: Uint128::one(),
+                swap_fee: Decimal::permille(3),
+                partial_fill: false,
+                require_improves_balance: false,
+            },
+        )
+        .unwrap();
+
+        let weight = uion_weight(&deps.storage);
+        assert!(weight > Decimal::percent(20));
+        assert!(weight - Decimal::percent(35) < Decimal::raw(100_000_000_000_000));
+    }
+
+    #[test]
+    fn test_set_asset_group_paused() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "axlusdc"),
+                Coin::new(1, "whusdc"),
+                Coin::new(1, "usdc"),
+            ],
+        );
+
+        let admin = "admin";
+        let moderator = "moderator";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("axlusdc"),
+                AssetConfig::from_denom_str("whusdc"),
+                AssetConfig::from_denom_str("usdc"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "allusdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "allusdc".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "user",
+                &[
+                    Coin::new(1_000_000, "axlusdc"),
+                    Coin::new(1_000_000, "whusdc"),
+                    Coin::new(1_000_000, "usdc"),
+                ],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let exec = |deps: DepsMut, sender: &str, msg: ExecMsg| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+        };
+
+        let swap = |deps: DepsMut, denom_in: &str, denom_out: &str| {
+            sudo(
+                deps,
+                mock_env(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: "user".to_string(),
+                    token_in: Coin::new(1000, denom_in),
+                    token_out_denom: denom_out.to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+        };
+
+        exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::CreateAssetGroup {
+                label: "bridged".to_string(),
+                denoms: vec!["axlusdc".to_string(), "whusdc".to_string()],
+            },
+        )
+        .unwrap();
+
+        // only moderator can pause asset group
+        let err = exec(
+            deps.as_mut(),
+            admin,
+            ExecMsg::SetAssetGroupPaused {
+                label: "bridged".to_string(),
+                paused: true,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::SetAssetGroupPaused {
+                label: "bridged".to_string(),
+                paused: true,
+            },
+        )
+        .unwrap();
+
+        // swaps into the paused group are rejected
+        let err = swap(deps.as_mut(), "axlusdc", "usdc").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AssetGroupPaused {
+                label: "bridged".to_string(),
+                denom: "axlusdc".to_string()
+            }
+        );
+
+        // swaps out of the paused group decrease exposure and are allowed
+        swap(deps.as_mut(), "usdc", "whusdc").unwrap();
+
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::SetAssetGroupPaused {
+                label: "bridged".to_string(),
+                paused: false,
+            },
+        )
+        .unwrap();
+
+        swap(deps.as_mut(), "axlusdc", "usdc").unwrap();
+    }
+
+    #[test]
+    fn test_corrupted_and_paused() {
+        let mut deps = mock_dependencies();
+
+        let denoms = ["axlusdc", "whusdc", "noblusdc", "usdc"];
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            denoms.iter().map(|denom| Coin::new(1, *denom)).collect(),
+        );
 
-        // Attempt to add assets with invalid denom
-        let info = mock_info(admin, &[]);
-        let invalid_denoms = vec!["invalid_asset1".to_string(), "invalid_asset2".to_string()];
-        let add_invalid_assets_msg = ContractExecMsg::Transmuter(ExecMsg::AddNewAssets {
-            asset_configs: invalid_denoms
-                .into_iter()
-                .map(|denom| AssetConfig::from_denom_str(denom.as_str()))
+        let admin = "admin";
+        let moderator = "moderator";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: denoms
+                .iter()
+                .map(|denom| AssetConfig::from_denom_str(denom))
                 .collect(),
-        });
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "allusdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: moderator.to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
 
-        env.block.time = env.block.time.plus_nanos(360);
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
-        let res = execute(
+        // Manually reply
+        reply(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            add_invalid_assets_msg,
-        );
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "allusdc".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
 
-        // Check if the attempt resulted in DenomHasNoSupply error
-        assert_eq!(
-            res.unwrap_err(),
-            ContractError::DenomHasNoSupply {
-                denom: "invalid_asset1".to_string()
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "user",
+                &denoms
+                    .iter()
+                    .map(|denom| Coin::new(1_000_000, *denom))
+                    .collect::<Vec<_>>(),
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let exec = |deps: DepsMut, sender: &str, msg: ExecMsg| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+        };
+
+        let swap = |deps: DepsMut, denom_in: &str, denom_out: &str| {
+            sudo(
+                deps,
+                mock_env(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: "user".to_string(),
+                    token_in: Coin::new(1000, denom_in),
+                    token_out_denom: denom_out.to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+        };
+
+        // whusdc is paused only, noblusdc is both paused and corrupted, axlusdc is corrupted only
+        for (label, denom) in [("wormhole", "whusdc"), ("noble", "noblusdc")] {
+            exec(
+                deps.as_mut(),
+                admin,
+                ExecMsg::CreateAssetGroup {
+                    label: label.to_string(),
+                    denoms: vec![denom.to_string()],
+                },
+            )
+            .unwrap();
+
+            exec(
+                deps.as_mut(),
+                moderator,
+                ExecMsg::SetAssetGroupPaused {
+                    label: label.to_string(),
+                    paused: true,
+                },
+            )
+            .unwrap();
+        }
+
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::MarkCorruptedAssets {
+                denoms: vec!["axlusdc".to_string(), "noblusdc".to_string()],
+            },
+        )
+        .unwrap();
+
+        let corrupted = |denom: &str| ContractError::CorruptedAssetRelativelyIncreased {
+            denom: denom.to_string(),
+        };
+        let paused = |label: &str, denom: &str| ContractError::AssetGroupPaused {
+            label: label.to_string(),
+            denom: denom.to_string(),
+        };
+
+        // either one blocks swapping in, corruption is the more specific reason when both apply
+        let transmuter = Transmuter::new();
+        let pool = transmuter.pool.load(&deps.storage).unwrap();
+        for (denom, expected_reason) in [
+            ("axlusdc", Some(corrupted("axlusdc"))),
+            ("whusdc", Some(paused("wormhole", "whusdc"))),
+            ("noblusdc", Some(corrupted("noblusdc"))),
+            ("usdc", None),
+        ] {
+            assert_eq!(
+                transmuter
+                    .is_swap_blocked(&deps.storage, &pool, denom, SwapDirection::In)
+                    .unwrap(),
+                expected_reason
+            );
+
+            // swapping out is never blocked
+            assert_eq!(
+                transmuter
+                    .is_swap_blocked(&deps.storage, &pool, denom, SwapDirection::Out)
+                    .unwrap(),
+                None
+            );
+
+            match expected_reason {
+                Some(reason) => {
+                    assert_eq!(swap(deps.as_mut(), denom, "usdc").unwrap_err(), reason)
+                }
+                None => {
+                    swap(deps.as_mut(), denom, "axlusdc").unwrap();
+                }
             }
+        }
+
+        for denom in ["axlusdc", "whusdc", "noblusdc"] {
+            swap(deps.as_mut(), "usdc", denom).unwrap();
+        }
+
+        // unpausing leaves the denom blocked by corruption
+        exec(
+            deps.as_mut(),
+            moderator,
+            ExecMsg::SetAssetGroupPaused {
+                label: "noble".to_string(),
+                paused: false,
+            },
+        )
+        .unwrap();
+
+        let err = swap(deps.as_mut(), "noblusdc", "usdc").unwrap_err();
+        assert_eq!(err, corrupted("noblusdc"));
+    }
+
+    #[test]
+    fn test_relative_limiter() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "uosmo"),
+                Coin::new(1, "uion"),
+                Coin::new(1, "uatom"),
+            ],
         );
 
-        let new_assets = vec!["new_asset1".to_string(), "new_asset2".to_string()];
-        let add_assets_msg = ContractExecMsg::Transmuter(ExecMsg::AddNewAssets {
-            asset_configs: new_assets
-                .into_iter()
-                .map(|denom| AssetConfig::from_denom_str(denom.as_str()))
-                .collect(),
-        });
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
 
-        env.block.time = env.block.time.plus_nanos(360);
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
-        // Attempt to add assets by non-admin
-        let non_admin_info = mock_info("non_admin", &[]);
-        let res = execute(
+        // Manually reply
+        reply(
             deps.as_mut(),
             env.clone(),
-            non_admin_info,
-            add_assets_msg.clone(),
-        );
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
 
-        // Check if the attempt was unauthorized
+        let exec = |deps: DepsMut, sender: &str, funds: &[Coin], msg: ExecMsg| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, funds),
+                ContractExecMsg::Transmuter(msg),
+            )
+        };
+
+        exec(
+            deps.as_mut(),
+            "user",
+            &[Coin::new(1000, "uosmo"), Coin::new(1000, "uion")],
+            ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+        )
+        .unwrap();
+
+        // max deviation must be within (0, 1]
+        for (max_deviation, expected_err) in [
+            (Decimal::zero(), ContractError::ZeroMaxDeviation {}),
+            (
+                Decimal::percent(101),
+                ContractError::ExceedHundredPercentMaxDeviation {},
+            ),
+        ] {
+            let err = exec(
+                deps.as_mut(),
+                admin,
+                &[],
+                ExecMsg::RegisterLimiter {
+                    denom: "uosmo".to_string(),
+                    label: "relative".to_string(),
+                    limiter_params: LimiterParams::RelativeLimiter { max_deviation },
+                },
+            )
+            .unwrap_err();
+            assert_eq!(err, expected_err);
+        }
+
+        // uosmo weight may deviate at most 10% from the even split,
+        // static limiter caps uosmo weight at 60% regardless of asset count
+        for (label, limiter_params) in [
+            (
+                "relative",
+                LimiterParams::RelativeLimiter {
+                    max_deviation: Decimal::percent(10),
+                },
+            ),
+            (
+                "static",
+                LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            ),
+        ] {
+            exec(
+                deps.as_mut(),
+                admin,
+                &[],
+                ExecMsg::RegisterLimiter {
+                    denom: "uosmo".to_string(),
+                    label: label.to_string(),
+                    limiter_params,
+                },
+            )
+            .unwrap();
+        }
+
+        // with 2 assets, uosmo weight must be within 40% - 60%
+        exec(
+            deps.as_mut(),
+            "user",
+            &[Coin::new(400, "uosmo")],
+            ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+        )
+        .unwrap();
+
+        // add third asset, the even split becomes 1/3
+        exec(
+            deps.as_mut(),
+            admin,
+            &[],
+            ExecMsg::AddNewAssets {
+                asset_configs: vec![AssetConfig::from_denom_str("uatom")],
+            },
+        )
+        .unwrap();
+
+        exec(
+            deps.as_mut(),
+            "user",
+            &[Coin::new(1400, "uatom")],
+            ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+        )
+        .unwrap();
+
+        // uosmo weight 2100 / 4500 is within the static 60% cap,
+        // but deviates more than 10% from 1/3
+        let err = exec(
+            deps.as_mut(),
+            "user",
+            &[Coin::new(700, "uosmo")],
+            ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+        )
+        .unwrap_err();
         assert_eq!(
-            res.unwrap_err(),
-            ContractError::Unauthorized {},
-            "Adding assets by non-admin should be unauthorized"
+            err,
+            ContractError::MaxDeviationExceeded {
+                denom: "uosmo".to_string(),
+                even_weight: Decimal::from_ratio(1u128, 3u128),
+                max_deviation: Decimal::percent(10),
+                value: Decimal::from_ratio(2100u128, 4500u128),
+            }
         );
 
-        env.block.time = env.block.time.plus_nanos(360);
+        // uosmo weight 1800 / 4200 is within 10% of 1/3
+        exec(
+            deps.as_mut(),
+            "user",
+            &[Coin::new(400, "uosmo")],
+            ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+        )
+        .unwrap();
 
-        // successful asset addition
-        execute(deps.as_mut(), env.clone(), info, add_assets_msg).unwrap();
+        // only relative limiter's max deviation can be set
+        let err = exec(
+            deps.as_mut(),
+            admin,
+            &[],
+            ExecMsg::SetRelativeLimiterMaxDeviation {
+                denom: "uosmo".to_string(),
+                label: "static".to_string(),
+                max_deviation: Decimal::percent(20),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::WrongLimiterType {
+                expected: "relative_limiter".to_string(),
+                actual: "static_limiter".to_string(),
+            }
+        );
 
-        let reset_at = env.block.time;
-        let transmuter = Transmuter::new();
+        exec(
+            deps.as_mut(),
+            admin,
+            &[],
+            ExecMsg::SetRelativeLimiterMaxDeviation {
+                denom: "uosmo".to_string(),
+                label: "relative".to_string(),
+                max_deviation: Decimal::percent(20),
+            },
+        )
+        .unwrap();
 
-        // Reset change limiter states if new assets are added
-        for denom in ["uosmo", "uion"] {
-            assert_reset_change_limiters_by_denom!(
-                denom,
-                reset_at,
-                transmuter,
-                deps.as_ref().storage
-            );
-        }
+        // uosmo weight 2100 / 4500 is now allowed
+        exec(
+            deps.as_mut(),
+            "user",
+            &[Coin::new(300, "uosmo")],
+            ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+        )
+        .unwrap();
 
-        env.block.time = env.block.time.plus_nanos(360);
+        let res: LimitersByTypeResponse = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                ContractQueryMsg::Transmuter(QueryMsg::LimitersByType {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.relative_limiters,
+            vec![RelativeLimiterInfo {
+                denom: "uosmo".to_string(),
+                label: "relative".to_string(),
+                max_deviation: Decimal::percent(20),
+            }]
+        );
 
-        // Check if the new assets were added
-        let res = query(
-            deps.as_ref(),
-            env,
-            ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+        // uosmo weight 2100 / 4500 is out of bound again with 10% max deviation
+        exec(
+            deps.as_mut(),
+            admin,
+            &[],
+            ExecMsg::SetRelativeLimiterMaxDeviation {
+                denom: "uosmo".to_string(),
+                label: "relative".to_string(),
+                max_deviation: Decimal::percent(10),
+            },
         )
         .unwrap();
-        let GetTotalPoolLiquidityResponse {
-            total_pool_liquidity,
-        } = from_json(res).unwrap();
 
+        let swap = |deps: DepsMut, denom_in: &str, denom_out: &str| {
+            sudo(
+                deps,
+                mock_env(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: "user".to_string(),
+                    token_in: Coin::new(100, denom_in),
+                    token_out_denom: denom_out.to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+        };
+
+        // moving uosmo further out of bound is rejected
+        let err = swap(deps.as_mut(), "uosmo", "uatom").unwrap_err();
         assert_eq!(
-            total_pool_liquidity,
-            vec![
-                Coin::new(1000001000, "uosmo"),
-                Coin::new(1000001000, "uion"),
-                Coin::new(0, "new_asset1"),
-                Coin::new(0, "new_asset2"),
-            ]
+            err,
+            ContractError::MaxDeviationExceeded {
+                denom: "uosmo".to_string(),
+                even_weight: Decimal::from_ratio(1u128, 3u128),
+                max_deviation: Decimal::percent(10),
+                value: Decimal::from_ratio(2200u128, 4500u128),
+            }
         );
+
+        // balancing swap is allowed even though uosmo weight 2000 / 4500 is still out of bound
+        swap(deps.as_mut(), "uatom", "uosmo").unwrap();
     }
 
     #[test]
-    fn test_corrupted_assets() {
+    fn test_net_flow_limiter() {
         let mut deps = mock_dependencies();
 
         // make denom has non-zero total supply
-        deps.querier.update_balance(
-            "someone",
-            vec![
-                Coin::new(1, "wbtc"),
-                Coin::new(1, "tbtc"),
-                Coin::new(1, "nbtc"),
-                Coin::new(1, "stbtc"),
-            ],
-        );
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
         let admin = "admin";
-        let moderator = "moderator";
-        let alloyed_subdenom = "btc";
         let init_msg = InstantiateMsg {
             pool_asset_configs: vec![
-                AssetConfig::from_denom_str("wbtc"),
-                AssetConfig::from_denom_str("tbtc"),
-                AssetConfig::from_denom_str("nbtc"),
-                AssetConfig::from_denom_str("stbtc"),
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
             ],
-            alloyed_asset_subdenom: alloyed_subdenom.to_string(),
-            alloyed_asset_normalization_factor: Uint128::one(),
             admin: Some(admin.to_string()),
-            moderator: moderator.to_string(),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
 
         // Instantiate the contract.
-        let info = mock_info(admin, &[]);
-        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
         // Manually reply
-
-        let res = reply(
+        reply(
             deps.as_mut(),
             env.clone(),
             Reply {
@@ -1216,7 +16410,7 @@ mod tests {
                     events: vec![],
                     data: Some(
                         MsgCreateDenomResponse {
-                            new_token_denom: alloyed_subdenom.to_string(),
+                            new_token_denom: "usomoion".to_string(),
                         }
                         .into(),
                     ),
@@ -1225,896 +16419,1198 @@ mod tests {
         )
         .unwrap();
 
-        let alloyed_token_denom_kv = res.attributes[0].clone();
-        assert_eq!(alloyed_token_denom_kv.key, "alloyed_denom");
-        let alloyed_denom = alloyed_token_denom_kv.value;
-
-        // set limiters
-        let change_limiter_params = LimiterParams::ChangeLimiter {
-            window_config: WindowConfig {
-                window_size: Uint64::from(3600000000000u64),
-                division_count: Uint64::from(5u64),
-            },
-            boundary_offset: Decimal::percent(20),
-        };
-
-        let static_limiter_params = LimiterParams::StaticLimiter {
-            upper_limit: Decimal::percent(30),
-        };
-
-        // Mark corrupted assets by non-moderator
-        let info = mock_info("someone", &[]);
-        let mark_corrupted_assets_msg = ContractExecMsg::Transmuter(ExecMsg::MarkCorruptedAssets {
-            denoms: vec!["wbtc".to_string(), "tbtc".to_string()],
-        });
-
-        let res = execute(
+        execute(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            mark_corrupted_assets_msg,
-        );
-
-        // Check if the attempt resulted in Unauthorized error
-        assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
-
-        // Corrupted denoms must be empty
-        let res = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetCorruptedDenoms {}),
+            mock_info(
+                "user",
+                &[Coin::new(10000, "uosmo"), Coin::new(10000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
         )
         .unwrap();
-        let GetCorrruptedDenomsResponse { corrupted_denoms } = from_json(res).unwrap();
 
-        assert_eq!(corrupted_denoms, Vec::<String>::new());
+        // max net flow must not be zero
+        let register = |deps: DepsMut, max_net_flow: Uint128| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                    denom: "uosmo".to_string(),
+                    label: "net_flow".to_string(),
+                    limiter_params: LimiterParams::NetFlowLimiter {
+                        window_config: WindowConfig {
+                            window_size: Uint64::from(3_600_000_000_000u64),
+                            division_count: Uint64::from(4u64),
+                        },
+                        max_net_flow,
+                    },
+                }),
+            )
+        };
 
-        // The asset must not yet be removed
-        let res = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
-        )
-        .unwrap();
-        let GetTotalPoolLiquidityResponse {
-            total_pool_liquidity,
-        } = from_json(res).unwrap();
+        let err = register(deps.as_mut(), Uint128::zero()).unwrap_err();
+        assert_eq!(err, ContractError::ZeroMaxNetFlow {});
 
+        let res = register(deps.as_mut(), Uint128::new(250)).unwrap();
         assert_eq!(
-            total_pool_liquidity,
+            res.attributes,
             vec![
-                Coin::new(0, "wbtc"),
-                Coin::new(0, "tbtc"),
-                Coin::new(0, "nbtc"),
-                Coin::new(0, "stbtc"),
+                attr("method", "register_limiter"),
+                attr("denom", "uosmo"),
+                attr("label", "net_flow"),
+                attr("limiter_type", "net_flow_limiter"),
+                attr("window_size", "3600000000000"),
+                attr("division_count", "4"),
+                attr("max_net_flow", "250"),
             ]
         );
 
-        // provide some liquidity
-        let liquidity = vec![
-            Coin::new(1_000_000_000_000, "wbtc"),
-            Coin::new(1_000_000_000_000, "tbtc"),
-            Coin::new(1_000_000_000_000, "nbtc"),
-            Coin::new(1_000_000_000_000, "stbtc"),
-        ];
-        deps.querier.update_balance("someone", liquidity.clone());
+        let swap = |deps: DepsMut, env: Env, token_in: Coin, token_out_denom: &str| {
+            sudo(
+                deps,
+                env,
+                SudoMsg::SwapExactAmountIn {
+                    sender: "user".to_string(),
+                    token_in,
+                    token_out_denom: token_out_denom.to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+        };
 
-        let info = mock_info("someone", &liquidity);
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
+        // small uosmo outflows barely move the weights, but accumulate net flow
+        for _ in 0..2 {
+            swap(deps.as_mut(), env.clone(), Coin::new(100, "uion"), "uosmo").unwrap();
+        }
 
-        execute(deps.as_mut(), env.clone(), info.clone(), join_pool_msg).unwrap();
+        let err = swap(deps.as_mut(), env.clone(), Coin::new(100, "uion"), "uosmo").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NetFlowLimitExceeded {
+                denom: "uosmo".to_string(),
+                max_net_flow: Uint128::new(250),
+                net_flow: Int256::from_i128(-300),
+            }
+        );
 
-        // set limiters
-        for denom in ["wbtc", "tbtc", "nbtc", "stbtc"] {
-            let register_limiter_msg = ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
-                denom: denom.to_string(),
-                label: "change_limiter".to_string(),
-                limiter_params: change_limiter_params.clone(),
-            });
+        // inflow offsets the accumulated outflow
+        swap(deps.as_mut(), env.clone(), Coin::new(100, "uosmo"), "uion").unwrap();
+        swap(deps.as_mut(), env.clone(), Coin::new(100, "uion"), "uosmo").unwrap();
 
-            let info = mock_info(admin, &[]);
-            execute(
-                deps.as_mut(),
-                env.clone(),
-                info.clone(),
-                register_limiter_msg,
-            )
-            .unwrap();
+        let err = swap(deps.as_mut(), env.clone(), Coin::new(100, "uion"), "uosmo").unwrap_err();
+        assert!(matches!(err, ContractError::NetFlowLimitExceeded { .. }));
 
-            let register_limiter_msg = ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
-                denom: denom.to_string(),
-                label: "static_limiter".to_string(),
-                limiter_params: static_limiter_params.clone(),
-            });
-            execute(
-                deps.as_mut(),
-                env.clone(),
-                info.clone(),
-                register_limiter_msg,
-            )
-            .unwrap();
-        }
+        // accumulated net flow ages out of the window
+        let mut env = env;
+        env.block.time = env.block.time.plus_hours(1);
+        swap(deps.as_mut(), env.clone(), Coin::new(100, "uion"), "uosmo").unwrap();
 
-        // exit pool a bit to make sure the limiters are dirty
+        let res = query(
+            deps.as_ref(),
+            env,
+            ContractQueryMsg::Transmuter(QueryMsg::ListLimiters {}),
+        )
+        .map(|res| from_json::<ListLimitersResponse>(res).unwrap())
+        .unwrap();
+        assert!(matches!(res.limiters[0].1, Limiter::NetFlowLimiter(_)));
+    }
+
+    #[test]
+    fn test_limiter_stress_tightening() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
         deps.querier
-            .update_balance("someone", vec![Coin::new(1_000, alloyed_denom.clone())]);
-        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
-            tokens_out: vec![Coin::new(1_000, "nbtc")],
-        });
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
-        let info = mock_info("someone", &[]);
-        execute(deps.as_mut(), env.clone(), info.clone(), exit_pool_msg).unwrap();
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
 
-        // Mark corrupted assets by moderator
-        let corrupted_denoms = vec!["wbtc".to_string(), "tbtc".to_string()];
-        let mark_corrupted_assets_msg = ContractExecMsg::Transmuter(ExecMsg::MarkCorruptedAssets {
-            denoms: corrupted_denoms.clone(),
-        });
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
-        let info = mock_info(moderator, &[]);
-        let res = execute(
+        // Manually reply
+        reply(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            mark_corrupted_assets_msg,
-        )
-        .unwrap();
-        // no bank message should be sent, the corrupted asset waits for withdrawal
-        assert_eq!(res.messages, vec![]);
-
-        // corrupted denoms must be updated
-        let res = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetCorruptedDenoms {}),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
         )
         .unwrap();
-        let res: GetCorrruptedDenomsResponse = from_json(res).unwrap();
 
-        assert_eq!(res.corrupted_denoms, corrupted_denoms);
+        let exec = |deps: DepsMut, sender: &str, funds: &[Coin], msg: ExecMsg| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, funds),
+                ContractExecMsg::Transmuter(msg),
+            )
+        };
 
-        // Check if the assets were removed
-        let res = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+        exec(
+            deps.as_mut(),
+            "user",
+            &[Coin::new(1000, "uosmo"), Coin::new(1000, "uion")],
+            ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
         )
         .unwrap();
-        let GetTotalPoolLiquidityResponse {
-            total_pool_liquidity,
-        } = from_json(res).unwrap();
 
+        exec(
+            deps.as_mut(),
+            admin,
+            &[],
+            ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(70),
+                },
+            },
+        )
+        .unwrap();
+
+        let set_stress_config = |deps: DepsMut, stress_config: Option<StressConfig>| {
+            exec(
+                deps,
+                admin,
+                &[],
+                ExecMsg::SetLimiterStressConfig { stress_config },
+            )
+        };
+        let stress_config = |tightening_factor: Decimal| StressConfig {
+            imbalance_threshold: Decimal::percent(20),
+            tightening_factor,
+        };
+
+        // tightening factor must be within (0, 1]
+        for tightening_factor in [Decimal::zero(), Decimal::percent(101)] {
+            let err = set_stress_config(deps.as_mut(), Some(stress_config(tightening_factor)))
+                .unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::InvalidTighteningFactor { tightening_factor }
+            );
+        }
+
+        // under stress, uosmo upper limit is tightened to 70% * 80% = 56%
+        let res =
+            set_stress_config(deps.as_mut(), Some(stress_config(Decimal::percent(80)))).unwrap();
         assert_eq!(
-            total_pool_liquidity,
+            res.attributes,
             vec![
-                Coin::new(1_000_000_000_000, "wbtc"),
-                Coin::new(1_000_000_000_000, "tbtc"),
-                Coin::new(999_999_999_000, "nbtc"),
-                Coin::new(1_000_000_000_000, "stbtc"),
+                attr("method", "set_limiter_stress_config"),
+                attr("imbalance_threshold", "0.2"),
+                attr("tightening_factor", "0.8"),
             ]
         );
 
-        // warm up the limiters
-        let env = increase_block_height(&env, 1);
-        deps.querier
-            .update_balance("someone", vec![Coin::new(4, alloyed_denom.clone())]);
-        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
-            tokens_out: vec![
-                Coin::new(1, "wbtc"),
-                Coin::new(1, "tbtc"),
-                Coin::new(1, "nbtc"),
-                Coin::new(1, "stbtc"),
-            ],
-        });
-        let info = mock_info("someone", &[]);
-        execute(deps.as_mut(), env.clone(), info.clone(), exit_pool_msg).unwrap();
-
-        let env = increase_block_height(&env, 1);
-
-        deps.querier
-            .update_balance("someone", vec![Coin::new(4, alloyed_denom.clone())]);
-        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
-            tokens_out: vec![
-                Coin::new(1, "wbtc"),
-                Coin::new(1, "tbtc"),
-                Coin::new(1, "nbtc"),
-                Coin::new(1, "stbtc"),
-            ],
-        });
-        let info = mock_info("someone", &[]);
-        execute(deps.as_mut(), env.clone(), info.clone(), exit_pool_msg).unwrap();
-
-        let env = increase_block_height(&env, 1);
-
-        for denom in corrupted_denoms {
-            let expected_err = ContractError::CorruptedAssetRelativelyIncreased {
-                denom: denom.clone(),
-            };
-
-            // join with corrupted denom should fail
-            let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
-            let err = execute(
-                deps.as_mut(),
-                env.clone(),
-                mock_info("user", &[Coin::new(1000, denom.clone())]),
-                join_pool_msg,
+        let swap = |deps: DepsMut, token_in: Coin, token_out_denom: &str| {
+            sudo(
+                deps,
+                mock_env(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: "user".to_string(),
+                    token_in,
+                    token_out_denom: token_out_denom.to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
             )
-            .unwrap_err();
-            assert_eq!(expected_err, err);
+        };
+        let under_stress = |deps: cosmwasm_std::Deps| {
+            query(
+                deps,
+                mock_env(),
+                ContractQueryMsg::Transmuter(QueryMsg::GetLimiterStressConfig {}),
+            )
+            .map(|res| from_json::<GetLimiterStressConfigResponse>(res).unwrap())
+            .unwrap()
+            .under_stress
+        };
 
-            // swap exact in with corrupted denom as token in should fail
-            let swap_msg = SudoMsg::SwapExactAmountIn {
-                token_in: Coin::new(1000, denom.clone()),
-                swap_fee: Decimal::zero(),
-                sender: "mock_sender".to_string(),
-                token_out_denom: "nbtc".to_string(),
-                token_out_min_amount: Uint128::new(500),
-            };
+        // uosmo weight 60% is above the tightened limit,
+        // but imbalance 0.2 does not exceed the threshold, so the own limit applies
+        swap(deps.as_mut(), Coin::new(200, "uosmo"), "uion").unwrap();
+        assert!(!under_stress(deps.as_ref()));
 
-            let err = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap_err();
-            assert_eq!(expected_err, err);
+        // crossing the imbalance threshold tightens the upper limit
+        let err = swap(deps.as_mut(), Coin::new(50, "uosmo"), "uion").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UpperLimitExceeded {
+                denom: "uosmo".to_string(),
+                upper_limit: Decimal::percent(56),
+                value: Decimal::from_ratio(1250u128, 2000u128),
+            }
+        );
 
-            // swap exact in with corrupted denom as token out should be ok since it decreases the corrupted asset
-            let swap_msg = SudoMsg::SwapExactAmountIn {
-                token_in: Coin::new(1000, "nbtc"),
-                swap_fee: Decimal::zero(),
-                sender: "mock_sender".to_string(),
-                token_out_denom: denom.clone(),
-                token_out_min_amount: Uint128::new(500),
-            };
+        // without stress config, only the own upper limit applies
+        set_stress_config(deps.as_mut(), None).unwrap();
+        swap(deps.as_mut(), Coin::new(50, "uosmo"), "uion").unwrap();
+        assert!(!under_stress(deps.as_ref()));
 
-            sudo(deps.as_mut(), env.clone(), swap_msg).unwrap();
+        set_stress_config(deps.as_mut(), Some(stress_config(Decimal::percent(80)))).unwrap();
+        assert!(under_stress(deps.as_ref()));
 
-            // swap exact out with corrupted denom as token out should be ok since it decreases the corrupted asset
-            let swap_msg = SudoMsg::SwapExactAmountOut {
-                sender: "mock_sender".to_string(),
-                token_out: Coin::new(500, denom.clone()),
-                swap_fee: Decimal::zero(),
-                token_in_denom: "nbtc".to_string(),
-                token_in_max_amount: Uint128::new(1000),
-            };
+        // recovering from the imbalance relaxes the upper limit back
+        swap(deps.as_mut(), Coin::new(150, "uion"), "uosmo").unwrap();
+        assert!(!under_stress(deps.as_ref()));
+        swap(deps.as_mut(), Coin::new(100, "uosmo"), "uion").unwrap();
 
-            sudo(deps.as_mut(), env.clone(), swap_msg).unwrap();
+        // own upper limit is kept intact
+        let ListLimitersResponse { limiters } = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                ContractQueryMsg::Transmuter(QueryMsg::ListLimiters {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            limiters[0].1.params(),
+            LimiterParams::StaticLimiter {
+                upper_limit: Decimal::percent(70),
+            }
+        );
+    }
 
-            // swap exact out with corrupted denom as token in should fail
-            let swap_msg = SudoMsg::SwapExactAmountOut {
-                sender: "mock_sender".to_string(),
-                token_out: Coin::new(500, "nbtc"),
-                swap_fee: Decimal::zero(),
-                token_in_denom: denom.clone(),
-                token_in_max_amount: Uint128::new(1000),
-            };
+    #[test]
+    fn test_swap_routes() {
+        let mut deps = mock_dependencies();
 
-            let err = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap_err();
-            assert_eq!(expected_err, err);
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
-            // exit with by any denom requires corrupted denom to not increase in weight
-            // (this case increase other remaining corrupted denom weight)
-            deps.querier.update_balance(
-                "someone",
-                vec![Coin::new(4_000_000_000, alloyed_denom.clone())],
-            );
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
 
-            let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
-                tokens_out: vec![Coin::new(1_000_000_000, "stbtc")],
-            });
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
-            let info = mock_info("someone", &[]);
+        // Manually reply
+        let alloyed_denom = "usomoion";
 
-            // this causes all corrupted denoms to be increased in weight
-            let err = execute(deps.as_mut(), env.clone(), info, exit_pool_msg).unwrap_err();
-            assert!(matches!(
-                err,
-                ContractError::CorruptedAssetRelativelyIncreased { .. }
-            ));
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
 
-            let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
-                tokens_out: vec![
-                    Coin::new(1_000_000_000, "nbtc"),
-                    Coin::new(1_000_000_000, denom.clone()),
-                ],
-            });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "user",
+                &[Coin::new(1_000_000, "uosmo"), Coin::new(1_000_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
 
-            let info = mock_info("someone", &[]);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetSwapFee {
+                swap_fee: Decimal::percent(1),
+            }),
+        )
+        .unwrap();
 
-            // this causes other corrupted denom to be increased relatively
-            let err = execute(deps.as_mut(), env.clone(), info, exit_pool_msg).unwrap_err();
-            assert!(matches!(
-                err,
-                ContractError::CorruptedAssetRelativelyIncreased { .. }
-            ));
-        }
+        let swap_routes = |deps: cosmwasm_std::Deps, token_in: Coin, token_out_denom: &str| {
+            query(
+                deps,
+                mock_env(),
+                ContractQueryMsg::Transmuter(QueryMsg::SwapRoutes {
+                    token_in,
+                    token_out_denom: token_out_denom.to_string(),
+                }),
+            )
+            .map(|res| from_json::<SwapRoutesResponse>(res).unwrap())
+        };
 
-        // exit with corrupted denom requires all corrupted denom exit with the same value
-        deps.querier.update_balance(
-            "someone",
-            vec![Coin::new(4_000_000_000, alloyed_denom.clone())],
+        // swap fee is charged on each hop
+        let SwapRoutesResponse { routes } =
+            swap_routes(deps.as_ref(), Coin::new(10_000, "uosmo"), "uion").unwrap();
+        assert_eq!(
+            routes,
+            vec![
+                SwapRoute {
+                    path: vec!["uosmo".to_string(), "uion".to_string()],
+                    token_out: Coin::new(9_900, "uion"),
+                },
+                SwapRoute {
+                    path: vec![
+                        "uosmo".to_string(),
+                        alloyed_denom.to_string(),
+                        "uion".to_string()
+                    ],
+                    token_out: Coin::new(9_801, "uion"),
+                },
+            ]
         );
-        let info = mock_info("someone", &[]);
-        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
-            tokens_out: vec![
-                Coin::new(2_000_000_000, "nbtc"),
-                Coin::new(1_000_000_000, "wbtc"),
-                Coin::new(1_000_000_000, "tbtc"),
-            ],
-        });
-        execute(deps.as_mut(), env.clone(), info, exit_pool_msg).unwrap();
 
-        // force redeem corrupted assets
+        // both denoms must be pool assets
+        let err =
+            swap_routes(deps.as_ref(), Coin::new(10_000, "uosmo"), alloyed_denom).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPoolAssetDenom {
+                denom: alloyed_denom.to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_feasible_inputs() {
+        let mut deps = mock_dependencies();
 
+        // make denom has non-zero total supply
         deps.querier.update_balance(
             "someone",
-            vec![Coin::new(1_000_000_000_000, alloyed_denom.clone())], // TODO: increase shares
+            vec![
+                Coin::new(1, "uosmo"),
+                Coin::new(1, "uion"),
+                Coin::new(1, "uatom"),
+            ],
         );
-        let all_nbtc = total_liquidity_of("nbtc", &deps.storage);
-        let force_redeem_corrupted_assets_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
-            tokens_out: vec![all_nbtc],
-        });
 
-        let info = mock_info("someone", &[]);
-        let err = execute(
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+                AssetConfig::from_denom_str("uatom"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            force_redeem_corrupted_assets_msg,
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
         )
-        .unwrap_err();
-
-        assert_eq!(
-            err,
-            ContractError::CorruptedAssetRelativelyIncreased {
-                denom: "wbtc".to_string()
-            }
-        );
-
-        let all_wbtc = total_liquidity_of("wbtc", &deps.storage);
-        let force_redeem_corrupted_assets_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
-            tokens_out: vec![all_wbtc],
-        });
+        .unwrap();
 
-        deps.querier.update_balance(
-            "someone",
-            vec![Coin::new(1_000_000_000_000, alloyed_denom.clone())],
-        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "user",
+                &[
+                    Coin::new(1000, "uosmo"),
+                    Coin::new(1000, "uion"),
+                    Coin::new(1000, "uatom"),
+                ],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
 
-        let info = mock_info("someone", &[]);
+        // uion weight is 1/3, leaving little headroom under its upper limit
         execute(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            force_redeem_corrupted_assets_msg,
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "uion".to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(35),
+                },
+            }),
         )
         .unwrap();
 
-        // check liquidity
-        let GetTotalPoolLiquidityResponse {
-            total_pool_liquidity,
-        } = from_json(
+        let feasible_inputs = |deps: cosmwasm_std::Deps, token_out: Coin| {
             query(
-                deps.as_ref(),
-                env.clone(),
-                ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+                deps,
+                mock_env(),
+                ContractQueryMsg::Transmuter(QueryMsg::FeasibleInputs { token_out }),
             )
-            .unwrap(),
-        )
-        .unwrap();
+            .map(|res| from_json::<FeasibleInputsResponse>(res).unwrap())
+        };
 
-        assert_eq!(
-            total_pool_liquidity,
-            vec![
-                Coin::new(998999998498, "tbtc"),
-                Coin::new(998000001998, "nbtc"),
-                Coin::new(999999999998, "stbtc"),
-            ]
-        );
+        // uion weight stays at 1030 / 3000 within the limit
+        let res = feasible_inputs(deps.as_ref(), Coin::new(30, "uosmo")).unwrap();
+        assert_eq!(res.denoms, vec!["uion".to_string(), "uatom".to_string()]);
+
+        // uion weight would be 1100 / 3000, exceeding the limit
+        let res = feasible_inputs(deps.as_ref(), Coin::new(100, "uosmo")).unwrap();
+        assert_eq!(res.denoms, vec!["uatom".to_string()]);
+
+        // pool doesn't have enough uosmo for any input
+        let res = feasible_inputs(deps.as_ref(), Coin::new(1001, "uosmo")).unwrap();
+        assert!(res.denoms.is_empty());
 
+        // token out must be a pool asset
+        let err = feasible_inputs(deps.as_ref(), Coin::new(100, "usomoion")).unwrap_err();
         assert_eq!(
-            Transmuter::new()
-                .limiters
-                .list_limiters_by_denom(&deps.storage, "wbtc")
-                .unwrap(),
-            vec![]
+            err,
+            ContractError::InvalidPoolAssetDenom {
+                denom: "usomoion".to_string()
+            }
         );
+    }
 
-        for denom in ["tbtc", "nbtc", "stbtc"] {
-            assert_reset_change_limiters_by_denom!(
-                denom,
-                env.block.time,
-                Transmuter::new(),
-                deps.as_ref().storage
-            );
-        }
+    #[test]
+    fn test_disabled_alloyed_asset() {
+        let mut deps = mock_dependencies();
 
-        // try unmark nbtc should fail
-        let unmark_corrupted_assets_msg =
-            ContractExecMsg::Transmuter(ExecMsg::UnmarkCorruptedAssets {
-                denoms: vec!["nbtc".to_string()],
-            });
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
-        let info = mock_info(moderator, &[]);
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: Some(false),
+        };
+        let env = mock_env();
+
+        // alloyed denom is not created
+        let res = instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+        assert!(res.messages.is_empty());
+
+        let err = query(
+            deps.as_ref(),
+            env.clone(),
+            ContractQueryMsg::Transmuter(QueryMsg::GetShareDenom {}),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AlloyedAssetDisabled {});
+
+        // joining pool mints alloyed asset, so it is rejected
         let err = execute(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            unmark_corrupted_assets_msg,
+            mock_info("user", &[Coin::new(1_000, "uosmo")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
         )
         .unwrap_err();
+        assert_eq!(err, ContractError::AlloyedAssetDisabled {});
 
-        assert_eq!(
-            err,
-            ContractError::InvalidCorruptedAssetDenom {
-                denom: "nbtc".to_string()
-            }
-        );
-
-        // unmark tbtc by non moderator should fail
-        let unmark_corrupted_assets_msg =
-            ContractExecMsg::Transmuter(ExecMsg::UnmarkCorruptedAssets {
-                denoms: vec!["tbtc".to_string()],
-            });
+        let transmuter = Transmuter::new();
+        let mut pool = transmuter.pool.load(&deps.storage).unwrap();
+        pool.join_pool(&[Coin::new(1_000, "uosmo"), Coin::new(1_000, "uion")])
+            .unwrap();
+        transmuter.pool.save(&mut deps.storage, &pool).unwrap();
 
-        let info = mock_info("someone", &[]);
-        let err = execute(
+        // swap between pool assets works as usual
+        sudo(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            unmark_corrupted_assets_msg,
+            SudoMsg::SwapExactAmountIn {
+                sender: "someone".to_string(),
+                token_in: Coin::new(100, "uosmo"),
+                token_out_denom: "uion".to_string(),
+                token_out_min_amount: Uint128::new(100),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                require_improves_balance: false,
+            },
         )
-        .unwrap_err();
+        .unwrap();
 
-        assert_eq!(err, ContractError::Unauthorized {});
+        assert_eq!(
+            total_liquidity_of("uosmo", &deps.storage),
+            Coin::new(1_100, "uosmo")
+        );
+        assert_eq!(
+            total_liquidity_of("uion", &deps.storage),
+            Coin::new(900, "uion")
+        );
 
-        // unmark tbtc
-        let unmark_corrupted_assets_msg =
-            ContractExecMsg::Transmuter(ExecMsg::UnmarkCorruptedAssets {
-                denoms: vec!["tbtc".to_string()],
-            });
+        // pool asset status doesn't depend on the alloyed asset
+        let DenomStatusResponse {
+            can_swap_in,
+            can_swap_out,
+            reasons,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::DenomStatus {
+                    denom: "uosmo".to_string(),
+                }),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(can_swap_in && can_swap_out);
+        assert!(reasons.is_empty());
 
-        let info = mock_info(moderator, &[]);
+        // rebalancing only values fees by normalization factor, so it works as usual
+        transmuter
+            .accrued_fees
+            .save(&mut deps.storage, "uosmo", &Uint128::new(50))
+            .unwrap();
         execute(
             deps.as_mut(),
             env.clone(),
-            info.clone(),
-            unmark_corrupted_assets_msg,
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetFeeCollector {
+                address: "collector".to_string(),
+            }),
         )
         .unwrap();
 
-        // query corrupted denoms
-        let res = query(
-            deps.as_ref(),
+        let res = execute(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetCorruptedDenoms {}),
+            mock_info("keeper", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::Rebalance {}),
         )
         .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: vec![Coin::new(50, "uosmo")],
+            })]
+        );
 
-        let GetCorrruptedDenomsResponse { corrupted_denoms } = from_json(res).unwrap();
-
-        assert_eq!(corrupted_denoms, Vec::<String>::new());
+        // pool asset display decimals can be set without the alloyed asset
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetDisplayDecimals {
+                denom: "uosmo".to_string(),
+                decimals: Some(6),
+            }),
+        )
+        .unwrap();
 
-        // no liquidity or pool assets changes
-        let GetTotalPoolLiquidityResponse {
-            total_pool_liquidity,
-        } = from_json(
+        // there is no alloyed asset supply to flow
+        let res: SupplyFlowResponse = from_json(
             query(
                 deps.as_ref(),
                 env.clone(),
-                ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+                ContractQueryMsg::Transmuter(QueryMsg::SupplyFlow {
+                    window: Uint64::new(3_600_000_000_000),
+                }),
             )
             .unwrap(),
         )
         .unwrap();
-
         assert_eq!(
-            total_pool_liquidity,
-            vec![
-                Coin::new(998999998498, "tbtc"),
-                Coin::new(998000001998, "nbtc"),
-                Coin::new(999999999998, "stbtc"),
-            ]
+            res,
+            SupplyFlowResponse {
+                minted: Uint128::zero(),
+                burned: Uint128::zero(),
+                net_flow: Int256::zero(),
+            }
         );
 
-        // still has all the limiters
+        // there is no alloyed asset to swap into
+        let alloyed_denom = format!("factory/{}/alloyed/usomoion", env.contract.address);
+        let err = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountIn {
+                sender: "someone".to_string(),
+                token_in: Coin::new(100, "uosmo"),
+                token_out_denom: alloyed_denom.clone(),
+                token_out_min_amount: Uint128::new(100),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                require_improves_balance: false,
+            },
+        )
+        .unwrap_err();
         assert_eq!(
-            Transmuter::new()
-                .limiters
-                .list_limiters_by_denom(&deps.storage, "tbtc")
-                .unwrap()
-                .len(),
-            2
+            err,
+            ContractError::InvalidTransmuteDenom {
+                denom: alloyed_denom,
+                expected_denom: vec!["uosmo".to_string(), "uion".to_string()],
+            }
         );
-    }
-
-    fn increase_block_height(env: &Env, height: u64) -> Env {
-        let block_time = 5; // hypothetical block time
-        Env {
-            block: BlockInfo {
-                height: env.block.height + height,
-                time: env.block.time.plus_seconds(block_time * height),
-                chain_id: env.block.chain_id.clone(),
-            },
-            ..env.clone()
-        }
-    }
 
-    fn total_liquidity_of(denom: &str, storage: &dyn Storage) -> Coin {
-        Transmuter::new()
-            .pool
-            .load(storage)
-            .unwrap()
-            .pool_assets
-            .into_iter()
-            .find(|a| a.denom() == denom)
-            .unwrap()
-            .to_coin()
+        // exiting pool burns alloyed asset, so it is rejected
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("user", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+                tokens_out: vec![Coin::new(100, "uosmo")],
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::AlloyedAssetDisabled {});
     }
 
     #[test]
-    fn test_set_active_status() {
+    fn test_registered_alloyed_assets() {
         let mut deps = mock_dependencies();
 
         // make denom has non-zero total supply
-        deps.querier
-            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "uusdc"),
+                Coin::new(1, "uusdt"),
+                Coin::new(1, "ubtc"),
+                Coin::new(1, "wbtc"),
+            ],
+        );
 
         let admin = "admin";
-        let moderator = "moderator";
         let init_msg = InstantiateMsg {
             pool_asset_configs: vec![
-                AssetConfig::from_denom_str("uosmo"),
-                AssetConfig::from_denom_str("uion"),
+                AssetConfig::from_denom_str("uusdc"),
+                AssetConfig::from_denom_str("uusdt"),
+                AssetConfig::from_denom_str("ubtc"),
+                AssetConfig::from_denom_str("wbtc"),
             ],
-            alloyed_asset_subdenom: "uosmouion".to_string(),
-            alloyed_asset_normalization_factor: Uint128::one(),
             admin: Some(admin.to_string()),
-            moderator: moderator.to_string(),
+            alloyed_asset_subdenom: "all".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
-        let info = mock_info(admin, &[]);
 
         // Instantiate the contract.
-        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
-
-        // Manually set alloyed denom
-        let alloyed_denom = "uosmo".to_string();
-
-        let transmuter = Transmuter::new();
-        transmuter
-            .alloyed_asset
-            .set_alloyed_denom(&mut deps.storage, &alloyed_denom)
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        let reply_with_denom = |deps: DepsMut, id: u64, denom: &str| {
+            reply(
+                deps,
+                mock_env(),
+                Reply {
+                    id,
+                    result: SubMsgResult::Ok(SubMsgResponse {
+                        events: vec![],
+                        data: Some(
+                            MsgCreateDenomResponse {
+                                new_token_denom: denom.to_string(),
+                            }
+                            .into(),
+                        ),
+                    }),
+                },
+            )
             .unwrap();
+        };
 
-        // Check the initial active status.
-        let res = query(
-            deps.as_ref(),
+        reply_with_denom(deps.as_mut(), 1, "all");
+
+        // only admin can register alloyed asset
+        let err = execute(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::IsActive {}),
+            mock_info("someone", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterAlloyedAsset {
+                subdenom: "senior".to_string(),
+                backing_denoms: vec!["uusdc".to_string(), "uusdt".to_string()],
+                normalization_factor: Uint128::one(),
+            }),
         )
-        .unwrap();
-        let active_status: IsActiveResponse = from_json(res).unwrap();
-        assert!(active_status.is_active);
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
 
-        // Attempt to set the active status by a non-admin user.
-        let non_admin_info = mock_info("non_moderator", &[]);
-        let non_admin_msg = ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus { active: false });
-        let err = execute(deps.as_mut(), env.clone(), non_admin_info, non_admin_msg).unwrap_err();
+        // register two alloyed assets backed by disjoint denom subsets
+        for (subdenom, backing_denoms) in
+            [("senior", ["uusdc", "uusdt"]), ("junior", ["ubtc", "wbtc"])]
+        {
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::RegisterAlloyedAsset {
+                    subdenom: subdenom.to_string(),
+                    backing_denoms: backing_denoms.iter().map(|d| d.to_string()).collect(),
+                    normalization_factor: Uint128::one(),
+                }),
+            )
+            .unwrap();
+            assert_eq!(
+                res.messages,
+                vec![SubMsg::reply_on_success(
+                    MsgCreateDenom {
+                        sender: env.contract.address.to_string(),
+                        subdenom: format!("alloyed/{}", subdenom),
+                    },
+                    ReplyId::CreateRegisteredAlloyedDenom.id(),
+                )]
+            );
 
-        assert_eq!(err, ContractError::Unauthorized {});
+            reply_with_denom(deps.as_mut(), 2, subdenom);
+        }
 
-        // Set the active status to false.
-        let msg = ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus { active: false });
-        execute(
+        // joining with a denom outside of the backing is rejected
+        let err = execute(
             deps.as_mut(),
             env.clone(),
-            mock_info(moderator, &[]),
-            msg.clone(),
+            mock_info("user", &[Coin::new(1_000, "ubtc")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinAlloyedAsset {
+                alloyed_denom: "senior".to_string(),
+            }),
         )
-        .unwrap();
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidTransmuteDenom {
+                denom: "ubtc".to_string(),
+                expected_denom: vec!["uusdc".to_string(), "uusdt".to_string()],
+            }
+        );
 
-        // Check the active status again.
-        let res = query(
-            deps.as_ref(),
+        // sender access mode applies to joining registered alloyed assets, whichever the entrypoint
+        execute(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::IsActive {}),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetSenderAccessMode {
+                mode: SenderAccessMode::Allowlist,
+            }),
         )
         .unwrap();
-        let active_status: IsActiveResponse = from_json(res).unwrap();
-        assert!(!active_status.is_active);
-
-        // try to set the active status to false again
-        let err = execute(deps.as_mut(), env.clone(), mock_info(moderator, &[]), msg).unwrap_err();
-        assert_eq!(err, ContractError::UnchangedActiveStatus { status: false });
 
-        // Test that JoinPool is blocked when active status is false
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
         let err = execute(
             deps.as_mut(),
             env.clone(),
-            mock_info("user", &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]),
-            join_pool_msg,
+            mock_info("user", &[Coin::new(1_000, "uusdc")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinAlloyedAsset {
+                alloyed_denom: "senior".to_string(),
+            }),
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::InactivePool {});
-
-        // Test that SwapExactAmountIn is blocked when active status is false
-        let swap_exact_amount_in_msg = SudoMsg::SwapExactAmountIn {
-            token_in: Coin::new(1000, "uion"),
-            swap_fee: Decimal::zero(),
-            sender: "mock_sender".to_string(),
-            token_out_denom: "uosmo".to_string(),
-            token_out_min_amount: Uint128::new(500),
-        };
-        let err = sudo(deps.as_mut(), env.clone(), swap_exact_amount_in_msg).unwrap_err();
-        assert_eq!(err, ContractError::InactivePool {});
-
-        // Test that SwapExactAmountOut is blocked when active status is false
-        let swap_exact_amount_out_msg = SudoMsg::SwapExactAmountOut {
-            sender: "mock_sender".to_string(),
-            token_out: Coin::new(500, "uosmo"),
-            swap_fee: Decimal::zero(),
-            token_in_denom: "uion".to_string(),
-            token_in_max_amount: Uint128::new(1000),
-        };
-        let err = sudo(deps.as_mut(), env.clone(), swap_exact_amount_out_msg).unwrap_err();
-        assert_eq!(err, ContractError::InactivePool {});
+        assert_eq!(
+            err,
+            ContractError::SenderNotAllowed {
+                sender: "user".to_string()
+            }
+        );
 
-        // Test that ExitPool is blocked when active status is false
-        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
-            tokens_out: vec![Coin::new(1000, "uion"), Coin::new(1000, "uosmo")],
-        });
-        let err = execute(
+        let err = sudo(
             deps.as_mut(),
             env.clone(),
-            mock_info("user", &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]),
-            exit_pool_msg,
+            SudoMsg::SwapExactAmountOut {
+                sender: "user".to_string(),
+                token_in_denom: "wbtc".to_string(),
+                token_in_max_amount: Uint128::new(500),
+                token_out: Coin::new(500, "junior"),
+                swap_fee: Decimal::zero(),
+                require_improves_balance: false,
+            },
         )
         .unwrap_err();
-        assert_eq!(err, ContractError::InactivePool {});
-
-        // Set the active status back to true
-        let msg = ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus { active: true });
-        execute(deps.as_mut(), env.clone(), mock_info(moderator, &[]), msg).unwrap();
+        assert_eq!(
+            err,
+            ContractError::SenderNotAllowed {
+                sender: "user".to_string()
+            }
+        );
 
-        // Check the active status again.
-        let res = query(
-            deps.as_ref(),
+        execute(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::IsActive {}),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetSenderAccessMode {
+                mode: SenderAccessMode::Open,
+            }),
         )
         .unwrap();
-        let active_status: IsActiveResponse = from_json(res).unwrap();
-        assert!(active_status.is_active);
 
-        // Test that JoinPool is active when active status is true
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
         let res = execute(
             deps.as_mut(),
             env.clone(),
-            mock_info("user", &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]),
-            join_pool_msg,
+            mock_info("user", &[Coin::new(1_000, "uusdc")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinAlloyedAsset {
+                alloyed_denom: "senior".to_string(),
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(MsgMint {
+                sender: env.contract.address.to_string(),
+                amount: Some(Coin::new(1_000, "senior").into()),
+                mint_to_address: "user".to_string(),
+            })]
         );
-        assert!(res.is_ok());
-
-        // Test that SwapExactAmountIn is active when active status is true
-        let swap_exact_amount_in_msg = SudoMsg::SwapExactAmountIn {
-            token_in: Coin::new(100, "uion"),
-            swap_fee: Decimal::zero(),
-            sender: "mock_sender".to_string(),
-            token_out_denom: "uosmo".to_string(),
-            token_out_min_amount: Uint128::new(100),
-        };
-        let res = sudo(deps.as_mut(), env.clone(), swap_exact_amount_in_msg);
-        assert!(res.is_ok());
-
-        // Test that SwapExactAmountOut is active when active status is true
-        let swap_exact_amount_out_msg = SudoMsg::SwapExactAmountOut {
-            sender: "mock_sender".to_string(),
-            token_out: Coin::new(100, "uosmo"),
-            swap_fee: Decimal::zero(),
-            token_in_denom: "uion".to_string(),
-            token_in_max_amount: Uint128::new(100),
-        };
-        let res = sudo(deps.as_mut(), env.clone(), swap_exact_amount_out_msg);
 
-        assert!(res.is_ok());
-
-        // Test setting active status through sudo
-        let set_active_status_msg = SudoMsg::SetActive { is_active: false };
-        let res = sudo(deps.as_mut(), env.clone(), set_active_status_msg);
-        assert!(res.is_ok());
-
-        // Check the active status again.
-        let res = query(
-            deps.as_ref(),
+        // registered alloyed asset swaps are all or nothing
+        let err = sudo(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::IsActive {}),
+            SudoMsg::SwapExactAmountIn {
+                sender: "user".to_string(),
+                token_in: Coin::new(500, "wbtc"),
+                token_out_denom: "junior".to_string(),
+                token_out_min_amount: Uint128::new(500),
+                swap_fee: Decimal::zero(),
+                partial_fill: true,
+                require_improves_balance: false,
+            },
         )
-        .unwrap();
-        let active_status: IsActiveResponse = from_json(res).unwrap();
-        assert!(!active_status.is_active);
-
-        // Set the active status back to true through sudo
-        let set_active_status_msg = SudoMsg::SetActive { is_active: true };
-        let res = sudo(deps.as_mut(), env.clone(), set_active_status_msg);
-        assert!(res.is_ok());
+        .unwrap_err();
+        assert_eq!(err, ContractError::PartialFillNotSupported {});
 
-        // Check the active status again.
-        let res = query(
-            deps.as_ref(),
+        // they don't change pool balance, so it can't be required to improve
+        let err = sudo(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::IsActive {}),
+            SudoMsg::SwapExactAmountIn {
+                sender: "user".to_string(),
+                token_in: Coin::new(500, "wbtc"),
+                token_out_denom: "junior".to_string(),
+                token_out_min_amount: Uint128::new(500),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                require_improves_balance: true,
+            },
         )
-        .unwrap();
-        let active_status: IsActiveResponse = from_json(res).unwrap();
-        assert!(active_status.is_active);
-
-        // try to set active status to true when it's already true
-        let set_active_status_msg = SudoMsg::SetActive { is_active: true };
-
-        let err = sudo(deps.as_mut(), env, set_active_status_msg).unwrap_err();
-
-        assert_eq!(err, ContractError::UnchangedActiveStatus { status: true });
-    }
-
-    #[test]
-    fn test_transfer_and_claim_admin() {
-        let mut deps = mock_dependencies();
-
-        // make denom has non-zero total supply
-        deps.querier
-            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
-
-        let admin = "admin";
-        let moderator = "moderator";
-        let canceling_candidate = "canceling_candidate";
-        let rejecting_candidate = "rejecting_candidate";
-        let candidate = "candidate";
-        let init_msg = InstantiateMsg {
-            pool_asset_configs: vec![
-                AssetConfig::from_denom_str("uosmo"),
-                AssetConfig::from_denom_str("uion"),
-            ],
-            admin: Some(admin.to_string()),
-            alloyed_asset_subdenom: "usomoion".to_string(),
-            alloyed_asset_normalization_factor: Uint128::one(),
-            moderator: moderator.to_string(),
-        };
-        let env = mock_env();
-        let info = mock_info(admin, &[]);
-
-        // Instantiate the contract.
-        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+        .unwrap_err();
+        assert_eq!(err, ContractError::ImprovesBalanceNotSupported {});
 
-        // Transfer admin rights to the canceling candidate
-        let transfer_admin_msg = ContractExecMsg::Transmuter(ExecMsg::TransferAdmin {
-            candidate: canceling_candidate.to_string(),
-        });
-        execute(deps.as_mut(), env.clone(), info.clone(), transfer_admin_msg).unwrap();
+        let err = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountOut {
+                sender: "user".to_string(),
+                token_in_denom: "wbtc".to_string(),
+                token_in_max_amount: Uint128::new(500),
+                token_out: Coin::new(500, "junior"),
+                swap_fee: Decimal::zero(),
+                require_improves_balance: true,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::ImprovesBalanceNotSupported {});
 
-        // Check the admin candidate
-        let res = query(
-            deps.as_ref(),
+        // swapping into a registered alloyed asset mints it against its own backing
+        let res = sudo(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetAdminCandidate {}),
+            SudoMsg::SwapExactAmountIn {
+                sender: "user".to_string(),
+                token_in: Coin::new(500, "wbtc"),
+                token_out_denom: "junior".to_string(),
+                token_out_min_amount: Uint128::new(500),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                require_improves_balance: false,
+            },
         )
         .unwrap();
-        let admin_candidate: GetAdminCandidateResponse = from_json(res).unwrap();
         assert_eq!(
-            admin_candidate.admin_candidate.unwrap().as_str(),
-            canceling_candidate
+            res.messages,
+            vec![SubMsg::new(MsgMint {
+                sender: env.contract.address.to_string(),
+                amount: Some(Coin::new(500, "junior").into()),
+                mint_to_address: "user".to_string(),
+            })]
         );
 
-        // Cancel admin rights transfer
-        let cancel_admin_transfer_msg =
-            ContractExecMsg::Transmuter(ExecMsg::CancelAdminTransfer {});
+        // pool itself is untouched
+        assert_eq!(
+            total_liquidity_of("uusdc", &deps.storage),
+            Coin::new(0, "uusdc")
+        );
+        assert_eq!(
+            total_liquidity_of("wbtc", &deps.storage),
+            Coin::new(0, "wbtc")
+        );
 
-        execute(
+        // senior holders have no claim on other denoms
+        deps.querier
+            .update_balance("user", vec![Coin::new(1_000, "senior")]);
+        let err = execute(
             deps.as_mut(),
             env.clone(),
-            mock_info(admin, &[]),
-            cancel_admin_transfer_msg,
+            mock_info("user", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::ExitAlloyedAsset {
+                alloyed_denom: "senior".to_string(),
+                tokens_out: vec![Coin::new(400, "uusdt")],
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientPoolAsset {
+                required: Coin::new(400, "uusdt"),
+                available: Coin::new(0, "uusdt"),
+            }
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::ExitAlloyedAsset {
+                alloyed_denom: "senior".to_string(),
+                tokens_out: vec![Coin::new(400, "uusdc")],
+            }),
         )
         .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(MsgBurn {
+                    sender: env.contract.address.to_string(),
+                    amount: Some(Coin::new(400, "senior").into()),
+                    burn_from_address: "user".to_string(),
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "user".to_string(),
+                    amount: vec![Coin::new(400, "uusdc")],
+                }),
+            ]
+        );
 
-        // Check the admin candidate
-        let res = query(
-            deps.as_ref(),
+        // swapping out of a registered alloyed asset burns the sent funds
+        deps.querier.update_balance(
+            env.contract.address.to_string(),
+            vec![Coin::new(200, "junior")],
+        );
+        sudo(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetAdminCandidate {}),
+            SudoMsg::SwapExactAmountOut {
+                sender: "user".to_string(),
+                token_in_denom: "junior".to_string(),
+                token_in_max_amount: Uint128::new(200),
+                token_out: Coin::new(200, "wbtc"),
+                swap_fee: Decimal::zero(),
+                require_improves_balance: false,
+            },
         )
         .unwrap();
-        let admin_candidate: GetAdminCandidateResponse = from_json(res).unwrap();
-        assert_eq!(admin_candidate.admin_candidate, None);
 
-        // Transfer admin rights to the rejecting candidate
-        let transfer_admin_msg = ContractExecMsg::Transmuter(ExecMsg::TransferAdmin {
-            candidate: rejecting_candidate.to_string(),
-        });
-        execute(deps.as_mut(), env.clone(), info.clone(), transfer_admin_msg).unwrap();
+        let ListRegisteredAlloyedAssetsResponse { alloyed_assets } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::ListRegisteredAlloyedAssets {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            alloyed_assets,
+            vec![
+                RegisteredAlloyedAsset {
+                    denom: "junior".to_string(),
+                    normalization_factor: Uint128::one(),
+                    backing: TransmuterPool::new(vec![
+                        Asset::new(Uint128::zero(), "ubtc", Uint128::one()).unwrap(),
+                        Asset::new(Uint128::new(300), "wbtc", Uint128::one()).unwrap(),
+                    ])
+                    .unwrap(),
+                },
+                RegisteredAlloyedAsset {
+                    denom: "senior".to_string(),
+                    normalization_factor: Uint128::one(),
+                    backing: TransmuterPool::new(vec![
+                        Asset::new(Uint128::new(600), "uusdc", Uint128::one()).unwrap(),
+                        Asset::new(Uint128::zero(), "uusdt", Uint128::one()).unwrap(),
+                    ])
+                    .unwrap(),
+                },
+            ]
+        );
 
-        // Check the admin candidate
-        let res = query(
-            deps.as_ref(),
+        // registered alloyed asset backing counts toward the total liquidity cap
+        execute(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetAdminCandidate {}),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetMaxTotalLiquidity {
+                max_total_liquidity: Some(Uint128::new(900)),
+            }),
         )
         .unwrap();
-        let admin_candidate: GetAdminCandidateResponse = from_json(res).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &[Coin::new(1, "uusdc")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinAlloyedAsset {
+                alloyed_denom: "senior".to_string(),
+            }),
+        )
+        .unwrap_err();
         assert_eq!(
-            admin_candidate.admin_candidate.unwrap().as_str(),
-            rejecting_candidate
+            err,
+            ContractError::TvlCapExceeded {
+                max: Uint128::new(900),
+                attempted: Uint128::new(901),
+            }
         );
 
-        // Reject admin rights transfer
-        let reject_admin_transfer_msg =
-            ContractExecMsg::Transmuter(ExecMsg::RejectAdminTransfer {});
-
         execute(
             deps.as_mut(),
             env.clone(),
-            mock_info(rejecting_candidate, &[]),
-            reject_admin_transfer_msg,
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetMaxTotalLiquidity {
+                max_total_liquidity: None,
+            }),
         )
         .unwrap();
 
-        // Check the admin candidate
-        let res = query(
-            deps.as_ref(),
+        // denom corrupted in the pool can't be added to the backing, but can still be withdrawn
+        execute(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetAdminCandidate {}),
+            mock_info("moderator", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::MarkCorruptedAssets {
+                denoms: vec!["uusdc".to_string()],
+            }),
         )
         .unwrap();
-        let admin_candidate: GetAdminCandidateResponse = from_json(res).unwrap();
-        assert_eq!(admin_candidate.admin_candidate, None);
 
-        // Transfer admin rights to the candidate
-        let transfer_admin_msg = ContractExecMsg::Transmuter(ExecMsg::TransferAdmin {
-            candidate: candidate.to_string(),
-        });
-        execute(deps.as_mut(), env.clone(), info, transfer_admin_msg).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &[Coin::new(1, "uusdc")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinAlloyedAsset {
+                alloyed_denom: "senior".to_string(),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::CorruptedAssetRelativelyIncreased {
+                denom: "uusdc".to_string()
+            }
+        );
 
-        // Check the admin candidate
-        let res = query(
-            deps.as_ref(),
+        execute(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetAdminCandidate {}),
+            mock_info("user", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::ExitAlloyedAsset {
+                alloyed_denom: "senior".to_string(),
+                tokens_out: vec![Coin::new(100, "uusdc")],
+            }),
         )
         .unwrap();
-        let admin_candidate: GetAdminCandidateResponse = from_json(res).unwrap();
-        assert_eq!(admin_candidate.admin_candidate.unwrap().as_str(), candidate);
 
-        // Claim admin rights by the candidate
-        let claim_admin_msg = ContractExecMsg::Transmuter(ExecMsg::ClaimAdmin {});
+        // registered alloyed asset mints count toward the mint rate limit
         execute(
             deps.as_mut(),
             env.clone(),
-            mock_info(candidate, &[]),
-            claim_admin_msg,
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetMintRateLimit {
+                mint_window: Some(Uint64::new(60_000_000_000)),
+                max_mint_per_window: Uint128::new(100),
+            }),
         )
         .unwrap();
 
-        // Check the current admin
-        let res = query(
-            deps.as_ref(),
+        let err = execute(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetAdmin {}),
+            mock_info("user", &[Coin::new(101, "uusdt")]),
+            ContractExecMsg::Transmuter(ExecMsg::JoinAlloyedAsset {
+                alloyed_denom: "senior".to_string(),
+            }),
         )
-        .unwrap();
-        let admin: GetAdminResponse = from_json(res).unwrap();
-        assert_eq!(admin.admin.as_str(), candidate);
+        .unwrap_err();
+        assert!(matches!(err, ContractError::MintRateLimitExceeded { .. }));
     }
 
     #[test]
-    fn test_assign_and_remove_moderator() {
-        let admin = "admin";
-        let moderator = "moderator";
-
+    fn test_limiter_trip_counts_on_partial_fills() {
         let mut deps = mock_dependencies();
 
         // make denom has non-zero total supply
         deps.querier
             .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
-        // Instantiate the contract.
+        let admin = "admin";
         let init_msg = InstantiateMsg {
             pool_asset_configs: vec![
                 AssetConfig::from_denom_str("uosmo"),
@@ -2123,60 +17619,144 @@ mod tests {
             admin: Some(admin.to_string()),
             alloyed_asset_subdenom: "usomoion".to_string(),
             alloyed_asset_normalization_factor: Uint128::one(),
-            moderator: moderator.to_string(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
-        instantiate(deps.as_mut(), mock_env(), mock_info(admin, &[]), init_msg).unwrap();
+        let env = mock_env();
 
-        // Check the current moderator
-        let res = query(
-            deps.as_ref(),
-            mock_env(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetModerator {}),
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
         )
         .unwrap();
-        let moderator_response: GetModeratorResponse = from_json(res).unwrap();
-        assert_eq!(moderator_response.moderator, moderator);
-
-        let new_moderator = "new_moderator";
 
-        // Try to assign new moderator by non admin
-        let err = execute(
+        execute(
             deps.as_mut(),
-            mock_env(),
-            mock_info("non_admin", &[]),
-            ContractExecMsg::Transmuter(ExecMsg::AssignModerator {
-                address: new_moderator.to_string(),
+            env.clone(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "uosmo".to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
             }),
         )
-        .unwrap_err();
-
-        assert_eq!(err, ContractError::Unauthorized {});
+        .unwrap();
 
-        // Assign new moderator by admin
         execute(
             deps.as_mut(),
-            mock_env(),
-            mock_info(admin, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::AssignModerator {
-                address: new_moderator.to_string(),
+            env.clone(),
+            mock_info(
+                "provider",
+                &[Coin::new(1_000, "uosmo"), Coin::new(1_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
             }),
         )
         .unwrap();
 
-        // Check the current moderator
-        let res = query(
-            deps.as_ref(),
-            mock_env(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetModerator {}),
+        let trip_counts = |deps: cosmwasm_std::Deps| {
+            let LimiterTripCountsResponse { trip_counts } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::LimiterTripCounts {}),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            trip_counts
+        };
+
+        assert_eq!(trip_counts(deps.as_ref()), vec![]);
+
+        let swap_msg = |partial_fill: bool| SudoMsg::SwapExactAmountIn {
+            sender: "someone".to_string(),
+            token_in: Coin::new(300, "uosmo"),
+            token_out_denom: "uion".to_string(),
+            token_out_min_amount: Uint128::one(),
+            swap_fee: Decimal::zero(),
+            partial_fill,
+            require_improves_balance: false,
+        };
+
+        // uosmo weight would be 65%, over the 60% upper limit,
+        // rejected swap is reverted on chain so it writes no trip
+        let err = sudo(deps.as_mut(), env.clone(), swap_msg(false)).unwrap_err();
+        assert!(matches!(err, ContractError::UpperLimitExceeded { .. }));
+        assert_eq!(trip_counts(deps.as_ref()), vec![]);
+
+        // partially filled swap succeeds, so its trip persists
+        for count in 1..=2 {
+            let res = sudo(deps.as_mut(), env.clone(), swap_msg(true)).unwrap();
+            assert!(res
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "unfilled_token_in"));
+
+            assert_eq!(
+                trip_counts(deps.as_ref()),
+                vec![(("uosmo".to_string(), "static".to_string()), count)]
+            );
+
+            // bring uosmo weight back to 50% for the next partial fill
+            sudo(
+                deps.as_mut(),
+                env.clone(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: "someone".to_string(),
+                    token_in: Coin::new(200, "uion"),
+                    token_out_denom: "uosmo".to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+            .unwrap();
+        }
+
+        // each trip is appended to the log, listed most recent first
+        let RecentLimiterTripsResponse { trips } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                ContractQueryMsg::Transmuter(QueryMsg::RecentLimiterTrips { limit: Some(2) }),
+            )
+            .unwrap(),
         )
         .unwrap();
-        let moderator_response: GetModeratorResponse = from_json(res).unwrap();
-        assert_eq!(moderator_response.moderator, new_moderator);
+
+        let trip = |id: u64| LimiterTripRecord {
+            id,
+            denom: "uosmo".to_string(),
+            label: "static".to_string(),
+            value: Decimal::percent(65),
+            time: mock_env().block.time,
+        };
+        assert_eq!(trips, vec![trip(1), trip(0)]);
     }
 
     #[test]
-    fn test_limiter_registration_and_config() {
-        // register limiter
+    fn test_require_limiter_coverage() {
         let mut deps = mock_dependencies();
 
         // make denom has non-zero total supply
@@ -2184,443 +17764,673 @@ mod tests {
             .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
         let admin = "admin";
-        let user = "user";
         let init_msg = InstantiateMsg {
             pool_asset_configs: vec![
                 AssetConfig::from_denom_str("uosmo"),
                 AssetConfig::from_denom_str("uion"),
             ],
             admin: Some(admin.to_string()),
-            moderator: "moderator".to_string(),
             alloyed_asset_subdenom: "usomoion".to_string(),
             alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
+        let env = mock_env();
 
-        instantiate(deps.as_mut(), mock_env(), mock_info(admin, &[]), init_msg).unwrap();
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
-        // normal user can't register limiter
-        let err = execute(
+        // Manually reply
+        reply(
             deps.as_mut(),
-            mock_env(),
-            mock_info(user, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
-                denom: "uosmo".to_string(),
-                label: "1h".to_string(),
-                limiter_params: LimiterParams::ChangeLimiter {
-                    window_config: WindowConfig {
-                        window_size: Uint64::from(604_800_000_000u64),
-                        division_count: Uint64::from(5u64),
-                    },
-                    boundary_offset: Decimal::one(),
-                },
-            }),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
         )
-        .unwrap_err();
-
-        assert_eq!(err, ContractError::Unauthorized {});
+        .unwrap();
 
-        let err = execute(
+        // only uosmo is covered by a limiter
+        execute(
             deps.as_mut(),
-            mock_env(),
-            mock_info(user, &[]),
+            env.clone(),
+            mock_info(admin, &[]),
             ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
                 denom: "uosmo".to_string(),
-                label: "1h".to_string(),
+                label: "static".to_string(),
                 limiter_params: LimiterParams::StaticLimiter {
-                    upper_limit: Decimal::percent(60),
+                    upper_limit: Decimal::percent(90),
                 },
             }),
         )
-        .unwrap_err();
-
-        assert_eq!(err, ContractError::Unauthorized {});
+        .unwrap();
 
-        // admin can register limiter
-        let window_config_1h = WindowConfig {
-            window_size: Uint64::from(3_600_000_000_000u64),
-            division_count: Uint64::from(5u64),
-        };
-        let res = execute(
+        execute(
             deps.as_mut(),
-            mock_env(),
-            mock_info(admin, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
-                denom: "uosmo".to_string(),
-                label: "1h".to_string(),
-                limiter_params: LimiterParams::ChangeLimiter {
-                    window_config: window_config_1h.clone(),
-                    boundary_offset: Decimal::percent(1),
-                },
+            env.clone(),
+            mock_info(
+                "provider",
+                &[Coin::new(1_000, "uosmo"), Coin::new(1_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
             }),
         )
         .unwrap();
 
-        let attrs = vec![
-            attr("method", "register_limiter"),
-            attr("denom", "uosmo"),
-            attr("label", "1h"),
-            attr("limiter_type", "change_limiter"),
-            attr("window_size", "3600000000000"),
-            attr("division_count", "5"),
-            attr("boundary_offset", "0.01"),
-        ];
-
-        assert_eq!(res.attributes, attrs);
+        let set_require_limiter_coverage = |deps: DepsMut, require_limiter_coverage: bool| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::SetRequireLimiterCoverage {
+                    require_limiter_coverage,
+                }),
+            )
+        };
 
-        // denom that is not in the pool can't be registered
-        let err = execute(
-            deps.as_mut(),
-            mock_env(),
-            mock_info(admin, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
-                denom: "invalid_denom".to_string(),
-                label: "1h".to_string(),
-                limiter_params: LimiterParams::ChangeLimiter {
-                    window_config: window_config_1h.clone(),
-                    boundary_offset: Decimal::percent(1),
+        let swap = |deps: DepsMut, denom_in: &str, denom_out: &str| {
+            sudo(
+                deps,
+                mock_env(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: "someone".to_string(),
+                    token_in: Coin::new(100, denom_in),
+                    token_out_denom: denom_out.to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
                 },
-            }),
+            )
+        };
+
+        // not required by default
+        let GetRequireLimiterCoverageResponse {
+            require_limiter_coverage,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::GetRequireLimiterCoverage {}),
+            )
+            .unwrap(),
         )
-        .unwrap_err();
+        .unwrap();
+        assert!(!require_limiter_coverage);
+        swap(deps.as_mut(), "uion", "uosmo").unwrap();
+
+        set_require_limiter_coverage(deps.as_mut(), true).unwrap();
 
+        // uncovered denom can't be increased, but can still be swapped out
+        let err = swap(deps.as_mut(), "uion", "uosmo").unwrap_err();
         assert_eq!(
             err,
-            ContractError::InvalidPoolAssetDenom {
-                denom: "invalid_denom".to_string(),
+            ContractError::NoLimiterCoverage {
+                denom: "uion".to_string()
             }
         );
+        swap(deps.as_mut(), "uosmo", "uion").unwrap();
 
-        // Query the list of limiters
-        let query_msg = ContractQueryMsg::Transmuter(QueryMsg::ListLimiters {});
-        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
-        let limiters: ListLimitersResponse = from_json(res).unwrap();
+        set_require_limiter_coverage(deps.as_mut(), false).unwrap();
+        swap(deps.as_mut(), "uion", "uosmo").unwrap();
+    }
 
-        assert_eq!(
-            limiters.limiters,
-            vec![(
-                (String::from("uosmo"), String::from("1h")),
-                Limiter::ChangeLimiter(
-                    ChangeLimiter::new(window_config_1h.clone(), Decimal::percent(1)).unwrap()
-                )
-            )]
+    #[test]
+    fn test_full_balance_drain() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "uosmo"),
+                Coin::new(1, "uion"),
+                Coin::new(1, "uatom"),
+            ],
         );
 
-        let window_config_1w = WindowConfig {
-            window_size: Uint64::from(604_800_000_000u64),
-            division_count: Uint64::from(5u64),
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+                AssetConfig::from_denom_str("uatom"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
-        let res = execute(
-            deps.as_mut(),
-            mock_env(),
-            mock_info(admin, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
-                denom: "uosmo".to_string(),
-                label: "1w".to_string(),
-                limiter_params: LimiterParams::ChangeLimiter {
-                    window_config: window_config_1w.clone(),
-                    boundary_offset: Decimal::percent(1),
-                },
-            }),
-        )
-        .unwrap();
-
-        let attrs_1w = vec![
-            attr("method", "register_limiter"),
-            attr("denom", "uosmo"),
-            attr("label", "1w"),
-            attr("limiter_type", "change_limiter"),
-            attr("window_size", "604800000000"),
-            attr("division_count", "5"),
-            attr("boundary_offset", "0.01"),
-        ];
-
-        assert_eq!(res.attributes, attrs_1w);
-
-        // Query the list of limiters
-        let query_msg = ContractQueryMsg::Transmuter(QueryMsg::ListLimiters {});
-        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
-        let limiters: ListLimitersResponse = from_json(res).unwrap();
+        let env = mock_env();
 
-        assert_eq!(
-            limiters.limiters,
-            vec![
-                (
-                    (String::from("uosmo"), String::from("1h")),
-                    Limiter::ChangeLimiter(
-                        ChangeLimiter::new(window_config_1h, Decimal::percent(1)).unwrap()
-                    )
-                ),
-                (
-                    (String::from("uosmo"), String::from("1w")),
-                    Limiter::ChangeLimiter(
-                        ChangeLimiter::new(window_config_1w.clone(), Decimal::percent(1)).unwrap()
-                    )
-                ),
-            ]
-        );
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
-        // register static limiter
-        let res = execute(
+        // Manually reply
+        reply(
             deps.as_mut(),
-            mock_env(),
-            mock_info(admin, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
-                denom: "uosmo".to_string(),
-                label: "static".to_string(),
-                limiter_params: LimiterParams::StaticLimiter {
-                    upper_limit: Decimal::percent(60),
-                },
-            }),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
         )
         .unwrap();
 
-        let attrs = vec![
-            attr("method", "register_limiter"),
-            attr("denom", "uosmo"),
-            attr("label", "static"),
-            attr("limiter_type", "static_limiter"),
-            attr("upper_limit", "0.6"),
-        ];
-
-        assert_eq!(res.attributes, attrs);
-
-        // deregister limiter by user is unauthorized
-        let err = execute(
+        execute(
             deps.as_mut(),
-            mock_env(),
-            mock_info(user, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::DeregisterLimiter {
-                denom: "uosmo".to_string(),
-                label: "1h".to_string(),
+            env.clone(),
+            mock_info(
+                "provider",
+                &[
+                    Coin::new(1_000, "uosmo"),
+                    Coin::new(1_000, "uion"),
+                    Coin::new(1_000, "uatom"),
+                ],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
             }),
         )
-        .unwrap_err();
+        .unwrap();
 
-        assert_eq!(err, ContractError::Unauthorized {});
+        let drain = |deps: DepsMut, denom_in: &str, denom_out: &str| {
+            sudo(
+                deps,
+                mock_env(),
+                SudoMsg::SwapExactAmountOut {
+                    sender: "someone".to_string(),
+                    token_in_denom: denom_in.to_string(),
+                    token_in_max_amount: Uint128::new(1_000),
+                    token_out: Coin::new(1_000, denom_out),
+                    swap_fee: Decimal::zero(),
+                    require_improves_balance: false,
+                },
+            )
+        };
 
-        // deregister limiter by admin should work
-        let res = execute(
-            deps.as_mut(),
-            mock_env(),
-            mock_info(admin, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::DeregisterLimiter {
-                denom: "uosmo".to_string(),
-                label: "1h".to_string(),
-            }),
+        // allowed by default
+        let GetAllowFullDrainResponse { allow_full_drain } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::GetAllowFullDrain {}),
+            )
+            .unwrap(),
         )
         .unwrap();
+        assert!(allow_full_drain);
 
-        let attrs = vec![
-            attr("method", "deregister_limiter"),
-            attr("denom", "uosmo"),
-            attr("label", "1h"),
-        ];
-
-        assert_eq!(res.attributes, attrs);
-
-        // Query the list of limiters
-        let query_msg = ContractQueryMsg::Transmuter(QueryMsg::ListLimiters {});
-        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
-        let limiters: ListLimitersResponse = from_json(res).unwrap();
+        let res = drain(deps.as_mut(), "uion", "uosmo").unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "someone".to_string(),
+                amount: vec![Coin::new(1_000, "uosmo")],
+            })]
+        );
 
+        // drained denom is left at exactly zero, and weights are over the remaining assets
+        let pool = Transmuter::new().pool.load(&deps.storage).unwrap();
         assert_eq!(
-            limiters.limiters,
+            pool.get_pool_asset_by_denom("uosmo").unwrap().amount(),
+            Uint128::zero()
+        );
+        assert_eq!(
+            pool.weights().unwrap().unwrap(),
             vec![
-                (
-                    (String::from("uosmo"), String::from("1w")),
-                    Limiter::ChangeLimiter(
-                        ChangeLimiter::new(window_config_1w.clone(), Decimal::percent(1)).unwrap()
-                    )
-                ),
-                (
-                    (String::from("uosmo"), String::from("static")),
-                    Limiter::StaticLimiter(StaticLimiter::new(Decimal::percent(60)).unwrap())
-                )
+                ("uatom".to_string(), Decimal::from_ratio(1u128, 3u128)),
+                ("uion".to_string(), Decimal::from_ratio(2u128, 3u128)),
+                ("uosmo".to_string(), Decimal::zero()),
             ]
         );
 
-        // set boundary offset by user is unauthorized
-        let err = execute(
+        // draining again is short of pool asset
+        let err = drain(deps.as_mut(), "uion", "uosmo").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientPoolAsset {
+                required: Coin::new(1_000, "uosmo"),
+                available: Coin::new(0, "uosmo"),
+            }
+        );
+
+        execute(
             deps.as_mut(),
-            mock_env(),
-            mock_info(user, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::SetChangeLimiterBoundaryOffset {
-                denom: "uosmo".to_string(),
-                label: "1w".to_string(),
-                boundary_offset: Decimal::zero(),
+            env.clone(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetAllowFullDrain {
+                allow_full_drain: false,
             }),
         )
-        .unwrap_err();
+        .unwrap();
 
-        assert_eq!(err, ContractError::Unauthorized {});
+        let err = drain(deps.as_mut(), "uosmo", "uatom").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::FullDrainNotAllowed {
+                denom: "uatom".to_string()
+            }
+        );
 
-        // set boundary offset by admin but for osmo 1h should fail
-        let err = execute(
+        // leaving any balance is still allowed, and balance below the dust threshold
+        // is not swept, since sweeping it would drain the denom fully
+        execute(
             deps.as_mut(),
-            mock_env(),
+            env.clone(),
             mock_info(admin, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::SetChangeLimiterBoundaryOffset {
-                denom: "uosmo".to_string(),
-                label: "1h".to_string(),
-                boundary_offset: Decimal::zero(),
+            ContractExecMsg::Transmuter(ExecMsg::SetDustThreshold {
+                dust_threshold: Uint128::new(10),
             }),
         )
-        .unwrap_err();
+        .unwrap();
+
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountOut {
+                sender: "someone".to_string(),
+                token_in_denom: "uion".to_string(),
+                token_in_max_amount: Uint128::new(999),
+                token_out: Coin::new(999, "uatom"),
+                swap_fee: Decimal::zero(),
+                require_improves_balance: false,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            total_liquidity_of("uatom", &deps.storage),
+            Coin::new(1, "uatom")
+        );
+
+        // same applies to redeeming alloyed asset
+        deps.querier
+            .update_balance("provider", vec![Coin::new(3_000, "usomoion")]);
+        let exit = |deps: DepsMut, tokens_out: Vec<Coin>| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info("provider", &[]),
+                ContractExecMsg::Transmuter(ExecMsg::ExitPool { tokens_out }),
+            )
+        };
 
+        let err = exit(deps.as_mut(), vec![Coin::new(1, "uatom")]).unwrap_err();
         assert_eq!(
             err,
-            ContractError::LimiterDoesNotExist {
-                denom: "uosmo".to_string(),
-                label: "1h".to_string()
+            ContractError::FullDrainNotAllowed {
+                denom: "uatom".to_string()
             }
         );
 
-        // set boundary offset by admin for existing limiter should work
-        let res = execute(
+        exit(deps.as_mut(), vec![Coin::new(2_990, "uion")]).unwrap();
+        assert_eq!(
+            total_liquidity_of("uion", &deps.storage),
+            Coin::new(9, "uion")
+        );
+    }
+
+    #[test]
+    fn test_rounding_audit() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                // 1 uosmo is worth 3 uion
+                AssetConfig {
+                    denom: "uion".to_string(),
+                    normalization_factor: Uint128::new(3),
+                },
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
             deps.as_mut(),
-            mock_env(),
-            mock_info(admin, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::SetChangeLimiterBoundaryOffset {
-                denom: "uosmo".to_string(),
-                label: "1w".to_string(),
-                boundary_offset: Decimal::percent(10),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "provider",
+                &[Coin::new(1_000, "uosmo"), Coin::new(3_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
             }),
         )
         .unwrap();
 
-        let attrs = vec![
-            attr("method", "set_change_limiter_boundary_offset"),
-            attr("denom", "uosmo"),
-            attr("label", "1w"),
-            attr("boundary_offset", "0.1"),
-        ];
+        let swap_exact_in = |deps: DepsMut| {
+            sudo(
+                deps,
+                mock_env(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: "someone".to_string(),
+                    token_in: Coin::new(10, "uion"),
+                    token_out_denom: "uosmo".to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+        };
+        let set_enable_rounding_audit = |deps: DepsMut, sender: &str, enable: bool| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::SetEnableRoundingAudit {
+                    enable_rounding_audit: enable,
+                }),
+            )
+        };
+        let rounding_audit = |deps: cosmwasm_std::Deps| {
+            query(
+                deps,
+                mock_env(),
+                ContractQueryMsg::Transmuter(QueryMsg::RoundingAudit { limit: None }),
+            )
+            .map(|res| from_json::<RoundingAuditResponse>(res).unwrap())
+            .unwrap()
+            .residuals
+        };
 
-        assert_eq!(res.attributes, attrs);
+        // residuals are not recorded by default
+        swap_exact_in(deps.as_mut()).unwrap();
+        assert!(rounding_audit(deps.as_ref()).is_empty());
 
-        // Query the list of limiters
-        let query_msg = ContractQueryMsg::Transmuter(QueryMsg::ListLimiters {});
-        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
-        let limiters: ListLimitersResponse = from_json(res).unwrap();
+        // only admin can enable rounding audit
+        let err = set_enable_rounding_audit(deps.as_mut(), "someone", true).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        set_enable_rounding_audit(deps.as_mut(), admin, true).unwrap();
+
+        let GetEnableRoundingAuditResponse {
+            enable_rounding_audit,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::GetEnableRoundingAudit {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(enable_rounding_audit);
+
+        // 10 uion is worth 3.33 uosmo, rounded down
+        swap_exact_in(deps.as_mut()).unwrap();
 
+        // 1 uion costs 0.33 uosmo, rounded up
+        sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountOut {
+                sender: "someone".to_string(),
+                token_in_denom: "uosmo".to_string(),
+                token_in_max_amount: Uint128::new(1),
+                token_out: Coin::new(1, "uion"),
+                swap_fee: Decimal::zero(),
+                require_improves_balance: false,
+            },
+        )
+        .unwrap();
+
+        let time = env.block.time;
         assert_eq!(
-            limiters.limiters,
+            rounding_audit(deps.as_ref()),
             vec![
-                (
-                    (String::from("uosmo"), String::from("1w")),
-                    Limiter::ChangeLimiter(
-                        ChangeLimiter::new(window_config_1w.clone(), Decimal::percent(10)).unwrap()
-                    )
-                ),
-                (
-                    (String::from("uosmo"), String::from("static")),
-                    Limiter::StaticLimiter(StaticLimiter::new(Decimal::percent(60)).unwrap())
-                )
+                RoundingResidual {
+                    id: 1,
+                    token_in: Coin::new(1, "uosmo"),
+                    token_out: Coin::new(1, "uion"),
+                    fee: Coin::new(0, "uosmo"),
+                    ideal_token_out_amount: Decimal256::from_ratio(3u128, 1u128),
+                    residual: cosmwasm_std::SignedDecimal256::from_ratio(2i128, 1i128),
+                    time,
+                },
+                RoundingResidual {
+                    id: 0,
+                    token_in: Coin::new(10, "uion"),
+                    token_out: Coin::new(3, "uosmo"),
+                    fee: Coin::new(0, "uion"),
+                    ideal_token_out_amount: Decimal256::from_ratio(10u128, 3u128),
+                    residual: cosmwasm_std::SignedDecimal256::from_ratio(1i128, 3i128),
+                    time,
+                },
             ]
         );
 
-        // set upper limit by user is unauthorized
+        // residuals are no longer recorded once disabled
+        set_enable_rounding_audit(deps.as_mut(), admin, false).unwrap();
+        swap_exact_in(deps.as_mut()).unwrap();
+        assert_eq!(rounding_audit(deps.as_ref()).len(), 2);
+    }
+
+    #[test]
+    fn test_dynamic_swap_fee() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // uosmo is over its even split target
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "provider",
+                &[Coin::new(15_000, "uosmo"), Coin::new(5_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        let dynamic_fee_params = DynamicFeeParams {
+            base: Decimal::permille(1),
+            slope: Decimal::percent(10),
+        };
+
+        // only admin can set dynamic fee params
         let err = execute(
             deps.as_mut(),
-            mock_env(),
-            mock_info(user, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::SetStaticLimiterUpperLimit {
-                denom: "uosmo".to_string(),
-                label: "static".to_string(),
-                upper_limit: Decimal::percent(50),
+            env.clone(),
+            mock_info("moderator", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetDynamicFeeParams {
+                dynamic_fee_params: Some(dynamic_fee_params.clone()),
             }),
         )
         .unwrap_err();
-
         assert_eq!(err, ContractError::Unauthorized {});
 
-        // set upper limit by admin but for uosmo 1h should fail
-        let err = execute(
+        execute(
             deps.as_mut(),
-            mock_env(),
+            env.clone(),
             mock_info(admin, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::SetStaticLimiterUpperLimit {
-                denom: "uosmo".to_string(),
-                label: "1h".to_string(),
-                upper_limit: Decimal::percent(50),
+            ContractExecMsg::Transmuter(ExecMsg::SetDynamicFeeParams {
+                dynamic_fee_params: Some(dynamic_fee_params.clone()),
             }),
         )
-        .unwrap_err();
+        .unwrap();
+
+        let GetFeeConfigResponse {
+            dynamic_fee_params: stored_dynamic_fee_params,
+            ..
+        } = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                ContractQueryMsg::Transmuter(QueryMsg::GetFeeConfig {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stored_dynamic_fee_params, Some(dynamic_fee_params));
+
+        let out_amount = |deps: cosmwasm_std::Deps, denom_in: &str, denom_out: &str| {
+            let CalcOutAmtGivenInResponse { token_out } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::CalcOutAmtGivenIn {
+                        token_in: Coin::new(1_000, denom_in),
+                        token_out_denom: denom_out.to_string(),
+                        swap_fee: Decimal::zero(),
+                    }),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            token_out.amount
+        };
 
+        // balancing swap results in 30% / 70% weights, deviating 20% from target:
+        // fee = 0.1% + 10% * 20% = 2.1%
         assert_eq!(
-            err,
-            ContractError::LimiterDoesNotExist {
-                denom: "uosmo".to_string(),
-                label: "1h".to_string()
-            }
+            out_amount(deps.as_ref(), "uion", "uosmo"),
+            Uint128::new(979)
         );
 
-        // set upper limit by admin for change limiter should fail
-        let err = execute(
+        // unbalancing swap results in 80% / 20% weights, deviating 30% from target:
+        // fee = 0.1% + 10% * 30% = 3.1%
+        assert_eq!(
+            out_amount(deps.as_ref(), "uosmo", "uion"),
+            Uint128::new(969)
+        );
+
+        // the fee actually charged matches
+        let res = sudo(
             deps.as_mut(),
-            mock_env(),
-            mock_info(admin, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::SetStaticLimiterUpperLimit {
-                denom: "uosmo".to_string(),
-                label: "1w".to_string(),
-                upper_limit: Decimal::percent(50),
-            }),
+            env.clone(),
+            SudoMsg::SwapExactAmountIn {
+                sender: "someone".to_string(),
+                token_in: Coin::new(1_000, "uosmo"),
+                token_out_denom: "uion".to_string(),
+                token_out_min_amount: Uint128::one(),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                require_improves_balance: false,
+            },
         )
-        .unwrap_err();
-
+        .unwrap();
         assert_eq!(
-            err,
-            ContractError::WrongLimiterType {
-                expected: "static_limiter".to_string(),
-                actual: "change_limiter".to_string()
-            }
+            res.data,
+            Some(
+                to_json_binary(&SwapExactAmountInResponseData {
+                    token_out_amount: Uint128::new(969)
+                })
+                .unwrap()
+            )
         );
 
-        // set upper limit by admin for static limiter should work
-        let res = execute(
+        // flat fee is restored when dynamic fee is disabled
+        execute(
             deps.as_mut(),
-            mock_env(),
+            env,
             mock_info(admin, &[]),
-            ContractExecMsg::Transmuter(ExecMsg::SetStaticLimiterUpperLimit {
-                denom: "uosmo".to_string(),
-                label: "static".to_string(),
-                upper_limit: Decimal::percent(50),
+            ContractExecMsg::Transmuter(ExecMsg::SetDynamicFeeParams {
+                dynamic_fee_params: None,
             }),
         )
         .unwrap();
-
-        let attrs = vec![
-            attr("method", "set_static_limiter_upper_limit"),
-            attr("denom", "uosmo"),
-            attr("label", "static"),
-            attr("upper_limit", "0.5"),
-        ];
-
-        assert_eq!(res.attributes, attrs);
-
-        // Query the list of limiters
-        let query_msg = ContractQueryMsg::Transmuter(QueryMsg::ListLimiters {});
-        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
-        let limiters: ListLimitersResponse = from_json(res).unwrap();
-
         assert_eq!(
-            limiters.limiters,
-            vec![
-                (
-                    (String::from("uosmo"), String::from("1w")),
-                    Limiter::ChangeLimiter(
-                        ChangeLimiter::new(window_config_1w, Decimal::percent(10)).unwrap()
-                    )
-                ),
-                (
-                    (String::from("uosmo"), String::from("static")),
-                    Limiter::StaticLimiter(StaticLimiter::new(Decimal::percent(50)).unwrap())
-                )
-            ]
+            out_amount(deps.as_ref(), "uion", "uosmo"),
+            Uint128::new(1_000)
         );
     }
 
     #[test]
-    fn test_set_alloyed_denom_metadata() {
+    fn test_preview_swap() {
         let mut deps = mock_dependencies();
 
         // make denom has non-zero total supply
@@ -2628,65 +18438,189 @@ mod tests {
             .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
         let admin = "admin";
-        let non_admin = "non_admin";
         let init_msg = InstantiateMsg {
             pool_asset_configs: vec![
                 AssetConfig::from_denom_str("uosmo"),
                 AssetConfig::from_denom_str("uion"),
             ],
-            alloyed_asset_subdenom: "uosmouion".to_string(),
             admin: Some(admin.to_string()),
-            moderator: "moderator".to_string(),
+            alloyed_asset_subdenom: "usomoion".to_string(),
             alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
-        let info = mock_info(admin, &[]);
 
         // Instantiate the contract.
-        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
-        let metadata = Metadata {
-            description: "description".to_string(),
-            base: "base".to_string(),
-            display: "display".to_string(),
-            name: "name".to_string(),
-            symbol: "symbol".to_string(),
-            denom_units: vec![],
-            uri: String::new(),
-            uri_hash: String::new(),
+        // Manually reply
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // uosmo is over its even split target
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "provider",
+                &[Coin::new(15_000, "uosmo"), Coin::new(5_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        // all fee is collected, so that the fee actually charged can be observed
+        for msg in [
+            ExecMsg::SetFeeCollector {
+                address: "collector".to_string(),
+            },
+            ExecMsg::SetFeeSplit {
+                fee_split: Decimal::one(),
+            },
+            ExecMsg::SetDynamicFeeParams {
+                dynamic_fee_params: Some(DynamicFeeParams {
+                    base: Decimal::permille(1),
+                    slope: Decimal::percent(10),
+                }),
+            },
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+            .unwrap();
+        }
+
+        let preview_then_swap = |deps: DepsMut, denom_in: &str, denom_out: &str| {
+            let token_in = Coin::new(1_000, denom_in);
+            let preview: PreviewSwapResponse = from_json(
+                query(
+                    deps.as_ref(),
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::PreviewSwap {
+                        token_in: token_in.clone(),
+                        token_out_denom: denom_out.to_string(),
+                        with_corrupted: None,
+                    }),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+            let res = sudo(
+                deps,
+                mock_env(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: "someone".to_string(),
+                    token_in,
+                    token_out_denom: denom_out.to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+            .unwrap();
+
+            let SwapExactAmountInResponseData { token_out_amount } =
+                from_json(res.data.unwrap()).unwrap();
+            assert_eq!(
+                preview.token_out,
+                Coin::new(token_out_amount.u128(), denom_out)
+            );
+            assert!(res.messages.iter().any(|msg| msg.msg
+                == CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "collector".to_string(),
+                    amount: vec![preview.fee.clone()],
+                })));
+
+            preview
         };
 
-        // Attempt to set alloyed denom metadata by a non-admin user.
-        let non_admin_info = mock_info(non_admin, &[]);
-        let non_admin_msg = ContractExecMsg::Transmuter(ExecMsg::SetAlloyedDenomMetadata {
-            metadata: metadata.clone(),
-        });
-        let err = execute(deps.as_mut(), env.clone(), non_admin_info, non_admin_msg).unwrap_err();
+        // balancing swap, resulting in 20% deviation from target: fee = 0.1% + 10% * 20%
+        let preview = preview_then_swap(deps.as_mut(), "uion", "uosmo");
+        assert_eq!(
+            preview,
+            PreviewSwapResponse {
+                fee: Coin::new(21, "uion"),
+                token_out: Coin::new(979, "uosmo"),
+            }
+        );
 
-        assert_eq!(err, ContractError::Unauthorized {});
+        // unbalancing swap is charged a higher fee
+        let preview = preview_then_swap(deps.as_mut(), "uosmo", "uion");
+        assert!(preview.fee.amount > Uint128::new(21));
 
-        // Set the alloyed denom metadata by admin.
-        let msg = ContractExecMsg::Transmuter(ExecMsg::SetAlloyedDenomMetadata {
-            metadata: metadata.clone(),
-        });
-        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let preview_with_corrupted = |deps: cosmwasm_std::Deps, with_corrupted: &[&str]| {
+            query(
+                deps,
+                mock_env(),
+                ContractQueryMsg::Transmuter(QueryMsg::PreviewSwap {
+                    token_in: Coin::new(1_000, "uion"),
+                    token_out_denom: "uosmo".to_string(),
+                    with_corrupted: Some(with_corrupted.iter().map(|d| d.to_string()).collect()),
+                }),
+            )
+            .map(|res| from_json::<PreviewSwapResponse>(res).unwrap())
+        };
 
+        // allowed normally, but blocked if the token in were corrupted
+        preview_with_corrupted(deps.as_ref(), &[]).unwrap();
+        let err = preview_with_corrupted(deps.as_ref(), &["uion"]).unwrap_err();
         assert_eq!(
-            res.attributes,
-            vec![attr("method", "set_alloyed_denom_metadata")]
+            err,
+            ContractError::CorruptedAssetRelativelyIncreased {
+                denom: "uion".to_string()
+            }
         );
 
+        // draining the hypothetically corrupted denom is still allowed
+        preview_with_corrupted(deps.as_ref(), &["uosmo"]).unwrap();
+
+        // hypothetical corrupted denoms must be pool assets
+        let err = preview_with_corrupted(deps.as_ref(), &["uatom"]).unwrap_err();
         assert_eq!(
-            res.messages,
-            vec![SubMsg::new(MsgSetDenomMetadata {
-                sender: env.contract.address.to_string(),
-                metadata: Some(metadata)
-            })]
+            err,
+            ContractError::InvalidPoolAssetDenom {
+                denom: "uatom".to_string()
+            }
+        );
+
+        // nothing is actually marked as corrupted
+        let GetCorrruptedDenomsResponse { corrupted_denoms } = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                ContractQueryMsg::Transmuter(QueryMsg::GetCorruptedDenoms {}),
+            )
+            .unwrap(),
         )
+        .unwrap();
+        assert!(corrupted_denoms.is_empty());
     }
 
     #[test]
-    fn test_join_pool() {
+    fn test_withdraw_fees() {
         let mut deps = mock_dependencies();
 
         // make denom has non-zero total supply
@@ -2694,7 +18628,6 @@ mod tests {
             .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
         let admin = "admin";
-        let user = "user";
         let init_msg = InstantiateMsg {
             pool_asset_configs: vec![
                 AssetConfig::from_denom_str("uosmo"),
@@ -2704,16 +18637,14 @@ mod tests {
             alloyed_asset_subdenom: "usomoion".to_string(),
             alloyed_asset_normalization_factor: Uint128::one(),
             moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
-        let info = mock_info(admin, &[]);
 
         // Instantiate the contract.
-        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
         // Manually reply
-        let alloyed_denom = "usomoion";
-
         reply(
             deps.as_mut(),
             env.clone(),
@@ -2723,7 +18654,7 @@ mod tests {
                     events: vec![],
                     data: Some(
                         MsgCreateDenomResponse {
-                            new_token_denom: alloyed_denom.to_string(),
+                            new_token_denom: "usomoion".to_string(),
                         }
                         .into(),
                     ),
@@ -2732,38 +18663,117 @@ mod tests {
         )
         .unwrap();
 
-        // join pool with amount 0 coin should error
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
-        let info = mock_info(user, &[Coin::new(1000, "uion"), Coin::new(0, "uosmo")]);
-        let err = execute(deps.as_mut(), env.clone(), info, join_pool_msg).unwrap_err();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                "provider",
+                &[Coin::new(10_000, "uosmo"), Coin::new(10_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
 
-        assert_eq!(err, ContractError::ZeroValueOperation {});
+        // mock the minted alloyed asset supply
+        deps.querier
+            .update_balance("provider", vec![Coin::new(20_000, "usomoion")]);
+
+        // all fee is retained in the pool
+        for msg in [
+            ExecMsg::SetFeeCollector {
+                address: "collector".to_string(),
+            },
+            ExecMsg::SetSwapFee {
+                swap_fee: Decimal::percent(1),
+            },
+            ExecMsg::SetFeeSplit {
+                fee_split: Decimal::zero(),
+            },
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+            .unwrap();
+        }
+
+        let accrued_fees = |deps: cosmwasm_std::Deps| {
+            let GetAccruedFeesResponse { accrued_fees } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::GetAccruedFees {}),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            accrued_fees
+        };
+
+        let withdraw_fees = |deps: DepsMut, sender: &str| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::WithdrawFees {
+                    recipient: "treasury".to_string(),
+                }),
+            )
+        };
+
+        swap_with_configured_fee(deps.as_mut(), "uion", "uosmo");
+        swap_with_configured_fee(deps.as_mut(), "uosmo", "uion");
+        assert_eq!(
+            accrued_fees(deps.as_ref()),
+            vec![Coin::new(10, "uion"), Coin::new(10, "uosmo")]
+        );
+
+        // only admin or fee collector can withdraw fees
+        let err = withdraw_fees(deps.as_mut(), "someone").unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let res = withdraw_fees(deps.as_mut(), "collector").unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: vec![Coin::new(10, "uion"), Coin::new(10, "uosmo")],
+            })]
+        );
+        assert_eq!(accrued_fees(deps.as_ref()), vec![]);
+
+        // principal backing the alloyed asset is left in the pool
+        let pool = Transmuter::new().pool.load(&deps.storage).unwrap();
+        assert_eq!(
+            pool.pool_assets
+                .iter()
+                .map(|asset| asset.amount())
+                .sum::<Uint128>(),
+            Uint128::new(20_000)
+        );
 
-        // join pool properly works
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
-        let info = mock_info(user, &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]);
-        execute(deps.as_mut(), env.clone(), info, join_pool_msg).unwrap();
+        // withdrawal can't dip into the backing of outstanding alloyed asset
+        swap_with_configured_fee(deps.as_mut(), "uion", "uosmo");
+        deps.querier
+            .update_balance("provider", vec![Coin::new(20_005, "usomoion")]);
 
-        // Check pool asset
-        let GetTotalPoolLiquidityResponse {
-            total_pool_liquidity,
-        } = from_json(
-            query(
-                deps.as_ref(),
-                env,
-                ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
-            )
-            .unwrap(),
-        )
-        .unwrap();
+        let err = withdraw_fees(deps.as_mut(), admin).unwrap_err();
         assert_eq!(
-            total_pool_liquidity,
-            vec![Coin::new(1000, "uosmo"), Coin::new(1000, "uion")]
+            err,
+            ContractError::InsufficientFeeReserve {
+                required: Uint128::new(20_005),
+                available: Uint128::new(20_000),
+            }
         );
+        assert_eq!(accrued_fees(deps.as_ref()), vec![Coin::new(10, "uion")]);
     }
 
     #[test]
-    fn test_exit_pool() {
+    fn test_rebalance() {
         let mut deps = mock_dependencies();
 
         // make denom has non-zero total supply
@@ -2771,7 +18781,6 @@ mod tests {
             .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
         let admin = "admin";
-        let user = "user";
         let init_msg = InstantiateMsg {
             pool_asset_configs: vec![
                 AssetConfig::from_denom_str("uosmo"),
@@ -2781,16 +18790,14 @@ mod tests {
             alloyed_asset_subdenom: "usomoion".to_string(),
             alloyed_asset_normalization_factor: Uint128::one(),
             moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
-        let info = mock_info(admin, &[]);
 
         // Instantiate the contract.
-        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
         // Manually reply
-        let alloyed_denom = "usomoion";
-
         reply(
             deps.as_mut(),
             env.clone(),
@@ -2800,7 +18807,7 @@ mod tests {
                     events: vec![],
                     data: Some(
                         MsgCreateDenomResponse {
-                            new_token_denom: alloyed_denom.to_string(),
+                            new_token_denom: "usomoion".to_string(),
                         }
                         .into(),
                     ),
@@ -2809,94 +18816,154 @@ mod tests {
         )
         .unwrap();
 
-        // join pool by others for sufficient amount
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
-        let info = mock_info(admin, &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]);
-        execute(deps.as_mut(), env.clone(), info, join_pool_msg).unwrap();
-
-        // User tries to exit pool
-        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
-            tokens_out: vec![Coin::new(1000, "uion"), Coin::new(1000, "uosmo")],
-        });
-        let err = execute(
+        execute(
             deps.as_mut(),
             env.clone(),
-            mock_info(user, &[]),
-            exit_pool_msg,
+            mock_info(
+                "provider",
+                &[Coin::new(10_000, "uosmo"), Coin::new(10_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
         )
-        .unwrap_err();
-
-        assert_eq!(
-            err,
-            ContractError::InsufficientShares {
-                required: 2000u128.into(),
-                available: Uint128::zero()
-            }
-        );
-        // User tries to join pool
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
-        let res = execute(
-            deps.as_mut(),
-            env.clone(),
-            mock_info(user, &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]),
-            join_pool_msg,
-        );
-        assert!(res.is_ok());
+        .unwrap();
 
+        // mock the minted alloyed asset supply
         deps.querier
-            .update_balance(user, vec![Coin::new(2000, alloyed_denom)]);
+            .update_balance("provider", vec![Coin::new(20_000, "usomoion")]);
 
-        // User tries to exit pool with zero amount
-        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
-            tokens_out: vec![Coin::new(0, "uion"), Coin::new(1, "uosmo")],
-        });
-        let err = execute(
-            deps.as_mut(),
-            env.clone(),
-            mock_info(user, &[]),
-            exit_pool_msg,
-        )
-        .unwrap_err();
-        assert_eq!(err, ContractError::ZeroValueOperation {});
+        let rebalance = |deps: DepsMut| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info("keeper", &[]),
+                ContractExecMsg::Transmuter(ExecMsg::Rebalance {}),
+            )
+        };
 
-        // User tries to exit pool again
-        let exit_pool_msg = ContractExecMsg::Transmuter(ExecMsg::ExitPool {
-            tokens_out: vec![Coin::new(1000, "uion"), Coin::new(1000, "uosmo")],
-        });
-        let res = execute(
+        // fee collector is required to receive the excess
+        let err = rebalance(deps.as_mut()).unwrap_err();
+        assert_eq!(err, ContractError::FeeCollectorNotSet {});
+
+        // all fee is retained in the pool
+        for msg in [
+            ExecMsg::SetFeeCollector {
+                address: "collector".to_string(),
+            },
+            ExecMsg::SetSwapFee {
+                swap_fee: Decimal::percent(1),
+            },
+            ExecMsg::SetFeeSplit {
+                fee_split: Decimal::zero(),
+            },
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+            .unwrap();
+        }
+
+        let accrued_fees = |deps: cosmwasm_std::Deps| {
+            let GetAccruedFeesResponse { accrued_fees } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::GetAccruedFees {}),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            accrued_fees
+        };
+
+        let uion_weight = |deps: cosmwasm_std::Deps| {
+            let pool = Transmuter::new().pool.load(deps.storage).unwrap();
+            pool.weights()
+                .unwrap()
+                .unwrap()
+                .into_iter()
+                .find(|(denom, _)| denom == "uion")
+                .unwrap()
+                .1
+        };
+
+        // uion: 13_010, uosmo: 7_040
+        swap_with_configured_fee(deps.as_mut(), "uosmo", "uion");
+        for _ in 0..4 {
+            swap_with_configured_fee(deps.as_mut(), "uion", "uosmo");
+        }
+        assert_eq!(
+            accrued_fees(deps.as_ref()),
+            vec![Coin::new(40, "uion"), Coin::new(10, "uosmo")]
+        );
+
+        execute(
             deps.as_mut(),
             env.clone(),
-            mock_info(user, &[]),
-            exit_pool_msg,
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetTargetWeights {
+                target_weights: BTreeMap::from([
+                    ("uion".to_string(), Decimal::permille(648)),
+                    ("uosmo".to_string(), Decimal::permille(352)),
+                ]),
+            }),
         )
         .unwrap();
 
-        let expected = Response::new()
-            .add_attribute("method", "exit_pool")
-            .add_message(MsgBurn {
-                sender: env.contract.address.to_string(),
-                amount: Some(Coin::new(2000u128, alloyed_denom).into()),
-                burn_from_address: user.to_string(),
-            })
-            .add_message(BankMsg::Send {
-                to_address: user.to_string(),
-                amount: vec![Coin::new(1000, "uion"), Coin::new(1000, "uosmo")],
-            });
+        let weight_before = uion_weight(deps.as_ref());
+        assert!(weight_before > Decimal::permille(648));
 
-        assert_eq!(res, expected);
+        // excess over target is worth 17, within the fee budget of 50
+        let res = rebalance(deps.as_mut()).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: vec![Coin::new(17, "uion")],
+            })]
+        );
+
+        // weight moves toward, but not past, the target
+        let weight_after = uion_weight(deps.as_ref());
+        assert!(weight_after < weight_before);
+        assert!(weight_after >= Decimal::permille(648));
+
+        // fees are consumed proportionally, 17/50 of each, rounded up
+        assert_eq!(
+            accrued_fees(deps.as_ref()),
+            vec![Coin::new(26, "uion"), Coin::new(6, "uosmo")]
+        );
     }
 
     #[test]
-    fn test_shares_and_liquidity() {
+    fn test_check_and_mark_corruption() {
+        use crate::oracle::{OraclePriceResponse, OracleQueryMsg};
+
         let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| match query {
+            cosmwasm_std::WasmQuery::Smart { contract_addr, msg } => {
+                assert_eq!(contract_addr, "oracle");
+                let OracleQueryMsg::Price { denom } = from_json(msg).unwrap();
+                let price = match denom.as_str() {
+                    "uion" => Decimal::percent(90),
+                    _ => Decimal::one(),
+                };
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&OraclePriceResponse { price }).unwrap(),
+                ))
+            }
+            _ => unimplemented!(),
+        });
 
         // make denom has non-zero total supply
         deps.querier
             .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
         let admin = "admin";
-        let user_1 = "user_1";
-        let user_2 = "user_2";
         let init_msg = InstantiateMsg {
             pool_asset_configs: vec![
                 AssetConfig::from_denom_str("uosmo"),
@@ -2906,127 +18973,342 @@ mod tests {
             alloyed_asset_subdenom: "usomoion".to_string(),
             alloyed_asset_normalization_factor: Uint128::one(),
             moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
-        let info = mock_info(admin, &[]);
 
         // Instantiate the contract.
-        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
-        // Manually reply
-        let alloyed_denom = "usomoion";
+        let check_and_mark_corruption = |deps: DepsMut, denom: &str| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info("someone", &[]),
+                ContractExecMsg::Transmuter(ExecMsg::CheckAndMarkCorruption {
+                    denom: denom.to_string(),
+                }),
+            )
+        };
 
-        reply(
+        let is_corrupted = |storage: &dyn Storage, denom: &str| {
+            Transmuter::new()
+                .pool
+                .load(storage)
+                .unwrap()
+                .is_corrupted_asset(denom)
+        };
+
+        // denom without config can't be checked
+        let err = check_and_mark_corruption(deps.as_mut(), "uion").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::AutoCorruptionNotConfigured {
+                denom: "uion".to_string()
+            }
+        );
+
+        let config = AutoCorruptionConfig {
+            oracle: Addr::unchecked("oracle"),
+            threshold: Decimal::percent(5),
+        };
+
+        // only admin can set auto corruption config
+        for sender in ["someone", admin] {
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(sender, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::SetAutoCorruptionConfig {
+                    denom: "uion".to_string(),
+                    auto_corruption_config: Some(config.clone()),
+                }),
+            );
+
+            if sender == admin {
+                res.unwrap();
+            } else {
+                assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
+            }
+        }
+
+        execute(
             deps.as_mut(),
             env.clone(),
-            Reply {
-                id: 1,
-                result: SubMsgResult::Ok(SubMsgResponse {
-                    events: vec![],
-                    data: Some(
-                        MsgCreateDenomResponse {
-                            new_token_denom: alloyed_denom.to_string(),
-                        }
-                        .into(),
-                    ),
-                }),
-            },
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetAutoCorruptionConfig {
+                denom: "uosmo".to_string(),
+                auto_corruption_config: Some(config),
+            }),
         )
         .unwrap();
 
-        // Join pool
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
+        // price within band does not mark corruption
+        let res = check_and_mark_corruption(deps.as_mut(), "uosmo").unwrap();
+        assert!(res.attributes.contains(&attr("marked_corrupted", "false")));
+        assert!(!is_corrupted(&deps.storage, "uosmo"));
+
+        // price below band marks corruption, regardless of the min active assets
         execute(
             deps.as_mut(),
             env.clone(),
-            mock_info(user_1, &[Coin::new(1000, "uion"), Coin::new(1000, "uosmo")]),
-            join_pool_msg,
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetMinActiveAssets {
+                min_active_assets: 2,
+            }),
         )
         .unwrap();
 
-        // Update alloyed asset denom balance for user
-        deps.querier
-            .update_balance(user_1, vec![Coin::new(2000, "usomoion")]);
+        let res = check_and_mark_corruption(deps.as_mut(), "uion").unwrap();
+        assert!(res.attributes.contains(&attr("price", "0.9")));
+        assert!(res.attributes.contains(&attr("marked_corrupted", "true")));
+        assert!(is_corrupted(&deps.storage, "uion"));
 
-        // Query the shares of the user
-        let res = query(
-            deps.as_ref(),
+        // already corrupted denom is not marked again
+        let res = check_and_mark_corruption(deps.as_mut(), "uion").unwrap();
+        assert!(res.attributes.contains(&attr("marked_corrupted", "false")));
+
+        // auto corrupted denom is unmarked directly
+        execute(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetShares {
-                address: user_1.to_string(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::UnmarkDenomCorrupted {
+                denom: "uion".to_string(),
             }),
         )
         .unwrap();
-        let shares: GetSharesResponse = from_json(res).unwrap();
-        assert_eq!(shares.shares.u128(), 2000u128);
+        assert!(!is_corrupted(&deps.storage, "uion"));
+    }
+
+    #[test]
+    fn test_target_weights() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![
+                Coin::new(1, "uosmo"),
+                Coin::new(1, "uion"),
+                Coin::new(1, "uatom"),
+            ],
+        );
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+                AssetConfig::from_denom_str("uatom"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        let set_target_weights = |deps: DepsMut, sender: &str, weights: &[(&str, Decimal)]| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::SetTargetWeights {
+                    target_weights: weights
+                        .iter()
+                        .map(|(denom, weight)| (denom.to_string(), *weight))
+                        .collect(),
+                }),
+            )
+        };
+
+        let target_weights = |deps: cosmwasm_std::Deps| {
+            let TargetWeightsResponse { target_weights } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::TargetWeights {}),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            target_weights
+        };
+
+        // only admin can set target weights
+        let err =
+            set_target_weights(deps.as_mut(), "someone", &[("uosmo", Decimal::one())]).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // targets must sum to 1
+        let err = set_target_weights(
+            deps.as_mut(),
+            admin,
+            &[
+                ("uosmo", Decimal::percent(50)),
+                ("uion", Decimal::percent(40)),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidTargetWeightsSum {
+                sum: Decimal::percent(90)
+            }
+        );
 
-        // Query the total shares
-        let res = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetTotalShares {}),
+        // targets must be for pool assets
+        let err = set_target_weights(
+            deps.as_mut(),
+            admin,
+            &[
+                ("uosmo", Decimal::percent(50)),
+                ("uusdc", Decimal::percent(50)),
+            ],
         )
-        .unwrap();
-        let total_shares: GetTotalSharesResponse = from_json(res).unwrap();
-        assert_eq!(total_shares.total_shares.u128(), 2000u128);
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidPoolAssetDenom {
+                denom: "uusdc".to_string()
+            }
+        );
 
-        // Query the total pool liquidity
-        let res = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+        assert_eq!(target_weights(deps.as_ref()), BTreeMap::new());
+
+        // weights that can't be represented exactly are within tolerance
+        let one_third = Decimal::from_ratio(1u128, 3u128);
+        set_target_weights(
+            deps.as_mut(),
+            admin,
+            &[
+                ("uosmo", one_third),
+                ("uion", one_third),
+                ("uatom", one_third),
+            ],
         )
         .unwrap();
-        let total_pool_liquidity: GetTotalPoolLiquidityResponse = from_json(res).unwrap();
         assert_eq!(
-            total_pool_liquidity.total_pool_liquidity,
-            vec![Coin::new(1000, "uosmo"), Coin::new(1000, "uion")]
+            target_weights(deps.as_ref()),
+            BTreeMap::from([
+                ("uatom".to_string(), one_third),
+                ("uion".to_string(), one_third),
+                ("uosmo".to_string(), one_third),
+            ])
         );
 
-        // Join pool
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
-        execute(
+        // setting targets again replaces the previous ones
+        set_target_weights(
             deps.as_mut(),
-            env.clone(),
-            mock_info(user_2, &[Coin::new(1000, "uion")]),
-            join_pool_msg,
+            admin,
+            &[
+                ("uosmo", Decimal::percent(70)),
+                ("uion", Decimal::percent(30)),
+            ],
         )
         .unwrap();
+        assert_eq!(
+            target_weights(deps.as_ref()),
+            BTreeMap::from([
+                ("uion".to_string(), Decimal::percent(30)),
+                ("uosmo".to_string(), Decimal::percent(70)),
+            ])
+        );
 
-        // Update balance for user 2
+        // pool without assets has no even split to target
+        let empty_pool = TransmuterPool {
+            pool_assets: vec![],
+        };
+        assert_eq!(
+            Transmuter::new()
+                .effective_target_weights(&mock_dependencies().storage, &empty_pool)
+                .unwrap(),
+            BTreeMap::new()
+        );
+    }
+
+    #[test]
+    fn test_deviation_from_targets() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
         deps.querier
-            .update_balance(user_2, vec![Coin::new(1000, "usomoion")]);
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
-        // Query the total shares
-        let res = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::GetTotalShares {}),
-        )
-        .unwrap();
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
 
-        let total_shares: GetTotalSharesResponse = from_json(res).unwrap();
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
-        assert_eq!(total_shares.total_shares.u128(), 3000u128);
+        let transmuter = Transmuter::new();
+        let mut pool = transmuter.pool.load(&deps.storage).unwrap();
+        pool.join_pool(&[Coin::new(750, "uosmo"), Coin::new(250, "uion")])
+            .unwrap();
+        transmuter.pool.save(&mut deps.storage, &pool).unwrap();
+
+        let deviations = |deps: cosmwasm_std::Deps| {
+            let DeviationFromTargetsResponse { deviations } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::DeviationFromTargets {}),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            deviations
+        };
 
-        // Query the total pool liquidity
-        let res = query(
-            deps.as_ref(),
+        // without targets, deviation is from the even split
+        assert_eq!(
+            deviations(deps.as_ref()),
+            BTreeMap::from([
+                ("uion".to_string(), SignedDecimal::percent(-25)),
+                ("uosmo".to_string(), SignedDecimal::percent(25)),
+            ])
+        );
+
+        execute(
+            deps.as_mut(),
             env,
-            ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetTargetWeights {
+                target_weights: BTreeMap::from([
+                    ("uosmo".to_string(), Decimal::percent(80)),
+                    ("uion".to_string(), Decimal::percent(20)),
+                ]),
+            }),
         )
         .unwrap();
 
-        let total_pool_liquidity: GetTotalPoolLiquidityResponse = from_json(res).unwrap();
-
         assert_eq!(
-            total_pool_liquidity.total_pool_liquidity,
-            vec![Coin::new(1000, "uosmo"), Coin::new(2000, "uion")]
+            deviations(deps.as_ref()),
+            BTreeMap::from([
+                ("uion".to_string(), SignedDecimal::percent(5)),
+                ("uosmo".to_string(), SignedDecimal::percent(-5)),
+            ])
         );
     }
 
     #[test]
-    fn test_denom() {
+    fn test_concentration_index() {
         let mut deps = mock_dependencies();
 
         // make denom has non-zero total supply
@@ -3043,48 +19325,55 @@ mod tests {
             alloyed_asset_subdenom: "usomoion".to_string(),
             alloyed_asset_normalization_factor: Uint128::one(),
             moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
-        let info = mock_info(admin, &[]);
 
         // Instantiate the contract.
-        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+        instantiate(deps.as_mut(), env, mock_info(admin, &[]), init_msg).unwrap();
+
+        let concentration_index = |deps: cosmwasm_std::Deps| {
+            let ConcentrationIndexResponse {
+                concentration_index,
+            } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::ConcentrationIndex {}),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            concentration_index
+        };
 
-        // Manually reply
-        let alloyed_denom = "usomoion";
+        // empty pool has no weights
+        assert_eq!(concentration_index(deps.as_ref()), None);
 
-        reply(
-            deps.as_mut(),
-            env.clone(),
-            Reply {
-                id: 1,
-                result: SubMsgResult::Ok(SubMsgResponse {
-                    events: vec![],
-                    data: Some(
-                        MsgCreateDenomResponse {
-                            new_token_denom: alloyed_denom.to_string(),
-                        }
-                        .into(),
-                    ),
-                }),
-            },
-        )
-        .unwrap();
+        let transmuter = Transmuter::new();
+        let mut pool = transmuter.pool.load(&deps.storage).unwrap();
+        pool.join_pool(&[Coin::new(500, "uosmo"), Coin::new(500, "uion")])
+            .unwrap();
+        transmuter.pool.save(&mut deps.storage, &pool).unwrap();
 
-        // Query the share denom
-        let res = query(
-            deps.as_ref(),
-            env,
-            ContractQueryMsg::Transmuter(QueryMsg::GetShareDenom {}),
-        )
-        .unwrap();
+        // evenly split across 2 assets
+        assert_eq!(
+            concentration_index(deps.as_ref()),
+            Some(Decimal::percent(50))
+        );
 
-        let share_denom: GetShareDenomResponse = from_json(res).unwrap();
-        assert_eq!(share_denom.share_denom, "usomoion");
+        pool.join_pool(&[Coin::new(1000, "uosmo")]).unwrap();
+        transmuter.pool.save(&mut deps.storage, &pool).unwrap();
+
+        // 0.75^2 + 0.25^2
+        assert_eq!(
+            concentration_index(deps.as_ref()),
+            Some(Decimal::from_ratio(5u128, 8u128))
+        );
     }
 
     #[test]
-    fn test_spot_price() {
+    fn test_max_proportional_exit() {
         let mut deps = mock_dependencies();
 
         // make denom has non-zero total supply
@@ -3095,22 +19384,23 @@ mod tests {
         let init_msg = InstantiateMsg {
             pool_asset_configs: vec![
                 AssetConfig::from_denom_str("uosmo"),
-                AssetConfig::from_denom_str("uion"),
+                AssetConfig {
+                    denom: "uion".to_string(),
+                    normalization_factor: Uint128::new(100),
+                },
             ],
             admin: Some(admin.to_string()),
-            alloyed_asset_subdenom: "uosmoion".to_string(),
+            alloyed_asset_subdenom: "usomoion".to_string(),
             alloyed_asset_normalization_factor: Uint128::one(),
             moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
-        let info = mock_info(admin, &[]);
 
         // Instantiate the contract.
-        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
         // Manually reply
-        let alloyed_denom = "uosmoion";
-
         reply(
             deps.as_mut(),
             env.clone(),
@@ -3120,7 +19410,7 @@ mod tests {
                     events: vec![],
                     data: Some(
                         MsgCreateDenomResponse {
-                            new_token_denom: alloyed_denom.to_string(),
+                            new_token_denom: "usomoion".to_string(),
                         }
                         .into(),
                     ),
@@ -3129,134 +19419,118 @@ mod tests {
         )
         .unwrap();
 
-        // Test spot price with same denom
-        let err = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
-                quote_asset_denom: "uosmo".to_string(),
-                base_asset_denom: "uosmo".to_string(),
-            }),
-        )
-        .unwrap_err();
-
-        assert_eq!(
-            err,
-            ContractError::SpotPriceQueryFailed {
-                reason: "quote_asset_denom and base_asset_denom cannot be the same".to_string()
-            }
-        );
+        let max_proportional_exit = |deps: cosmwasm_std::Deps| {
+            query(
+                deps,
+                mock_env(),
+                ContractQueryMsg::Transmuter(QueryMsg::MaxProportionalExit {}),
+            )
+            .map(|res| from_json::<MaxProportionalExitResponse>(res).unwrap())
+            .unwrap()
+        };
 
-        // Test spot price with denom not in swappable assets
-        let err = query(
-            deps.as_ref(),
+        // imbalanced pool, uion is worth 1000 shares out of 4000
+        execute(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
-                quote_asset_denom: "uatom".to_string(),
-                base_asset_denom: "uosmo".to_string(),
+            mock_info(
+                "user",
+                &[Coin::new(3000, "uosmo"), Coin::new(100_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
             }),
         )
-        .unwrap_err();
+        .unwrap();
 
+        // capped by total shares when they are scarcer than any denom
+        deps.querier
+            .update_balance("user", vec![Coin::new(1000, "usomoion")]);
         assert_eq!(
-            err,
-            ContractError::SpotPriceQueryFailed {
-                reason: "quote_asset_denom is not in swappable assets: must be one of [\"uion\", \"uosmo\", \"uosmoion\"] but got uatom".to_string()
+            max_proportional_exit(deps.as_ref()),
+            MaxProportionalExitResponse {
+                share_amount: Uint128::new(1000),
+                tokens_out: vec![Coin::new(500, "uosmo"), Coin::new(50_000, "uion")],
             }
         );
 
-        let err = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
-                quote_asset_denom: "uion".to_string(),
-                base_asset_denom: "uatom".to_string(),
-            }),
-        )
-        .unwrap_err();
-
+        // capped by the scarcest denom, uion
+        deps.querier
+            .update_balance("user", vec![Coin::new(4000, "usomoion")]);
+        let MaxProportionalExitResponse {
+            share_amount,
+            tokens_out,
+        } = max_proportional_exit(deps.as_ref());
+        assert_eq!(share_amount, Uint128::new(2000));
         assert_eq!(
-            err,
-            ContractError::SpotPriceQueryFailed {
-                reason: "base_asset_denom is not in swappable assets: must be one of [\"uion\", \"uosmo\", \"uosmoion\"] but got uatom".to_string()
-            }
+            tokens_out,
+            vec![Coin::new(1000, "uosmo"), Coin::new(100_000, "uion")]
         );
 
-        // Test spot price with pool assets
-        let res = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
-                quote_asset_denom: "uosmo".to_string(),
-                base_asset_denom: "uion".to_string(),
-            }),
-        )
-        .unwrap();
-
-        let spot_price: SpotPriceResponse = from_json(res).unwrap();
-        assert_eq!(spot_price.spot_price, Decimal::one());
-
-        // Test spot price with alloyed denom
-        let res = query(
-            deps.as_ref(),
+        // exiting with the basket drains uion
+        execute(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
-                quote_asset_denom: "uosmo".to_string(),
-                base_asset_denom: alloyed_denom.to_string(),
-            }),
+            mock_info("user", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::ExitPool { tokens_out }),
         )
         .unwrap();
 
-        let spot_price: SpotPriceResponse = from_json(res).unwrap();
-        assert_eq!(spot_price.spot_price, Decimal::one());
-
-        let res = query(
-            deps.as_ref(),
-            env,
-            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
-                quote_asset_denom: alloyed_denom.to_string(),
-                base_asset_denom: "uion".to_string(),
-            }),
+        let GetTotalPoolLiquidityResponse {
+            total_pool_liquidity,
+        } = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                ContractQueryMsg::Transmuter(QueryMsg::GetTotalPoolLiquidity {}),
+            )
+            .unwrap(),
         )
         .unwrap();
+        assert_eq!(
+            total_pool_liquidity,
+            vec![Coin::new(2000, "uosmo"), Coin::new(0, "uion")]
+        );
 
-        let spot_price: SpotPriceResponse = from_json(res).unwrap();
-        assert_eq!(spot_price.spot_price, Decimal::one());
+        // nothing can be withdrawn proportionally once any denom is empty
+        deps.querier
+            .update_balance("user", vec![Coin::new(2000, "usomoion")]);
+        assert_eq!(
+            max_proportional_exit(deps.as_ref()),
+            MaxProportionalExitResponse {
+                share_amount: Uint128::zero(),
+                tokens_out: vec![Coin::new(0, "uosmo"), Coin::new(0, "uion")],
+            }
+        );
     }
 
     #[test]
-    fn test_spot_price_with_different_norm_factor() {
+    fn test_holder_count() {
         let mut deps = mock_dependencies();
 
         // make denom has non-zero total supply
         deps.querier
-            .update_balance("someone", vec![Coin::new(1, "tbtc"), Coin::new(1, "nbtc")]);
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
         let admin = "admin";
         let init_msg = InstantiateMsg {
             pool_asset_configs: vec![
-                AssetConfig {
-                    denom: "tbtc".to_string(),
-                    normalization_factor: Uint128::from(1u128),
-                },
-                AssetConfig {
-                    denom: "nbtc".to_string(),
-                    normalization_factor: Uint128::from(100u128),
-                },
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
             ],
             admin: Some(admin.to_string()),
-            alloyed_asset_subdenom: "allbtc".to_string(),
-            alloyed_asset_normalization_factor: Uint128::from(100u128),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
             moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
-        let info = mock_info(admin, &[]);
 
         // Instantiate the contract.
-        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
         // Manually reply
-        let alloyed_denom = "allbtc";
+        let alloyed_denom = "usomoion";
 
         reply(
             deps.as_mut(),
@@ -3276,99 +19550,92 @@ mod tests {
         )
         .unwrap();
 
-        // Test spot price with pool assets
-        let res = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
-                base_asset_denom: "nbtc".to_string(),
-                quote_asset_denom: "tbtc".to_string(),
-            }),
-        )
-        .unwrap();
+        let holder_count = |deps: cosmwasm_std::Deps| {
+            let HolderCountResponse { holder_count } = from_json(
+                query(
+                    deps,
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::HolderCount {}),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            holder_count
+        };
 
-        // tbtc/1 = nbtc/100
-        // tbtc = 1nbtc/100
-        let spot_price: SpotPriceResponse = from_json(res).unwrap();
-        assert_eq!(spot_price.spot_price, Decimal::from_ratio(1u128, 100u128));
+        assert_eq!(holder_count(deps.as_ref()), 0);
 
-        let res = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
-                base_asset_denom: "tbtc".to_string(),
-                quote_asset_denom: "nbtc".to_string(),
-            }),
-        )
-        .unwrap();
+        for (user, amount) in [("user1", 1_000), ("user2", 2_000), ("user1", 500)] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(user, &[Coin::new(amount, "uosmo")]),
+                ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                    min_shares_out: None,
+                }),
+            )
+            .unwrap();
+        }
 
-        // nbtc/100 = tbtc/1
-        // nbtc = 100tbtc
-        let spot_price: SpotPriceResponse = from_json(res).unwrap();
-        assert_eq!(spot_price.spot_price, Decimal::from_ratio(100u128, 1u128));
+        // joining again does not double count
+        assert_eq!(holder_count(deps.as_ref()), 2);
 
-        // Test spot price with alloyed denom
-        let res = query(
-            deps.as_ref(),
+        deps.querier
+            .update_balance("user1", vec![Coin::new(1_500, alloyed_denom)]);
+        deps.querier
+            .update_balance("user2", vec![Coin::new(2_000, alloyed_denom)]);
+
+        // partial exit keeps the holder
+        execute(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
-                quote_asset_denom: "nbtc".to_string(),
-                base_asset_denom: alloyed_denom.to_string(),
+            mock_info("user2", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+                tokens_out: vec![Coin::new(1_000, "uosmo")],
             }),
         )
         .unwrap();
+        assert_eq!(holder_count(deps.as_ref()), 2);
 
-        // nbtc/100 = allbtc/100
-        // nbtc = 1allbtc
-        let spot_price: SpotPriceResponse = from_json(res).unwrap();
-        assert_eq!(spot_price.spot_price, Decimal::one());
-
-        let res = query(
-            deps.as_ref(),
+        // full exit removes the holder
+        execute(
+            deps.as_mut(),
             env,
-            ContractQueryMsg::Transmuter(QueryMsg::SpotPrice {
-                quote_asset_denom: alloyed_denom.to_string(),
-                base_asset_denom: "tbtc".to_string(),
+            mock_info("user1", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+                tokens_out: vec![Coin::new(1_500, "uosmo")],
             }),
         )
         .unwrap();
-
-        // allbtc/100 = tbtc/1
-        // tbtc = 100allbtc
-        let spot_price: SpotPriceResponse = from_json(res).unwrap();
-        assert_eq!(spot_price.spot_price, Decimal::from_ratio(100u128, 1u128));
+        assert_eq!(holder_count(deps.as_ref()), 1);
     }
 
     #[test]
-    fn test_calc_out_amt_given_in() {
+    fn test_dust_threshold() {
         let mut deps = mock_dependencies();
 
         // make denom has non-zero total supply
-        deps.querier.update_balance(
-            "someone",
-            vec![Coin::new(1, "axlusdc"), Coin::new(1, "whusdc")],
-        );
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
         let admin = "admin";
         let init_msg = InstantiateMsg {
             pool_asset_configs: vec![
-                AssetConfig::from_denom_str("axlusdc"),
-                AssetConfig::from_denom_str("whusdc"),
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
             ],
             admin: Some(admin.to_string()),
-            alloyed_asset_subdenom: "alloyedusdc".to_string(),
+            alloyed_asset_subdenom: "usomoion".to_string(),
             alloyed_asset_normalization_factor: Uint128::one(),
             moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
-        let info = mock_info(admin, &[]);
 
         // Instantiate the contract.
-        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
         // Manually reply
-        let alloyed_denom = "alloyedusdc";
-
         reply(
             deps.as_mut(),
             env.clone(),
@@ -3378,7 +19645,7 @@ mod tests {
                     events: vec![],
                     data: Some(
                         MsgCreateDenomResponse {
-                            new_token_denom: alloyed_denom.to_string(),
+                            new_token_denom: "usomoion".to_string(),
                         }
                         .into(),
                     ),
@@ -3387,201 +19654,128 @@ mod tests {
         )
         .unwrap();
 
-        // join pool
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
         execute(
             deps.as_mut(),
             env.clone(),
             mock_info(
-                admin,
-                &[Coin::new(1000, "axlusdc"), Coin::new(2000, "whusdc")],
+                "user",
+                &[Coin::new(1_000, "uosmo"), Coin::new(1_000, "uion")],
             ),
-            join_pool_msg,
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
         )
         .unwrap();
 
-        struct Case {
-            name: String,
-            token_in: Coin,
-            token_out_denom: String,
-            swap_fee: Decimal,
-            expected: Result<CalcOutAmtGivenInResponse, ContractError>,
-        }
+        // only admin can set dust threshold
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetDustThreshold {
+                dust_threshold: Uint128::new(10),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
 
-        for Case {
-            name,
-            token_in,
-            token_out_denom,
-            swap_fee,
-            expected,
-        } in vec![
-            Case {
-                name: String::from("axlusdc to whusdc - ok"),
-                token_in: Coin::new(1000, "axlusdc"),
-                token_out_denom: "whusdc".to_string(),
-                swap_fee: Decimal::zero(),
-                expected: Ok(CalcOutAmtGivenInResponse {
-                    token_out: Coin::new(1000, "whusdc"),
-                }),
-            },
-            Case {
-                name: String::from("whusdc to axlusdc - ok"),
-                token_in: Coin::new(1000, "whusdc"),
-                token_out_denom: "axlusdc".to_string(),
-                swap_fee: Decimal::zero(),
-                expected: Ok(CalcOutAmtGivenInResponse {
-                    token_out: Coin::new(1000, "axlusdc"),
-                }),
-            },
-            Case {
-                name: String::from("whusdc to axlusdc - token out not enough"),
-                token_in: Coin::new(1001, "whusdc"),
-                token_out_denom: "axlusdc".to_string(),
-                swap_fee: Decimal::zero(),
-                expected: Err(ContractError::InsufficientPoolAsset {
-                    required: Coin::new(1001, "axlusdc"),
-                    available: Coin::new(1000, "axlusdc"),
-                }),
-            },
-            Case {
-                name: String::from("same denom error (pool asset)"),
-                token_in: Coin::new(1000, "axlusdc"),
-                token_out_denom: "axlusdc".to_string(),
-                swap_fee: Decimal::zero(),
-                expected: Err(ContractError::SameDenomNotAllowed {
-                    denom: "axlusdc".to_string(),
-                }),
-            },
-            Case {
-                name: String::from("same denom error (alloyed asset)"),
-                token_in: Coin::new(1000, "alloyedusdc"),
-                token_out_denom: "alloyedusdc".to_string(),
-                swap_fee: Decimal::zero(),
-                expected: Err(ContractError::SameDenomNotAllowed {
-                    denom: "alloyedusdc".to_string(),
-                }),
-            },
-            Case {
-                name: String::from("alloyedusdc to axlusdc - ok"),
-                token_in: Coin::new(1000, "alloyedusdc"),
-                token_out_denom: "axlusdc".to_string(),
-                swap_fee: Decimal::zero(),
-                expected: Ok(CalcOutAmtGivenInResponse {
-                    token_out: Coin::new(1000, "axlusdc"),
-                }),
-            },
-            Case {
-                name: String::from("alloyedusdc to whusdc - ok"),
-                token_in: Coin::new(1000, "alloyedusdc"),
-                token_out_denom: "whusdc".to_string(),
-                swap_fee: Decimal::zero(),
-                expected: Ok(CalcOutAmtGivenInResponse {
-                    token_out: Coin::new(1000, "whusdc"),
-                }),
-            },
-            Case {
-                name: String::from("alloyedusdc to axlusdc - token out not enough"),
-                token_in: Coin::new(1001, "alloyedusdc"),
-                token_out_denom: "axlusdc".to_string(),
-                swap_fee: Decimal::zero(),
-                expected: Err(ContractError::InsufficientPoolAsset {
-                    required: Coin::new(1001, "axlusdc"),
-                    available: Coin::new(1000, "axlusdc"),
-                }),
-            },
-            Case {
-                name: String::from("axlusdc to alloyedusdc - ok"),
-                token_in: Coin::new(1000, "axlusdc"),
-                token_out_denom: "alloyedusdc".to_string(),
-                swap_fee: Decimal::zero(),
-                expected: Ok(CalcOutAmtGivenInResponse {
-                    token_out: Coin::new(1000, "alloyedusdc"),
-                }),
-            },
-            Case {
-                name: String::from("whusdc to alloyedusdc - ok"),
-                token_in: Coin::new(1000, "whusdc"),
-                token_out_denom: "alloyedusdc".to_string(),
-                swap_fee: Decimal::zero(),
-                expected: Ok(CalcOutAmtGivenInResponse {
-                    token_out: Coin::new(1000, "alloyedusdc"),
-                }),
-            },
-            Case {
-                name: String::from("invalid swap fee"),
-                token_in: Coin::new(1000, "axlusdc"),
-                token_out_denom: "whusdc".to_string(),
-                swap_fee: Decimal::percent(1),
-                expected: Err(ContractError::InvalidSwapFee {
-                    expected: Decimal::zero(),
-                    actual: Decimal::percent(1),
-                }),
-            },
-            Case {
-                name: String::from("invalid swap fee (alloyed asset as token in)"),
-                token_in: Coin::new(1000, "alloyedusdc"),
-                token_out_denom: "whusdc".to_string(),
-                swap_fee: Decimal::percent(1),
-                expected: Err(ContractError::InvalidSwapFee {
-                    expected: Decimal::zero(),
-                    actual: Decimal::percent(1),
-                }),
-            },
-            Case {
-                name: String::from("invalid swap fee (alloyed asset as token out)"),
-                token_in: Coin::new(1000, "axlusdc"),
-                token_out_denom: "alloyedusdc".to_string(),
-                swap_fee: Decimal::percent(2),
-                expected: Err(ContractError::InvalidSwapFee {
-                    expected: Decimal::zero(),
-                    actual: Decimal::percent(2),
-                }),
-            },
-        ] {
-            let res = query(
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            ContractExecMsg::Transmuter(ExecMsg::SetDustThreshold {
+                dust_threshold: Uint128::new(10),
+            }),
+        )
+        .unwrap();
+
+        let GetDustThresholdResponse { dust_threshold } = from_json(
+            query(
                 deps.as_ref(),
                 env.clone(),
-                ContractQueryMsg::Transmuter(QueryMsg::CalcOutAmtGivenIn {
-                    token_in: token_in.clone(),
-                    token_out_denom: token_out_denom.clone(),
-                    swap_fee,
-                }),
+                ContractQueryMsg::Transmuter(QueryMsg::GetDustThreshold {}),
             )
-            .map(|value| from_json(value).unwrap());
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(dust_threshold, Uint128::new(10));
+
+        let swap = |deps: DepsMut, amount: u128| {
+            sudo(
+                deps,
+                mock_env(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: "someone".to_string(),
+                    token_in: Coin::new(amount, "uosmo"),
+                    token_out_denom: "uion".to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: Decimal::zero(),
+                    partial_fill: false,
+                    require_improves_balance: false,
+                },
+            )
+            .unwrap()
+        };
 
-            assert_eq!(res, expected, "case: {}", name);
-        }
+        // residual above threshold is kept
+        swap(deps.as_mut(), 990);
+        assert_eq!(
+            total_liquidity_of("uion", &deps.storage),
+            Coin::new(10, "uion")
+        );
+
+        // residual below threshold is folded into retained dust
+        swap(deps.as_mut(), 5);
+        assert_eq!(
+            total_liquidity_of("uion", &deps.storage),
+            Coin::new(0, "uion")
+        );
+
+        let RetainedDustResponse { retained_dust } = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                ContractQueryMsg::Transmuter(QueryMsg::RetainedDust {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(retained_dust, Decimal256::from_ratio(5u128, 1u128));
     }
 
     #[test]
-    fn test_calc_in_amt_given_out() {
+    fn test_retained_dust() {
         let mut deps = mock_dependencies();
 
         // make denom has non-zero total supply
-        deps.querier.update_balance(
-            "someone",
-            vec![Coin::new(1, "axlusdc"), Coin::new(1, "whusdc")],
-        );
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
         let admin = "admin";
         let init_msg = InstantiateMsg {
             pool_asset_configs: vec![
-                AssetConfig::from_denom_str("axlusdc"),
-                AssetConfig::from_denom_str("whusdc"),
+                AssetConfig {
+                    denom: "uosmo".to_string(),
+                    normalization_factor: Uint128::new(3),
+                },
+                AssetConfig {
+                    denom: "uion".to_string(),
+                    normalization_factor: Uint128::new(7),
+                },
             ],
             admin: Some(admin.to_string()),
-            alloyed_asset_subdenom: "alloyedusdc".to_string(),
-            alloyed_asset_normalization_factor: Uint128::one(),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::new(2),
             moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
-        let info = mock_info(admin, &[]);
 
         // Instantiate the contract.
-        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
         // Manually reply
-        let alloyed_denom = "alloyedusdc";
+        let alloyed_denom = "usomoion";
 
         reply(
             deps.as_mut(),
@@ -3601,193 +19795,313 @@ mod tests {
         )
         .unwrap();
 
-        // join pool
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
         execute(
             deps.as_mut(),
             env.clone(),
             mock_info(
-                admin,
-                &[Coin::new(1000, "axlusdc"), Coin::new(2000, "whusdc")],
+                "user",
+                &[
+                    Coin::new(300_000_000, "uosmo"),
+                    Coin::new(700_000_000, "uion"),
+                ],
             ),
-            join_pool_msg,
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
         )
         .unwrap();
+        deps.querier
+            .update_balance("user", vec![Coin::new(400_000_000, alloyed_denom)]);
 
-        struct Case {
-            name: String,
-            token_in_denom: String,
-            token_out: Coin,
-            swap_fee: Decimal,
-            expected: Result<CalcInAmtGivenOutResponse, ContractError>,
+        let normalization_factor = |denom: &str| match denom {
+            "uosmo" => 3u128,
+            "uion" => 7u128,
+            _ => unreachable!(),
+        };
+
+        // deterministic pseudo-random amounts
+        let mut seed = 42u64;
+        let mut random_amount = |max: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            u128::from((seed >> 33) % max + 1)
+        };
+
+        let mut expected_dust = Decimal256::zero();
+        for i in 0..100 {
+            let msg = if i % 2 == 0 {
+                ExecMsg::ExitPool {
+                    tokens_out: vec![
+                        Coin::new(random_amount(10_000), "uosmo"),
+                        Coin::new(random_amount(10_000), "uion"),
+                    ],
+                }
+            } else {
+                ExecMsg::ExitToSingleDenom {
+                    share_amount: Uint128::new(random_amount(10_000)),
+                    target_denom: if i % 4 == 1 { "uosmo" } else { "uion" }.to_string(),
+                    min_amount_out: Uint128::zero(),
+                }
+            };
+
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("user", &[]),
+                ContractExecMsg::Transmuter(msg),
+            )
+            .unwrap();
+
+            let burned = res
+                .events
+                .iter()
+                .find(|event| event.ty == ALLOYED_ASSET_TRANSFER_EVENT)
+                .and_then(|event| event.attributes.iter().find(|attr| attr.key == "amount"))
+                .map(|attr| {
+                    attr.value
+                        .trim_end_matches(alloyed_denom)
+                        .parse::<u128>()
+                        .unwrap()
+                })
+                .unwrap();
+
+            let paid_out = res
+                .messages
+                .iter()
+                .find_map(|msg| match &msg.msg {
+                    CosmosMsg::Bank(BankMsg::Send { amount, .. }) => Some(amount.clone()),
+                    _ => None,
+                })
+                .unwrap()
+                .iter()
+                .map(|coin| {
+                    Decimal256::from_ratio(
+                        coin.amount.u128() * 2,
+                        normalization_factor(&coin.denom),
+                    )
+                })
+                .fold(Decimal256::zero(), |acc, value| acc + value);
+
+            // payouts never exceed the value of burned shares
+            let burned = Decimal256::from_ratio(burned, 1u128);
+            assert!(paid_out <= burned, "{paid_out} > {burned}");
+
+            expected_dust += burned - paid_out;
         }
 
-        for Case {
-            name,
-            token_in_denom,
-            token_out,
-            swap_fee,
-            expected,
-        } in vec![
-            Case {
-                name: String::from("axlusdc to whusdc - ok"),
-                token_in_denom: "axlusdc".to_string(),
-                token_out: Coin::new(1000, "whusdc"),
-                swap_fee: Decimal::zero(),
-                expected: Ok(CalcInAmtGivenOutResponse {
-                    token_in: Coin::new(1000, "axlusdc"),
-                }),
-            },
-            Case {
-                name: String::from("whusdc to axlusdc - ok"),
-                token_in_denom: "whusdc".to_string(),
-                token_out: Coin::new(1000, "axlusdc"),
-                swap_fee: Decimal::zero(),
-                expected: Ok(CalcInAmtGivenOutResponse {
-                    token_in: Coin::new(1000, "whusdc"),
-                }),
-            },
-            Case {
-                name: String::from("whusdc to axlusdc - token out not enough"),
-                token_in_denom: "whusdc".to_string(),
-                token_out: Coin::new(1001, "axlusdc"),
-                swap_fee: Decimal::zero(),
-                expected: Err(ContractError::InsufficientPoolAsset {
-                    required: Coin::new(1001, "axlusdc"),
-                    available: Coin::new(1000, "axlusdc"),
-                }),
-            },
-            Case {
-                name: String::from("same denom error (pool asset)"),
-                token_in_denom: "axlusdc".to_string(),
-                token_out: Coin::new(1000, "axlusdc"),
-                swap_fee: Decimal::zero(),
-                expected: Err(ContractError::SameDenomNotAllowed {
-                    denom: "axlusdc".to_string(),
-                }),
-            },
-            Case {
-                name: String::from("same denom error (alloyed asset)"),
-                token_in_denom: "alloyedusdc".to_string(),
-                token_out: Coin::new(1000, "alloyedusdc"),
-                swap_fee: Decimal::zero(),
-                expected: Err(ContractError::SameDenomNotAllowed {
-                    denom: "alloyedusdc".to_string(),
-                }),
-            },
-            Case {
-                name: String::from("alloyedusdc to axlusdc - ok"),
-                token_in_denom: "alloyedusdc".to_string(),
-                token_out: Coin::new(1000, "axlusdc"),
-                swap_fee: Decimal::zero(),
-                expected: Ok(CalcInAmtGivenOutResponse {
-                    token_in: Coin::new(1000, "alloyedusdc"),
-                }),
-            },
-            Case {
-                name: String::from("alloyedusdc to whusdc - ok"),
-                token_in_denom: "alloyedusdc".to_string(),
-                token_out: Coin::new(1000, "whusdc"),
-                swap_fee: Decimal::zero(),
-                expected: Ok(CalcInAmtGivenOutResponse {
-                    token_in: Coin::new(1000, "alloyedusdc"),
-                }),
-            },
-            Case {
-                name: String::from("alloyedusdc to axlusdc - token out not enough"),
-                token_in_denom: "alloyedusdc".to_string(),
-                token_out: Coin::new(1001, "axlusdc"),
-                swap_fee: Decimal::zero(),
-                expected: Err(ContractError::InsufficientPoolAsset {
-                    required: Coin::new(1001, "axlusdc"),
-                    available: Coin::new(1000, "axlusdc"),
-                }),
-            },
-            Case {
-                name: String::from("pool asset to alloyed asset - ok"),
-                token_in_denom: "axlusdc".to_string(),
-                token_out: Coin::new(1000, "alloyedusdc"),
-                swap_fee: Decimal::zero(),
-                expected: Ok(CalcInAmtGivenOutResponse {
-                    token_in: Coin::new(1000, "axlusdc"),
-                }),
-            },
-            Case {
-                name: String::from("invalid swap fee"),
-                token_in_denom: "whusdc".to_string(),
-                token_out: Coin::new(1000, "axlusdc"),
-                swap_fee: Decimal::percent(1),
-                expected: Err(ContractError::InvalidSwapFee {
-                    expected: Decimal::zero(),
-                    actual: Decimal::percent(1),
-                }),
-            },
-            Case {
-                name: String::from("invalid swap fee (alloyed asset as token in)"),
-                token_in_denom: "alloyedusdc".to_string(),
-                token_out: Coin::new(1000, "axlusdc"),
-                swap_fee: Decimal::percent(1),
-                expected: Err(ContractError::InvalidSwapFee {
-                    expected: Decimal::zero(),
-                    actual: Decimal::percent(1),
+        let RetainedDustResponse { retained_dust } = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                ContractQueryMsg::Transmuter(QueryMsg::RetainedDust {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert!(retained_dust > Decimal256::zero());
+        assert_eq!(retained_dust, expected_dust);
+    }
+
+    #[test]
+    fn test_min_locked_shares() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let contract_address = env.contract.address.to_string();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "usomoion";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
                 }),
             },
-            Case {
-                name: String::from("invalid swap fee (alloyed asset as token out)"),
-                token_in_denom: "whusdc".to_string(),
-                token_out: Coin::new(1000, "alloyedusdc"),
-                swap_fee: Decimal::percent(2),
-                expected: Err(ContractError::InvalidSwapFee {
-                    expected: Decimal::zero(),
-                    actual: Decimal::percent(2),
+        )
+        .unwrap();
+
+        // only admin can set min locked shares
+        let set_min_locked_shares_msg = ContractExecMsg::Transmuter(ExecMsg::SetMinLockedShares {
+            min_locked_shares: Uint128::new(1000),
+        });
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("someone", &[]),
+            set_min_locked_shares_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(admin, &[]),
+            set_min_locked_shares_msg,
+        )
+        .unwrap();
+
+        let join_pool = |deps: DepsMut, amount: u128| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info("user", &[Coin::new(amount, "uosmo")]),
+                ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                    min_shares_out: None,
                 }),
-            },
-        ] {
-            let res = query(
+            )
+        };
+
+        // first join must exceed min locked shares
+        let err = join_pool(deps.as_mut(), 1000).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientInitialLiquidity {
+                min_locked_shares: Uint128::new(1000),
+                amount: Uint128::new(1000),
+            }
+        );
+
+        // first join locks min locked shares in the contract
+        let res = join_pool(deps.as_mut(), 5000).unwrap();
+        assert!(res.events.contains(&alloyed_asset_transfer_event(
+            "lock",
+            "",
+            &contract_address,
+            &Coin::new(1000, alloyed_denom),
+        )));
+        assert!(res.events.contains(&alloyed_asset_transfer_event(
+            "mint",
+            "",
+            "user",
+            &Coin::new(4000, alloyed_denom),
+        )));
+
+        let GetMinLockedSharesResponse {
+            min_locked_shares,
+            locked_shares,
+        } = from_json(
+            query(
                 deps.as_ref(),
                 env.clone(),
-                ContractQueryMsg::Transmuter(QueryMsg::CalcInAmtGivenOut {
-                    token_in_denom: token_in_denom.clone(),
-                    token_out: token_out.clone(),
-                    swap_fee,
-                }),
+                ContractQueryMsg::Transmuter(QueryMsg::GetMinLockedShares {}),
             )
-            .map(|value| from_json(value).unwrap());
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(min_locked_shares, Uint128::new(1000));
+        assert_eq!(locked_shares, Uint128::new(1000));
 
-            assert_eq!(res, expected, "case: {}", name);
-        }
+        deps.querier
+            .update_balance("user", vec![Coin::new(4000, alloyed_denom)]);
+        deps.querier
+            .update_balance(&contract_address, vec![Coin::new(1000, alloyed_denom)]);
+
+        // subsequent joins do not lock any more shares
+        let res = join_pool(deps.as_mut(), 5000).unwrap();
+        assert!(res
+            .events
+            .iter()
+            .all(|event| !event.attributes.contains(&attr("action", "lock"))));
+
+        // locked shares can not be redeemed, so the pool is never drained to zero shares
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &[]),
+            ContractExecMsg::Transmuter(ExecMsg::ExitPool {
+                tokens_out: vec![Coin::new(10000, "uosmo")],
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientShares {
+                required: Uint128::new(10000),
+                available: Uint128::new(4000),
+            }
+        );
+
+        let err = sudo(
+            deps.as_mut(),
+            env.clone(),
+            SudoMsg::SwapExactAmountIn {
+                sender: "someone".to_string(),
+                token_in: Coin::new(1000, alloyed_denom),
+                token_out_denom: "uosmo".to_string(),
+                token_out_min_amount: Uint128::one(),
+                swap_fee: Decimal::zero(),
+                partial_fill: false,
+                require_improves_balance: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InsufficientShares {
+                required: Uint128::new(1000),
+                available: Uint128::zero(),
+            }
+        );
     }
 
     #[test]
-    fn test_rescale_normalization_factor() {
+    fn test_join_pool_min_shares_out() {
         let mut deps = mock_dependencies();
 
         // make denom has non-zero total supply
-        deps.querier.update_balance(
-            "someone",
-            vec![Coin::new(1, "axlusdc"), Coin::new(1, "whusdc")],
-        );
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
 
         let admin = "admin";
         let init_msg = InstantiateMsg {
             pool_asset_configs: vec![
-                AssetConfig::from_denom_str("axlusdc"),
-                AssetConfig::from_denom_str("whusdc"),
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
             ],
             admin: Some(admin.to_string()),
-            alloyed_asset_subdenom: "alloyedusdc".to_string(),
-            alloyed_asset_normalization_factor: Uint128::from(100u128),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
             moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
-        let info = mock_info(admin, &[]);
 
         // Instantiate the contract.
-        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
 
         // Manually reply
-        let alloyed_denom = "alloyedusdc";
-
         reply(
             deps.as_mut(),
             env.clone(),
@@ -3797,7 +20111,7 @@ mod tests {
                     events: vec![],
                     data: Some(
                         MsgCreateDenomResponse {
-                            new_token_denom: alloyed_denom.to_string(),
+                            new_token_denom: "usomoion".to_string(),
                         }
                         .into(),
                     ),
@@ -3806,110 +20120,188 @@ mod tests {
         )
         .unwrap();
 
-        // list asset configs
-        let res: Result<ListAssetConfigsResponse, ContractError> = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::ListAssetConfigs {}),
-        )
-        .map(|value| from_json(value).unwrap());
-
-        assert_eq!(
-            res,
-            Ok(ListAssetConfigsResponse {
-                asset_configs: vec![
-                    AssetConfig::from_denom_str("axlusdc"),
-                    AssetConfig::from_denom_str("whusdc"),
-                    AssetConfig {
-                        denom: alloyed_denom.to_string(),
-                        normalization_factor: Uint128::from(100u128),
-                    }
-                ]
-            })
-        );
-
-        // scale up
-        let rescale_msg = ContractExecMsg::Transmuter(ExecMsg::RescaleNormalizationFactor {
-            numerator: Uint128::from(100u128),
-            denominator: Uint128::one(),
-        });
+        let join_pool = |deps: DepsMut, min_shares_out: Option<Uint128>| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info("user", &[Coin::new(5000, "uosmo")]),
+                ContractExecMsg::Transmuter(ExecMsg::JoinPool { min_shares_out }),
+            )
+        };
 
+        // shares get locked after the join was constructed, so the mint falls short of the floor
         execute(
             deps.as_mut(),
-            env.clone(),
+            env,
             mock_info(admin, &[]),
-            rescale_msg,
+            ContractExecMsg::Transmuter(ExecMsg::SetMinLockedShares {
+                min_locked_shares: Uint128::new(1000),
+            }),
         )
         .unwrap();
 
-        // list asset configs
-        let res: Result<ListAssetConfigsResponse, ContractError> = query(
-            deps.as_ref(),
-            env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::ListAssetConfigs {}),
-        )
-        .map(|value| from_json(value).unwrap());
-
+        let err = join_pool(deps.as_mut(), Some(Uint128::new(5000))).unwrap_err();
         assert_eq!(
-            res,
-            Ok(ListAssetConfigsResponse {
-                asset_configs: vec![
-                    AssetConfig {
-                        denom: "axlusdc".to_string(),
-                        normalization_factor: Uint128::from(100u128),
-                    },
-                    AssetConfig {
-                        denom: "whusdc".to_string(),
-                        normalization_factor: Uint128::from(100u128),
-                    },
-                    AssetConfig {
-                        denom: alloyed_denom.to_string(),
-                        normalization_factor: Uint128::from(10000u128),
-                    }
-                ]
-            })
+            err,
+            ContractError::InsufficientShares {
+                required: Uint128::new(5000),
+                available: Uint128::new(4000),
+            }
         );
 
-        // scale down
-        let rescale_msg = ContractExecMsg::Transmuter(ExecMsg::RescaleNormalizationFactor {
-            numerator: Uint128::one(),
-            denominator: Uint128::from(100u128),
-        });
+        join_pool(deps.as_mut(), Some(Uint128::new(4000))).unwrap();
+    }
 
-        execute(
+    #[test]
+    fn test_check_weights() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1, "uosmo"), Coin::new(1, "uion")]);
+
+        let admin = "admin";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("uosmo"),
+                AssetConfig::from_denom_str("uion"),
+            ],
+            admin: Some(admin.to_string()),
+            alloyed_asset_subdenom: "usomoion".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), mock_info(admin, &[]), init_msg).unwrap();
+
+        // Manually reply
+        reply(
             deps.as_mut(),
             env.clone(),
-            mock_info(admin, &[]),
-            rescale_msg,
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: "usomoion".to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
         )
         .unwrap();
 
-        // list asset configs
-        let res: Result<ListAssetConfigsResponse, ContractError> = query(
-            deps.as_ref(),
+        for (denom, label, limiter_params) in [
+            (
+                "uosmo",
+                "static",
+                LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            ),
+            (
+                "uion",
+                "static",
+                LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(70),
+                },
+            ),
+            (
+                "uion",
+                "relative",
+                LimiterParams::RelativeLimiter {
+                    max_deviation: Decimal::percent(10),
+                },
+            ),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(admin, &[]),
+                ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                    denom: denom.to_string(),
+                    label: label.to_string(),
+                    limiter_params,
+                }),
+            )
+            .unwrap();
+        }
+
+        // relative limiter takes the even split as its latest value
+        execute(
+            deps.as_mut(),
             env.clone(),
-            ContractQueryMsg::Transmuter(QueryMsg::ListAssetConfigs {}),
+            mock_info(
+                "provider",
+                &[Coin::new(1_000, "uosmo"), Coin::new(1_000, "uion")],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
         )
-        .map(|value| from_json(value).unwrap());
+        .unwrap();
+
+        let check_weights = |weights: Vec<(&str, Decimal)>| {
+            let CheckWeightsResponse { results } = from_json(
+                query(
+                    deps.as_ref(),
+                    mock_env(),
+                    ContractQueryMsg::Transmuter(QueryMsg::CheckWeights {
+                        weights: weights
+                            .into_iter()
+                            .map(|(denom, weight)| (denom.to_string(), weight))
+                            .collect(),
+                    }),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+            results
+        };
+
+        let result = |denom: &str, label: &str, passes: bool| LimiterCheckResult {
+            denom: denom.to_string(),
+            label: label.to_string(),
+            passes,
+        };
 
+        // uosmo exceeds its static limit while uion passes both of its limiters
         assert_eq!(
-            res,
-            Ok(ListAssetConfigsResponse {
-                asset_configs: vec![
-                    AssetConfig {
-                        denom: "axlusdc".to_string(),
-                        normalization_factor: Uint128::from(1u128),
-                    },
-                    AssetConfig {
-                        denom: "whusdc".to_string(),
-                        normalization_factor: Uint128::from(1u128),
-                    },
-                    AssetConfig {
-                        denom: alloyed_denom.to_string(),
-                        normalization_factor: Uint128::from(100u128),
-                    }
-                ]
-            })
+            check_weights(vec![
+                ("uosmo", Decimal::percent(59)),
+                ("uion", Decimal::percent(41)),
+            ]),
+            vec![
+                result("uosmo", "static", true),
+                result("uion", "relative", true),
+                result("uion", "static", true),
+            ]
+        );
+
+        assert_eq!(
+            check_weights(vec![
+                ("uosmo", Decimal::percent(65)),
+                ("uion", Decimal::percent(35)),
+            ]),
+            vec![
+                result("uosmo", "static", false),
+                result("uion", "relative", false),
+                result("uion", "static", true),
+            ]
+        );
+
+        // only limiters of the supplied denoms are checked
+        assert_eq!(
+            check_weights(vec![("uion", Decimal::percent(100))]),
+            vec![
+                result("uion", "relative", true),
+                result("uion", "static", false),
+            ]
         );
     }
 }
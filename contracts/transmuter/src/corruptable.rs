@@ -0,0 +1,10 @@
+/// Something that can be marked as corrupted, e.g. a single pool asset or an asset group.
+/// Corrupted assets are not allowed to be increased by any means,
+/// both in terms of amount and weight.
+pub trait Corruptable {
+    fn is_corrupted(&self) -> bool;
+
+    fn mark_as_corrupted(&mut self) -> &mut Self;
+
+    fn unmark_as_corrupted(&mut self) -> &mut Self;
+}
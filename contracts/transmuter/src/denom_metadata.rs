@@ -0,0 +1,137 @@
+use cosmwasm_std::{QuerierWrapper, StdResult, Storage};
+use cw_storage_plus::Map;
+use osmosis_std::types::cosmos::bank::v1beta1::{BankQuerier, Metadata};
+
+use crate::ContractError;
+
+/// Cache of bank denom metadata, so that metadata-dependent paths query the bank module
+/// at most once per denom until the cached metadata is invalidated.
+pub struct DenomMetadataCache<'a> {
+    /// Map of denom -> metadata
+    metadata: Map<'a, &'a str, Metadata>,
+}
+
+impl<'a> DenomMetadataCache<'a> {
+    pub const fn new(metadata_namespace: &'a str) -> Self {
+        Self {
+            metadata: Map::new(metadata_namespace),
+        }
+    }
+
+    /// Metadata of `denom`, queried from the bank module and cached on a cache miss.
+    /// Denoms without metadata are not cached, since it can be set later.
+    pub fn get_or_query(
+        &self,
+        storage: &mut dyn Storage,
+        querier: &QuerierWrapper,
+        denom: &str,
+    ) -> Result<Option<Metadata>, ContractError> {
+        if let Some(metadata) = self.metadata.may_load(storage, denom)? {
+            return Ok(Some(metadata));
+        }
+
+        let metadata = BankQuerier::new(querier)
+            .denom_metadata(denom.to_string())?
+            .metadata;
+
+        if let Some(metadata) = &metadata {
+            self.metadata.save(storage, denom, metadata)?;
+        }
+
+        Ok(metadata)
+    }
+
+    /// Drop cached metadata of `denom`, so that it is queried again on next access.
+    pub fn invalidate(&self, storage: &mut dyn Storage, denom: &str) -> StdResult<()> {
+        self.metadata.remove(storage, denom);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use cosmwasm_std::{
+        from_json,
+        testing::{MockQuerier, MockStorage},
+        to_json_binary, ContractResult, Empty, Querier, QuerierResult, QueryRequest, SystemResult,
+    };
+    use osmosis_std::types::cosmos::bank::v1beta1::{DenomUnit, QueryDenomMetadataResponse};
+
+    /// Querier that serves `metadata` for denom metadata queries and counts them.
+    struct MetadataQuerier {
+        metadata: Metadata,
+        query_count: Cell<u32>,
+    }
+
+    impl Querier for MetadataQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            match from_json::<QueryRequest<Empty>>(bin_request).unwrap() {
+                QueryRequest::Stargate { path, .. }
+                    if path == "/cosmos.bank.v1beta1.Query/DenomMetadata" =>
+                {
+                    self.query_count.set(self.query_count.get() + 1);
+                    SystemResult::Ok(ContractResult::Ok(
+                        to_json_binary(&QueryDenomMetadataResponse {
+                            metadata: Some(self.metadata.clone()),
+                        })
+                        .unwrap(),
+                    ))
+                }
+                _ => MockQuerier::<Empty>::new(&[]).raw_query(bin_request),
+            }
+        }
+    }
+
+    #[test]
+    fn test_denom_metadata_cache() {
+        let mut storage = MockStorage::new();
+        let cache = DenomMetadataCache::new("denom_metadata_cache");
+        let metadata = Metadata {
+            description: "Osmosis".to_string(),
+            denom_units: vec![
+                DenomUnit {
+                    denom: "uosmo".to_string(),
+                    exponent: 0,
+                    aliases: vec![],
+                },
+                DenomUnit {
+                    denom: "osmo".to_string(),
+                    exponent: 6,
+                    aliases: vec![],
+                },
+            ],
+            base: "uosmo".to_string(),
+            display: "osmo".to_string(),
+            name: "Osmosis".to_string(),
+            symbol: "OSMO".to_string(),
+            uri: String::new(),
+            uri_hash: String::new(),
+        };
+        let metadata_querier = MetadataQuerier {
+            metadata: metadata.clone(),
+            query_count: Cell::new(0),
+        };
+        let querier = QuerierWrapper::new(&metadata_querier);
+
+        // first access queries the bank module
+        let res = cache.get_or_query(&mut storage, &querier, "uosmo").unwrap();
+        assert_eq!(res, Some(metadata.clone()));
+        assert_eq!(metadata_querier.query_count.get(), 1);
+
+        // second access is served from the cache
+        let res = cache.get_or_query(&mut storage, &querier, "uosmo").unwrap();
+        assert_eq!(res, Some(metadata.clone()));
+        assert_eq!(metadata_querier.query_count.get(), 1);
+
+        // invalidation clears the cache, so the next access queries again
+        cache.invalidate(&mut storage, "uosmo").unwrap();
+        assert_eq!(cache.metadata.may_load(&storage, "uosmo").unwrap(), None);
+
+        let res = cache.get_or_query(&mut storage, &querier, "uosmo").unwrap();
+        assert_eq!(res, Some(metadata));
+        assert_eq!(metadata_querier.query_count.get(), 2);
+    }
+}
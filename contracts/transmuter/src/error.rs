@@ -1,6 +1,7 @@
 use cosmwasm_std::{
-    CheckedFromRatioError, CheckedMultiplyRatioError, Coin, ConversionOverflowError, Decimal,
-    DivideByZeroError, OverflowError, StdError, Timestamp, Uint128, Uint64,
+    CheckedFromRatioError, CheckedMultiplyFractionError, CheckedMultiplyRatioError, Coin,
+    ConversionOverflowError, Decimal, DivideByZeroError, Int256, OverflowError, StdError,
+    Timestamp, Uint128, Uint64,
 };
 use thiserror::Error;
 
@@ -20,6 +21,24 @@ pub enum ContractError {
     #[error("Funds must be empty")]
     Nonpayable {},
 
+    #[error("Swap would not improve pool balance: imbalance before: {imbalance_before}, imbalance after: {imbalance_after}")]
+    WouldWorsenBalance {
+        imbalance_before: Decimal,
+        imbalance_after: Decimal,
+    },
+
+    #[error("Unsupported query, supported versions: {supported_versions:?}")]
+    UnsupportedQuery { supported_versions: Vec<String> },
+
+    #[error("Batch size exceeds maximum: max: {max}, actual: {actual}")]
+    BatchTooLarge { max: usize, actual: usize },
+
+    #[error("Snapshot interval must be greater than zero")]
+    ZeroSnapshotInterval {},
+
+    #[error("Unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
+
     #[error("Funds must contain at least one token")]
     AtLeastSingleTokenExpected {},
 
@@ -29,6 +48,9 @@ pub enum ContractError {
     #[error("Subdenom must not contain extra parts (separated by '/'): {subdenom}")]
     SubDenomExtraPartsNotAllowed { subdenom: String },
 
+    #[error("Invalid alloyed asset subdenom: {subdenom}: {reason}")]
+    InvalidAlloyedSubdenom { subdenom: String, reason: String },
+
     #[error("Unable to join pool with denom: {denom}: expected one of: {expected_denom:?}")]
     InvalidJoinPoolDenom {
         denom: String,
@@ -47,6 +69,27 @@ pub enum ContractError {
     #[error("Not a corrupted asset denom: {denom}")]
     InvalidCorruptedAssetDenom { denom: String },
 
+    #[error("Asset group not found: {label}")]
+    AssetGroupNotFound { label: String },
+
+    #[error("Asset group {label} is paused, swaps increasing exposure to {denom} are not allowed")]
+    AssetGroupPaused { label: String, denom: String },
+
+    #[error("Asset group already exists: {label}")]
+    AssetGroupAlreadyExists { label: String },
+
+    #[error("Asset group {label} can't be both marked and unmarked as corrupted")]
+    ConflictingAssetGroupCorruption { label: String },
+
+    #[error("Denom alias not found: {alias}")]
+    DenomAliasNotFound { alias: String },
+
+    #[error("Denom alias is ambiguous, it is already registered or is a denom itself: {alias}")]
+    AmbiguousDenomAlias { alias: String },
+
+    #[error("Asset group can not be merged into itself: {label}")]
+    AssetGroupSelfMerge { label: String },
+
     #[error("Only corrupted asset with 0 amount can be removed")]
     InvalidCorruptedAssetRemoval {},
 
@@ -69,6 +112,40 @@ pub enum ContractError {
     #[error("Invalid swap fee: expected: {expected}, actual: {actual}")]
     InvalidSwapFee { expected: Decimal, actual: Decimal },
 
+    #[error("Swap fee must be less than 100%")]
+    SwapFeeMustBeLessThanOne {},
+
+    #[error("Corruption drain incentive must be less than 100%")]
+    CorruptionDrainIncentiveMustBeLessThanOne {},
+
+    #[error("Fee split must not exceed 100%")]
+    FeeSplitExceedsOne {},
+
+    #[error("Fee collector is not set")]
+    FeeCollectorNotSet {},
+
+    #[error("Swap value exceeds maximum swap value: max: {max}, attempted: {attempted}")]
+    SwapTooLarge { max: Uint128, attempted: Uint128 },
+
+    #[error(
+        "Total pool liquidity exceeds maximum total liquidity: max: {max}, attempted: {attempted}"
+    )]
+    TvlCapExceeded { max: Uint128, attempted: Uint128 },
+
+    #[error("Large swap cooldown is active, retry after: {retry_after}")]
+    SwapCooldownActive { retry_after: Timestamp },
+
+    #[error(
+        "Initial mint must exceed minimum locked shares: {min_locked_shares}, but got: {amount}"
+    )]
+    InsufficientInitialLiquidity {
+        min_locked_shares: Uint128,
+        amount: Uint128,
+    },
+
+    #[error("Mint rate limit exceeded, retry after: {retry_after}")]
+    MintRateLimitExceeded { retry_after: Timestamp },
+
     /// This error should never occur, but is here for completeness
     /// This will happens if and only if calculated token out and expected token out are not equal
     #[error("Invalid token out amount: expected: {expected}, actual: {actual}")]
@@ -102,8 +179,88 @@ pub enum ContractError {
     #[error("Duplicated pool asset denom: {denom}")]
     DuplicatedPoolAssetDenom { denom: String },
 
-    #[error("Pool asset not be share denom")]
-    ShareDenomNotAllowedAsPoolAsset {},
+    #[error("No limiter registered for {denom}, while limiter coverage is required")]
+    NoLimiterCoverage { denom: String },
+
+    #[error("Withdrawing fees would leave pool value {available} below backing required for outstanding alloyed asset {required}")]
+    InsufficientFeeReserve {
+        required: Uint128,
+        available: Uint128,
+    },
+
+    #[error("Auto corruption is not configured for {denom}")]
+    AutoCorruptionNotConfigured { denom: String },
+
+    #[error("Asset group count must not exceed {max}")]
+    TooManyAssetGroups { max: u32 },
+
+    #[error("Unsupported state dump version: expected: {expected}, actual: {actual}")]
+    UnsupportedStateDumpVersion { expected: u32, actual: u32 },
+
+    #[error(
+        "State can only be imported into a contract without liquidity, limiters or asset groups"
+    )]
+    StateNotFresh {},
+
+    #[error("State dump is incomplete, all of its pages must be merged before importing")]
+    IncompleteStateDump {},
+
+    #[error("Sender {sender} is not allowed")]
+    SenderNotAllowed { sender: String },
+
+    #[error("Insufficient allowance: required: {required}, available: {available}")]
+    InsufficientAllowance {
+        required: Uint128,
+        available: Uint128,
+    },
+
+    #[error("Reactivation time {reactivate_at} must be after block time {block_time}")]
+    ReactivationTimeNotInFuture {
+        reactivate_at: Timestamp,
+        block_time: Timestamp,
+    },
+
+    #[error("Basis points must be within 0..=10000: {bps}")]
+    InvalidBasisPoints { bps: u32 },
+
+    #[error("Swapping out the entire pool balance of {denom} is not allowed")]
+    FullDrainNotAllowed { denom: String },
+
+    #[error("Display decimals must be at most {max_decimals}: {decimals}")]
+    InvalidDisplayDecimals { decimals: u32, max_decimals: u32 },
+
+    #[error("Display decimals of {denom} can not be discovered from its bank metadata")]
+    DisplayDecimalsNotFound { denom: String },
+
+    #[error("Marking as corrupted would leave {active_assets} active assets, below the minimum of {min_active_assets}")]
+    TooFewActiveAssets {
+        active_assets: u32,
+        min_active_assets: u32,
+    },
+
+    #[error("Cannot use pool share denom {denom} as pool asset")]
+    CannotUsePoolShareDenom { denom: String },
+
+    #[error("Swap volume window must be positive and at most {max_window}ns, got {window}ns")]
+    InvalidSwapVolumeWindow { window: Uint64, max_window: Uint64 },
+
+    #[error("Target weights must sum to 1, got {sum}")]
+    InvalidTargetWeightsSum { sum: Decimal },
+
+    #[error("Token in {token_in} is too small, token out rounds to zero")]
+    OutputRoundsToZero { token_in: Coin },
+
+    #[error("Alloyed asset is disabled for this pool")]
+    AlloyedAssetDisabled {},
+
+    #[error("Alloyed asset is not registered: {denom}")]
+    AlloyedAssetNotRegistered { denom: String },
+
+    #[error("Partial fill is not supported for swaps of registered alloyed asset")]
+    PartialFillNotSupported {},
+
+    #[error("Requiring improved balance is not supported for swaps of registered alloyed asset")]
+    ImprovesBalanceNotSupported {},
 
     #[error("Token in must not have the same denom as token out: {denom}")]
     SameDenomNotAllowed { denom: String },
@@ -138,6 +295,24 @@ pub enum ContractError {
     #[error("Upper limit must not exceed 100%")]
     ExceedHundredPercentUpperLimit {},
 
+    #[error("Max deviation must be greater than zero")]
+    ZeroMaxDeviation {},
+
+    #[error("Max deviation must not exceed 100%")]
+    ExceedHundredPercentMaxDeviation {},
+
+    #[error("Temporary bound must be wider than the limiter's own bound: bound: {bound}, original bound: {original_bound}")]
+    TemporaryBoundNotWider {
+        bound: Decimal,
+        original_bound: Decimal,
+    },
+
+    #[error("Revert time must be in the future: revert at: {revert_at}, block time: {block_time}")]
+    RevertTimeNotInFuture {
+        revert_at: Timestamp,
+        block_time: Timestamp,
+    },
+
     #[error("Window must be evenly divisible by division size")]
     UnevenWindowDivision {},
 
@@ -173,12 +348,40 @@ pub enum ContractError {
         value: Decimal,
     },
 
+    #[error(
+        "Max deviation exceeded for `{denom}`, weight must be within {max_deviation} of {even_weight}, but the resulted weight is {value}"
+    )]
+    MaxDeviationExceeded {
+        denom: String,
+        even_weight: Decimal,
+        max_deviation: Decimal,
+        value: Decimal,
+    },
+
+    #[error(
+        "Net flow limit exceeded for `{denom}`, max net flow is {max_net_flow}, but the resulted net flow is {net_flow}"
+    )]
+    NetFlowLimitExceeded {
+        denom: String,
+        max_net_flow: Uint128,
+        net_flow: Int256,
+    },
+
+    #[error("Max net flow must be greater than zero")]
+    ZeroMaxNetFlow {},
+
+    #[error("Tightening factor must be within (0, 1]: {tightening_factor}")]
+    InvalidTighteningFactor { tightening_factor: Decimal },
+
     #[error("Modifying wrong limiter type: expected: {expected}, actual: {actual}")]
     WrongLimiterType { expected: String, actual: String },
 
     #[error("Normalization factor must be positive")]
     NormalizationFactorMustBePositive {},
 
+    #[error("Normalization factor of funded denom: {denom} can only be changed by force while the pool is inactive")]
+    FundedDenomNormalizationFactorChange { denom: String },
+
     #[error("Corrupted asset: {denom} must not increase in amount or weight")]
     CorruptedAssetRelativelyIncreased { denom: String },
 
@@ -194,6 +397,9 @@ pub enum ContractError {
     #[error("{0}")]
     CheckedMultiplyRatioError(#[from] CheckedMultiplyRatioError),
 
+    #[error("{0}")]
+    CheckedMultiplyFractionError(#[from] CheckedMultiplyFractionError),
+
     #[error("{0}")]
     ConversionOverflowError(#[from] ConversionOverflowError),
 
@@ -205,6 +411,137 @@ pub enum ContractError {
     Never,
 }
 
+/// Define stable numeric codes of `ContractError` variants,
+/// so that clients can map errors without matching error strings.
+/// The match is exhaustive, so every new variant must be given a code.
+macro_rules! error_codes {
+    ($($code:literal => $variant:ident,)*) => {
+        impl ContractError {
+            /// Stable numeric code of the error, see `ERROR_CATALOG`.
+            pub fn code(&self) -> u32 {
+                match self {
+                    $(ContractError::$variant { .. } => $code,)*
+                }
+            }
+        }
+
+        /// Code and name of every `ContractError` variant.
+        pub const ERROR_CATALOG: &[(u32, &str)] = &[$(($code, stringify!($variant)),)*];
+    };
+}
+
+// Codes must never be changed or reused, new variants take the next unused code.
+error_codes! {
+    1 => Std,
+    2 => VersionError,
+    3 => NonEmptyInputRequired,
+    4 => Nonpayable,
+    5 => WouldWorsenBalance,
+    6 => UnsupportedQuery,
+    7 => BatchTooLarge,
+    8 => ZeroSnapshotInterval,
+    9 => UnknownReplyId,
+    10 => AtLeastSingleTokenExpected,
+    11 => DenomHasNoSupply,
+    12 => SubDenomExtraPartsNotAllowed,
+    13 => InvalidAlloyedSubdenom,
+    14 => InvalidJoinPoolDenom,
+    15 => InvalidTransmuteDenom,
+    16 => InvalidPoolAssetDenom,
+    17 => InvalidCorruptedAssetDenom,
+    18 => AssetGroupNotFound,
+    19 => AssetGroupPaused,
+    20 => AssetGroupAlreadyExists,
+    21 => DenomAliasNotFound,
+    22 => AmbiguousDenomAlias,
+    23 => AssetGroupSelfMerge,
+    24 => InvalidCorruptedAssetRemoval,
+    25 => PoolAssetDenomCountOutOfRange,
+    26 => InsufficientPoolAsset,
+    27 => InsufficientShares,
+    28 => InvalidSwapFee,
+    29 => SwapFeeMustBeLessThanOne,
+    30 => CorruptionDrainIncentiveMustBeLessThanOne,
+    31 => FeeSplitExceedsOne,
+    32 => FeeCollectorNotSet,
+    33 => SwapTooLarge,
+    34 => TvlCapExceeded,
+    35 => SwapCooldownActive,
+    36 => InsufficientInitialLiquidity,
+    37 => MintRateLimitExceeded,
+    38 => InvalidTokenOutAmount,
+    39 => InvalidTokenInAmount,
+    40 => SpotPriceQueryFailed,
+    41 => InsufficientTokenOut,
+    42 => ExcessiveRequiredTokenIn,
+    43 => InactivePool,
+    44 => UnchangedActiveStatus,
+    45 => DuplicatedPoolAssetDenom,
+    46 => NoLimiterCoverage,
+    47 => InsufficientFeeReserve,
+    48 => AutoCorruptionNotConfigured,
+    49 => CannotUsePoolShareDenom,
+    50 => InvalidSwapVolumeWindow,
+    51 => InvalidTargetWeightsSum,
+    52 => OutputRoundsToZero,
+    53 => AlloyedAssetDisabled,
+    54 => AlloyedAssetNotRegistered,
+    55 => SameDenomNotAllowed,
+    56 => Unauthorized,
+    57 => InoperableAdminTransferringState,
+    58 => MaxLimiterCountPerDenomExceeded,
+    59 => EmptyLimiterNotAllowed,
+    60 => EmptyLimiterLabel,
+    61 => ZeroValueOperation,
+    62 => ZeroWindowSize,
+    63 => ZeroBoundaryOffset,
+    64 => ZeroUpperLimit,
+    65 => ExceedHundredPercentUpperLimit,
+    66 => ZeroMaxDeviation,
+    67 => ExceedHundredPercentMaxDeviation,
+    68 => TemporaryBoundNotWider,
+    69 => RevertTimeNotInFuture,
+    70 => UnevenWindowDivision,
+    71 => DivisionCountExceeded,
+    72 => UndefinedMovingAverage,
+    73 => NonMonotonicTime,
+    74 => UpdateAfterDivisionEnded,
+    75 => LimiterDoesNotExist,
+    76 => LimiterAlreadyExists,
+    77 => UpperLimitExceeded,
+    78 => MaxDeviationExceeded,
+    79 => WrongLimiterType,
+    80 => NormalizationFactorMustBePositive,
+    81 => FundedDenomNormalizationFactorChange,
+    82 => CorruptedAssetRelativelyIncreased,
+    83 => OverflowError,
+    84 => DivideByZeroError,
+    85 => CheckedFromRatioError,
+    86 => CheckedMultiplyRatioError,
+    87 => CheckedMultiplyFractionError,
+    88 => ConversionOverflowError,
+    89 => MathError,
+    90 => Never,
+    91 => TooManyAssetGroups,
+    92 => UnsupportedStateDumpVersion,
+    93 => StateNotFresh,
+    94 => SenderNotAllowed,
+    95 => InsufficientAllowance,
+    96 => ReactivationTimeNotInFuture,
+    97 => InvalidBasisPoints,
+    98 => FullDrainNotAllowed,
+    99 => InvalidDisplayDecimals,
+    100 => DisplayDecimalsNotFound,
+    101 => TooFewActiveAssets,
+    102 => NetFlowLimitExceeded,
+    103 => ZeroMaxNetFlow,
+    104 => ConflictingAssetGroupCorruption,
+    105 => InvalidTighteningFactor,
+    106 => IncompleteStateDump,
+    107 => PartialFillNotSupported,
+    108 => ImprovesBalanceNotSupported,
+}
+
 pub fn nonpayable(funds: &[Coin]) -> Result<(), ContractError> {
     if funds.is_empty() {
         Ok(())
@@ -222,3 +559,32 @@ pub fn non_empty_input_required<T>(field_name: &str, value: &[T]) -> Result<(),
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_error_codes() {
+        let codes: HashSet<_> = ERROR_CATALOG.iter().map(|(code, _)| code).collect();
+        let names: HashSet<_> = ERROR_CATALOG.iter().map(|(_, name)| name).collect();
+        assert_eq!(codes.len(), ERROR_CATALOG.len());
+        assert_eq!(names.len(), ERROR_CATALOG.len());
+
+        // codes are stable
+        assert_eq!(ContractError::Unauthorized {}.code(), 56);
+        assert_eq!(ContractError::Std(StdError::generic_err("error")).code(), 1);
+        assert_eq!(
+            ContractError::InsufficientShares {
+                required: Uint128::one(),
+                available: Uint128::zero(),
+            }
+            .code(),
+            27
+        );
+        assert_eq!(ContractError::Never.code(), 90);
+        assert!(ERROR_CATALOG.contains(&(56, "Unauthorized")));
+    }
+}
@@ -0,0 +1,555 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Addr, Decimal, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+
+use crate::ContractError;
+
+/// Default swap fee, swap fee is zero unless configured by admin.
+pub const DEFAULT_SWAP_FEE: Decimal = Decimal::zero();
+
+/// Cap of the dynamic swap fee, so that heavily unbalancing swaps are still charged less than 100%.
+pub const MAX_DYNAMIC_SWAP_FEE: Decimal = Decimal::percent(99);
+
+/// Swap fee that scales with how far the swap pushes denoms from their target weights,
+/// to discourage unbalancing swaps: `base + slope * resulting_deviation`,
+/// capped at [`MAX_DYNAMIC_SWAP_FEE`].
+#[cw_serde]
+pub struct DynamicFeeParams {
+    pub base: Decimal,
+    pub slope: Decimal,
+}
+
+impl DynamicFeeParams {
+    /// Swap fee of a swap resulting in `resulting_deviation` from target weights.
+    pub fn swap_fee(&self, resulting_deviation: Decimal) -> Decimal {
+        self.base
+            .saturating_add(self.slope.saturating_mul(resulting_deviation))
+            .min(MAX_DYNAMIC_SWAP_FEE)
+    }
+}
+
+/// Ensure `swap_fee` is less than one, so that a swap never takes all of the charged token as fee.
+pub fn ensure_swap_fee_less_than_one(swap_fee: Decimal) -> Result<(), ContractError> {
+    ensure!(
+        swap_fee < Decimal::one(),
+        ContractError::SwapFeeMustBeLessThanOne {}
+    );
+
+    Ok(())
+}
+
+/// Ensure `fee_split` is at most one and, unless it is zero, that there is a fee collector to send it to.
+pub fn ensure_valid_fee_split(
+    fee_split: Decimal,
+    has_fee_collector: bool,
+) -> Result<(), ContractError> {
+    ensure!(
+        fee_split <= Decimal::one(),
+        ContractError::FeeSplitExceedsOne {}
+    );
+
+    // fee can only be sent out if there is a fee collector to send to
+    ensure!(
+        fee_split.is_zero() || has_fee_collector,
+        ContractError::FeeCollectorNotSet {}
+    );
+
+    Ok(())
+}
+
+/// Side of the swap the fee is charged on.
+#[cw_serde]
+#[derive(Default, Copy)]
+pub enum FeeSide {
+    /// Fee is deducted from the token in before swapping.
+    #[default]
+    In,
+    /// Fee is deducted from the swapped token out before sending it to the user.
+    /// Only applies to exact in swaps between pool assets,
+    /// swaps involving the alloyed asset are always charged on the token in.
+    Out,
+}
+
+/// Swap fee configuration.
+///
+/// Swap fee is charged on the token in, unless configured otherwise by [`FeeSide`]. The `fee_split` portion of the fee
+/// is sent to the fee collector, while the rest is retained in the pool.
+///
+/// Riskier denoms can have higher per-denom swap fee, the effective swap fee
+/// of a swap is the max of the swap fee and the per-denom swap fees of all denoms involved.
+///
+/// If [`DynamicFeeParams`] are set, the dynamic swap fee replaces the swap fee,
+/// while per-denom swap fees still apply as floors.
+pub struct Fee<'a> {
+    swap_fee: Item<'a, Decimal>,
+    fee_collector: Item<'a, Addr>,
+    fee_split: Item<'a, Decimal>,
+    denom_swap_fees: Map<'a, &'a str, Decimal>,
+    fee_side: Item<'a, FeeSide>,
+    dynamic_fee_params: Item<'a, DynamicFeeParams>,
+}
+
+impl<'a> Fee<'a> {
+    pub const fn new(
+        swap_fee_namespace: &'a str,
+        fee_collector_namespace: &'a str,
+        fee_split_namespace: &'a str,
+        denom_swap_fees_namespace: &'a str,
+        fee_side_namespace: &'a str,
+        dynamic_fee_params_namespace: &'a str,
+    ) -> Self {
+        Self {
+            swap_fee: Item::new(swap_fee_namespace),
+            fee_collector: Item::new(fee_collector_namespace),
+            fee_split: Item::new(fee_split_namespace),
+            denom_swap_fees: Map::new(denom_swap_fees_namespace),
+            fee_side: Item::new(fee_side_namespace),
+            dynamic_fee_params: Item::new(dynamic_fee_params_namespace),
+        }
+    }
+
+    /// get the swap fee, defaults to [`DEFAULT_SWAP_FEE`] if not set
+    pub fn get_swap_fee(&self, storage: &dyn Storage) -> StdResult<Decimal> {
+        Ok(self.swap_fee.may_load(storage)?.unwrap_or(DEFAULT_SWAP_FEE))
+    }
+
+    pub fn set_swap_fee(
+        &self,
+        storage: &mut dyn Storage,
+        swap_fee: Decimal,
+    ) -> Result<(), ContractError> {
+        ensure_swap_fee_less_than_one(swap_fee)?;
+
+        self.swap_fee.save(storage, &swap_fee).map_err(Into::into)
+    }
+
+    /// get the fee collector, returns None if not set
+    pub fn get_fee_collector(&self, storage: &dyn Storage) -> StdResult<Option<Addr>> {
+        self.fee_collector.may_load(storage)
+    }
+
+    pub fn set_fee_collector(&self, storage: &mut dyn Storage, address: Addr) -> StdResult<()> {
+        self.fee_collector.save(storage, &address)
+    }
+
+    /// get the fraction of the swap fee that goes to the fee collector, defaults to zero
+    pub fn get_fee_split(&self, storage: &dyn Storage) -> StdResult<Decimal> {
+        Ok(self.fee_split.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn set_fee_split(
+        &self,
+        storage: &mut dyn Storage,
+        fee_split: Decimal,
+    ) -> Result<(), ContractError> {
+        ensure_valid_fee_split(fee_split, self.fee_collector.may_load(storage)?.is_some())?;
+
+        self.fee_split.save(storage, &fee_split).map_err(Into::into)
+    }
+
+    /// get the side of the swap the fee is charged on, defaults to [`FeeSide::In`]
+    pub fn get_fee_side(&self, storage: &dyn Storage) -> StdResult<FeeSide> {
+        Ok(self.fee_side.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn set_fee_side(&self, storage: &mut dyn Storage, fee_side: FeeSide) -> StdResult<()> {
+        self.fee_side.save(storage, &fee_side)
+    }
+
+    /// get the dynamic fee params, returns None if dynamic fee is disabled
+    pub fn get_dynamic_fee_params(
+        &self,
+        storage: &dyn Storage,
+    ) -> StdResult<Option<DynamicFeeParams>> {
+        self.dynamic_fee_params.may_load(storage)
+    }
+
+    /// set the dynamic fee params, `None` disables dynamic fee
+    pub fn set_dynamic_fee_params(
+        &self,
+        storage: &mut dyn Storage,
+        dynamic_fee_params: Option<DynamicFeeParams>,
+    ) -> Result<(), ContractError> {
+        match dynamic_fee_params {
+            Some(dynamic_fee_params) => {
+                ensure_swap_fee_less_than_one(dynamic_fee_params.base)?;
+
+                self.dynamic_fee_params.save(storage, &dynamic_fee_params)?;
+            }
+            None => self.dynamic_fee_params.remove(storage),
+        }
+
+        Ok(())
+    }
+
+    /// get the per-denom swap fee, returns None if not set
+    pub fn get_denom_swap_fee(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+    ) -> StdResult<Option<Decimal>> {
+        self.denom_swap_fees.may_load(storage, denom)
+    }
+
+    /// set the per-denom swap fee, `None` removes it
+    pub fn set_denom_swap_fee(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        swap_fee: Option<Decimal>,
+    ) -> Result<(), ContractError> {
+        match swap_fee {
+            Some(swap_fee) => {
+                ensure_swap_fee_less_than_one(swap_fee)?;
+
+                self.denom_swap_fees.save(storage, denom, &swap_fee)?;
+            }
+            None => self.denom_swap_fees.remove(storage, denom),
+        }
+
+        Ok(())
+    }
+
+    /// list all per-denom swap fees
+    pub fn list_denom_swap_fees(&self, storage: &dyn Storage) -> StdResult<Vec<(String, Decimal)>> {
+        self.denom_swap_fees
+            .range(storage, None, None, Order::Ascending)
+            .collect()
+    }
+
+    /// effective swap fee for a swap involving `denoms`, which is the max of
+    /// the swap fee and the per-denom swap fees of `denoms`.
+    /// In dynamic fee mode, the swap fee is scaled by `resulting_deviation`
+    /// from target weights, which is ignored otherwise.
+    pub fn get_effective_swap_fee(
+        &self,
+        storage: &dyn Storage,
+        denoms: &[&str],
+        resulting_deviation: Decimal,
+    ) -> StdResult<Decimal> {
+        let mut swap_fee = match self.get_dynamic_fee_params(storage)? {
+            Some(dynamic_fee_params) => dynamic_fee_params.swap_fee(resulting_deviation),
+            None => self.get_swap_fee(storage)?,
+        };
+
+        for denom in denoms {
+            if let Some(denom_swap_fee) = self.get_denom_swap_fee(storage, denom)? {
+                swap_fee = swap_fee.max(denom_swap_fee);
+            }
+        }
+
+        Ok(swap_fee)
+    }
+}
+
+/// Swap fee charged on the token in amount.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeCharge {
+    /// amount after fee deduction, this is the token in amount used for swap calculation,
+    /// or the token out amount sent to the user if fee is charged on token out
+    pub net_amount: Uint128,
+    /// fee amount sent to the fee collector
+    pub collected_amount: Uint128,
+    /// fee amount retained in the pool
+    pub retained_amount: Uint128,
+}
+
+impl FeeCharge {
+    /// Charge fee from the gross token in amount (exact in).
+    pub fn from_gross_amount(
+        gross_amount: Uint128,
+        swap_fee: Decimal,
+        fee_split: Decimal,
+    ) -> Result<Self, ContractError> {
+        let fee_amount = fee_amount_from_gross(gross_amount, swap_fee)?;
+        let net_amount = gross_amount.checked_sub(fee_amount)?;
+        let (collected_amount, retained_amount) = split_fee_amount(fee_amount, fee_split)?;
+
+        Ok(Self {
+            net_amount,
+            collected_amount,
+            retained_amount,
+        })
+    }
+
+    /// Charge fee on top of the net token in amount required by the swap (exact out).
+    pub fn from_net_amount(
+        net_amount: Uint128,
+        swap_fee: Decimal,
+        fee_split: Decimal,
+    ) -> Result<Self, ContractError> {
+        let gross_amount = gross_amount_from_net(net_amount, swap_fee)?;
+        let fee_amount = gross_amount.checked_sub(net_amount)?;
+        let (collected_amount, retained_amount) = split_fee_amount(fee_amount, fee_split)?;
+
+        Ok(Self {
+            net_amount,
+            collected_amount,
+            retained_amount,
+        })
+    }
+
+    /// Total token in amount, including fee.
+    pub fn gross_amount(&self) -> Result<Uint128, ContractError> {
+        Ok(self
+            .net_amount
+            .checked_add(self.collected_amount)?
+            .checked_add(self.retained_amount)?)
+    }
+
+    /// Token in amount that goes into the pool, which is everything except the collected fee.
+    pub fn pool_amount(&self) -> Result<Uint128, ContractError> {
+        Ok(self.net_amount.checked_add(self.retained_amount)?)
+    }
+}
+
+/// Fee amount charged from the token in amount, rounded up in favor of the pool.
+fn fee_amount_from_gross(
+    gross_amount: Uint128,
+    swap_fee: Decimal,
+) -> Result<Uint128, ContractError> {
+    Ok(gross_amount.checked_mul_ceil(swap_fee)?)
+}
+
+/// Gross token in amount required so that `net_amount` remains after the fee is deducted,
+/// rounded up in favor of the pool.
+///
+/// gross_amount = net_amount / (1 - swap_fee)
+fn gross_amount_from_net(net_amount: Uint128, swap_fee: Decimal) -> Result<Uint128, ContractError> {
+    if swap_fee.is_zero() {
+        return Ok(net_amount);
+    }
+
+    let net_ratio = Decimal::one().checked_sub(swap_fee)?;
+    Ok(net_amount.checked_div_ceil(net_ratio)?)
+}
+
+/// Split fee amount into (collected, retained), where collected is sent to the fee collector
+/// and retained stays in the pool. Collected amount is rounded down in favor of the pool.
+fn split_fee_amount(
+    fee_amount: Uint128,
+    fee_split: Decimal,
+) -> Result<(Uint128, Uint128), ContractError> {
+    let collected = fee_amount.checked_mul_floor(fee_split)?;
+    let retained = fee_amount.checked_sub(collected)?;
+
+    Ok((collected, retained))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1000u128, Decimal::zero(), 0u128)]
+    #[case(1000u128, Decimal::percent(1), 10u128)]
+    #[case(1001u128, Decimal::percent(1), 11u128)]
+    #[case(1u128, Decimal::permille(1), 1u128)]
+    fn test_fee_amount_from_gross(
+        #[case] gross_amount: u128,
+        #[case] swap_fee: Decimal,
+        #[case] expected: u128,
+    ) {
+        assert_eq!(
+            fee_amount_from_gross(Uint128::new(gross_amount), swap_fee).unwrap(),
+            Uint128::new(expected)
+        );
+    }
+
+    #[rstest]
+    #[case(990u128, Decimal::zero(), 990u128)]
+    #[case(990u128, Decimal::percent(1), 1000u128)]
+    #[case(991u128, Decimal::percent(1), 1002u128)]
+    fn test_gross_amount_from_net(
+        #[case] net_amount: u128,
+        #[case] swap_fee: Decimal,
+        #[case] expected: u128,
+    ) {
+        let gross_amount = gross_amount_from_net(Uint128::new(net_amount), swap_fee).unwrap();
+        assert_eq!(gross_amount, Uint128::new(expected));
+
+        // net amount after fee deduction must cover the required net amount
+        let fee_amount = fee_amount_from_gross(gross_amount, swap_fee).unwrap();
+        assert!(gross_amount - fee_amount >= Uint128::new(net_amount));
+    }
+
+    #[rstest]
+    #[case(Decimal::zero(), (0u128, 100u128))]
+    #[case(Decimal::percent(50), (50u128, 50u128))]
+    #[case(Decimal::percent(100), (100u128, 0u128))]
+    #[case(Decimal::percent(33), (33u128, 67u128))]
+    fn test_split_fee_amount(#[case] fee_split: Decimal, #[case] expected: (u128, u128)) {
+        assert_eq!(
+            split_fee_amount(Uint128::new(100), fee_split).unwrap(),
+            (Uint128::new(expected.0), Uint128::new(expected.1))
+        );
+    }
+
+    #[rstest]
+    #[case(Decimal::zero(), (0u128, 10u128))]
+    #[case(Decimal::percent(50), (5u128, 5u128))]
+    #[case(Decimal::percent(100), (10u128, 0u128))]
+    fn test_fee_charge(#[case] fee_split: Decimal, #[case] expected: (u128, u128)) {
+        let swap_fee = Decimal::percent(1);
+
+        let charge = FeeCharge::from_gross_amount(Uint128::new(1000), swap_fee, fee_split).unwrap();
+        assert_eq!(
+            charge,
+            FeeCharge {
+                net_amount: Uint128::new(990),
+                collected_amount: Uint128::new(expected.0),
+                retained_amount: Uint128::new(expected.1),
+            }
+        );
+        assert_eq!(charge.gross_amount().unwrap(), Uint128::new(1000));
+        assert_eq!(
+            charge.pool_amount().unwrap(),
+            Uint128::new(1000 - expected.0)
+        );
+
+        let charge = FeeCharge::from_net_amount(Uint128::new(990), swap_fee, fee_split).unwrap();
+        assert_eq!(
+            charge,
+            FeeCharge {
+                net_amount: Uint128::new(990),
+                collected_amount: Uint128::new(expected.0),
+                retained_amount: Uint128::new(expected.1),
+            }
+        );
+    }
+
+    #[test]
+    fn test_fee_config() {
+        let mut deps = mock_dependencies();
+        let fee = Fee::new(
+            "swap_fee",
+            "fee_collector",
+            "fee_split",
+            "denom_swap_fees",
+            "fee_side",
+            "dynamic_fee_params",
+        );
+
+        // defaults
+        assert_eq!(fee.get_swap_fee(&deps.storage).unwrap(), DEFAULT_SWAP_FEE);
+        assert_eq!(fee.get_fee_collector(&deps.storage).unwrap(), None);
+        assert_eq!(fee.get_fee_split(&deps.storage).unwrap(), Decimal::zero());
+        assert_eq!(fee.get_fee_side(&deps.storage).unwrap(), FeeSide::In);
+
+        // swap fee must be less than one
+        assert_eq!(
+            fee.set_swap_fee(&mut deps.storage, Decimal::one())
+                .unwrap_err(),
+            ContractError::SwapFeeMustBeLessThanOne {}
+        );
+
+        // fee split can't be set without fee collector
+        assert_eq!(
+            fee.set_fee_split(&mut deps.storage, Decimal::percent(50))
+                .unwrap_err(),
+            ContractError::FeeCollectorNotSet {}
+        );
+
+        fee.set_fee_collector(&mut deps.storage, Addr::unchecked("collector"))
+            .unwrap();
+
+        // fee split must not exceed one
+        assert_eq!(
+            fee.set_fee_split(&mut deps.storage, Decimal::percent(101))
+                .unwrap_err(),
+            ContractError::FeeSplitExceedsOne {}
+        );
+
+        fee.set_fee_split(&mut deps.storage, Decimal::one())
+            .unwrap();
+        assert_eq!(fee.get_fee_split(&deps.storage).unwrap(), Decimal::one());
+
+        fee.set_fee_side(&mut deps.storage, FeeSide::Out).unwrap();
+        assert_eq!(fee.get_fee_side(&deps.storage).unwrap(), FeeSide::Out);
+    }
+
+    #[rstest]
+    #[case(Decimal::zero(), Decimal::permille(5))]
+    #[case(Decimal::percent(10), Decimal::permille(15))]
+    #[case(Decimal::percent(50), Decimal::permille(55))]
+    // capped below 100%
+    #[case(Decimal::MAX, MAX_DYNAMIC_SWAP_FEE)]
+    fn test_dynamic_swap_fee(#[case] resulting_deviation: Decimal, #[case] expected: Decimal) {
+        let params = DynamicFeeParams {
+            base: Decimal::permille(5),
+            slope: Decimal::percent(10),
+        };
+
+        assert_eq!(params.swap_fee(resulting_deviation), expected);
+    }
+
+    #[rstest]
+    // module fee dominates
+    #[case(Decimal::percent(3), Decimal::percent(3))]
+    // in denom's higher fee dominates
+    #[case(Decimal::percent(1), Decimal::percent(2))]
+    fn test_effective_swap_fee(#[case] swap_fee: Decimal, #[case] expected: Decimal) {
+        let mut deps = mock_dependencies();
+        let fee = Fee::new(
+            "swap_fee",
+            "fee_collector",
+            "fee_split",
+            "denom_swap_fees",
+            "fee_side",
+            "dynamic_fee_params",
+        );
+
+        fee.set_swap_fee(&mut deps.storage, swap_fee).unwrap();
+        fee.set_denom_swap_fee(&mut deps.storage, "denom_in", Some(Decimal::percent(2)))
+            .unwrap();
+        fee.set_denom_swap_fee(&mut deps.storage, "denom_out", Some(Decimal::percent(1)))
+            .unwrap();
+
+        assert_eq!(
+            fee.get_effective_swap_fee(&deps.storage, &["denom_in", "denom_out"], Decimal::zero())
+                .unwrap(),
+            expected
+        );
+
+        // denom without per-denom fee only pays the module fee
+        assert_eq!(
+            fee.get_effective_swap_fee(&deps.storage, &["denom_other"], Decimal::zero())
+                .unwrap(),
+            swap_fee
+        );
+
+        // per-denom swap fee must be less than one
+        assert_eq!(
+            fee.set_denom_swap_fee(&mut deps.storage, "denom_in", Some(Decimal::one()))
+                .unwrap_err(),
+            ContractError::SwapFeeMustBeLessThanOne {}
+        );
+
+        // dynamic fee replaces the module fee, scaled by the resulting deviation
+        fee.set_dynamic_fee_params(
+            &mut deps.storage,
+            Some(DynamicFeeParams {
+                base: Decimal::permille(5),
+                slope: Decimal::percent(10),
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            fee.get_effective_swap_fee(&deps.storage, &["denom_other"], Decimal::percent(20))
+                .unwrap(),
+            Decimal::permille(25)
+        );
+        fee.set_dynamic_fee_params(&mut deps.storage, None).unwrap();
+
+        // removing per-denom swap fee
+        fee.set_denom_swap_fee(&mut deps.storage, "denom_in", None)
+            .unwrap();
+        assert_eq!(
+            fee.get_effective_swap_fee(&deps.storage, &["denom_in", "denom_out"], Decimal::zero())
+                .unwrap(),
+            swap_fee.max(Decimal::percent(1))
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Order, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Bound, Item, Map};
+
+/// Maximum number of governance actions kept in the log,
+/// older actions are pruned as new ones are recorded.
+pub const MAX_GOVERNANCE_LOG_SIZE: u64 = 1000;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+#[cw_serde]
+pub struct GovernanceAction {
+    pub id: u64,
+    /// name of the executed action
+    pub action: String,
+    /// address that executed the action, `None` if executed via sudo or migration
+    pub actor: Option<Addr>,
+    pub time: Timestamp,
+}
+
+/// Append-only log of admin, moderator and sudo actions for auditability.
+pub struct GovernanceLog<'a> {
+    next_id: Item<'a, u64>,
+    actions: Map<'a, u64, GovernanceAction>,
+}
+
+impl<'a> GovernanceLog<'a> {
+    pub const fn new(next_id_namespace: &'a str, actions_namespace: &'a str) -> Self {
+        Self {
+            next_id: Item::new(next_id_namespace),
+            actions: Map::new(actions_namespace),
+        }
+    }
+
+    /// Record an action, pruning the oldest one if the log exceeds [`MAX_GOVERNANCE_LOG_SIZE`].
+    pub fn record(
+        &self,
+        storage: &mut dyn Storage,
+        action: &str,
+        actor: Option<&Addr>,
+        time: Timestamp,
+    ) -> StdResult<()> {
+        let id = self.next_id.may_load(storage)?.unwrap_or_default();
+
+        self.actions.save(
+            storage,
+            id,
+            &GovernanceAction {
+                id,
+                action: action.to_string(),
+                actor: actor.cloned(),
+                time,
+            },
+        )?;
+
+        if let Some(pruned_id) = id.checked_sub(MAX_GOVERNANCE_LOG_SIZE) {
+            self.actions.remove(storage, pruned_id);
+        }
+
+        self.next_id.save(storage, &(id + 1))
+    }
+
+    /// List recorded actions in the order they were recorded.
+    pub fn list(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<GovernanceAction>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        self.actions
+            .range(
+                storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| item.map(|(_, action)| action))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_governance_log_is_capped() {
+        let mut deps = mock_dependencies();
+        let log = GovernanceLog::new("next_id", "actions");
+        let admin = Addr::unchecked("admin");
+
+        for _ in 0..MAX_GOVERNANCE_LOG_SIZE + 2 {
+            log.record(
+                &mut deps.storage,
+                "set_swap_fee",
+                Some(&admin),
+                Timestamp::from_seconds(1),
+            )
+            .unwrap();
+        }
+
+        // oldest actions are pruned
+        let actions = log.list(&deps.storage, None, Some(2)).unwrap();
+        assert_eq!(actions.iter().map(|a| a.id).collect::<Vec<_>>(), vec![2, 3]);
+
+        let actions = log
+            .list(&deps.storage, Some(MAX_GOVERNANCE_LOG_SIZE), None)
+            .unwrap();
+        assert_eq!(
+            actions,
+            vec![GovernanceAction {
+                id: MAX_GOVERNANCE_LOG_SIZE + 1,
+                action: "set_swap_fee".to_string(),
+                actor: Some(admin),
+                time: Timestamp::from_seconds(1),
+            }]
+        );
+    }
+}
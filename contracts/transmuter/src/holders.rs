@@ -0,0 +1,72 @@
+use cosmwasm_std::{Addr, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
+
+/// Best-effort set of alloyed asset holders.
+///
+/// Addresses are added when alloyed asset is minted to them and removed when they
+/// burn their whole balance through the contract. Transfers via the bank module
+/// are not observed, so the count is only an approximation.
+pub struct AlloyedHolders<'a> {
+    count: Item<'a, u64>,
+    holders: Map<'a, &'a Addr, ()>,
+}
+
+impl<'a> AlloyedHolders<'a> {
+    pub const fn new(count_namespace: &'a str, holders_namespace: &'a str) -> Self {
+        Self {
+            count: Item::new(count_namespace),
+            holders: Map::new(holders_namespace),
+        }
+    }
+
+    /// Record `holder` as holding alloyed asset, no-op if already recorded.
+    pub fn add(&self, storage: &mut dyn Storage, holder: &Addr) -> StdResult<()> {
+        if self.holders.has(storage, holder) {
+            return Ok(());
+        }
+
+        self.holders.save(storage, holder, &())?;
+        let count = self.count(storage)? + 1;
+        self.count.save(storage, &count)
+    }
+
+    /// Remove `holder` from the holder set, no-op if not recorded.
+    pub fn remove(&self, storage: &mut dyn Storage, holder: &Addr) -> StdResult<()> {
+        if !self.holders.has(storage, holder) {
+            return Ok(());
+        }
+
+        self.holders.remove(storage, holder);
+        let count = self.count(storage)?.saturating_sub(1);
+        self.count.save(storage, &count)
+    }
+
+    pub fn count(&self, storage: &dyn Storage) -> StdResult<u64> {
+        Ok(self.count.may_load(storage)?.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_holder_count() {
+        let mut deps = mock_dependencies();
+        let holders = AlloyedHolders::new("count", "holders");
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+
+        assert_eq!(holders.count(&deps.storage).unwrap(), 0);
+
+        holders.add(&mut deps.storage, &alice).unwrap();
+        holders.add(&mut deps.storage, &alice).unwrap();
+        holders.add(&mut deps.storage, &bob).unwrap();
+        assert_eq!(holders.count(&deps.storage).unwrap(), 2);
+
+        holders.remove(&mut deps.storage, &alice).unwrap();
+        holders.remove(&mut deps.storage, &alice).unwrap();
+        assert_eq!(holders.count(&deps.storage).unwrap(), 1);
+    }
+}
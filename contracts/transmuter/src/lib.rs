@@ -1,14 +1,27 @@
+mod allowance;
 mod alloyed_asset;
+mod alloyed_registry;
 mod asset;
+mod asset_group;
 pub mod contract;
+mod corruptable;
+mod denom_metadata;
 mod error;
+mod fee;
+mod governance_log;
+mod holders;
 mod limiter;
 mod math;
 mod migrations;
+mod oracle;
 mod role;
+mod rounding_audit;
+mod snapshot;
+mod state_dump;
 mod sudo;
 mod swap;
 mod transmuter_pool;
+mod volume;
 pub use crate::error::ContractError;
 
 #[cfg(test)]
@@ -16,37 +29,18 @@ mod test;
 
 #[cfg(not(feature = "library"))]
 mod entry_points {
-    use cosmwasm_std::{
-        ensure, entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
-    };
+    use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response};
 
-    use crate::contract::sv::{ContractExecMsg, ContractQueryMsg, ExecMsg, InstantiateMsg};
-    use crate::contract::Transmuter;
+    use crate::contract::sv::{
+        query_messages, ContractExecMsg, ContractQueryMsg, ExecMsg, InstantiateMsg,
+    };
+    use crate::contract::{PausableOperation, Transmuter, CONTRACT_VERSION};
     use crate::error::ContractError;
     use crate::migrations;
     use crate::sudo::SudoMsg;
 
     const CONTRACT: Transmuter = Transmuter::new();
 
-    macro_rules! ensure_active_status {
-        ($msg:expr, $deps:expr, $env:expr, except: $pattern:pat) => {
-            match $msg {
-                $pattern => (),
-                _ => {
-                    ensure!(
-                        CONTRACT
-                            .is_active(sylvia::types::QueryCtx {
-                                deps: $deps.as_ref(),
-                                env: $env.clone()
-                            })?
-                            .is_active,
-                        ContractError::InactivePool {}
-                    );
-                }
-            }
-        };
-    }
-
     #[entry_point]
     pub fn instantiate(
         deps: DepsMut,
@@ -69,29 +63,98 @@ mod entry_points {
         info: MessageInfo,
         msg: ContractExecMsg,
     ) -> Result<Response, ContractError> {
-        ensure_active_status!(
-            msg,
-            deps,
-            env,
-            except: ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus { .. })
-        );
+        match msg {
+            // normalization factor of funded denom can only be forced while the pool is inactive
+            ContractExecMsg::Transmuter(
+                ExecMsg::SetActiveStatus { .. } | ExecMsg::SetNormalizationFactor { .. },
+            ) => (),
+            ContractExecMsg::Transmuter(
+                ExecMsg::JoinPool { .. }
+                | ExecMsg::ExitPool { .. }
+                | ExecMsg::ExitPoolFrom { .. }
+                | ExecMsg::ExitToSingleDenom { .. }
+                | ExecMsg::JoinAlloyedAsset { .. }
+                | ExecMsg::ExitAlloyedAsset { .. },
+            ) => CONTRACT.ensure_not_paused(
+                deps.storage,
+                env.block.time,
+                PausableOperation::JoinOrExit,
+            )?,
+            _ => CONTRACT.ensure_not_paused(
+                deps.storage,
+                env.block.time,
+                PausableOperation::Other,
+            )?,
+        }
 
         msg.dispatch(&CONTRACT, (deps, env, info))
     }
 
+    /// Query message as received by the query entry point.
+    /// Any message whose name is not a query of this contract, e.g. a query added
+    /// in a newer contract version, is captured as `Unsupported`.
+    pub enum IncomingQueryMsg {
+        Supported(ContractQueryMsg),
+        Unsupported,
+    }
+
+    impl<'de> serde::Deserialize<'de> for IncomingQueryMsg {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            use serde::de::Error;
+            use sylvia::serde_value::Value;
+
+            let val = Value::deserialize(deserializer)?;
+
+            // known queries with malformed fields keep their deserialization error
+            let is_known = match &val {
+                Value::Map(map) => map.keys().any(|name| {
+                    matches!(name, Value::String(name) if query_messages().contains(&name.as_str()))
+                }),
+                _ => true,
+            };
+
+            if is_known {
+                val.deserialize_into()
+                    .map(IncomingQueryMsg::Supported)
+                    .map_err(D::Error::custom)
+            } else {
+                Ok(IncomingQueryMsg::Unsupported)
+            }
+        }
+    }
+
+    /// Reports unsupported query messages as [`ContractError::UnsupportedQuery`]
+    /// instead of an opaque deserialization error.
     #[entry_point]
-    pub fn query(deps: Deps, env: Env, msg: ContractQueryMsg) -> Result<Binary, ContractError> {
+    pub fn query(deps: Deps, env: Env, msg: IncomingQueryMsg) -> Result<Binary, ContractError> {
+        match msg {
+            IncomingQueryMsg::Supported(msg) => dispatch_query(deps, env, msg),
+            IncomingQueryMsg::Unsupported => Err(ContractError::UnsupportedQuery {
+                supported_versions: vec![CONTRACT_VERSION.to_string()],
+            }),
+        }
+    }
+
+    /// Dispatch parsed query message.
+    pub fn dispatch_query(
+        deps: Deps,
+        env: Env,
+        msg: ContractQueryMsg,
+    ) -> Result<Binary, ContractError> {
         msg.dispatch(&CONTRACT, (deps, env))
     }
 
     #[entry_point]
     pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
-        ensure_active_status!(
-            msg,
-            deps,
-            env,
-            except: SudoMsg::SetActive { .. }
-        );
+        match msg {
+            SudoMsg::SetActive { .. } => (),
+            SudoMsg::SwapExactAmountIn { .. } | SudoMsg::SwapExactAmountOut { .. } => {
+                CONTRACT.ensure_not_paused(deps.storage, env.block.time, PausableOperation::Swap)?
+            }
+        }
 
         msg.dispatch(&CONTRACT, (deps, env))
     }
@@ -99,10 +162,48 @@ mod entry_points {
     #[entry_point]
     pub fn migrate(
         deps: DepsMut,
-        _env: Env,
+        env: Env,
         _msg: migrations::v3_1_0::MigrateMsg,
     ) -> Result<Response, ContractError> {
-        migrations::v3_1_0::execute_migration(deps)
+        migrations::v3_1_0::execute_migration(deps, env)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::contract::sv::QueryMsg;
+        use cosmwasm_std::{
+            from_json,
+            testing::{mock_dependencies, mock_env},
+        };
+
+        #[test]
+        fn test_unsupported_query() {
+            let deps = mock_dependencies();
+
+            let msg: IncomingQueryMsg =
+                from_json(br#"{"query_from_the_future":{"denom":"uosmo"}}"#).unwrap();
+            let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::UnsupportedQuery {
+                    supported_versions: vec![CONTRACT_VERSION.to_string()],
+                }
+            );
+
+            // known query with malformed fields keeps its deserialization error
+            let err = from_json::<IncomingQueryMsg>(br#"{"get_shares":{"address":1}}"#)
+                .err()
+                .unwrap();
+            assert!(err.to_string().contains("Expected a string"), "{}", err);
+
+            // supported query is dispatched as usual
+            let msg: IncomingQueryMsg = from_json(br#"{"get_swap_fee":{}}"#).unwrap();
+            assert!(matches!(
+                msg,
+                IncomingQueryMsg::Supported(ContractQueryMsg::Transmuter(QueryMsg::GetSwapFee {}))
+            ));
+        }
     }
 }
 
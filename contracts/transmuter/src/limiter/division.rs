@@ -387,7 +387,7 @@ impl Division {
         let block_time = Uint64::from(block_time.nanos());
 
         let latest_value_persist_until = block_time.min(ended_at);
-        elapsed_time(self.updated_at.nanos(), latest_value_persist_until).map_err(Into::into)
+        elapsed_time(self.updated_at.nanos(), latest_value_persist_until)
     }
 
     fn latest_value_integral(
@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{ensure, Decimal, StdError, Storage, Timestamp, Uint64};
-use cw_storage_plus::Map;
+use cosmwasm_std::{
+    ensure, Decimal, Int256, Order, StdError, StdResult, Storage, Timestamp, Uint128, Uint256,
+    Uint64,
+};
+use cw_storage_plus::{Item, Map};
 
 use crate::ContractError;
 
@@ -18,6 +21,12 @@ const MAX_DIVISION_COUNT: Uint64 = Uint64::new(10u64);
 /// causing high gas usage when checking the limit, cleaning up divisions, etc.
 const MAX_LIMITER_COUNT_PER_DENOM: Uint64 = Uint64::new(10u64);
 
+/// Maximum number of limiter trips kept in the trip log,
+/// older trips are pruned as new ones are recorded.
+pub const MAX_LIMITER_TRIP_LOG_SIZE: u64 = 100;
+
+const DEFAULT_TRIP_LOG_LIMIT: u32 = 10;
+
 #[cw_serde]
 pub struct WindowConfig {
     /// Size of the window in nanoseconds
@@ -41,6 +50,32 @@ impl WindowConfig {
             .checked_div(self.division_count)
             .map_err(Into::into)
     }
+
+    fn ensure_constraint(&self) -> Result<(), ContractError> {
+        // window size must be greater than zero
+        ensure!(
+            self.window_size > Uint64::zero(),
+            ContractError::ZeroWindowSize {}
+        );
+
+        // division count must not exceed MAX_DIVISION_COUNT
+        ensure!(
+            self.division_count <= MAX_DIVISION_COUNT,
+            ContractError::DivisionCountExceeded {
+                max_division_count: MAX_DIVISION_COUNT
+            }
+        );
+
+        // division count must evenly divide window size
+        let is_window_evenly_dividable =
+            self.window_size.checked_rem(self.division_count)? == Uint64::zero();
+        ensure!(
+            is_window_evenly_dividable,
+            ContractError::UnevenWindowDivision {}
+        );
+
+        Ok(())
+    }
 }
 
 /// Limiter that determines limit by upper bound of SMA (Simple Moving Average) of the value.
@@ -89,6 +124,14 @@ impl ChangeLimiter {
         self.latest_value
     }
 
+    pub fn window_config(&self) -> &WindowConfig {
+        &self.window_config
+    }
+
+    pub fn boundary_offset(&self) -> Decimal {
+        self.boundary_offset
+    }
+
     pub fn reset(self) -> Self {
         Self {
             divisions: vec![],
@@ -108,30 +151,7 @@ impl ChangeLimiter {
     }
 
     fn ensure_window_config_constraint(self) -> Result<Self, ContractError> {
-        let config = &self.window_config;
-
-        // window size must be greater than zero
-        ensure!(
-            config.window_size > Uint64::zero(),
-            ContractError::ZeroWindowSize {}
-        );
-
-        // division count must not exceed MAX_DIVISION_COUNT
-        ensure!(
-            config.division_count <= MAX_DIVISION_COUNT,
-            ContractError::DivisionCountExceeded {
-                max_division_count: MAX_DIVISION_COUNT
-            }
-        );
-
-        // division count must evenly divide window size
-        let is_window_evenly_dividable =
-            config.window_size.checked_rem(config.division_count)? == Uint64::zero();
-        ensure!(
-            is_window_evenly_dividable,
-            ContractError::UnevenWindowDivision {}
-        );
-
+        self.window_config.ensure_constraint()?;
         Ok(self)
     }
 
@@ -160,8 +180,14 @@ impl ChangeLimiter {
             // using saturating_add/sub since the overflowed value can't be exceeded anyway
             let upper_limit = avg.saturating_add(updated_limiter.boundary_offset);
 
+            // zero moving average means the denom has had zero weight over the whole window,
+            // the deposit that establishes the denom is not limited since there is
+            // no meaningful reference value, later updates are limited as usual
+            // once the established value is part of the moving average
+            let is_establishing_denom = avg.is_zero();
+
             ensure!(
-                value <= upper_limit,
+                is_establishing_denom || value <= upper_limit,
                 ContractError::UpperLimitExceeded {
                     denom: denom.to_string(),
                     upper_limit,
@@ -273,6 +299,10 @@ impl StaticLimiter {
         Self { upper_limit }.ensure_upper_limit_constraint()
     }
 
+    pub fn upper_limit(&self) -> Decimal {
+        self.upper_limit
+    }
+
     fn ensure_upper_limit_constraint(self) -> Result<Self, ContractError> {
         ensure!(
             self.upper_limit > Decimal::zero(),
@@ -305,13 +335,304 @@ impl StaticLimiter {
     }
 }
 
+/// Limiter that bounds how far the value may deviate from the even split (1/n) among n pool assets.
+/// Unlike [`StaticLimiter`], the bound follows the asset count, so it stays meaningful
+/// when pool assets are added or removed.
+///
+/// Like [`ChangeLimiter`], only moving the value further out of bound is rejected,
+/// so that the pool can be bootstrapped and an out of bound value can be brought back.
+#[cw_serde]
+pub struct RelativeLimiter {
+    /// Maximum absolute deviation of the value from 1/n
+    max_deviation: Decimal,
+
+    /// Latest value the limiter has been updated with, `None` until the first update
+    #[serde(default)]
+    latest_value: Option<Decimal>,
+}
+
+impl RelativeLimiter {
+    pub fn new(max_deviation: Decimal) -> Result<Self, ContractError> {
+        Self {
+            max_deviation,
+            latest_value: None,
+        }
+        .ensure_max_deviation_constraint()
+    }
+
+    pub fn max_deviation(&self) -> Decimal {
+        self.max_deviation
+    }
+
+    fn ensure_max_deviation_constraint(self) -> Result<Self, ContractError> {
+        ensure!(
+            self.max_deviation > Decimal::zero(),
+            ContractError::ZeroMaxDeviation {}
+        );
+
+        ensure!(
+            self.max_deviation <= Decimal::percent(100),
+            ContractError::ExceedHundredPercentMaxDeviation {}
+        );
+
+        Ok(self)
+    }
+
+    fn ensure_max_deviation(
+        self,
+        denom: &str,
+        value: Decimal,
+        asset_count: usize,
+    ) -> Result<Self, ContractError> {
+        let even_weight = Decimal::checked_from_ratio(1u128, asset_count as u128)?;
+        let deviation = value.abs_diff(even_weight);
+
+        // without a latest value there is no reference to tell whether the deviation increases
+        let is_deviation_increasing = self
+            .latest_value
+            .is_some_and(|latest_value| deviation > latest_value.abs_diff(even_weight));
+
+        ensure!(
+            deviation <= self.max_deviation || !is_deviation_increasing,
+            ContractError::MaxDeviationExceeded {
+                denom: denom.to_string(),
+                even_weight,
+                max_deviation: self.max_deviation,
+                value,
+            }
+        );
+
+        Ok(self)
+    }
+
+    fn set_max_deviation(self, max_deviation: Decimal) -> Result<Self, ContractError> {
+        Self {
+            max_deviation,
+            ..self
+        }
+        .ensure_max_deviation_constraint()
+    }
+
+    fn update(self, value: Decimal) -> Self {
+        Self {
+            latest_value: Some(value),
+            ..self
+        }
+    }
+}
+
+/// Net flow of the denom accumulated within a division of a [`NetFlowLimiter`] window.
+#[cw_serde]
+pub struct NetFlowDivision {
+    /// Index of the division since epoch, i.e. block time divided by division size
+    index: u64,
+    /// Inflow minus outflow of the denom's pool amount within the division
+    net_flow: Int256,
+}
+
+/// Limiter that bounds the net flow, i.e. inflow minus outflow, of the denom's pool amount
+/// over a rolling window in absolute token terms, in either direction.
+/// Unlike weight based limiters, it catches slow drains that leave weights steady,
+/// e.g. while other denoms are drained in proportion.
+///
+/// The window is made of the latest `division_count` divisions, including the current one.
 #[cw_serde]
+pub struct NetFlowLimiter {
+    /// Divisions in the window, divisions are ordered from oldest to newest.
+    divisions: Vec<NetFlowDivision>,
+
+    /// Configuration of the window
+    window_config: WindowConfig,
+
+    /// Max absolute net flow within the window
+    max_net_flow: Uint128,
+}
+
+impl NetFlowLimiter {
+    pub fn new(window_config: WindowConfig, max_net_flow: Uint128) -> Result<Self, ContractError> {
+        window_config.ensure_constraint()?;
+        ensure!(!max_net_flow.is_zero(), ContractError::ZeroMaxNetFlow {});
+
+        Ok(Self {
+            divisions: vec![],
+            window_config,
+            max_net_flow,
+        })
+    }
+
+    pub fn window_config(&self) -> &WindowConfig {
+        &self.window_config
+    }
+
+    pub fn max_net_flow(&self) -> Uint128 {
+        self.max_net_flow
+    }
+
+    /// Net flow within the window at `block_time`.
+    pub fn net_flow(&self, block_time: Timestamp) -> Result<Int256, ContractError> {
+        let current_index = self.division_index(block_time)?;
+
+        self.divisions
+            .iter()
+            .filter(|division| self.is_within_window(division.index, current_index))
+            .try_fold(Int256::zero(), |acc, division| {
+                acc.checked_add(division.net_flow)
+            })
+            .map_err(Into::into)
+    }
+
+    fn division_index(&self, block_time: Timestamp) -> Result<u64, ContractError> {
+        Ok(block_time.nanos() / self.window_config.division_size()?.u64())
+    }
+
+    fn is_within_window(&self, index: u64, current_index: u64) -> bool {
+        index + self.window_config.division_count.u64() > current_index
+    }
+
+    /// Add `flow` to the current division, dropping divisions that are out of the window,
+    /// and ensure that the resulting net flow is within the max net flow.
+    /// Flow that brings the net flow closer to zero is always allowed,
+    /// so that an exceeded limit can be recovered from.
+    fn update(
+        self,
+        block_time: Timestamp,
+        denom: &str,
+        flow: Int256,
+    ) -> Result<Self, ContractError> {
+        let prev_net_flow = self.net_flow(block_time)?;
+        let net_flow = prev_net_flow.checked_add(flow)?;
+
+        ensure!(
+            net_flow.unsigned_abs() <= Uint256::from(self.max_net_flow)
+                || net_flow.unsigned_abs() < prev_net_flow.unsigned_abs(),
+            ContractError::NetFlowLimitExceeded {
+                denom: denom.to_string(),
+                max_net_flow: self.max_net_flow,
+                net_flow,
+            }
+        );
+
+        if flow.is_zero() {
+            return Ok(self);
+        }
+
+        let current_index = self.division_index(block_time)?;
+        let mut divisions: Vec<_> = self
+            .divisions
+            .iter()
+            .filter(|division| self.is_within_window(division.index, current_index))
+            .cloned()
+            .collect();
+
+        match divisions.last_mut() {
+            Some(division) if division.index == current_index => {
+                division.net_flow = division.net_flow.checked_add(flow)?;
+            }
+            _ => divisions.push(NetFlowDivision {
+                index: current_index,
+                net_flow: flow,
+            }),
+        }
+
+        Ok(Self { divisions, ..self })
+    }
+}
+
+#[cw_serde]
+#[allow(clippy::enum_variant_names)]
 pub enum Limiter {
     ChangeLimiter(ChangeLimiter),
     StaticLimiter(StaticLimiter),
+    RelativeLimiter(RelativeLimiter),
+    NetFlowLimiter(NetFlowLimiter),
+}
+
+impl Limiter {
+    /// The weight bound enforced by the limiter: boundary offset for [`ChangeLimiter`],
+    /// upper limit for [`StaticLimiter`] and max deviation for [`RelativeLimiter`].
+    /// `None` for [`NetFlowLimiter`], which bounds token amounts rather than weights.
+    pub fn bound(&self) -> Option<Decimal> {
+        match self {
+            Limiter::ChangeLimiter(limiter) => Some(limiter.boundary_offset),
+            Limiter::StaticLimiter(limiter) => Some(limiter.upper_limit),
+            Limiter::RelativeLimiter(limiter) => Some(limiter.max_deviation),
+            Limiter::NetFlowLimiter(_) => None,
+        }
+    }
+
+    /// Params the limiter is registered with, leaving out its tracked state.
+    pub fn params(&self) -> LimiterParams {
+        match self {
+            Limiter::ChangeLimiter(limiter) => LimiterParams::ChangeLimiter {
+                window_config: limiter.window_config.clone(),
+                boundary_offset: limiter.boundary_offset,
+            },
+            Limiter::StaticLimiter(limiter) => LimiterParams::StaticLimiter {
+                upper_limit: limiter.upper_limit,
+            },
+            Limiter::RelativeLimiter(limiter) => LimiterParams::RelativeLimiter {
+                max_deviation: limiter.max_deviation,
+            },
+            Limiter::NetFlowLimiter(limiter) => LimiterParams::NetFlowLimiter {
+                window_config: limiter.window_config.clone(),
+                max_net_flow: limiter.max_net_flow,
+            },
+        }
+    }
+
+    /// Replace the bound enforced by the limiter, see [`Limiter::bound`].
+    fn with_bound(self, bound: Decimal) -> Result<Self, ContractError> {
+        Ok(match self {
+            Limiter::ChangeLimiter(limiter) => Limiter::ChangeLimiter(
+                ChangeLimiter {
+                    boundary_offset: bound,
+                    ..limiter
+                }
+                .ensure_boundary_offset_constrain()?,
+            ),
+            Limiter::StaticLimiter(limiter) => {
+                Limiter::StaticLimiter(limiter.set_upper_limit(bound)?)
+            }
+            Limiter::RelativeLimiter(limiter) => {
+                Limiter::RelativeLimiter(limiter.set_max_deviation(bound)?)
+            }
+            Limiter::NetFlowLimiter(_) => {
+                return Err(ContractError::WrongLimiterType {
+                    expected: "weight_limiter".to_string(),
+                    actual: "net_flow_limiter".to_string(),
+                })
+            }
+        })
+    }
+
+    /// Check the value against the limiter without updating its state.
+    fn ensure_within_limit(
+        &self,
+        denom: &str,
+        value: Decimal,
+        asset_count: usize,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        match self.clone() {
+            Limiter::ChangeLimiter(limiter) => {
+                limiter.ensure_upper_limit(block_time, denom, value)?;
+            }
+            Limiter::StaticLimiter(limiter) => {
+                limiter.ensure_upper_limit(denom, value)?;
+            }
+            Limiter::RelativeLimiter(limiter) => {
+                limiter.ensure_max_deviation(denom, value, asset_count)?;
+            }
+            // weight is not bounded by net flow limiter
+            Limiter::NetFlowLimiter(_) => {}
+        };
+
+        Ok(())
+    }
 }
 
 #[cw_serde]
+#[allow(clippy::enum_variant_names)]
 pub enum LimiterParams {
     ChangeLimiter {
         window_config: WindowConfig,
@@ -320,17 +641,134 @@ pub enum LimiterParams {
     StaticLimiter {
         upper_limit: Decimal,
     },
+    RelativeLimiter {
+        max_deviation: Decimal,
+    },
+    /// [`LimiterParams::ChangeLimiter`] with `boundary_offset` in basis points
+    ChangeLimiterBps {
+        window_config: WindowConfig,
+        boundary_offset_bps: u32,
+    },
+    /// [`LimiterParams::StaticLimiter`] with `upper_limit` in basis points
+    StaticLimiterBps {
+        upper_limit_bps: u32,
+    },
+    /// [`LimiterParams::RelativeLimiter`] with `max_deviation` in basis points
+    RelativeLimiterBps {
+        max_deviation_bps: u32,
+    },
+    NetFlowLimiter {
+        window_config: WindowConfig,
+        max_net_flow: Uint128,
+    },
+}
+
+/// Basis points in a whole, bounds given in basis points must be within `0..=MAX_BPS`.
+const MAX_BPS: u32 = 10_000;
+
+/// Convert `bps` basis points to its [`Decimal`] equivalent.
+fn decimal_from_bps(bps: u32) -> Result<Decimal, ContractError> {
+    ensure!(bps <= MAX_BPS, ContractError::InvalidBasisPoints { bps });
+    Ok(Decimal::from_ratio(bps, MAX_BPS))
+}
+
+/// Swap cut short by a limiter on a partial fill, kept for reconstructing the timeline of an incident.
+/// Swaps rejected by a limiter are never recorded, since the rejection reverts the record.
+#[cw_serde]
+pub struct LimiterTripRecord {
+    pub id: u64,
+    pub denom: String,
+    pub label: String,
+    /// Weight of the denom that filling the whole swap would have resulted in
+    pub value: Decimal,
+    pub time: Timestamp,
+}
+
+/// Bound that temporarily replaces a limiter's own bound until `revert_at`.
+#[cw_serde]
+pub struct TemporaryBound {
+    pub bound: Decimal,
+    pub revert_at: Timestamp,
+}
+
+/// Automatic tightening of static limiters while the pool is under stress,
+/// i.e. while its imbalance exceeds `imbalance_threshold`. Bounds relax back to
+/// their own bounds as soon as the imbalance recovers.
+#[cw_serde]
+pub struct StressConfig {
+    /// Imbalance, the sum of absolute deviations of each weight from the even split,
+    /// above which the pool is under stress
+    pub imbalance_threshold: Decimal,
+
+    /// Factor that static limiter upper limits are multiplied by while under stress, within (0, 1]
+    pub tightening_factor: Decimal,
+}
+
+impl StressConfig {
+    pub fn ensure_tightening_factor_constraint(self) -> Result<Self, ContractError> {
+        ensure!(
+            !self.tightening_factor.is_zero() && self.tightening_factor <= Decimal::one(),
+            ContractError::InvalidTighteningFactor {
+                tightening_factor: self.tightening_factor
+            }
+        );
+
+        Ok(self)
+    }
+}
+
+/// Sum of absolute deviations of each weight from the even split.
+fn imbalance(denom_weight_pairs: &[(String, Decimal)]) -> Result<Decimal, ContractError> {
+    if denom_weight_pairs.is_empty() {
+        return Ok(Decimal::zero());
+    }
+
+    let even_weight = Decimal::checked_from_ratio(1u128, denom_weight_pairs.len() as u128)?;
+
+    denom_weight_pairs
+        .iter()
+        .try_fold(Decimal::zero(), |acc, (_, weight)| {
+            acc.checked_add(weight.abs_diff(even_weight))
+        })
+        .map_err(Into::into)
 }
 
 pub struct Limiters<'a> {
     /// Map of (denom, label) -> Limiter
     limiters: Map<'a, (&'a str, &'a str), Limiter>,
+
+    /// Map of (denom, label) -> TemporaryBound
+    temporary_bounds: Map<'a, (&'a str, &'a str), TemporaryBound>,
+
+    /// Map of (denom, label) -> number of times the limiter tripped on a partial fill
+    trip_counts: Map<'a, (&'a str, &'a str), u64>,
+
+    /// Id of the next trip record
+    trip_log_next_id: Item<'a, u64>,
+
+    /// Map of id -> recent trip, capped at [`MAX_LIMITER_TRIP_LOG_SIZE`]
+    trip_log: Map<'a, u64, LimiterTripRecord>,
+
+    /// Tightening of static limiters under stress, no tightening if not set
+    stress_config: Item<'a, StressConfig>,
 }
 
 impl<'a> Limiters<'a> {
-    pub const fn new(limiters_namespace: &'a str) -> Self {
+    pub const fn new(
+        limiters_namespace: &'a str,
+        temporary_bounds_namespace: &'a str,
+        trip_counts_namespace: &'a str,
+        trip_log_next_id_namespace: &'a str,
+        trip_log_namespace: &'a str,
+        stress_config_namespace: &'a str,
+    ) -> Self {
         Self {
             limiters: Map::new(limiters_namespace),
+            temporary_bounds: Map::new(temporary_bounds_namespace),
+            trip_counts: Map::new(trip_counts_namespace),
+            trip_log_next_id: Item::new(trip_log_next_id_namespace),
+            trip_log: Map::new(trip_log_namespace),
+            stress_config: Item::new(stress_config_namespace),
         }
     }
 
@@ -341,6 +779,24 @@ impl<'a> Limiters<'a> {
         label: &str,
         limiter_params: LimiterParams,
     ) -> Result<(), ContractError> {
+        let limiter = self.ensure_registrable(storage, denom, label, limiter_params, 0)?;
+
+        self.limiters
+            .save(storage, (denom, label), &limiter)
+            .map_err(Into::into)
+    }
+
+    /// Ensure a limiter can be registered, returning it without saving,
+    /// so that a batch of registrations can be validated before the first one is saved.
+    /// `pending_count` is the number of limiters of the denom in the batch validated so far.
+    pub fn ensure_registrable(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+        label: &str,
+        limiter_params: LimiterParams,
+        pending_count: u64,
+    ) -> Result<Limiter, ContractError> {
         let is_registering_limiter_exists =
             self.limiters.may_load(storage, (denom, label))?.is_some();
 
@@ -354,6 +810,7 @@ impl<'a> Limiters<'a> {
             }
         );
 
+        // bounds in basis points are converted, so that both forms result in the same limiter
         let limiter = match limiter_params {
             LimiterParams::ChangeLimiter {
                 window_config,
@@ -362,10 +819,31 @@ impl<'a> Limiters<'a> {
             LimiterParams::StaticLimiter { upper_limit } => {
                 Limiter::StaticLimiter(StaticLimiter::new(upper_limit)?)
             }
+            LimiterParams::RelativeLimiter { max_deviation } => {
+                Limiter::RelativeLimiter(RelativeLimiter::new(max_deviation)?)
+            }
+            LimiterParams::ChangeLimiterBps {
+                window_config,
+                boundary_offset_bps,
+            } => Limiter::ChangeLimiter(ChangeLimiter::new(
+                window_config,
+                decimal_from_bps(boundary_offset_bps)?,
+            )?),
+            LimiterParams::StaticLimiterBps { upper_limit_bps } => {
+                Limiter::StaticLimiter(StaticLimiter::new(decimal_from_bps(upper_limit_bps)?)?)
+            }
+            LimiterParams::RelativeLimiterBps { max_deviation_bps } => Limiter::RelativeLimiter(
+                RelativeLimiter::new(decimal_from_bps(max_deviation_bps)?)?,
+            ),
+            LimiterParams::NetFlowLimiter {
+                window_config,
+                max_net_flow,
+            } => Limiter::NetFlowLimiter(NetFlowLimiter::new(window_config, max_net_flow)?),
         };
 
         // ensure limiters for the denom has not yet reached the maximum
-        let limiter_count_for_denom = self.list_limiters_by_denom(storage, denom)?.len() as u64;
+        let limiter_count_for_denom =
+            self.list_limiters_by_denom(storage, denom)?.len() as u64 + pending_count;
         ensure!(
             limiter_count_for_denom < MAX_LIMITER_COUNT_PER_DENOM.u64(),
             ContractError::MaxLimiterCountPerDenomExceeded {
@@ -374,9 +852,7 @@ impl<'a> Limiters<'a> {
             }
         );
 
-        self.limiters
-            .save(storage, (denom, label), &limiter)
-            .map_err(Into::into)
+        Ok(limiter)
     }
 
     /// Deregsiter all limiters for the denom without checking if it will be empty.
@@ -390,6 +866,7 @@ impl<'a> Limiters<'a> {
 
         for (label, _) in limiters {
             self.limiters.remove(storage, (denom, &label));
+            self.temporary_bounds.remove(storage, (denom, &label));
         }
 
         Ok(())
@@ -414,6 +891,8 @@ impl<'a> Limiters<'a> {
                 );
 
                 self.limiters.remove(storage, (denom, label));
+                self.temporary_bounds.remove(storage, (denom, label));
+                self.trip_counts.remove(storage, (denom, label));
                 Ok(limiter)
             }
             None => Err(ContractError::LimiterDoesNotExist {
@@ -455,6 +934,14 @@ impl<'a> Limiters<'a> {
                         expected: "change_limiter".to_string(),
                         actual: "static_limiter".to_string(),
                     }),
+                    Limiter::RelativeLimiter(_) => Err(ContractError::WrongLimiterType {
+                        expected: "change_limiter".to_string(),
+                        actual: "relative_limiter".to_string(),
+                    }),
+                    Limiter::NetFlowLimiter(_) => Err(ContractError::WrongLimiterType {
+                        expected: "change_limiter".to_string(),
+                        actual: "net_flow_limiter".to_string(),
+                    }),
                 }
             },
         )?;
@@ -478,21 +965,213 @@ impl<'a> Limiters<'a> {
                     label: label.to_string(),
                 })?;
 
-                // check if the limiter is a StaticLimiter
+                // check if the limiter is a StaticLimiter
+                match limiter {
+                    Limiter::StaticLimiter(limiter) => Ok(Limiter::StaticLimiter(
+                        limiter.set_upper_limit(upper_limit)?,
+                    )),
+                    Limiter::ChangeLimiter(_) => Err(ContractError::WrongLimiterType {
+                        expected: "static_limiter".to_string(),
+                        actual: "change_limiter".to_string(),
+                    }),
+                    Limiter::RelativeLimiter(_) => Err(ContractError::WrongLimiterType {
+                        expected: "static_limiter".to_string(),
+                        actual: "relative_limiter".to_string(),
+                    }),
+                    Limiter::NetFlowLimiter(_) => Err(ContractError::WrongLimiterType {
+                        expected: "static_limiter".to_string(),
+                        actual: "net_flow_limiter".to_string(),
+                    }),
+                }
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Set max deviation for a [`RelativeLimiter`] only, otherwise it will fail.
+    pub fn set_relative_limiter_max_deviation(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        label: &str,
+        max_deviation: Decimal,
+    ) -> Result<(), ContractError> {
+        self.limiters.update(
+            storage,
+            (denom, label),
+            |limiter: Option<Limiter>| -> Result<Limiter, ContractError> {
+                let limiter = limiter.ok_or(ContractError::LimiterDoesNotExist {
+                    denom: denom.to_string(),
+                    label: label.to_string(),
+                })?;
+
+                // check if the limiter is a RelativeLimiter
                 match limiter {
-                    Limiter::StaticLimiter(limiter) => Ok(Limiter::StaticLimiter(
-                        limiter.set_upper_limit(upper_limit)?,
+                    Limiter::RelativeLimiter(limiter) => Ok(Limiter::RelativeLimiter(
+                        limiter.set_max_deviation(max_deviation)?,
                     )),
                     Limiter::ChangeLimiter(_) => Err(ContractError::WrongLimiterType {
-                        expected: "static_limiter".to_string(),
+                        expected: "relative_limiter".to_string(),
                         actual: "change_limiter".to_string(),
                     }),
+                    Limiter::StaticLimiter(_) => Err(ContractError::WrongLimiterType {
+                        expected: "relative_limiter".to_string(),
+                        actual: "static_limiter".to_string(),
+                    }),
+                    Limiter::NetFlowLimiter(_) => Err(ContractError::WrongLimiterType {
+                        expected: "relative_limiter".to_string(),
+                        actual: "net_flow_limiter".to_string(),
+                    }),
                 }
             },
         )?;
         Ok(())
     }
 
+    /// Temporarily widen the limiter's bound to `bound` until `revert_at`,
+    /// after which the limiter's own bound applies again.
+    /// The limiter's own bound is kept intact, see [`Limiter::bound`].
+    pub fn set_temporary_bound(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        label: &str,
+        bound: Decimal,
+        revert_at: Timestamp,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let limiter = self.get_limiter(storage, denom, label)?;
+
+        ensure!(
+            revert_at > block_time,
+            ContractError::RevertTimeNotInFuture {
+                revert_at,
+                block_time
+            }
+        );
+
+        let original_bound = limiter
+            .bound()
+            .ok_or_else(|| ContractError::WrongLimiterType {
+                expected: "weight_limiter".to_string(),
+                actual: "net_flow_limiter".to_string(),
+            })?;
+        ensure!(
+            bound > original_bound,
+            ContractError::TemporaryBoundNotWider {
+                bound,
+                original_bound
+            }
+        );
+
+        // ensure the bound is valid for the limiter type
+        limiter.with_bound(bound)?;
+
+        self.temporary_bounds
+            .save(
+                storage,
+                (denom, label),
+                &TemporaryBound { bound, revert_at },
+            )
+            .map_err(Into::into)
+    }
+
+    pub fn get_temporary_bound(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+        label: &str,
+    ) -> Result<Option<TemporaryBound>, ContractError> {
+        self.temporary_bounds
+            .may_load(storage, (denom, label))
+            .map_err(Into::into)
+    }
+
+    /// Set the stress config, or remove it to stop tightening static limiters under stress.
+    pub fn set_stress_config(
+        &self,
+        storage: &mut dyn Storage,
+        stress_config: Option<StressConfig>,
+    ) -> Result<(), ContractError> {
+        match stress_config {
+            Some(stress_config) => self.stress_config.save(
+                storage,
+                &stress_config.ensure_tightening_factor_constraint()?,
+            )?,
+            None => self.stress_config.remove(storage),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_stress_config(
+        &self,
+        storage: &dyn Storage,
+    ) -> Result<Option<StressConfig>, ContractError> {
+        self.stress_config.may_load(storage).map_err(Into::into)
+    }
+
+    /// Tightening factor to apply to static limiters given the resulting weights of the pool,
+    /// `None` if the pool is not under stress.
+    pub fn stress_tightening_factor(
+        &self,
+        storage: &dyn Storage,
+        denom_weight_pairs: &[(String, Decimal)],
+    ) -> Result<Option<Decimal>, ContractError> {
+        let Some(stress_config) = self.get_stress_config(storage)? else {
+            return Ok(None);
+        };
+
+        if imbalance(denom_weight_pairs)? > stress_config.imbalance_threshold {
+            Ok(Some(stress_config.tightening_factor))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// The limiter as enforced at `block_time`, with its temporary bound applied if not yet reverted,
+    /// and static limiter's upper limit tightened by `tightening_factor` if the pool is under stress.
+    fn effective_limiter(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+        label: &str,
+        limiter: Limiter,
+        block_time: Timestamp,
+        tightening_factor: Option<Decimal>,
+    ) -> Result<Limiter, ContractError> {
+        let limiter = match self.get_temporary_bound(storage, denom, label)? {
+            Some(TemporaryBound { bound, revert_at }) if block_time < revert_at => {
+                limiter.with_bound(bound)?
+            }
+            _ => limiter,
+        };
+
+        match (limiter, tightening_factor) {
+            (Limiter::StaticLimiter(limiter), Some(tightening_factor)) => {
+                let upper_limit = limiter.upper_limit.checked_mul(tightening_factor)?;
+                Ok(Limiter::StaticLimiter(
+                    limiter.set_upper_limit(upper_limit)?,
+                ))
+            }
+            (limiter, _) => Ok(limiter),
+        }
+    }
+
+    pub fn get_limiter(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+        label: &str,
+    ) -> Result<Limiter, ContractError> {
+        self.limiters
+            .may_load(storage, (denom, label))?
+            .ok_or_else(|| ContractError::LimiterDoesNotExist {
+                denom: denom.to_string(),
+                label: label.to_string(),
+            })
+    }
+
     pub fn list_limiters_by_denom(
         &self,
         storage: &dyn Storage,
@@ -524,38 +1203,318 @@ impl<'a> Limiters<'a> {
         denom_value_pairs: Vec<(String, Decimal)>,
         block_time: Timestamp,
     ) -> Result<(), ContractError> {
+        let asset_count = denom_value_pairs.len();
+        let tightening_factor = self.stress_tightening_factor(storage, &denom_value_pairs)?;
+
+        // updated limiters are saved only once all limiters pass, so that no limiter is updated
+        // by a check that is rejected by another limiter
+        let mut updated_limiters = vec![];
+
         for (denom, value) in denom_value_pairs {
             let limiters = self.list_limiters_by_denom(storage, denom.as_str())?;
 
             for (label, limiter) in limiters {
-                // match limiter type
+                // net flow limiters are checked against flows, see `check_net_flows_and_update`
+                let Some(own_bound) = limiter.bound() else {
+                    continue;
+                };
+                let limiter = self.effective_limiter(
+                    storage,
+                    &denom,
+                    &label,
+                    limiter,
+                    block_time,
+                    tightening_factor,
+                )?;
 
-                let limiter = match limiter {
-                    Limiter::ChangeLimiter(limiter) => Limiter::ChangeLimiter(
-                        limiter
-                            .ensure_upper_limit(block_time, denom.as_str(), value)?
-                            .update(block_time, value)?,
-                    ),
-                    Limiter::StaticLimiter(limiter) => {
-                        Limiter::StaticLimiter(limiter.ensure_upper_limit(denom.as_str(), value)?)
-                    }
+                // match limiter type
+                let checked = match limiter {
+                    Limiter::ChangeLimiter(limiter) => limiter
+                        .ensure_upper_limit(block_time, denom.as_str(), value)
+                        .and_then(|limiter| limiter.update(block_time, value))
+                        .map(Limiter::ChangeLimiter),
+                    Limiter::StaticLimiter(limiter) => limiter
+                        .ensure_upper_limit(denom.as_str(), value)
+                        .map(Limiter::StaticLimiter),
+                    Limiter::RelativeLimiter(limiter) => limiter
+                        .ensure_max_deviation(denom.as_str(), value, asset_count)
+                        .map(|limiter| Limiter::RelativeLimiter(limiter.update(value))),
+                    Limiter::NetFlowLimiter(limiter) => Ok(Limiter::NetFlowLimiter(limiter)),
                 };
 
-                // save updated limiter
-                self.limiters
-                    .save(storage, (denom.as_str(), &label), &limiter)?;
+                // trips are not recorded here, since the rejection reverts any write,
+                // see `record_trip` for recording trips of partially filled swaps
+                let limiter = checked?;
+
+                // keep updated limiter with its own bound, so that temporary bound is not persisted
+                updated_limiters.push((denom.clone(), label, limiter.with_bound(own_bound)?));
+            }
+        }
+
+        for (denom, label, limiter) in updated_limiters {
+            self.limiters
+                .save(storage, (denom.as_str(), &label), &limiter)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check net flow limiters of each denom against its `flow`, i.e. the change of
+    /// its pool amount, and update them. As with weight limiters, updated limiters
+    /// are saved only once all of them pass.
+    pub fn check_net_flows_and_update(
+        &self,
+        storage: &mut dyn Storage,
+        denom_flow_pairs: Vec<(String, Int256)>,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let mut updated_limiters = vec![];
+
+        for (denom, flow) in denom_flow_pairs {
+            for (label, limiter) in self.list_limiters_by_denom(storage, &denom)? {
+                if let Limiter::NetFlowLimiter(limiter) = limiter {
+                    let limiter = limiter.update(block_time, &denom, flow)?;
+                    updated_limiters.push((denom.clone(), label, Limiter::NetFlowLimiter(limiter)));
+                }
+            }
+        }
+
+        for (denom, label, limiter) in updated_limiters {
+            self.limiters
+                .save(storage, (denom.as_str(), &label), &limiter)?;
+        }
+
+        Ok(())
+    }
+
+    /// Count a trip of the limiter on a partial fill and append it to the trip log,
+    /// pruning the oldest trip if the log exceeds [`MAX_LIMITER_TRIP_LOG_SIZE`].
+    pub fn record_trip(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        label: &str,
+        value: Decimal,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        self.trip_counts
+            .update(storage, (denom, label), |count| -> StdResult<_> {
+                Ok(count.unwrap_or_default() + 1)
+            })?;
+
+        let id = self.trip_log_next_id.may_load(storage)?.unwrap_or_default();
+        self.trip_log.save(
+            storage,
+            id,
+            &LimiterTripRecord {
+                id,
+                denom: denom.to_string(),
+                label: label.to_string(),
+                value,
+                time: block_time,
+            },
+        )?;
+
+        if let Some(pruned_id) = id.checked_sub(MAX_LIMITER_TRIP_LOG_SIZE) {
+            self.trip_log.remove(storage, pruned_id);
+        }
+
+        self.trip_log_next_id.save(storage, &(id + 1))?;
+
+        Ok(())
+    }
+
+    /// List up to `limit` most recent trips, most recent first.
+    pub fn list_recent_trips(
+        &self,
+        storage: &dyn Storage,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<LimiterTripRecord>> {
+        let limit = limit
+            .unwrap_or(DEFAULT_TRIP_LOG_LIMIT)
+            .min(MAX_LIMITER_TRIP_LOG_SIZE as u32) as usize;
+
+        self.trip_log
+            .range(storage, None, None, Order::Descending)
+            .take(limit)
+            .map(|item| item.map(|(_, record)| record))
+            .collect()
+    }
+
+    /// Number of trips of each limiter on partial fills, limiters that never tripped are omitted.
+    #[allow(clippy::type_complexity)]
+    pub fn list_trip_counts(
+        &self,
+        storage: &dyn Storage,
+    ) -> Result<Vec<((String, String), u64)>, ContractError> {
+        // there is no need to limit, since the number of limiters is expected to be small
+        self.trip_counts
+            .range(storage, None, None, cosmwasm_std::Order::Ascending)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Check limits without updating limiter states.
+    /// Useful for simulating whether a pool state would pass the limiters.
+    pub fn check_limits(
+        &self,
+        storage: &dyn Storage,
+        denom_value_pairs: Vec<(String, Decimal)>,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let asset_count = denom_value_pairs.len();
+        let tightening_factor = self.stress_tightening_factor(storage, &denom_value_pairs)?;
+
+        for (denom, value) in denom_value_pairs {
+            let limiters = self.list_limiters_by_denom(storage, denom.as_str())?;
+
+            for (label, limiter) in limiters {
+                self.effective_limiter(
+                    storage,
+                    &denom,
+                    &label,
+                    limiter,
+                    block_time,
+                    tightening_factor,
+                )?
+                .ensure_within_limit(
+                    denom.as_str(),
+                    value,
+                    asset_count,
+                    block_time,
+                )?;
             }
         }
 
         Ok(())
     }
 
+    /// Check each limiter of the given denoms without updating limiter states,
+    /// returning whether each `(denom, label)` limiter passes.
+    #[allow(clippy::type_complexity)]
+    pub fn check_each(
+        &self,
+        storage: &dyn Storage,
+        denom_value_pairs: Vec<(String, Decimal)>,
+        block_time: Timestamp,
+    ) -> Result<Vec<((String, String), bool)>, ContractError> {
+        let asset_count = denom_value_pairs.len();
+        let tightening_factor = self.stress_tightening_factor(storage, &denom_value_pairs)?;
+        let mut results = vec![];
+
+        for (denom, value) in denom_value_pairs {
+            let limiters = self.list_limiters_by_denom(storage, denom.as_str())?;
+
+            for (label, limiter) in limiters {
+                // net flow limiters don't bound weights
+                if let Limiter::NetFlowLimiter(_) = limiter {
+                    continue;
+                }
+
+                let limiter = self.effective_limiter(
+                    storage,
+                    &denom,
+                    &label,
+                    limiter,
+                    block_time,
+                    tightening_factor,
+                )?;
+                let passes = match limiter.ensure_within_limit(
+                    denom.as_str(),
+                    value,
+                    asset_count,
+                    block_time,
+                ) {
+                    Ok(()) => true,
+                    Err(
+                        ContractError::UpperLimitExceeded { .. }
+                        | ContractError::MaxDeviationExceeded { .. },
+                    ) => false,
+                    Err(e) => return Err(e),
+                };
+
+                results.push(((denom.clone(), label), passes));
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Whether any value is at or within `epsilon` of its limit,
+    /// without updating limiter states.
+    /// Values are shifted both up and down by `epsilon`, since a [`RelativeLimiter`]
+    /// bounds deviation from the even split on both sides.
+    pub fn any_binding(
+        &self,
+        storage: &dyn Storage,
+        denom_value_pairs: Vec<(String, Decimal)>,
+        block_time: Timestamp,
+        epsilon: Decimal,
+    ) -> Result<bool, ContractError> {
+        let shifts: [fn(Decimal, Decimal) -> Decimal; 2] =
+            [Decimal::saturating_add, Decimal::saturating_sub];
+
+        for shift in shifts {
+            let shifted_pairs = denom_value_pairs
+                .iter()
+                .map(|(denom, value)| (denom.clone(), shift(*value, epsilon)))
+                .collect();
+
+            match self.check_limits(storage, shifted_pairs, block_time) {
+                Ok(()) => {}
+                Err(
+                    ContractError::UpperLimitExceeded { .. }
+                    | ContractError::MaxDeviationExceeded { .. },
+                ) => return Ok(true),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Reset a single change limiter state, starting over with `value` as the only data point.
+    /// Useful when the moving average has gone stale, e.g. after an extended pause.
+    pub fn reset_change_limiter_state(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        label: &str,
+        block_time: Timestamp,
+        value: Decimal,
+    ) -> Result<(), ContractError> {
+        match self.get_limiter(storage, denom, label)? {
+            Limiter::ChangeLimiter(limiter) => self
+                .limiters
+                .save(
+                    storage,
+                    (denom, label),
+                    &Limiter::ChangeLimiter(limiter.reset().update(block_time, value)?),
+                )
+                .map_err(Into::into),
+            Limiter::StaticLimiter(_) => Err(ContractError::WrongLimiterType {
+                expected: "change_limiter".to_string(),
+                actual: "static_limiter".to_string(),
+            }),
+            Limiter::RelativeLimiter(_) => Err(ContractError::WrongLimiterType {
+                expected: "change_limiter".to_string(),
+                actual: "relative_limiter".to_string(),
+            }),
+            Limiter::NetFlowLimiter(_) => Err(ContractError::WrongLimiterType {
+                expected: "change_limiter".to_string(),
+                actual: "net_flow_limiter".to_string(),
+            }),
+        }
+    }
+
     /// If the normalization factor has a non-uniform update, staled divisions will become invalid.
     /// In case of adding new assets, even if there is nothing wrong with the normalization factor,
     /// the asset composition change required some time to be properly reflected.
     ///
     /// This function cleans up the staled divisions and create new division with updated state,
     /// which is a start over with the new asset composition and normalization factor.
+    /// Latest values of relative limiters are brought up to date as well, since they are
+    /// the reference for whether the next update increases the deviation.
     pub fn reset_change_limiter_states(
         &self,
         storage: &mut dyn Storage,
@@ -577,7 +1536,16 @@ impl<'a> Limiters<'a> {
                             &Limiter::ChangeLimiter(limiter.reset().update(block_time, value)?)
                         })?
                 }
-                Limiter::StaticLimiter(_) => {}
+                Limiter::RelativeLimiter(limiter) => {
+                    if let Some(value) = weights.get(denom.as_str()) {
+                        self.limiters.save(
+                            storage,
+                            (denom.as_str(), label.as_str()),
+                            &Limiter::RelativeLimiter(limiter.update(*value)),
+                        )?
+                    }
+                }
+                Limiter::StaticLimiter(_) | Limiter::NetFlowLimiter(_) => {}
             };
         }
 
@@ -636,7 +1604,9 @@ macro_rules! assert_dirty_change_limiters_by_denom {
                         label
                     );
                 }
-                Limiter::StaticLimiter(_) => {}
+                Limiter::StaticLimiter(_)
+                | Limiter::RelativeLimiter(_)
+                | Limiter::NetFlowLimiter(_) => {}
             };
         }
     };
@@ -654,7 +1624,14 @@ mod tests {
         #[test]
         fn test_register_limiter_works() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
 
             limiter
                 .register(
@@ -868,7 +1845,14 @@ mod tests {
         #[test]
         fn test_register_with_empty_label_fails() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
 
             let err = limiter
                 .register(
@@ -891,7 +1875,14 @@ mod tests {
         #[test]
         fn test_register_same_key_fail() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
 
             limiter
                 .register(
@@ -935,7 +1926,14 @@ mod tests {
         #[test]
         fn test_register_limiter_exceed_max_limiter_per_denom() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
 
             for h in 1..=10u64 {
                 let label = format!("{}h", h);
@@ -982,31 +1980,155 @@ mod tests {
                 )
                 .unwrap();
 
-            // register another one should fail
-            let err = limiter
+            // register another one should fail
+            let err = limiter
+                .register(
+                    &mut deps.storage,
+                    "denoma",
+                    "static2",
+                    LimiterParams::StaticLimiter {
+                        upper_limit: Decimal::percent(9),
+                    },
+                )
+                .unwrap_err();
+
+            assert_eq!(
+                err,
+                ContractError::MaxLimiterCountPerDenomExceeded {
+                    denom: "denoma".to_string(),
+                    max: MAX_LIMITER_COUNT_PER_DENOM
+                }
+            );
+        }
+
+        #[test]
+        fn test_register_limiter_with_bps() {
+            let new_limiters = || {
+                Limiters::new(
+                    "limiters",
+                    "temporary_bounds",
+                    "trip_counts",
+                    "trip_log_next_id",
+                    "trip_log",
+                    "stress_config",
+                )
+            };
+            let window_config = WindowConfig {
+                window_size: Uint64::from(10000u64),
+                division_count: Uint64::from(5u64),
+            };
+
+            let mut decimal_deps = mock_dependencies();
+            let decimal_limiters = new_limiters();
+            for (label, params) in [
+                (
+                    "change",
+                    LimiterParams::ChangeLimiter {
+                        window_config: window_config.clone(),
+                        boundary_offset: Decimal::percent(10),
+                    },
+                ),
+                (
+                    "static",
+                    LimiterParams::StaticLimiter {
+                        upper_limit: Decimal::from_ratio(5525u128, 10_000u128),
+                    },
+                ),
+                (
+                    "relative",
+                    LimiterParams::RelativeLimiter {
+                        max_deviation: Decimal::percent(20),
+                    },
+                ),
+            ] {
+                decimal_limiters
+                    .register(&mut decimal_deps.storage, "denoma", label, params)
+                    .unwrap();
+            }
+
+            let mut bps_deps = mock_dependencies();
+            let bps_limiters = new_limiters();
+            for (label, params) in [
+                (
+                    "change",
+                    LimiterParams::ChangeLimiterBps {
+                        window_config: window_config.clone(),
+                        boundary_offset_bps: 1000,
+                    },
+                ),
+                (
+                    "static",
+                    LimiterParams::StaticLimiterBps {
+                        upper_limit_bps: 5525,
+                    },
+                ),
+                (
+                    "relative",
+                    LimiterParams::RelativeLimiterBps {
+                        max_deviation_bps: 2000,
+                    },
+                ),
+            ] {
+                bps_limiters
+                    .register(&mut bps_deps.storage, "denoma", label, params)
+                    .unwrap();
+            }
+
+            // both forms result in identical stored state
+            assert_eq!(
+                decimal_limiters
+                    .list_limiters(&decimal_deps.storage)
+                    .unwrap(),
+                bps_limiters.list_limiters(&bps_deps.storage).unwrap()
+            );
+
+            // and enforce the same bound
+            let static_limiter = |limiters: &Limiters, storage: &dyn Storage| {
+                limiters
+                    .list_limiters_by_denom(storage, "denoma")
+                    .unwrap()
+                    .into_iter()
+                    .find(|(label, _)| label == "static")
+                    .unwrap()
+                    .1
+            };
+            let block_time = Timestamp::from_nanos(1000);
+            for bps in [5525u128, 5526u128] {
+                let value = Decimal::from_ratio(bps, 10_000u128);
+                let decimal_result = static_limiter(&decimal_limiters, &decimal_deps.storage)
+                    .ensure_within_limit("denoma", value, 2, block_time);
+                let bps_result = static_limiter(&bps_limiters, &bps_deps.storage)
+                    .ensure_within_limit("denoma", value, 2, block_time);
+
+                assert_eq!(decimal_result, bps_result);
+                assert_eq!(bps_result.is_ok(), bps == 5525);
+            }
+
+            // basis points must be within a whole
+            let err = bps_limiters
                 .register(
-                    &mut deps.storage,
+                    &mut bps_deps.storage,
                     "denoma",
-                    "static2",
-                    LimiterParams::StaticLimiter {
-                        upper_limit: Decimal::percent(9),
+                    "invalid",
+                    LimiterParams::StaticLimiterBps {
+                        upper_limit_bps: 10_001,
                     },
                 )
                 .unwrap_err();
-
-            assert_eq!(
-                err,
-                ContractError::MaxLimiterCountPerDenomExceeded {
-                    denom: "denoma".to_string(),
-                    max: MAX_LIMITER_COUNT_PER_DENOM
-                }
-            );
+            assert_eq!(err, ContractError::InvalidBasisPoints { bps: 10_001 });
         }
 
         #[test]
         fn test_deregister() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
 
             limiter
                 .register(
@@ -1239,7 +2361,14 @@ mod tests {
         fn test_fail_due_to_div_count_does_not_evenly_divide_the_window() {
             let mut deps = mock_dependencies();
 
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
 
             let err = limiter
                 .register(
@@ -1263,7 +2392,14 @@ mod tests {
         fn test_fail_due_to_div_size_is_zero() {
             let mut deps = mock_dependencies();
 
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
 
             let err = limiter
                 .register(
@@ -1292,7 +2428,14 @@ mod tests {
         fn test_fail_due_to_window_size_is_zero() {
             let mut deps = mock_dependencies();
 
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
 
             let err = limiter
                 .register(
@@ -1316,7 +2459,14 @@ mod tests {
         fn test_fail_due_to_max_division_count_exceeded() {
             let mut deps = mock_dependencies();
 
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
 
             let err = limiter
                 .register(
@@ -1345,7 +2495,14 @@ mod tests {
         fn test_successful() {
             let mut deps = mock_dependencies();
 
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
 
             limiter
                 .register(
@@ -1873,7 +3030,14 @@ mod tests {
         #[test]
         fn test_change_limiter_no_clean_up_outdated() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
             let config = WindowConfig {
                 window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
                 division_count: Uint64::from(2u64),              // 30 mins each
@@ -2054,7 +3218,14 @@ mod tests {
         #[test]
         fn test_change_limiter_with_clean_up_outdated() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
             let config = WindowConfig {
                 window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
                 division_count: Uint64::from(4u64),              // 15 mins each
@@ -2205,7 +3376,14 @@ mod tests {
         #[test]
         fn test_change_limiter_with_skipped_windows() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
             let config = WindowConfig {
                 window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
                 division_count: Uint64::from(4u64),              // 15 mins each
@@ -2282,10 +3460,106 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_change_limiter_with_zero_weight_denom() {
+            let mut deps = mock_dependencies();
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
+            let config = WindowConfig {
+                window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
+                division_count: Uint64::from(4u64),              // 15 mins each
+            };
+            limiter
+                .register(
+                    &mut deps.storage,
+                    "denoma",
+                    "1h",
+                    LimiterParams::ChangeLimiter {
+                        window_config: config,
+                        boundary_offset: Decimal::percent(5),
+                    },
+                )
+                .unwrap();
+
+            // denom has zero balance, hence zero weight
+            let block_time = Timestamp::from_nanos(1661231280000000000);
+            limiter
+                .check_limits_and_update(
+                    &mut deps.storage,
+                    vec![("denoma".to_string(), Decimal::zero())],
+                    block_time,
+                )
+                .unwrap();
+
+            // first deposit that establishes the denom is not limited
+            // even though it exceeds 0% + 5%
+            let block_time = block_time.plus_minutes(20);
+            limiter
+                .check_limits_and_update(
+                    &mut deps.storage,
+                    vec![("denoma".to_string(), Decimal::percent(50))],
+                    block_time,
+                )
+                .unwrap();
+
+            // once established, the denom is limited as usual
+            let block_time = block_time.plus_minutes(10);
+            let err = limiter
+                .check_limits_and_update(
+                    &mut deps.storage,
+                    vec![("denoma".to_string(), Decimal::percent(60))],
+                    block_time,
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                ContractError::UpperLimitExceeded { denom, value, .. }
+                    if denom == "denoma" && value == Decimal::percent(60)
+            ));
+
+            // denom drained to zero is not considered newly established
+            // while its previous weight is still within the window
+            let block_time = block_time.plus_minutes(5);
+            limiter
+                .check_limits_and_update(
+                    &mut deps.storage,
+                    vec![("denoma".to_string(), Decimal::zero())],
+                    block_time,
+                )
+                .unwrap();
+
+            let block_time = block_time.plus_minutes(5);
+            let err = limiter
+                .check_limits_and_update(
+                    &mut deps.storage,
+                    vec![("denoma".to_string(), Decimal::percent(90))],
+                    block_time,
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                ContractError::UpperLimitExceeded { denom, value, .. }
+                    if denom == "denoma" && value == Decimal::percent(90)
+            ));
+        }
+
         #[test]
         fn test_static_limiter() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
 
             limiter
                 .register(
@@ -2399,10 +3673,238 @@ mod tests {
                 .unwrap();
         }
 
+        #[test]
+        fn test_net_flow_limiter() {
+            let mut deps = mock_dependencies();
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
+
+            limiter
+                .register(
+                    &mut deps.storage,
+                    "denoma",
+                    "net_flow",
+                    LimiterParams::NetFlowLimiter {
+                        window_config: WindowConfig {
+                            window_size: Uint64::from(3_600_000_000_000u64),
+                            division_count: Uint64::from(2u64),
+                        },
+                        max_net_flow: Uint128::new(100),
+                    },
+                )
+                .unwrap();
+
+            let block_time = Timestamp::from_nanos(1661231280000000000);
+            let net_flow = |storage: &dyn Storage, block_time: Timestamp| match limiter
+                .get_limiter(storage, "denoma", "net_flow")
+                .unwrap()
+            {
+                Limiter::NetFlowLimiter(limiter) => limiter.net_flow(block_time).unwrap(),
+                _ => panic!("not a net flow limiter"),
+            };
+            let check = |storage: &mut dyn Storage, flow: i128, block_time: Timestamp| {
+                limiter.check_net_flows_and_update(
+                    storage,
+                    vec![("denoma".to_string(), Int256::from_i128(flow))],
+                    block_time,
+                )
+            };
+
+            check(&mut deps.storage, 60, block_time).unwrap();
+
+            // net flow is bounded regardless of weight
+            limiter
+                .check_limits_and_update(
+                    &mut deps.storage,
+                    vec![("denoma".to_string(), Decimal::percent(100))],
+                    block_time,
+                )
+                .unwrap();
+
+            let err = check(&mut deps.storage, 41, block_time).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::NetFlowLimitExceeded {
+                    denom: "denoma".to_string(),
+                    max_net_flow: Uint128::new(100),
+                    net_flow: Int256::from_i128(101),
+                }
+            );
+            assert_eq!(net_flow(&deps.storage, block_time), Int256::from_i128(60));
+
+            // outflow is bounded as well
+            let err = check(&mut deps.storage, -161, block_time).unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::NetFlowLimitExceeded {
+                    denom: "denoma".to_string(),
+                    max_net_flow: Uint128::new(100),
+                    net_flow: Int256::from_i128(-101),
+                }
+            );
+            check(&mut deps.storage, -160, block_time).unwrap();
+            assert_eq!(net_flow(&deps.storage, block_time), Int256::from_i128(-100));
+
+            // flow in the next division accumulates within the window
+            let block_time = block_time.plus_minutes(30);
+            check(&mut deps.storage, 150, block_time).unwrap();
+            assert_eq!(net_flow(&deps.storage, block_time), Int256::from_i128(50));
+
+            // the first division ages out of the window
+            let block_time = block_time.plus_minutes(30);
+            assert_eq!(net_flow(&deps.storage, block_time), Int256::from_i128(150));
+
+            // flow bringing an exceeded net flow closer to zero is allowed
+            check(&mut deps.storage, -10, block_time).unwrap();
+            let err = check(&mut deps.storage, 1, block_time).unwrap_err();
+            assert!(matches!(err, ContractError::NetFlowLimitExceeded { .. }));
+
+            // net flow limiter has no weight bound to temporarily widen
+            let err = limiter
+                .set_temporary_bound(
+                    &mut deps.storage,
+                    "denoma",
+                    "net_flow",
+                    Decimal::percent(10),
+                    block_time.plus_hours(1),
+                    block_time,
+                )
+                .unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::WrongLimiterType {
+                    expected: "weight_limiter".to_string(),
+                    actual: "net_flow_limiter".to_string(),
+                }
+            );
+        }
+
+        #[test]
+        fn test_temporary_bound() {
+            let mut deps = mock_dependencies();
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
+
+            limiter
+                .register(
+                    &mut deps.storage,
+                    "denoma",
+                    "static",
+                    LimiterParams::StaticLimiter {
+                        upper_limit: Decimal::percent(60),
+                    },
+                )
+                .unwrap();
+
+            let block_time = Timestamp::from_nanos(1661231280000000000);
+            let revert_at = block_time.plus_seconds(3600);
+
+            // temporary bound must be wider than the limiter's own bound
+            let err = limiter
+                .set_temporary_bound(
+                    &mut deps.storage,
+                    "denoma",
+                    "static",
+                    Decimal::percent(50),
+                    revert_at,
+                    block_time,
+                )
+                .unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::TemporaryBoundNotWider {
+                    bound: Decimal::percent(50),
+                    original_bound: Decimal::percent(60),
+                }
+            );
+
+            // revert time must be in the future
+            let err = limiter
+                .set_temporary_bound(
+                    &mut deps.storage,
+                    "denoma",
+                    "static",
+                    Decimal::percent(80),
+                    block_time,
+                    block_time,
+                )
+                .unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::RevertTimeNotInFuture {
+                    revert_at: block_time,
+                    block_time,
+                }
+            );
+
+            limiter
+                .set_temporary_bound(
+                    &mut deps.storage,
+                    "denoma",
+                    "static",
+                    Decimal::percent(80),
+                    revert_at,
+                    block_time,
+                )
+                .unwrap();
+
+            let value_a = Decimal::percent(70);
+            let value_b = Decimal::one() - value_a;
+            let weights = vec![
+                ("denoma".to_string(), value_a),
+                ("denomb".to_string(), value_b),
+            ];
+
+            // widened bound applies before revert time
+            limiter
+                .check_limits_and_update(&mut deps.storage, weights.clone(), block_time)
+                .unwrap();
+
+            // own bound is kept intact
+            assert_eq!(
+                limiter
+                    .get_limiter(&deps.storage, "denoma", "static")
+                    .unwrap(),
+                Limiter::StaticLimiter(StaticLimiter::new(Decimal::percent(60)).unwrap())
+            );
+
+            // own bound applies again from revert time
+            let err = limiter
+                .check_limits_and_update(&mut deps.storage, weights, revert_at)
+                .unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::UpperLimitExceeded {
+                    denom: "denoma".to_string(),
+                    upper_limit: Decimal::percent(60),
+                    value: value_a,
+                }
+            );
+        }
+
         #[test]
         fn test_multiple_registered_limiters() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
             let config_1h = WindowConfig {
                 window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
                 division_count: Uint64::from(2u64),              // 30 mins each
@@ -2604,7 +4106,14 @@ mod tests {
             #[test]
             fn test_set_boundary_offset() {
                 let mut deps = mock_dependencies();
-                let limiters = Limiters::new("limiters");
+                let limiters = Limiters::new(
+                    "limiters",
+                    "temporary_bounds",
+                    "trip_counts",
+                    "trip_log_next_id",
+                    "trip_log",
+                    "stress_config",
+                );
                 let config = WindowConfig {
                     window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
                     division_count: Uint64::from(4u64),              // 15 mins each
@@ -2647,7 +4156,11 @@ mod tests {
                     .unwrap()
                 {
                     Limiter::ChangeLimiter(limiter) => limiter,
-                    Limiter::StaticLimiter(_) => panic!("not a change limiter"),
+                    Limiter::StaticLimiter(_)
+                    | Limiter::RelativeLimiter(_)
+                    | Limiter::NetFlowLimiter(_) => {
+                        panic!("not a change limiter")
+                    }
                 };
 
                 let boundary_offset = limiter.boundary_offset;
@@ -2686,7 +4199,14 @@ mod tests {
             #[test]
             fn test_set_upper_limit() {
                 let mut deps = mock_dependencies();
-                let limiters = Limiters::new("limiters");
+                let limiters = Limiters::new(
+                    "limiters",
+                    "temporary_bounds",
+                    "trip_counts",
+                    "trip_log_next_id",
+                    "trip_log",
+                    "stress_config",
+                );
                 let config = WindowConfig {
                     window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
                     division_count: Uint64::from(4u64),              // 15 mins each
@@ -2730,7 +4250,11 @@ mod tests {
                     .unwrap()
                 {
                     Limiter::StaticLimiter(limiter) => limiter,
-                    Limiter::ChangeLimiter(_) => panic!("not a static limiter"),
+                    Limiter::ChangeLimiter(_)
+                    | Limiter::RelativeLimiter(_)
+                    | Limiter::NetFlowLimiter(_) => {
+                        panic!("not a static limiter")
+                    }
                 };
 
                 assert_eq!(limiter.upper_limit, upper_limit);
@@ -2758,7 +4282,14 @@ mod tests {
         #[test]
         fn test_reset_change_limiter_states() {
             let mut deps = mock_dependencies();
-            let limiters = Limiters::new("limiters");
+            let limiters = Limiters::new(
+                "limiters",
+                "temporary_bounds",
+                "trip_counts",
+                "trip_log_next_id",
+                "trip_log",
+                "stress_config",
+            );
 
             // register 2 change limiters
             let config_1h = WindowConfig {
@@ -2848,6 +4379,114 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_trip_log_is_capped() {
+        let mut deps = mock_dependencies();
+        let limiters = Limiters::new(
+            "limiters",
+            "temporary_bounds",
+            "trip_counts",
+            "trip_log_next_id",
+            "trip_log",
+            "stress_config",
+        );
+        let block_time = Timestamp::from_seconds(1);
+
+        for i in 0..MAX_LIMITER_TRIP_LOG_SIZE + 2 {
+            limiters
+                .record_trip(
+                    &mut deps.storage,
+                    "denoma",
+                    "static",
+                    Decimal::percent(50),
+                    block_time.plus_seconds(i),
+                )
+                .unwrap();
+        }
+
+        // most recent trips come first
+        let trips = limiters.list_recent_trips(&deps.storage, Some(2)).unwrap();
+        assert_eq!(
+            trips,
+            vec![
+                LimiterTripRecord {
+                    id: MAX_LIMITER_TRIP_LOG_SIZE + 1,
+                    denom: "denoma".to_string(),
+                    label: "static".to_string(),
+                    value: Decimal::percent(50),
+                    time: block_time.plus_seconds(MAX_LIMITER_TRIP_LOG_SIZE + 1),
+                },
+                LimiterTripRecord {
+                    id: MAX_LIMITER_TRIP_LOG_SIZE,
+                    denom: "denoma".to_string(),
+                    label: "static".to_string(),
+                    value: Decimal::percent(50),
+                    time: block_time.plus_seconds(MAX_LIMITER_TRIP_LOG_SIZE),
+                },
+            ]
+        );
+
+        // oldest trips are pruned
+        let trips = limiters
+            .list_recent_trips(&deps.storage, Some(u32::MAX))
+            .unwrap();
+        assert_eq!(trips.len() as u64, MAX_LIMITER_TRIP_LOG_SIZE);
+        assert_eq!(trips.last().unwrap().id, 2);
+
+        assert_eq!(
+            limiters.list_trip_counts(&deps.storage).unwrap(),
+            vec![(
+                ("denoma".to_string(), "static".to_string()),
+                MAX_LIMITER_TRIP_LOG_SIZE + 2
+            )]
+        );
+    }
+
+    #[test]
+    fn test_rejection_writes_no_trip() {
+        let mut deps = mock_dependencies();
+        let limiters = Limiters::new(
+            "limiters",
+            "temporary_bounds",
+            "trip_counts",
+            "trip_log_next_id",
+            "trip_log",
+            "stress_config",
+        );
+
+        limiters
+            .register(
+                &mut deps.storage,
+                "denoma",
+                "static",
+                LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            )
+            .unwrap();
+
+        // rejection reverts every write on chain, so nothing is written before it
+        let err = limiters
+            .check_limits_and_update(
+                &mut deps.storage,
+                vec![
+                    ("denoma".to_string(), Decimal::percent(65)),
+                    ("denomb".to_string(), Decimal::percent(35)),
+                ],
+                Timestamp::from_seconds(1),
+            )
+            .unwrap_err();
+        assert!(matches!(err, ContractError::UpperLimitExceeded { .. }));
+
+        assert_eq!(limiters.list_trip_counts(&deps.storage).unwrap(), vec![]);
+        assert_eq!(
+            limiters
+                .list_recent_trips(&deps.storage, Some(u32::MAX))
+                .unwrap(),
+            vec![]
+        );
+    }
+
     fn list_divisions(
         limiters: &Limiters,
         denom: &str,
@@ -2856,7 +4495,11 @@ mod tests {
     ) -> Vec<Division> {
         match limiters.limiters.load(storage, (denom, window)).unwrap() {
             Limiter::ChangeLimiter(limiter) => limiter.divisions,
-            Limiter::StaticLimiter(_) => panic!("not a change limiter"),
+            Limiter::StaticLimiter(_)
+            | Limiter::RelativeLimiter(_)
+            | Limiter::NetFlowLimiter(_) => {
+                panic!("not a change limiter")
+            }
         }
     }
 }
@@ -2,9 +2,11 @@ mod division;
 mod helpers;
 mod limiters;
 
-pub use limiters::{Limiter, LimiterParams, Limiters};
+pub use limiters::{
+    Limiter, LimiterParams, LimiterTripRecord, Limiters, StressConfig, WindowConfig,
+};
 
 #[cfg(test)]
 pub use division::Division;
 #[cfg(test)]
-pub use limiters::{ChangeLimiter, StaticLimiter, WindowConfig};
+pub use limiters::{ChangeLimiter, StaticLimiter};
@@ -1 +1,36 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{StdResult, Storage, Timestamp};
+use cw_storage_plus::Item;
+
 pub mod v3_1_0;
+
+const MIGRATION_HISTORY: Item<Vec<MigrationRecord>> = Item::new("migration_history");
+
+#[cw_serde]
+pub struct MigrationRecord {
+    pub from_version: String,
+    pub to_version: String,
+    pub migrated_at: Timestamp,
+}
+
+/// Append a completed migration to the migration history.
+pub fn record_migration(
+    storage: &mut dyn Storage,
+    from_version: &str,
+    to_version: &str,
+    migrated_at: Timestamp,
+) -> StdResult<()> {
+    let mut history = migration_history(storage)?;
+    history.push(MigrationRecord {
+        from_version: from_version.to_string(),
+        to_version: to_version.to_string(),
+        migrated_at,
+    });
+
+    MIGRATION_HISTORY.save(storage, &history)
+}
+
+/// Migrations completed since migration history started being recorded, oldest first.
+pub fn migration_history(storage: &dyn Storage) -> StdResult<Vec<MigrationRecord>> {
+    Ok(MIGRATION_HISTORY.may_load(storage)?.unwrap_or_default())
+}
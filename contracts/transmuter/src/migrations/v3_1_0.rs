@@ -1,9 +1,10 @@
 use cosmwasm_schema::cw_serde;
 
-use cosmwasm_std::{ensure_eq, DepsMut, Response};
+use cosmwasm_std::{ensure_eq, DepsMut, Env, Response};
 
 use crate::{
-    contract::{CONTRACT_NAME, CONTRACT_VERSION},
+    contract::{Transmuter, CONTRACT_NAME, CONTRACT_VERSION},
+    migrations::record_migration,
     ContractError,
 };
 
@@ -13,7 +14,7 @@ const TO_VERSION: &str = "3.1.0";
 #[cw_serde]
 pub struct MigrateMsg {}
 
-pub fn execute_migration(deps: DepsMut) -> Result<Response, ContractError> {
+pub fn execute_migration(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
     // Assert that the stored contract version matches the expected version before migration
     cw2::assert_contract_version(deps.storage, CONTRACT_NAME, FROM_VERSION)?;
 
@@ -29,6 +30,10 @@ pub fn execute_migration(deps: DepsMut) -> Result<Response, ContractError> {
 
     // Set the contract version to the target version after successful migration
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, TO_VERSION)?;
+    record_migration(deps.storage, FROM_VERSION, TO_VERSION, env.block.time)?;
+    Transmuter::new()
+        .governance_log
+        .record(deps.storage, "migrate", None, env.block.time)?;
 
     // Return a response with an attribute indicating the method that was executed
     Ok(Response::new().add_attribute("method", "v3_1_0/execute_migraiton"))
@@ -36,7 +41,7 @@ pub fn execute_migration(deps: DepsMut) -> Result<Response, ContractError> {
 
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::testing::mock_dependencies;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
 
     use super::*;
 
@@ -46,12 +51,20 @@ mod tests {
 
         cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, FROM_VERSION).unwrap();
 
-        let res = execute_migration(deps.as_mut()).unwrap();
+        let res = execute_migration(deps.as_mut(), mock_env()).unwrap();
 
         assert_eq!(
             res,
             Response::new().add_attribute("method", "v3_1_0/execute_migraiton")
         );
+
+        let actions = Transmuter::new()
+            .governance_log
+            .list(&deps.storage, None, None)
+            .unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].action, "migrate");
+        assert_eq!(actions[0].actor, None);
     }
 
     #[test]
@@ -60,7 +73,7 @@ mod tests {
 
         cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "2.0.0").unwrap();
 
-        let err = execute_migration(deps.as_mut()).unwrap_err();
+        let err = execute_migration(deps.as_mut(), mock_env()).unwrap_err();
         assert_eq!(
             err,
             ContractError::VersionError(cw2::VersionError::WrongVersion {
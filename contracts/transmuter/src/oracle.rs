@@ -0,0 +1,61 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, QuerierWrapper, StdResult};
+
+/// Oracle driven corruption of a denom, which is marked as corrupted
+/// once its oracle price deviates from the peg by more than `threshold`.
+#[cw_serde]
+pub struct AutoCorruptionConfig {
+    /// Oracle contract queried for the denom's price, expected to be quoted against the peg
+    pub oracle: Addr,
+    /// Max deviation of the price from 1 before the denom is considered corrupted
+    pub threshold: Decimal,
+}
+
+impl AutoCorruptionConfig {
+    /// Whether `price` deviates from the peg beyond the threshold.
+    pub fn is_out_of_band(&self, price: Decimal) -> bool {
+        price.abs_diff(Decimal::one()) > self.threshold
+    }
+}
+
+/// Query interface expected from the oracle contract.
+#[cw_serde]
+pub enum OracleQueryMsg {
+    Price { denom: String },
+}
+
+#[cw_serde]
+pub struct OraclePriceResponse {
+    pub price: Decimal,
+}
+
+/// Query the price of `denom` from `oracle`.
+pub fn query_price(querier: &QuerierWrapper, oracle: &Addr, denom: &str) -> StdResult<Decimal> {
+    let OraclePriceResponse { price } = querier.query_wasm_smart(
+        oracle,
+        &OracleQueryMsg::Price {
+            denom: denom.to_string(),
+        },
+    )?;
+
+    Ok(price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_out_of_band() {
+        let config = AutoCorruptionConfig {
+            oracle: Addr::unchecked("oracle"),
+            threshold: Decimal::percent(5),
+        };
+
+        assert!(!config.is_out_of_band(Decimal::one()));
+        assert!(!config.is_out_of_band(Decimal::percent(95)));
+        assert!(!config.is_out_of_band(Decimal::percent(105)));
+        assert!(config.is_out_of_band(Decimal::percent(94)));
+        assert!(config.is_out_of_band(Decimal::percent(106)));
+    }
+}
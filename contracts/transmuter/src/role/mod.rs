@@ -1,10 +1,22 @@
-use cosmwasm_std::{Addr, DepsMut};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Deps, DepsMut, StdError};
 
 use crate::{ensure_admin_authority, ContractError};
 
 pub mod admin;
 pub mod moderator;
 
+/// Capability required to perform a set of actions
+#[cw_serde]
+pub enum Capability {
+    /// Actions that require admin role, e.g. managing pool assets, limiters and fees
+    Admin,
+    /// Actions that require moderator role, e.g. setting active status or marking corrupted assets
+    Moderator,
+    /// Claiming or rejecting pending admin transfer
+    AdminCandidate,
+}
+
 pub struct Role<'a> {
     pub admin: admin::Admin<'a>,
     pub moderator: moderator::Moderator<'a>,
@@ -30,6 +42,27 @@ impl<'a> Role<'a> {
 
         self.moderator.unchecked_set(deps, address)
     }
+
+    /// Check whether the address has the capability.
+    /// Returns false if the role required for the capability is not set.
+    pub fn has_capability(
+        &self,
+        deps: Deps,
+        address: &Addr,
+        capability: &Capability,
+    ) -> Result<bool, ContractError> {
+        let role_holder = match capability {
+            Capability::Admin => self.admin.current(deps).map(Some),
+            Capability::Moderator => self.moderator.get(deps).map(Some),
+            Capability::AdminCandidate => self.admin.candidate(deps),
+        };
+
+        match role_holder {
+            Ok(role_holder) => Ok(role_holder.as_ref() == Some(address)),
+            Err(ContractError::Std(StdError::NotFound { .. })) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -62,4 +95,53 @@ mod tests {
 
         assert_eq!(err, ContractError::Unauthorized {});
     }
+
+    #[test]
+    fn test_has_capability() {
+        let mut deps = mock_dependencies();
+        let admin = Addr::unchecked("admin");
+        let moderator = Addr::unchecked("moderator");
+        let candidate = Addr::unchecked("candidate");
+
+        let role = Role::new("admin", "moderator");
+
+        // no role is set
+        for capability in [
+            Capability::Admin,
+            Capability::Moderator,
+            Capability::AdminCandidate,
+        ] {
+            assert!(!role
+                .has_capability(deps.as_ref(), &admin, &capability)
+                .unwrap());
+        }
+
+        role.admin.init(&mut deps.storage, admin.clone()).unwrap();
+        role.moderator
+            .init(&mut deps.storage, moderator.clone())
+            .unwrap();
+        role.admin
+            .transfer(deps.as_mut(), admin.clone(), candidate.clone())
+            .unwrap();
+
+        let cases = [
+            (&admin, Capability::Admin, true),
+            (&admin, Capability::Moderator, false),
+            (&admin, Capability::AdminCandidate, false),
+            (&moderator, Capability::Admin, false),
+            (&moderator, Capability::Moderator, true),
+            (&moderator, Capability::AdminCandidate, false),
+            (&candidate, Capability::Admin, false),
+            (&candidate, Capability::Moderator, false),
+            (&candidate, Capability::AdminCandidate, true),
+        ];
+
+        for (address, capability, expected) in cases {
+            assert_eq!(
+                role.has_capability(deps.as_ref(), address, &capability)
+                    .unwrap(),
+                expected
+            );
+        }
+    }
 }
@@ -0,0 +1,108 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Coin, Decimal256, Order, SignedDecimal256, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Item, Map};
+
+/// Maximum number of swaps kept in the rounding audit log,
+/// older swaps are pruned as new ones are recorded.
+pub const MAX_ROUNDING_AUDIT_LOG_SIZE: u64 = 1000;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+#[cw_serde]
+pub struct RoundingResidual {
+    pub id: u64,
+    pub token_in: Coin,
+    pub token_out: Coin,
+    /// Fee charged on the swap, either in token in or token out denom
+    pub fee: Coin,
+    /// Token out amount before rounding, given token in and fee
+    pub ideal_token_out_amount: Decimal256,
+    /// Ideal minus actual token out amount, non-negative when rounding favors the pool.
+    /// It is only negative when a corruption drain bonus is paid on top of token out.
+    pub residual: SignedDecimal256,
+    pub time: Timestamp,
+}
+
+/// Log of swap rounding residuals, so that auditors can verify rounding favors the pool.
+pub struct RoundingAuditLog<'a> {
+    next_id: Item<'a, u64>,
+    residuals: Map<'a, u64, RoundingResidual>,
+}
+
+impl<'a> RoundingAuditLog<'a> {
+    pub const fn new(next_id_namespace: &'a str, residuals_namespace: &'a str) -> Self {
+        Self {
+            next_id: Item::new(next_id_namespace),
+            residuals: Map::new(residuals_namespace),
+        }
+    }
+
+    /// Record a residual, its id is assigned here.
+    /// Prunes the oldest residual if the log exceeds [`MAX_ROUNDING_AUDIT_LOG_SIZE`].
+    pub fn record(&self, storage: &mut dyn Storage, residual: RoundingResidual) -> StdResult<()> {
+        let id = self.next_id.may_load(storage)?.unwrap_or_default();
+
+        self.residuals
+            .save(storage, id, &RoundingResidual { id, ..residual })?;
+
+        if let Some(pruned_id) = id.checked_sub(MAX_ROUNDING_AUDIT_LOG_SIZE) {
+            self.residuals.remove(storage, pruned_id);
+        }
+
+        self.next_id.save(storage, &(id + 1))
+    }
+
+    /// List up to `limit` most recent residuals, most recent first.
+    pub fn list_recent(
+        &self,
+        storage: &dyn Storage,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<RoundingResidual>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+        self.residuals
+            .range(storage, None, None, Order::Descending)
+            .take(limit)
+            .map(|item| item.map(|(_, residual)| residual))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_rounding_audit_log_is_capped() {
+        let mut deps = mock_dependencies();
+        let log = RoundingAuditLog::new("next_id", "residuals");
+        let residual = RoundingResidual {
+            id: 0,
+            token_in: Coin::new(10, "uion"),
+            token_out: Coin::new(3, "uosmo"),
+            fee: Coin::new(0, "uion"),
+            ideal_token_out_amount: Decimal256::from_ratio(10u128, 3u128),
+            residual: SignedDecimal256::from_ratio(1i128, 3i128),
+            time: Timestamp::from_seconds(1),
+        };
+
+        for _ in 0..MAX_ROUNDING_AUDIT_LOG_SIZE + 2 {
+            log.record(&mut deps.storage, residual.clone()).unwrap();
+        }
+
+        // most recent first, ids are assigned on record
+        let residuals = log.list_recent(&deps.storage, Some(2)).unwrap();
+        assert_eq!(
+            residuals.iter().map(|r| r.id).collect::<Vec<_>>(),
+            vec![MAX_ROUNDING_AUDIT_LOG_SIZE + 1, MAX_ROUNDING_AUDIT_LOG_SIZE]
+        );
+
+        // oldest residuals are pruned
+        let pruned = log.residuals.may_load(&deps.storage, 1).unwrap();
+        assert_eq!(pruned, None);
+        let oldest = log.residuals.load(&deps.storage, 2).unwrap();
+        assert_eq!(oldest, RoundingResidual { id: 2, ..residual });
+    }
+}
@@ -0,0 +1,157 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, BlockInfo, Coin, Decimal, Order, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Bound, Item, Map};
+
+use crate::{asset::Asset, transmuter_pool::TransmuterPool, ContractError};
+
+/// Maximum number of pool snapshots kept, older snapshots are pruned as new ones are taken.
+pub const MAX_POOL_SNAPSHOTS: usize = 100;
+
+#[cw_serde]
+pub struct PoolSnapshot {
+    pub height: u64,
+    pub time: Timestamp,
+    pub pool_assets: Vec<Coin>,
+    pub weights: Vec<(String, Decimal)>,
+}
+
+/// Snapshots of pool composition keyed by block height,
+/// so that past pool composition can be queried.
+pub struct PoolSnapshots<'a> {
+    /// Minimum number of blocks between snapshots, snapshots are disabled if not set
+    interval: Item<'a, u64>,
+    snapshots: Map<'a, u64, PoolSnapshot>,
+}
+
+impl<'a> PoolSnapshots<'a> {
+    pub const fn new(interval_namespace: &'a str, snapshots_namespace: &'a str) -> Self {
+        Self {
+            interval: Item::new(interval_namespace),
+            snapshots: Map::new(snapshots_namespace),
+        }
+    }
+
+    pub fn get_interval(&self, storage: &dyn Storage) -> StdResult<Option<u64>> {
+        self.interval.may_load(storage)
+    }
+
+    /// Set snapshot interval in blocks, `None` disables snapshots.
+    pub fn set_interval(
+        &self,
+        storage: &mut dyn Storage,
+        interval: Option<u64>,
+    ) -> Result<(), ContractError> {
+        match interval {
+            Some(interval) => {
+                ensure!(interval > 0, ContractError::ZeroSnapshotInterval {});
+
+                self.interval.save(storage, &interval)?;
+            }
+            None => self.interval.remove(storage),
+        }
+
+        Ok(())
+    }
+
+    /// Take snapshot of the pool if the block height has advanced past
+    /// the interval since the latest snapshot.
+    pub fn take_if_due(
+        &self,
+        storage: &mut dyn Storage,
+        pool: &TransmuterPool,
+        block: &BlockInfo,
+    ) -> Result<(), ContractError> {
+        let Some(interval) = self.interval.may_load(storage)? else {
+            return Ok(());
+        };
+
+        if let Some(latest) = self.latest(storage)? {
+            if block.height < latest.height.saturating_add(interval) {
+                return Ok(());
+            }
+        }
+
+        self.snapshots.save(
+            storage,
+            block.height,
+            &PoolSnapshot {
+                height: block.height,
+                time: block.time,
+                pool_assets: pool.pool_assets.iter().map(Asset::to_coin).collect(),
+                weights: pool.weights()?.unwrap_or_default(),
+            },
+        )?;
+
+        self.prune(storage)?;
+
+        Ok(())
+    }
+
+    /// Nearest snapshot at or before the given height.
+    pub fn at(&self, storage: &dyn Storage, height: u64) -> StdResult<Option<PoolSnapshot>> {
+        self.snapshots
+            .range(
+                storage,
+                None,
+                Some(Bound::inclusive(height)),
+                Order::Descending,
+            )
+            .next()
+            .transpose()
+            .map(|snapshot| snapshot.map(|(_, snapshot)| snapshot))
+    }
+
+    fn latest(&self, storage: &dyn Storage) -> StdResult<Option<PoolSnapshot>> {
+        self.at(storage, u64::MAX)
+    }
+
+    fn prune(&self, storage: &mut dyn Storage) -> StdResult<()> {
+        let heights = self
+            .snapshots
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let excess = heights.len().saturating_sub(MAX_POOL_SNAPSHOTS);
+        for height in heights.into_iter().take(excess) {
+            self.snapshots.remove(storage, height);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+    #[test]
+    fn test_pool_snapshots_are_pruned() {
+        let mut deps = mock_dependencies();
+        let snapshots = PoolSnapshots::new("interval", "snapshots");
+        let pool =
+            TransmuterPool::new(vec![Asset::new(1000u128, "denom1", 1u128).unwrap()]).unwrap();
+
+        snapshots.set_interval(&mut deps.storage, Some(1)).unwrap();
+
+        let mut block = mock_env().block;
+        for height in 1..=(MAX_POOL_SNAPSHOTS as u64 + 1) {
+            block.height = height;
+            snapshots
+                .take_if_due(&mut deps.storage, &pool, &block)
+                .unwrap();
+        }
+
+        // oldest snapshot is pruned
+        assert_eq!(snapshots.at(&deps.storage, 1).unwrap(), None);
+        assert_eq!(snapshots.at(&deps.storage, 2).unwrap().unwrap().height, 2);
+        assert_eq!(
+            snapshots
+                .at(&deps.storage, u64::MAX)
+                .unwrap()
+                .unwrap()
+                .height,
+            MAX_POOL_SNAPSHOTS as u64 + 1
+        );
+    }
+}
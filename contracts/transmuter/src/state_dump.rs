@@ -0,0 +1,622 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Addr, Decimal, Deps, Order, StdResult, Storage, Timestamp, Uint128};
+use cw_storage_plus::Bound;
+
+use crate::{
+    alloyed_registry::RegisteredAlloyedAsset,
+    asset_group::AssetGroup,
+    contract::{PauseScope, SenderAccessMode, Transmuter},
+    fee::{self, DynamicFeeParams, FeeSide},
+    limiter::{Limiter, StressConfig},
+    oracle::AutoCorruptionConfig,
+    swap::{LargeSwapCooldown, MintRateLimit},
+    transmuter_pool::TransmuterPool,
+    ContractError,
+};
+
+/// Version of the `FullStateDump` layout, to be bumped on any change to it
+pub const STATE_DUMP_VERSION: u32 = 2;
+
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 100;
+
+/// Serializable dump of the contract state, for off-chain backup and migration rehearsal.
+///
+/// Pool assets and limiters per denom are capped, so they are dumped as a whole on every page.
+/// Asset groups, denom aliases and the sender access list are not capped, so they are paginated,
+/// see [`StateDumpCursor`].
+///
+/// Tracked state that is derived from swaps rather than configured, i.e. limiter windows,
+/// swap and mint volumes, snapshots, holders, allowances, locked shares, retained dust,
+/// cached denom metadata and governance and rounding audit logs, is not dumped.
+#[cw_serde]
+pub struct FullStateDump {
+    /// Layout version of the dump, see `STATE_DUMP_VERSION`
+    pub version: u32,
+    /// cw2 contract version the dump is exported from
+    pub contract_version: String,
+    pub pool: TransmuterPool,
+    /// Denoms marked as corrupted directly, rather than through an asset group
+    pub directly_corrupted_denoms: Vec<String>,
+    /// Retained swap fee accrued per denom and not yet withdrawn
+    pub accrued_fees: Vec<(String, Uint128)>,
+    pub alloyed_asset: AlloyedAssetDump,
+    pub registered_alloyed_assets: Vec<RegisteredAlloyedAsset>,
+    pub limiters: Vec<LimiterDump>,
+    /// Page of asset groups
+    pub asset_groups: Vec<AssetGroupDump>,
+    /// Page of alias -> denom
+    pub denom_aliases: Vec<(String, String)>,
+    /// Page of senders allowed or denied depending on the sender access mode
+    pub sender_access_list: Vec<Addr>,
+    pub config: ConfigDump,
+    /// Where to continue the export from, `None` if this is the last page
+    pub next: Option<StateDumpCursor>,
+}
+
+/// Paginated parts of the dump, in the order they are exported.
+#[cw_serde]
+#[derive(Copy, Eq, PartialOrd, Ord)]
+pub enum StateDumpSection {
+    AssetGroups,
+    DenomAliases,
+    SenderAccessList,
+}
+
+/// Position to continue a paginated export from, entries of `section` after the
+/// `start_after` key, or from the start of `section` if `None`.
+/// Pages are filled across sections in [`StateDumpSection`] order.
+#[cw_serde]
+pub struct StateDumpCursor {
+    pub section: StateDumpSection,
+    pub start_after: Option<String>,
+}
+
+#[cw_serde]
+pub struct AlloyedAssetDump {
+    /// `None` if the alloyed asset is disabled
+    pub denom: Option<String>,
+    pub normalization_factor: Uint128,
+}
+
+#[cw_serde]
+pub struct LimiterDump {
+    pub denom: String,
+    pub label: String,
+    pub limiter: Limiter,
+}
+
+#[cw_serde]
+pub struct AssetGroupDump {
+    pub label: String,
+    pub asset_group: AssetGroup,
+}
+
+#[cw_serde]
+pub struct ConfigDump {
+    pub admin: Addr,
+    pub moderator: Addr,
+    /// Whether the pool is active at the block time of the export
+    pub is_active: bool,
+    /// `None` while the pool is active
+    pub pause_scope: Option<PauseScope>,
+    /// `None` while the pool is active or paused indefinitely
+    pub reactivate_at: Option<Timestamp>,
+    pub swap_fee: Decimal,
+    pub fee_collector: Option<Addr>,
+    pub fee_split: Decimal,
+    pub fee_side: FeeSide,
+    pub dynamic_fee_params: Option<DynamicFeeParams>,
+    pub denom_swap_fees: Vec<(String, Decimal)>,
+    pub max_swap_value: Option<Uint128>,
+    pub max_total_liquidity: Option<Uint128>,
+    pub amount_tolerance: Option<Uint128>,
+    pub large_swap_cooldown: Option<LargeSwapCooldown>,
+    pub mint_rate_limit: Option<MintRateLimit>,
+    pub min_locked_shares: Option<Uint128>,
+    pub dust_threshold: Option<Uint128>,
+    pub corruption_drain_incentive: Option<Decimal>,
+    pub target_weights: Vec<(String, Decimal)>,
+    pub require_limiter_coverage: bool,
+    pub max_asset_groups: Option<u32>,
+    pub min_active_assets: Option<u32>,
+    pub pool_id: Option<u64>,
+    pub auto_corruption_configs: Vec<(String, AutoCorruptionConfig)>,
+    pub sender_access_mode: SenderAccessMode,
+    pub allow_full_drain: bool,
+    pub enable_rounding_audit: bool,
+    /// Denom -> display decimals, including the alloyed asset denom
+    pub display_decimals: Vec<(String, u32)>,
+    pub limiter_stress_config: Option<StressConfig>,
+}
+
+/// Fills a page of the dump across [`StateDumpSection`]s, starting from a cursor.
+struct StateDumpPager {
+    start_after: Option<StateDumpCursor>,
+    remaining: usize,
+    next: Option<StateDumpCursor>,
+}
+
+impl StateDumpPager {
+    /// Entries of `section` on this page, where `range` lists up to the given number
+    /// of entries after the given key, in key order.
+    fn page<T>(
+        &mut self,
+        section: StateDumpSection,
+        range: impl FnOnce(Option<&str>, usize) -> StdResult<Vec<(String, T)>>,
+    ) -> StdResult<Vec<(String, T)>> {
+        let start_after = match &self.start_after {
+            Some(cursor) if cursor.section > section => return Ok(vec![]),
+            Some(cursor) if cursor.section == section => cursor.start_after.clone(),
+            _ => None,
+        };
+
+        // page is already full
+        if self.next.is_some() {
+            return Ok(vec![]);
+        }
+
+        // one more entry than fits tells whether the section continues on the next page
+        let mut entries = range(start_after.as_deref(), self.remaining + 1)?;
+        if entries.len() > self.remaining {
+            entries.truncate(self.remaining);
+            self.next = Some(StateDumpCursor {
+                section,
+                start_after: entries.last().map(|(key, _)| key.clone()).or(start_after),
+            });
+        }
+        self.remaining -= entries.len();
+
+        Ok(entries)
+    }
+}
+
+impl Transmuter<'_> {
+    /// Dump the contract state as of `block_time`, read-only.
+    /// Asset groups, denom aliases and the sender access list are paginated with up to `limit`
+    /// entries in total per page.
+    pub fn export_state_dump(
+        &self,
+        deps: Deps,
+        block_time: Timestamp,
+        start_after: Option<StateDumpCursor>,
+        limit: Option<u32>,
+    ) -> Result<FullStateDump, ContractError> {
+        let storage = deps.storage;
+
+        let limiters = self
+            .limiters
+            .list_limiters(storage)?
+            .into_iter()
+            .map(|((denom, label), limiter)| LimiterDump {
+                denom,
+                label,
+                limiter,
+            })
+            .collect();
+
+        let mut pager = StateDumpPager {
+            start_after,
+            remaining: limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize,
+            next: None,
+        };
+
+        let asset_groups = pager
+            .page(StateDumpSection::AssetGroups, |start_after, limit| {
+                self.asset_groups.list_page(storage, start_after, limit)
+            })?
+            .into_iter()
+            .map(|(label, asset_group)| AssetGroupDump { label, asset_group })
+            .collect();
+
+        let denom_aliases = pager.page(StateDumpSection::DenomAliases, |start_after, limit| {
+            self.denom_aliases
+                .range(
+                    storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .collect()
+        })?;
+
+        let sender_access_list = pager
+            .page(StateDumpSection::SenderAccessList, |start_after, limit| {
+                let start_after = start_after.map(Addr::unchecked);
+                self.sender_access_list
+                    .keys(
+                        storage,
+                        start_after.as_ref().map(Bound::exclusive),
+                        None,
+                        Order::Ascending,
+                    )
+                    .take(limit)
+                    .map(|sender| sender.map(|sender| (sender.to_string(), sender)))
+                    .collect()
+            })?
+            .into_iter()
+            .map(|(_, sender)| sender)
+            .collect();
+
+        let is_active = self.is_active_at(storage, block_time)?;
+        let config = ConfigDump {
+            admin: self.role.admin.current(deps)?,
+            moderator: self.role.moderator.get(deps)?,
+            is_active,
+            pause_scope: match is_active {
+                true => None,
+                false => self.pause_scope.may_load(storage)?,
+            },
+            reactivate_at: match is_active {
+                true => None,
+                false => self.reactivate_at.may_load(storage)?,
+            },
+            swap_fee: self.fee.get_swap_fee(storage)?,
+            fee_collector: self.fee.get_fee_collector(storage)?,
+            fee_split: self.fee.get_fee_split(storage)?,
+            fee_side: self.fee.get_fee_side(storage)?,
+            dynamic_fee_params: self.fee.get_dynamic_fee_params(storage)?,
+            denom_swap_fees: self.fee.list_denom_swap_fees(storage)?,
+            max_swap_value: self.max_swap_value.may_load(storage)?,
+            max_total_liquidity: self.max_total_liquidity.may_load(storage)?,
+            amount_tolerance: self.amount_tolerance.may_load(storage)?,
+            large_swap_cooldown: self.large_swap_cooldown.may_load(storage)?,
+            mint_rate_limit: self.mint_rate_limit.may_load(storage)?,
+            min_locked_shares: self.min_locked_shares.may_load(storage)?,
+            dust_threshold: self.dust_threshold.may_load(storage)?,
+            corruption_drain_incentive: self.corruption_drain_incentive.may_load(storage)?,
+            target_weights: self
+                .target_weights
+                .range(storage, None, None, Order::Ascending)
+                .collect::<StdResult<_>>()?,
+            require_limiter_coverage: self
+                .require_limiter_coverage
+                .may_load(storage)?
+                .unwrap_or_default(),
+            max_asset_groups: self.max_asset_groups.may_load(storage)?,
+            min_active_assets: self.min_active_assets.may_load(storage)?,
+            pool_id: self.pool_id.may_load(storage)?,
+            auto_corruption_configs: self
+                .auto_corruption_configs
+                .range(storage, None, None, Order::Ascending)
+                .collect::<StdResult<_>>()?,
+            sender_access_mode: self
+                .sender_access_mode
+                .may_load(storage)?
+                .unwrap_or_default(),
+            allow_full_drain: self.allow_full_drain.may_load(storage)?.unwrap_or_default(),
+            enable_rounding_audit: self
+                .enable_rounding_audit
+                .may_load(storage)?
+                .unwrap_or_default(),
+            display_decimals: self
+                .display_decimals
+                .range(storage, None, None, Order::Ascending)
+                .collect::<StdResult<_>>()?,
+            limiter_stress_config: self.limiters.get_stress_config(storage)?,
+        };
+
+        Ok(FullStateDump {
+            version: STATE_DUMP_VERSION,
+            contract_version: cw2::get_contract_version(storage)?.version,
+            pool: self.pool.load(storage)?,
+            directly_corrupted_denoms: self
+                .directly_corrupted_denoms
+                .keys(storage, None, None, Order::Ascending)
+                .collect::<StdResult<_>>()?,
+            accrued_fees: self
+                .accrued_fees
+                .range(storage, None, None, Order::Ascending)
+                .collect::<StdResult<_>>()?,
+            alloyed_asset: AlloyedAssetDump {
+                denom: self.alloyed_asset.may_get_alloyed_denom(storage)?,
+                normalization_factor: self.alloyed_asset.get_normalization_factor(storage)?,
+            },
+            registered_alloyed_assets: self.alloyed_registry.list(storage)?,
+            limiters,
+            asset_groups,
+            denom_aliases,
+            sender_access_list,
+            config,
+            next: pager.next,
+        })
+    }
+
+    /// Reconstruct limiters, asset groups, denom aliases, sender access list and config from `dump`
+    /// on a fresh contract,
+    /// i.e. one without liquidity, limiters or asset groups. All pages of the dump must be merged
+    /// into `dump` beforehand.
+    ///
+    /// Limiters are registered anew from their params, since their tracked state belongs to
+    /// the pool they were exported from. Likewise, corruption and accrued fees are left out
+    /// as they are pool state. Roles, pool id and registered alloyed assets are not imported,
+    /// as they are specific to the deployment.
+    pub fn import_state_dump(
+        &self,
+        storage: &mut dyn Storage,
+        dump: FullStateDump,
+    ) -> Result<(), ContractError> {
+        ensure!(
+            dump.version == STATE_DUMP_VERSION,
+            ContractError::UnsupportedStateDumpVersion {
+                expected: STATE_DUMP_VERSION,
+                actual: dump.version,
+            }
+        );
+        ensure!(dump.next.is_none(), ContractError::IncompleteStateDump {});
+
+        let pool = self.pool.load(storage)?;
+        ensure!(
+            pool.pool_assets
+                .iter()
+                .all(|asset| asset.amount().is_zero())
+                && self.limiters.list_limiters(storage)?.is_empty()
+                && self.asset_groups.count(storage) == 0,
+            ContractError::StateNotFresh {}
+        );
+
+        let ensure_pool_denom = |denom: &str| -> Result<(), ContractError> {
+            ensure!(
+                pool.has_denom(denom),
+                ContractError::InvalidPoolAssetDenom {
+                    denom: denom.to_string()
+                }
+            );
+            Ok(())
+        };
+
+        // the whole dump is validated before anything is saved, so that a rejected dump
+        // leaves no partial state behind
+        let mut registered_limiters: Vec<(&str, &str)> = vec![];
+        for LimiterDump {
+            denom,
+            label,
+            limiter,
+        } in dump.limiters.iter()
+        {
+            ensure_pool_denom(denom)?;
+            ensure!(
+                !registered_limiters.contains(&(denom.as_str(), label.as_str())),
+                ContractError::LimiterAlreadyExists {
+                    denom: denom.clone(),
+                    label: label.clone()
+                }
+            );
+
+            let pending_count = registered_limiters
+                .iter()
+                .filter(|(registered_denom, _)| registered_denom == denom)
+                .count() as u64;
+            self.limiters.ensure_registrable(
+                storage,
+                denom,
+                label,
+                limiter.params(),
+                pending_count,
+            )?;
+            registered_limiters.push((denom, label));
+        }
+
+        let mut created_groups: Vec<&str> = vec![];
+        for AssetGroupDump { label, asset_group } in dump.asset_groups.iter() {
+            ensure!(
+                !label.is_empty(),
+                ContractError::NonEmptyInputRequired {
+                    field: "label".to_string()
+                }
+            );
+            ensure!(
+                !created_groups.contains(&label.as_str()),
+                ContractError::AssetGroupAlreadyExists {
+                    label: label.clone()
+                }
+            );
+            for denom in asset_group.denoms() {
+                ensure_pool_denom(denom)?;
+            }
+            created_groups.push(label);
+        }
+
+        let alloyed_denom = self.alloyed_asset.may_get_alloyed_denom(storage)?;
+        let mut registered_aliases: Vec<&str> = vec![];
+        for (alias, denom) in dump.denom_aliases.iter() {
+            ensure!(
+                !alias.is_empty(),
+                ContractError::NonEmptyInputRequired {
+                    field: "alias".to_string()
+                }
+            );
+            ensure_pool_denom(denom)?;
+
+            // alias must not shadow any denom or other alias
+            let is_ambiguous = pool.has_denom(alias)
+                || Some(alias) == alloyed_denom.as_ref()
+                || registered_aliases.contains(&alias.as_str());
+            ensure!(
+                !is_ambiguous,
+                ContractError::AmbiguousDenomAlias {
+                    alias: alias.clone()
+                }
+            );
+            registered_aliases.push(alias);
+        }
+
+        let config = &dump.config;
+        fee::ensure_swap_fee_less_than_one(config.swap_fee)?;
+        fee::ensure_valid_fee_split(config.fee_split, config.fee_collector.is_some())?;
+        if let Some(dynamic_fee_params) = &config.dynamic_fee_params {
+            fee::ensure_swap_fee_less_than_one(dynamic_fee_params.base)?;
+        }
+        for (denom, swap_fee) in config.denom_swap_fees.iter() {
+            ensure_pool_denom(denom)?;
+            fee::ensure_swap_fee_less_than_one(*swap_fee)?;
+        }
+        for (denom, _) in config.target_weights.iter() {
+            ensure_pool_denom(denom)?;
+        }
+        for (denom, _) in config.auto_corruption_configs.iter() {
+            ensure_pool_denom(denom)?;
+        }
+        for (denom, decimals) in config.display_decimals.iter() {
+            if Some(denom) != dump.alloyed_asset.denom.as_ref() {
+                ensure_pool_denom(denom)?;
+            }
+            ensure!(
+                *decimals <= Decimal::DECIMAL_PLACES,
+                ContractError::InvalidDisplayDecimals {
+                    decimals: *decimals,
+                    max_decimals: Decimal::DECIMAL_PLACES,
+                }
+            );
+        }
+        if let Some(stress_config) = &config.limiter_stress_config {
+            stress_config
+                .clone()
+                .ensure_tightening_factor_constraint()?;
+        }
+
+        for LimiterDump {
+            denom,
+            label,
+            limiter,
+        } in dump.limiters
+        {
+            self.limiters
+                .register(storage, &denom, &label, limiter.params())?;
+        }
+
+        for AssetGroupDump { label, asset_group } in dump.asset_groups {
+            self.asset_groups
+                .create(storage, &label, asset_group.denoms().to_vec())?;
+            self.asset_groups.update(storage, &label, |group| {
+                group.set_paused(asset_group.is_paused());
+            })?;
+        }
+
+        for (alias, denom) in dump.denom_aliases {
+            self.denom_aliases.save(storage, &alias, &denom)?;
+        }
+
+        for sender in dump.sender_access_list {
+            self.sender_access_list.save(storage, &sender, &())?;
+        }
+
+        let ConfigDump {
+            admin: _,
+            moderator: _,
+            is_active,
+            pause_scope,
+            reactivate_at,
+            swap_fee,
+            fee_collector,
+            fee_split,
+            fee_side,
+            dynamic_fee_params,
+            denom_swap_fees,
+            max_swap_value,
+            max_total_liquidity,
+            amount_tolerance,
+            large_swap_cooldown,
+            mint_rate_limit,
+            min_locked_shares,
+            dust_threshold,
+            corruption_drain_incentive,
+            target_weights,
+            require_limiter_coverage,
+            max_asset_groups,
+            min_active_assets,
+            pool_id: _,
+            auto_corruption_configs,
+            sender_access_mode,
+            allow_full_drain,
+            enable_rounding_audit,
+            display_decimals,
+            limiter_stress_config,
+        } = dump.config;
+
+        self.active_status.save(storage, &is_active)?;
+        if let Some(pause_scope) = pause_scope {
+            self.pause_scope.save(storage, &pause_scope)?;
+        }
+        if let Some(reactivate_at) = reactivate_at {
+            self.reactivate_at.save(storage, &reactivate_at)?;
+        }
+
+        self.fee.set_swap_fee(storage, swap_fee)?;
+        if let Some(fee_collector) = fee_collector {
+            self.fee.set_fee_collector(storage, fee_collector)?;
+        }
+        self.fee.set_fee_split(storage, fee_split)?;
+        self.fee.set_fee_side(storage, fee_side)?;
+        self.fee
+            .set_dynamic_fee_params(storage, dynamic_fee_params)?;
+        for (denom, swap_fee) in denom_swap_fees {
+            self.fee
+                .set_denom_swap_fee(storage, &denom, Some(swap_fee))?;
+        }
+
+        if let Some(max_swap_value) = max_swap_value {
+            self.max_swap_value.save(storage, &max_swap_value)?;
+        }
+        if let Some(max_total_liquidity) = max_total_liquidity {
+            self.max_total_liquidity
+                .save(storage, &max_total_liquidity)?;
+        }
+        if let Some(amount_tolerance) = amount_tolerance {
+            self.amount_tolerance.save(storage, &amount_tolerance)?;
+        }
+        if let Some(large_swap_cooldown) = large_swap_cooldown {
+            self.large_swap_cooldown
+                .save(storage, &large_swap_cooldown)?;
+        }
+        if let Some(mint_rate_limit) = mint_rate_limit {
+            self.mint_rate_limit.save(storage, &mint_rate_limit)?;
+        }
+        if let Some(min_locked_shares) = min_locked_shares {
+            self.min_locked_shares.save(storage, &min_locked_shares)?;
+        }
+        if let Some(dust_threshold) = dust_threshold {
+            self.dust_threshold.save(storage, &dust_threshold)?;
+        }
+        if let Some(corruption_drain_incentive) = corruption_drain_incentive {
+            self.corruption_drain_incentive
+                .save(storage, &corruption_drain_incentive)?;
+        }
+        for (denom, weight) in target_weights {
+            self.target_weights.save(storage, &denom, &weight)?;
+        }
+        self.require_limiter_coverage
+            .save(storage, &require_limiter_coverage)?;
+        if let Some(max_asset_groups) = max_asset_groups {
+            self.max_asset_groups.save(storage, &max_asset_groups)?;
+        }
+        for (denom, config) in auto_corruption_configs {
+            self.auto_corruption_configs
+                .save(storage, &denom, &config)?;
+        }
+        self.sender_access_mode.save(storage, &sender_access_mode)?;
+        self.allow_full_drain.save(storage, &allow_full_drain)?;
+        self.enable_rounding_audit
+            .save(storage, &enable_rounding_audit)?;
+        if let Some(min_active_assets) = min_active_assets {
+            self.min_active_assets.save(storage, &min_active_assets)?;
+        }
+        for (denom, decimals) in display_decimals {
+            // alloyed asset denom is specific to the deployment, so its decimals move over to ours
+            let denom = if Some(&denom) == dump.alloyed_asset.denom.as_ref() {
+                match &alloyed_denom {
+                    Some(alloyed_denom) => alloyed_denom.clone(),
+                    None => continue,
+                }
+            } else {
+                denom
+            };
+            self.display_decimals.save(storage, &denom, &decimals)?;
+        }
+        self.limiters
+            .set_stress_config(storage, limiter_stress_config)?;
+
+        Ok(())
+    }
+}
@@ -1,40 +1,71 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{ensure, Coin, Decimal, DepsMut, Env, Response, Uint128};
+use cosmwasm_std::{
+    ensure, BankMsg, Coin, Decimal, DepsMut, Env, Response, Storage, Timestamp, Uint128,
+};
 
 use crate::{
-    contract::Transmuter,
+    contract::{PauseScope, Transmuter},
     swap::{
         BurnTarget, Entrypoint, SwapFromAlloyedConstraint, SwapToAlloyedConstraint, SwapVariant,
     },
+    transmuter_pool::TransmuterPool,
     ContractError,
 };
 
 #[cw_serde]
 pub enum SudoMsg {
+    /// Set active status of the pool. When deactivating, `scope` determines
+    /// which operations are paused, defaults to [`PauseScope::All`].
+    /// If `reactivate_at` is set, the pause lifts by itself once the block time reaches it.
     SetActive {
         is_active: bool,
+        #[serde(default)]
+        scope: Option<PauseScope>,
+        #[serde(default)]
+        reactivate_at: Option<Timestamp>,
     },
     /// SwapExactAmountIn swaps an exact amount of tokens in for as many tokens out as possible.
     /// The amount of tokens out is determined by the current exchange rate and the swap fee.
     /// The user specifies a minimum amount of tokens out, and the transaction will revert if that amount of tokens
     /// is not received.
+    ///
+    /// If `partial_fill` is true and the swap would breach a limiter, only the maximum amount of
+    /// token in that does not breach any limiter is swapped, and the rest is refunded to the sender.
+    /// In that case, `token_out_min_amount` is scaled down pro-rata to the filled amount.
+    /// Swaps of registered alloyed assets are not subject to limiters, so they reject `partial_fill`.
+    ///
+    /// If `require_improves_balance` is true, the swap reverts unless it reduces pool imbalance.
+    /// Swaps of registered alloyed assets don't change the pool, so they reject it.
+    ///
+    /// `token_out_denom` can be a registered denom alias.
     SwapExactAmountIn {
         sender: String,
         token_in: Coin,
         token_out_denom: String,
         token_out_min_amount: Uint128,
         swap_fee: Decimal,
+        #[serde(default)]
+        partial_fill: bool,
+        #[serde(default)]
+        require_improves_balance: bool,
     },
     /// SwapExactAmountOut swaps as many tokens in as possible for an exact amount of tokens out.
     /// The amount of tokens in is determined by the current exchange rate and the swap fee.
     /// The user specifies a maximum amount of tokens in, and the transaction will revert if that amount of tokens
     /// is exceeded.
+    ///
+    /// If `require_improves_balance` is true, the swap reverts unless it reduces pool imbalance.
+    /// Swaps of registered alloyed assets don't change the pool, so they reject it.
+    ///
+    /// `token_in_denom` can be a registered denom alias.
     SwapExactAmountOut {
         sender: String,
         token_in_denom: String,
         token_in_max_amount: Uint128,
         token_out: Coin,
         swap_fee: Decimal,
+        #[serde(default)]
+        require_improves_balance: bool,
     },
 }
 
@@ -45,9 +76,25 @@ impl SudoMsg {
         ctx: (DepsMut, Env),
     ) -> Result<Response, ContractError> {
         match self {
-            SudoMsg::SetActive { is_active } => {
-                let (deps, _env) = ctx;
-                transmuter.checked_set_active_status(deps.storage, is_active)?;
+            SudoMsg::SetActive {
+                is_active,
+                scope,
+                reactivate_at,
+            } => {
+                let (deps, env) = ctx;
+                transmuter.checked_set_active_status(
+                    deps.storage,
+                    env.block.time,
+                    is_active,
+                    scope,
+                    reactivate_at,
+                )?;
+                transmuter.governance_log.record(
+                    deps.storage,
+                    "set_active",
+                    None,
+                    env.block.time,
+                )?;
 
                 Ok(Response::new().add_attribute("method", "set_active"))
             }
@@ -57,6 +104,8 @@ impl SudoMsg {
                 token_out_denom,
                 token_out_min_amount,
                 swap_fee,
+                partial_fill,
+                require_improves_balance,
             } => {
                 // ensure non-zero token_in amount
                 ensure!(
@@ -64,10 +113,77 @@ impl SudoMsg {
                     ContractError::ZeroValueOperation {}
                 );
 
-                transmuter.ensure_valid_swap_fee(swap_fee)?;
+                let (mut deps, env) = ctx;
 
-                let (deps, env) = ctx;
+                transmuter.ensure_valid_swap_fee(deps.storage, swap_fee)?;
                 let sender = deps.api.addr_validate(&sender)?;
+                transmuter.ensure_sender_allowed(deps.storage, &sender)?;
+                let token_out_denom = transmuter.resolve_denom(deps.storage, token_out_denom)?;
+
+                // registered alloyed assets are settled against their own backing
+                if transmuter.is_registered_alloyed_swap(
+                    deps.storage,
+                    &token_in.denom,
+                    &token_out_denom,
+                ) {
+                    ensure!(!partial_fill, ContractError::PartialFillNotSupported {});
+                    ensure!(
+                        !require_improves_balance,
+                        ContractError::ImprovesBalanceNotSupported {}
+                    );
+
+                    return transmuter
+                        .swap_registered_alloyed_exact_amount_in(
+                            token_in,
+                            &token_out_denom,
+                            token_out_min_amount,
+                            sender,
+                            deps,
+                            env,
+                        )
+                        .map(|res| res.add_attribute("method", "swap_exact_amount_in"));
+                }
+
+                // if no amount can be filled, swap the full amount to surface the limiter error
+                let filled_amount = if partial_fill {
+                    transmuter
+                        .max_fillable_token_in_amount(
+                            deps.as_ref(),
+                            env.block.time,
+                            &token_in,
+                            &token_out_denom,
+                        )?
+                        .unwrap_or(token_in.amount)
+                } else {
+                    token_in.amount
+                };
+
+                if filled_amount < token_in.amount {
+                    transmuter.record_partial_fill_trips(
+                        deps.branch(),
+                        env.block.time,
+                        &token_in,
+                        &token_out_denom,
+                    )?;
+                }
+
+                let unfilled_token_in = Coin::new(
+                    token_in.amount.checked_sub(filled_amount)?.u128(),
+                    &token_in.denom,
+                );
+                let token_out_min_amount =
+                    token_out_min_amount.multiply_ratio(filled_amount, token_in.amount);
+                let token_in = Coin::new(filled_amount.u128(), token_in.denom);
+                let refund_to_address = sender.to_string();
+
+                if require_improves_balance {
+                    let (pool_after, _, _) = transmuter.out_amt_given_in(
+                        deps.as_ref(),
+                        token_in.clone(),
+                        &token_out_denom,
+                    )?;
+                    ensure_improves_balance(transmuter, deps.storage, &pool_after)?;
+                }
 
                 let swap_variant =
                     transmuter.swap_variant(&token_in.denom, &token_out_denom, deps.as_ref())?;
@@ -104,7 +220,20 @@ impl SudoMsg {
                         env,
                     ),
                 }
-                .map(|res| res.add_attribute("method", "swap_exact_amount_in"))
+                .map(|res| {
+                    let res = res.add_attribute("method", "swap_exact_amount_in");
+
+                    if unfilled_token_in.amount.is_zero() {
+                        return res;
+                    }
+
+                    // refund unfilled token in
+                    res.add_attribute("unfilled_token_in", unfilled_token_in.to_string())
+                        .add_message(BankMsg::Send {
+                            to_address: refund_to_address,
+                            amount: vec![unfilled_token_in],
+                        })
+                })
             }
             SudoMsg::SwapExactAmountOut {
                 sender,
@@ -112,6 +241,7 @@ impl SudoMsg {
                 token_in_max_amount,
                 token_out,
                 swap_fee,
+                require_improves_balance,
             } => {
                 // ensure non-zero token_out amount
                 ensure!(
@@ -119,11 +249,45 @@ impl SudoMsg {
                     ContractError::ZeroValueOperation {}
                 );
 
-                transmuter.ensure_valid_swap_fee(swap_fee)?;
-
                 let (deps, env) = ctx;
 
+                transmuter.ensure_valid_swap_fee(deps.storage, swap_fee)?;
+
                 let sender = deps.api.addr_validate(&sender)?;
+                transmuter.ensure_sender_allowed(deps.storage, &sender)?;
+                let token_in_denom = transmuter.resolve_denom(deps.storage, token_in_denom)?;
+
+                // registered alloyed assets are settled against their own backing
+                if transmuter.is_registered_alloyed_swap(
+                    deps.storage,
+                    &token_in_denom,
+                    &token_out.denom,
+                ) {
+                    ensure!(
+                        !require_improves_balance,
+                        ContractError::ImprovesBalanceNotSupported {}
+                    );
+
+                    return transmuter
+                        .swap_registered_alloyed_exact_amount_out(
+                            &token_in_denom,
+                            token_in_max_amount,
+                            token_out,
+                            sender,
+                            deps,
+                            env,
+                        )
+                        .map(|res| res.add_attribute("method", "swap_exact_amount_out"));
+                }
+
+                if require_improves_balance {
+                    let (pool_after, _, _) = transmuter.in_amt_given_out(
+                        deps.as_ref(),
+                        token_out.clone(),
+                        token_in_denom.clone(),
+                    )?;
+                    ensure_improves_balance(transmuter, deps.storage, &pool_after)?;
+                }
 
                 let swap_variant =
                     transmuter.swap_variant(&token_in_denom, &token_out.denom, deps.as_ref())?;
@@ -166,18 +330,44 @@ impl SudoMsg {
     }
 }
 
+/// Ensure that the swap strictly reduces pool imbalance,
+/// given the simulated pool state after the swap.
+fn ensure_improves_balance(
+    transmuter: &Transmuter,
+    storage: &dyn Storage,
+    pool_after: &TransmuterPool,
+) -> Result<(), ContractError> {
+    let imbalance_before = transmuter.pool.load(storage)?.imbalance()?;
+    let imbalance_after = pool_after.imbalance()?;
+
+    ensure!(
+        imbalance_after < imbalance_before,
+        ContractError::WouldWorsenBalance {
+            imbalance_before,
+            imbalance_after,
+        }
+    );
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         asset::AssetConfig,
         contract::sv::{ContractExecMsg, ExecMsg, InstantiateMsg},
-        execute, instantiate, reply, sudo,
-        swap::{SwapExactAmountInResponseData, SwapExactAmountOutResponseData},
+        execute, instantiate,
+        limiter::LimiterParams,
+        reply, sudo,
+        swap::{
+            alloyed_asset_transfer_event, SwapExactAmountInResponseData,
+            SwapExactAmountOutResponseData,
+        },
     };
     use cosmwasm_std::{
         testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR},
-        to_json_binary, BankMsg, Reply, SubMsgResponse, SubMsgResult,
+        to_json_binary, Reply, SubMsgResponse, SubMsgResult,
     };
     use osmosis_std::types::osmosis::tokenfactory::v1beta1::{
         MsgBurn, MsgCreateDenomResponse, MsgMint,
@@ -204,6 +394,7 @@ mod tests {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: Some(admin.to_string()),
             moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
         let info = mock_info(admin, &[]);
@@ -232,7 +423,9 @@ mod tests {
         )
         .unwrap();
 
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
         execute(
             deps.as_mut(),
             env.clone(),
@@ -254,6 +447,8 @@ mod tests {
             token_out_denom: "whusdc".to_string(),
             token_out_min_amount: Uint128::from(0u128),
             swap_fee: Decimal::zero(),
+            partial_fill: false,
+            require_improves_balance: false,
         };
 
         let err = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap_err();
@@ -266,6 +461,8 @@ mod tests {
             token_out_denom: "whusdc".to_string(),
             token_out_min_amount: Uint128::from(500u128),
             swap_fee: Decimal::zero(),
+            partial_fill: false,
+            require_improves_balance: false,
         };
 
         let res = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap();
@@ -294,12 +491,20 @@ mod tests {
             token_out_denom: "whusdc".to_string(),
             token_out_min_amount: Uint128::from(500u128),
             swap_fee: Decimal::zero(),
+            partial_fill: false,
+            require_improves_balance: false,
         };
 
         let res = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap();
 
         let expected = Response::new()
             .add_attribute("method", "swap_exact_amount_in")
+            .add_event(alloyed_asset_transfer_event(
+                "burn",
+                env.contract.address.as_str(),
+                "",
+                &Coin::new(500, alloyed_denom),
+            ))
             .add_message(MsgBurn {
                 amount: Some(Coin::new(500, alloyed_denom).into()),
                 sender: env.contract.address.to_string(),
@@ -325,12 +530,20 @@ mod tests {
             token_out_denom: alloyed_denom.to_string(),
             token_out_min_amount: Uint128::from(500u128),
             swap_fee: Decimal::zero(),
+            partial_fill: false,
+            require_improves_balance: false,
         };
 
         let res = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap();
 
         let expected = Response::new()
             .add_attribute("method", "swap_exact_amount_in")
+            .add_event(alloyed_asset_transfer_event(
+                "mint",
+                "",
+                user,
+                &Coin::new(500, alloyed_denom),
+            ))
             .add_message(MsgMint {
                 sender: env.contract.address.to_string(),
                 amount: Some(Coin::new(500, alloyed_denom).into()),
@@ -352,6 +565,8 @@ mod tests {
             token_out_denom: "axlusdc".to_string(),
             token_out_min_amount: Uint128::from(1000u128), // set min amount greater than token_in
             swap_fee: Decimal::zero(),
+            partial_fill: false,
+            require_improves_balance: false,
         };
 
         let res = sudo(deps.as_mut(), env.clone(), swap_msg);
@@ -371,6 +586,8 @@ mod tests {
             token_out_denom: "axlusdc".to_string(),
             token_out_min_amount: Uint128::from(1000u128), // set min amount greater than token_in
             swap_fee: Decimal::zero(),
+            partial_fill: false,
+            require_improves_balance: false,
         };
 
         let res = sudo(deps.as_mut(), env.clone(), swap_msg);
@@ -390,6 +607,8 @@ mod tests {
             token_out_denom: alloyed_denom.to_string(),
             token_out_min_amount: Uint128::from(1000u128), // set min amount greater than token_in
             swap_fee: Decimal::zero(),
+            partial_fill: false,
+            require_improves_balance: false,
         };
 
         let res = sudo(deps.as_mut(), env, swap_msg);
@@ -403,6 +622,190 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_swap_exact_amount_in_with_partial_fill() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![Coin::new(1, "axlusdc"), Coin::new(1, "whusdc")],
+        );
+
+        let admin = "admin";
+        let user = "user";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("axlusdc"),
+                AssetConfig::from_denom_str("whusdc"),
+            ],
+            alloyed_asset_subdenom: "uusdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            admin: Some(admin.to_string()),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "uusdc";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                user,
+                &[Coin::new(1000, "axlusdc"), Coin::new(1000, "whusdc")],
+            ),
+            join_pool_msg,
+        )
+        .unwrap();
+
+        // axlusdc weight must not exceed 60%
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ContractExecMsg::Transmuter(ExecMsg::RegisterLimiter {
+                denom: "axlusdc".to_string(),
+                label: "static".to_string(),
+                limiter_params: LimiterParams::StaticLimiter {
+                    upper_limit: Decimal::percent(60),
+                },
+            }),
+        )
+        .unwrap();
+
+        // swap that breaches the limiter is rejected without partial fill
+        let swap_msg = SudoMsg::SwapExactAmountIn {
+            sender: user.to_string(),
+            token_in: Coin::new(500, "axlusdc"),
+            token_out_denom: "whusdc".to_string(),
+            token_out_min_amount: Uint128::from(500u128),
+            swap_fee: Decimal::zero(),
+            partial_fill: false,
+            require_improves_balance: false,
+        };
+
+        let err = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UpperLimitExceeded {
+                denom: "axlusdc".to_string(),
+                upper_limit: Decimal::percent(60),
+                value: Decimal::percent(75),
+            }
+        );
+
+        // swap within the limiter is fully filled
+        let swap_msg = SudoMsg::SwapExactAmountIn {
+            sender: user.to_string(),
+            token_in: Coin::new(100, "axlusdc"),
+            token_out_denom: "whusdc".to_string(),
+            token_out_min_amount: Uint128::from(100u128),
+            swap_fee: Decimal::zero(),
+            partial_fill: true,
+            require_improves_balance: false,
+        };
+
+        let res = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap();
+
+        let expected = Response::new()
+            .add_attribute("method", "swap_exact_amount_in")
+            .add_message(BankMsg::Send {
+                to_address: user.to_string(),
+                amount: vec![Coin::new(100, "whusdc")],
+            })
+            .set_data(
+                to_json_binary(&SwapExactAmountInResponseData {
+                    token_out_amount: Uint128::from(100u128),
+                })
+                .unwrap(),
+            );
+
+        assert_eq!(res, expected);
+
+        // swap that breaches the limiter is filled up to the limiter boundary:
+        // (1100 + x) / 2000 <= 60% => x <= 100
+        let swap_msg = SudoMsg::SwapExactAmountIn {
+            sender: user.to_string(),
+            token_in: Coin::new(500, "axlusdc"),
+            token_out_denom: "whusdc".to_string(),
+            token_out_min_amount: Uint128::from(500u128),
+            swap_fee: Decimal::zero(),
+            partial_fill: true,
+            require_improves_balance: false,
+        };
+
+        let res = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap();
+
+        let expected = Response::new()
+            .add_attribute("method", "swap_exact_amount_in")
+            .add_attribute("unfilled_token_in", "400axlusdc")
+            .add_message(BankMsg::Send {
+                to_address: user.to_string(),
+                amount: vec![Coin::new(100, "whusdc")],
+            })
+            .add_message(BankMsg::Send {
+                to_address: user.to_string(),
+                amount: vec![Coin::new(400, "axlusdc")],
+            })
+            .set_data(
+                to_json_binary(&SwapExactAmountInResponseData {
+                    token_out_amount: Uint128::from(100u128),
+                })
+                .unwrap(),
+            );
+
+        assert_eq!(res, expected);
+
+        // nothing can be filled, limiter error is returned
+        let swap_msg = SudoMsg::SwapExactAmountIn {
+            sender: user.to_string(),
+            token_in: Coin::new(500, "axlusdc"),
+            token_out_denom: "whusdc".to_string(),
+            token_out_min_amount: Uint128::from(500u128),
+            swap_fee: Decimal::zero(),
+            partial_fill: true,
+            require_improves_balance: false,
+        };
+
+        let err = sudo(deps.as_mut(), env, swap_msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::UpperLimitExceeded {
+                denom: "axlusdc".to_string(),
+                upper_limit: Decimal::percent(60),
+                value: Decimal::percent(85),
+            }
+        );
+    }
+
     #[test]
     fn test_swap_exact_token_out() {
         let mut deps = mock_dependencies();
@@ -424,6 +827,7 @@ mod tests {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: Some(admin.to_string()),
             moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
         };
         let env = mock_env();
         let info = mock_info(admin, &[]);
@@ -452,7 +856,9 @@ mod tests {
         )
         .unwrap();
 
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+            min_shares_out: None,
+        });
         execute(
             deps.as_mut(),
             env.clone(),
@@ -474,6 +880,7 @@ mod tests {
             token_out: Coin::new(0, "axlusdc".to_string()),
             token_in_max_amount: Uint128::from(0u128),
             swap_fee: Decimal::zero(),
+            require_improves_balance: false,
         };
 
         let err = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap_err();
@@ -486,6 +893,7 @@ mod tests {
             token_in_max_amount: Uint128::from(500u128),
             token_out: Coin::new(500, "whusdc".to_string()),
             swap_fee: Decimal::zero(),
+            require_improves_balance: false,
         };
 
         let res = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap();
@@ -515,12 +923,19 @@ mod tests {
             token_in_max_amount: Uint128::from(500u128),
             token_out: Coin::new(500, "whusdc".to_string()),
             swap_fee: Decimal::zero(),
+            require_improves_balance: false,
         };
 
         let res = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap();
 
         let expected = Response::new()
             .add_attribute("method", "swap_exact_amount_out")
+            .add_event(alloyed_asset_transfer_event(
+                "burn",
+                env.contract.address.as_str(),
+                "",
+                &Coin::new(500, alloyed_denom),
+            ))
             .add_message(MsgBurn {
                 amount: Some(Coin::new(500, alloyed_denom).into()),
                 sender: env.contract.address.to_string(),
@@ -546,12 +961,19 @@ mod tests {
             token_in_max_amount: Uint128::from(500u128),
             token_out: Coin::new(500, alloyed_denom.to_string()),
             swap_fee: Decimal::zero(),
+            require_improves_balance: false,
         };
 
         let res = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap();
 
         let expected = Response::new()
             .add_attribute("method", "swap_exact_amount_out")
+            .add_event(alloyed_asset_transfer_event(
+                "mint",
+                "",
+                user,
+                &Coin::new(500, alloyed_denom),
+            ))
             .add_message(MsgMint {
                 sender: env.contract.address.to_string(),
                 amount: Some(Coin::new(500, alloyed_denom).into()),
@@ -573,6 +995,7 @@ mod tests {
             token_in_max_amount: Uint128::from(500u128), // set max amount less than token_out
             token_out: Coin::new(1000, "axlusdc".to_string()),
             swap_fee: Decimal::zero(),
+            require_improves_balance: false,
         };
 
         let res = sudo(deps.as_mut(), env.clone(), swap_msg);
@@ -592,6 +1015,7 @@ mod tests {
             token_in_max_amount: Uint128::from(500u128), // set max amount less than token_out
             token_out: Coin::new(1000, "axlusdc".to_string()),
             swap_fee: Decimal::zero(),
+            require_improves_balance: false,
         };
 
         let res = sudo(deps.as_mut(), env.clone(), swap_msg);
@@ -611,6 +1035,7 @@ mod tests {
             token_in_max_amount: Uint128::from(500u128), // set max amount less than token_out
             token_out: Coin::new(1000, alloyed_denom.to_string()),
             swap_fee: Decimal::zero(),
+            require_improves_balance: false,
         };
 
         let res = sudo(deps.as_mut(), env, swap_msg);
@@ -623,4 +1048,145 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_swap_require_improves_balance() {
+        let mut deps = mock_dependencies();
+
+        // make denom has non-zero total supply
+        deps.querier.update_balance(
+            "someone",
+            vec![Coin::new(1, "axlusdc"), Coin::new(1, "whusdc")],
+        );
+
+        let admin = "admin";
+        let user = "user";
+        let init_msg = InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("axlusdc"),
+                AssetConfig::from_denom_str("whusdc"),
+            ],
+            alloyed_asset_subdenom: "uusdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            admin: Some(admin.to_string()),
+            moderator: "moderator".to_string(),
+            enable_alloyed_asset: None,
+        };
+        let env = mock_env();
+        let info = mock_info(admin, &[]);
+
+        // Instantiate the contract.
+        instantiate(deps.as_mut(), env.clone(), info, init_msg).unwrap();
+
+        // Manually reply
+        let alloyed_denom = "uusdc";
+
+        reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: 1,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        MsgCreateDenomResponse {
+                            new_token_denom: alloyed_denom.to_string(),
+                        }
+                        .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        // weights = 25:75, imbalance = 0.25 + 0.25 = 0.5
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(
+                user,
+                &[
+                    Coin::new(1_000_000, "axlusdc"),
+                    Coin::new(3_000_000, "whusdc"),
+                ],
+            ),
+            ContractExecMsg::Transmuter(ExecMsg::JoinPool {
+                min_shares_out: None,
+            }),
+        )
+        .unwrap();
+
+        // flag defaults to false when omitted
+        let swap_msg: SudoMsg = cosmwasm_std::from_json(
+            r#"{"swap_exact_amount_out":{"sender":"user","token_in_denom":"axlusdc","token_in_max_amount":"1","token_out":{"denom":"whusdc","amount":"1"},"swap_fee":"0"}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            swap_msg,
+            SudoMsg::SwapExactAmountOut {
+                require_improves_balance: false,
+                ..
+            }
+        ));
+
+        // balancing swap, weights = 37.5:62.5, imbalance = 0.25
+        let swap_msg = SudoMsg::SwapExactAmountIn {
+            sender: user.to_string(),
+            token_in: Coin::new(500_000, "axlusdc"),
+            token_out_denom: "whusdc".to_string(),
+            token_out_min_amount: Uint128::one(),
+            swap_fee: Decimal::zero(),
+            partial_fill: false,
+            require_improves_balance: true,
+        };
+        sudo(deps.as_mut(), env.clone(), swap_msg).unwrap();
+
+        // unbalancing swap, weights would be 35:65, imbalance = 0.3
+        let swap_msg = SudoMsg::SwapExactAmountIn {
+            sender: user.to_string(),
+            token_in: Coin::new(100_000, "whusdc"),
+            token_out_denom: "axlusdc".to_string(),
+            token_out_min_amount: Uint128::one(),
+            swap_fee: Decimal::zero(),
+            partial_fill: false,
+            require_improves_balance: true,
+        };
+        let err = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::WouldWorsenBalance {
+                imbalance_before: Decimal::percent(25),
+                imbalance_after: Decimal::percent(30),
+            }
+        );
+
+        // unbalancing exact out swap also reverts
+        let swap_msg = SudoMsg::SwapExactAmountOut {
+            sender: user.to_string(),
+            token_in_denom: "whusdc".to_string(),
+            token_in_max_amount: Uint128::new(100_000),
+            token_out: Coin::new(100_000, "axlusdc"),
+            swap_fee: Decimal::zero(),
+            require_improves_balance: true,
+        };
+        let err = sudo(deps.as_mut(), env.clone(), swap_msg).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::WouldWorsenBalance {
+                imbalance_before: Decimal::percent(25),
+                imbalance_after: Decimal::percent(30),
+            }
+        );
+
+        // the same swap without the flag goes through
+        let swap_msg = SudoMsg::SwapExactAmountOut {
+            sender: user.to_string(),
+            token_in_denom: "whusdc".to_string(),
+            token_in_max_amount: Uint128::new(100_000),
+            token_out: Coin::new(100_000, "axlusdc"),
+            swap_fee: Decimal::zero(),
+            require_improves_balance: false,
+        };
+        sudo(deps.as_mut(), env, swap_msg).unwrap();
+    }
 }
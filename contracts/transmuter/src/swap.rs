@@ -1,20 +1,148 @@
+use std::iter;
+
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    ensure, ensure_eq, to_json_binary, Addr, BankMsg, Coin, Decimal, Deps, DepsMut, Env, Response,
-    StdError, Storage, Uint128,
+    ensure, ensure_eq, to_json_binary, Addr, BankMsg, Coin, Decimal, Decimal256, Deps, DepsMut,
+    Env, Event, Int256, Response, SignedDecimal256, StdError, StdResult, Storage, Timestamp,
+    Uint128, Uint64,
 };
 use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgBurn, MsgMint};
 use serde::Serialize;
 
 use crate::{
     alloyed_asset::{swap_from_alloyed, swap_to_alloyed},
+    alloyed_registry::RegisteredAlloyedAsset,
+    asset::{convert_amount, Asset, Rounding},
     contract::Transmuter,
+    fee::{ensure_swap_fee_less_than_one, FeeCharge, FeeSide},
+    rounding_audit::RoundingResidual,
     transmuter_pool::{AmountConstraint, TransmuterPool},
     ContractError,
 };
 
-/// Swap fee is hardcoded to zero intentionally.
-pub const SWAP_FEE: Decimal = Decimal::zero();
+/// Type of the event emitted alongside every alloyed asset mint and burn,
+/// so that generic indexers following cw20 conventions can track alloyed asset balances.
+///
+/// Attributes:
+/// - `action`: `mint` or `burn`
+/// - `from`: address alloyed asset is burned from, empty on mint
+/// - `to`: address alloyed asset is minted to, empty on burn
+/// - `amount`: minted or burned amount with denom, e.g. `100factory/.../alloyed/usdc`
+pub const ALLOYED_ASSET_TRANSFER_EVENT: &str = "alloyed_asset_transfer";
+
+pub(crate) fn alloyed_asset_transfer_event(
+    action: &str,
+    from: &str,
+    to: &str,
+    amount: &Coin,
+) -> Event {
+    Event::new(ALLOYED_ASSET_TRANSFER_EVENT)
+        .add_attribute("action", action)
+        .add_attribute("from", from)
+        .add_attribute("to", to)
+        .add_attribute("amount", amount.to_string())
+}
+
+fn ensure_min_token_out(
+    amount_out: Uint128,
+    token_out_min_amount: Uint128,
+) -> Result<(), ContractError> {
+    ensure!(
+        amount_out >= token_out_min_amount,
+        ContractError::InsufficientTokenOut {
+            min_required: token_out_min_amount,
+            amount_out
+        }
+    );
+    Ok(())
+}
+
+fn ensure_max_token_in(
+    required: Uint128,
+    token_in_max_amount: Uint128,
+) -> Result<(), ContractError> {
+    ensure!(
+        required <= token_in_max_amount,
+        ContractError::ExcessiveRequiredTokenIn {
+            limit: token_in_max_amount,
+            required
+        }
+    );
+    Ok(())
+}
+
+/// Reject a swap whose token in is too small to produce any token out,
+/// rather than charging the user for nothing.
+fn ensure_output_not_rounded_to_zero(
+    amount_out: Uint128,
+    token_in: &Coin,
+) -> Result<(), ContractError> {
+    ensure!(
+        !amount_out.is_zero(),
+        ContractError::OutputRoundsToZero {
+            token_in: token_in.clone()
+        }
+    );
+    Ok(())
+}
+
+/// Pool after adding `tokens_in` and removing `tokens_out`, ignoring fee, for estimating
+/// how far a swap pushes denoms from their target weights before its fee is known.
+/// Tokens that are not pool assets, i.e. alloyed asset, are skipped,
+/// and tokens out are capped at the pool balance.
+fn fee_free_resulting_pool(
+    pool: &TransmuterPool,
+    tokens_in: &[Coin],
+    tokens_out: &[Coin],
+) -> Result<TransmuterPool, ContractError> {
+    let mut pool = pool.clone();
+
+    for asset in pool.pool_assets.iter_mut() {
+        let denom = asset.denom().to_string();
+        let amount_of = |coins: &[Coin]| {
+            coins
+                .iter()
+                .filter(|coin| coin.denom == denom)
+                .try_fold(Uint128::zero(), |acc, coin| acc.checked_add(coin.amount))
+        };
+        let added = amount_of(tokens_in)?;
+        let removed = amount_of(tokens_out)?;
+
+        asset.update_amount(|amount| Ok(amount.checked_add(added)?.saturating_sub(removed)))?;
+    }
+
+    Ok(pool)
+}
+
+/// Whether `actual` differs from `expected` by no more than `tolerance`.
+fn within_tolerance(expected: Uint128, actual: Uint128, tolerance: Uint128) -> bool {
+    expected.abs_diff(actual) <= tolerance
+}
+
+/// Binary search the maximum amount, up to `max`, for which `passes` holds,
+/// assuming `passes` is monotonic. Returns zero if no positive amount passes.
+fn max_passing_amount(
+    max: Uint128,
+    passes: impl Fn(Uint128) -> Result<bool, ContractError>,
+) -> Result<Uint128, ContractError> {
+    if passes(max)? {
+        return Ok(max);
+    }
+
+    // invariant: `low` passes (or is zero), `high` does not
+    let mut low = Uint128::zero();
+    let mut high = max;
+    while high - low > Uint128::one() {
+        let mid = low + (high - low) / Uint128::new(2);
+        if passes(mid)? {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    Ok(low)
+}
 
 impl Transmuter<'_> {
     /// Getting the [SwapVariant] of the swap operation
@@ -32,8 +160,10 @@ impl Transmuter<'_> {
             }
         );
 
-        let alloyed_denom = self.alloyed_asset.get_alloyed_denom(deps.storage)?;
-        let alloyed_denom = alloyed_denom.as_str();
+        // without alloyed asset, every swap is between pool assets
+        let Some(alloyed_denom) = self.alloyed_asset.may_get_alloyed_denom(deps.storage)? else {
+            return Ok(SwapVariant::TokenToToken);
+        };
 
         if alloyed_denom == token_in_denom {
             return Ok(SwapVariant::AlloyedToToken);
@@ -55,22 +185,67 @@ impl Transmuter<'_> {
         env: Env,
     ) -> Result<Response, ContractError> {
         let mut pool: TransmuterPool = self.pool.load(deps.storage)?;
+        let alloyed_denom = self.alloyed_asset.get_alloyed_denom(deps.storage)?;
 
         let response = Response::new();
+        let shares_to_lock = self.shares_to_lock(deps.as_ref())?;
 
-        let (tokens_in, out_amount, response) = match constraint {
+        let (tokens_in, fee_charges, out_amount, response) = match constraint {
             SwapToAlloyedConstraint::ExactIn {
                 tokens_in,
                 token_out_min_amount,
             } => {
+                let resulting_pool = fee_free_resulting_pool(&pool, tokens_in, &[])?;
+                let fee_charges = tokens_in
+                    .iter()
+                    .map(|coin| {
+                        let (swap_fee, fee_split) = self.swap_fee_params(
+                            deps.storage,
+                            &entrypoint,
+                            &[&coin.denom, &alloyed_denom],
+                            &resulting_pool,
+                        )?;
+                        FeeCharge::from_gross_amount(coin.amount, swap_fee, fee_split)
+                    })
+                    .collect::<Result<Vec<_>, ContractError>>()?;
+
+                let net_tokens_in = tokens_in
+                    .iter()
+                    .zip(fee_charges.iter())
+                    .map(|(coin, charge)| Coin::new(charge.net_amount.u128(), &coin.denom))
+                    .collect::<Vec<_>>();
+
                 let tokens_in_with_norm_factor =
-                    pool.pair_coins_with_normalization_factor(tokens_in)?;
+                    pool.pair_coins_with_normalization_factor(&net_tokens_in)?;
                 let out_amount = swap_to_alloyed::out_amount_via_exact_in(
                     tokens_in_with_norm_factor,
-                    token_out_min_amount,
+                    Uint128::zero(),
                     self.alloyed_asset.get_normalization_factor(deps.storage)?,
                 )?;
 
+                // output rounds to zero only if every token in does, so any of them is the culprit
+                if let Some(token_in) = tokens_in.first() {
+                    ensure_output_not_rounded_to_zero(out_amount, token_in)?;
+                }
+
+                // shares to lock are deducted from the first minter
+                ensure!(
+                    shares_to_lock.is_zero() || out_amount > shares_to_lock,
+                    ContractError::InsufficientInitialLiquidity {
+                        min_locked_shares: shares_to_lock,
+                        amount: out_amount,
+                    }
+                );
+                let out_amount = out_amount.checked_sub(shares_to_lock)?;
+
+                ensure!(
+                    out_amount >= token_out_min_amount,
+                    ContractError::InsufficientTokenOut {
+                        min_required: token_out_min_amount,
+                        amount_out: out_amount
+                    }
+                );
+
                 let response = set_data_if_sudo(
                     response,
                     &entrypoint,
@@ -79,7 +254,7 @@ impl Transmuter<'_> {
                     },
                 )?;
 
-                (tokens_in.to_owned(), out_amount, response)
+                (tokens_in.to_owned(), fee_charges, out_amount, response)
             }
 
             SwapToAlloyedConstraint::ExactOut {
@@ -90,12 +265,36 @@ impl Transmuter<'_> {
                 let token_in_norm_factor = pool
                     .get_pool_asset_by_denom(token_in_denom)?
                     .normalization_factor();
-                let in_amount = swap_to_alloyed::in_amount_via_exact_out(
+                // shares to lock are paid for by the first minter
+                let net_in_amount = swap_to_alloyed::in_amount_via_exact_out(
                     token_in_norm_factor,
-                    token_in_max_amount,
-                    token_out_amount,
+                    Uint128::MAX,
+                    token_out_amount.checked_add(shares_to_lock)?,
                     self.alloyed_asset.get_normalization_factor(deps.storage)?,
                 )?;
+
+                let resulting_pool = fee_free_resulting_pool(
+                    &pool,
+                    &[Coin::new(net_in_amount.u128(), token_in_denom)],
+                    &[],
+                )?;
+                let (swap_fee, fee_split) = self.swap_fee_params(
+                    deps.storage,
+                    &entrypoint,
+                    &[token_in_denom, &alloyed_denom],
+                    &resulting_pool,
+                )?;
+                let fee_charge = FeeCharge::from_net_amount(net_in_amount, swap_fee, fee_split)?;
+                let in_amount = fee_charge.gross_amount()?;
+
+                ensure!(
+                    in_amount <= token_in_max_amount,
+                    ContractError::ExcessiveRequiredTokenIn {
+                        limit: token_in_max_amount,
+                        required: in_amount,
+                    }
+                );
+
                 let tokens_in = vec![Coin::new(in_amount.u128(), token_in_denom)];
 
                 let response = set_data_if_sudo(
@@ -106,7 +305,7 @@ impl Transmuter<'_> {
                     },
                 )?;
 
-                (tokens_in, token_out_amount, response)
+                (tokens_in, vec![fee_charge], token_out_amount, response)
             }
         };
 
@@ -122,7 +321,35 @@ impl Transmuter<'_> {
             ContractError::ZeroValueOperation {}
         );
 
-        pool.join_pool(&tokens_in)?;
+        self.ensure_swap_not_blocked(deps.storage, &pool, &tokens_in)?;
+        self.ensure_limiter_coverage(deps.storage, &tokens_in)?;
+        self.ensure_swap_size_constraints(
+            deps.storage,
+            &pool,
+            &tokens_in,
+            &mint_to_address,
+            env.block.time,
+        )?;
+
+        // collected fee goes to the fee collector, the rest goes into the pool
+        let mut tokens_to_pool = vec![];
+        let mut fee_collected = vec![];
+        let mut fee_retained = vec![];
+        for (coin, charge) in tokens_in.iter().zip(fee_charges.iter()) {
+            tokens_to_pool.push(Coin::new(charge.pool_amount()?.u128(), &coin.denom));
+            fee_collected.push(Coin::new(charge.collected_amount.u128(), &coin.denom));
+            fee_retained.push(Coin::new(charge.retained_amount.u128(), &coin.denom));
+        }
+
+        pool.join_pool(&tokens_to_pool)?;
+        self.accrue_retained_fee(deps.storage, &fee_retained)?;
+
+        self.ensure_within_max_total_liquidity(deps.storage, &pool, None)?;
+        self.record_mint(
+            deps.storage,
+            out_amount.checked_add(shares_to_lock)?,
+            env.block.time,
+        )?;
 
         // check and update limiters only if pool assets are not zero
         if let Some(denom_weight_pairs) = pool.weights()? {
@@ -132,25 +359,65 @@ impl Transmuter<'_> {
                 env.block.time,
             )?;
         }
+        self.check_net_flows_and_update(deps.storage, &pool, env.block.time)?;
 
         // no need for cleaning up drained corrupted assets here
         // since this function will only adding more underlying assets
         // rather than removing any of them
 
         self.pool.save(deps.storage, &pool)?;
+        self.pool_snapshots
+            .take_if_due(deps.storage, &pool, &env.block)?;
+        self.swap_volumes
+            .record(deps.storage, &tokens_in, env.block.time)?;
+        self.alloyed_minted_volumes.record(
+            deps.storage,
+            &[Coin::new(
+                out_amount.checked_add(shares_to_lock)?.u128(),
+                &alloyed_denom,
+            )],
+            env.block.time,
+        )?;
+
+        self.alloyed_holders.add(deps.storage, &mint_to_address)?;
+
+        let alloyed_asset_out = Coin::new(out_amount.u128(), &alloyed_denom);
+
+        let response = if shares_to_lock.is_zero() {
+            response
+        } else {
+            self.locked_shares.save(deps.storage, &shares_to_lock)?;
+
+            // locked shares are minted to the contract itself and are never redeemed
+            let locked_alloyed_asset = Coin::new(shares_to_lock.u128(), &alloyed_denom);
+            response
+                .add_event(alloyed_asset_transfer_event(
+                    "lock",
+                    "",
+                    env.contract.address.as_str(),
+                    &locked_alloyed_asset,
+                ))
+                .add_message(MsgMint {
+                    sender: env.contract.address.to_string(),
+                    amount: Some(locked_alloyed_asset.into()),
+                    mint_to_address: env.contract.address.to_string(),
+                })
+        };
 
-        let alloyed_asset_out = Coin::new(
-            out_amount.u128(),
-            self.alloyed_asset.get_alloyed_denom(deps.storage)?,
-        );
-
-        let response = response.add_message(MsgMint {
-            sender: env.contract.address.to_string(),
-            amount: Some(alloyed_asset_out.into()),
-            mint_to_address: mint_to_address.to_string(),
-        });
+        let response = response
+            .add_event(alloyed_asset_transfer_event(
+                "mint",
+                "",
+                mint_to_address.as_str(),
+                &alloyed_asset_out,
+            ))
+            .add_message(MsgMint {
+                sender: env.contract.address.to_string(),
+                amount: Some(alloyed_asset_out.into()),
+                mint_to_address: mint_to_address.to_string(),
+            });
 
-        Ok(response)
+        self.send_collected_fee(deps.storage, response, fee_collected)
     }
 
     pub fn swap_alloyed_asset_to_tokens(
@@ -163,10 +430,11 @@ impl Transmuter<'_> {
         env: Env,
     ) -> Result<Response, ContractError> {
         let mut pool: TransmuterPool = self.pool.load(deps.storage)?;
+        let alloyed_denom = self.alloyed_asset.get_alloyed_denom(deps.storage)?;
 
         let response = Response::new();
 
-        let (in_amount, tokens_out, response) = match constraint {
+        let (in_amount, fee_charge, tokens_out, response) = match constraint {
             SwapFromAlloyedConstraint::ExactIn {
                 token_out_denom,
                 token_out_min_amount,
@@ -175,12 +443,40 @@ impl Transmuter<'_> {
                 let token_out_norm_factor = pool
                     .get_pool_asset_by_denom(token_out_denom)?
                     .normalization_factor();
+                let resulting_pool = fee_free_resulting_pool(
+                    &pool,
+                    &[],
+                    &[self.fee_free_counterpart(
+                        deps.storage,
+                        &pool,
+                        &Coin::new(token_in_amount.u128(), &alloyed_denom),
+                        token_out_denom,
+                    )?],
+                )?;
+                let (swap_fee, fee_split) = self.swap_fee_params(
+                    deps.storage,
+                    &entrypoint,
+                    &[&alloyed_denom, token_out_denom],
+                    &resulting_pool,
+                )?;
+                let fee_charge =
+                    FeeCharge::from_gross_amount(token_in_amount, swap_fee, fee_split)?;
                 let out_amount = swap_from_alloyed::out_amount_via_exact_in(
-                    token_in_amount,
+                    fee_charge.net_amount,
                     self.alloyed_asset.get_normalization_factor(deps.storage)?,
                     token_out_norm_factor,
-                    token_out_min_amount,
+                    Uint128::zero(),
                 )?;
+                ensure_output_not_rounded_to_zero(
+                    out_amount,
+                    &Coin::new(token_in_amount.u128(), &alloyed_denom),
+                )?;
+                ensure_min_token_out(out_amount, token_out_min_amount)?;
+                let out_amount = out_amount.checked_add(self.corruption_drain_bonus(
+                    deps.as_ref(),
+                    &pool,
+                    &Coin::new(out_amount.u128(), token_out_denom),
+                )?)?;
 
                 let response = set_data_if_sudo(
                     response,
@@ -192,7 +488,7 @@ impl Transmuter<'_> {
 
                 let tokens_out = vec![Coin::new(out_amount.u128(), token_out_denom)];
 
-                (token_in_amount, tokens_out, response)
+                (token_in_amount, fee_charge, tokens_out, response)
             }
             SwapFromAlloyedConstraint::ExactOut {
                 tokens_out,
@@ -200,12 +496,29 @@ impl Transmuter<'_> {
             } => {
                 let tokens_out_with_norm_factor =
                     pool.pair_coins_with_normalization_factor(tokens_out)?;
-                let in_amount = swap_from_alloyed::in_amount_via_exact_out(
-                    token_in_max_amount,
+                let net_in_amount = swap_from_alloyed::in_amount_via_exact_out(
+                    Uint128::MAX,
                     self.alloyed_asset.get_normalization_factor(deps.storage)?,
                     tokens_out_with_norm_factor,
                 )?;
 
+                let denoms: Vec<&str> = iter::once(alloyed_denom.as_str())
+                    .chain(tokens_out.iter().map(|coin| coin.denom.as_str()))
+                    .collect();
+                let resulting_pool = fee_free_resulting_pool(&pool, &[], tokens_out)?;
+                let (swap_fee, fee_split) =
+                    self.swap_fee_params(deps.storage, &entrypoint, &denoms, &resulting_pool)?;
+                let fee_charge = FeeCharge::from_net_amount(net_in_amount, swap_fee, fee_split)?;
+                let in_amount = fee_charge.gross_amount()?;
+
+                ensure!(
+                    in_amount <= token_in_max_amount,
+                    ContractError::ExcessiveRequiredTokenIn {
+                        limit: token_in_max_amount,
+                        required: in_amount,
+                    }
+                );
+
                 let response = set_data_if_sudo(
                     response,
                     &entrypoint,
@@ -214,7 +527,7 @@ impl Transmuter<'_> {
                     },
                 )?;
 
-                (in_amount, tokens_out.to_vec(), response)
+                (in_amount, fee_charge, tokens_out.to_vec(), response)
             }
         };
 
@@ -224,7 +537,15 @@ impl Transmuter<'_> {
             ContractError::ZeroValueOperation {}
         );
 
-        let burn_from_address = match burn_target {
+        self.ensure_swap_size_constraints(
+            deps.storage,
+            &pool,
+            &[Coin::new(in_amount.u128(), &alloyed_denom)],
+            &sender,
+            env.block.time,
+        )?;
+
+        let burn_from_address = match &burn_target {
             BurnTarget::SenderAccount => {
                 // Check if the sender's shares is sufficient to burn
                 let shares = self.alloyed_asset.get_balance(deps.as_ref(), &sender)?;
@@ -236,6 +557,10 @@ impl Transmuter<'_> {
                     }
                 );
 
+                if shares == in_amount {
+                    self.alloyed_holders.remove(deps.storage, &sender)?;
+                }
+
                 Ok::<&Addr, ContractError>(&sender)
             }
 
@@ -249,10 +574,15 @@ impl Transmuter<'_> {
             // So it's safe to check just check that contract has enough alloyed assets to burn.
             // Since it's only being a loss for the actor that does not follow the normal mechanism.
             BurnTarget::SentFunds => {
-                // get alloyed denom contract balance
+                // get alloyed denom contract balance, excluding locked shares
                 let alloyed_contract_balance = self
                     .alloyed_asset
-                    .get_balance(deps.as_ref(), &env.contract.address)?;
+                    .get_balance(deps.as_ref(), &env.contract.address)?
+                    .saturating_sub(
+                        self.locked_shares
+                            .may_load(deps.storage)?
+                            .unwrap_or_default(),
+                    );
 
                 // ensure that alloyed contract balance is greater than in_amount
                 ensure!(
@@ -263,8 +593,42 @@ impl Transmuter<'_> {
                     }
                 );
 
+                // funds are already sent, so any remaining balance is what the sender still holds
+                if self
+                    .alloyed_asset
+                    .get_balance(deps.as_ref(), &sender)?
+                    .is_zero()
+                {
+                    self.alloyed_holders.remove(deps.storage, &sender)?;
+                }
+
                 Ok(&env.contract.address)
             }
+
+            BurnTarget::Address(owner) => {
+                let shares = self.alloyed_asset.get_balance(deps.as_ref(), owner)?;
+                ensure!(
+                    shares >= in_amount,
+                    ContractError::InsufficientShares {
+                        required: in_amount,
+                        available: shares
+                    }
+                );
+
+                self.alloyed_allowances.spend(
+                    deps.storage,
+                    &alloyed_denom,
+                    owner,
+                    &sender,
+                    in_amount,
+                )?;
+
+                if shares == in_amount {
+                    self.alloyed_holders.remove(deps.storage, owner)?;
+                }
+
+                Ok(owner)
+            }
         }?
         .to_string();
 
@@ -302,31 +666,61 @@ impl Transmuter<'_> {
                     env.block.time,
                 )?;
             }
+            self.check_net_flows_and_update(deps.storage, &pool, env.block.time)?;
         }
 
+        self.record_retained_dust(deps.storage, &pool, fee_charge.net_amount, &tokens_out)?;
+        let mut denoms_out = vec![];
+        for coin in tokens_out.iter() {
+            self.ensure_full_drain_allowed(deps.storage, &pool, &coin.denom)?;
+            // sweeping dust drains the denom fully, so it is only swept where that is allowed
+            if self.is_full_drain_allowed(deps.storage, &pool, &coin.denom)? {
+                denoms_out.push(coin.denom.as_str());
+            }
+        }
+        self.sweep_dust(deps.storage, &mut pool, &denoms_out)?;
         self.clean_up_drained_corrupted_assets(deps.storage, &mut pool)?;
 
         self.pool.save(deps.storage, &pool)?;
+        self.pool_snapshots
+            .take_if_due(deps.storage, &pool, &env.block)?;
+        self.swap_volumes
+            .record(deps.storage, &tokens_out, env.block.time)?;
 
         let bank_send_msg = BankMsg::Send {
             to_address: sender.to_string(),
             amount: tokens_out,
         };
 
-        let alloyed_asset_to_burn = Coin::new(
-            in_amount.u128(),
-            self.alloyed_asset.get_alloyed_denom(deps.storage)?,
-        )
-        .into();
+        // collected fee is sent to the fee collector as alloyed asset,
+        // the rest is burned, leaving the retained fee's backing in the pool
+        let alloyed_asset_to_burn = Coin::new(fee_charge.pool_amount()?.u128(), &alloyed_denom);
+        self.alloyed_burned_volumes.record(
+            deps.storage,
+            std::slice::from_ref(&alloyed_asset_to_burn),
+            env.block.time,
+        )?;
+        let burn_event =
+            alloyed_asset_transfer_event("burn", &burn_from_address, "", &alloyed_asset_to_burn);
 
         // burn alloyed assets
         let burn_msg = MsgBurn {
             sender: env.contract.address.to_string(),
-            amount: Some(alloyed_asset_to_burn),
+            amount: Some(alloyed_asset_to_burn.into()),
             burn_from_address,
         };
 
-        Ok(response.add_message(burn_msg).add_message(bank_send_msg))
+        let response = response
+            .add_event(burn_event)
+            .add_message(burn_msg)
+            .add_message(bank_send_msg);
+        self.accrue_retained_fee(
+            deps.storage,
+            &[Coin::new(fee_charge.retained_amount.u128(), &alloyed_denom)],
+        )?;
+        let fee_collected = vec![Coin::new(fee_charge.collected_amount.u128(), alloyed_denom)];
+
+        self.send_collected_fee(deps.storage, response, fee_collected)
     }
 
     pub fn swap_non_alloyed_exact_amount_in(
@@ -338,8 +732,22 @@ impl Transmuter<'_> {
         deps: DepsMut,
         env: Env,
     ) -> Result<Response, ContractError> {
-        let (mut pool, actual_token_out) =
-            self.out_amt_given_in(deps.as_ref(), token_in, token_out_denom)?;
+        let token_in_denom = token_in.denom.clone();
+        let (mut pool, actual_token_out, fee_charge) =
+            self.out_amt_given_in(deps.as_ref(), token_in.clone(), token_out_denom)?;
+
+        ensure_output_not_rounded_to_zero(actual_token_out.amount, &token_in)?;
+
+        self.ensure_swap_not_blocked(deps.storage, &pool, std::slice::from_ref(&token_in))?;
+        self.ensure_limiter_coverage(deps.storage, std::slice::from_ref(&token_in))?;
+
+        self.ensure_swap_size_constraints(
+            deps.storage,
+            &pool,
+            std::slice::from_ref(&token_in),
+            &sender,
+            env.block.time,
+        )?;
 
         // ensure token_out amount is greater than or equal to token_out_min_amount
         ensure!(
@@ -358,11 +766,43 @@ impl Transmuter<'_> {
                 env.block.time,
             )?;
         }
+        self.check_net_flows_and_update(deps.storage, &pool, env.block.time)?;
 
+        let fee_denom = match self.fee.get_fee_side(deps.storage)? {
+            FeeSide::In => token_in_denom,
+            FeeSide::Out => actual_token_out.denom.clone(),
+        };
+        self.record_rounding_residual(
+            deps.storage,
+            &pool,
+            &token_in,
+            &actual_token_out,
+            Coin::new(
+                fee_charge
+                    .collected_amount
+                    .checked_add(fee_charge.retained_amount)?
+                    .u128(),
+                &fee_denom,
+            ),
+            env.block.time,
+        )?;
+
+        self.ensure_full_drain_allowed(deps.storage, &pool, &actual_token_out.denom)?;
+        // sweeping dust drains the denom fully, so it is only swept where that is allowed
+        if self.is_full_drain_allowed(deps.storage, &pool, &actual_token_out.denom)? {
+            self.sweep_dust(deps.storage, &mut pool, &[&actual_token_out.denom])?;
+        }
         self.clean_up_drained_corrupted_assets(deps.storage, &mut pool)?;
 
         // save pool
         self.pool.save(deps.storage, &pool)?;
+        self.pool_snapshots
+            .take_if_due(deps.storage, &pool, &env.block)?;
+        self.swap_volumes.record(
+            deps.storage,
+            &[token_in, actual_token_out.clone()],
+            env.block.time,
+        )?;
 
         let send_token_out_to_sender_msg = BankMsg::Send {
             to_address: sender.to_string(),
@@ -373,9 +813,16 @@ impl Transmuter<'_> {
             token_out_amount: actual_token_out.amount,
         };
 
-        Ok(Response::new()
+        let response = Response::new()
             .add_message(send_token_out_to_sender_msg)
-            .set_data(to_json_binary(&swap_result)?))
+            .set_data(to_json_binary(&swap_result)?);
+        self.accrue_retained_fee(
+            deps.storage,
+            &[Coin::new(fee_charge.retained_amount.u128(), &fee_denom)],
+        )?;
+        let fee_collected = vec![Coin::new(fee_charge.collected_amount.u128(), fee_denom)];
+
+        self.send_collected_fee(deps.storage, response, fee_collected)
     }
 
     pub fn swap_non_alloyed_exact_amount_out(
@@ -387,7 +834,7 @@ impl Transmuter<'_> {
         deps: DepsMut,
         env: Env,
     ) -> Result<Response, ContractError> {
-        let (mut pool, actual_token_in) =
+        let (mut pool, actual_token_in, fee_charge) =
             self.in_amt_given_out(deps.as_ref(), token_out.clone(), token_in_denom.to_string())?;
 
         ensure!(
@@ -398,6 +845,17 @@ impl Transmuter<'_> {
             }
         );
 
+        self.ensure_swap_not_blocked(deps.storage, &pool, std::slice::from_ref(&actual_token_in))?;
+        self.ensure_limiter_coverage(deps.storage, std::slice::from_ref(&actual_token_in))?;
+
+        self.ensure_swap_size_constraints(
+            deps.storage,
+            &pool,
+            std::slice::from_ref(&actual_token_in),
+            &sender,
+            env.block.time,
+        )?;
+
         // check and update limiters only if pool assets are not zero
         if let Some(denom_weight_pairs) = pool.weights()? {
             self.limiters.check_limits_and_update(
@@ -406,11 +864,39 @@ impl Transmuter<'_> {
                 env.block.time,
             )?;
         }
-
+        self.check_net_flows_and_update(deps.storage, &pool, env.block.time)?;
+
+        self.record_rounding_residual(
+            deps.storage,
+            &pool,
+            &actual_token_in,
+            &token_out,
+            Coin::new(
+                fee_charge
+                    .collected_amount
+                    .checked_add(fee_charge.retained_amount)?
+                    .u128(),
+                token_in_denom,
+            ),
+            env.block.time,
+        )?;
+
+        self.ensure_full_drain_allowed(deps.storage, &pool, &token_out.denom)?;
+        // sweeping dust drains the denom fully, so it is only swept where that is allowed
+        if self.is_full_drain_allowed(deps.storage, &pool, &token_out.denom)? {
+            self.sweep_dust(deps.storage, &mut pool, &[&token_out.denom])?;
+        }
         self.clean_up_drained_corrupted_assets(deps.storage, &mut pool)?;
 
         // save pool
         self.pool.save(deps.storage, &pool)?;
+        self.pool_snapshots
+            .take_if_due(deps.storage, &pool, &env.block)?;
+        self.swap_volumes.record(
+            deps.storage,
+            &[actual_token_in.clone(), token_out.clone()],
+            env.block.time,
+        )?;
 
         let send_token_out_to_sender_msg = BankMsg::Send {
             to_address: sender.to_string(),
@@ -421,9 +907,306 @@ impl Transmuter<'_> {
             token_in_amount: actual_token_in.amount,
         };
 
-        Ok(Response::new()
+        let response = Response::new()
             .add_message(send_token_out_to_sender_msg)
-            .set_data(to_json_binary(&swap_result)?))
+            .set_data(to_json_binary(&swap_result)?);
+        self.accrue_retained_fee(
+            deps.storage,
+            &[Coin::new(fee_charge.retained_amount.u128(), token_in_denom)],
+        )?;
+        let fee_collected = vec![Coin::new(
+            fee_charge.collected_amount.u128(),
+            token_in_denom,
+        )];
+
+        self.send_collected_fee(deps.storage, response, fee_collected)
+    }
+
+    /// Whether the swap mints or burns an alloyed asset from the alloyed asset registry,
+    /// which is settled against its own backing instead of the pool.
+    pub fn is_registered_alloyed_swap(
+        &self,
+        storage: &dyn Storage,
+        token_in_denom: &str,
+        token_out_denom: &str,
+    ) -> bool {
+        self.alloyed_registry.is_registered(storage, token_in_denom)
+            || self
+                .alloyed_registry
+                .is_registered(storage, token_out_denom)
+    }
+
+    /// Mint registered `alloyed_denom` for `tokens_in`, which are added to its backing.
+    pub fn mint_registered_alloyed_asset(
+        &self,
+        alloyed_denom: &str,
+        tokens_in: &[Coin],
+        mint_to_address: Addr,
+        deps: DepsMut,
+        env: Env,
+    ) -> Result<Response, ContractError> {
+        let alloyed_asset = self.alloyed_registry.load(deps.storage, alloyed_denom)?;
+        let out_amount = alloyed_asset.mint_amount_via_exact_in(tokens_in)?;
+
+        self.settle_registered_mint(
+            alloyed_asset,
+            tokens_in,
+            out_amount,
+            mint_to_address,
+            deps,
+            env,
+        )
+    }
+
+    /// Burn registered `alloyed_denom` for `tokens_out`, which are withdrawn from its backing.
+    pub fn burn_registered_alloyed_asset(
+        &self,
+        alloyed_denom: &str,
+        tokens_out: &[Coin],
+        burn_target: BurnTarget,
+        sender: Addr,
+        deps: DepsMut,
+        env: Env,
+    ) -> Result<Response, ContractError> {
+        let alloyed_asset = self.alloyed_registry.load(deps.storage, alloyed_denom)?;
+        let in_amount = alloyed_asset.burn_amount_via_exact_out(tokens_out)?;
+
+        self.settle_registered_burn(
+            alloyed_asset,
+            in_amount,
+            tokens_out,
+            burn_target,
+            sender,
+            deps,
+            env,
+        )
+    }
+
+    /// Swap exact amount in, minting or burning a registered alloyed asset.
+    /// Registered alloyed assets are not subject to swap fees or limiters.
+    pub fn swap_registered_alloyed_exact_amount_in(
+        &self,
+        token_in: Coin,
+        token_out_denom: &str,
+        token_out_min_amount: Uint128,
+        sender: Addr,
+        deps: DepsMut,
+        env: Env,
+    ) -> Result<Response, ContractError> {
+        let is_burn = self
+            .alloyed_registry
+            .is_registered(deps.storage, &token_in.denom);
+
+        let (out_amount, response) = if is_burn {
+            let alloyed_asset = self.alloyed_registry.load(deps.storage, &token_in.denom)?;
+            let out_amount =
+                alloyed_asset.withdraw_amount_via_exact_in(token_in.amount, token_out_denom)?;
+            ensure_min_token_out(out_amount, token_out_min_amount)?;
+
+            let response = self.settle_registered_burn(
+                alloyed_asset,
+                token_in.amount,
+                &[Coin::new(out_amount.u128(), token_out_denom)],
+                BurnTarget::SentFunds,
+                sender,
+                deps,
+                env,
+            )?;
+            (out_amount, response)
+        } else {
+            let alloyed_asset = self.alloyed_registry.load(deps.storage, token_out_denom)?;
+            let out_amount =
+                alloyed_asset.mint_amount_via_exact_in(std::slice::from_ref(&token_in))?;
+            ensure_min_token_out(out_amount, token_out_min_amount)?;
+
+            let response = self.settle_registered_mint(
+                alloyed_asset,
+                &[token_in],
+                out_amount,
+                sender,
+                deps,
+                env,
+            )?;
+            (out_amount, response)
+        };
+
+        Ok(
+            response.set_data(to_json_binary(&SwapExactAmountInResponseData {
+                token_out_amount: out_amount,
+            })?),
+        )
+    }
+
+    /// Swap exact amount out, minting or burning a registered alloyed asset.
+    /// Registered alloyed assets are not subject to swap fees or limiters.
+    pub fn swap_registered_alloyed_exact_amount_out(
+        &self,
+        token_in_denom: &str,
+        token_in_max_amount: Uint128,
+        token_out: Coin,
+        sender: Addr,
+        deps: DepsMut,
+        env: Env,
+    ) -> Result<Response, ContractError> {
+        let is_burn = self
+            .alloyed_registry
+            .is_registered(deps.storage, token_in_denom);
+
+        let (in_amount, response) = if is_burn {
+            let alloyed_asset = self.alloyed_registry.load(deps.storage, token_in_denom)?;
+            let in_amount =
+                alloyed_asset.burn_amount_via_exact_out(std::slice::from_ref(&token_out))?;
+            ensure_max_token_in(in_amount, token_in_max_amount)?;
+
+            let response = self.settle_registered_burn(
+                alloyed_asset,
+                in_amount,
+                &[token_out],
+                BurnTarget::SentFunds,
+                sender,
+                deps,
+                env,
+            )?;
+            (in_amount, response)
+        } else {
+            let alloyed_asset = self.alloyed_registry.load(deps.storage, &token_out.denom)?;
+            let in_amount =
+                alloyed_asset.deposit_amount_via_exact_out(token_in_denom, token_out.amount)?;
+            ensure_max_token_in(in_amount, token_in_max_amount)?;
+
+            let response = self.settle_registered_mint(
+                alloyed_asset,
+                &[Coin::new(in_amount.u128(), token_in_denom)],
+                token_out.amount,
+                sender,
+                deps,
+                env,
+            )?;
+            (in_amount, response)
+        };
+
+        Ok(
+            response.set_data(to_json_binary(&SwapExactAmountOutResponseData {
+                token_in_amount: in_amount,
+            })?),
+        )
+    }
+
+    fn settle_registered_mint(
+        &self,
+        mut alloyed_asset: RegisteredAlloyedAsset,
+        tokens_in: &[Coin],
+        out_amount: Uint128,
+        mint_to_address: Addr,
+        deps: DepsMut,
+        env: Env,
+    ) -> Result<Response, ContractError> {
+        // every entrypoint joining a registered alloyed asset settles here, so access is checked alike
+        self.ensure_sender_allowed(deps.storage, &mint_to_address)?;
+
+        // backing is exposed to the same denoms as the pool, so it is blocked alike
+        let pool = self.pool.load(deps.storage)?;
+        self.ensure_swap_not_blocked(deps.storage, &pool, tokens_in)?;
+
+        alloyed_asset.backing.join_pool(tokens_in)?;
+
+        self.ensure_within_max_total_liquidity(deps.storage, &pool, Some(&alloyed_asset))?;
+
+        // mint rate limit is accounted in pool alloyed asset amount
+        let minted = out_amount.checked_multiply_ratio(
+            self.alloyed_asset.get_normalization_factor(deps.storage)?,
+            alloyed_asset.normalization_factor,
+        )?;
+        self.record_mint(deps.storage, minted, env.block.time)?;
+
+        self.alloyed_registry.save(deps.storage, &alloyed_asset)?;
+
+        let alloyed_asset_out = Coin::new(out_amount.u128(), alloyed_asset.denom);
+
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event(
+                "mint",
+                "",
+                mint_to_address.as_str(),
+                &alloyed_asset_out,
+            ))
+            .add_message(MsgMint {
+                sender: env.contract.address.to_string(),
+                amount: Some(alloyed_asset_out.into()),
+                mint_to_address: mint_to_address.to_string(),
+            }))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn settle_registered_burn(
+        &self,
+        mut alloyed_asset: RegisteredAlloyedAsset,
+        in_amount: Uint128,
+        tokens_out: &[Coin],
+        burn_target: BurnTarget,
+        sender: Addr,
+        deps: DepsMut,
+        env: Env,
+    ) -> Result<Response, ContractError> {
+        let burn_from_address = match burn_target {
+            BurnTarget::SenderAccount => sender.clone(),
+            // sent funds are held by the contract until burned
+            BurnTarget::SentFunds => env.contract.address.clone(),
+            BurnTarget::Address(owner) => {
+                self.alloyed_allowances.spend(
+                    deps.storage,
+                    &alloyed_asset.denom,
+                    &owner,
+                    &sender,
+                    in_amount,
+                )?;
+                owner
+            }
+        };
+
+        let available = deps
+            .querier
+            .query_balance(burn_from_address.as_str(), &alloyed_asset.denom)?
+            .amount;
+        ensure!(
+            available >= in_amount,
+            ContractError::InsufficientShares {
+                required: in_amount,
+                available
+            }
+        );
+
+        // withdrawing backing reduces exposure, which is checked as swapping out of the pool
+        let pool = self.pool.load(deps.storage)?;
+        for token_out in tokens_out {
+            if let Some(reason) =
+                self.is_swap_blocked(deps.storage, &pool, &token_out.denom, SwapDirection::Out)?
+            {
+                return Err(reason);
+            }
+        }
+
+        alloyed_asset.backing.exit_pool(tokens_out)?;
+        self.alloyed_registry.save(deps.storage, &alloyed_asset)?;
+
+        let alloyed_asset_to_burn = Coin::new(in_amount.u128(), alloyed_asset.denom);
+
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event(
+                "burn",
+                burn_from_address.as_str(),
+                "",
+                &alloyed_asset_to_burn,
+            ))
+            .add_message(MsgBurn {
+                sender: env.contract.address.to_string(),
+                amount: Some(alloyed_asset_to_burn.into()),
+                burn_from_address: burn_from_address.to_string(),
+            })
+            .add_message(BankMsg::Send {
+                to_address: sender.to_string(),
+                amount: tokens_out.to_vec(),
+            }))
     }
 
     pub fn in_amt_given_out(
@@ -431,9 +1214,20 @@ impl Transmuter<'_> {
         deps: Deps,
         token_out: Coin,
         token_in_denom: String,
-    ) -> Result<(TransmuterPool, Coin), ContractError> {
+    ) -> Result<(TransmuterPool, Coin, FeeCharge), ContractError> {
         let swap_variant = self.swap_variant(&token_in_denom, &token_out.denom, deps)?;
         let mut pool = self.pool.load(deps.storage)?;
+        let resulting_pool = fee_free_resulting_pool(
+            &pool,
+            &[self.fee_free_counterpart(deps.storage, &pool, &token_out, &token_in_denom)?],
+            std::slice::from_ref(&token_out),
+        )?;
+        let (swap_fee, fee_split) = self.swap_fee_params(
+            deps.storage,
+            &Entrypoint::Sudo,
+            &[&token_in_denom, &token_out.denom],
+            &resulting_pool,
+        )?;
 
         Ok(match swap_variant {
             SwapVariant::TokenToAlloyed => {
@@ -441,48 +1235,65 @@ impl Transmuter<'_> {
                     .get_pool_asset_by_denom(&token_in_denom)?
                     .normalization_factor();
 
-                let token_in_amount = swap_to_alloyed::in_amount_via_exact_out(
+                let net_in_amount = swap_to_alloyed::in_amount_via_exact_out(
                     token_in_norm_factor,
                     Uint128::MAX,
                     token_out.amount,
                     self.alloyed_asset.get_normalization_factor(deps.storage)?,
                 )?;
-                let token_in = Coin::new(token_in_amount.u128(), token_in_denom);
-                pool.join_pool(&[token_in.clone()])?;
-                (pool, token_in)
+                let fee_charge = FeeCharge::from_net_amount(net_in_amount, swap_fee, fee_split)?;
+                pool.join_pool(&[Coin::new(fee_charge.pool_amount()?.u128(), &token_in_denom)])?;
+
+                let token_in = Coin::new(fee_charge.gross_amount()?.u128(), token_in_denom);
+                (pool, token_in, fee_charge)
             }
             SwapVariant::AlloyedToToken => {
                 let token_out_norm_factor = pool
                     .get_pool_asset_by_denom(&token_out.denom)?
                     .normalization_factor();
 
-                let token_in_amount = swap_from_alloyed::in_amount_via_exact_out(
+                let net_in_amount = swap_from_alloyed::in_amount_via_exact_out(
                     Uint128::MAX,
                     self.alloyed_asset.get_normalization_factor(deps.storage)?,
                     vec![(token_out.clone(), token_out_norm_factor)],
                 )?;
-                let token_in = Coin::new(token_in_amount.u128(), token_in_denom);
+                let fee_charge = FeeCharge::from_net_amount(net_in_amount, swap_fee, fee_split)?;
                 pool.exit_pool(&[token_out])?;
-                (pool, token_in)
+
+                let token_in = Coin::new(fee_charge.gross_amount()?.u128(), token_in_denom);
+                (pool, token_in, fee_charge)
             }
             SwapVariant::TokenToToken => {
-                let (token_in, actual_token_out) = pool.transmute(
+                let (net_token_in, actual_token_out) = pool.transmute(
                     AmountConstraint::exact_out(token_out.amount),
                     &token_in_denom,
                     &token_out.denom,
                 )?;
 
-                // ensure that actual_token_out is equal to token_out
-                ensure_eq!(
-                    token_out,
-                    actual_token_out,
+                // ensure that actual_token_out is equal to token_out, within tolerance
+                ensure!(
+                    token_out.denom == actual_token_out.denom
+                        && within_tolerance(
+                            token_out.amount,
+                            actual_token_out.amount,
+                            self.load_amount_tolerance(deps.storage)?
+                        ),
                     ContractError::InvalidTokenOutAmount {
                         expected: token_out.amount,
                         actual: actual_token_out.amount
                     }
                 );
 
-                (pool, token_in)
+                // retained fee stays in the pool
+                let fee_charge =
+                    FeeCharge::from_net_amount(net_token_in.amount, swap_fee, fee_split)?;
+                pool.join_pool(&[Coin::new(
+                    fee_charge.retained_amount.u128(),
+                    &token_in_denom,
+                )])?;
+
+                let token_in = Coin::new(fee_charge.gross_amount()?.u128(), token_in_denom);
+                (pool, token_in, fee_charge)
             }
         })
     }
@@ -492,9 +1303,38 @@ impl Transmuter<'_> {
         deps: Deps,
         token_in: Coin,
         token_out_denom: &str,
-    ) -> Result<(TransmuterPool, Coin), ContractError> {
-        let mut pool = self.pool.load(deps.storage)?;
+    ) -> Result<(TransmuterPool, Coin, FeeCharge), ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+        self.out_amt_given_in_on_pool(deps, pool, token_in, token_out_denom)
+    }
+
+    /// Same as [`Self::out_amt_given_in`], but swapping against `pool` instead of the stored pool,
+    /// e.g. one with hypothetical corrupted assets.
+    pub fn out_amt_given_in_on_pool(
+        &self,
+        deps: Deps,
+        mut pool: TransmuterPool,
+        token_in: Coin,
+        token_out_denom: &str,
+    ) -> Result<(TransmuterPool, Coin, FeeCharge), ContractError> {
         let swap_variant = self.swap_variant(&token_in.denom, token_out_denom, deps)?;
+        let resulting_pool = fee_free_resulting_pool(
+            &pool,
+            std::slice::from_ref(&token_in),
+            &[self.fee_free_counterpart(deps.storage, &pool, &token_in, token_out_denom)?],
+        )?;
+        let (swap_fee, fee_split) = self.swap_fee_params(
+            deps.storage,
+            &Entrypoint::Sudo,
+            &[&token_in.denom, token_out_denom],
+            &resulting_pool,
+        )?;
+        // rounding is always in favor of the pool: the fee `token_in * swap_fee` is rounded up,
+        // so the net token in used to calculate token out is rounded down, and the conversion
+        // to token out is rounded down as well. The same holds when fee is charged on token out.
+        let fee_charge = FeeCharge::from_gross_amount(token_in.amount, swap_fee, fee_split)?;
+        let net_token_in = Coin::new(fee_charge.net_amount.u128(), &token_in.denom);
+        let pre_swap_pool = pool.clone();
 
         Ok(match swap_variant {
             SwapVariant::TokenToAlloyed => {
@@ -503,13 +1343,13 @@ impl Transmuter<'_> {
                     .normalization_factor();
 
                 let token_out_amount = swap_to_alloyed::out_amount_via_exact_in(
-                    vec![(token_in.clone(), token_in_norm_factor)],
+                    vec![(net_token_in, token_in_norm_factor)],
                     Uint128::zero(),
                     self.alloyed_asset.get_normalization_factor(deps.storage)?,
                 )?;
                 let token_out = Coin::new(token_out_amount.u128(), token_out_denom);
-                pool.join_pool(&[token_in])?;
-                (pool, token_out)
+                pool.join_pool(&[Coin::new(fee_charge.pool_amount()?.u128(), token_in.denom)])?;
+                (pool, token_out, fee_charge)
             }
             SwapVariant::AlloyedToToken => {
                 let token_out_norm_factor = pool
@@ -517,261 +1357,1737 @@ impl Transmuter<'_> {
                     .normalization_factor();
 
                 let token_out_amount = swap_from_alloyed::out_amount_via_exact_in(
-                    token_in.amount,
+                    net_token_in.amount,
                     self.alloyed_asset.get_normalization_factor(deps.storage)?,
                     token_out_norm_factor,
                     Uint128::zero(),
                 )?;
+                let token_out_amount =
+                    token_out_amount.checked_add(self.corruption_drain_bonus(
+                        deps,
+                        &pre_swap_pool,
+                        &Coin::new(token_out_amount.u128(), token_out_denom),
+                    )?)?;
                 let token_out = Coin::new(token_out_amount.u128(), token_out_denom);
-                pool.exit_pool(&[token_out.clone()])?;
-                (pool, token_out)
+                pool.exit_pool(std::slice::from_ref(&token_out))?;
+                (pool, token_out, fee_charge)
             }
             SwapVariant::TokenToToken => {
+                let fee_side = self.fee.get_fee_side(deps.storage)?;
+
+                // fee charged on token out leaves the whole token in to be swapped
+                let net_token_in = match fee_side {
+                    FeeSide::In => net_token_in,
+                    FeeSide::Out => token_in.clone(),
+                };
+
                 let (actual_token_in, token_out) = pool.transmute(
-                    AmountConstraint::exact_in(token_in.amount),
+                    AmountConstraint::exact_in(net_token_in.amount),
                     &token_in.denom,
                     token_out_denom,
                 )?;
 
-                // ensure that actual_token_in is equal to token_in
-                ensure_eq!(
-                    token_in,
-                    actual_token_in,
+                // ensure that actual_token_in is equal to net token_in, within tolerance
+                ensure!(
+                    net_token_in.denom == actual_token_in.denom
+                        && within_tolerance(
+                            net_token_in.amount,
+                            actual_token_in.amount,
+                            self.load_amount_tolerance(deps.storage)?
+                        ),
                     ContractError::InvalidTokenInAmount {
-                        expected: token_in.amount,
+                        expected: net_token_in.amount,
                         actual: actual_token_in.amount
                     }
                 );
 
-                (pool, token_out)
+                let (token_out, fee_charge) = match fee_side {
+                    FeeSide::In => {
+                        // retained fee stays in the pool
+                        pool.join_pool(&[Coin::new(
+                            fee_charge.retained_amount.u128(),
+                            token_in.denom,
+                        )])?;
+
+                        (token_out, fee_charge)
+                    }
+                    FeeSide::Out => {
+                        let fee_charge =
+                            FeeCharge::from_gross_amount(token_out.amount, swap_fee, fee_split)?;
+
+                        // retained fee stays in the pool
+                        pool.join_pool(&[Coin::new(
+                            fee_charge.retained_amount.u128(),
+                            &token_out.denom,
+                        )])?;
+
+                        let token_out = Coin::new(fee_charge.net_amount.u128(), token_out.denom);
+                        (token_out, fee_charge)
+                    }
+                };
+
+                // bonus for draining corrupted asset
+                let bonus = self.corruption_drain_bonus(deps, &pre_swap_pool, &token_out)?;
+                let bonus = Coin::new(bonus.u128(), &token_out.denom);
+                pool.exit_pool(std::slice::from_ref(&bonus))?;
+                let token_out = Coin::new(
+                    token_out.amount.checked_add(bonus.amount)?.u128(),
+                    token_out.denom,
+                );
+
+                (pool, token_out, fee_charge)
             }
         })
     }
 
-    pub fn ensure_valid_swap_fee(&self, swap_fee: Decimal) -> Result<(), ContractError> {
-        // ensure swap fee is the same as one from get_swap_fee which essentially is always 0
-        // in case where the swap fee mismatch, it can cause the pool to be imbalanced
-        ensure_eq!(
-            swap_fee,
-            SWAP_FEE,
-            ContractError::InvalidSwapFee {
-                expected: SWAP_FEE,
-                actual: swap_fee
-            }
-        );
-        Ok(())
+    /// Shares to lock in the contract on the first mint, which is when minimum locked shares is set
+    /// and the alloyed asset has no supply yet. Locked shares keep the share supply from
+    /// ever being drained to zero.
+    fn shares_to_lock(&self, deps: Deps) -> Result<Uint128, ContractError> {
+        let Some(min_locked_shares) = self.min_locked_shares.may_load(deps.storage)? else {
+            return Ok(Uint128::zero());
+        };
+
+        if self.alloyed_asset.get_total_supply(deps)?.is_zero() {
+            Ok(min_locked_shares)
+        } else {
+            Ok(Uint128::zero())
+        }
     }
 
-    /// remove corrupted assets from the pool & deregister all limiters for that denom
-    /// when each corrupted asset is all redeemed
-    fn clean_up_drained_corrupted_assets(
+    /// Record minted alloyed asset amount in the rolling mint window,
+    /// rejecting the mint if the amount minted within the window would exceed the mint rate limit.
+    pub fn record_mint(
         &self,
         storage: &mut dyn Storage,
-        pool: &mut TransmuterPool,
+        amount: Uint128,
+        block_time: Timestamp,
     ) -> Result<(), ContractError> {
-        for corrupted in pool.clone().corrupted_assets() {
-            if corrupted.amount().is_zero() {
-                pool.remove_corrupted_asset(corrupted.denom())?;
-                self.limiters
-                    .uncheck_deregister_all_for_denom(storage, corrupted.denom())?;
+        let Some(MintRateLimit {
+            max_mint_per_window,
+            window,
+        }) = self.mint_rate_limit.may_load(storage)?
+        else {
+            return Ok(());
+        };
+
+        if amount.is_zero() {
+            return Ok(());
+        }
+
+        self.mint_volumes
+            .record(storage, amount, window, max_mint_per_window, block_time)
+    }
+
+    /// Accumulate the value of burned alloyed asset that is not paid out as tokens out.
+    /// Token out amounts are rounded down and burned amounts are rounded up,
+    /// so the remainder is retained in the pool rather than over-redeemed.
+    fn record_retained_dust(
+        &self,
+        storage: &mut dyn Storage,
+        pool: &TransmuterPool,
+        burned_amount: Uint128,
+        tokens_out: &[Coin],
+    ) -> Result<(), ContractError> {
+        let alloyed_denom_normalization_factor =
+            self.alloyed_asset.get_normalization_factor(storage)?;
+
+        let mut paid_out = Decimal256::zero();
+        for (coin, normalization_factor) in pool.pair_coins_with_normalization_factor(tokens_out)? {
+            paid_out = paid_out.checked_add(Decimal256::checked_from_ratio(
+                coin.amount.full_mul(alloyed_denom_normalization_factor),
+                normalization_factor,
+            )?)?;
+        }
+
+        // paid out value only exceeds burned amount by corruption drain bonus, which is not dust
+        let dust = Decimal256::from_ratio(burned_amount, 1u128).saturating_sub(paid_out);
+
+        self.add_retained_dust(storage, dust)
+    }
+
+    /// Record the rounding residual of a token to token swap in the rounding audit log,
+    /// if enabled. Ideal token out is converted from token in net of fee without rounding,
+    /// less the fee if charged on token out, so the residual is what rounding kept in the pool.
+    fn record_rounding_residual(
+        &self,
+        storage: &mut dyn Storage,
+        pool: &TransmuterPool,
+        token_in: &Coin,
+        token_out: &Coin,
+        fee: Coin,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        if !self
+            .enable_rounding_audit
+            .may_load(storage)?
+            .unwrap_or_default()
+        {
+            return Ok(());
+        }
+
+        let token_in_norm_factor = pool
+            .get_pool_asset_by_denom(&token_in.denom)?
+            .normalization_factor();
+        let token_out_norm_factor = pool
+            .get_pool_asset_by_denom(&token_out.denom)?
+            .normalization_factor();
+
+        let (fee_in, fee_out) = if fee.denom == token_in.denom {
+            (fee.amount, Uint128::zero())
+        } else {
+            (Uint128::zero(), fee.amount)
+        };
+        let net_token_in_amount = token_in.amount.checked_sub(fee_in)?;
+
+        let ideal_token_out_amount = Decimal256::checked_from_ratio(
+            net_token_in_amount.full_mul(token_out_norm_factor),
+            token_in_norm_factor,
+        )?
+        .checked_sub(Decimal256::from_ratio(fee_out, 1u128))?;
+
+        // (net token in * out norm factor - (fee out + token out) * in norm factor) / in norm factor
+        let residual_numerator = Int256::from(net_token_in_amount)
+            .checked_mul(Int256::from(token_out_norm_factor))?
+            .checked_sub(
+                Int256::from(fee_out.checked_add(token_out.amount)?)
+                    .checked_mul(Int256::from(token_in_norm_factor))?,
+            )?;
+        let residual = SignedDecimal256::checked_from_ratio(
+            residual_numerator,
+            Int256::from(token_in_norm_factor),
+        )?;
+
+        self.rounding_audit.record(
+            storage,
+            RoundingResidual {
+                id: 0,
+                token_in: token_in.clone(),
+                token_out: token_out.clone(),
+                fee,
+                ideal_token_out_amount,
+                residual,
+                time: block_time,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    fn add_retained_dust(
+        &self,
+        storage: &mut dyn Storage,
+        dust: Decimal256,
+    ) -> Result<(), ContractError> {
+        let retained_dust = self
+            .retained_dust
+            .may_load(storage)?
+            .unwrap_or_default()
+            .checked_add(dust)?;
+        self.retained_dust.save(storage, &retained_dust)?;
+
+        Ok(())
+    }
+
+    /// Sweep pool balances of `denoms` left below the dust threshold out of pool accounting.
+    /// Swept tokens stay in the contract, backing the alloyed asset, and their value
+    /// is added to retained dust, so they effectively become a fee to the remaining holders.
+    fn sweep_dust(
+        &self,
+        storage: &mut dyn Storage,
+        pool: &mut TransmuterPool,
+        denoms: &[&str],
+    ) -> Result<(), ContractError> {
+        let dust_threshold = self.dust_threshold.may_load(storage)?.unwrap_or_default();
+        if dust_threshold.is_zero() {
+            return Ok(());
+        }
+
+        let alloyed_denom_normalization_factor =
+            self.alloyed_asset.get_normalization_factor(storage)?;
+
+        for denom in denoms {
+            let asset = pool.get_pool_asset_by_denom(denom)?;
+            if asset.amount().is_zero() || asset.amount() >= dust_threshold {
+                continue;
+            }
+
+            let dust = asset.to_coin();
+            let dust_value = Decimal256::checked_from_ratio(
+                dust.amount.full_mul(alloyed_denom_normalization_factor),
+                asset.normalization_factor(),
+            )?;
+
+            pool.unchecked_exit_pool(&[dust])?;
+            self.add_retained_dust(storage, dust_value)?;
+        }
+
+        Ok(())
+    }
+
+    fn load_amount_tolerance(&self, storage: &dyn Storage) -> Result<Uint128, ContractError> {
+        Ok(self.amount_tolerance.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn ensure_valid_swap_fee(
+        &self,
+        storage: &dyn Storage,
+        swap_fee: Decimal,
+    ) -> Result<(), ContractError> {
+        // a fee of 100% or more would zero out or invert the output,
+        // so reject it before it gets anywhere near the swap computation
+        ensure_swap_fee_less_than_one(swap_fee)?;
+
+        // ensure swap fee is the same as one from get_swap_fee
+        // in case where the swap fee mismatch, it can cause the pool to be imbalanced
+        let expected_swap_fee = self.fee.get_swap_fee(storage)?;
+        ensure_eq!(
+            swap_fee,
+            expected_swap_fee,
+            ContractError::InvalidSwapFee {
+                expected: expected_swap_fee,
+                actual: swap_fee
+            }
+        );
+        Ok(())
+    }
+
+    /// Whether the pool weights pass all limiters, without updating limiter states.
+    pub fn passes_limiters(
+        &self,
+        storage: &dyn Storage,
+        pool: &TransmuterPool,
+        block_time: Timestamp,
+    ) -> Result<bool, ContractError> {
+        let Some(denom_weight_pairs) = pool.weights()? else {
+            return Ok(true);
+        };
+
+        match self
+            .limiters
+            .check_limits(storage, denom_weight_pairs, block_time)
+        {
+            Ok(()) => Ok(true),
+            Err(
+                ContractError::UpperLimitExceeded { .. }
+                | ContractError::MaxDeviationExceeded { .. },
+            ) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Find the maximum token in amount, up to `token_in.amount`, that can be swapped
+    /// for `token_out_denom` without breaching any limiter.
+    ///
+    /// Since increasing token in amount only moves weights further in the same direction,
+    /// whether an amount passes the limiters is monotonic and can be binary searched.
+    /// Returns `None` if no amount can be swapped without breaching limiters.
+    pub fn max_fillable_token_in_amount(
+        &self,
+        deps: Deps,
+        block_time: Timestamp,
+        token_in: &Coin,
+        token_out_denom: &str,
+    ) -> Result<Option<Uint128>, ContractError> {
+        let fillable = |amount: Uint128| -> Result<bool, ContractError> {
+            let (pool, _, _) = self.out_amt_given_in(
+                deps,
+                Coin::new(amount.u128(), &token_in.denom),
+                token_out_denom,
+            )?;
+
+            self.passes_limiters(deps.storage, &pool, block_time)
+        };
+
+        let max_amount = max_passing_amount(token_in.amount, fillable)?;
+
+        Ok(if max_amount.is_zero() {
+            None
+        } else {
+            Some(max_amount)
+        })
+    }
+
+    /// Largest amount of `token_in_denom` that, swapped for `token_out_denom`, keeps the weight
+    /// of `token_out_denom` at or above `target_weight`, capped at the max amount before a limiter binds.
+    /// Swapping in only lowers the weight of the out denom, so zero is returned if the weight
+    /// is already at or below the target.
+    pub fn max_input_within_target_weight(
+        &self,
+        deps: Deps,
+        block_time: Timestamp,
+        token_in_denom: &str,
+        token_out_denom: &str,
+        target_weight: Decimal,
+    ) -> Result<Uint128, ContractError> {
+        let out_weight = |pool: &TransmuterPool| -> Result<Decimal, ContractError> {
+            Ok(pool
+                .weights()?
+                .unwrap_or_default()
+                .into_iter()
+                .find(|(denom, _)| denom == token_out_denom)
+                .map(|(_, weight)| weight)
+                .unwrap_or_default())
+        };
+
+        let pool = self.pool.load(deps.storage)?;
+        let out_asset = pool.get_pool_asset_by_denom(token_out_denom)?;
+        if out_weight(&pool)? <= target_weight {
+            return Ok(Uint128::zero());
+        }
+
+        let within_target = |amount: Uint128| -> Result<bool, ContractError> {
+            let pool = match self.out_amt_given_in(
+                deps,
+                Coin::new(amount.u128(), token_in_denom),
+                token_out_denom,
+            ) {
+                Ok((pool, _, _)) => pool,
+                // amount is more than the whole out denom balance is worth
+                Err(ContractError::InsufficientPoolAsset { .. }) => return Ok(false),
+                Err(err) => return Err(err),
+            };
+
+            Ok(out_weight(&pool)? >= target_weight
+                && self.passes_limiters(deps.storage, &pool, block_time)?)
+        };
+
+        // draining the whole out denom balance bounds the input, with headroom for fee
+        let max_amount = self
+            .fee_free_counterpart(deps.storage, &pool, &out_asset.to_coin(), token_in_denom)?
+            .amount
+            .checked_mul(Uint128::new(2))?;
+
+        max_passing_amount(max_amount, within_target)
+    }
+
+    /// Record a trip for every limiter that would reject swapping the full `token_in`.
+    /// Used when the swap is partially filled instead, since a rejected swap reverts its own trips.
+    pub fn record_partial_fill_trips(
+        &self,
+        deps: DepsMut,
+        block_time: Timestamp,
+        token_in: &Coin,
+        token_out_denom: &str,
+    ) -> Result<(), ContractError> {
+        let (pool, _, _) =
+            self.out_amt_given_in(deps.as_ref(), token_in.clone(), token_out_denom)?;
+        let Some(denom_weight_pairs) = pool.weights()? else {
+            return Ok(());
+        };
+
+        let tripped = self
+            .limiters
+            .check_each(deps.storage, denom_weight_pairs.clone(), block_time)?
+            .into_iter()
+            .filter(|(_, passes)| !passes);
+
+        for ((denom, label), _) in tripped {
+            let value = denom_weight_pairs
+                .iter()
+                .find(|(d, _)| d == &denom)
+                .map(|(_, weight)| *weight)
+                .unwrap_or_default();
+
+            self.limiters
+                .record_trip(deps.storage, &denom, &label, value, block_time)?;
+        }
+
+        Ok(())
+    }
+
+    /// Find the maximum alloyed asset amount that can be redeemed purely into `denom`,
+    /// capped by the denom's pool balance and by limiters.
+    ///
+    /// Redeeming more alloyed asset only drains more of `denom`,
+    /// so whether an amount can be redeemed is monotonic and can be binary searched.
+    pub fn max_redeemable_into(
+        &self,
+        deps: Deps,
+        block_time: Timestamp,
+        denom: &str,
+    ) -> Result<Uint128, ContractError> {
+        let alloyed_denom = self.alloyed_asset.get_alloyed_denom(deps.storage)?;
+        let balance = self
+            .pool
+            .load(deps.storage)?
+            .get_pool_asset_by_denom(denom)?
+            .amount();
+
+        if balance.is_zero() {
+            return Ok(Uint128::zero());
+        }
+
+        // alloyed asset amount required to drain the whole denom balance,
+        // rounded up, so it is an upper bound of the redeemable amount
+        let (_, drain_amount, _) = self.in_amt_given_out(
+            deps,
+            Coin::new(balance.u128(), denom),
+            alloyed_denom.clone(),
+        )?;
+
+        let redeemable = |amount: Uint128| -> Result<bool, ContractError> {
+            let pool = match self.out_amt_given_in(
+                deps,
+                Coin::new(amount.u128(), &alloyed_denom),
+                denom,
+            ) {
+                Ok((pool, _, _)) => pool,
+                Err(ContractError::InsufficientPoolAsset { .. }) => return Ok(false),
+                Err(e) => return Err(e),
+            };
+
+            self.passes_limiters(deps.storage, &pool, block_time)
+        };
+
+        max_passing_amount(drain_amount.amount, redeemable)
+    }
+
+    /// Total value of tokens in, denominated in alloyed asset amount,
+    /// so that tokens with different normalization factors are comparable.
+    fn swap_value(
+        &self,
+        storage: &dyn Storage,
+        pool: &TransmuterPool,
+        tokens_in: &[Coin],
+    ) -> Result<Uint128, ContractError> {
+        let alloyed_denom = self.alloyed_asset.may_get_alloyed_denom(storage)?;
+        let alloyed_normalization_factor = self.alloyed_asset.get_normalization_factor(storage)?;
+
+        let mut swap_value = Uint128::zero();
+        for coin in tokens_in {
+            let value = if Some(&coin.denom) == alloyed_denom.as_ref() {
+                coin.amount
+            } else {
+                convert_amount(
+                    coin.amount,
+                    pool.get_pool_asset_by_denom(&coin.denom)?
+                        .normalization_factor(),
+                    alloyed_normalization_factor,
+                    &Rounding::Up,
+                )?
+            };
+
+            swap_value = swap_value.checked_add(value)?;
+        }
+
+        Ok(swap_value)
+    }
+
+    /// Ensure that total liquidity, denominated in alloyed asset amount,
+    /// does not exceed max total liquidity, if set.
+    /// Total liquidity includes the backing of registered alloyed assets,
+    /// with `registered_alloyed_asset` taking the place of its stored version.
+    /// Only needs checking when alloyed asset is minted, since swaps between pool assets
+    /// do not change total liquidity.
+    pub fn ensure_within_max_total_liquidity(
+        &self,
+        storage: &dyn Storage,
+        pool: &TransmuterPool,
+        registered_alloyed_asset: Option<&RegisteredAlloyedAsset>,
+    ) -> Result<(), ContractError> {
+        let Some(max_total_liquidity) = self.max_total_liquidity.may_load(storage)? else {
+            return Ok(());
+        };
+
+        let pool_assets = pool
+            .pool_assets
+            .iter()
+            .map(Asset::to_coin)
+            .collect::<Vec<_>>();
+        let mut total_liquidity = self.swap_value(storage, pool, &pool_assets)?;
+
+        for stored in self.alloyed_registry.list(storage)? {
+            let alloyed_asset = match registered_alloyed_asset {
+                Some(alloyed_asset) if alloyed_asset.denom == stored.denom => alloyed_asset,
+                _ => &stored,
+            };
+            let backing_assets = alloyed_asset
+                .backing
+                .pool_assets
+                .iter()
+                .map(Asset::to_coin)
+                .collect::<Vec<_>>();
+            total_liquidity = total_liquidity.checked_add(self.swap_value(
+                storage,
+                &alloyed_asset.backing,
+                &backing_assets,
+            )?)?;
+        }
+
+        ensure!(
+            total_liquidity <= max_total_liquidity,
+            ContractError::TvlCapExceeded {
+                max: max_total_liquidity,
+                attempted: total_liquidity,
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Ensure swapping in none of `tokens_in`, which increase the pool's exposure to their denoms,
+    /// is blocked, see [`Self::is_swap_blocked`].
+    fn ensure_swap_not_blocked(
+        &self,
+        storage: &dyn Storage,
+        pool: &TransmuterPool,
+        tokens_in: &[Coin],
+    ) -> Result<(), ContractError> {
+        for token_in in tokens_in {
+            if let Some(reason) =
+                self.is_swap_blocked(storage, pool, &token_in.denom, SwapDirection::In)?
+            {
+                return Err(reason);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reason swapping `denom` in `direction` is blocked by corruption or asset group pause, if any.
+    ///
+    /// Both block swapping in and compose with OR semantics, i.e. either one alone blocks it.
+    /// Neither blocks swapping out, so that exposure to the denom can always be reduced.
+    /// When both apply, corruption is returned as the more specific reason, since it is
+    /// a state of the denom itself, while a pause applies to its whole asset group.
+    pub fn is_swap_blocked(
+        &self,
+        storage: &dyn Storage,
+        pool: &TransmuterPool,
+        denom: &str,
+        direction: SwapDirection,
+    ) -> Result<Option<ContractError>, ContractError> {
+        if direction == SwapDirection::Out {
+            return Ok(None);
+        }
+
+        if pool.is_corrupted_asset(denom) {
+            return Ok(Some(ContractError::CorruptedAssetRelativelyIncreased {
+                denom: denom.to_string(),
+            }));
+        }
+
+        if let Some(label) = self.asset_groups.paused_group_of(storage, denom)? {
+            return Ok(Some(ContractError::AssetGroupPaused {
+                label,
+                denom: denom.to_string(),
+            }));
+        }
+
+        Ok(None)
+    }
+
+    /// Check and update net flow limiters against the change of each pool asset amount
+    /// from the stored pool to `pool`, so this must be called before `pool` is saved.
+    fn check_net_flows_and_update(
+        &self,
+        storage: &mut dyn Storage,
+        pool: &TransmuterPool,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let prev_pool = self.pool.load(storage)?;
+
+        let denom_flow_pairs = pool
+            .pool_assets
+            .iter()
+            .map(|asset| {
+                let prev_amount = prev_pool
+                    .get_pool_asset_by_denom(asset.denom())
+                    .map(|prev_asset| prev_asset.amount())
+                    .unwrap_or_default();
+                let flow = Int256::from(asset.amount()).checked_sub(Int256::from(prev_amount))?;
+
+                Ok((asset.denom().to_string(), flow))
+            })
+            .collect::<Result<Vec<_>, ContractError>>()?;
+
+        self.limiters
+            .check_net_flows_and_update(storage, denom_flow_pairs, block_time)
+    }
+
+    /// Ensure each of `tokens_in` has at least one limiter registered for its denom,
+    /// if limiter coverage is required.
+    fn ensure_limiter_coverage(
+        &self,
+        storage: &dyn Storage,
+        tokens_in: &[Coin],
+    ) -> Result<(), ContractError> {
+        if !self
+            .require_limiter_coverage
+            .may_load(storage)?
+            .unwrap_or_default()
+        {
+            return Ok(());
+        }
+
+        for token_in in tokens_in {
+            ensure!(
+                !self
+                    .limiters
+                    .list_limiters_by_denom(storage, &token_in.denom)?
+                    .is_empty(),
+                ContractError::NoLimiterCoverage {
+                    denom: token_in.denom.clone()
+                }
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Whether the entire pool balance of `token_out_denom` may be swapped out.
+    /// Corrupted denoms are meant to be drained, so they are always allowed.
+    fn is_full_drain_allowed(
+        &self,
+        storage: &dyn Storage,
+        pool: &TransmuterPool,
+        token_out_denom: &str,
+    ) -> StdResult<bool> {
+        Ok(self.allow_full_drain.may_load(storage)?.unwrap_or(true)
+            || pool.is_corrupted_asset(token_out_denom))
+    }
+
+    /// Ensure a swap that took the entire pool balance of `token_out_denom` is allowed.
+    /// The balance left by an exact full drain is exactly zero, since the out amount is
+    /// checked against the balance without rounding, so this compares against zero only.
+    fn ensure_full_drain_allowed(
+        &self,
+        storage: &dyn Storage,
+        pool: &TransmuterPool,
+        token_out_denom: &str,
+    ) -> Result<(), ContractError> {
+        if self.is_full_drain_allowed(storage, pool, token_out_denom)? {
+            return Ok(());
+        }
+
+        ensure!(
+            !pool
+                .get_pool_asset_by_denom(token_out_denom)?
+                .amount()
+                .is_zero(),
+            ContractError::FullDrainNotAllowed {
+                denom: token_out_denom.to_string()
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Ensure that the swap value does not exceed max swap value, if set,
+    /// and that large swaps by the same sender respect the large swap cooldown, if set.
+    /// Time of the large swap is recorded for the sender.
+    fn ensure_swap_size_constraints(
+        &self,
+        storage: &mut dyn Storage,
+        pool: &TransmuterPool,
+        tokens_in: &[Coin],
+        sender: &Addr,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let max_swap_value = self.max_swap_value.may_load(storage)?;
+        let large_swap_cooldown = self.large_swap_cooldown.may_load(storage)?;
+
+        if max_swap_value.is_none() && large_swap_cooldown.is_none() {
+            return Ok(());
+        }
+
+        let swap_value = self.swap_value(storage, pool, tokens_in)?;
+
+        if let Some(max_swap_value) = max_swap_value {
+            ensure!(
+                swap_value <= max_swap_value,
+                ContractError::SwapTooLarge {
+                    max: max_swap_value,
+                    attempted: swap_value,
+                }
+            );
+        }
+
+        if let Some(LargeSwapCooldown {
+            threshold,
+            cooldown,
+        }) = large_swap_cooldown
+        {
+            if swap_value > threshold {
+                if let Some(last_large_swap_time) =
+                    self.last_large_swap_times.may_load(storage, sender)?
+                {
+                    let retry_after = last_large_swap_time.plus_nanos(cooldown.u64());
+                    ensure!(
+                        block_time >= retry_after,
+                        ContractError::SwapCooldownActive { retry_after }
+                    );
+                }
+
+                self.last_large_swap_times
+                    .save(storage, sender, &block_time)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Bonus paid on top of `token_out` when swapping out a corrupted asset,
+    /// to incentivize the market to drain it. The bonus is `corruption_drain_incentive`
+    /// of the token out amount, bounded by the fee reserve and the remaining pool balance
+    /// of the corrupted asset.
+    ///
+    /// Fee reserve is the pool value in excess of the alloyed asset supply,
+    /// which is accrued from swap fees retained in the pool.
+    fn corruption_drain_bonus(
+        &self,
+        deps: Deps,
+        pre_swap_pool: &TransmuterPool,
+        token_out: &Coin,
+    ) -> Result<Uint128, ContractError> {
+        if !pre_swap_pool.is_corrupted_asset(&token_out.denom) {
+            return Ok(Uint128::zero());
+        }
+
+        let incentive = self
+            .corruption_drain_incentive
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+
+        if incentive.is_zero() {
+            return Ok(Uint128::zero());
+        }
+
+        let alloyed_normalization_factor =
+            self.alloyed_asset.get_normalization_factor(deps.storage)?;
+        let pool_value = self.pool_value_in_alloyed_asset(deps.storage, pre_swap_pool)?;
+
+        let alloyed_supply = self.alloyed_asset.get_total_supply(deps)?;
+        let fee_reserve = pool_value.saturating_sub(alloyed_supply);
+
+        let corrupted_asset = pre_swap_pool.get_pool_asset_by_denom(&token_out.denom)?;
+        let max_bonus_by_reserve = convert_amount(
+            fee_reserve,
+            alloyed_normalization_factor,
+            corrupted_asset.normalization_factor(),
+            &Rounding::Down,
+        )?;
+        let remaining_amount = corrupted_asset.amount().saturating_sub(token_out.amount);
+
+        Ok(token_out
+            .amount
+            .checked_mul_floor(incentive)?
+            .min(max_bonus_by_reserve)
+            .min(remaining_amount))
+    }
+
+    /// (swap_fee, fee_split) to be applied on swap of `denoms` via the given entrypoint.
+    /// Swap fee is only charged on swaps via sudo, joining and exiting pool are free of charge.
+    /// In dynamic fee mode, the swap fee scales with the deviation of `denoms` from their
+    /// target weights in `resulting_pool`, the pool after the swap ignoring fee.
+    fn swap_fee_params(
+        &self,
+        storage: &dyn Storage,
+        entrypoint: &Entrypoint,
+        denoms: &[&str],
+        resulting_pool: &TransmuterPool,
+    ) -> Result<(Decimal, Decimal), ContractError> {
+        Ok(match entrypoint {
+            Entrypoint::Sudo => {
+                let resulting_deviation = match self.fee.get_dynamic_fee_params(storage)? {
+                    Some(_) => self.max_target_deviation(storage, resulting_pool, denoms)?,
+                    None => Decimal::zero(),
+                };
+
+                // effective swap fee is less than one, as each fee it is derived from
+                // is validated when set and dynamic swap fee is capped below one
+                (
+                    self.fee
+                        .get_effective_swap_fee(storage, denoms, resulting_deviation)?,
+                    self.fee.get_fee_split(storage)?,
+                )
+            }
+            Entrypoint::Exec => (Decimal::zero(), Decimal::zero()),
+        })
+    }
+
+    /// Amount of `denom` worth the same normalized value as `coin`, i.e. swapped without fee,
+    /// rounded down. Either side can be a pool asset or the alloyed asset.
+    fn fee_free_counterpart(
+        &self,
+        storage: &dyn Storage,
+        pool: &TransmuterPool,
+        coin: &Coin,
+        denom: &str,
+    ) -> Result<Coin, ContractError> {
+        let normalization_factor = |denom: &str| -> Result<Uint128, ContractError> {
+            if pool.has_denom(denom) {
+                Ok(pool.get_pool_asset_by_denom(denom)?.normalization_factor())
+            } else {
+                Ok(self.alloyed_asset.get_normalization_factor(storage)?)
             }
+        };
+
+        let amount = convert_amount(
+            coin.amount,
+            normalization_factor(&coin.denom)?,
+            normalization_factor(denom)?,
+            &Rounding::Down,
+        )?;
+
+        Ok(Coin::new(amount.u128(), denom))
+    }
+
+    /// send collected fee to the fee collector, zero amount fee is skipped
+    fn send_collected_fee(
+        &self,
+        storage: &dyn Storage,
+        response: Response,
+        fee_collected: Vec<Coin>,
+    ) -> Result<Response, ContractError> {
+        let fee_collected: Vec<Coin> = fee_collected
+            .into_iter()
+            .filter(|coin| !coin.amount.is_zero())
+            .collect();
+
+        if fee_collected.is_empty() {
+            return Ok(response);
+        }
+
+        let fee_collector = self
+            .fee
+            .get_fee_collector(storage)?
+            .ok_or(ContractError::FeeCollectorNotSet {})?;
+
+        Ok(response.add_message(BankMsg::Send {
+            to_address: fee_collector.to_string(),
+            amount: fee_collected,
+        }))
+    }
+
+    /// Accrue fee retained in the pool per denom, so that it can be withdrawn later
+    /// without touching the backing of outstanding alloyed asset.
+    fn accrue_retained_fee(
+        &self,
+        storage: &mut dyn Storage,
+        fee_retained: &[Coin],
+    ) -> Result<(), ContractError> {
+        for coin in fee_retained.iter().filter(|coin| !coin.amount.is_zero()) {
+            self.accrued_fees
+                .update(storage, &coin.denom, |accrued| -> StdResult<_> {
+                    Ok(accrued.unwrap_or_default().checked_add(coin.amount)?)
+                })?;
         }
 
-        Ok(())
-    }
-}
+        Ok(())
+    }
+
+    /// Total value of the pool in alloyed asset amount, rounded down.
+    pub fn pool_value_in_alloyed_asset(
+        &self,
+        storage: &dyn Storage,
+        pool: &TransmuterPool,
+    ) -> Result<Uint128, ContractError> {
+        let alloyed_normalization_factor = self.alloyed_asset.get_normalization_factor(storage)?;
+
+        let mut pool_value = Uint128::zero();
+        for asset in pool.pool_assets.iter() {
+            pool_value = pool_value.checked_add(convert_amount(
+                asset.amount(),
+                asset.normalization_factor(),
+                alloyed_normalization_factor,
+                &Rounding::Down,
+            )?)?;
+        }
+
+        Ok(pool_value)
+    }
+
+    /// remove corrupted assets from the pool & deregister all limiters for that denom
+    /// & remove the denom from asset groups when each corrupted asset is all redeemed
+    fn clean_up_drained_corrupted_assets(
+        &self,
+        storage: &mut dyn Storage,
+        pool: &mut TransmuterPool,
+    ) -> Result<(), ContractError> {
+        for corrupted in pool.clone().corrupted_assets() {
+            if corrupted.amount().is_zero() {
+                pool.remove_corrupted_asset(corrupted.denom())?;
+                self.limiters
+                    .uncheck_deregister_all_for_denom(storage, corrupted.denom())?;
+                self.asset_groups.remove_denom(storage, corrupted.denom())?;
+                self.directly_corrupted_denoms
+                    .remove(storage, corrupted.denom());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Possible variants of swap, depending on the input and output tokens
+#[derive(PartialEq, Debug)]
+pub enum SwapVariant {
+    /// Swap any token to alloyed asset
+    TokenToAlloyed,
+
+    /// Swap alloyed asset to any token
+    AlloyedToToken,
+
+    /// Swap any token to any token
+    TokenToToken,
+}
+
+pub enum Entrypoint {
+    Exec,
+    Sudo,
+}
+
+/// Direction of a swap relative to the pool balance of a denom
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SwapDirection {
+    /// Denom is swapped into the pool, increasing its pool balance
+    In,
+
+    /// Denom is swapped out of the pool, decreasing its pool balance
+    Out,
+}
+
+pub fn set_data_if_sudo<T>(
+    response: Response,
+    entrypoint: &Entrypoint,
+    data: &T,
+) -> Result<Response, StdError>
+where
+    T: Serialize + ?Sized,
+{
+    Ok(match entrypoint {
+        Entrypoint::Sudo => response.set_data(to_json_binary(data)?),
+        Entrypoint::Exec => response,
+    })
+}
+
+#[cw_serde]
+/// Fixing token in amount makes token amount out varies
+pub struct SwapExactAmountInResponseData {
+    pub token_out_amount: Uint128,
+}
+
+#[cw_serde]
+/// Fixing token out amount makes token amount in varies
+pub struct SwapExactAmountOutResponseData {
+    pub token_in_amount: Uint128,
+}
+
+#[derive(Debug)]
+pub enum SwapToAlloyedConstraint<'a> {
+    ExactIn {
+        tokens_in: &'a [Coin],
+        token_out_min_amount: Uint128,
+    },
+    ExactOut {
+        token_in_denom: &'a str,
+        token_in_max_amount: Uint128,
+        token_out_amount: Uint128,
+    },
+}
+
+#[derive(Debug)]
+pub enum SwapFromAlloyedConstraint<'a> {
+    ExactIn {
+        token_out_denom: &'a str,
+        token_out_min_amount: Uint128,
+        token_in_amount: Uint128,
+    },
+    ExactOut {
+        tokens_out: &'a [Coin],
+        token_in_max_amount: Uint128,
+    },
+}
+
+/// Cooldown between consecutive large swaps by the same sender
+#[cw_serde]
+pub struct LargeSwapCooldown {
+    /// Swap with value above this threshold, denominated in alloyed asset amount, is considered large
+    pub threshold: Uint128,
+
+    /// Duration in nanoseconds that the sender must wait before making another large swap
+    pub cooldown: Uint64,
+}
+
+#[cw_serde]
+pub struct MintRateLimit {
+    /// Maximum alloyed asset amount that can be minted within a window
+    pub max_mint_per_window: Uint128,
+
+    /// Window duration in nanoseconds
+    pub window: Uint64,
+}
+
+/// Determines where to burn alloyed assets from.
+pub enum BurnTarget {
+    /// Burn alloyed asset from the sender's account.
+    /// This is used when the sender wants to exit pool
+    /// forcing no funds attached in the process.
+    SenderAccount,
+    /// Burn alloyed assets from the sent funds.
+    /// This is used when the sender wants to swap tokens for alloyed assets,
+    /// since alloyed asset needs to be sent to the contract before swapping.
+    SentFunds,
+    /// Burn alloyed asset from the given owner's account on the sender's behalf,
+    /// spending the allowance the owner granted to the sender.
+    Address(Addr),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::limiter::LimiterParams;
+
+    use super::*;
+    use cosmwasm_std::{
+        coin,
+        testing::{mock_dependencies, mock_env, MOCK_CONTRACT_ADDR},
+        Uint256,
+    };
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("denom1", "denom2", Ok(SwapVariant::TokenToToken))]
+    #[case("denom2", "denom1", Ok(SwapVariant::TokenToToken))]
+    #[case("denom1", "denom1", Err(ContractError::SameDenomNotAllowed {
+        denom: "denom1".to_string()
+    }))]
+    #[case("denom1", "alloyed", Ok(SwapVariant::TokenToAlloyed))]
+    #[case("alloyed", "denom1", Ok(SwapVariant::AlloyedToToken))]
+    #[case("alloyed", "alloyed", Err(ContractError::SameDenomNotAllowed {
+        denom: "alloyed".to_string()
+    }))]
+    fn test_swap_variant(
+        #[case] denom1: &str,
+        #[case] denom2: &str,
+        #[case] res: Result<SwapVariant, ContractError>,
+    ) {
+        let mut deps = cosmwasm_std::testing::mock_dependencies();
+        let transmuter = Transmuter::new();
+        transmuter
+            .alloyed_asset
+            .set_alloyed_denom(&mut deps.storage, &"alloyed".to_string())
+            .unwrap();
+
+        assert_eq!(transmuter.swap_variant(denom1, denom2, deps.as_ref()), res);
+    }
+
+    #[rstest]
+    #[case(
+        Entrypoint::Exec,
+        SwapToAlloyedConstraint::ExactIn {
+            tokens_in: &[Coin::new(100, "denom1")],
+            token_out_min_amount: Uint128::one(),
+        },
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event("mint", "", "addr1", &Coin::new(10000u128, "alloyed")))
+            .add_message(MsgMint {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(10000u128, "alloyed").into()),
+                mint_to_address: "addr1".to_string()
+            })),
+    )]
+    #[case(
+        Entrypoint::Sudo,
+        SwapToAlloyedConstraint::ExactIn {
+            tokens_in: &[Coin::new(100, "denom1")],
+            token_out_min_amount: Uint128::one(),
+        },
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .set_data(to_json_binary(&SwapExactAmountInResponseData {
+                token_out_amount: Uint128::new(10000u128)
+            }).unwrap())
+            .add_event(alloyed_asset_transfer_event("mint", "", "addr1", &Coin::new(10000u128, "alloyed")))
+            .add_message(MsgMint {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(10000u128, "alloyed").into()),
+                mint_to_address: "addr1".to_string()
+            })),
+    )]
+    #[case(
+        Entrypoint::Exec,
+        SwapToAlloyedConstraint::ExactOut {
+            token_in_denom: "denom1",
+            token_in_max_amount: Uint128::new(100),
+            token_out_amount: Uint128::new(10000u128)
+        },
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event("mint", "", "addr1", &Coin::new(10000u128, "alloyed")))
+            .add_message(MsgMint {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(10000u128, "alloyed").into()),
+                mint_to_address: "addr1".to_string()
+            })),
+    )]
+    #[case(
+        Entrypoint::Sudo,
+        SwapToAlloyedConstraint::ExactOut {
+            token_in_denom: "denom1",
+            token_in_max_amount: Uint128::new(100),
+            token_out_amount: Uint128::new(10000u128)
+        },
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .set_data(to_json_binary(&SwapExactAmountOutResponseData {
+                token_in_amount: Uint128::new(100u128)
+            }).unwrap())
+            .add_event(alloyed_asset_transfer_event("mint", "", "addr1", &Coin::new(10000u128, "alloyed")))
+            .add_message(MsgMint {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(10000u128, "alloyed").into()),
+                mint_to_address: "addr1".to_string()
+            })),
+    )]
+    fn test_swap_tokens_to_alloyed_asset(
+        #[case] entrypoint: Entrypoint,
+        #[case] constraint: SwapToAlloyedConstraint,
+        #[case] mint_to_address: Addr,
+        #[case] expected_res: Result<Response, ContractError>,
+    ) {
+        let mut deps = mock_dependencies();
+        let transmuter = Transmuter::new();
+        transmuter
+            .alloyed_asset
+            .set_alloyed_denom(&mut deps.storage, &"alloyed".to_string())
+            .unwrap();
+
+        transmuter
+            .alloyed_asset
+            .set_normalization_factor(&mut deps.storage, 100u128.into())
+            .unwrap();
+
+        transmuter
+            .pool
+            .save(
+                &mut deps.storage,
+                &TransmuterPool {
+                    pool_assets: vec![
+                        Asset::new(Uint128::from(1000u128), "denom1", 1u128).unwrap(),
+                        Asset::new(Uint128::from(1000u128), "denom2", 10u128).unwrap(),
+                    ],
+                },
+            )
+            .unwrap();
+
+        let res = transmuter.swap_tokens_to_alloyed_asset(
+            entrypoint,
+            constraint,
+            mint_to_address,
+            deps.as_mut(),
+            mock_env(),
+        );
+
+        assert_eq!(res, expected_res);
+    }
+
+    #[rstest]
+    #[case(
+        Entrypoint::Exec,
+        SwapFromAlloyedConstraint::ExactIn {
+            token_out_denom: "denom1",
+            token_out_min_amount: Uint128::from(1u128),
+            token_in_amount: Uint128::from(100u128),
+        },
+        BurnTarget::SenderAccount,
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event("burn", "addr1", "", &Coin::new(100u128, "alloyed")))
+            .add_message(MsgBurn {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(100u128, "alloyed").into()),
+                burn_from_address: "addr1".to_string()
+            })
+            .add_message(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: vec![Coin::new(1u128, "denom1")]
+            }))
+    )]
+    #[case(
+        Entrypoint::Sudo,
+        SwapFromAlloyedConstraint::ExactIn {
+            token_out_denom: "denom1",
+            token_out_min_amount: Uint128::from(1u128),
+            token_in_amount: Uint128::from(100u128),
+        },
+        BurnTarget::SentFunds,
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event("burn", MOCK_CONTRACT_ADDR, "", &Coin::new(100u128, "alloyed")))
+            .add_message(MsgBurn {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(100u128, "alloyed").into()),
+                burn_from_address: MOCK_CONTRACT_ADDR.to_string()
+            })
+            .add_message(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: vec![Coin::new(1u128, "denom1")]
+            })
+            .set_data(to_json_binary(&SwapExactAmountInResponseData {
+                token_out_amount: Uint128::from(1u128)
+            }).unwrap()))
+    )]
+    #[case(
+        Entrypoint::Exec,
+        SwapFromAlloyedConstraint::ExactOut {
+            tokens_out: &[Coin::new(1u128, "denom1")],
+            token_in_max_amount: Uint128::from(100u128),
+        },
+        BurnTarget::SenderAccount,
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event("burn", "addr1", "", &Coin::new(100u128, "alloyed")))
+            .add_message(MsgBurn {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(100u128, "alloyed").into()),
+                burn_from_address: "addr1".to_string()
+            })
+            .add_message(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: vec![Coin::new(1u128, "denom1")]
+            }))
+    )]
+    #[case(
+        Entrypoint::Sudo,
+        SwapFromAlloyedConstraint::ExactOut {
+            tokens_out: &[Coin::new(1u128, "denom1")],
+            token_in_max_amount: Uint128::from(100u128),
+        },
+        BurnTarget::SentFunds,
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event("burn", MOCK_CONTRACT_ADDR, "", &Coin::new(100u128, "alloyed")))
+            .add_message(MsgBurn {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(100u128, "alloyed").into()),
+                burn_from_address: MOCK_CONTRACT_ADDR.to_string()
+            })
+            .add_message(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: vec![Coin::new(1u128, "denom1")]
+            })
+            .set_data(to_json_binary(&SwapExactAmountOutResponseData {
+                token_in_amount: Uint128::from(100u128)
+            }).unwrap()))
+    )]
+    fn test_swap_alloyed_asset_to_tokens(
+        #[case] entrypoint: Entrypoint,
+        #[case] constraint: SwapFromAlloyedConstraint,
+        #[case] burn_target: BurnTarget,
+        #[case] sender: Addr,
+        #[case] expected_res: Result<Response, ContractError>,
+    ) {
+        let alloyed_holder = match burn_target {
+            BurnTarget::SenderAccount => sender.to_string(),
+            BurnTarget::SentFunds => MOCK_CONTRACT_ADDR.to_string(),
+            BurnTarget::Address(ref owner) => owner.to_string(),
+        };
+
+        let mut deps = cosmwasm_std::testing::mock_dependencies_with_balances(&[(
+            alloyed_holder.as_str(),
+            &[Coin::new(110000000000000u128, "alloyed")],
+        )]);
+
+        let transmuter = Transmuter::new();
+        transmuter
+            .alloyed_asset
+            .set_alloyed_denom(&mut deps.storage, &"alloyed".to_string())
+            .unwrap();
+
+        transmuter
+            .alloyed_asset
+            .set_normalization_factor(&mut deps.storage, 100u128.into())
+            .unwrap();
+
+        transmuter
+            .pool
+            .save(
+                &mut deps.storage,
+                &TransmuterPool {
+                    pool_assets: vec![
+                        Asset::new(Uint128::from(1000000000000u128), "denom1", 1u128).unwrap(),
+                        Asset::new(Uint128::from(1000000000000u128), "denom2", 10u128).unwrap(),
+                    ],
+                },
+            )
+            .unwrap();
+
+        let res = transmuter.swap_alloyed_asset_to_tokens(
+            entrypoint,
+            constraint,
+            burn_target,
+            sender,
+            deps.as_mut(),
+            mock_env(),
+        );
+
+        assert_eq!(res, expected_res);
+
+        let pool = transmuter.pool.load(&deps.storage).unwrap();
+
+        for denom in ["denom1", "denom2"] {
+            assert!(pool.has_denom(denom))
+        }
+    }
+
+    #[rstest]
+    #[case(
+        Entrypoint::Sudo,
+        SwapFromAlloyedConstraint::ExactOut {
+            tokens_out: &[Coin::new(1000000000000u128, "denom1")],
+            token_in_max_amount: Uint128::from(100000000000000u128),
+        },
+        vec!["denom1"],
+        vec!["denom1"],
+        BurnTarget::SentFunds,
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event("burn", MOCK_CONTRACT_ADDR, "", &Coin::new(100000000000000u128, "alloyed")))
+            .add_message(MsgBurn {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(100000000000000u128, "alloyed").into()),
+                burn_from_address: MOCK_CONTRACT_ADDR.to_string()
+            })
+            .add_message(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: vec![Coin::new(1000000000000u128, "denom1")]
+            })
+            .set_data(to_json_binary(&SwapExactAmountOutResponseData {
+                token_in_amount: Uint128::from(100000000000000u128)
+            }).unwrap()))
+    )]
+    #[case(
+        Entrypoint::Sudo,
+        SwapFromAlloyedConstraint::ExactIn {
+            token_out_denom: "denom1",
+            token_out_min_amount: 1000000000000u128.into(),
+            token_in_amount: 100000000000000u128.into(),
+        },
+        vec!["denom1"],
+        vec!["denom1"],
+        BurnTarget::SentFunds,
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event("burn", MOCK_CONTRACT_ADDR, "", &Coin::new(100000000000000u128, "alloyed")))
+            .add_message(MsgBurn {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(100000000000000u128, "alloyed").into()),
+                burn_from_address: MOCK_CONTRACT_ADDR.to_string()
+            })
+            .add_message(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: vec![Coin::new(1000000000000u128, "denom1")]
+            })
+            .set_data(to_json_binary(&SwapExactAmountInResponseData {
+                token_out_amount: 1000000000000u128.into(),
+            }).unwrap()))
+    )]
+    #[case(
+        Entrypoint::Exec,
+        SwapFromAlloyedConstraint::ExactIn {
+            token_out_denom: "denom1",
+            token_out_min_amount: 1000000000000u128.into(),
+            token_in_amount: 100000000000000u128.into(),
+        },
+        vec!["denom1"],
+        vec!["denom1"],
+        BurnTarget::SenderAccount,
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event("burn", "addr1", "", &Coin::new(100000000000000u128, "alloyed")))
+            .add_message(MsgBurn {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(100000000000000u128, "alloyed").into()),
+                burn_from_address: "addr1".to_string()
+            })
+            .add_message(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: vec![Coin::new(1000000000000u128, "denom1")]
+            }))
+    )]
+    #[case(
+        Entrypoint::Exec,
+        SwapFromAlloyedConstraint::ExactOut {
+            tokens_out: &[Coin::new(1000000000000u128, "denom1")],
+            token_in_max_amount: Uint128::from(100000000000000u128),
+        },
+        vec!["denom1"],
+        vec!["denom1"],
+        BurnTarget::SenderAccount,
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event("burn", "addr1", "", &Coin::new(100000000000000u128, "alloyed")))
+            .add_message(MsgBurn {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(100000000000000u128, "alloyed").into()),
+                burn_from_address: "addr1".to_string()
+            })
+            .add_message(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: vec![Coin::new(1000000000000u128, "denom1")]
+            }))
+    )]
+    #[case(
+        Entrypoint::Sudo,
+        SwapFromAlloyedConstraint::ExactOut {
+            tokens_out: &[Coin::new(1000000000000u128, "denom1"), Coin::new(1000000000000u128, "denom2")],
+            token_in_max_amount: Uint128::from(110000000000000u128),
+        },
+        vec!["denom1", "denom2"],
+        vec!["denom1", "denom2"],
+        BurnTarget::SentFunds,
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event("burn", MOCK_CONTRACT_ADDR, "", &Coin::new(110000000000000u128, "alloyed")))
+            .add_message(MsgBurn {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(110000000000000u128, "alloyed").into()),
+                burn_from_address: MOCK_CONTRACT_ADDR.to_string()
+            })
+            .add_message(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: vec![Coin::new(1000000000000u128, "denom1"), Coin::new(1000000000000u128, "denom2")]
+            })
+            .set_data(to_json_binary(&SwapExactAmountOutResponseData {
+                token_in_amount: Uint128::from(110000000000000u128),
+            }).unwrap()))
+    )]
+    #[case(
+        Entrypoint::Sudo,
+        SwapFromAlloyedConstraint::ExactOut {
+            tokens_out: &[Coin::new(1000000000000u128, "denom1"), Coin::new(500000000000u128, "denom2")],
+            token_in_max_amount: Uint128::from(105000000000000u128),
+        },
+        vec!["denom1", "denom2"],
+        vec!["denom1"],
+        BurnTarget::SentFunds,
+        Addr::unchecked("addr1"),
+        Ok(Response::new()
+            .add_event(alloyed_asset_transfer_event("burn", MOCK_CONTRACT_ADDR, "", &Coin::new(105000000000000u128, "alloyed")))
+            .add_message(MsgBurn {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(105000000000000u128, "alloyed").into()),
+                burn_from_address: MOCK_CONTRACT_ADDR.to_string()
+            })
+            .add_message(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: vec![Coin::new(1000000000000u128, "denom1"), Coin::new(500000000000u128, "denom2")],
+            })
+            .set_data(to_json_binary(&SwapExactAmountOutResponseData {
+                token_in_amount: Uint128::from(105000000000000u128),
+            }).unwrap()))
+    )]
+    fn test_swap_alloyed_asset_to_tokens_with_corrupted_assets(
+        #[case] entrypoint: Entrypoint,
+        #[case] constraint: SwapFromAlloyedConstraint,
+        #[case] corrupted_denoms: Vec<&str>,
+        #[case] removed_denoms: Vec<&str>,
+        #[case] burn_target: BurnTarget,
+        #[case] sender: Addr,
+        #[case] expected_res: Result<Response, ContractError>,
+    ) {
+        let alloyed_holder = match burn_target {
+            BurnTarget::SenderAccount => sender.to_string(),
+            BurnTarget::SentFunds => MOCK_CONTRACT_ADDR.to_string(),
+            BurnTarget::Address(ref owner) => owner.to_string(),
+        };
+
+        let mut deps = cosmwasm_std::testing::mock_dependencies_with_balances(&[(
+            alloyed_holder.as_str(),
+            &[Coin::new(210000000000000u128, "alloyed")],
+        )]);
 
-/// Possible variants of swap, depending on the input and output tokens
-#[derive(PartialEq, Debug)]
-pub enum SwapVariant {
-    /// Swap any token to alloyed asset
-    TokenToAlloyed,
+        let transmuter = Transmuter::new();
+        transmuter
+            .alloyed_asset
+            .set_alloyed_denom(&mut deps.storage, &"alloyed".to_string())
+            .unwrap();
 
-    /// Swap alloyed asset to any token
-    AlloyedToToken,
+        transmuter
+            .alloyed_asset
+            .set_normalization_factor(&mut deps.storage, 100u128.into())
+            .unwrap();
 
-    /// Swap any token to any token
-    TokenToToken,
-}
+        let mut pool = TransmuterPool {
+            pool_assets: vec![
+                Asset::new(Uint128::from(1000000000000u128), "denom1", 1u128).unwrap(), // 1000000000000 * 100
+                Asset::new(Uint128::from(1000000000000u128), "denom2", 10u128).unwrap(), // 1000000000000 * 10
+                Asset::new(Uint128::from(1000000000000u128), "denom3", 1u128).unwrap(), // 1000000000000 * 100
+            ],
+        };
 
-pub enum Entrypoint {
-    Exec,
-    Sudo,
-}
+        let all_denoms = pool
+            .clone()
+            .pool_assets
+            .into_iter()
+            .map(|asset| asset.denom().to_string())
+            .collect::<Vec<_>>();
 
-pub fn set_data_if_sudo<T>(
-    response: Response,
-    entrypoint: &Entrypoint,
-    data: &T,
-) -> Result<Response, StdError>
-where
-    T: Serialize + ?Sized,
-{
-    Ok(match entrypoint {
-        Entrypoint::Sudo => response.set_data(to_json_binary(data)?),
-        Entrypoint::Exec => response,
-    })
-}
+        pool.mark_corrupted_assets(
+            corrupted_denoms
+                .iter()
+                .map(|denom| denom.to_string())
+                .collect::<Vec<_>>()
+                .as_slice(),
+        )
+        .unwrap();
 
-#[cw_serde]
-/// Fixing token in amount makes token amount out varies
-pub struct SwapExactAmountInResponseData {
-    pub token_out_amount: Uint128,
-}
+        transmuter.pool.save(&mut deps.storage, &pool).unwrap();
 
-#[cw_serde]
-/// Fixing token out amount makes token amount in varies
-pub struct SwapExactAmountOutResponseData {
-    pub token_in_amount: Uint128,
-}
+        for denom in all_denoms.clone() {
+            transmuter
+                .limiters
+                .register(
+                    &mut deps.storage,
+                    denom.as_str(),
+                    "static",
+                    LimiterParams::StaticLimiter {
+                        upper_limit: Decimal::percent(100),
+                    },
+                )
+                .unwrap();
+        }
 
-#[derive(Debug)]
-pub enum SwapToAlloyedConstraint<'a> {
-    ExactIn {
-        tokens_in: &'a [Coin],
-        token_out_min_amount: Uint128,
-    },
-    ExactOut {
-        token_in_denom: &'a str,
-        token_in_max_amount: Uint128,
-        token_out_amount: Uint128,
-    },
-}
+        let res = transmuter.swap_alloyed_asset_to_tokens(
+            entrypoint,
+            constraint,
+            burn_target,
+            sender,
+            deps.as_mut(),
+            mock_env(),
+        );
 
-#[derive(Debug)]
-pub enum SwapFromAlloyedConstraint<'a> {
-    ExactIn {
-        token_out_denom: &'a str,
-        token_out_min_amount: Uint128,
-        token_in_amount: Uint128,
-    },
-    ExactOut {
-        tokens_out: &'a [Coin],
-        token_in_max_amount: Uint128,
-    },
-}
+        assert_eq!(res, expected_res);
 
-/// Determines where to burn alloyed assets from.
-pub enum BurnTarget {
-    /// Burn alloyed asset from the sender's account.
-    /// This is used when the sender wants to exit pool
-    /// forcing no funds attached in the process.
-    SenderAccount,
-    /// Burn alloyed assets from the sent funds.
-    /// This is used when the sender wants to swap tokens for alloyed assets,
-    /// since alloyed asset needs to be sent to the contract before swapping.
-    SentFunds,
-}
+        // all drained denoms that are corrupted should not be in the pool
+        let pool = transmuter.pool.load(&deps.storage).unwrap();
 
-#[cfg(test)]
-mod tests {
-    use crate::{asset::Asset, limiter::LimiterParams};
+        for denom in all_denoms {
+            if removed_denoms.contains(&denom.as_str()) {
+                assert!(
+                    !pool.has_denom(denom.as_str()),
+                    "must not contain {} since it's corrupted and drained",
+                    denom
+                );
 
-    use super::*;
-    use cosmwasm_std::{
-        coin,
-        testing::{mock_dependencies, mock_env, MOCK_CONTRACT_ADDR},
-    };
-    use itertools::Itertools;
-    use rstest::rstest;
+                // limiters should be removed
+                assert!(
+                    transmuter
+                        .limiters
+                        .list_limiters_by_denom(&deps.storage, denom.as_str())
+                        .unwrap()
+                        .is_empty(),
+                    "must not contain limiter for {} since it's corrupted and drained",
+                    denom
+                );
+            } else {
+                assert!(
+                    pool.has_denom(denom.as_str()),
+                    "must contain {} since it's not corrupted or not drained",
+                    denom
+                );
 
-    #[rstest]
-    #[case("denom1", "denom2", Ok(SwapVariant::TokenToToken))]
-    #[case("denom2", "denom1", Ok(SwapVariant::TokenToToken))]
-    #[case("denom1", "denom1", Err(ContractError::SameDenomNotAllowed {
-        denom: "denom1".to_string()
-    }))]
-    #[case("denom1", "alloyed", Ok(SwapVariant::TokenToAlloyed))]
-    #[case("alloyed", "denom1", Ok(SwapVariant::AlloyedToToken))]
-    #[case("alloyed", "alloyed", Err(ContractError::SameDenomNotAllowed {
-        denom: "alloyed".to_string()
-    }))]
-    fn test_swap_variant(
-        #[case] denom1: &str,
-        #[case] denom2: &str,
-        #[case] res: Result<SwapVariant, ContractError>,
-    ) {
-        let mut deps = cosmwasm_std::testing::mock_dependencies();
+                // limiters should be removed
+                assert!(
+                    !transmuter
+                        .limiters
+                        .list_limiters_by_denom(&deps.storage, denom.as_str())
+                        .unwrap()
+                        .is_empty(),
+                    "must contain limiter for {} since it's not corrupted or not drained",
+                    denom
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_swap_non_alloyed_exact_amount_in_with_corrupted_assets() {
+        let mut deps = mock_dependencies();
         let transmuter = Transmuter::new();
         transmuter
             .alloyed_asset
             .set_alloyed_denom(&mut deps.storage, &"alloyed".to_string())
             .unwrap();
 
-        assert_eq!(transmuter.swap_variant(denom1, denom2, deps.as_ref()), res);
+        transmuter
+            .alloyed_asset
+            .set_normalization_factor(&mut deps.storage, 100u128.into())
+            .unwrap();
+
+        let mut pool = TransmuterPool {
+            pool_assets: vec![
+                Asset::new(Uint128::from(1000000000000u128), "denom1", 1u128).unwrap(), // 1000000000000 * 100
+                Asset::new(Uint128::from(1000000000000u128), "denom2", 10u128).unwrap(), // 1000000000000 * 10
+                Asset::new(Uint128::from(1000000000000u128), "denom3", 1u128).unwrap(), // 1000000000000 * 100
+            ],
+        };
+
+        let all_denoms = pool
+            .clone()
+            .pool_assets
+            .into_iter()
+            .map(|asset| asset.denom().to_string())
+            .collect::<Vec<_>>();
+
+        pool.mark_corrupted_assets(&["denom1".to_owned()]).unwrap();
+
+        transmuter.pool.save(&mut deps.storage, &pool).unwrap();
+
+        for denom in all_denoms.clone() {
+            transmuter
+                .limiters
+                .register(
+                    &mut deps.storage,
+                    denom.as_str(),
+                    "static",
+                    LimiterParams::StaticLimiter {
+                        upper_limit: Decimal::percent(100),
+                    },
+                )
+                .unwrap();
+        }
+
+        transmuter
+            .swap_non_alloyed_exact_amount_in(
+                coin(1000000000000, "denom3"),
+                "denom1",
+                1000000000000u128.into(),
+                deps.api.addr_make("sender"),
+                deps.as_mut(),
+                mock_env(),
+            )
+            .unwrap();
+
+        // all drained denoms that are corrupted should not be in the pool
+        let pool = transmuter.pool.load(&deps.storage).unwrap();
+
+        let denoms = pool
+            .pool_assets
+            .into_iter()
+            .map(|a| a.denom().to_string())
+            .collect_vec();
+
+        assert_eq!(denoms, vec!["denom2", "denom3"]);
+
+        let limiter_denoms = transmuter
+            .limiters
+            .list_limiters(&deps.storage)
+            .unwrap()
+            .into_iter()
+            .map(|((denom, _), _)| denom)
+            .unique()
+            .collect_vec();
+
+        assert_eq!(limiter_denoms, vec!["denom2", "denom3"]);
     }
 
-    #[rstest]
-    #[case(
-        Entrypoint::Exec,
-        SwapToAlloyedConstraint::ExactIn {
-            tokens_in: &[Coin::new(100, "denom1")],
-            token_out_min_amount: Uint128::one(),
-        },
-        Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .add_message(MsgMint {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(10000u128, "alloyed").into()),
-                mint_to_address: "addr1".to_string()
-            })),
-    )]
-    #[case(
-        Entrypoint::Sudo,
-        SwapToAlloyedConstraint::ExactIn {
-            tokens_in: &[Coin::new(100, "denom1")],
-            token_out_min_amount: Uint128::one(),
-        },
-        Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .set_data(to_json_binary(&SwapExactAmountInResponseData {
-                token_out_amount: Uint128::new(10000u128)
-            }).unwrap())
-            .add_message(MsgMint {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(10000u128, "alloyed").into()),
-                mint_to_address: "addr1".to_string()
-            })),
-    )]
-    #[case(
-        Entrypoint::Exec,
-        SwapToAlloyedConstraint::ExactOut {
-            token_in_denom: "denom1",
-            token_in_max_amount: Uint128::new(100),
-            token_out_amount: Uint128::new(10000u128)
-        },
-        Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .add_message(MsgMint {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(10000u128, "alloyed").into()),
-                mint_to_address: "addr1".to_string()
-            })),
-    )]
-    #[case(
-        Entrypoint::Sudo,
-        SwapToAlloyedConstraint::ExactOut {
-            token_in_denom: "denom1",
-            token_in_max_amount: Uint128::new(100),
-            token_out_amount: Uint128::new(10000u128)
-        },
-        Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .set_data(to_json_binary(&SwapExactAmountOutResponseData {
-                token_in_amount: Uint128::new(100u128)
-            }).unwrap())
-            .add_message(MsgMint {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(10000u128, "alloyed").into()),
-                mint_to_address: "addr1".to_string()
-            })),
-    )]
-    fn test_swap_tokens_to_alloyed_asset(
-        #[case] entrypoint: Entrypoint,
-        #[case] constraint: SwapToAlloyedConstraint,
-        #[case] mint_to_address: Addr,
-        #[case] expected_res: Result<Response, ContractError>,
-    ) {
+    #[test]
+    fn test_swap_non_alloyed_exact_amount_out_with_corrupted_assets() {
         let mut deps = mock_dependencies();
         let transmuter = Transmuter::new();
         transmuter
@@ -784,130 +3100,132 @@ mod tests {
             .set_normalization_factor(&mut deps.storage, 100u128.into())
             .unwrap();
 
+        let mut pool = TransmuterPool {
+            pool_assets: vec![
+                Asset::new(Uint128::from(1000000000000u128), "denom1", 1u128).unwrap(), // 1000000000000 * 100
+                Asset::new(Uint128::from(1000000000000u128), "denom2", 10u128).unwrap(), // 1000000000000 * 10
+                Asset::new(Uint128::from(1000000000000u128), "denom3", 1u128).unwrap(), // 1000000000000 * 100
+            ],
+        };
+
+        let all_denoms = pool
+            .clone()
+            .pool_assets
+            .into_iter()
+            .map(|asset| asset.denom().to_string())
+            .collect::<Vec<_>>();
+
+        pool.mark_corrupted_assets(&["denom1".to_owned()]).unwrap();
+
+        transmuter.pool.save(&mut deps.storage, &pool).unwrap();
+
+        for denom in all_denoms.clone() {
+            transmuter
+                .limiters
+                .register(
+                    &mut deps.storage,
+                    denom.as_str(),
+                    "static",
+                    LimiterParams::StaticLimiter {
+                        upper_limit: Decimal::percent(100),
+                    },
+                )
+                .unwrap();
+        }
+
         transmuter
-            .pool
-            .save(
-                &mut deps.storage,
-                &TransmuterPool {
-                    pool_assets: vec![
-                        Asset::new(Uint128::from(1000u128), "denom1", 1u128).unwrap(),
-                        Asset::new(Uint128::from(1000u128), "denom2", 10u128).unwrap(),
-                    ],
-                },
+            .swap_non_alloyed_exact_amount_out(
+                "denom3",
+                1000000000000u128.into(),
+                coin(1000000000000, "denom1"),
+                deps.api.addr_make("sender"),
+                deps.as_mut(),
+                mock_env(),
             )
             .unwrap();
 
-        let res = transmuter.swap_tokens_to_alloyed_asset(
-            entrypoint,
-            constraint,
-            mint_to_address,
-            deps.as_mut(),
-            mock_env(),
-        );
+        // all drained denoms that are corrupted should not be in the pool
+        let pool = transmuter.pool.load(&deps.storage).unwrap();
 
-        assert_eq!(res, expected_res);
+        let denoms = pool
+            .pool_assets
+            .into_iter()
+            .map(|a| a.denom().to_string())
+            .collect_vec();
+
+        assert_eq!(denoms, vec!["denom2", "denom3"]);
+
+        let limiter_denoms = transmuter
+            .limiters
+            .list_limiters(&deps.storage)
+            .unwrap()
+            .into_iter()
+            .map(|((denom, _), _)| denom)
+            .unique()
+            .collect_vec();
+
+        assert_eq!(limiter_denoms, vec!["denom2", "denom3"]);
     }
 
     #[rstest]
     #[case(
-        Entrypoint::Exec,
-        SwapFromAlloyedConstraint::ExactIn {
-            token_out_denom: "denom1",
-            token_out_min_amount: Uint128::from(1u128),
-            token_in_amount: Uint128::from(100u128),
-        },
-        BurnTarget::SenderAccount,
-        Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .add_message(MsgBurn {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(100u128, "alloyed").into()),
-                burn_from_address: "addr1".to_string()
-            })
-            .add_message(BankMsg::Send {
-                to_address: "addr1".to_string(),
-                amount: vec![Coin::new(1u128, "denom1")]
-            }))
-    )]
-    #[case(
-        Entrypoint::Sudo,
-        SwapFromAlloyedConstraint::ExactIn {
-            token_out_denom: "denom1",
-            token_out_min_amount: Uint128::from(1u128),
-            token_in_amount: Uint128::from(100u128),
-        },
-        BurnTarget::SentFunds,
+        Coin::new(100u128, "denom1"),
+        "denom2",
+        1000u128,
         Addr::unchecked("addr1"),
         Ok(Response::new()
-            .add_message(MsgBurn {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(100u128, "alloyed").into()),
-                burn_from_address: MOCK_CONTRACT_ADDR.to_string()
-            })
             .add_message(BankMsg::Send {
                 to_address: "addr1".to_string(),
-                amount: vec![Coin::new(1u128, "denom1")]
+                amount: vec![Coin::new(1000u128, "denom2")]
             })
             .set_data(to_json_binary(&SwapExactAmountInResponseData {
-                token_out_amount: Uint128::from(1u128)
+                token_out_amount: Uint128::from(1000u128)
             }).unwrap()))
     )]
     #[case(
-        Entrypoint::Exec,
-        SwapFromAlloyedConstraint::ExactOut {
-            tokens_out: &[Coin::new(1u128, "denom1")],
-            token_in_max_amount: Uint128::from(100u128),
-        },
-        BurnTarget::SenderAccount,
-        Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .add_message(MsgBurn {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(100u128, "alloyed").into()),
-                burn_from_address: "addr1".to_string()
-            })
-            .add_message(BankMsg::Send {
-                to_address: "addr1".to_string(),
-                amount: vec![Coin::new(1u128, "denom1")]
-            }))
-    )]
-    #[case(
-        Entrypoint::Sudo,
-        SwapFromAlloyedConstraint::ExactOut {
-            tokens_out: &[Coin::new(1u128, "denom1")],
-            token_in_max_amount: Uint128::from(100u128),
-        },
-        BurnTarget::SentFunds,
+        Coin::new(100u128, "denom2"),
+        "denom1",
+        10u128,
         Addr::unchecked("addr1"),
         Ok(Response::new()
-            .add_message(MsgBurn {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(100u128, "alloyed").into()),
-                burn_from_address: MOCK_CONTRACT_ADDR.to_string()
-            })
             .add_message(BankMsg::Send {
                 to_address: "addr1".to_string(),
-                amount: vec![Coin::new(1u128, "denom1")]
+                amount: vec![Coin::new(10u128, "denom1")]
             })
-            .set_data(to_json_binary(&SwapExactAmountOutResponseData {
-                token_in_amount: Uint128::from(100u128)
+            .set_data(to_json_binary(&SwapExactAmountInResponseData {
+                token_out_amount: Uint128::from(10u128)
             }).unwrap()))
     )]
-    fn test_swap_alloyed_asset_to_tokens(
-        #[case] entrypoint: Entrypoint,
-        #[case] constraint: SwapFromAlloyedConstraint,
-        #[case] burn_target: BurnTarget,
+    #[case(
+        Coin::new(100u128, "denom2"),
+        "denom1",
+        100u128,
+        Addr::unchecked("addr1"),
+        Err(ContractError::InsufficientTokenOut {
+            min_required: 100u128.into(),
+            amount_out: 10u128.into()
+        })
+    )]
+    #[case(
+        Coin::new(100000000001u128, "denom1"),
+        "denom2",
+        1000000000010u128,
+        Addr::unchecked("addr1"),
+        Err(ContractError::InsufficientPoolAsset {
+            required: Coin::new(1000000000010u128, "denom2"),
+            available: Coin::new(1000000000000u128, "denom2"),
+        })
+    )]
+    fn test_swap_non_alloyed_exact_amount_in(
+        #[case] token_in: Coin,
+        #[case] token_out_denom: &str,
+        #[case] token_out_min_amount: u128,
         #[case] sender: Addr,
         #[case] expected_res: Result<Response, ContractError>,
     ) {
-        let alloyed_holder = match burn_target {
-            BurnTarget::SenderAccount => sender.to_string(),
-            BurnTarget::SentFunds => MOCK_CONTRACT_ADDR.to_string(),
-        };
-
         let mut deps = cosmwasm_std::testing::mock_dependencies_with_balances(&[(
-            alloyed_holder.as_str(),
-            &[Coin::new(110000000000000u128, "alloyed")],
+            sender.to_string().as_str(),
+            &[Coin::new(2000000000000u128, "alloyed")],
         )]);
 
         let transmuter = Transmuter::new();
@@ -934,182 +3252,77 @@ mod tests {
             )
             .unwrap();
 
-        let res = transmuter.swap_alloyed_asset_to_tokens(
-            entrypoint,
-            constraint,
-            burn_target,
+        let res = transmuter.swap_non_alloyed_exact_amount_in(
+            token_in.clone(),
+            token_out_denom,
+            token_out_min_amount.into(),
             sender,
             deps.as_mut(),
             mock_env(),
         );
 
         assert_eq!(res, expected_res);
-
-        let pool = transmuter.pool.load(&deps.storage).unwrap();
-
-        for denom in ["denom1", "denom2"] {
-            assert!(pool.has_denom(denom))
-        }
     }
 
     #[rstest]
     #[case(
-        Entrypoint::Sudo,
-        SwapFromAlloyedConstraint::ExactOut {
-            tokens_out: &[Coin::new(1000000000000u128, "denom1")],
-            token_in_max_amount: Uint128::from(100000000000000u128),
-        },
-        vec!["denom1"],
-        vec!["denom1"],
-        BurnTarget::SentFunds,
+        "denom1",
+        100u128,
+        Coin::new(1000u128, "denom2"),
         Addr::unchecked("addr1"),
         Ok(Response::new()
-            .add_message(MsgBurn {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(100000000000000u128, "alloyed").into()),
-                burn_from_address: MOCK_CONTRACT_ADDR.to_string()
-            })
             .add_message(BankMsg::Send {
                 to_address: "addr1".to_string(),
-                amount: vec![Coin::new(1000000000000u128, "denom1")]
+                amount: vec![Coin::new(1000u128, "denom2")]
             })
             .set_data(to_json_binary(&SwapExactAmountOutResponseData {
-                token_in_amount: Uint128::from(100000000000000u128)
+                token_in_amount: 100u128.into()
             }).unwrap()))
     )]
     #[case(
-        Entrypoint::Sudo,
-        SwapFromAlloyedConstraint::ExactIn {
-            token_out_denom: "denom1",
-            token_out_min_amount: 1000000000000u128.into(),
-            token_in_amount: 100000000000000u128.into(),
-        },
-        vec!["denom1"],
-        vec!["denom1"],
-        BurnTarget::SentFunds,
+        "denom2",
+        100u128,
+        Coin::new(10u128, "denom1"),
         Addr::unchecked("addr1"),
         Ok(Response::new()
-            .add_message(MsgBurn {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(100000000000000u128, "alloyed").into()),
-                burn_from_address: MOCK_CONTRACT_ADDR.to_string()
-            })
             .add_message(BankMsg::Send {
                 to_address: "addr1".to_string(),
-                amount: vec![Coin::new(1000000000000u128, "denom1")]
+                amount: vec![Coin::new(10u128, "denom1")]
             })
-            .set_data(to_json_binary(&SwapExactAmountInResponseData {
-                token_out_amount: 1000000000000u128.into(),
+            .set_data(to_json_binary(&SwapExactAmountOutResponseData {
+                token_in_amount: 100u128.into()
             }).unwrap()))
     )]
     #[case(
-        Entrypoint::Exec,
-        SwapFromAlloyedConstraint::ExactIn {
-            token_out_denom: "denom1",
-            token_out_min_amount: 1000000000000u128.into(),
-            token_in_amount: 100000000000000u128.into(),
-        },
-        vec!["denom1"],
-        vec!["denom1"],
-        BurnTarget::SenderAccount,
-        Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .add_message(MsgBurn {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(100000000000000u128, "alloyed").into()),
-                burn_from_address: "addr1".to_string()
-            })
-            .add_message(BankMsg::Send {
-                to_address: "addr1".to_string(),
-                amount: vec![Coin::new(1000000000000u128, "denom1")]
-            }))
-    )]
-    #[case(
-        Entrypoint::Exec,
-        SwapFromAlloyedConstraint::ExactOut {
-            tokens_out: &[Coin::new(1000000000000u128, "denom1")],
-            token_in_max_amount: Uint128::from(100000000000000u128),
-        },
-        vec!["denom1"],
-        vec!["denom1"],
-        BurnTarget::SenderAccount,
-        Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .add_message(MsgBurn {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(100000000000000u128, "alloyed").into()),
-                burn_from_address: "addr1".to_string()
-            })
-            .add_message(BankMsg::Send {
-                to_address: "addr1".to_string(),
-                amount: vec![Coin::new(1000000000000u128, "denom1")]
-            }))
-    )]
-    #[case(
-        Entrypoint::Sudo,
-        SwapFromAlloyedConstraint::ExactOut {
-            tokens_out: &[Coin::new(1000000000000u128, "denom1"), Coin::new(1000000000000u128, "denom2")],
-            token_in_max_amount: Uint128::from(110000000000000u128),
-        },
-        vec!["denom1", "denom2"],
-        vec!["denom1", "denom2"],
-        BurnTarget::SentFunds,
+        "denom2",
+        100u128,
+        Coin::new(100u128, "denom1"),
         Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .add_message(MsgBurn {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(110000000000000u128, "alloyed").into()),
-                burn_from_address: MOCK_CONTRACT_ADDR.to_string()
-            })
-            .add_message(BankMsg::Send {
-                to_address: "addr1".to_string(),
-                amount: vec![Coin::new(1000000000000u128, "denom1"), Coin::new(1000000000000u128, "denom2")]
-            })
-            .set_data(to_json_binary(&SwapExactAmountOutResponseData {
-                token_in_amount: Uint128::from(110000000000000u128),
-            }).unwrap()))
+        Err(ContractError::ExcessiveRequiredTokenIn {
+            limit: 100u128.into(),
+            required: 1000u128.into()
+        })
     )]
     #[case(
-        Entrypoint::Sudo,
-        SwapFromAlloyedConstraint::ExactOut {
-            tokens_out: &[Coin::new(1000000000000u128, "denom1"), Coin::new(500000000000u128, "denom2")],
-            token_in_max_amount: Uint128::from(105000000000000u128),
-        },
-        vec!["denom1", "denom2"],
-        vec!["denom1"],
-        BurnTarget::SentFunds,
+        "denom1",
+        100000000001u128,
+        Coin::new(1000000000010u128, "denom2"),
         Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .add_message(MsgBurn {
-                sender: MOCK_CONTRACT_ADDR.to_string(),
-                amount: Some(Coin::new(105000000000000u128, "alloyed").into()),
-                burn_from_address: MOCK_CONTRACT_ADDR.to_string()
-            })
-            .add_message(BankMsg::Send {
-                to_address: "addr1".to_string(),
-                amount: vec![Coin::new(1000000000000u128, "denom1"), Coin::new(500000000000u128, "denom2")],
-            })
-            .set_data(to_json_binary(&SwapExactAmountOutResponseData {
-                token_in_amount: Uint128::from(105000000000000u128),
-            }).unwrap()))
+        Err(ContractError::InsufficientPoolAsset {
+            required: Coin::new(1000000000010u128, "denom2"),
+            available: Coin::new(1000000000000u128, "denom2"),
+        })
     )]
-    fn test_swap_alloyed_asset_to_tokens_with_corrupted_assets(
-        #[case] entrypoint: Entrypoint,
-        #[case] constraint: SwapFromAlloyedConstraint,
-        #[case] corrupted_denoms: Vec<&str>,
-        #[case] removed_denoms: Vec<&str>,
-        #[case] burn_target: BurnTarget,
+    fn test_swap_non_alloyed_exact_amount_out(
+        #[case] token_in_denom: &str,
+        #[case] token_in_max_amount: u128,
+        #[case] token_out: Coin,
         #[case] sender: Addr,
         #[case] expected_res: Result<Response, ContractError>,
     ) {
-        let alloyed_holder = match burn_target {
-            BurnTarget::SenderAccount => sender.to_string(),
-            BurnTarget::SentFunds => MOCK_CONTRACT_ADDR.to_string(),
-        };
-
         let mut deps = cosmwasm_std::testing::mock_dependencies_with_balances(&[(
-            alloyed_holder.as_str(),
-            &[Coin::new(210000000000000u128, "alloyed")],
+            sender.to_string().as_str(),
+            &[Coin::new(2000000000000u128, "alloyed")],
         )]);
 
         let transmuter = Transmuter::new();
@@ -1119,335 +3332,524 @@ mod tests {
             .unwrap();
 
         transmuter
-            .alloyed_asset
-            .set_normalization_factor(&mut deps.storage, 100u128.into())
-            .unwrap();
-
-        let mut pool = TransmuterPool {
-            pool_assets: vec![
-                Asset::new(Uint128::from(1000000000000u128), "denom1", 1u128).unwrap(), // 1000000000000 * 100
-                Asset::new(Uint128::from(1000000000000u128), "denom2", 10u128).unwrap(), // 1000000000000 * 10
-                Asset::new(Uint128::from(1000000000000u128), "denom3", 1u128).unwrap(), // 1000000000000 * 100
-            ],
-        };
-
-        let all_denoms = pool
-            .clone()
-            .pool_assets
-            .into_iter()
-            .map(|asset| asset.denom().to_string())
-            .collect::<Vec<_>>();
-
-        pool.mark_corrupted_assets(
-            corrupted_denoms
-                .iter()
-                .map(|denom| denom.to_string())
-                .collect::<Vec<_>>()
-                .as_slice(),
-        )
-        .unwrap();
-
-        transmuter.pool.save(&mut deps.storage, &pool).unwrap();
-
-        for denom in all_denoms.clone() {
-            transmuter
-                .limiters
-                .register(
-                    &mut deps.storage,
-                    denom.as_str(),
-                    "static",
-                    LimiterParams::StaticLimiter {
-                        upper_limit: Decimal::percent(100),
-                    },
-                )
-                .unwrap();
-        }
+            .alloyed_asset
+            .set_normalization_factor(&mut deps.storage, 100u128.into())
+            .unwrap();
 
-        let res = transmuter.swap_alloyed_asset_to_tokens(
-            entrypoint,
-            constraint,
-            burn_target,
+        transmuter
+            .pool
+            .save(
+                &mut deps.storage,
+                &TransmuterPool {
+                    pool_assets: vec![
+                        Asset::new(Uint128::from(1000000000000u128), "denom1", 1u128).unwrap(),
+                        Asset::new(Uint128::from(1000000000000u128), "denom2", 10u128).unwrap(),
+                    ],
+                },
+            )
+            .unwrap();
+
+        let res = transmuter.swap_non_alloyed_exact_amount_out(
+            token_in_denom,
+            token_in_max_amount.into(),
+            token_out,
             sender,
             deps.as_mut(),
             mock_env(),
         );
 
         assert_eq!(res, expected_res);
+    }
 
-        // all drained denoms that are corrupted should not be in the pool
-        let pool = transmuter.pool.load(&deps.storage).unwrap();
+    #[rstest]
+    #[case(Decimal::zero(), 0u128)]
+    #[case(Decimal::percent(50), 5u128)]
+    #[case(Decimal::percent(100), 10u128)]
+    fn test_swap_non_alloyed_exact_amount_in_with_fee_split(
+        #[case] fee_split: Decimal,
+        #[case] expected_collected: u128,
+    ) {
+        let mut deps = mock_dependencies();
+        let transmuter = Transmuter::new();
+        transmuter
+            .alloyed_asset
+            .set_alloyed_denom(&mut deps.storage, &"alloyed".to_string())
+            .unwrap();
 
-        for denom in all_denoms {
-            if removed_denoms.contains(&denom.as_str()) {
-                assert!(
-                    !pool.has_denom(denom.as_str()),
-                    "must not contain {} since it's corrupted and drained",
-                    denom
-                );
+        transmuter
+            .pool
+            .save(
+                &mut deps.storage,
+                &TransmuterPool {
+                    pool_assets: vec![
+                        Asset::new(Uint128::from(1000000000000u128), "denom1", 1u128).unwrap(),
+                        Asset::new(Uint128::from(1000000000000u128), "denom2", 10u128).unwrap(),
+                    ],
+                },
+            )
+            .unwrap();
 
-                // limiters should be removed
-                assert!(
-                    transmuter
-                        .limiters
-                        .list_limiters_by_denom(&deps.storage, denom.as_str())
-                        .unwrap()
-                        .is_empty(),
-                    "must not contain limiter for {} since it's corrupted and drained",
-                    denom
-                );
-            } else {
-                assert!(
-                    pool.has_denom(denom.as_str()),
-                    "must contain {} since it's not corrupted or not drained",
-                    denom
-                );
+        transmuter
+            .fee
+            .set_swap_fee(&mut deps.storage, Decimal::percent(1))
+            .unwrap();
+        transmuter
+            .fee
+            .set_fee_collector(&mut deps.storage, Addr::unchecked("collector"))
+            .unwrap();
+        transmuter
+            .fee
+            .set_fee_split(&mut deps.storage, fee_split)
+            .unwrap();
 
-                // limiters should be removed
-                assert!(
-                    !transmuter
-                        .limiters
-                        .list_limiters_by_denom(&deps.storage, denom.as_str())
-                        .unwrap()
-                        .is_empty(),
-                    "must contain limiter for {} since it's not corrupted or not drained",
-                    denom
-                );
-            }
+        // 1% fee of 1000denom1 is 10denom1, the rest (990denom1) is swapped for 9900denom2
+        let res = transmuter
+            .swap_non_alloyed_exact_amount_in(
+                Coin::new(1000, "denom1"),
+                "denom2",
+                9900u128.into(),
+                Addr::unchecked("addr1"),
+                deps.as_mut(),
+                mock_env(),
+            )
+            .unwrap();
+
+        let mut expected_res = Response::new()
+            .add_message(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: vec![Coin::new(9900, "denom2")],
+            })
+            .set_data(
+                to_json_binary(&SwapExactAmountInResponseData {
+                    token_out_amount: Uint128::new(9900),
+                })
+                .unwrap(),
+            );
+
+        if expected_collected > 0 {
+            expected_res = expected_res.add_message(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: vec![Coin::new(expected_collected, "denom1")],
+            });
         }
+
+        assert_eq!(res, expected_res);
+
+        // fee that is not sent to the collector is retained in the pool
+        let pool = transmuter.pool.load(&deps.storage).unwrap();
+        assert_eq!(
+            pool.pool_assets,
+            vec![
+                Asset::new(
+                    Uint128::from(1000000000000u128 + 1000 - expected_collected),
+                    "denom1",
+                    1u128
+                )
+                .unwrap(),
+                Asset::new(Uint128::from(1000000000000u128 - 9900), "denom2", 10u128).unwrap(),
+            ]
+        );
     }
 
-    #[test]
-    fn test_swap_non_alloyed_exact_amount_in_with_corrupted_assets() {
+    #[rstest]
+    // fee reserve covers the full bonus
+    #[case("denom1", 1_000_000u128, 1010u128)]
+    // bonus is bounded by fee reserve
+    #[case("denom1", 1_999_995u128, 1005u128)]
+    // no fee reserve
+    #[case("denom1", 2_000_000u128, 1000u128)]
+    // no bonus for non-corrupted asset
+    #[case("denom3", 1_000_000u128, 1000u128)]
+    fn test_swap_non_alloyed_exact_amount_in_with_corruption_drain_incentive(
+        #[case] token_out_denom: &str,
+        #[case] alloyed_supply: u128,
+        #[case] expected_out: u128,
+    ) {
         let mut deps = mock_dependencies();
+        deps.querier
+            .update_balance("someone", vec![Coin::new(alloyed_supply, "alloyed")]);
+
         let transmuter = Transmuter::new();
         transmuter
             .alloyed_asset
             .set_alloyed_denom(&mut deps.storage, &"alloyed".to_string())
             .unwrap();
-
         transmuter
             .alloyed_asset
-            .set_normalization_factor(&mut deps.storage, 100u128.into())
+            .set_normalization_factor(&mut deps.storage, Uint128::one())
             .unwrap();
 
         let mut pool = TransmuterPool {
             pool_assets: vec![
-                Asset::new(Uint128::from(1000000000000u128), "denom1", 1u128).unwrap(), // 1000000000000 * 100
-                Asset::new(Uint128::from(1000000000000u128), "denom2", 10u128).unwrap(), // 1000000000000 * 10
-                Asset::new(Uint128::from(1000000000000u128), "denom3", 1u128).unwrap(), // 1000000000000 * 100
+                Asset::new(Uint128::from(1_000_000u128), "denom1", 1u128).unwrap(),
+                Asset::new(Uint128::from(500_000u128), "denom2", 1u128).unwrap(),
+                Asset::new(Uint128::from(500_000u128), "denom3", 1u128).unwrap(),
             ],
         };
-
-        let all_denoms = pool
-            .clone()
-            .pool_assets
-            .into_iter()
-            .map(|asset| asset.denom().to_string())
-            .collect::<Vec<_>>();
-
-        pool.mark_corrupted_assets(&["denom1".to_owned()]).unwrap();
-
+        pool.mark_corrupted_assets(&["denom1".to_string()]).unwrap();
         transmuter.pool.save(&mut deps.storage, &pool).unwrap();
 
-        for denom in all_denoms.clone() {
-            transmuter
-                .limiters
-                .register(
-                    &mut deps.storage,
-                    denom.as_str(),
-                    "static",
-                    LimiterParams::StaticLimiter {
-                        upper_limit: Decimal::percent(100),
-                    },
-                )
-                .unwrap();
-        }
-
         transmuter
+            .corruption_drain_incentive
+            .save(&mut deps.storage, &Decimal::percent(1))
+            .unwrap();
+
+        let res = transmuter
             .swap_non_alloyed_exact_amount_in(
-                coin(1000000000000, "denom3"),
-                "denom1",
-                1000000000000u128.into(),
-                deps.api.addr_make("sender"),
+                Coin::new(1000, "denom2"),
+                token_out_denom,
+                1000u128.into(),
+                Addr::unchecked("addr1"),
                 deps.as_mut(),
                 mock_env(),
             )
             .unwrap();
 
-        // all drained denoms that are corrupted should not be in the pool
+        assert_eq!(
+            res,
+            Response::new()
+                .add_message(BankMsg::Send {
+                    to_address: "addr1".to_string(),
+                    amount: vec![Coin::new(expected_out, token_out_denom)],
+                })
+                .set_data(
+                    to_json_binary(&SwapExactAmountInResponseData {
+                        token_out_amount: Uint128::new(expected_out),
+                    })
+                    .unwrap(),
+                )
+        );
+
         let pool = transmuter.pool.load(&deps.storage).unwrap();
+        assert_eq!(
+            pool.get_pool_asset_by_denom(token_out_denom)
+                .unwrap()
+                .amount(),
+            Uint128::new(
+                if token_out_denom == "denom1" {
+                    1_000_000
+                } else {
+                    500_000
+                } - expected_out
+            )
+        );
+    }
 
-        let denoms = pool
-            .pool_assets
-            .into_iter()
-            .map(|a| a.denom().to_string())
-            .collect_vec();
+    #[rstest]
+    // in denom's higher fee dominates
+    #[case(Decimal::percent(1), Some(Decimal::percent(2)), None, 9800u128)]
+    // out denom's higher fee dominates
+    #[case(
+        Decimal::percent(1),
+        Some(Decimal::percent(2)),
+        Some(Decimal::percent(4)),
+        9600u128
+    )]
+    // module fee dominates
+    #[case(Decimal::percent(3), Some(Decimal::percent(2)), None, 9700u128)]
+    fn test_swap_non_alloyed_exact_amount_in_with_denom_swap_fee(
+        #[case] swap_fee: Decimal,
+        #[case] denom_in_swap_fee: Option<Decimal>,
+        #[case] denom_out_swap_fee: Option<Decimal>,
+        #[case] expected_out: u128,
+    ) {
+        let mut deps = mock_dependencies();
+        let transmuter = Transmuter::new();
+        transmuter
+            .alloyed_asset
+            .set_alloyed_denom(&mut deps.storage, &"alloyed".to_string())
+            .unwrap();
 
-        assert_eq!(denoms, vec!["denom2", "denom3"]);
+        transmuter
+            .pool
+            .save(
+                &mut deps.storage,
+                &TransmuterPool {
+                    pool_assets: vec![
+                        Asset::new(Uint128::from(1000000000000u128), "denom1", 1u128).unwrap(),
+                        Asset::new(Uint128::from(1000000000000u128), "denom2", 10u128).unwrap(),
+                    ],
+                },
+            )
+            .unwrap();
 
-        let limiter_denoms = transmuter
-            .limiters
-            .list_limiters(&deps.storage)
-            .unwrap()
-            .into_iter()
-            .map(|((denom, _), _)| denom)
-            .unique()
-            .collect_vec();
+        transmuter
+            .fee
+            .set_swap_fee(&mut deps.storage, swap_fee)
+            .unwrap();
+        transmuter
+            .fee
+            .set_denom_swap_fee(&mut deps.storage, "denom1", denom_in_swap_fee)
+            .unwrap();
+        transmuter
+            .fee
+            .set_denom_swap_fee(&mut deps.storage, "denom2", denom_out_swap_fee)
+            .unwrap();
 
-        assert_eq!(limiter_denoms, vec!["denom2", "denom3"]);
+        let res = transmuter
+            .swap_non_alloyed_exact_amount_in(
+                Coin::new(1000, "denom1"),
+                "denom2",
+                expected_out.into(),
+                Addr::unchecked("addr1"),
+                deps.as_mut(),
+                mock_env(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new()
+                .add_message(BankMsg::Send {
+                    to_address: "addr1".to_string(),
+                    amount: vec![Coin::new(expected_out, "denom2")],
+                })
+                .set_data(
+                    to_json_binary(&SwapExactAmountInResponseData {
+                        token_out_amount: Uint128::new(expected_out),
+                    })
+                    .unwrap(),
+                )
+        );
     }
 
-    #[test]
-    fn test_swap_non_alloyed_exact_amount_out_with_corrupted_assets() {
+    #[rstest]
+    #[case(Decimal::zero(), 0u128)]
+    #[case(Decimal::percent(50), 5u128)]
+    #[case(Decimal::percent(100), 10u128)]
+    fn test_swap_tokens_to_alloyed_asset_with_fee_split(
+        #[case] fee_split: Decimal,
+        #[case] expected_collected: u128,
+    ) {
         let mut deps = mock_dependencies();
         let transmuter = Transmuter::new();
         transmuter
             .alloyed_asset
             .set_alloyed_denom(&mut deps.storage, &"alloyed".to_string())
             .unwrap();
-
         transmuter
             .alloyed_asset
             .set_normalization_factor(&mut deps.storage, 100u128.into())
             .unwrap();
 
-        let mut pool = TransmuterPool {
-            pool_assets: vec![
-                Asset::new(Uint128::from(1000000000000u128), "denom1", 1u128).unwrap(), // 1000000000000 * 100
-                Asset::new(Uint128::from(1000000000000u128), "denom2", 10u128).unwrap(), // 1000000000000 * 10
-                Asset::new(Uint128::from(1000000000000u128), "denom3", 1u128).unwrap(), // 1000000000000 * 100
-            ],
-        };
-
-        let all_denoms = pool
-            .clone()
-            .pool_assets
-            .into_iter()
-            .map(|asset| asset.denom().to_string())
-            .collect::<Vec<_>>();
-
-        pool.mark_corrupted_assets(&["denom1".to_owned()]).unwrap();
-
-        transmuter.pool.save(&mut deps.storage, &pool).unwrap();
-
-        for denom in all_denoms.clone() {
-            transmuter
-                .limiters
-                .register(
-                    &mut deps.storage,
-                    denom.as_str(),
-                    "static",
-                    LimiterParams::StaticLimiter {
-                        upper_limit: Decimal::percent(100),
-                    },
-                )
-                .unwrap();
-        }
+        transmuter
+            .pool
+            .save(
+                &mut deps.storage,
+                &TransmuterPool::new(vec![Asset::new(Uint128::zero(), "denom1", 1u128).unwrap()])
+                    .unwrap(),
+            )
+            .unwrap();
 
         transmuter
-            .swap_non_alloyed_exact_amount_out(
-                "denom3",
-                1000000000000u128.into(),
-                coin(1000000000000, "denom1"),
-                deps.api.addr_make("sender"),
+            .fee
+            .set_swap_fee(&mut deps.storage, Decimal::percent(1))
+            .unwrap();
+        transmuter
+            .fee
+            .set_fee_collector(&mut deps.storage, Addr::unchecked("collector"))
+            .unwrap();
+        transmuter
+            .fee
+            .set_fee_split(&mut deps.storage, fee_split)
+            .unwrap();
+
+        let tokens_in = [Coin::new(1000, "denom1")];
+
+        // joining pool via exec is free of charge
+        let res = transmuter
+            .swap_tokens_to_alloyed_asset(
+                Entrypoint::Exec,
+                SwapToAlloyedConstraint::ExactIn {
+                    tokens_in: &tokens_in,
+                    token_out_min_amount: Uint128::zero(),
+                },
+                Addr::unchecked("addr1"),
                 deps.as_mut(),
                 mock_env(),
             )
             .unwrap();
 
-        // all drained denoms that are corrupted should not be in the pool
-        let pool = transmuter.pool.load(&deps.storage).unwrap();
+        assert_eq!(
+            res,
+            Response::new()
+                .add_event(alloyed_asset_transfer_event(
+                    "mint",
+                    "",
+                    "addr1",
+                    &Coin::new(100000u128, "alloyed")
+                ))
+                .add_message(MsgMint {
+                    sender: MOCK_CONTRACT_ADDR.to_string(),
+                    amount: Some(Coin::new(100000u128, "alloyed").into()),
+                    mint_to_address: "addr1".to_string()
+                })
+        );
 
-        let denoms = pool
-            .pool_assets
-            .into_iter()
-            .map(|a| a.denom().to_string())
-            .collect_vec();
+        // swapping via sudo is charged, 990denom1 is swapped for 99000alloyed
+        let res = transmuter
+            .swap_tokens_to_alloyed_asset(
+                Entrypoint::Sudo,
+                SwapToAlloyedConstraint::ExactIn {
+                    tokens_in: &tokens_in,
+                    token_out_min_amount: Uint128::zero(),
+                },
+                Addr::unchecked("addr1"),
+                deps.as_mut(),
+                mock_env(),
+            )
+            .unwrap();
 
-        assert_eq!(denoms, vec!["denom2", "denom3"]);
+        let mut expected_res = Response::new()
+            .set_data(
+                to_json_binary(&SwapExactAmountInResponseData {
+                    token_out_amount: Uint128::new(99000),
+                })
+                .unwrap(),
+            )
+            .add_event(alloyed_asset_transfer_event(
+                "mint",
+                "",
+                "addr1",
+                &Coin::new(99000u128, "alloyed"),
+            ))
+            .add_message(MsgMint {
+                sender: MOCK_CONTRACT_ADDR.to_string(),
+                amount: Some(Coin::new(99000u128, "alloyed").into()),
+                mint_to_address: "addr1".to_string(),
+            });
+
+        if expected_collected > 0 {
+            expected_res = expected_res.add_message(BankMsg::Send {
+                to_address: "collector".to_string(),
+                amount: vec![Coin::new(expected_collected, "denom1")],
+            });
+        }
 
-        let limiter_denoms = transmuter
-            .limiters
-            .list_limiters(&deps.storage)
-            .unwrap()
-            .into_iter()
-            .map(|((denom, _), _)| denom)
-            .unique()
-            .collect_vec();
+        assert_eq!(res, expected_res);
 
-        assert_eq!(limiter_denoms, vec!["denom2", "denom3"]);
+        // fee that is not sent to the collector is retained in the pool
+        let pool = transmuter.pool.load(&deps.storage).unwrap();
+        assert_eq!(
+            pool.pool_assets,
+            vec![Asset::new(Uint128::from(2000 - expected_collected), "denom1", 1u128).unwrap()]
+        );
     }
 
     #[rstest]
-    #[case(
-        Coin::new(100u128, "denom1"),
-        "denom2",
-        1000u128,
-        Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .add_message(BankMsg::Send {
-                to_address: "addr1".to_string(),
-                amount: vec![Coin::new(1000u128, "denom2")]
-            })
-            .set_data(to_json_binary(&SwapExactAmountInResponseData {
-                token_out_amount: Uint128::from(1000u128)
-            }).unwrap()))
-    )]
-    #[case(
-        Coin::new(100u128, "denom2"),
-        "denom1",
-        10u128,
-        Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .add_message(BankMsg::Send {
-                to_address: "addr1".to_string(),
-                amount: vec![Coin::new(10u128, "denom1")]
-            })
-            .set_data(to_json_binary(&SwapExactAmountInResponseData {
-                token_out_amount: Uint128::from(10u128)
-            }).unwrap()))
-    )]
-    #[case(
-        Coin::new(100u128, "denom2"),
-        "denom1",
-        100u128,
-        Addr::unchecked("addr1"),
-        Err(ContractError::InsufficientTokenOut {
-            min_required: 100u128.into(),
-            amount_out: 10u128.into()
-        })
-    )]
-    #[case(
-        Coin::new(100000000001u128, "denom1"),
-        "denom2",
-        1000000000010u128,
-        Addr::unchecked("addr1"),
-        Err(ContractError::InsufficientPoolAsset {
-            required: Coin::new(1000000000010u128, "denom2"),
-            available: Coin::new(1000000000000u128, "denom2"),
-        })
-    )]
-    fn test_swap_non_alloyed_exact_amount_in(
-        #[case] token_in: Coin,
-        #[case] token_out_denom: &str,
-        #[case] token_out_min_amount: u128,
-        #[case] sender: Addr,
-        #[case] expected_res: Result<Response, ContractError>,
+    #[case(100u128, 100u128, 0u128, true)]
+    #[case(100u128, 101u128, 0u128, false)]
+    #[case(101u128, 100u128, 0u128, false)]
+    #[case(100u128, 101u128, 1u128, true)]
+    #[case(101u128, 100u128, 1u128, true)]
+    #[case(100u128, 102u128, 1u128, false)]
+    fn test_within_tolerance(
+        #[case] expected: u128,
+        #[case] actual: u128,
+        #[case] tolerance: u128,
+        #[case] within: bool,
     ) {
-        let mut deps = cosmwasm_std::testing::mock_dependencies_with_balances(&[(
-            sender.to_string().as_str(),
-            &[Coin::new(2000000000000u128, "alloyed")],
-        )]);
+        assert_eq!(
+            within_tolerance(expected.into(), actual.into(), tolerance.into()),
+            within
+        );
+    }
 
+    #[rstest]
+    // fee is deducted from denom1 in, collected and retained in denom1
+    #[case(FeeSide::In, Coin::new(5, "denom1"), (1_000_995u128, 990_100u128))]
+    // fee is deducted from denom2 out, collected and retained in denom2
+    #[case(FeeSide::Out, Coin::new(50, "denom2"), (1_001_000u128, 990_050u128))]
+    fn test_swap_non_alloyed_exact_amount_in_with_fee_side(
+        #[case] fee_side: FeeSide,
+        #[case] expected_collected: Coin,
+        #[case] expected_pool_amounts: (u128, u128),
+    ) {
+        let mut deps = mock_dependencies();
         let transmuter = Transmuter::new();
         transmuter
             .alloyed_asset
             .set_alloyed_denom(&mut deps.storage, &"alloyed".to_string())
             .unwrap();
 
+        transmuter
+            .pool
+            .save(
+                &mut deps.storage,
+                &TransmuterPool {
+                    pool_assets: vec![
+                        Asset::new(Uint128::from(1_000_000u128), "denom1", 1u128).unwrap(),
+                        Asset::new(Uint128::from(1_000_000u128), "denom2", 10u128).unwrap(),
+                    ],
+                },
+            )
+            .unwrap();
+
+        transmuter
+            .fee
+            .set_swap_fee(&mut deps.storage, Decimal::percent(1))
+            .unwrap();
+        transmuter
+            .fee
+            .set_fee_collector(&mut deps.storage, Addr::unchecked("collector"))
+            .unwrap();
+        transmuter
+            .fee
+            .set_fee_split(&mut deps.storage, Decimal::percent(50))
+            .unwrap();
+        transmuter
+            .fee
+            .set_fee_side(&mut deps.storage, fee_side)
+            .unwrap();
+
+        let res = transmuter
+            .swap_non_alloyed_exact_amount_in(
+                Coin::new(1000, "denom1"),
+                "denom2",
+                Uint128::one(),
+                Addr::unchecked("addr1"),
+                deps.as_mut(),
+                mock_env(),
+            )
+            .unwrap();
+
+        // user receives the same amount either way
+        assert_eq!(
+            res,
+            Response::new()
+                .add_message(BankMsg::Send {
+                    to_address: "addr1".to_string(),
+                    amount: vec![Coin::new(9900, "denom2")],
+                })
+                .set_data(
+                    to_json_binary(&SwapExactAmountInResponseData {
+                        token_out_amount: Uint128::new(9900),
+                    })
+                    .unwrap(),
+                )
+                .add_message(BankMsg::Send {
+                    to_address: "collector".to_string(),
+                    amount: vec![expected_collected],
+                })
+        );
+
+        let pool = transmuter.pool.load(&deps.storage).unwrap();
+        assert_eq!(
+            pool.pool_assets,
+            vec![
+                Asset::new(Uint128::from(expected_pool_amounts.0), "denom1", 1u128).unwrap(),
+                Asset::new(Uint128::from(expected_pool_amounts.1), "denom2", 10u128).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_swap_non_alloyed_exact_amount_in_output_rounds_to_zero() {
+        let mut deps = mock_dependencies();
+        let transmuter = Transmuter::new();
         transmuter
             .alloyed_asset
-            .set_normalization_factor(&mut deps.storage, 100u128.into())
+            .set_alloyed_denom(&mut deps.storage, &"alloyed".to_string())
             .unwrap();
 
         transmuter
@@ -1456,119 +3858,134 @@ mod tests {
                 &mut deps.storage,
                 &TransmuterPool {
                     pool_assets: vec![
-                        Asset::new(Uint128::from(1000000000000u128), "denom1", 1u128).unwrap(),
-                        Asset::new(Uint128::from(1000000000000u128), "denom2", 10u128).unwrap(),
+                        Asset::new(Uint128::from(1_000_000u128), "denom1", 1u128).unwrap(),
+                        Asset::new(Uint128::from(1_000_000u128), "denom2", 100u128).unwrap(),
                     ],
                 },
             )
             .unwrap();
 
-        let res = transmuter.swap_non_alloyed_exact_amount_in(
-            token_in.clone(),
-            token_out_denom,
-            token_out_min_amount.into(),
-            sender,
-            deps.as_mut(),
-            mock_env(),
+        // 99denom2 is worth less than 1denom1
+        let err = transmuter
+            .swap_non_alloyed_exact_amount_in(
+                Coin::new(99, "denom2"),
+                "denom1",
+                Uint128::zero(),
+                Addr::unchecked("addr1"),
+                deps.as_mut(),
+                mock_env(),
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::OutputRoundsToZero {
+                token_in: Coin::new(99, "denom2")
+            }
         );
 
-        assert_eq!(res, expected_res);
+        // 100denom2 is worth exactly 1denom1
+        transmuter
+            .swap_non_alloyed_exact_amount_in(
+                Coin::new(100, "denom2"),
+                "denom1",
+                Uint128::one(),
+                Addr::unchecked("addr1"),
+                deps.as_mut(),
+                mock_env(),
+            )
+            .unwrap();
+    }
+
+    /// Deterministic xorshift generator, so that property tests are reproducible
+    struct Xorshift(u64);
+
+    impl Xorshift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        /// Amount with random magnitude, so that both tiny and large amounts are covered
+        fn amount(&mut self) -> Uint128 {
+            let magnitude = (self.next() % 19) as u32;
+            Uint128::from(self.next() % 10u64.pow(magnitude) + 1)
+        }
+
+        /// Swap fee in [0, 1)
+        fn swap_fee(&mut self) -> Decimal {
+            Decimal::raw((self.next() % 1_000_000_000_000_000_000) as u128)
+        }
     }
 
     #[rstest]
-    #[case(
-        "denom1",
-        100u128,
-        Coin::new(1000u128, "denom2"),
-        Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .add_message(BankMsg::Send {
-                to_address: "addr1".to_string(),
-                amount: vec![Coin::new(1000u128, "denom2")]
-            })
-            .set_data(to_json_binary(&SwapExactAmountOutResponseData {
-                token_in_amount: 100u128.into()
-            }).unwrap()))
-    )]
-    #[case(
-        "denom2",
-        100u128,
-        Coin::new(10u128, "denom1"),
-        Addr::unchecked("addr1"),
-        Ok(Response::new()
-            .add_message(BankMsg::Send {
-                to_address: "addr1".to_string(),
-                amount: vec![Coin::new(10u128, "denom1")]
-            })
-            .set_data(to_json_binary(&SwapExactAmountOutResponseData {
-                token_in_amount: 100u128.into()
-            }).unwrap()))
-    )]
-    #[case(
-        "denom2",
-        100u128,
-        Coin::new(100u128, "denom1"),
-        Addr::unchecked("addr1"),
-        Err(ContractError::ExcessiveRequiredTokenIn {
-            limit: 100u128.into(),
-            required: 1000u128.into()
-        })
-    )]
-    #[case(
-        "denom1",
-        100000000001u128,
-        Coin::new(1000000000010u128, "denom2"),
-        Addr::unchecked("addr1"),
-        Err(ContractError::InsufficientPoolAsset {
-            required: Coin::new(1000000000010u128, "denom2"),
-            available: Coin::new(1000000000000u128, "denom2"),
-        })
-    )]
-    fn test_swap_non_alloyed_exact_amount_out(
+    #[case(FeeSide::In, "denom1", "denom2")]
+    #[case(FeeSide::Out, "denom1", "denom2")]
+    #[case(FeeSide::In, "denom2", "alloyed")]
+    fn test_out_amt_given_in_rounding_never_favors_user(
+        #[case] fee_side: FeeSide,
         #[case] token_in_denom: &str,
-        #[case] token_in_max_amount: u128,
-        #[case] token_out: Coin,
-        #[case] sender: Addr,
-        #[case] expected_res: Result<Response, ContractError>,
+        #[case] token_out_denom: &str,
     ) {
-        let mut deps = cosmwasm_std::testing::mock_dependencies_with_balances(&[(
-            sender.to_string().as_str(),
-            &[Coin::new(2000000000000u128, "alloyed")],
-        )]);
-
+        let mut deps = mock_dependencies();
         let transmuter = Transmuter::new();
         transmuter
             .alloyed_asset
             .set_alloyed_denom(&mut deps.storage, &"alloyed".to_string())
             .unwrap();
-
         transmuter
             .alloyed_asset
-            .set_normalization_factor(&mut deps.storage, 100u128.into())
+            .set_normalization_factor(&mut deps.storage, Uint128::one())
             .unwrap();
 
+        let pool = TransmuterPool {
+            pool_assets: vec![
+                Asset::new(Uint128::from(10u128.pow(30)), "denom1", 1u128).unwrap(),
+                Asset::new(Uint128::from(10u128.pow(30)), "denom2", 10u128).unwrap(),
+            ],
+        };
+        transmuter.pool.save(&mut deps.storage, &pool).unwrap();
         transmuter
-            .pool
-            .save(
-                &mut deps.storage,
-                &TransmuterPool {
-                    pool_assets: vec![
-                        Asset::new(Uint128::from(1000000000000u128), "denom1", 1u128).unwrap(),
-                        Asset::new(Uint128::from(1000000000000u128), "denom2", 10u128).unwrap(),
-                    ],
-                },
-            )
+            .fee
+            .set_fee_side(&mut deps.storage, fee_side)
             .unwrap();
 
-        let res = transmuter.swap_non_alloyed_exact_amount_out(
-            token_in_denom,
-            token_in_max_amount.into(),
-            token_out,
-            sender,
-            deps.as_mut(),
-            mock_env(),
-        );
+        let norm_factor = |denom: &str| match denom {
+            "alloyed" => Uint256::one(),
+            denom => pool
+                .get_pool_asset_by_denom(denom)
+                .unwrap()
+                .normalization_factor()
+                .into(),
+        };
+        let (token_in_norm_factor, token_out_norm_factor) =
+            (norm_factor(token_in_denom), norm_factor(token_out_denom));
+        let one = Uint256::from(Decimal::one().atomics());
+
+        let mut rng = Xorshift(0x2545_f491_4f6c_dd1d);
+        for _ in 0..2_000 {
+            let swap_fee = rng.swap_fee();
+            let token_in = Coin::new(rng.amount().u128(), token_in_denom);
+            transmuter
+                .fee
+                .set_swap_fee(&mut deps.storage, swap_fee)
+                .unwrap();
 
-        assert_eq!(res, expected_res);
+            let (_, token_out, _) = transmuter
+                .out_amt_given_in(deps.as_ref(), token_in.clone(), token_out_denom)
+                .unwrap();
+
+            // token out value must not exceed token in value after the exact (unrounded) fee
+            let token_out_value = Uint256::from(token_out.amount) * token_in_norm_factor * one;
+            let entitled_value = Uint256::from(token_in.amount)
+                * (one - Uint256::from(swap_fee.atomics()))
+                * token_out_norm_factor;
+            assert!(
+                token_out_value <= entitled_value,
+                "swap fee: {swap_fee}, token in: {token_in}, token out: {token_out}"
+            );
+        }
     }
 }
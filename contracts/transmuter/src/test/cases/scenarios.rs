@@ -6,12 +6,12 @@ use crate::{
     contract::sv::{ExecMsg, InstantiateMsg},
     contract::{
         GetShareDenomResponse, GetSharesResponse, GetTotalPoolLiquidityResponse,
-        GetTotalSharesResponse, ListLimitersResponse,
+        GetTotalSharesResponse, ListAssetGroupsResponse, ListLimitersResponse,
     },
     limiter::{ChangeLimiter, Limiter, LimiterParams, StaticLimiter, WindowConfig},
     test::{
         modules::cosmwasm_pool::CosmwasmPool,
-        test_env::{assert_contract_err, TestEnvBuilder},
+        test_env::{assert_contract_err, assert_gas_used, TestEnvBuilder},
     },
     ContractError,
 };
@@ -59,13 +59,20 @@ fn test_join_pool() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
         })
         .build(&app);
 
     // failed to join pool with 0 denom
     let err = t
         .contract
-        .execute(&ExecMsg::JoinPool {}, &[], &t.accounts["provider_1"])
+        .execute(
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+            &[],
+            &t.accounts["provider_1"],
+        )
         .unwrap_err();
 
     assert_contract_err(ContractError::AtLeastSingleTokenExpected {}, err);
@@ -74,7 +81,13 @@ fn test_join_pool() {
     let tokens_in = vec![Coin::new(1_000, "urandom")];
     let err = t
         .contract
-        .execute(&ExecMsg::JoinPool {}, &tokens_in, &t.accounts["provider_1"])
+        .execute(
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+            &tokens_in,
+            &t.accounts["provider_1"],
+        )
         .unwrap_err();
 
     assert_contract_err(
@@ -89,7 +102,13 @@ fn test_join_pool() {
     let tokens_in = vec![Coin::new(1_000, COSMOS_USDC)];
 
     t.contract
-        .execute(&ExecMsg::JoinPool {}, &tokens_in, &t.accounts["provider_1"])
+        .execute(
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+            &tokens_in,
+            &t.accounts["provider_1"],
+        )
         .unwrap();
 
     // check contract balances
@@ -127,7 +146,13 @@ fn test_join_pool() {
     // join pool with multiple correct pool's denom should added to the contract's balance and update state
     let tokens_in = vec![Coin::new(1_000, AXL_USDC), Coin::new(1_000, COSMOS_USDC)];
     t.contract
-        .execute(&ExecMsg::JoinPool {}, &tokens_in, &t.accounts["provider_1"])
+        .execute(
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+            &tokens_in,
+            &t.accounts["provider_1"],
+        )
         .unwrap();
 
     // check contract balances
@@ -165,7 +190,13 @@ fn test_join_pool() {
     // join pool with another provider with multiple correct pool's denom should added to the contract's balance and update state
     let tokens_in = vec![Coin::new(2_000, AXL_USDC), Coin::new(2_000, COSMOS_USDC)];
     t.contract
-        .execute(&ExecMsg::JoinPool {}, &tokens_in, &t.accounts["provider_2"])
+        .execute(
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+            &tokens_in,
+            &t.accounts["provider_2"],
+        )
         .unwrap();
 
     // check contract balances
@@ -227,6 +258,7 @@ fn test_swap() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
         })
         .build(&app);
 
@@ -269,7 +301,13 @@ fn test_swap() {
 
     // join pool properly
     t.contract
-        .execute(&ExecMsg::JoinPool {}, &tokens_in, &t.accounts["provider"])
+        .execute(
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+            &tokens_in,
+            &t.accounts["provider"],
+        )
         .unwrap();
 
     // transmute with incorrect funds should still fail
@@ -489,13 +527,16 @@ fn test_exit_pool() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
         })
         .build(&app);
 
     // join pool
     t.contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
             &[Coin::new(100_000, COSMOS_USDC)],
             &t.accounts["provider_1"],
         )
@@ -503,7 +544,9 @@ fn test_exit_pool() {
 
     t.contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
             &[Coin::new(100_000, COSMOS_USDC)],
             &t.accounts["provider_2"],
         )
@@ -699,6 +742,7 @@ fn test_3_pool_swap() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
         })
         .build(&app);
 
@@ -709,7 +753,9 @@ fn test_3_pool_swap() {
     // join pool
     t.contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
             &[Coin::new(100_000, COSMOS_USDC)],
             &t.accounts["provider"],
         )
@@ -918,6 +964,7 @@ fn test_swap_alloyed_asset() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
         })
         .build(&app);
 
@@ -975,6 +1022,7 @@ fn test_limiters() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: Some(admin.address()),
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
         })
         .build(&app);
 
@@ -1101,7 +1149,9 @@ fn test_limiters() {
     // join pool - weight = 50:50
     t.contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
             &[
                 Coin::new(500_000, AXL_USDC),
                 Coin::new(500_000, COSMOS_USDC),
@@ -1204,7 +1254,9 @@ fn test_limiters() {
     let err = t
         .contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
             &[Coin::new(200_000, AXL_USDC)],
             &t.accounts["provider"],
         )
@@ -1275,3 +1327,242 @@ fn test_limiters() {
         err,
     );
 }
+
+#[test]
+fn test_preregistered_limiters_and_asset_groups() {
+    let app = OsmosisTestApp::new();
+    let cp = CosmwasmPool::new(&app);
+
+    let t = TestEnvBuilder::new()
+        .with_account(
+            "alice",
+            vec![
+                Coin::new(1_000_000, AXL_USDC),
+                Coin::new(1_000_000, COSMOS_USDC),
+            ],
+        )
+        .with_account("admin", vec![])
+        .with_account(
+            "provider",
+            vec![
+                Coin::new(1_000_000, AXL_USDC),
+                Coin::new(1_000_000, COSMOS_USDC),
+            ],
+        )
+        .with_instantiate_msg(InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str(AXL_USDC),
+                AssetConfig::from_denom_str(COSMOS_USDC),
+            ],
+            alloyed_asset_subdenom: "usdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            admin: None,
+            moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
+        })
+        .with_limiter(
+            COSMOS_USDC,
+            "static",
+            LimiterParams::StaticLimiter {
+                upper_limit: Decimal::percent(55),
+            },
+        )
+        .with_asset_group("axelar", &[AXL_USDC])
+        .build(&app);
+
+    let ListLimitersResponse { limiters } = t.contract.query(&QueryMsg::ListLimiters {}).unwrap();
+    assert_eq!(
+        limiters,
+        vec![(
+            (COSMOS_USDC.to_string(), "static".to_string()),
+            Limiter::StaticLimiter(StaticLimiter::new(Decimal::percent(55)).unwrap())
+        )]
+    );
+
+    let ListAssetGroupsResponse { asset_groups } =
+        t.contract.query(&QueryMsg::ListAssetGroups {}).unwrap();
+    assert_eq!(
+        asset_groups.keys().collect::<Vec<_>>(),
+        vec![&"axelar".to_string()]
+    );
+
+    // join pool - weight = 50:50
+    t.contract
+        .execute(
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+            &[
+                Coin::new(500_000, AXL_USDC),
+                Coin::new(500_000, COSMOS_USDC),
+            ],
+            &t.accounts["provider"],
+        )
+        .unwrap();
+
+    // first swap is already limited
+    let err = cp
+        .swap_exact_amount_in(
+            MsgSwapExactAmountIn {
+                sender: t.accounts["alice"].address(),
+                token_in: Some(Coin::new(50_001, COSMOS_USDC).into()),
+                routes: vec![SwapAmountInRoute {
+                    pool_id: t.contract.pool_id,
+                    token_out_denom: AXL_USDC.to_string(),
+                }],
+                token_out_min_amount: Uint128::from(50_001u128).to_string(),
+            },
+            &t.accounts["alice"],
+        )
+        .unwrap_err();
+
+    assert_contract_err(
+        ContractError::UpperLimitExceeded {
+            denom: COSMOS_USDC.to_string(),
+            upper_limit: Decimal::from_str("0.55").unwrap(),
+            value: Decimal::from_str("0.550001").unwrap(),
+        },
+        err,
+    );
+}
+
+#[test]
+fn test_weights_after_swap() {
+    let app = OsmosisTestApp::new();
+    let cp = CosmwasmPool::new(&app);
+
+    let t = TestEnvBuilder::new()
+        .with_account("alice", vec![Coin::new(1_000_000, AXL_USDC)])
+        .with_account(
+            "provider",
+            vec![
+                Coin::new(1_000_000, AXL_USDC),
+                Coin::new(1_000_000, COSMOS_USDC),
+            ],
+        )
+        .with_instantiate_msg(InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str(AXL_USDC),
+                AssetConfig::from_denom_str(COSMOS_USDC),
+            ],
+            alloyed_asset_subdenom: "usdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            admin: None,
+            moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
+        })
+        .build(&app);
+
+    // empty pool has zero weights
+    t.assert_weights(
+        &[(AXL_USDC, Decimal::zero()), (COSMOS_USDC, Decimal::zero())],
+        Decimal::zero(),
+    );
+
+    // join pool
+    t.contract
+        .execute(
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
+            &[
+                Coin::new(500_000, AXL_USDC),
+                Coin::new(300_000, COSMOS_USDC),
+            ],
+            &t.accounts["provider"],
+        )
+        .unwrap();
+
+    // 500_000 : 300_000 = 62.5% : 37.5%
+    t.assert_weights(
+        &[
+            (AXL_USDC, Decimal::percent(62)),
+            (COSMOS_USDC, Decimal::percent(38)),
+        ],
+        Decimal::permille(5),
+    );
+
+    cp.swap_exact_amount_in(
+        MsgSwapExactAmountIn {
+            sender: t.accounts["alice"].address(),
+            token_in: Some(Coin::new(100_000, AXL_USDC).into()),
+            routes: vec![SwapAmountInRoute {
+                pool_id: t.contract.pool_id,
+                token_out_denom: COSMOS_USDC.to_string(),
+            }],
+            token_out_min_amount: Uint128::from(100_000u128).to_string(),
+        },
+        &t.accounts["alice"],
+    )
+    .unwrap();
+
+    // 600_000 : 200_000 = 75% : 25%
+    t.assert_weights(
+        &[
+            (AXL_USDC, Decimal::percent(75)),
+            (COSMOS_USDC, Decimal::percent(25)),
+        ],
+        Decimal::zero(),
+    );
+}
+
+#[test]
+fn test_swap_gas_usage() {
+    // generous ceilings, these are meant to catch regressions rather than to be tight bounds
+    const MAX_JOIN_POOL_GAS: u64 = 500_000;
+    const MAX_SWAP_GAS: u64 = 500_000;
+
+    let app = OsmosisTestApp::new();
+    let cp = CosmwasmPool::new(&app);
+
+    let t = TestEnvBuilder::new()
+        .with_account("alice", vec![Coin::new(1_000_000, AXL_USDC)])
+        .with_account(
+            "provider",
+            vec![
+                Coin::new(1_000_000, AXL_USDC),
+                Coin::new(1_000_000, COSMOS_USDC),
+            ],
+        )
+        .with_instantiate_msg(InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str(AXL_USDC),
+                AssetConfig::from_denom_str(COSMOS_USDC),
+            ],
+            alloyed_asset_subdenom: "usdc".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            admin: None,
+            moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
+        })
+        .build(&app);
+
+    t.execute_and_assert_gas(
+        &ExecMsg::JoinPool {
+            min_shares_out: None,
+        },
+        &[
+            Coin::new(500_000, AXL_USDC),
+            Coin::new(500_000, COSMOS_USDC),
+        ],
+        &t.accounts["provider"],
+        MAX_JOIN_POOL_GAS,
+    );
+
+    let res = cp
+        .swap_exact_amount_in(
+            MsgSwapExactAmountIn {
+                sender: t.accounts["alice"].address(),
+                token_in: Some(Coin::new(100_000, AXL_USDC).into()),
+                routes: vec![SwapAmountInRoute {
+                    pool_id: t.contract.pool_id,
+                    token_out_denom: COSMOS_USDC.to_string(),
+                }],
+                token_out_min_amount: Uint128::from(100_000u128).to_string(),
+            },
+            &t.accounts["alice"],
+        )
+        .unwrap();
+
+    assert_gas_used(&res.gas_info, MAX_SWAP_GAS);
+}
@@ -31,11 +31,12 @@ fn test_add_new_assets() {
             alloyed_asset_subdenom: "denomx".to_string(),
             alloyed_asset_normalization_factor: Uint128::one(),
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
         })
         .build(&app);
 
     // add new asset
-    let denoms = vec![
+    let denoms = [
         "denom2".to_string(),
         "denom3".to_string(),
         "denom4".to_string(),
@@ -108,5 +109,10 @@ fn test_add_new_assets() {
         )
         .unwrap_err();
 
-    assert_contract_err(ContractError::ShareDenomNotAllowedAsPoolAsset {}, err);
+    assert_contract_err(
+        ContractError::CannotUsePoolShareDenom {
+            denom: alloyed_denom,
+        },
+        err,
+    );
 }
@@ -36,6 +36,7 @@ fn test_admin_set_denom_metadata() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
         })
         .with_admin("admin")
         .build(&app);
@@ -30,6 +30,7 @@ fn test_create_pool() {
             alloyed_asset_subdenom: "denomx".to_string(),
             alloyed_asset_normalization_factor: Uint128::one(),
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
         })
         .build(&app);
 
@@ -68,3 +69,39 @@ fn test_create_pool() {
     let IsActiveResponse { is_active } = t.contract.query(&QueryMsg::IsActive {}).unwrap();
     assert!(is_active);
 }
+
+#[test]
+fn test_create_pool_with_custom_gas_denom() {
+    let app = OsmosisTestApp::new();
+
+    // create denom
+    app.init_account(&[Coin::new(1, "denom1"), Coin::new(1, "denom2")])
+        .unwrap();
+
+    let t = TestEnvBuilder::new()
+        .with_gas_denom("ustake")
+        .with_gas_amount(1000000)
+        .with_account("alice", vec![Coin::new(1_000, "denom1")])
+        .with_instantiate_msg(InstantiateMsg {
+            pool_asset_configs: vec![
+                AssetConfig::from_denom_str("denom1"),
+                AssetConfig::from_denom_str("denom2"),
+            ],
+            admin: None,
+            alloyed_asset_subdenom: "denomx".to_string(),
+            alloyed_asset_normalization_factor: Uint128::one(),
+            moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
+        })
+        .build(&app);
+
+    // deployment succeeds and accounts are funded with the custom gas denom
+    let IsActiveResponse { is_active } = t.contract.query(&QueryMsg::IsActive {}).unwrap();
+    assert!(is_active);
+
+    t.assert_account_balances(
+        "alice",
+        vec![Coin::new(1_000, "denom1"), Coin::new(1000000, "ustake")],
+        vec![],
+    );
+}
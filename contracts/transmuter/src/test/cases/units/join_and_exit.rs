@@ -98,11 +98,18 @@ fn test_join_pool_with_single_lp_should_update_shares_and_liquidity_properly() {
                 alloyed_asset_normalization_factor: Uint128::one(),
                 admin: None,
                 moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+                enable_alloyed_asset: None,
             })
             .build(&app);
 
         t.contract
-            .execute(&ExecMsg::JoinPool {}, &case.funds, &t.accounts["provider"])
+            .execute(
+                &ExecMsg::JoinPool {
+                    min_shares_out: None,
+                },
+                &case.funds,
+                &t.accounts["provider"],
+            )
             .unwrap();
 
         // check if shares are updated
@@ -241,13 +248,20 @@ fn test_join_pool_should_update_shares_and_liquidity_properly() {
                 alloyed_asset_normalization_factor: Uint128::one(),
                 admin: None,
                 moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+                enable_alloyed_asset: None,
             })
             .build(&app);
 
         for (addr, funds) in case.joins.clone() {
             // join pool
             t.contract
-                .execute(&ExecMsg::JoinPool {}, &funds, &t.accounts[addr])
+                .execute(
+                    &ExecMsg::JoinPool {
+                        min_shares_out: None,
+                    },
+                    &funds,
+                    &t.accounts[addr],
+                )
                 .unwrap();
         }
 
@@ -364,11 +378,18 @@ fn test_exit_pool_less_than_their_shares_should_update_shares_and_liquidity_prop
                 alloyed_asset_normalization_factor: Uint128::one(),
                 admin: None,
                 moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+                enable_alloyed_asset: None,
             })
             .build(&app);
 
         t.contract
-            .execute(&ExecMsg::JoinPool {}, &case.join, &t.accounts["addr1"])
+            .execute(
+                &ExecMsg::JoinPool {
+                    min_shares_out: None,
+                },
+                &case.join,
+                &t.accounts["addr1"],
+            )
             .unwrap();
 
         // check if shares are updated
@@ -453,7 +474,7 @@ fn test_exit_pool_less_than_their_shares_should_update_shares_and_liquidity_prop
         assert_eq!(
             burn_attrs,
             vec![
-                attr("burn_from_address", &t.accounts["addr1"].address()),
+                attr("burn_from_address", t.accounts["addr1"].address()),
                 attr("amount", format!("{}{}", exit_amount, share_denom)),
             ]
         );
@@ -564,11 +585,18 @@ fn test_exit_pool_greater_than_their_shares_should_fail() {
                 alloyed_asset_normalization_factor: Uint128::one(),
                 admin: None,
                 moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+                enable_alloyed_asset: None,
             })
             .build(&app);
 
         t.contract
-            .execute(&ExecMsg::JoinPool {}, &case.join, &t.accounts["addr"])
+            .execute(
+                &ExecMsg::JoinPool {
+                    min_shares_out: None,
+                },
+                &case.join,
+                &t.accounts["addr"],
+            )
             .unwrap();
 
         let err = t
@@ -616,12 +644,15 @@ fn test_exit_pool_within_shares_but_over_joined_denom_amount() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
         })
         .build(&app);
 
     t.contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
             &[Coin::new(100_000_000, "denoma")],
             &t.accounts["instantiator"],
         )
@@ -629,7 +660,9 @@ fn test_exit_pool_within_shares_but_over_joined_denom_amount() {
 
     t.contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool {
+                min_shares_out: None,
+            },
             &[Coin::new(200_000_000, "denomb")],
             &t.accounts["addr1"],
         )
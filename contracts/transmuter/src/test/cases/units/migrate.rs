@@ -2,10 +2,7 @@ use std::{iter, path::PathBuf};
 
 use crate::{
     asset::AssetConfig,
-    contract::{
-        sv::{InstantiateMsg, QueryMsg},
-        GetModeratorResponse, ListAssetConfigsResponse,
-    },
+    contract::{sv::QueryMsg, GetModeratorResponse, ListAssetConfigsResponse},
     migrations::v3_1_0::MigrateMsg,
     test::{modules::cosmwasm_pool::CosmwasmPool, test_env::TransmuterContract},
 };
@@ -28,6 +25,15 @@ struct InstantiateMsgV2 {
     moderator: Option<String>,
 }
 
+#[cw_serde]
+struct InstantiateMsgV3 {
+    pool_asset_configs: Vec<AssetConfig>,
+    alloyed_asset_subdenom: String,
+    alloyed_asset_normalization_factor: Uint128,
+    admin: Option<String>,
+    moderator: String,
+}
+
 #[cw_serde]
 struct MigrateMsgV3 {
     asset_configs: Vec<AssetConfig>,
@@ -173,7 +179,7 @@ fn test_migrate_v3_to_v3_1() {
     )
     .unwrap();
 
-    let instantiate_msg = InstantiateMsg {
+    let instantiate_msg = InstantiateMsgV3 {
         pool_asset_configs: vec![
             AssetConfig {
                 denom: "denom1".to_string(),
@@ -43,6 +43,7 @@ fn test_spot_price(liquidity: &[Coin]) {
             Uint128::one(),
             None,
             "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            None,
         )
         .unwrap();
 
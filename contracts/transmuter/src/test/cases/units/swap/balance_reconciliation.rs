@@ -0,0 +1,106 @@
+use super::*;
+
+use crate::contract::{BalanceReconciliation, BalanceReconciliationResponse};
+
+#[test]
+fn test_tracked_balances_match_actual_balances_after_swaps() {
+    let app = OsmosisTestApp::new();
+    let t = pool_with_single_lp(
+        &app,
+        vec![
+            Coin::new(1_000_000, "denom0"),
+            Coin::new(1_000_000, "denom1"),
+        ],
+        vec![AssetConfig {
+            denom: "denom1".to_string(),
+            normalization_factor: Uint128::new(10),
+        }],
+    );
+
+    let share_denom = t
+        .contract
+        .query::<GetShareDenomResponse>(&QueryMsg::GetShareDenom {})
+        .unwrap()
+        .share_denom;
+
+    let cp = CosmwasmPool::new(t.app);
+
+    let swap_exact_amount_in = |token_in: Coin, token_out_denom: &str| {
+        cp.swap_exact_amount_in(
+            MsgSwapExactAmountIn {
+                sender: t.accounts[SWAPPER].address(),
+                routes: vec![SwapAmountInRoute {
+                    pool_id: t.contract.pool_id,
+                    token_out_denom: token_out_denom.to_string(),
+                }],
+                token_in: Some(token_in.into()),
+                token_out_min_amount: "1".to_string(),
+            },
+            &t.accounts[SWAPPER],
+        )
+        .unwrap();
+    };
+
+    let swap_exact_amount_out = |token_in_denom: &str, token_out: Coin| {
+        cp.swap_exact_amount_out(
+            MsgSwapExactAmountOut {
+                sender: t.accounts[SWAPPER].address(),
+                routes: vec![SwapAmountOutRoute {
+                    pool_id: t.contract.pool_id,
+                    token_in_denom: token_in_denom.to_string(),
+                }],
+                token_out: Some(token_out.into()),
+                token_in_max_amount: Uint128::MAX.to_string(),
+            },
+            &t.accounts[SWAPPER],
+        )
+        .unwrap();
+    };
+
+    swap_exact_amount_in(Coin::new(1_000, "denom0"), "denom1");
+    swap_exact_amount_in(Coin::new(1_999, "denom1"), "denom0");
+    swap_exact_amount_out("denom0", Coin::new(3_333, "denom1"));
+    swap_exact_amount_out("denom1", Coin::new(7, "denom0"));
+    swap_exact_amount_in(Coin::new(5_000, "denom0"), &share_denom);
+    swap_exact_amount_in(Coin::new(1_000, &share_denom), "denom1");
+    swap_exact_amount_out(&share_denom, Coin::new(11, "denom0"));
+
+    let BalanceReconciliationResponse { balances } = t
+        .contract
+        .query(&QueryMsg::BalanceReconciliation {})
+        .unwrap();
+
+    let tracked_balances = t
+        .contract
+        .query::<GetTotalPoolLiquidityResponse>(&QueryMsg::GetTotalPoolLiquidity {})
+        .unwrap()
+        .total_pool_liquidity;
+
+    let bank = Bank::new(t.app);
+    let expected_balances = tracked_balances
+        .into_iter()
+        .map(|coin| {
+            let actual = bank
+                .query_balance(&QueryBalanceRequest {
+                    address: t.contract.contract_addr.clone(),
+                    denom: coin.denom.clone(),
+                })
+                .unwrap()
+                .balance
+                .unwrap()
+                .amount
+                .parse::<u128>()
+                .unwrap();
+
+            BalanceReconciliation {
+                denom: coin.denom,
+                tracked: coin.amount,
+                actual: Uint128::new(actual),
+                is_mismatched: coin.amount.u128() != actual,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(balances, expected_balances);
+    assert!(balances.iter().all(|b| !b.is_mismatched));
+}
@@ -512,13 +512,16 @@ fn pool_with_single_lp(
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            enable_alloyed_asset: None,
         })
         .build(app);
 
     if !non_zero_pool_assets.is_empty() {
         t.contract
             .execute(
-                &ExecMsg::JoinPool {},
+                &ExecMsg::JoinPool {
+                    min_shares_out: None,
+                },
                 &non_zero_pool_assets,
                 &t.accounts["provider"],
             )
@@ -528,6 +531,7 @@ fn pool_with_single_lp(
     t
 }
 
+mod balance_reconciliation;
 mod client_error;
 mod non_empty_pool;
 mod swap_share_denom;
@@ -2,10 +2,12 @@ use std::{collections::HashMap, path::PathBuf};
 
 use crate::{
     contract::sv::{ExecMsg, InstantiateMsg, QueryMsg},
+    contract::BackingResponse,
+    limiter::LimiterParams,
     ContractError,
 };
 
-use cosmwasm_std::{to_json_binary, Coin};
+use cosmwasm_std::{to_json_binary, Coin, Decimal};
 use osmosis_std::types::{
     cosmos::bank::v1beta1::QueryAllBalancesRequest,
     cosmwasm::wasm::v1::MsgExecuteContractResponse,
@@ -19,8 +21,8 @@ use osmosis_test_tube::{
 };
 
 use osmosis_test_tube::{
-    Account, Bank, Module, OsmosisTestApp, RunnerError, RunnerExecuteResult, RunnerResult,
-    SigningAccount, Wasm,
+    cosmrs::proto::cosmos::base::abci::v1beta1::GasInfo, Account, Bank, ExecuteResponse, Module,
+    OsmosisTestApp, RunnerError, RunnerExecuteResult, RunnerResult, SigningAccount, Wasm,
 };
 use serde::de::DeserializeOwned;
 
@@ -28,6 +30,7 @@ use super::modules::cosmwasm_pool::CosmwasmPool;
 
 pub struct TestEnv<'a> {
     pub app: &'a OsmosisTestApp,
+    #[allow(dead_code)]
     pub creator: SigningAccount,
     pub contract: TransmuterContract<'a>,
     pub accounts: HashMap<String, SigningAccount>,
@@ -69,12 +72,72 @@ impl<'a> TestEnv<'a> {
 
         assert_eq!(contract_balances, expected_balances);
     }
+
+    /// Execute contract and assert that gas used does not exceed `max_gas`,
+    /// to guard against gas regressions.
+    pub fn execute_and_assert_gas(
+        &self,
+        msg: &ExecMsg,
+        funds: &[Coin],
+        signer: &SigningAccount,
+        max_gas: u64,
+    ) -> ExecuteResponse<MsgExecuteContractResponse> {
+        let res = self.contract.execute(msg, funds, signer).unwrap();
+        assert_gas_used(&res.gas_info, max_gas);
+        res
+    }
+
+    /// Pool weights by denom, empty pool has zero weight for every denom.
+    pub fn query_weights(&self) -> HashMap<String, Decimal> {
+        let BackingResponse { backing, .. } = self.contract.query(&QueryMsg::Backing {}).unwrap();
+
+        backing
+            .into_iter()
+            .map(|denom_backing| (denom_backing.denom, denom_backing.share))
+            .collect()
+    }
+
+    /// Assert that pool weights match expected weights within tolerance.
+    pub fn assert_weights(&self, expected_weights: &[(&str, Decimal)], tolerance: Decimal) {
+        let weights = self.query_weights();
+
+        assert_eq!(
+            weights.len(),
+            expected_weights.len(),
+            "weights: {:?}, expected: {:?}",
+            weights,
+            expected_weights
+        );
+
+        for (denom, expected_weight) in expected_weights {
+            let weight = weights
+                .get(*denom)
+                .unwrap_or_else(|| panic!("weight for {} not found", denom));
+            let diff = weight.abs_diff(*expected_weight);
+
+            assert!(
+                diff <= tolerance,
+                "weight for {}: {}, expected: {} ± {}",
+                denom,
+                weight,
+                expected_weight,
+                tolerance
+            );
+        }
+    }
 }
 
+const DEFAULT_GAS_DENOM: &str = "uosmo";
+const DEFAULT_GAS_AMOUNT: u128 = 1000000000000;
+
 pub struct TestEnvBuilder {
     account_balances: HashMap<String, Vec<Coin>>,
     instantiate_msg: Option<InstantiateMsg>,
     admin: Option<String>,
+    gas_denom: String,
+    gas_amount: u128,
+    limiters: Vec<(String, String, LimiterParams)>,
+    asset_groups: Vec<(String, Vec<String>)>,
 }
 
 impl TestEnvBuilder {
@@ -83,9 +146,25 @@ impl TestEnvBuilder {
             account_balances: HashMap::new(),
             instantiate_msg: None,
             admin: None,
+            gas_denom: DEFAULT_GAS_DENOM.to_string(),
+            gas_amount: DEFAULT_GAS_AMOUNT,
+            limiters: vec![],
+            asset_groups: vec![],
         }
     }
 
+    /// Denom that every account is funded with on top of its balance, defaults to uosmo.
+    pub fn with_gas_denom(mut self, gas_denom: &str) -> Self {
+        self.gas_denom = gas_denom.to_string();
+        self
+    }
+
+    /// Amount of gas denom that every account is funded with.
+    pub fn with_gas_amount(mut self, gas_amount: u128) -> Self {
+        self.gas_amount = gas_amount;
+        self
+    }
+
     pub fn with_instantiate_msg(mut self, msg: InstantiateMsg) -> Self {
         self.instantiate_msg = Some(msg);
         self
@@ -100,15 +179,29 @@ impl TestEnvBuilder {
         self.account_balances.insert(account.to_string(), balance);
         self
     }
+
+    /// Register limiter right after deployment, requires "admin" account.
+    pub fn with_limiter(mut self, denom: &str, label: &str, limiter_params: LimiterParams) -> Self {
+        self.limiters
+            .push((denom.to_string(), label.to_string(), limiter_params));
+        self
+    }
+
+    /// Create asset group right after deployment, requires "admin" account.
+    pub fn with_asset_group(mut self, label: &str, denoms: &[&str]) -> Self {
+        self.asset_groups.push((
+            label.to_string(),
+            denoms.iter().map(|denom| denom.to_string()).collect(),
+        ));
+        self
+    }
     pub fn build(self, app: &'_ OsmosisTestApp) -> TestEnv<'_> {
+        let gas = Coin::new(self.gas_amount, self.gas_denom);
         let accounts: HashMap<_, _> = self
             .account_balances
             .into_iter()
             .map(|(account, balance)| {
-                let balance: Vec<_> = balance
-                    .into_iter()
-                    .chain(vec![Coin::new(1000000000000, "uosmo")])
-                    .collect();
+                let balance: Vec<_> = balance.into_iter().chain(vec![gas.clone()]).collect();
 
                 (account, app.init_account(&balance).unwrap())
             })
@@ -126,6 +219,32 @@ impl TestEnvBuilder {
 
         let contract = TransmuterContract::deploy(app, &instantiate_msg, &creator).unwrap();
 
+        if !self.limiters.is_empty() || !self.asset_groups.is_empty() {
+            let admin = accounts
+                .get("admin")
+                .expect("admin account is required to pre-register limiters and asset groups");
+
+            for (denom, label, limiter_params) in self.limiters {
+                contract
+                    .execute(
+                        &ExecMsg::RegisterLimiter {
+                            denom,
+                            label,
+                            limiter_params,
+                        },
+                        &[],
+                        admin,
+                    )
+                    .unwrap();
+            }
+
+            for (label, denoms) in self.asset_groups {
+                contract
+                    .execute(&ExecMsg::CreateAssetGroup { label, denoms }, &[], admin)
+                    .unwrap();
+            }
+        }
+
         TestEnv {
             app,
             creator,
@@ -137,6 +256,7 @@ impl TestEnvBuilder {
 
 pub struct TransmuterContract<'a> {
     app: &'a OsmosisTestApp,
+    #[allow(dead_code)]
     pub code_id: u64,
     pub pool_id: u64,
     pub contract_addr: String,
@@ -207,7 +327,7 @@ impl<'a> TransmuterContract<'a> {
 
     pub fn query<Res>(&self, msg: &QueryMsg) -> RunnerResult<Res>
     where
-        Res: ?Sized + DeserializeOwned,
+        Res: DeserializeOwned,
     {
         let wasm = Wasm::new(self.app);
         wasm.query(&self.contract_addr, msg)
@@ -228,6 +348,15 @@ impl<'a> TransmuterContract<'a> {
     }
 }
 
+pub fn assert_gas_used(gas_info: &GasInfo, max_gas: u64) {
+    assert!(
+        gas_info.gas_used <= max_gas,
+        "gas used exceeds max gas: max: {}, used: {}",
+        max_gas,
+        gas_info.gas_used
+    );
+}
+
 pub fn assert_contract_err(expected: ContractError, actual: RunnerError) {
     match actual {
         RunnerError::ExecuteError { msg } => {
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use cosmwasm_std::{ensure, Decimal};
 
-use crate::{asset::Asset, ContractError};
+use crate::{asset::Asset, corruptable::Corruptable, ContractError};
 
 use super::TransmuterPool;
 
@@ -25,6 +25,8 @@ const MAX_POOL_ASSET_DENOMS: Uint64 = Uint64::new(20u64);
 
 #[cw_serde]
 pub struct TransmuterPool {
+    /// Pool assets in insertion order, new assets are appended at the end.
+    /// Anything derived from pool assets that is exposed, e.g. weights, is sorted by denom instead.
     pub pool_assets: Vec<Asset>,
 }
 
@@ -97,6 +99,24 @@ impl TransmuterPool {
             .collect::<Result<Vec<_>, ContractError>>()
     }
 
+    pub fn set_normalization_factor(
+        &mut self,
+        denom: &str,
+        normalization_factor: Uint128,
+    ) -> Result<(), ContractError> {
+        let pool_asset = self
+            .pool_assets
+            .iter_mut()
+            .find(|pool_asset| pool_asset.denom() == denom)
+            .ok_or_else(|| ContractError::InvalidPoolAssetDenom {
+                denom: denom.to_string(),
+            })?;
+
+        pool_asset.set_normalization_factor(normalization_factor)?;
+
+        Ok(())
+    }
+
     pub fn update_normalization_factor<F>(self, update_fn: F) -> Result<Self, ContractError>
     where
         F: Fn(Uint128) -> Result<Uint128, ContractError>,
@@ -57,6 +57,24 @@ impl TransmuterPool {
             &amount_constraint,
         )?;
 
+        // recompute token out amount from token in amount, rounding down,
+        // to ensure token in value always covers token out value regardless of
+        // normalization factors, any discrepancy is surfaced as an error
+        let max_token_out_amount = convert_amount(
+            token_in_amount,
+            token_in_pool_asset.normalization_factor(),
+            token_out_pool_asset.normalization_factor(),
+            &Rounding::Down,
+        )?;
+
+        ensure!(
+            token_out_amount <= max_token_out_amount,
+            ContractError::InvalidTokenOutAmount {
+                expected: max_token_out_amount,
+                actual: token_out_amount
+            }
+        );
+
         let token_in = Coin::new(token_in_amount.u128(), token_in_denom);
         let token_out = Coin::new(token_out_amount.u128(), token_out_denom);
 
@@ -563,4 +581,74 @@ mod tests {
         // Check that the input is as expected, rounded up
         assert_eq!(updated_ub, Uint128::from(10u128.pow(8) + 1));
     }
+
+    /// Deterministic xorshift generator so that property tests are reproducible
+    /// without pulling in an rng dependency.
+    fn xorshift(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    fn random_normalization_factor(state: &mut u64) -> Uint128 {
+        // spread normalization factors across magnitudes, from 1 up to 9 * 10^12
+        let exponent = (xorshift(state) % 13) as u32;
+        let mantissa = (xorshift(state) % 9 + 1) as u128;
+        Uint128::from(mantissa * 10u128.pow(exponent))
+    }
+
+    #[test]
+    fn test_transmute_amount_consistency_property() {
+        let mut state = 0x2545_f491_4f6c_dd1d;
+
+        for _ in 0..1000 {
+            let in_norm_factor = random_normalization_factor(&mut state);
+            let out_norm_factor = random_normalization_factor(&mut state);
+            let amount = Uint128::from(xorshift(&mut state) as u128 % 10u128.pow(12));
+
+            let pool = TransmuterPool {
+                pool_assets: vec![
+                    Asset::unchecked(10u128.pow(30).into(), "denom_in", in_norm_factor),
+                    Asset::unchecked(10u128.pow(30).into(), "denom_out", out_norm_factor),
+                ],
+            };
+
+            // exact in: token in is exactly the given amount and
+            // token out is the largest amount whose value does not exceed token in value
+            let (token_in, token_out) = pool
+                .clone()
+                .transmute(AmountConstraint::exact_in(amount), "denom_in", "denom_out")
+                .unwrap();
+            assert_eq!(token_in.amount, amount);
+            assert!(
+                token_out.amount.full_mul(in_norm_factor) <= amount.full_mul(out_norm_factor),
+                "token out value exceeds token in value: in_nf={in_norm_factor} out_nf={out_norm_factor} amount={amount}"
+            );
+            assert!(
+                (token_out.amount + Uint128::one()).full_mul(in_norm_factor)
+                    > amount.full_mul(out_norm_factor),
+                "token out is not maximal: in_nf={in_norm_factor} out_nf={out_norm_factor} amount={amount}"
+            );
+
+            // exact out: token out is exactly the given amount and
+            // token in is the smallest amount whose value covers token out value
+            let (token_in, token_out) = pool
+                .clone()
+                .transmute(AmountConstraint::exact_out(amount), "denom_in", "denom_out")
+                .unwrap();
+            assert_eq!(token_out.amount, amount);
+            assert!(
+                token_in.amount.full_mul(out_norm_factor) >= amount.full_mul(in_norm_factor),
+                "token in value does not cover token out value: in_nf={in_norm_factor} out_nf={out_norm_factor} amount={amount}"
+            );
+            if !token_in.amount.is_zero() {
+                assert!(
+                    (token_in.amount - Uint128::one()).full_mul(out_norm_factor)
+                        < amount.full_mul(in_norm_factor),
+                    "token in is not minimal: in_nf={in_norm_factor} out_nf={out_norm_factor} amount={amount}"
+                );
+            }
+        }
+    }
 }
@@ -16,6 +16,9 @@ impl TransmuterPool {
     /// - converting each pool asset amount to the standard normalization factor
     /// - calculating ratio of each pool asset amount to the total of normalized pool asset values
     ///
+    /// Weights are sorted by denom regardless of the order of pool assets,
+    /// so that limiter iteration, events and queries are deterministic.
+    ///
     /// If total pool asset amount is zero, returns None to signify that
     /// it makes no sense to calculate ratios, but not an error.
     pub fn weights(&self) -> Result<Option<Vec<(String, Decimal)>>, ContractError> {
@@ -25,7 +28,8 @@ impl TransmuterPool {
                 .map(|pool_asset| pool_asset.normalization_factor()),
         )?;
 
-        let normalized_asset_values = self.normalized_asset_values(std_norm_factor)?;
+        let mut normalized_asset_values = self.normalized_asset_values(std_norm_factor)?;
+        normalized_asset_values.sort_by(|(a, _), (b, _)| a.cmp(b));
 
         let total_normalized_pool_value = normalized_asset_values
             .iter()
@@ -49,6 +53,23 @@ impl TransmuterPool {
         Ok(Some(ratios))
     }
 
+    /// Pool imbalance, the sum of absolute deviations of each weight from equal weight.
+    /// Zero means the pool is perfectly balanced, empty pool is considered balanced.
+    pub fn imbalance(&self) -> Result<Decimal, ContractError> {
+        let Some(weights) = self.weights()? else {
+            return Ok(Decimal::zero());
+        };
+
+        let equal_weight = Decimal::checked_from_ratio(1u128, weights.len() as u128)?;
+
+        weights
+            .iter()
+            .try_fold(Decimal::zero(), |acc, (_, weight)| {
+                acc.checked_add(weight.abs_diff(equal_weight))
+            })
+            .map_err(Into::into)
+    }
+
     fn normalized_asset_values(
         &self,
         std_norm_factor: Uint128,
@@ -157,6 +178,19 @@ mod tests {
             ("c".to_string(), Decimal::from_ratio(6000u128, 400_000_012_000u128))
         ]
     )]
+    // weights are sorted by denom regardless of pool asset order
+    #[case(
+        vec![
+            Asset::new(1000u128, "whusdc", 1u128),
+            Asset::new(3000u128, "axlusdc", 1u128),
+            Asset::new(6000u128, "usdc", 1u128),
+        ],
+        vec![
+            ("axlusdc".to_string(), Decimal::percent(30)),
+            ("usdc".to_string(), Decimal::percent(60)),
+            ("whusdc".to_string(), Decimal::percent(10))
+        ]
+    )]
     fn test_all_ratios(
         #[case] pool_assets: Vec<Result<Asset, ContractError>>,
         #[case] expected: Vec<(String, Decimal)>,
@@ -0,0 +1,377 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Coin, Order, StdResult, Storage, Timestamp, Uint128, Uint64};
+use cw_storage_plus::Map;
+
+use crate::ContractError;
+
+/// Duration of each swap volume bucket, windowed volume is accounted at this granularity.
+pub const VOLUME_BUCKET_DURATION: Uint64 = Uint64::new(3_600_000_000_000); // 1 hour
+
+/// Number of buckets kept per denom, which bounds the longest window that can be queried.
+pub const MAX_VOLUME_BUCKETS: u64 = 168; // 7 days
+
+#[cw_serde]
+struct VolumeBucket {
+    /// Index of the bucket since epoch, used to detect stale buckets in a reused slot
+    index: u64,
+    amount: Uint128,
+}
+
+/// Swap volume of each denom, kept as a ring buffer of hourly buckets per denom,
+/// so that old buckets age out as their slots are reused.
+pub struct SwapVolumes<'a> {
+    /// Map of (denom, slot) -> bucket
+    buckets: Map<'a, (&'a str, u64), VolumeBucket>,
+}
+
+impl<'a> SwapVolumes<'a> {
+    pub const fn new(buckets_namespace: &'a str) -> Self {
+        Self {
+            buckets: Map::new(buckets_namespace),
+        }
+    }
+
+    /// Add each coin's amount to its denom's volume in the bucket of `block_time`.
+    pub fn record(
+        &self,
+        storage: &mut dyn Storage,
+        coins: &[Coin],
+        block_time: Timestamp,
+    ) -> StdResult<()> {
+        let index = block_time.nanos() / VOLUME_BUCKET_DURATION.u64();
+        let slot = index % MAX_VOLUME_BUCKETS;
+
+        for coin in coins.iter().filter(|coin| !coin.amount.is_zero()) {
+            self.buckets.update(
+                storage,
+                (coin.denom.as_str(), slot),
+                |bucket| -> StdResult<_> {
+                    let amount = match bucket {
+                        Some(bucket) if bucket.index == index => {
+                            bucket.amount.checked_add(coin.amount)?
+                        }
+                        // slot is either new or holds an aged out bucket
+                        _ => coin.amount,
+                    };
+
+                    Ok(VolumeBucket { index, amount })
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Volume of `denom` within `window` nanoseconds up to `block_time`,
+    /// rounded up to whole buckets, including the current one.
+    pub fn windowed(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+        window: Uint64,
+        block_time: Timestamp,
+    ) -> Result<Uint128, ContractError> {
+        let max_window = VOLUME_BUCKET_DURATION.checked_mul(MAX_VOLUME_BUCKETS.into())?;
+        ensure!(
+            !window.is_zero() && window <= max_window,
+            ContractError::InvalidSwapVolumeWindow { window, max_window }
+        );
+
+        let bucket_count = window
+            .checked_add(VOLUME_BUCKET_DURATION)?
+            .checked_sub(Uint64::one())?
+            .checked_div(VOLUME_BUCKET_DURATION)?
+            .u64();
+        let current_index = block_time.nanos() / VOLUME_BUCKET_DURATION.u64();
+        let oldest_index = (current_index + 1).saturating_sub(bucket_count);
+
+        self.buckets
+            .prefix(denom)
+            .range(storage, None, None, Order::Ascending)
+            .try_fold(Uint128::zero(), |volume, item| {
+                let (_, bucket) = item?;
+                if bucket.index < oldest_index || bucket.index > current_index {
+                    return Ok(volume);
+                }
+
+                Ok(volume.checked_add(bucket.amount)?)
+            })
+    }
+}
+
+/// Number of buckets the mint rate limit window is divided into,
+/// minted amount ages out of the window at this granularity.
+pub const MINT_WINDOW_BUCKETS: u64 = 24;
+
+/// Alloyed asset amount minted, kept as a ring buffer of buckets spanning the mint rate limit window,
+/// so that the window rolls forward as old buckets age out instead of resetting all at once.
+pub struct MintVolumes<'a> {
+    /// Map of slot -> bucket
+    buckets: Map<'a, u64, VolumeBucket>,
+}
+
+impl<'a> MintVolumes<'a> {
+    pub const fn new(buckets_namespace: &'a str) -> Self {
+        Self {
+            buckets: Map::new(buckets_namespace),
+        }
+    }
+
+    /// Bucket duration in nanoseconds for `window`, rounded up so that the buckets cover the window.
+    fn bucket_duration(window: Uint64) -> u64 {
+        window.u64().div_ceil(MINT_WINDOW_BUCKETS).max(1)
+    }
+
+    /// Buckets of `window` that has not aged out at `block_time`, ordered from oldest to newest.
+    fn live_buckets(
+        &self,
+        storage: &dyn Storage,
+        window: Uint64,
+        block_time: Timestamp,
+    ) -> StdResult<Vec<VolumeBucket>> {
+        let current_index = block_time.nanos() / Self::bucket_duration(window);
+        let oldest_index = (current_index + 1).saturating_sub(MINT_WINDOW_BUCKETS);
+
+        let mut buckets = self
+            .buckets
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, bucket)| bucket))
+            .filter(|bucket| {
+                bucket.as_ref().map_or(true, |bucket| {
+                    bucket.index >= oldest_index && bucket.index <= current_index
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        buckets.sort_by_key(|bucket| bucket.index);
+
+        Ok(buckets)
+    }
+
+    /// Amount minted within `window` nanoseconds up to `block_time`,
+    /// rounded up to whole buckets, including the current one.
+    pub fn windowed(
+        &self,
+        storage: &dyn Storage,
+        window: Uint64,
+        block_time: Timestamp,
+    ) -> StdResult<Uint128> {
+        self.live_buckets(storage, window, block_time)?
+            .into_iter()
+            .try_fold(Uint128::zero(), |minted, bucket| {
+                Ok(minted.checked_add(bucket.amount)?)
+            })
+    }
+
+    /// Add `amount` to the bucket of `block_time`, rejecting it if the amount minted
+    /// within `window` would exceed `max_mint_per_window`.
+    pub fn record(
+        &self,
+        storage: &mut dyn Storage,
+        amount: Uint128,
+        window: Uint64,
+        max_mint_per_window: Uint128,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let bucket_duration = Self::bucket_duration(window);
+        let buckets = self.live_buckets(storage, window, block_time)?;
+
+        let minted = buckets
+            .iter()
+            .try_fold(amount, |minted, bucket| minted.checked_add(bucket.amount))?;
+
+        if minted > max_mint_per_window {
+            // earliest time when enough of the minted amount ages out for this mint to fit,
+            // or when all of it does, if the mint alone exceeds the limit
+            let mut excess = minted - max_mint_per_window;
+            let mut aged_out_index = block_time.nanos() / bucket_duration;
+            for bucket in &buckets {
+                aged_out_index = bucket.index;
+                if bucket.amount >= excess {
+                    break;
+                }
+                excess -= bucket.amount;
+            }
+
+            return Err(ContractError::MintRateLimitExceeded {
+                retry_after: Timestamp::from_nanos(
+                    (aged_out_index + MINT_WINDOW_BUCKETS) * bucket_duration,
+                ),
+            });
+        }
+
+        let index = block_time.nanos() / bucket_duration;
+        let slot = index % MINT_WINDOW_BUCKETS;
+        self.buckets
+            .update(storage, slot, |bucket| -> StdResult<_> {
+                let amount = match bucket {
+                    Some(bucket) if bucket.index == index => bucket.amount.checked_add(amount)?,
+                    // slot is either new or holds an aged out bucket
+                    _ => amount,
+                };
+
+                Ok(VolumeBucket { index, amount })
+            })?;
+
+        Ok(())
+    }
+
+    /// Drop all buckets, e.g. when the window they were accounted for changes.
+    pub fn clear(&self, storage: &mut dyn Storage) -> StdResult<()> {
+        let slots = self
+            .buckets
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for slot in slots {
+            self.buckets.remove(storage, slot);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_windowed_swap_volume() {
+        let mut deps = mock_dependencies();
+        let volumes = SwapVolumes::new("swap_volume_buckets");
+        let hour = VOLUME_BUCKET_DURATION.u64();
+        let start = Timestamp::from_nanos(1000 * hour);
+        let day = Uint64::new(24 * hour);
+
+        volumes
+            .record(&mut deps.storage, &[Coin::new(100, "uosmo")], start)
+            .unwrap();
+        volumes
+            .record(
+                &mut deps.storage,
+                &[Coin::new(50, "uosmo"), Coin::new(10, "uion")],
+                start.plus_nanos(hour),
+            )
+            .unwrap();
+
+        let windowed = |storage: &dyn Storage, denom: &str, window: Uint64, block_time| {
+            volumes
+                .windowed(storage, denom, window, block_time)
+                .unwrap()
+        };
+
+        let now = start.plus_nanos(hour);
+        assert_eq!(
+            windowed(&deps.storage, "uosmo", day, now),
+            Uint128::new(150)
+        );
+        assert_eq!(windowed(&deps.storage, "uion", day, now), Uint128::new(10));
+        assert_eq!(
+            windowed(&deps.storage, "uosmo", Uint64::new(hour), now),
+            Uint128::new(50)
+        );
+
+        // first bucket ages out of the 24h window
+        let now = start.plus_nanos(24 * hour);
+        assert_eq!(windowed(&deps.storage, "uosmo", day, now), Uint128::new(50));
+
+        // both buckets age out
+        let now = start.plus_nanos(25 * hour);
+        assert_eq!(windowed(&deps.storage, "uosmo", day, now), Uint128::zero());
+
+        // reused slot drops the aged out bucket rather than adding to it
+        let later = start.plus_nanos(MAX_VOLUME_BUCKETS * hour);
+        volumes
+            .record(&mut deps.storage, &[Coin::new(7, "uosmo")], later)
+            .unwrap();
+        assert_eq!(
+            windowed(
+                &deps.storage,
+                "uosmo",
+                Uint64::new(MAX_VOLUME_BUCKETS * hour),
+                later
+            ),
+            Uint128::new(57)
+        );
+
+        // window must be within the buckets kept
+        let err = volumes
+            .windowed(
+                &deps.storage,
+                "uosmo",
+                Uint64::new((MAX_VOLUME_BUCKETS + 1) * hour),
+                later,
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidSwapVolumeWindow {
+                window: Uint64::new((MAX_VOLUME_BUCKETS + 1) * hour),
+                max_window: Uint64::new(MAX_VOLUME_BUCKETS * hour),
+            }
+        );
+    }
+
+    #[test]
+    fn test_mint_volumes() {
+        let mut deps = mock_dependencies();
+        let volumes = MintVolumes::new("mint_volume_buckets");
+        let window = Uint64::new(MINT_WINDOW_BUCKETS * 1_000);
+        let max = Uint128::new(100);
+        let start = Timestamp::from_nanos(1_000 * window.u64());
+
+        volumes
+            .record(&mut deps.storage, Uint128::new(60), window, max, start)
+            .unwrap();
+        volumes
+            .record(
+                &mut deps.storage,
+                Uint128::new(30),
+                window,
+                max,
+                start.plus_nanos(window.u64() / 2),
+            )
+            .unwrap();
+
+        // exceeding mint waits for just enough to age out
+        let now = start.plus_nanos(window.u64() / 2);
+        let err = volumes
+            .record(&mut deps.storage, Uint128::new(20), window, max, now)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MintRateLimitExceeded {
+                retry_after: start.plus_nanos(window.u64())
+            }
+        );
+
+        let err = volumes
+            .record(&mut deps.storage, Uint128::new(80), window, max, now)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::MintRateLimitExceeded {
+                retry_after: now.plus_nanos(window.u64())
+            }
+        );
+
+        // window rolls forward as the first bucket ages out
+        let now = start.plus_nanos(window.u64());
+        assert_eq!(
+            volumes.windowed(&deps.storage, window, now).unwrap(),
+            Uint128::new(30)
+        );
+        volumes
+            .record(&mut deps.storage, Uint128::new(70), window, max, now)
+            .unwrap();
+        assert_eq!(
+            volumes.windowed(&deps.storage, window, now).unwrap(),
+            Uint128::new(100)
+        );
+
+        volumes.clear(&mut deps.storage).unwrap();
+        assert_eq!(
+            volumes.windowed(&deps.storage, window, now).unwrap(),
+            Uint128::zero()
+        );
+    }
+}